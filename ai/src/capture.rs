@@ -0,0 +1,175 @@
+//! 推理输入/输出采集模块
+//!
+//! 将每次推理的降采样快照（输入直方图、Top-K输出）写入有界日志环，
+//! 用于现场诊断模型异常
+
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+use starry_kernel::sync::IrqMutex;
+
+/// 输入直方图分箱数
+pub const HISTOGRAM_BINS: usize = 8;
+/// 记录的最大输出数
+pub const TOP_K: usize = 4;
+
+/// 采集模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CaptureMode {
+    /// 不采集（默认）
+    #[default]
+    Off,
+    /// 仅采集输入
+    Inputs,
+    /// 采集输入和输出
+    InputsOutputs,
+}
+
+/// 单次推理快照
+#[derive(Debug, Clone, PartialEq)]
+pub struct InferenceSnapshot {
+    /// 推理序号
+    pub sequence: u64,
+    /// 输入长度
+    pub input_len: usize,
+    /// 输入最小值
+    pub input_min: f32,
+    /// 输入最大值
+    pub input_max: f32,
+    /// 输入在[min, max]区间内的直方图
+    pub input_histogram: [u32; HISTOGRAM_BINS],
+    /// 输出中数值最大的TOP_K项 (索引, 值)，按值降序
+    pub top_outputs: [(u32, f32); TOP_K],
+    /// `top_outputs`中的有效项数
+    pub top_output_count: usize,
+}
+
+impl InferenceSnapshot {
+    /// 根据推理输入和（可选）输出生成快照
+    pub fn capture(sequence: u64, input: &[f32], output: Option<&[f32]>) -> Self {
+        let (input_min, input_max) = input
+            .iter()
+            .fold((f32::MAX, f32::MIN), |(min, max), &v| (min.min(v), max.max(v)));
+        let (input_min, input_max) = if input.is_empty() { (0.0, 0.0) } else { (input_min, input_max) };
+
+        let mut input_histogram = [0u32; HISTOGRAM_BINS];
+        let range = input_max - input_min;
+        for &value in input {
+            let bin = if range > 0.0 {
+                (((value - input_min) / range) * HISTOGRAM_BINS as f32) as usize
+            } else {
+                0
+            };
+            input_histogram[bin.min(HISTOGRAM_BINS - 1)] += 1;
+        }
+
+        let mut top_outputs = [(0u32, 0.0f32); TOP_K];
+        let mut top_output_count = 0;
+        for (index, &value) in output.unwrap_or(&[]).iter().enumerate() {
+            // 插入排序维护降序的Top-K，值相同时保留下标较小的输出
+            let mut pos = top_output_count;
+            while pos > 0 && top_outputs[pos - 1].1 < value {
+                if pos < TOP_K {
+                    top_outputs[pos] = top_outputs[pos - 1];
+                }
+                pos -= 1;
+            }
+            if pos < TOP_K {
+                top_outputs[pos] = (index as u32, value);
+                top_output_count = (top_output_count + 1).min(TOP_K);
+            }
+        }
+
+        Self {
+            sequence,
+            input_len: input.len(),
+            input_min,
+            input_max,
+            input_histogram,
+            top_outputs,
+            top_output_count,
+        }
+    }
+
+    /// 有效的Top-K输出
+    pub fn top_outputs(&self) -> &[(u32, f32)] {
+        &self.top_outputs[..self.top_output_count]
+    }
+}
+
+/// 有界快照日志环，写满后覆盖最旧的快照
+///
+/// 推理路径与诊断读取方共享，持锁期间屏蔽本核IRQ
+pub struct LogRing {
+    entries: IrqMutex<VecDeque<InferenceSnapshot>>,
+    capacity: usize,
+}
+
+impl LogRing {
+    /// 创建指定容量的日志环
+    pub const fn new(capacity: usize) -> Self {
+        Self {
+            entries: IrqMutex::new(VecDeque::new()),
+            capacity,
+        }
+    }
+
+    /// 写入快照
+    pub fn push(&self, snapshot: InferenceSnapshot) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut entries = self.entries.lock();
+        if entries.len() >= self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(snapshot);
+    }
+
+    /// 取出所有快照（由旧到新）
+    pub fn drain(&self) -> Vec<InferenceSnapshot> {
+        self.entries.lock().drain(..).collect()
+    }
+
+    /// 当前快照数
+    pub fn len(&self) -> usize {
+        self.entries.lock().len()
+    }
+
+    /// 日志环是否为空
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_histogram_and_top_k() {
+        // 直方图覆盖全部输入，Top-K按值降序
+        let input = [0.0, 0.1, 0.5, 0.9, 1.0];
+        let output = [0.1, 0.8, 0.3, 0.9, 0.05, 0.7];
+        let snapshot = InferenceSnapshot::capture(3, &input, Some(&output));
+
+        assert_eq!(snapshot.input_histogram.iter().sum::<u32>(), 5);
+        assert_eq!(snapshot.input_histogram[0], 2);
+        assert_eq!(snapshot.input_histogram[HISTOGRAM_BINS - 1], 2);
+        assert_eq!(snapshot.top_outputs(), &[(3, 0.9), (1, 0.8), (5, 0.7), (2, 0.3)]);
+
+        let inputs_only = InferenceSnapshot::capture(4, &input, None);
+        assert!(inputs_only.top_outputs().is_empty());
+    }
+
+    #[test]
+    fn test_log_ring_is_bounded() {
+        // 超出容量时覆盖最旧的快照
+        let ring = LogRing::new(2);
+        for sequence in 0..5 {
+            ring.push(InferenceSnapshot::capture(sequence, &[1.0], None));
+        }
+        let sequences: Vec<u64> = ring.drain().iter().map(|s| s.sequence).collect();
+        assert_eq!(sequences, vec![3, 4]);
+        assert!(ring.is_empty());
+    }
+}
@@ -13,11 +13,22 @@ pub mod inference;
 pub mod optimization;
 pub mod npu;
 pub mod rk3588_npu;
+pub mod capture;
+pub mod result_cache;
+pub mod power_policy;
+pub mod preprocess;
 
 // 工具模块
 mod utils;
 
+use core::any::Any;
 use core::fmt;
+use capture::{CaptureMode, InferenceSnapshot, LogRing};
+use result_cache::{input_key, CacheStats, ResultCache};
+use power_policy::{PowerAwarePolicy, PowerProfile};
+use npu::{ModelReader, NPUDevice};
+use yolo_v8::YoloV8Engine;
+use starry_kernel::init_stage::{self, InitStage, InitTracker};
 
 /// AI推理引擎特征
 pub trait InferenceEngine {
@@ -32,6 +43,17 @@ pub trait InferenceEngine {
     
     /// 设置推理参数
     fn set_params(&mut self, params: InferenceParams) -> Result<(), AIError>;
+    
+    /// 转换为`Any`，用于向下转型为具体引擎类型
+    fn as_any(&self) -> &dyn Any;
+    
+    /// 流式加载模型：通过`reader`按块拉取共`total_len`字节的模型数据
+    /// 
+    /// 支持DMA的引擎应逐块直接写入设备内存，完整模型不在CPU内存中缓冲；
+    /// 默认返回`NotSupported`，调用者应改用`load_model`
+    fn load_model_streaming(&mut self, _reader: ModelReader<'_>, _total_len: usize) -> Result<(), AIError> {
+        Err(AIError::NotSupported)
+    }
 }
 
 /// 模型信息
@@ -42,6 +64,39 @@ pub struct ModelInfo {
     pub input_shape: Vec<usize>,
     pub output_shape: Vec<usize>,
     pub precision: Precision,
+    /// INT8模型的量化参数，未提供时按对称量化处理
+    pub quant: Option<QuantParams>,
+}
+
+/// 每张量INT8量化参数：q = round(x / scale) + zero_point
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuantParams {
+    pub scale: f32,
+    pub zero_point: i32,
+}
+
+impl QuantParams {
+    /// 量化为INT8，结果限制在[-128, 127]
+    pub fn quantize(&self, value: f32) -> i8 {
+        let scaled = value / self.scale;
+        let rounded = (if scaled >= 0.0 { scaled + 0.5 } else { scaled - 0.5 }) as i32;
+        rounded.saturating_add(self.zero_point).clamp(-128, 127) as i8
+    }
+
+    /// 反量化：(q - zero_point) * scale
+    pub fn dequantize(&self, quantized: i8) -> f32 {
+        (quantized as i32 - self.zero_point) as f32 * self.scale
+    }
+}
+
+impl Default for QuantParams {
+    /// 对称量化：[-1, 1]映射到[-127, 127]
+    fn default() -> Self {
+        Self {
+            scale: 1.0 / 127.0,
+            zero_point: 0,
+        }
+    }
 }
 
 /// 推理参数
@@ -70,19 +125,93 @@ pub enum OptimizationLevel {
 
 // Detection和BoundingBox已从common库导入
 
+/// AI系统初始化状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InitStatus {
+    /// 使用NPU加速
+    Accelerated(NPUDevice),
+    /// 未检测到可用NPU，降级为CPU推理
+    Degraded,
+}
+
 /// AI管理器
 pub struct AIManager {
     engines: Vec<Box<dyn InferenceEngine>>,
     current_engine: Option<usize>,
+    init_tracker: &'static InitTracker,
+    capture_mode: CaptureMode,
+    capture_ring: Option<&'static LogRing>,
+    inference_count: u64,
+    result_cache: Option<ResultCache>,
+    power_policy: PowerAwarePolicy,
 }
 
 impl AIManager {
     /// 创建新的AI管理器
     pub fn new() -> Self {
+        Self::with_init_tracker(&init_stage::INIT_TRACKER)
+    }
+    
+    /// 使用指定的初始化阶段跟踪器创建AI管理器
+    pub fn with_init_tracker(init_tracker: &'static InitTracker) -> Self {
         Self {
             engines: Vec::with_capacity(4), // 预分配容量，减少内存分配
             current_engine: None,
+            init_tracker,
+            capture_mode: CaptureMode::Off,
+            capture_ring: None,
+            inference_count: 0,
+            result_cache: None,
+            power_policy: PowerAwarePolicy::new(),
+        }
+    }
+    
+    /// 设置推理输入/输出采集模式，快照写入`ring`
+    pub fn set_capture(&mut self, mode: CaptureMode, ring: &'static LogRing) {
+        self.capture_mode = mode;
+        self.capture_ring = match mode {
+            CaptureMode::Off => None,
+            _ => Some(ring),
+        };
+    }
+    
+    /// 当前采集模式
+    pub fn capture_mode(&self) -> CaptureMode {
+        self.capture_mode
+    }
+    
+    /// 启用推理结果缓存（默认关闭），最多缓存`capacity`个输出
+    pub fn enable_result_cache(&mut self, capacity: usize) {
+        self.result_cache = Some(ResultCache::new(capacity));
+    }
+    
+    /// 关闭推理结果缓存
+    pub fn disable_result_cache(&mut self) {
+        self.result_cache = None;
+    }
+    
+    /// 推理结果缓存统计，未启用缓存时为None
+    pub fn cache_stats(&self) -> Option<CacheStats> {
+        self.result_cache.as_ref().map(ResultCache::stats)
+    }
+    
+    /// 更新电池电量，档位变化时切换到该档位精度的引擎（若已注册）
+    pub fn set_battery_level(&mut self, percent: u8) -> PowerProfile {
+        if let Some(profile) = self.power_policy.set_battery_level(percent) {
+            log::info!("电量{}%，推理间隔{}ms，精度{:?}", percent, profile.inference_interval_ms, profile.precision);
+            self.select_engine_for_precision(profile.precision);
         }
+        self.power_policy.profile()
+    }
+    
+    /// 当前功耗档位
+    pub fn power_profile(&self) -> PowerProfile {
+        self.power_policy.profile()
+    }
+    
+    /// 采集循环节流：距上次推理已达到当前档位的推理间隔时返回true
+    pub fn should_infer(&mut self, now_ms: u64) -> bool {
+        self.power_policy.should_infer(now_ms)
     }
     
     /// 注册推理引擎
@@ -90,35 +219,92 @@ impl AIManager {
         self.engines.push(engine);
     }
     
+    /// 初始化推理引擎，NPU不可用时降级为CPU上的YOLOv8引擎
+    pub fn init_with_fallback(&mut self) -> InitStatus {
+        self.init_with_devices(&npu::detect_available_npus())
+    }
+    
+    /// 依次尝试`devices`中的NPU硬件，均不可用时启用CPU引擎
+    fn init_with_devices(&mut self, devices: &[NPUDevice]) -> InitStatus {
+        for &device in devices.iter().filter(|device| device.is_hardware_accelerator()) {
+            match npu::create_npu_driver(device) {
+                Ok(driver) => {
+                    self.activate_engine(driver);
+                    return InitStatus::Accelerated(device);
+                }
+                Err(error) => log::warn!("NPU {:?} 初始化失败: {:?}", device, error),
+            }
+        }
+        
+        log::warn!("未检测到可用的NPU，降级为CPU推理");
+        self.activate_engine(Box::new(YoloV8Engine::new()));
+        InitStatus::Degraded
+    }
+    
+    /// 注册引擎并设为当前引擎
+    fn activate_engine(&mut self, engine: Box<dyn InferenceEngine>) {
+        self.register_engine(engine);
+        let _ = self.set_current_engine(self.engines.len() - 1);
+    }
+    
+    /// 向当前引擎加载模型
+    pub fn load_model(&mut self, model_data: &[u8]) -> Result<(), AIError> {
+        match self.current_engine {
+            Some(index) => self.engines[index].load_model(model_data),
+            None => Err(AIError::NoEngine),
+        }
+    }
+    
+    /// 切换到首个指定精度的引擎，没有时保持当前引擎
+    fn select_engine_for_precision(&mut self, precision: Precision) {
+        let current_precision = self.current_engine.map(|index| self.engines[index].model_info().precision);
+        if current_precision == Some(precision) {
+            return;
+        }
+        if let Some(index) = self.engines.iter().position(|engine| engine.model_info().precision == precision) {
+            let _ = self.set_current_engine(index);
+        }
+    }
+    
     /// 设置当前使用的引擎
     pub fn set_current_engine(&mut self, index: usize) -> Result<(), AIError> {
         if index < self.engines.len() {
             self.current_engine = Some(index);
+            // 缓存的输出属于之前的引擎
+            if let Some(cache) = &mut self.result_cache {
+                cache.clear();
+            }
             Ok(())
         } else {
-            Err(AIError::HardwareNotSupported)
+            Err(AIError::NoEngine)
         }
     }
     
     /// 执行推理
     pub fn infer(&mut self, input: &[f32]) -> Result<Vec<f32>, AIError> {
+        self.require_initialized()?;
         if let Some(index) = self.current_engine {
-            self.engines[index].infer(input)
+            let output = self.infer_cached(index, input)?;
+            self.record_capture(input, &output);
+            Ok(output)
         } else {
-            Err(AIError::InferenceError)
+            Err(AIError::NoEngine)
         }
     }
     
     /// 批量推理，提高吞吐量
     pub fn infer_batch(&mut self, inputs: &[&[f32]]) -> Result<Vec<Vec<f32>>, AIError> {
+        self.require_initialized()?;
         if let Some(index) = self.current_engine {
             let mut results = Vec::with_capacity(inputs.len());
             for input in inputs {
-                results.push(self.engines[index].infer(input)?);
+                let output = self.infer_cached(index, input)?;
+                self.record_capture(input, &output);
+                results.push(output);
             }
             Ok(results)
         } else {
-            Err(AIError::InferenceError)
+            Err(AIError::NoEngine)
         }
     }
     
@@ -127,6 +313,11 @@ impl AIManager {
         self.engines.len()
     }
     
+    /// 以具体类型访问第`index`个引擎，类型不符或下标越界时返回None
+    pub fn engine_as<T: 'static>(&self, index: usize) -> Option<&T> {
+        self.engines.get(index)?.as_any().downcast_ref::<T>()
+    }
+    
     /// 获取当前引擎信息（避免克隆）
     pub fn current_engine_info(&self) -> Option<&ModelInfo> {
         if let Some(index) = self.current_engine {
@@ -135,6 +326,44 @@ impl AIManager {
             None
         }
     }
+    
+    /// 经结果缓存执行推理，命中时跳过引擎
+    fn infer_cached(&mut self, index: usize, input: &[f32]) -> Result<Vec<f32>, AIError> {
+        let cache = match &mut self.result_cache {
+            Some(cache) => cache,
+            None => return self.engines[index].infer(input),
+        };
+        
+        let key = input_key(input);
+        if let Some(output) = cache.get(key) {
+            return Ok(output.to_vec());
+        }
+        let output = self.engines[index].infer(input)?;
+        cache.insert(key, output.clone());
+        Ok(output)
+    }
+    
+    /// 按采集模式记录推理快照
+    fn record_capture(&mut self, input: &[f32], output: &[f32]) {
+        let ring = match self.capture_ring {
+            Some(ring) => ring,
+            None => return,
+        };
+        
+        let output = match self.capture_mode {
+            CaptureMode::InputsOutputs => Some(output),
+            _ => None,
+        };
+        ring.push(InferenceSnapshot::capture(self.inference_count, input, output));
+        self.inference_count += 1;
+    }
+    
+    /// 检查内核、驱动和AI系统均已初始化
+    fn require_initialized(&self) -> Result<(), AIError> {
+        self.init_tracker
+            .require(InitStage::Ai)
+            .map_err(|_| AIError::NotInitialized)
+    }
 }
 
 /// 全局AI管理器实例
@@ -142,7 +371,278 @@ pub static mut AI_MANAGER: Option<AIManager> = None;
 
 /// 初始化AI系统
 pub fn init() {
+    let mut manager = AIManager::new();
+    manager.init_with_fallback();
     unsafe {
-        AI_MANAGER = Some(AIManager::new());
+        AI_MANAGER = Some(manager);
+    }
+    init_stage::complete_stage(InitStage::Ai);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct EchoEngine;
+
+    impl InferenceEngine for EchoEngine {
+        fn load_model(&mut self, _model_data: &[u8]) -> Result<(), AIError> {
+            Ok(())
+        }
+
+        fn infer(&mut self, input: &[f32]) -> Result<Vec<f32>, AIError> {
+            Ok(input.to_vec())
+        }
+
+        fn model_info(&self) -> ModelInfo {
+            ModelInfo {
+                name: "echo",
+                version: "1.0",
+                input_shape: vec![1],
+                output_shape: vec![1],
+                precision: Precision::FP32,
+                quant: None,
+            }
+        }
+
+        fn set_params(&mut self, _params: InferenceParams) -> Result<(), AIError> {
+            Ok(())
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    /// 已完成全部初始化阶段的跟踪器
+    static READY: InitTracker = InitTracker::at(InitStage::Ai);
+
+    #[test]
+    fn test_infer_without_engine() {
+        // 未注册引擎时返回NoEngine
+        let mut manager = AIManager::with_init_tracker(&READY);
+        assert_eq!(manager.infer(&[1.0]), Err(AIError::NoEngine));
+        assert_eq!(manager.infer_batch(&[&[1.0]]), Err(AIError::NoEngine));
+        assert_eq!(manager.set_current_engine(0), Err(AIError::NoEngine));
+    }
+
+    #[test]
+    fn test_infer_with_selected_engine() {
+        // 注册但未选择时仍为NoEngine，选择后推理成功
+        let mut manager = AIManager::with_init_tracker(&READY);
+        manager.register_engine(Box::new(EchoEngine));
+        assert_eq!(manager.infer(&[1.0]), Err(AIError::NoEngine));
+
+        manager.set_current_engine(0).unwrap();
+        assert_eq!(manager.infer(&[1.0, 2.0]), Ok(vec![1.0, 2.0]));
+    }
+
+    #[test]
+    fn test_infer_before_ai_init_fails_guard() {
+        // AI系统初始化前推理被拒绝，按顺序完成各阶段后推理成功
+        static TRACKER: InitTracker = InitTracker::new();
+        let mut manager = AIManager::with_init_tracker(&TRACKER);
+        manager.register_engine(Box::new(EchoEngine));
+        manager.set_current_engine(0).unwrap();
+
+        TRACKER.complete(InitStage::Kernel).unwrap();
+        TRACKER.complete(InitStage::Drivers).unwrap();
+        assert_eq!(manager.infer(&[1.0]), Err(AIError::NotInitialized));
+        assert_eq!(manager.infer_batch(&[&[1.0]]), Err(AIError::NotInitialized));
+
+        TRACKER.complete(InitStage::Ai).unwrap();
+        assert_eq!(manager.infer(&[1.0]), Ok(vec![1.0]));
+    }
+
+    #[test]
+    fn test_capture_records_snapshot_per_inference() {
+        // 开启采集后每次推理记录一个快照，仅采集输入时不含输出
+        static RING: LogRing = LogRing::new(8);
+        let mut manager = AIManager::with_init_tracker(&READY);
+        manager.register_engine(Box::new(EchoEngine));
+        manager.set_current_engine(0).unwrap();
+
+        manager.set_capture(CaptureMode::InputsOutputs, &RING);
+        manager.infer(&[0.2, 0.9]).unwrap();
+        manager.infer_batch(&[&[0.1], &[0.3]]).unwrap();
+        manager.set_capture(CaptureMode::Inputs, &RING);
+        manager.infer(&[0.5]).unwrap();
+
+        let snapshots = RING.drain();
+        assert_eq!(snapshots.len(), 4);
+        assert_eq!(snapshots[0].top_outputs(), &[(1, 0.9), (0, 0.2)]);
+        assert_eq!(snapshots[3].sequence, 3);
+        assert!(snapshots[3].top_outputs().is_empty());
+    }
+
+    #[test]
+    fn test_capture_off_records_nothing() {
+        // 默认关闭采集，切换为Off后不再记录
+        static RING: LogRing = LogRing::new(8);
+        let mut manager = AIManager::with_init_tracker(&READY);
+        manager.register_engine(Box::new(EchoEngine));
+        manager.set_current_engine(0).unwrap();
+        assert_eq!(manager.capture_mode(), CaptureMode::Off);
+
+        manager.infer(&[1.0]).unwrap();
+        manager.set_capture(CaptureMode::Inputs, &RING);
+        manager.set_capture(CaptureMode::Off, &RING);
+        manager.infer(&[1.0]).unwrap();
+        assert!(RING.is_empty());
+    }
+
+    /// 记录调用次数的引擎，输出为输入乘以调用序号
+    struct CountingEngine {
+        calls: u32,
+    }
+
+    impl InferenceEngine for CountingEngine {
+        fn load_model(&mut self, _model_data: &[u8]) -> Result<(), AIError> {
+            Ok(())
+        }
+
+        fn infer(&mut self, input: &[f32]) -> Result<Vec<f32>, AIError> {
+            self.calls += 1;
+            Ok(input.iter().map(|v| v * self.calls as f32).collect())
+        }
+
+        fn model_info(&self) -> ModelInfo {
+            EchoEngine.model_info()
+        }
+
+        fn set_params(&mut self, _params: InferenceParams) -> Result<(), AIError> {
+            Ok(())
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_result_cache_hits_repeated_input() {
+        // 默认不缓存；启用后相同输入直接返回缓存输出，命中率计入统计
+        let mut manager = AIManager::with_init_tracker(&READY);
+        manager.register_engine(Box::new(CountingEngine { calls: 0 }));
+        manager.set_current_engine(0).unwrap();
+        assert_eq!(manager.cache_stats(), None);
+        assert_eq!(manager.infer(&[1.0]), Ok(vec![1.0]));
+        assert_eq!(manager.infer(&[1.0]), Ok(vec![2.0]));
+
+        manager.enable_result_cache(4);
+        assert_eq!(manager.infer(&[1.0]), Ok(vec![3.0]));
+        assert_eq!(manager.infer(&[1.0]), Ok(vec![3.0]));
+        assert_eq!(manager.infer_batch(&[&[1.0]]), Ok(vec![vec![3.0]]));
+
+        let stats = manager.cache_stats().unwrap();
+        assert_eq!((stats.hits, stats.misses), (2, 1));
+        assert!((stats.hit_rate() - 2.0 / 3.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_result_cache_changed_input_recomputes() {
+        // 输入变化时未命中并重新推理，原输入的缓存仍可命中
+        let mut manager = AIManager::with_init_tracker(&READY);
+        manager.register_engine(Box::new(CountingEngine { calls: 0 }));
+        manager.set_current_engine(0).unwrap();
+        manager.enable_result_cache(4);
+
+        assert_eq!(manager.infer(&[1.0, 2.0]), Ok(vec![1.0, 2.0]));
+        assert_eq!(manager.infer(&[1.0, 2.5]), Ok(vec![2.0, 5.0]));
+        assert_eq!(manager.infer(&[1.0, 2.0]), Ok(vec![1.0, 2.0]));
+
+        let stats = manager.cache_stats().unwrap();
+        assert_eq!((stats.hits, stats.misses), (1, 2));
+    }
+
+    /// 指定精度的回显引擎
+    struct PrecisionEngine(Precision);
+
+    impl InferenceEngine for PrecisionEngine {
+        fn load_model(&mut self, _model_data: &[u8]) -> Result<(), AIError> {
+            Ok(())
+        }
+
+        fn infer(&mut self, input: &[f32]) -> Result<Vec<f32>, AIError> {
+            Ok(input.to_vec())
+        }
+
+        fn model_info(&self) -> ModelInfo {
+            ModelInfo { precision: self.0, ..EchoEngine.model_info() }
+        }
+
+        fn set_params(&mut self, _params: InferenceParams) -> Result<(), AIError> {
+            Ok(())
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    #[test]
+    fn test_low_battery_switches_to_int8_engine() {
+        // 电量低于20%时切换到INT8引擎并拉长推理间隔，电量恢复后切回
+        let mut manager = AIManager::with_init_tracker(&READY);
+        manager.register_engine(Box::new(PrecisionEngine(Precision::FP16)));
+        manager.register_engine(Box::new(PrecisionEngine(Precision::INT8)));
+        manager.set_current_engine(0).unwrap();
+        let full = manager.power_profile();
+
+        let low = manager.set_battery_level(12);
+        assert_eq!(low.precision, Precision::INT8);
+        assert!(low.inference_interval_ms > full.inference_interval_ms);
+        assert_eq!(manager.current_engine, Some(1));
+
+        manager.set_battery_level(80);
+        assert_eq!(manager.current_engine, Some(0));
+    }
+
+    #[test]
+    fn test_engine_as_downcasts_to_registered_type() {
+        // 注册的YOLO引擎可按具体类型取回，类型不符或下标越界时为None
+        let mut manager = AIManager::with_init_tracker(&READY);
+        manager.register_engine(Box::new(YoloV8Engine::new()));
+        manager.register_engine(Box::new(EchoEngine));
+
+        assert!(manager.engine_as::<YoloV8Engine>(0).is_some());
+        assert!(manager.engine_as::<EchoEngine>(0).is_none());
+        assert!(manager.engine_as::<YoloV8Engine>(1).is_none());
+        assert!(manager.engine_as::<EchoEngine>(1).is_some());
+        assert!(manager.engine_as::<YoloV8Engine>(2).is_none());
+    }
+
+    #[test]
+    fn test_fallback_to_cpu_when_no_npu_detected() {
+        // 未检测到NPU时降级为CPU引擎，加载模型后仍可推理
+        let mut manager = AIManager::with_init_tracker(&READY);
+        assert_eq!(manager.init_with_devices(&[]), InitStatus::Degraded);
+        assert_eq!(manager.engine_count(), 1);
+
+        manager.load_model(&[0u8; 16]).unwrap();
+        let output = manager.infer(&vec![0.0; 3 * 640 * 640]).unwrap();
+        assert_eq!(output.len(), 84 * 8400);
+        assert_eq!(output[4], 0.8);
+    }
+
+    #[test]
+    fn test_generic_backends_do_not_count_as_npu() {
+        // 仅有通用后端时同样进入降级模式
+        let mut manager = AIManager::with_init_tracker(&READY);
+        let status = manager.init_with_devices(&[NPUDevice::GenericOpenCL, NPUDevice::GenericVulkan]);
+        assert_eq!(status, InitStatus::Degraded);
+        assert_eq!(manager.engine_count(), 1);
+    }
+
+    #[test]
+    fn test_streaming_load_not_supported_by_default() {
+        // 未实现流式加载的引擎返回NotSupported，不会拉取模型数据
+        let mut pulled = 0;
+        let result = EchoEngine.load_model_streaming(&mut |chunk: &mut [u8]| {
+            pulled += chunk.len();
+            Ok(chunk.len())
+        }, 1024);
+        assert_eq!(result, Err(AIError::NotSupported));
+        assert_eq!(pulled, 0);
+    }
+}
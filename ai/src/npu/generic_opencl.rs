@@ -0,0 +1,307 @@
+//! 通用OpenCL/CPU回退驱动
+//!
+//! 无可用NPU时在CPU上执行全连接网络，矩阵运算使用common::math::gemm
+
+use super::slots::SlotTable;
+use super::{
+    GenericNPUDriver, InferenceHandle, MemoryHandle, NPUConfig, NPUDeviceInfo, NPUDriver,
+    NPUPerformanceStats, PowerMode, MAX_PENDING_INFERENCES,
+};
+use crate::{AIError, InferenceEngine, InferenceParams, ModelInfo};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::any::Any;
+use common::math::{gemm_with, GemmOptions};
+
+/// 全连接层
+#[derive(Debug, Clone)]
+struct DenseLayer {
+    in_features: usize,
+    out_features: usize,
+    /// 权重，行主序 in_features×out_features
+    weights: Vec<f32>,
+    bias: Vec<f32>,
+}
+
+/// 通用OpenCL/CPU回退驱动
+///
+/// 模型格式为若干全连接层依次拼接，每层为（小端序）：
+/// `in: u32, out: u32, weights: [f32; in*out], bias: [f32; out]`。
+/// 层间使用ReLU激活，最后一层不激活
+pub struct GenericOpenCLDriver {
+    base: GenericNPUDriver,
+    /// 当前执行的网络
+    layers: Arc<Vec<DenseLayer>>,
+    /// 装入各块内存的网络，切换模型时共享而不重新解析
+    resident: Vec<(MemoryHandle, Arc<Vec<DenseLayer>>)>,
+    /// 已提交、尚未取回的异步推理结果（CPU上提交时即完成计算）
+    pending: SlotTable<Vec<f32>>,
+}
+
+impl GenericOpenCLDriver {
+    /// 创建新的回退驱动
+    pub fn new(config: NPUConfig) -> Result<Self, AIError> {
+        Ok(Self {
+            base: GenericNPUDriver::new(config)?,
+            layers: Arc::new(Vec::new()),
+            resident: Vec::new(),
+            pending: SlotTable::new(MAX_PENDING_INFERENCES),
+        })
+    }
+
+    /// 解析模型数据
+    ///
+    /// 层维度来自不可信的模型数据，长度计算溢出按格式错误处理
+    fn parse_layers(model_data: &[u8]) -> Result<Vec<DenseLayer>, AIError> {
+        let mut layers: Vec<DenseLayer> = Vec::new();
+        let mut offset = 0;
+
+        let read_u32 = |offset: usize| -> Result<u32, AIError> {
+            model_data
+                .get(offset..offset.checked_add(4).ok_or(AIError::ModelFormatError)?)
+                .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .ok_or(AIError::ModelFormatError)
+        };
+        let read_f32s = |offset: usize, count: usize| -> Result<Vec<f32>, AIError> {
+            let end = count
+                .checked_mul(4)
+                .and_then(|len| offset.checked_add(len))
+                .ok_or(AIError::ModelFormatError)?;
+            let bytes = model_data.get(offset..end).ok_or(AIError::ModelFormatError)?;
+            Ok(bytes
+                .chunks_exact(4)
+                .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .collect())
+        };
+
+        while offset < model_data.len() {
+            let in_features = read_u32(offset)? as usize;
+            let out_features = read_u32(offset + 4)? as usize;
+            offset += 8;
+
+            if in_features == 0 || out_features == 0 {
+                return Err(AIError::ModelFormatError);
+            }
+            // 相邻层维度必须衔接
+            if let Some(previous) = layers.last() {
+                if previous.out_features != in_features {
+                    return Err(AIError::ModelFormatError);
+                }
+            }
+
+            let weight_count = in_features.checked_mul(out_features).ok_or(AIError::ModelFormatError)?;
+            let weights = read_f32s(offset, weight_count)?;
+            offset += weights.len() * 4;
+            let bias = read_f32s(offset, out_features)?;
+            offset += bias.len() * 4;
+
+            layers.push(DenseLayer { in_features, out_features, weights, bias });
+        }
+
+        if layers.is_empty() {
+            return Err(AIError::ModelFormatError);
+        }
+        Ok(layers)
+    }
+}
+
+impl InferenceEngine for GenericOpenCLDriver {
+    fn load_model(&mut self, model_data: &[u8]) -> Result<(), AIError> {
+        self.layers = Arc::new(Self::parse_layers(model_data)?);
+        Ok(())
+    }
+
+    fn infer(&mut self, input: &[f32]) -> Result<Vec<f32>, AIError> {
+        let first = self.layers.first().ok_or(AIError::ModelNotFound)?;
+        if input.len() != first.in_features {
+            return Err(AIError::InvalidInput);
+        }
+
+        let mut activations = input.to_vec();
+        let last_index = self.layers.len() - 1;
+        for (index, layer) in self.layers.iter().enumerate() {
+            let mut output = layer.bias.clone();
+            // 以1×in行向量乘以in×out权重，偏置作为beta=1的累加初值
+            gemm_with(
+                &activations,
+                &layer.weights,
+                1,
+                layer.in_features,
+                layer.out_features,
+                &mut output,
+                GemmOptions { beta: 1.0, ..Default::default() },
+            );
+            if index != last_index {
+                for value in output.iter_mut() {
+                    *value = value.max(0.0);
+                }
+            }
+            activations = output;
+        }
+
+        Ok(activations)
+    }
+
+    fn model_info(&self) -> ModelInfo {
+        ModelInfo {
+            name: "dense_cpu",
+            version: "1.0",
+            input_shape: self.layers.first().map(|l| vec![1, l.in_features]).unwrap_or_default(),
+            output_shape: self.layers.last().map(|l| vec![1, l.out_features]).unwrap_or_default(),
+            precision: crate::Precision::FP32,
+            quant: None,
+        }
+    }
+
+    fn set_params(&mut self, _params: InferenceParams) -> Result<(), AIError> {
+        Ok(())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl NPUDriver for GenericOpenCLDriver {
+    fn device_info(&self) -> NPUDeviceInfo {
+        let mut info = self.base.device_info();
+        info.vendor = "Generic";
+        info.device_name = "OpenCL/CPU Fallback";
+        info
+    }
+
+    fn set_clock_frequency(&mut self, frequency: u32) -> Result<(), AIError> {
+        self.base.set_clock_frequency(frequency)
+    }
+
+    fn performance_stats(&self) -> NPUPerformanceStats {
+        self.base.performance_stats()
+    }
+
+    fn warmup(&mut self) -> Result<(), AIError> {
+        Ok(())
+    }
+
+    fn reset(&mut self) -> Result<(), AIError> {
+        self.resident.clear();
+        self.pending.clear();
+        self.base.reset()
+    }
+
+    fn set_power_mode(&mut self, mode: PowerMode) -> Result<(), AIError> {
+        self.base.set_power_mode(mode)
+    }
+
+    fn get_temperature(&self) -> Result<f32, AIError> {
+        self.base.get_temperature()
+    }
+
+    fn allocate_memory(&mut self, size: usize) -> Result<MemoryHandle, AIError> {
+        self.base.allocate_memory(size)
+    }
+
+    fn free_memory(&mut self, handle: MemoryHandle) -> Result<(), AIError> {
+        self.base.free_memory(handle)?;
+        self.resident.retain(|(memory, _)| *memory != handle);
+        Ok(())
+    }
+
+    fn load_model_into(&mut self, memory: MemoryHandle, model_data: &[u8]) -> Result<(), AIError> {
+        let layers = Arc::new(Self::parse_layers(model_data)?);
+        self.base.load_model_into(memory, model_data)?;
+        self.resident.retain(|(resident, _)| *resident != memory);
+        self.resident.push((memory, layers.clone()));
+        self.layers = layers;
+        Ok(())
+    }
+
+    fn activate_model(&mut self, memory: MemoryHandle) -> Result<(), AIError> {
+        let (_, layers) = self
+            .resident
+            .iter()
+            .find(|(resident, _)| *resident == memory)
+            .ok_or(AIError::ModelNotFound)?
+            .clone();
+        self.base.activate_model(memory)?;
+        self.layers = layers;
+        Ok(())
+    }
+
+    /// CPU回退没有独立的计算单元，提交时即在当前网络上完成计算，结果保留到`wait_inference`取回
+    fn infer_async(&mut self, input: &[f32]) -> Result<InferenceHandle, AIError> {
+        let output = self.infer(input)?;
+        Ok(InferenceHandle(self.pending.insert(output)?))
+    }
+
+    fn wait_inference(&mut self, handle: InferenceHandle) -> Result<Vec<f32>, AIError> {
+        self.pending.remove(handle.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode_layer(model: &mut Vec<u8>, in_features: u32, out_features: u32, weights: &[f32], bias: &[f32]) {
+        model.extend_from_slice(&in_features.to_le_bytes());
+        model.extend_from_slice(&out_features.to_le_bytes());
+        for value in weights.iter().chain(bias) {
+            model.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+
+    #[test]
+    fn test_dense_inference_on_cpu() {
+        // 两层全连接：第一层ReLU截断负值，第二层求和
+        let mut model = Vec::new();
+        encode_layer(&mut model, 2, 2, &[1.0, -1.0, 2.0, 1.0], &[0.0, 0.5]);
+        encode_layer(&mut model, 2, 1, &[1.0, 1.0], &[1.0]);
+
+        let mut driver = GenericOpenCLDriver::new(NPUConfig::default()).unwrap();
+        driver.load_model(&model).unwrap();
+
+        // 第一层：[1,2]·W + b = [5, 1.5]；第二层：5 + 1.5 + 1
+        assert_eq!(driver.infer(&[1.0, 2.0]).unwrap(), vec![7.5]);
+        // 第一层：[3,-1]·W + b = [1, -3.5] → ReLU → [1, 0]
+        assert_eq!(driver.infer(&[3.0, -1.0]).unwrap(), vec![2.0]);
+        assert_eq!(driver.infer(&[1.0]), Err(AIError::InvalidInput));
+    }
+
+    #[test]
+    fn test_malformed_model_rejected() {
+        // 截断的数据和维度不衔接的层均被拒绝
+        let mut model = Vec::new();
+        encode_layer(&mut model, 2, 3, &[0.0; 6], &[0.0; 3]);
+        let mut driver = GenericOpenCLDriver::new(NPUConfig::default()).unwrap();
+        assert_eq!(driver.load_model(&model[..model.len() - 2]), Err(AIError::ModelFormatError));
+
+        encode_layer(&mut model, 2, 1, &[0.0; 2], &[0.0]);
+        assert_eq!(driver.load_model(&model), Err(AIError::ModelFormatError));
+    }
+
+    #[test]
+    fn test_oversized_dimensions_rejected() {
+        // 维度换算为字节长度时溢出usize的层按格式错误拒绝，而不是回绕后读取错误的长度
+        let mut model = Vec::new();
+        model.extend_from_slice(&u32::MAX.to_le_bytes());
+        model.extend_from_slice(&u32::MAX.to_le_bytes());
+        model.extend_from_slice(&[0u8; 16]);
+        assert_eq!(GenericOpenCLDriver::parse_layers(&model).unwrap_err(), AIError::ModelFormatError);
+    }
+
+    #[test]
+    fn test_async_inference_runs_loaded_network() {
+        // 异步推理在已加载的网络上计算，结果取走后句柄失效
+        let mut model = Vec::new();
+        encode_layer(&mut model, 2, 1, &[1.0, 1.0], &[1.0]);
+        let mut driver = GenericOpenCLDriver::new(NPUConfig::default()).unwrap();
+        driver.load_model(&model).unwrap();
+
+        let first = driver.infer_async(&[1.0, 2.0]).unwrap();
+        let second = driver.infer_async(&[3.0, 4.0]).unwrap();
+        assert_eq!(driver.wait_inference(second), Ok(vec![8.0]));
+        assert_eq!(driver.wait_inference(first), Ok(vec![4.0]));
+        assert_eq!(driver.wait_inference(first), Err(AIError::StaleHandle));
+        assert_eq!(driver.infer_async(&[1.0]), Err(AIError::InvalidInput));
+    }
+}
@@ -5,11 +5,21 @@
 mod allwinner_v851s;
 mod rockchip_rk3588;
 mod generic_opencl;
+pub mod model_cache;
+pub mod perf_history;
+pub mod slots;
+
+pub use starry_drivers::register_map::{HardwareBackend, MmioBackend, MockHardwareBackend};
+pub use model_cache::ModelId;
 
 use crate::{AIError, InferenceEngine, ModelInfo, InferenceParams};
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
+use core::any::Any;
 use core::time::Duration;
+use model_cache::{ModelCache, ResidentModel};
+use perf_history::PerfHistory;
+use slots::{SlotKey, SlotTable};
 
 /// NPU设备类型
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -20,6 +30,13 @@ pub enum NPUDevice {
     GenericVulkan,
 }
 
+impl NPUDevice {
+    /// 是否为SoC内置的NPU硬件（通用后端不计入）
+    pub fn is_hardware_accelerator(&self) -> bool {
+        matches!(self, NPUDevice::AllwinnerV851S | NPUDevice::RockchipRK3588)
+    }
+}
+
 /// 计算精度
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Precision {
@@ -48,6 +65,8 @@ pub struct NPUConfig {
     pub power_mode: PowerMode,
     pub thermal_threshold: f32,
     pub enable_profiling: bool,
+    /// NPU内存中最多同时驻留的模型数
+    pub max_resident_models: usize,
 }
 
 /// 电源模式
@@ -87,6 +106,12 @@ pub trait NPUDriver: InferenceEngine {
     /// 内存释放
     fn free_memory(&mut self, handle: MemoryHandle) -> Result<(), AIError>;
     
+    /// 把模型装入已分配的NPU内存`memory`，并将计算图指向该模型
+    fn load_model_into(&mut self, memory: MemoryHandle, model_data: &[u8]) -> Result<(), AIError>;
+    
+    /// 将计算图指向已装入`memory`的模型，不重新传输模型数据
+    fn activate_model(&mut self, memory: MemoryHandle) -> Result<(), AIError>;
+    
     /// 异步推理
     fn infer_async(&mut self, input: &[f32]) -> Result<InferenceHandle, AIError>;
     
@@ -134,13 +159,61 @@ pub struct NPUPerformanceStats {
     pub throughput: f32,        // 推理次数/秒
 }
 
+/// 单个驱动最多同时持有的内存分配数
+pub const MAX_MEMORY_ALLOCATIONS: usize = 64;
+
+/// 单个驱动最多同时挂起的异步推理数
+pub const MAX_PENDING_INFERENCES: usize = 16;
+
+/// 默认最多同时驻留的模型数
+pub const DEFAULT_RESIDENT_MODELS: usize = 2;
+
+/// 模型流式加载分块大小
+pub const MODEL_CHUNK_SIZE: usize = 64 * 1024; // 64KB
+
+/// 流式加载的数据源：每次最多填充传入缓冲区，返回实际读取的字节数
+pub type ModelReader<'a> = &'a mut dyn FnMut(&mut [u8]) -> Result<usize, AIError>;
+
+/// 分块读取模型数据并逐块写入目标
+/// 
+/// `sink`接收(偏移, 数据块)，返回写入的总字节数。
+/// 数据提前结束或`reader`返回的长度越界时返回`ModelLoadError`
+fn stream_model<S>(reader: ModelReader<'_>, total_len: usize, buffer: &mut [u8], mut sink: S) -> Result<usize, AIError>
+where
+    S: FnMut(usize, &[u8]) -> Result<(), AIError>,
+{
+    if buffer.is_empty() {
+        return Err(AIError::MemoryAllocationError);
+    }
+    
+    let mut offset = 0;
+    while offset < total_len {
+        let wanted = buffer.len().min(total_len - offset);
+        let read = reader(&mut buffer[..wanted])?;
+        
+        // 数据提前结束或读取越界
+        if read == 0 || read > wanted {
+            return Err(AIError::ModelLoadError);
+        }
+        
+        sink(offset, &buffer[..read])?;
+        offset += read;
+    }
+    
+    Ok(offset)
+}
+
 /// 内存句柄
-#[derive(Debug, Clone, Copy)]
-pub struct MemoryHandle(usize);
+///
+/// 含槽位代数，释放后再使用返回`AIError::StaleHandle`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryHandle(SlotKey);
 
 /// 推理句柄
-#[derive(Debug, Clone, Copy)]
-pub struct InferenceHandle(usize);
+///
+/// 含槽位代数，结果取走后再使用返回`AIError::StaleHandle`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InferenceHandle(SlotKey);
 
 /// 推理任务
 #[derive(Debug, Clone)]
@@ -179,15 +252,36 @@ pub struct BenchmarkResult {
     pub memory_usage: usize,
 }
 
+/// 微秒时钟源
+pub type ClockFn = fn() -> u64;
+
+/// 当前单调时间（微秒），读取AArch64通用定时器
+/// 
+/// 非AArch64平台（如主机测试）没有通用定时器，返回0
+pub fn now_micros() -> u64 {
+    #[cfg(target_arch = "aarch64")]
+    {
+        starry_kernel::uptime_micros()
+    }
+    #[cfg(not(target_arch = "aarch64"))]
+    {
+        0
+    }
+}
+
 /// 通用NPU驱动实现
 pub struct GenericNPUDriver {
     config: NPUConfig,
     device_info: NPUDeviceInfo,
     performance_stats: NPUPerformanceStats,
-    memory_pool: Vec<MemoryHandle>,
-    inference_queue: Vec<InferenceTask>,
+    /// 已分配内存的大小
+    memory_pool: SlotTable<usize>,
+    /// 计算图当前指向的模型内存
+    active_memory: Option<MemoryHandle>,
+    inference_queue: SlotTable<InferenceTask>,
     is_initialized: bool,
     temperature: f32,
+    clock: ClockFn,
 }
 
 impl GenericNPUDriver {
@@ -219,13 +313,20 @@ impl GenericNPUDriver {
                 cache_hit_rate: 0.0,
                 throughput: 0.0,
             },
-            memory_pool: Vec::new(),
-            inference_queue: Vec::new(),
+            memory_pool: SlotTable::new(MAX_MEMORY_ALLOCATIONS),
+            active_memory: None,
+            inference_queue: SlotTable::new(MAX_PENDING_INFERENCES),
             is_initialized: false,
             temperature: 25.0,
+            clock: now_micros,
         })
     }
     
+    /// 替换时钟源（默认为`now_micros`），用于测试中注入模拟时钟
+    pub fn set_clock(&mut self, clock: ClockFn) {
+        self.clock = clock;
+    }
+    
     /// 初始化NPU驱动
     pub fn initialize(&mut self) -> Result<(), AIError> {
         if self.is_initialized {
@@ -241,11 +342,17 @@ impl GenericNPUDriver {
     }
     
     /// 执行基准测试
+    /// 
+    /// 延迟取各次推理耗时的平均值，时钟分辨率不足导致耗时为0时按1µs计
     pub fn benchmark(&mut self, model_data: &[u8], iterations: usize) -> Result<BenchmarkResult, AIError> {
         if !self.is_initialized {
             return Err(AIError::DeviceError("NPU未初始化".into()));
         }
         
+        if iterations == 0 {
+            return Err(AIError::InvalidInput);
+        }
+        
         // 加载测试模型
         self.load_model(model_data)?;
         
@@ -255,18 +362,15 @@ impl GenericNPUDriver {
         // 预热
         self.warmup()?;
         
-        // 执行基准测试
-        let start_time = self.get_current_time();
+        // 执行基准测试，累计每次推理记录的耗时
         let mut total_time = 0u64;
         
         for _ in 0..iterations {
-            let inference_start = self.get_current_time();
             let _output = self.infer(&test_input)?;
-            let inference_end = self.get_current_time();
-            
-            total_time += inference_end - inference_start;
+            total_time += self.performance_stats.inference_time;
         }
         
+        let total_time = total_time.max(iterations as u64);
         let avg_latency = Duration::from_micros(total_time / iterations as u64);
         let throughput = iterations as f32 / (total_time as f32 / 1_000_000.0);
         
@@ -281,26 +385,7 @@ impl GenericNPUDriver {
     
     /// 获取当前时间（微秒）
     fn get_current_time(&self) -> u64 {
-        // 在实际实现中应该使用系统时间
-        // 这里返回模拟时间
-        0
-    }
-    
-    /// 处理推理队列
-    fn process_inference_queue(&mut self) -> Result<(), AIError> {
-        // 按优先级排序
-        self.inference_queue.sort_by(|a, b| b.priority.cmp(&a.priority));
-        
-        // 处理队列中的任务
-        while let Some(task) = self.inference_queue.pop() {
-            // 执行推理
-            for input in &task.inputs {
-                let _output = self.infer(&input.data)?;
-                // 将输出存储到task.outputs
-            }
-        }
-        
-        Ok(())
+        (self.clock)()
     }
     
     /// 检查设备状态
@@ -349,7 +434,7 @@ impl InferenceEngine for GenericNPUDriver {
         let end_time = self.get_current_time();
         
         // 更新性能统计
-        self.performance_stats.inference_time = end_time - start_time;
+        self.performance_stats.inference_time = end_time.saturating_sub(start_time);
         self.performance_stats.utilization = (self.performance_stats.utilization * 0.9 + 10.0).min(100.0);
         self.performance_stats.power_consumption = 2.5 + self.performance_stats.utilization * 0.05;
         self.temperature = 25.0 + self.performance_stats.utilization * 0.3;
@@ -362,6 +447,7 @@ impl InferenceEngine for GenericNPUDriver {
             input_shape: vec![1, 1, 1000], // 模拟形状
             output_shape: vec![1, 1, 1000],
             precision: Precision::FP16,
+            quant: None,
             ops_count: 100,
         })
     }
@@ -369,6 +455,10 @@ impl InferenceEngine for GenericNPUDriver {
     fn set_inference_params(&mut self, _params: InferenceParams) -> Result<(), AIError> {
         Ok(())
     }
+    
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
 }
 
 impl NPUDriver for GenericNPUDriver {
@@ -400,6 +490,7 @@ impl NPUDriver for GenericNPUDriver {
     
     fn reset(&mut self) -> Result<(), AIError> {
         self.memory_pool.clear();
+        self.active_memory = None;
         self.inference_queue.clear();
         self.performance_stats = NPUPerformanceStats {
             inference_time: 0,
@@ -436,19 +527,39 @@ impl NPUDriver for GenericNPUDriver {
     }
     
     fn allocate_memory(&mut self, size: usize) -> Result<MemoryHandle, AIError> {
-        let handle = MemoryHandle(self.memory_pool.len());
-        self.memory_pool.push(handle);
+        let handle = MemoryHandle(self.memory_pool.insert(size)?);
         self.performance_stats.memory_usage += size;
         Ok(handle)
     }
     
     fn free_memory(&mut self, handle: MemoryHandle) -> Result<(), AIError> {
-        if let Some(pos) = self.memory_pool.iter().position(|&h| h.0 == handle.0) {
-            self.memory_pool.remove(pos);
+        let size = self.memory_pool.remove(handle.0)?;
+        self.performance_stats.memory_usage -= size;
+        if self.active_memory == Some(handle) {
+            self.active_memory = None;
         }
         Ok(())
     }
     
+    fn load_model_into(&mut self, memory: MemoryHandle, model_data: &[u8]) -> Result<(), AIError> {
+        if !self.is_initialized {
+            return Err(AIError::DeviceError("NPU未初始化".into()));
+        }
+        if model_data.len() > *self.memory_pool.get(memory.0)? {
+            return Err(AIError::MemoryAllocationError);
+        }
+        
+        // 模拟模型加载：数据已计入分配的内存
+        self.active_memory = Some(memory);
+        Ok(())
+    }
+    
+    fn activate_model(&mut self, memory: MemoryHandle) -> Result<(), AIError> {
+        self.memory_pool.get(memory.0)?;
+        self.active_memory = Some(memory);
+        Ok(())
+    }
+    
     fn infer_async(&mut self, input: &[f32]) -> Result<InferenceHandle, AIError> {
         let task = InferenceTask {
             model_id: 0,
@@ -462,22 +573,22 @@ impl NPUDriver for GenericNPUDriver {
             priority: TaskPriority::Normal,
         };
         
-        self.inference_queue.push(task);
-        Ok(InferenceHandle(self.inference_queue.len()))
+        Ok(InferenceHandle(self.inference_queue.insert(task)?))
     }
     
     fn wait_inference(&mut self, handle: InferenceHandle) -> Result<Vec<f32>, AIError> {
-        // 处理队列并返回结果
-        self.process_inference_queue()?;
-        
-        // 模拟返回结果
-        Ok(vec![0.0f32; 100])
+        // 取出句柄对应的任务并执行，失效句柄不会执行其他任务
+        let task = self.inference_queue.remove(handle.0)?;
+        match task.inputs.first() {
+            Some(input) => self.infer(&input.data),
+            None => Err(AIError::InvalidInput),
+        }
     }
 }
 
-/// 创建NPU驱动实例
-pub fn create_npu_driver(device: NPUDevice) -> Result<Box<dyn NPUDriver>, AIError> {
-    let config = NPUConfig {
+/// 指定设备的驱动配置
+fn driver_config(device: NPUDevice) -> NPUConfig {
+    NPUConfig {
         device_type: device,
         memory_size: 1024 * 1024 * 256, // 256MB
         clock_frequency: 1000,
@@ -485,7 +596,13 @@ pub fn create_npu_driver(device: NPUDevice) -> Result<Box<dyn NPUDriver>, AIErro
         power_mode: PowerMode::Balanced,
         thermal_threshold: 85.0,
         enable_profiling: false,
-    };
+        max_resident_models: DEFAULT_RESIDENT_MODELS,
+    }
+}
+
+/// 创建NPU驱动实例
+pub fn create_npu_driver(device: NPUDevice) -> Result<Box<dyn NPUDriver>, AIError> {
+    let config = driver_config(device);
     
     match device {
         NPUDevice::AllwinnerV851S => {
@@ -538,10 +655,18 @@ pub fn get_recommended_npu() -> Option<NPUDevice> {
     None
 }
 
+/// 性能历史默认采样间隔 (ms)
+pub const PERF_HISTORY_INTERVAL_MS: u64 = 1000;
+
+/// 性能历史默认容量（按默认间隔约为一小时）
+pub const PERF_HISTORY_CAPACITY: usize = 3600;
+
 /// NPU管理器
 pub struct NPUManager {
     drivers: Vec<Box<dyn NPUDriver>>,
     current_driver: usize,
+    perf_history: PerfHistory,
+    model_cache: ModelCache,
 }
 
 impl NPUManager {
@@ -550,11 +675,17 @@ impl NPUManager {
             .ok_or_else(|| AIError::DeviceError("未找到可用的NPU设备".into()))?;
         
         let driver = create_npu_driver(recommended)?;
-        
-        Ok(Self {
+        Ok(Self::with_driver(driver, &driver_config(recommended)))
+    }
+    
+    /// 使用指定驱动创建管理器，常驻模型数取自`config`
+    pub fn with_driver(driver: Box<dyn NPUDriver>, config: &NPUConfig) -> Self {
+        Self {
             drivers: vec![driver],
             current_driver: 0,
-        })
+            perf_history: PerfHistory::new(PERF_HISTORY_INTERVAL_MS, PERF_HISTORY_CAPACITY),
+            model_cache: ModelCache::new(config.max_resident_models),
+        }
     }
     
     /// 获取当前驱动
@@ -563,12 +694,83 @@ impl NPUManager {
     }
     
     /// 切换到另一个NPU设备
+    /// 
+    /// 常驻模型属于原设备，切换前全部释放；个别模型释放失败时仍释放其余模型，
+    /// 随后返回第一个错误且不切换设备
     pub fn switch_driver(&mut self, device: NPUDevice) -> Result<(), AIError> {
         let driver = create_npu_driver(device)?;
+        let mut released = Ok(());
+        for model in self.model_cache.drain() {
+            if let Err(e) = self.drivers[self.current_driver].free_memory(model.memory) {
+                released = released.and(Err(e));
+            }
+        }
+        released?;
         self.drivers.push(driver);
         self.current_driver = self.drivers.len() - 1;
         Ok(())
     }
+    
+    /// 加载模型并保持驻留
+    /// 
+    /// 已驻留的模型只更新使用顺序，不重新加载；缓存已满时先淘汰最久未使用的模型并释放其NPU内存。
+    /// 模型装入为其分配的NPU内存，新加载的模型成为当前模型
+    pub fn load_model_cached(&mut self, id: ModelId, data: &[u8]) -> Result<ModelId, AIError> {
+        if self.model_cache.touch(id).is_some() {
+            return Ok(id);
+        }
+        
+        let driver = &mut *self.drivers[self.current_driver];
+        if let Some(evicted) = self.model_cache.evict_if_full() {
+            driver.free_memory(evicted.memory)?;
+        }
+        
+        let memory = driver.allocate_memory(data.len())?;
+        if let Err(e) = driver.load_model_into(memory, data) {
+            let _ = driver.free_memory(memory);
+            self.model_cache.set_active(None);
+            return Err(e);
+        }
+        
+        self.model_cache.insert(ResidentModel { id, memory });
+        self.model_cache.set_active(Some(id));
+        Ok(id)
+    }
+    
+    /// 使用指定的驻留模型推理，仅在该模型不是当前模型时把计算图指向其NPU内存
+    pub fn infer_with(&mut self, id: ModelId, input: &[f32]) -> Result<Vec<f32>, AIError> {
+        let driver = &mut *self.drivers[self.current_driver];
+        let is_active = self.model_cache.active() == Some(id);
+        let model = self.model_cache.touch(id).ok_or(AIError::ModelNotFound)?;
+        if !is_active {
+            driver.activate_model(model.memory)?;
+            self.model_cache.set_active(Some(id));
+        }
+        driver.infer(input)
+    }
+    
+    /// 常驻模型缓存
+    pub fn model_cache(&self) -> &ModelCache {
+        &self.model_cache
+    }
+    
+    /// 替换性能历史记录（调整采样间隔或窗口大小，已有样本被丢弃）
+    pub fn set_perf_history(&mut self, history: PerfHistory) {
+        self.perf_history = history;
+    }
+    
+    /// 性能历史记录
+    pub fn perf_history(&self) -> &PerfHistory {
+        &self.perf_history
+    }
+    
+    /// 在`now_ms`时刻对当前驱动采样，返回是否记录了新样本
+    pub fn sample_performance(&mut self, now_ms: u64) -> Result<bool, AIError> {
+        let driver = &*self.drivers[self.current_driver];
+        let stats = driver.performance_stats();
+        let temperature = driver.get_temperature()?;
+        Ok(self.perf_history.sample(now_ms, &stats, temperature))
+    }
 }
 
 impl Default for NPUConfig {
@@ -581,6 +783,7 @@ impl Default for NPUConfig {
             power_mode: PowerMode::Balanced,
             thermal_threshold: 80.0,
             enable_profiling: true,
+            max_resident_models: DEFAULT_RESIDENT_MODELS,
         }
     }
 }
@@ -600,4 +803,114 @@ mod tests {
         let devices = detect_available_npus();
         assert!(!devices.is_empty());
     }
-}
\ No newline at end of file
+    
+    fn generic_driver() -> GenericNPUDriver {
+        GenericNPUDriver::new(NPUConfig {
+            device_type: NPUDevice::GenericVulkan,
+            memory_size: 1024 * 1024,
+            clock_frequency: 1000,
+            supported_precision: vec![Precision::FP32],
+            power_mode: PowerMode::Balanced,
+            thermal_threshold: 85.0,
+            enable_profiling: false,
+            max_resident_models: DEFAULT_RESIDENT_MODELS,
+        }).unwrap()
+    }
+    
+    #[test]
+    fn test_stale_memory_handle_does_not_free_new_allocation() {
+        // 释放后槽位被重新分配，旧句柄返回StaleHandle且新分配不受影响
+        let mut driver = generic_driver();
+        let old = driver.allocate_memory(4096).unwrap();
+        driver.free_memory(old).unwrap();
+        let new = driver.allocate_memory(1024).unwrap();
+        
+        assert_eq!(driver.free_memory(old), Err(AIError::StaleHandle));
+        assert_eq!(driver.performance_stats().memory_usage, 1024);
+        assert_eq!(driver.free_memory(new), Ok(()));
+        assert_eq!(driver.performance_stats().memory_usage, 0);
+    }
+    
+    #[test]
+    fn test_inference_handle_stale_after_reset() {
+        // 重置后之前的推理句柄失效，不会取到重置后提交的任务
+        let mut driver = generic_driver();
+        let before = driver.infer_async(&[1.0]).unwrap();
+        driver.reset().unwrap();
+        let _after = driver.infer_async(&[2.0]).unwrap();
+        
+        assert_eq!(driver.wait_inference(before), Err(AIError::StaleHandle));
+    }
+    
+    static MOCK_NOW_US: core::sync::atomic::AtomicU64 = core::sync::atomic::AtomicU64::new(0);
+    
+    fn mock_clock() -> u64 {
+        MOCK_NOW_US.fetch_add(5, core::sync::atomic::Ordering::Relaxed)
+    }
+    
+    #[test]
+    fn test_benchmark_with_mock_clock() {
+        // 模拟时钟每次读取前进5µs，每次推理耗时5µs
+        let mut driver = generic_driver();
+        driver.set_clock(mock_clock);
+        driver.initialize().unwrap();
+        
+        let result = driver.benchmark(&[0u8; 16], 8).unwrap();
+        assert_eq!(result.latency, Duration::from_micros(5));
+        assert!((result.throughput - 200_000.0).abs() < 1.0);
+    }
+    
+    #[test]
+    fn test_benchmark_guards_zero_elapsed_and_iterations() {
+        // 时钟不前进时延迟按1µs计，迭代次数为0时拒绝
+        let mut driver = generic_driver();
+        driver.set_clock(|| 42);
+        driver.initialize().unwrap();
+        
+        assert_eq!(driver.benchmark(&[0u8; 16], 0).unwrap_err(), AIError::InvalidInput);
+        let result = driver.benchmark(&[0u8; 16], 4).unwrap();
+        assert_eq!(result.latency, Duration::from_micros(1));
+        assert!(result.throughput.is_finite());
+    }
+    
+    #[test]
+    fn test_model_cache_evicts_least_recently_used() {
+        // 2槽位缓存依次加载三个模型：最久未使用的第一个模型被淘汰，之后可重新加载
+        let mut driver = generic_driver();
+        driver.initialize().unwrap();
+        let mut manager = NPUManager::with_driver(Box::new(driver), &NPUConfig::default());
+        let (yolo, classifier, detector) = (ModelId(1), ModelId(2), ModelId(3));
+        
+        manager.load_model_cached(yolo, &[1u8; 100]).unwrap();
+        manager.load_model_cached(classifier, &[2u8; 200]).unwrap();
+        manager.load_model_cached(detector, &[3u8; 300]).unwrap();
+        let resident: Vec<ModelId> = manager.model_cache().resident_ids().collect();
+        assert_eq!(resident, vec![classifier, detector]);
+        assert_eq!(manager.infer_with(yolo, &[0.5]), Err(AIError::ModelNotFound));
+        
+        // 切换到驻留模型无需重新加载；重新加载第一个模型时淘汰此时最久未使用的检测模型
+        assert!(manager.infer_with(classifier, &[0.5]).is_ok());
+        assert_eq!(manager.model_cache().active(), Some(classifier));
+        manager.load_model_cached(yolo, &[1u8; 100]).unwrap();
+        let resident: Vec<ModelId> = manager.model_cache().resident_ids().collect();
+        assert_eq!(resident, vec![classifier, yolo]);
+        assert!(manager.infer_with(yolo, &[0.5]).is_ok());
+        
+        // 切换模型不重新装入数据，NPU内存只有两个驻留模型的占用
+        assert_eq!(manager.current_driver().performance_stats().memory_usage, 300);
+    }
+    
+    #[test]
+    fn test_switch_driver_releases_resident_models() {
+        // 切换设备前原设备上的驻留模型内存全部释放
+        let mut driver = generic_driver();
+        driver.initialize().unwrap();
+        let mut manager = NPUManager::with_driver(Box::new(driver), &NPUConfig::default());
+        manager.load_model_cached(ModelId(1), &[1u8; 100]).unwrap();
+        manager.load_model_cached(ModelId(2), &[2u8; 200]).unwrap();
+        
+        manager.switch_driver(NPUDevice::GenericVulkan).unwrap();
+        assert!(manager.model_cache().is_empty());
+        assert_eq!(manager.drivers[0].performance_stats().memory_usage, 0);
+    }
+}
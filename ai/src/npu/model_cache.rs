@@ -0,0 +1,104 @@
+//! NPU常驻模型缓存
+//!
+//! 按最近使用顺序记录驻留在NPU内存中的模型，容量满时淘汰最久未使用的模型
+
+use alloc::vec::Vec;
+
+use super::MemoryHandle;
+
+/// 模型标识，由调用方分配
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ModelId(pub u32);
+
+/// 驻留模型
+pub(crate) struct ResidentModel {
+    pub(crate) id: ModelId,
+    /// 模型所在的NPU内存，切换回该模型时计算图直接指向这里
+    pub(crate) memory: MemoryHandle,
+}
+
+/// 常驻模型缓存
+pub struct ModelCache {
+    /// 按使用时间排序，末尾为最近使用
+    entries: Vec<ResidentModel>,
+    capacity: usize,
+    /// 当前装入驱动计算图的模型
+    active: Option<ModelId>,
+}
+
+impl ModelCache {
+    /// 创建最多驻留`capacity`个模型的缓存（至少为1）
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: Vec::new(),
+            capacity: capacity.max(1),
+            active: None,
+        }
+    }
+
+    /// 最多驻留的模型数
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// 驻留的模型数
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// 是否没有驻留模型
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// 模型是否驻留
+    pub fn contains(&self, id: ModelId) -> bool {
+        self.entries.iter().any(|entry| entry.id == id)
+    }
+
+    /// 当前装入计算图的模型
+    pub fn active(&self) -> Option<ModelId> {
+        self.active
+    }
+
+    /// 驻留模型，从最久未使用到最近使用
+    pub fn resident_ids(&self) -> impl Iterator<Item = ModelId> + '_ {
+        self.entries.iter().map(|entry| entry.id)
+    }
+
+    pub(crate) fn set_active(&mut self, id: Option<ModelId>) {
+        self.active = id;
+    }
+
+    /// 把模型标记为最近使用，返回其驻留信息
+    pub(crate) fn touch(&mut self, id: ModelId) -> Option<&ResidentModel> {
+        let index = self.entries.iter().position(|entry| entry.id == id)?;
+        let entry = self.entries.remove(index);
+        self.entries.push(entry);
+        self.entries.last()
+    }
+
+    /// 加入新模型（调用方保证未满且不重复）
+    pub(crate) fn insert(&mut self, model: ResidentModel) {
+        self.entries.push(model);
+    }
+
+    /// 已满时移出最久未使用的模型
+    pub(crate) fn evict_if_full(&mut self) -> Option<ResidentModel> {
+        if self.entries.len() < self.capacity {
+            return None;
+        }
+
+        let evicted = self.entries.remove(0);
+        if self.active == Some(evicted.id) {
+            self.active = None;
+        }
+        Some(evicted)
+    }
+
+    /// 移出全部模型
+    pub(crate) fn drain(&mut self) -> Vec<ResidentModel> {
+        self.active = None;
+        core::mem::take(&mut self.entries)
+    }
+}
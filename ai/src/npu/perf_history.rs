@@ -0,0 +1,218 @@
+//! NPU性能历史记录
+//!
+//! 按固定间隔采样性能统计，保存在定长环形缓冲区中，
+//! 用于观察推理延迟、温度等指标在较长时间内的漂移趋势
+
+use alloc::collections::VecDeque;
+use common::PerformanceMonitor;
+use core::time::Duration;
+
+use super::NPUPerformanceStats;
+
+/// 单次采样
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PerfSample {
+    /// 采样时间 (ms)
+    pub timestamp_ms: u64,
+    /// 推理延迟 (us)
+    pub latency_us: u64,
+    /// 温度 (摄氏度)
+    pub temperature: f32,
+    /// 利用率百分比
+    pub utilization: f32,
+    /// 功耗 (W)
+    pub power: f32,
+}
+
+/// 可统计的指标
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PerfMetric {
+    Latency,
+    Temperature,
+    Utilization,
+    Power,
+}
+
+impl PerfSample {
+    fn value(&self, metric: PerfMetric) -> f32 {
+        match metric {
+            PerfMetric::Latency => self.latency_us as f32,
+            PerfMetric::Temperature => self.temperature,
+            PerfMetric::Utilization => self.utilization,
+            PerfMetric::Power => self.power,
+        }
+    }
+}
+
+/// 窗口内指标统计
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrendSummary {
+    pub min: f32,
+    pub max: f32,
+    pub mean: f32,
+    /// 最小二乘拟合斜率（指标单位/秒），正值表示上升
+    pub trend_per_sec: f32,
+}
+
+/// 性能历史环形缓冲区
+pub struct PerfHistory {
+    interval_ms: u64,
+    capacity: usize,
+    samples: VecDeque<PerfSample>,
+    last_sample_ms: Option<u64>,
+    latency_monitor: PerformanceMonitor,
+}
+
+impl PerfHistory {
+    /// 创建历史记录，每`interval_ms`毫秒最多采样一次，最多保留`capacity`个样本
+    pub fn new(interval_ms: u64, capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            interval_ms,
+            capacity,
+            samples: VecDeque::with_capacity(capacity),
+            last_sample_ms: None,
+            latency_monitor: PerformanceMonitor::new(),
+        }
+    }
+
+    /// 采样间隔 (ms)
+    pub fn interval_ms(&self) -> u64 {
+        self.interval_ms
+    }
+
+    /// 最大样本数
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// 当前样本数
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// 是否没有样本
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// 按时间顺序遍历样本
+    pub fn samples(&self) -> impl Iterator<Item = &PerfSample> {
+        self.samples.iter()
+    }
+
+    /// 延迟直方图所在的性能监控器（累计全部采样，不随环形缓冲区淘汰）
+    pub fn latency_monitor(&self) -> &PerformanceMonitor {
+        &self.latency_monitor
+    }
+
+    /// 在`now_ms`时刻尝试采样，距上次采样不足间隔时忽略并返回false
+    ///
+    /// 缓冲区已满时淘汰最旧的样本
+    pub fn sample(&mut self, now_ms: u64, stats: &NPUPerformanceStats, temperature: f32) -> bool {
+        if let Some(last) = self.last_sample_ms {
+            if now_ms.saturating_sub(last) < self.interval_ms {
+                return false;
+            }
+        }
+
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(PerfSample {
+            timestamp_ms: now_ms,
+            latency_us: stats.inference_time,
+            temperature,
+            utilization: stats.utilization,
+            power: stats.power_consumption,
+        });
+        self.latency_monitor.record(Duration::from_micros(stats.inference_time));
+        self.last_sample_ms = Some(now_ms);
+        true
+    }
+
+    /// 统计窗口内指定指标，无样本时返回None
+    pub fn summary(&self, metric: PerfMetric) -> Option<TrendSummary> {
+        let first = self.samples.front()?;
+        let count = self.samples.len() as f32;
+
+        let (mut min, mut max, mut sum) = (f32::MAX, f32::MIN, 0.0f32);
+        let (mut sum_t, mut sum_tt, mut sum_tv) = (0.0f32, 0.0f32, 0.0f32);
+        for sample in &self.samples {
+            let value = sample.value(metric);
+            let t = (sample.timestamp_ms - first.timestamp_ms) as f32 / 1000.0;
+            min = min.min(value);
+            max = max.max(value);
+            sum += value;
+            sum_t += t;
+            sum_tt += t * t;
+            sum_tv += t * value;
+        }
+
+        let mean = sum / count;
+        let variance = sum_tt - sum_t * sum_t / count;
+        let trend_per_sec = if variance > f32::EPSILON {
+            (sum_tv - sum_t * mean) / variance
+        } else {
+            0.0
+        };
+
+        Some(TrendSummary { min, max, mean, trend_per_sec })
+    }
+
+    /// 清空所有样本
+    pub fn clear(&mut self) {
+        self.samples.clear();
+        self.last_sample_ms = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    fn stats(latency_us: u64) -> NPUPerformanceStats {
+        NPUPerformanceStats {
+            inference_time: latency_us,
+            memory_usage: 0,
+            power_consumption: 2.5,
+            utilization: 50.0,
+            cache_hit_rate: 0.0,
+            throughput: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_rising_latency_has_positive_trend() {
+        // 每秒延迟上升100us，趋势约为+100us/s，温度不变时趋势为0
+        let mut history = PerfHistory::new(1000, 16);
+        for i in 0..10u64 {
+            assert!(history.sample(i * 1000, &stats(1000 + i * 100), 45.0));
+            // 间隔内的重复采样被忽略
+            assert!(!history.sample(i * 1000 + 500, &stats(99_999), 90.0));
+        }
+
+        let latency = history.summary(PerfMetric::Latency).unwrap();
+        assert_eq!(latency.min, 1000.0);
+        assert_eq!(latency.max, 1900.0);
+        assert!((latency.mean - 1450.0).abs() < 1e-3);
+        assert!((latency.trend_per_sec - 100.0).abs() < 1e-2);
+        assert_eq!(history.summary(PerfMetric::Temperature).unwrap().trend_per_sec, 0.0);
+        assert_eq!(history.latency_monitor().total_operations(), 10);
+    }
+
+    #[test]
+    fn test_ring_evicts_oldest_samples() {
+        // 超过容量后淘汰最旧样本，内存占用不随运行时间增长
+        let mut history = PerfHistory::new(10, 4);
+        for i in 0..6u64 {
+            history.sample(i * 10, &stats(i), 40.0);
+        }
+
+        assert_eq!(history.len(), 4);
+        let timestamps: Vec<u64> = history.samples().map(|s| s.timestamp_ms).collect();
+        assert_eq!(timestamps, vec![20, 30, 40, 50]);
+        assert_eq!(history.summary(PerfMetric::Latency).unwrap().min, 2.0);
+    }
+}
@@ -3,19 +3,86 @@
 //! 提供RK3588芯片6TOPS NPU的硬件加速支持
 
 use crate::{
-    AIError, InferenceEngine, ModelInfo, InferenceParams, 
+    AIError, InferenceEngine, ModelInfo, InferenceParams, QuantParams,
     NPUDriver, NPUDeviceInfo, NPUPerformanceStats, NPUConfig,
-    Precision, PowerMode, MemoryLayout, MemoryHandle, InferenceHandle,
-    OpType, InferenceTask, TaskPriority, Tensor
+    Precision, PowerMode, MemoryHandle, InferenceHandle, OpType
 };
+use super::{
+    now_micros, stream_model, ClockFn, HardwareBackend, MmioBackend, ModelReader,
+    MAX_MEMORY_ALLOCATIONS, MAX_PENDING_INFERENCES, MODEL_CHUNK_SIZE,
+};
+use super::slots::{SlotKey, SlotTable};
+use alloc::boxed::Box;
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
+use core::any::Any;
 use core::sync::atomic::{AtomicU32, Ordering};
+use core::task::Poll;
+use core::time::Duration;
+use starry_drivers::async_runtime;
+use starry_drivers::clock::{PeripheralClock, CLOCK_CONTROLLER};
+use starry_drivers::dma::DmaBuffer;
+use starry_drivers::register_map::{RegisterMap, Rk3588Map};
+use starry_kernel::gic::{self, PriorityBand};
 
-/// RK3588 NPU寄存器基地址
-const RK3588_NPU_BASE_ADDR: u32 = 0xFDE4_0000;
+/// RK3588 NPU寄存器映射
+const NPU_MAP: Rk3588Map = Rk3588Map::new(0xFDE4_0000);
+/// RK3588 NPU寄存器窗口大小
+const RK3588_NPU_REGISTER_WINDOW: u32 = 0x1000; // 4KB
 /// RK3588 NPU内存大小
 const RK3588_NPU_MEMORY_SIZE: usize = 1024 * 1024 * 512; // 512MB
+/// NPU模型内存基地址
+const RK3588_NPU_MODEL_ADDR: u32 = 0x2000_0000;
+/// 模型传输使用的DMA通道
+const MODEL_DMA_CHANNEL: u32 = 1;
+/// NPU输入缓冲区基地址
+const RK3588_NPU_INPUT_ADDR: u32 = 0x1000_0000;
+/// 异步推理每个槽位的输入缓冲区大小
+const INPUT_SLOT_STRIDE: u32 = 0x0020_0000; // 2MB
+/// 异步推理完成等待超时（微秒）
+const INFERENCE_TIMEOUT_US: u64 = 50_000;
+/// 中止任务后等待硬件确认的超时（微秒）
+const JOB_ABORT_TIMEOUT_US: u32 = 1000;
+/// NPU推理完成中断号（SPI 110）
+const RK3588_NPU_IRQ: u32 = 142;
+
+/// 完成中断处理函数记录的已完成推理槽位（每位对应一个槽位）
+static COMPLETED_SLOTS: AtomicU32 = AtomicU32::new(0);
+
+/// 已完成槽位的记录位置
+enum CompletionRecord {
+    /// 由GIC中断处理函数写入的全局记录
+    Interrupt,
+    /// 驱动自有的记录，用于不会产生硬件中断的后端
+    Local(AtomicU32),
+}
+
+impl CompletionRecord {
+    fn slots(&self) -> &AtomicU32 {
+        match self {
+            CompletionRecord::Interrupt => &COMPLETED_SLOTS,
+            CompletionRecord::Local(slots) => slots,
+        }
+    }
+}
+
+/// NPU内存中的一块分配
+struct NpuAllocation {
+    /// NPU侧地址
+    address: u32,
+    size: usize,
+    /// 已装入的模型
+    model: Option<ModelInfo>,
+}
+
+/// 已提交到NPU、尚未取回结果的推理
+struct PendingInference {
+    /// 输入DMA源缓冲区，需保留到推理完成
+    staging: DmaBuffer,
+    /// 该槽位独占的输出缓冲区，NPU把结果写到这里
+    output: DmaBuffer,
+    model: ModelInfo,
+}
 
 /// RK3588 NPU驱动
 pub struct RockchipRK3588Driver {
@@ -24,14 +91,21 @@ pub struct RockchipRK3588Driver {
     current_model: Option<ModelInfo>,
     performance_stats: NPUPerformanceStats,
     config: NPUConfig,
-    memory_pool: Vec<MemoryHandle>,
-    inference_queue: Vec<InferenceTask>,
+    memory_pool: SlotTable<NpuAllocation>,
+    /// 通过`load_model`加载、由驱动自行管理的模型内存
+    model_memory: Option<MemoryHandle>,
+    /// 计算图当前指向的模型内存
+    active_memory: Option<MemoryHandle>,
+    inference_queue: SlotTable<PendingInference>,
+    /// 中断处理中记录的已完成推理槽位
+    completed: CompletionRecord,
     temperature: f32,
     power_mode: PowerMode,
     clock_frequency: u32,
-    register_base: u32,
+    backend: Box<dyn HardwareBackend>,
     dma_channels: [bool; 4],
     interrupt_enabled: bool,
+    clock: ClockFn,
 }
 
 /// RK3588 NPU寄存器定义
@@ -47,11 +121,51 @@ mod registers {
     pub const DMA_CTRL_REG: u32 = 0x002C;
     pub const COMMAND_REG: u32 = 0x0040;
     pub const CONFIG_REG: u32 = 0x0044;
+    /// 提交推理时写入的任务槽位号
+    pub const JOB_SLOT_REG: u32 = 0x0048;
+    /// 已完成任务槽位掩码（写1清除）
+    pub const DONE_MASK_REG: u32 = 0x004C;
+    /// 计算图使用的模型在NPU内存中的地址
+    pub const MODEL_ADDR_REG: u32 = 0x0050;
+    /// 当前任务的输出缓冲区物理地址（低32位）
+    pub const OUTPUT_ADDR_REG: u32 = 0x0054;
+    /// 当前任务的输出缓冲区物理地址（高32位）
+    pub const OUTPUT_ADDR_HI_REG: u32 = 0x0058;
+    /// DMA源地址高32位，通道间隔与`DMA_SRC_REG`相同
+    pub const DMA_SRC_HI_REG: u32 = 0x0060;
+    /// 中止任务槽位掩码，硬件停止该槽位的DMA后置位对应的完成位
+    pub const JOB_ABORT_REG: u32 = 0x0064;
+}
+
+/// NPU寄存器，内容为相对寄存器窗口基地址的偏移
+///
+/// DMA寄存器按通道以0x10为间隔重复，偏移由调用者按通道计算
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NpuRegister(pub u32);
+
+/// RK3588 NPU寄存器布局
+impl RegisterMap<NpuRegister> for Rk3588Map {
+    fn base(&self) -> usize {
+        self.base_address()
+    }
+    
+    fn offset(&self, register: NpuRegister) -> usize {
+        register.0 as usize
+    }
 }
 
 impl RockchipRK3588Driver {
     /// 创建新的RK3588 NPU驱动实例
     pub fn new(config: NPUConfig) -> Result<Self, AIError> {
+        // NPU寄存器窗口由内核在启动时完成设备内存映射
+        let backend = unsafe { MmioBackend::new() };
+        let mut driver = Self::with_backend(config, Box::new(backend))?;
+        driver.completed = CompletionRecord::Interrupt;
+        Ok(driver)
+    }
+    
+    /// 使用指定的寄存器访问后端创建驱动实例
+    pub fn with_backend(config: NPUConfig, backend: Box<dyn HardwareBackend>) -> Result<Self, AIError> {
         Ok(Self {
             initialized: false,
             model_loaded: false,
@@ -65,17 +179,26 @@ impl RockchipRK3588Driver {
                 throughput: 0.0,
             },
             config,
-            memory_pool: Vec::new(),
-            inference_queue: Vec::new(),
+            memory_pool: SlotTable::new(MAX_MEMORY_ALLOCATIONS),
+            model_memory: None,
+            active_memory: None,
+            inference_queue: SlotTable::new(MAX_PENDING_INFERENCES),
+            completed: CompletionRecord::Local(AtomicU32::new(0)),
             temperature: 25.0,
             power_mode: PowerMode::Balanced,
-            clock_frequency: 800_000_000, // 800MHz
-            register_base: RK3588_NPU_BASE_ADDR,
+            clock_frequency: CLOCK_CONTROLLER.get_clock(PeripheralClock::Npu),
+            backend,
             dma_channels: [false; 4],
             interrupt_enabled: false,
+            clock: now_micros,
         })
     }
     
+    /// 替换时钟源（默认为`now_micros`），用于测试中注入模拟时钟
+    pub fn set_clock(&mut self, clock: ClockFn) {
+        self.clock = clock;
+    }
+    
     /// 初始化NPU硬件
     fn init_hardware(&mut self) -> Result<(), AIError> {
         if self.initialized {
@@ -133,7 +256,14 @@ impl RockchipRK3588Driver {
     }
     
     /// 配置中断
+    /// 
+    /// 使用硬件寄存器时向GIC注册完成中断，由`npu_completion_irq`记录完成的槽位
     fn configure_interrupts(&mut self) -> Result<(), AIError> {
+        if matches!(self.completed, CompletionRecord::Interrupt) && !gic::has_interrupt_handler(RK3588_NPU_IRQ) {
+            gic::request_irq(RK3588_NPU_IRQ, npu_completion_irq, PriorityBand::NpuDone)
+                .map_err(|e| AIError::DeviceError(e.into()))?;
+        }
+        
         // 启用完成中断和错误中断
         self.write_register(registers::INTERRUPT_REG, 0x3)?;
         self.interrupt_enabled = true;
@@ -155,20 +285,25 @@ impl RockchipRK3588Driver {
         Ok(())
     }
     
+    /// 检查寄存器偏移是否位于NPU寄存器窗口内且4字节对齐
+    fn check_register_offset(offset: u32) -> Result<(), AIError> {
+        if offset % 4 != 0 || offset > RK3588_NPU_REGISTER_WINDOW - 4 {
+            return Err(AIError::InvalidInput);
+        }
+        Ok(())
+    }
+    
     /// 写入寄存器
     fn write_register(&self, offset: u32, value: u32) -> Result<(), AIError> {
-        let addr = self.register_base + offset;
-        // 在实际硬件中，这里会进行内存映射IO写入
-        // unsafe { core::ptr::write_volatile(addr as *mut u32, value); }
+        Self::check_register_offset(offset)?;
+        NPU_MAP.write(&*self.backend, NpuRegister(offset), value);
         Ok(())
     }
     
     /// 读取寄存器
     fn read_register(&self, offset: u32) -> Result<u32, AIError> {
-        let addr = self.register_base + offset;
-        // 在实际硬件中，这里会进行内存映射IO读取
-        // Ok(unsafe { core::ptr::read_volatile(addr as *const u32) })
-        Ok(0)
+        Self::check_register_offset(offset)?;
+        Ok(NPU_MAP.read(&*self.backend, NpuRegister(offset)))
     }
     
     /// 等待寄存器状态
@@ -184,12 +319,27 @@ impl RockchipRK3588Driver {
         Err(AIError::DeviceError("寄存器等待超时".into()))
     }
     
-    /// 微秒延迟
-    fn delay_us(&self, us: u32) {
-        // 简单的忙等待延迟
-        for _ in 0..us * 100 {
-            core::hint::spin_loop();
+    /// 异步等待寄存器状态，轮询间隔内让出执行权
+    /// 
+    /// 超时按时钟截止时间判断，调度延迟不会延长实际等待时间
+    async fn wait_register_async(&self, offset: u32, mask: u32, timeout_us: u32) -> Result<(), AIError> {
+        let deadline = (self.clock)().saturating_add(timeout_us as u64);
+        loop {
+            let status = self.read_register(offset)?;
+            if (status & mask) == mask {
+                return Ok(());
+            }
+            if (self.clock)() >= deadline {
+                break;
+            }
+            async_runtime::sleep(Duration::from_micros(1)).await;
         }
+        Err(AIError::DeviceError("寄存器等待超时".into()))
+    }
+    
+    /// 微秒延迟（非异步上下文）
+    fn delay_us(&self, us: u32) {
+        async_runtime::delay(Duration::from_micros(us as u64));
     }
     
     /// 加载模型到NPU内存
@@ -199,23 +349,38 @@ impl RockchipRK3588Driver {
         }
         
         // RK3588 NPU模型加载流程
-        // 1. 解析模型格式 (RKNN/ONNX)
-        let model_info = self.parse_model_format(model_data)?;
-        
-        // 2. 优化模型结构
+        // 1. 优化模型结构
         let optimized_model = self.optimize_model(model_data)?;
         
-        // 3. 分配NPU内存
+        // 2. 分配NPU内存
         let model_handle = self.allocate_model_memory(optimized_model.len())?;
         
-        // 4. 传输模型权重到NPU
-        self.transfer_model_data(&optimized_model, model_handle)?;
+        // 3. 传输模型权重到NPU并配置计算图
+        if let Err(e) = self.install_model(model_handle, &optimized_model) {
+            let _ = self.free_memory(model_handle);
+            return Err(e);
+        }
         
-        // 5. 配置模型计算图
-        self.configure_model_graph(&model_info)?;
+        // 驱动自行管理的模型只保留最新一个
+        if let Some(previous) = self.model_memory.replace(model_handle) {
+            let _ = self.free_memory(previous);
+        }
+        Ok(())
+    }
+    
+    /// 把模型传输到已分配的NPU内存，并将计算图指向该模型
+    fn install_model(&mut self, memory: MemoryHandle, model_data: &[u8]) -> Result<(), AIError> {
+        // 解析模型格式 (RKNN/ONNX)
+        let model_info = self.parse_model_format(model_data)?;
         
-        self.model_loaded = true;
-        self.current_model = Some(model_info);
+        let allocation = self.memory_pool.get(memory.0)?;
+        if model_data.len() > allocation.size {
+            return Err(AIError::MemoryAllocationError);
+        }
+        self.transfer_model_data(model_data, allocation.address)?;
+        
+        self.memory_pool.get_mut(memory.0)?.model = Some(model_info);
+        self.activate_model(memory)?;
         
         log::info!("模型加载完成，输入形状: {:?}", self.current_model.as_ref().unwrap().input_shape);
         Ok(())
@@ -229,6 +394,7 @@ impl RockchipRK3588Driver {
             input_shape: vec![1, 3, 640, 640],
             output_shape: vec![1, 8400, 84],
             precision: Precision::INT8,
+            quant: None,
             ops_count: 150,
         })
     }
@@ -241,65 +407,98 @@ impl RockchipRK3588Driver {
     
     /// 分配模型内存
     fn allocate_model_memory(&mut self, size: usize) -> Result<MemoryHandle, AIError> {
-        let handle = MemoryHandle(self.memory_pool.len());
-        self.memory_pool.push(handle);
+        let address = self.find_model_region(size)?;
+        let handle = MemoryHandle(self.memory_pool.insert(NpuAllocation { address, size, model: None })?);
         self.performance_stats.memory_usage += size;
         Ok(handle)
     }
     
-    /// 传输模型数据
-    fn transfer_model_data(&self, model_data: &[u8], handle: MemoryHandle) -> Result<(), AIError> {
-        // 使用DMA传输模型数据到NPU内存
-        // 这里简化实现
-        Ok(())
+    /// 在NPU模型内存中为`size`字节查找第一个足够大的空闲区域（64字节对齐）
+    fn find_model_region(&self, size: usize) -> Result<u32, AIError> {
+        let mut used: Vec<(usize, usize)> = self.memory_pool
+            .values()
+            .map(|allocation| (allocation.address as usize, allocation.size))
+            .collect();
+        used.sort_unstable();
+        
+        let mut start = RK3588_NPU_MODEL_ADDR as usize;
+        for (address, len) in used {
+            if address >= start + size {
+                break;
+            }
+            start = start.max((address + len + 63) & !63);
+        }
+        
+        let end = RK3588_NPU_MODEL_ADDR as usize + RK3588_NPU_MEMORY_SIZE;
+        if size == 0 || start + size > end {
+            return Err(AIError::MemoryAllocationError);
+        }
+        Ok(start as u32)
     }
     
-    /// 配置模型计算图
-    fn configure_model_graph(&self, model_info: &ModelInfo) -> Result<(), AIError> {
-        // 配置NPU计算图结构
-        // 这里简化实现
+    /// 传输模型数据
+    fn transfer_model_data(&self, model_data: &[u8], address: u32) -> Result<(), AIError> {
+        let mut remaining = model_data;
+        self.stream_to_npu(&mut |chunk: &mut [u8]| {
+            let n = chunk.len().min(remaining.len());
+            chunk[..n].copy_from_slice(&remaining[..n]);
+            remaining = &remaining[n..];
+            Ok(n)
+        }, model_data.len(), address)?;
         Ok(())
     }
     
-    /// 执行NPU推理
-    fn execute_npu_inference(&mut self, input: &[f32]) -> Result<Vec<f32>, AIError> {
-        if !self.model_loaded {
-            return Err(AIError::ModelNotFound);
-        }
-        
-        let model_info = self.current_model.as_ref().unwrap();
-        let expected_input_size: usize = model_info.input_shape.iter().product();
-        
-        if input.len() != expected_input_size {
-            return Err(AIError::InvalidInput);
-        }
+    /// 经DMA暂存缓冲区分块把模型数据写入NPU内存`address`
+    /// 
+    /// 每块由`reader`直接填入暂存缓冲区，再以其物理地址作为DMA源；返回首块模型头解析出的模型信息
+    fn stream_to_npu(&self, reader: ModelReader<'_>, total_len: usize, address: u32) -> Result<ModelInfo, AIError> {
+        let mut staging = dma_buffer(MODEL_CHUNK_SIZE.min(total_len))?;
+        let source = staging.physical_address();
         
-        // RK3588 NPU推理流程
-        // 1. 预处理输入数据
-        let preprocessed_input = self.preprocess_input(input, model_info)?;
-        
-        // 2. 配置NPU计算单元
-        self.configure_computation_units()?;
-        
-        // 3. 启动DMA传输输入数据
-        self.dma_transfer_input(&preprocessed_input)?;
-        
-        // 4. 启动推理
-        self.start_inference()?;
-        
-        // 5. 等待推理完成
-        self.wait_inference_completion()?;
-        
-        // 6. 读取输出数据
-        let raw_output = self.read_output_data()?;
+        let mut model_info = None;
+        stream_model(reader, total_len, staging.as_mut_slice(), |offset, chunk| {
+            if offset == 0 {
+                model_info = Some(self.parse_model_format(chunk)?);
+            }
+            self.dma_transfer_model_chunk(source, address + offset as u32, chunk.len())
+        })?;
+        model_info.ok_or(AIError::ModelLoadError)
+    }
+    
+    /// 把流式读取的模型写入已分配的NPU内存`memory`，并将计算图指向该模型
+    fn install_streamed_model(&mut self, memory: MemoryHandle, reader: ModelReader<'_>, total_len: usize) -> Result<(), AIError> {
+        let address = self.memory_pool.get(memory.0)?.address;
+        let model_info = self.stream_to_npu(reader, total_len, address)?;
+        self.memory_pool.get_mut(memory.0)?.model = Some(model_info);
+        self.activate_model(memory)
+    }
+    
+    /// DMA传输`len`字节：源为物理地址`source`处的暂存缓冲区，目标为NPU内存地址`address`
+    fn dma_transfer_model_chunk(&self, source: u64, address: u32, len: usize) -> Result<(), AIError> {
+        let channel_offset = MODEL_DMA_CHANNEL * 0x10;
         
-        // 7. 后处理输出数据
-        let output = self.postprocess_output(&raw_output, model_info)?;
+        self.write_dma_address(registers::DMA_SRC_REG + channel_offset, registers::DMA_SRC_HI_REG + channel_offset, source)?;
+        self.write_register(registers::DMA_DST_REG + channel_offset, address)?;
+        self.write_register(registers::DMA_LEN_REG + channel_offset, len as u32)?;
         
-        // 更新性能统计
-        self.update_performance_stats();
+        // 启动DMA传输并等待完成
+        self.write_register(registers::DMA_CTRL_REG + channel_offset, 0x2)?;
+        self.wait_register(registers::DMA_CTRL_REG + channel_offset, 0x4, 1000)?;
         
-        Ok(output)
+        Ok(())
+    }
+    
+    /// 配置模型计算图，使其使用NPU内存地址`address`处的模型
+    fn configure_model_graph(&self, model_info: &ModelInfo, address: u32) -> Result<(), AIError> {
+        // 配置NPU计算图结构
+        // 这里简化实现，只切换模型地址
+        self.write_register(registers::MODEL_ADDR_REG, address)
+    }
+    
+    /// 执行NPU推理：提交到任务槽位并等待完成
+    fn execute_npu_inference(&mut self, input: &[f32]) -> Result<Vec<f32>, AIError> {
+        let handle = self.submit_inference(input)?;
+        self.wait_inference(handle)
     }
     
     /// 预处理输入数据
@@ -315,13 +514,9 @@ impl RockchipRK3588Driver {
                 Ok(result)
             }
             Precision::INT8 => {
-                // 量化到INT8
-                let mut result = Vec::with_capacity(input.len());
-                for &value in input {
-                    let quantized = (value * 127.0).clamp(-128.0, 127.0) as i8;
-                    result.push(quantized as u8);
-                }
-                Ok(result)
+                // 按模型的量化参数量化到INT8，缺省为对称量化
+                let params = model_info.quant.unwrap_or_default();
+                Ok(input.iter().map(|&value| params.quantize(value) as u8).collect())
             }
             _ => Err(AIError::UnsupportedPrecision),
         }
@@ -334,47 +529,123 @@ impl RockchipRK3588Driver {
         Ok(())
     }
     
-    /// DMA传输输入数据
-    fn dma_transfer_input(&self, input_data: &[u8]) -> Result<(), AIError> {
-        // 使用DMA通道0传输输入数据
-        let channel = 0;
-        let dma_src = registers::DMA_SRC_REG + (channel as u32 * 0x10);
-        let dma_dst = registers::DMA_DST_REG + (channel as u32 * 0x10);
-        let dma_len = registers::DMA_LEN_REG + (channel as u32 * 0x10);
+    /// 启动推理
+    fn start_inference(&self) -> Result<(), AIError> {
+        self.write_register(registers::COMMAND_REG, 0x1)?; // 启动命令
+        Ok(())
+    }
+    
+    /// 异步等待推理完成，等待期间执行器可运行其他任务
+    pub async fn wait_inference_completion_async(&self) -> Result<(), AIError> {
+        self.wait_register_async(registers::STATUS_REG, 0x2, 50000).await // 50ms超时
+    }
+    
+    /// 提交异步推理：预处理输入，配置DMA与任务槽位后启动推理，不等待完成
+    /// 
+    /// NPU在输入DMA完成后自动开始计算，结果写入该槽位的输出缓冲区，
+    /// 完成时置位`DONE_MASK_REG`中的槽位位并触发完成中断
+    fn submit_inference(&mut self, input: &[f32]) -> Result<InferenceHandle, AIError> {
+        if !self.model_loaded {
+            return Err(AIError::ModelNotFound);
+        }
+        self.check_device_status()?;
         
-        // 设置DMA传输参数
-        self.write_register(dma_src, input_data.as_ptr() as u32)?;
-        self.write_register(dma_dst, 0x1000_0000)?; // NPU输入缓冲区地址
-        self.write_register(dma_len, input_data.len() as u32)?;
+        let model = self.current_model.clone().ok_or(AIError::ModelNotFound)?;
+        if input.len() != model.input_shape.iter().product::<usize>() {
+            return Err(AIError::InvalidInput);
+        }
         
-        // 启动DMA传输
-        self.write_register(registers::DMA_CTRL_REG + (channel as u32 * 0x10), 0x2)?;
+        let input_data = self.preprocess_input(input, &model)?;
+        if input_data.len() > INPUT_SLOT_STRIDE as usize {
+            return Err(AIError::InvalidInput);
+        }
+        let mut staging = dma_buffer(input_data.len())?;
+        staging.as_mut_slice().copy_from_slice(&input_data);
+        let output = dma_buffer(output_bytes(&model)?)?;
         
-        // 等待DMA完成
-        self.wait_register(registers::DMA_CTRL_REG + (channel as u32 * 0x10), 0x4, 1000)?;
+        let key = self.inference_queue.insert(PendingInference { staging, output, model })?;
+        self.completed.slots().fetch_and(!(1 << key.index()), Ordering::AcqRel);
+        if let Err(e) = self.start_job(key) {
+            let _ = self.inference_queue.remove(key);
+            return Err(e);
+        }
+        Ok(InferenceHandle(key))
+    }
+    
+    /// 按物理地址配置槽位`key`的输入DMA和输出缓冲区并启动推理
+    fn start_job(&self, key: SlotKey) -> Result<(), AIError> {
+        let slot = key.index() as u32;
+        let pending = self.inference_queue.get(key)?;
         
-        Ok(())
+        self.configure_computation_units()?;
+        self.write_dma_address(registers::DMA_SRC_REG, registers::DMA_SRC_HI_REG, pending.staging.physical_address())?;
+        self.write_register(registers::DMA_DST_REG, RK3588_NPU_INPUT_ADDR + slot * INPUT_SLOT_STRIDE)?;
+        self.write_register(registers::DMA_LEN_REG, pending.staging.size() as u32)?;
+        self.write_dma_address(registers::OUTPUT_ADDR_REG, registers::OUTPUT_ADDR_HI_REG, pending.output.physical_address())?;
+        self.write_register(registers::DMA_CTRL_REG, 0x2)?;
+        self.write_register(registers::JOB_SLOT_REG, slot)?;
+        self.start_inference()
     }
     
-    /// 启动推理
-    fn start_inference(&self) -> Result<(), AIError> {
-        self.write_register(registers::COMMAND_REG, 0x1)?; // 启动命令
-        Ok(())
+    /// 中止槽位`key`的任务并等待硬件以完成位确认，确认后该槽位的缓冲区不再被DMA访问
+    fn abort_job(&self, key: SlotKey) -> Result<(), AIError> {
+        let bit = 1u32 << key.index();
+        self.write_register(registers::JOB_ABORT_REG, bit)?;
+        self.wait_register(registers::DONE_MASK_REG, bit, JOB_ABORT_TIMEOUT_US)?;
+        self.completed.slots().fetch_and(!bit, Ordering::AcqRel);
+        self.write_register(registers::DONE_MASK_REG, bit)
     }
     
-    /// 等待推理完成
-    fn wait_inference_completion(&self) -> Result<(), AIError> {
-        // 等待完成中断或轮询状态寄存器
-        self.wait_register(registers::STATUS_REG, 0x2, 50000)?; // 50ms超时
-        Ok(())
+    /// 放弃等待中的推理：任务中止后才释放缓冲区，无法中止时保留在队列中，由`reset`统一回收
+    fn abandon_inference(&mut self, key: SlotKey) {
+        match self.abort_job(key) {
+            Ok(()) => {
+                let _ = self.inference_queue.remove(key);
+            }
+            Err(e) => log::warn!("NPU任务槽位{}无法中止，保留其缓冲区: {:?}", key.index(), e),
+        }
+    }
+    
+    /// 写入64位物理地址：低32位写入`low`，高32位写入`high`
+    fn write_dma_address(&self, low: u32, high: u32, address: u64) -> Result<(), AIError> {
+        self.write_register(low, (address & 0xFFFF_FFFF) as u32)?;
+        self.write_register(high, (address >> 32) as u32)
     }
     
-    /// 读取输出数据
-    fn read_output_data(&self) -> Result<Vec<u8>, AIError> {
-        // 从NPU输出缓冲区读取数据
-        // 这里简化实现，返回模拟数据
-        let output_size = 8400 * 84 * 4; // FP32输出
-        Ok(vec![0u8; output_size])
+    /// 推理完成中断处理：记录完成的槽位并清除硬件完成位
+    pub fn handle_completion_interrupt(&self) {
+        record_completions(&*self.backend, self.completed.slots());
+    }
+    
+    /// 非阻塞查询异步推理结果
+    /// 
+    /// 未完成时返回`Pending`；完成后从该槽位的输出缓冲区取回输出并释放句柄，失效句柄返回`StaleHandle`
+    pub fn poll_inference(&mut self, handle: InferenceHandle) -> Poll<Result<Vec<f32>, AIError>> {
+        if let Err(e) = self.inference_queue.get(handle.0) {
+            return Poll::Ready(Err(e));
+        }
+        
+        let bit = 1u32 << handle.0.index();
+        let hardware_done = match self.read_register(registers::DONE_MASK_REG) {
+            Ok(done) => done & bit != 0,
+            Err(e) => return Poll::Ready(Err(e)),
+        };
+        let completed = self.completed.slots();
+        if !hardware_done && completed.load(Ordering::Acquire) & bit == 0 {
+            return Poll::Pending;
+        }
+        
+        completed.fetch_and(!bit, Ordering::AcqRel);
+        if hardware_done {
+            let _ = self.write_register(registers::DONE_MASK_REG, bit);
+        }
+        
+        let result = self.inference_queue.remove(handle.0).and_then(|pending| {
+            let output = self.postprocess_output(pending.output.as_slice(), &pending.model)?;
+            self.update_performance_stats();
+            Ok(output)
+        });
+        Poll::Ready(result)
     }
     
     /// 后处理输出数据
@@ -390,10 +661,8 @@ impl RockchipRK3588Driver {
                 }
             }
             Precision::INT8 => {
-                for &byte in raw_output {
-                    let value = (byte as i8 as f32) / 127.0;
-                    output.push(value);
-                }
+                let params = model_info.quant.unwrap_or_default();
+                output.extend(raw_output.iter().map(|&byte| params.dequantize(byte as i8)));
             }
             _ => return Err(AIError::UnsupportedPrecision),
         }
@@ -454,6 +723,36 @@ impl InferenceEngine for RockchipRK3588Driver {
         // 设置推理参数：批处理大小、精度等
         Ok(())
     }
+    
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    
+    /// 流式加载模型
+    /// 
+    /// 每块到达后立即DMA到NPU内存，完整模型不会同时驻留在CPU内存中。
+    /// 流式加载的模型需已离线优化；加载失败时释放已分配的NPU内存
+    fn load_model_streaming(&mut self, reader: ModelReader<'_>, total_len: usize) -> Result<(), AIError> {
+        if total_len == 0 {
+            return Err(AIError::ModelLoadError);
+        }
+        if !self.initialized {
+            self.init_hardware()?;
+        }
+        
+        let memory = self.allocate_model_memory(total_len)?;
+        if let Err(e) = self.install_streamed_model(memory, reader, total_len) {
+            let _ = self.free_memory(memory);
+            return Err(e);
+        }
+        
+        // 与`load_model`共用驱动自行管理的模型内存，只保留最新一个
+        if let Some(previous) = self.model_memory.replace(memory) {
+            let _ = self.free_memory(previous);
+        }
+        log::info!("模型流式加载完成，大小: {} 字节", total_len);
+        Ok(())
+    }
 }
 
 impl NPUDriver for RockchipRK3588Driver {
@@ -481,10 +780,9 @@ impl NPUDriver for RockchipRK3588Driver {
     }
     
     fn set_clock_frequency(&mut self, frequency: u32) -> Result<(), AIError> {
-        // RK3588 NPU时钟频率范围：100MHz - 800MHz
-        if frequency < 100_000_000 || frequency > 800_000_000 {
-            return Err(AIError::DeviceError("频率超出支持范围".into()));
-        }
+        // 频率范围由CRU校验（RK3588 NPU：100MHz - 800MHz）
+        CLOCK_CONTROLLER.set_clock(PeripheralClock::Npu, frequency)
+            .map_err(|_| AIError::DeviceError("频率超出支持范围".into()))?;
         
         self.clock_frequency = frequency;
         self.configure_clock()?;
@@ -507,6 +805,8 @@ impl NPUDriver for RockchipRK3588Driver {
     fn reset(&mut self) -> Result<(), AIError> {
         self.reset_npu()?;
         self.memory_pool.clear();
+        self.model_memory = None;
+        self.active_memory = None;
         self.inference_queue.clear();
         self.model_loaded = false;
         self.current_model = None;
@@ -544,64 +844,130 @@ impl NPUDriver for RockchipRK3588Driver {
     }
     
     fn get_temperature(&self) -> Result<f32, AIError> {
-        // 从温度传感器读取
+        // 从温度传感器读取，并上报给散热管理器
+        starry_drivers::thermal::report_npu_temperature(self.temperature);
         Ok(self.temperature)
     }
     
     fn allocate_memory(&mut self, size: usize) -> Result<MemoryHandle, AIError> {
-        let handle = MemoryHandle(self.memory_pool.len());
-        self.memory_pool.push(handle);
-        self.performance_stats.memory_usage += size;
-        Ok(handle)
+        self.allocate_model_memory(size)
     }
     
     fn free_memory(&mut self, handle: MemoryHandle) -> Result<(), AIError> {
-        if let Some(pos) = self.memory_pool.iter().position(|&h| h.0 == handle.0) {
-            self.memory_pool.remove(pos);
+        let allocation = self.memory_pool.remove(handle.0)?;
+        self.performance_stats.memory_usage -= allocation.size;
+        if self.model_memory == Some(handle) {
+            self.model_memory = None;
+        }
+        if self.active_memory == Some(handle) {
+            self.active_memory = None;
+            self.model_loaded = false;
+            self.current_model = None;
         }
         Ok(())
     }
     
-    fn infer_async(&mut self, input: &[f32]) -> Result<InferenceHandle, AIError> {
-        let task = InferenceTask {
-            model_id: 0,
-            inputs: vec![Tensor {
-                data: input.to_vec(),
-                shape: vec![1, 3, 640, 640],
-                data_type: Precision::FP32,
-                layout: MemoryLayout::NCHW,
-            }],
-            outputs: Vec::new(),
-            priority: TaskPriority::Normal,
-        };
+    fn load_model_into(&mut self, memory: MemoryHandle, model_data: &[u8]) -> Result<(), AIError> {
+        if !self.initialized {
+            self.init_hardware()?;
+        }
+        let optimized_model = self.optimize_model(model_data)?;
+        self.install_model(memory, &optimized_model)
+    }
+    
+    fn activate_model(&mut self, memory: MemoryHandle) -> Result<(), AIError> {
+        let allocation = self.memory_pool.get(memory.0)?;
+        let model_info = allocation.model.clone().ok_or(AIError::ModelNotFound)?;
+        self.configure_model_graph(&model_info, allocation.address)?;
         
-        self.inference_queue.push(task);
-        Ok(InferenceHandle(self.inference_queue.len()))
+        self.model_loaded = true;
+        self.current_model = Some(model_info);
+        self.active_memory = Some(memory);
+        Ok(())
+    }
+    
+    fn infer_async(&mut self, input: &[f32]) -> Result<InferenceHandle, AIError> {
+        self.submit_inference(input)
     }
     
     fn wait_inference(&mut self, handle: InferenceHandle) -> Result<Vec<f32>, AIError> {
-        // 在实际实现中，这里会处理异步推理队列
-        // 这里简化实现，直接执行同步推理
-        if let Some(task) = self.inference_queue.get(handle.0 - 1) {
-            if let Some(input) = task.inputs.first() {
-                return self.infer(&input.data);
+        // 在时钟截止时间前等待完成位（或中断记录的完成标志），等待期间持续检查温度和错误状态
+        let deadline = (self.clock)().saturating_add(INFERENCE_TIMEOUT_US);
+        loop {
+            if let Poll::Ready(result) = self.poll_inference(handle) {
+                return result;
+            }
+            
+            if let Err(e) = self.check_device_status() {
+                self.abandon_inference(handle.0);
+                return Err(e);
+            }
+            if (self.clock)() >= deadline {
+                break;
             }
+            core::hint::spin_loop();
         }
         
-        Err(AIError::InferenceError("推理任务未找到".into()))
+        self.abandon_inference(handle.0);
+        Err(AIError::DeviceError("推理等待超时".into()))
     }
 }
 
 impl Drop for RockchipRK3588Driver {
     fn drop(&mut self) {
-        let _ = self.reset();
+        // 未初始化的设备不访问寄存器
+        if self.initialized {
+            let _ = self.reset();
+        }
         log::info!("RK3588 NPU驱动已释放");
     }
 }
 
+/// 读取并清除硬件完成位，记录到`completed`
+fn record_completions(backend: &dyn HardwareBackend, completed: &AtomicU32) {
+    let register = NpuRegister(registers::DONE_MASK_REG);
+    let done = NPU_MAP.read(backend, register);
+    if done != 0 {
+        completed.fetch_or(done, Ordering::AcqRel);
+        NPU_MAP.write(backend, register, done);
+    }
+}
+
+/// NPU完成中断处理函数，寄存器窗口由内核在启动时完成映射
+fn npu_completion_irq(_interrupt_id: u32) {
+    let backend = unsafe { MmioBackend::new() };
+    record_completions(&backend, &COMPLETED_SLOTS);
+}
+
+/// 分配推理使用的DMA缓冲区
+fn dma_buffer(size: usize) -> Result<DmaBuffer, AIError> {
+    if size == 0 {
+        return Err(AIError::InvalidInput);
+    }
+    unsafe { DmaBuffer::new(size) }.map_err(|_| AIError::MemoryAllocationError)
+}
+
+/// 模型输出占用的字节数
+fn output_bytes(model: &ModelInfo) -> Result<usize, AIError> {
+    let element = match model.precision {
+        Precision::FP32 => 4,
+        Precision::INT8 => 1,
+        _ => return Err(AIError::UnsupportedPrecision),
+    };
+    Ok(model.output_shape.iter().product::<usize>() * element)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::npu::MockHardwareBackend;
+    use alloc::sync::Arc;
+    use starry_kernel::sync::IrqMutex;
+    
+    /// 寄存器偏移在模拟寄存器窗口中的绝对地址
+    fn register_address(offset: u32) -> usize {
+        NPU_MAP.base_address() + NPU_MAP.offset(NpuRegister(offset))
+    }
     
     #[test]
     fn test_rk3588_driver_creation() {
@@ -618,4 +984,319 @@ mod tests {
         assert_eq!(info.vendor, "Rockchip");
         assert_eq!(info.peak_performance, 6.0);
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_register_round_trip_on_mock_backend() {
+        // 模拟后端上写入的寄存器值可以原样读回
+        let backend = MockHardwareBackend::new(NPU_MAP.base_address(), RK3588_NPU_REGISTER_WINDOW as usize);
+        let driver = RockchipRK3588Driver::with_backend(NPUConfig::default(), Box::new(backend)).unwrap();
+        
+        driver.write_register(registers::CONFIG_REG, 0x7).unwrap();
+        driver.write_register(RK3588_NPU_REGISTER_WINDOW - 4, 0xDEAD_BEEF).unwrap();
+        assert_eq!(driver.read_register(registers::CONFIG_REG), Ok(0x7));
+        assert_eq!(driver.read_register(RK3588_NPU_REGISTER_WINDOW - 4), Ok(0xDEAD_BEEF));
+        assert_eq!(driver.read_register(registers::STATUS_REG), Ok(0));
+    }
+    
+    #[test]
+    fn test_register_offset_outside_window_rejected() {
+        // 越界或未对齐的寄存器偏移被拒绝
+        let backend = MockHardwareBackend::new(NPU_MAP.base_address(), RK3588_NPU_REGISTER_WINDOW as usize);
+        let driver = RockchipRK3588Driver::with_backend(NPUConfig::default(), Box::new(backend)).unwrap();
+        
+        assert_eq!(driver.write_register(RK3588_NPU_REGISTER_WINDOW, 1), Err(AIError::InvalidInput));
+        assert_eq!(driver.read_register(u32::MAX - 3), Err(AIError::InvalidInput));
+        assert_eq!(driver.read_register(registers::STATUS_REG + 1), Err(AIError::InvalidInput));
+    }
+    
+    /// 使用模拟后端、已加载FP32模型的驱动
+    fn mock_driver_with_model() -> RockchipRK3588Driver {
+        let backend = MockHardwareBackend::new(NPU_MAP.base_address(), RK3588_NPU_REGISTER_WINDOW as usize);
+        driver_with_model(Box::new(backend))
+    }
+    
+    /// 使用指定后端、已加载FP32模型的驱动
+    fn driver_with_model(backend: Box<dyn HardwareBackend>) -> RockchipRK3588Driver {
+        let mut driver = RockchipRK3588Driver::with_backend(NPUConfig::default(), backend).unwrap();
+        driver.initialized = true;
+        driver.model_loaded = true;
+        driver.current_model = Some(ModelInfo {
+            name: "fp32_test",
+            version: "1.0",
+            input_shape: vec![1, 4],
+            output_shape: vec![1, 8400, 84],
+            precision: Precision::FP32,
+            quant: None,
+        });
+        driver
+    }
+    
+    #[test]
+    fn test_async_inference_completes_on_done_bit() {
+        // 提交后未完成时poll返回Pending，槽位完成位置位后wait_inference返回输出
+        let mut driver = mock_driver_with_model();
+        let handle = driver.infer_async(&[0.1, 0.2, 0.3, 0.4]).unwrap();
+        let slot = handle.0.index() as u32;
+        assert_eq!(driver.read_register(registers::JOB_SLOT_REG), Ok(slot));
+        assert_eq!(driver.read_register(registers::COMMAND_REG), Ok(0x1));
+        assert!(driver.poll_inference(handle).is_pending());
+        
+        driver.write_register(registers::DONE_MASK_REG, 1 << slot).unwrap();
+        let output = driver.wait_inference(handle).unwrap();
+        assert_eq!(output.len(), 8400 * 84);
+        assert_eq!(driver.wait_inference(handle), Err(AIError::StaleHandle));
+    }
+    
+    /// 写入中止寄存器时立即置位对应完成位的模拟后端
+    struct AbortAckBackend {
+        registers: MockHardwareBackend,
+    }
+    
+    impl HardwareBackend for AbortAckBackend {
+        fn read32(&self, addr: usize) -> u32 {
+            self.registers.read32(addr)
+        }
+        
+        fn write32(&self, addr: usize, value: u32) {
+            self.registers.write32(addr, value);
+            if addr == register_address(registers::JOB_ABORT_REG) {
+                let done = register_address(registers::DONE_MASK_REG);
+                self.registers.write32(done, self.registers.read32(done) | value);
+            }
+        }
+    }
+    
+    #[test]
+    fn test_async_inference_aborts_when_overheated() {
+        // 等待期间温度超过阈值时先中止任务，硬件确认后才释放缓冲区
+        let backend = AbortAckBackend {
+            registers: MockHardwareBackend::new(NPU_MAP.base_address(), RK3588_NPU_REGISTER_WINDOW as usize),
+        };
+        let mut driver = driver_with_model(Box::new(backend));
+        let handle = driver.infer_async(&[0.0; 4]).unwrap();
+        driver.temperature = driver.config.thermal_threshold + 1.0;
+        
+        assert!(matches!(driver.wait_inference(handle), Err(AIError::DeviceError(_))));
+        assert_eq!(driver.read_register(registers::JOB_ABORT_REG), Ok(1 << handle.0.index()));
+        assert!(driver.inference_queue.is_empty());
+    }
+    
+    static MOCK_NOW_US: core::sync::atomic::AtomicU64 = core::sync::atomic::AtomicU64::new(0);
+    
+    fn mock_clock() -> u64 {
+        MOCK_NOW_US.fetch_add(1_000, Ordering::Relaxed)
+    }
+    
+    #[test]
+    fn test_wait_inference_times_out_by_clock() {
+        // 完成位始终未置位时按时钟截止时间超时；任务无法中止时缓冲区保留在队列中，reset后回收
+        let mut driver = mock_driver_with_model();
+        driver.set_clock(mock_clock);
+        let handle = driver.infer_async(&[0.0; 4]).unwrap();
+        
+        assert_eq!(driver.wait_inference(handle), Err(AIError::DeviceError("推理等待超时".into())));
+        assert!(driver.inference_queue.get(handle.0).is_ok());
+        
+        driver.write_register(registers::STATUS_REG, 0x1).unwrap();
+        driver.reset().unwrap();
+        assert!(driver.inference_queue.is_empty());
+    }
+    
+    #[test]
+    fn test_wait_register_async_times_out_by_clock() {
+        // 时钟已越过截止时间时立即超时，不再进入下一次休眠
+        use core::future::Future;
+        let mut driver = mock_driver_with_model();
+        driver.set_clock(mock_clock);
+        let mut cx = core::task::Context::from_waker(core::task::Waker::noop());
+        
+        let mut wait = core::pin::pin!(driver.wait_register_async(registers::STATUS_REG, 0x2, 500));
+        assert_eq!(wait.as_mut().poll(&mut cx), Poll::Ready(Err(AIError::DeviceError("寄存器等待超时".into()))));
+    }
+    
+    #[test]
+    fn test_completion_interrupt_records_slot() {
+        // 各槽位使用独立的输出缓冲区；中断记录的完成槽位在硬件完成位清除后仍可取回
+        let mut driver = mock_driver_with_model();
+        let first = driver.infer_async(&[0.0; 4]).unwrap();
+        let first_output = driver.read_register(registers::OUTPUT_ADDR_REG).unwrap();
+        let second = driver.infer_async(&[0.0; 4]).unwrap();
+        assert_ne!(driver.read_register(registers::OUTPUT_ADDR_REG).unwrap(), first_output);
+        
+        driver.write_register(registers::DONE_MASK_REG, 1 << second.0.index()).unwrap();
+        driver.handle_completion_interrupt();
+        driver.write_register(registers::DONE_MASK_REG, 0).unwrap();
+        
+        assert!(driver.poll_inference(first).is_pending());
+        assert!(matches!(driver.poll_inference(second), Poll::Ready(Ok(_))));
+    }
+    
+    #[test]
+    fn test_activate_model_repoints_graph() {
+        // 两个驻留模型位于不同的NPU地址，切换只改写计算图的模型地址而不发起模型DMA
+        let mut driver = mock_driver_with_model();
+        let first = driver.allocate_memory(4096).unwrap();
+        let second = driver.allocate_memory(4096).unwrap();
+        for memory in [first, second] {
+            driver.memory_pool.get_mut(memory.0).unwrap().model = driver.current_model.clone();
+        }
+        let first_address = driver.memory_pool.get(first.0).unwrap().address;
+        let second_address = driver.memory_pool.get(second.0).unwrap().address;
+        assert_eq!(first_address, RK3588_NPU_MODEL_ADDR);
+        assert_eq!(second_address, RK3588_NPU_MODEL_ADDR + 4096);
+        
+        driver.activate_model(second).unwrap();
+        assert_eq!(driver.read_register(registers::MODEL_ADDR_REG), Ok(second_address));
+        driver.activate_model(first).unwrap();
+        assert_eq!(driver.read_register(registers::MODEL_ADDR_REG), Ok(first_address));
+        assert_eq!(driver.read_register(registers::DMA_CTRL_REG + MODEL_DMA_CHANNEL * 0x10), Ok(0));
+        
+        // 释放当前模型后不能再推理，释放的区域可被重新分配
+        driver.free_memory(first).unwrap();
+        assert_eq!(driver.infer_async(&[0.0; 4]), Err(AIError::ModelNotFound));
+        let reused = driver.allocate_memory(1024).unwrap();
+        assert_eq!(driver.memory_pool.get(reused.0).unwrap().address, first_address);
+    }
+    
+    fn int8_model(quant: Option<QuantParams>) -> ModelInfo {
+        ModelInfo {
+            name: "int8_test",
+            version: "1.0",
+            input_shape: vec![1, 6],
+            output_shape: vec![1, 6],
+            precision: Precision::INT8,
+            quant,
+        }
+    }
+    
+    #[test]
+    fn test_int8_asymmetric_round_trip() {
+        // 非对称量化参数下量化再反量化，误差不超过半个量化步长
+        let driver = RockchipRK3588Driver::new(NPUConfig::default()).unwrap();
+        let params = QuantParams { scale: 0.05, zero_point: -20 };
+        let model = int8_model(Some(params));
+        let values = [0.0, 0.37, -0.98, 2.5, 6.1, -5.4];
+        
+        let quantized = driver.preprocess_input(&values, &model).unwrap();
+        assert_eq!(quantized[0] as i8, -20);
+        let restored = driver.postprocess_output(&quantized, &model).unwrap();
+        for (value, restored) in values.iter().zip(restored.iter()) {
+            assert!((value - restored).abs() <= params.scale / 2.0 + 1e-6, "{} -> {}", value, restored);
+        }
+    }
+    
+    #[test]
+    fn test_int8_defaults_to_symmetric() {
+        // 未提供量化参数时按scale=1/127、zero_point=0处理，超出范围的值被截断
+        let driver = RockchipRK3588Driver::new(NPUConfig::default()).unwrap();
+        let model = int8_model(None);
+        let values = [0.0, 0.25, -0.25, 1.0, -1.0, 3.0];
+        
+        let quantized = driver.preprocess_input(&values, &model).unwrap();
+        let quantized: Vec<i8> = quantized.iter().map(|&q| q as i8).collect();
+        assert_eq!(quantized, vec![0, 32, -32, 127, -127, 127]);
+        let restored = driver.postprocess_output(&[32u8], &model).unwrap();
+        assert!((restored[0] - 0.25).abs() <= 1.0 / 127.0);
+    }
+    
+    /// 模拟模型DMA通道：启动传输时从源物理地址拷贝到模拟的NPU模型内存并置位完成位
+    struct DmaSimBackend {
+        registers: MockHardwareBackend,
+        npu_memory: Arc<IrqMutex<Vec<u8>>>,
+    }
+    
+    impl DmaSimBackend {
+        fn register(&self, offset: u32) -> u32 {
+            self.registers.read32(register_address(offset + MODEL_DMA_CHANNEL * 0x10))
+        }
+    }
+    
+    impl HardwareBackend for DmaSimBackend {
+        fn read32(&self, addr: usize) -> u32 {
+            self.registers.read32(addr)
+        }
+        
+        fn write32(&self, addr: usize, value: u32) {
+            self.registers.write32(addr, value);
+            let ctrl = register_address(registers::DMA_CTRL_REG + MODEL_DMA_CHANNEL * 0x10);
+            if addr != ctrl || value != 0x2 {
+                return;
+            }
+            
+            let source = ((self.register(registers::DMA_SRC_HI_REG) as u64) << 32) | self.register(registers::DMA_SRC_REG) as u64;
+            let destination = (self.register(registers::DMA_DST_REG) - RK3588_NPU_MODEL_ADDR) as usize;
+            let len = self.register(registers::DMA_LEN_REG) as usize;
+            let data = unsafe { core::slice::from_raw_parts(source as usize as *const u8, len) };
+            self.npu_memory.lock()[destination..destination + len].copy_from_slice(data);
+            self.registers.write32(addr, 0x2 | 0x4);
+        }
+    }
+    
+    fn dma_sim_driver() -> (RockchipRK3588Driver, Arc<IrqMutex<Vec<u8>>>) {
+        let npu_memory = Arc::new(IrqMutex::new(vec![0u8; 4096]));
+        let backend = DmaSimBackend {
+            registers: MockHardwareBackend::new(NPU_MAP.base_address(), RK3588_NPU_REGISTER_WINDOW as usize),
+            npu_memory: npu_memory.clone(),
+        };
+        let mut driver = RockchipRK3588Driver::with_backend(NPUConfig::default(), Box::new(backend)).unwrap();
+        driver.initialized = true;
+        (driver, npu_memory)
+    }
+    
+    #[test]
+    fn test_streaming_writes_model_to_npu_memory() {
+        // 数据源每次最多返回13字节，经DMA写入NPU内存的内容与模型一致，模型成为当前模型
+        let model: Vec<u8> = (0..1000u32).map(|i| (i * 31 % 251) as u8).collect();
+        let (mut driver, npu_memory) = dma_sim_driver();
+        
+        let mut position = 0;
+        driver.load_model_streaming(&mut |chunk: &mut [u8]| {
+            let n = chunk.len().min(13).min(model.len() - position);
+            chunk[..n].copy_from_slice(&model[position..position + n]);
+            position += n;
+            Ok(n)
+        }, model.len()).unwrap();
+        
+        assert_eq!(&npu_memory.lock()[..model.len()], &model[..]);
+        assert!(driver.model_loaded);
+        assert_eq!(driver.read_register(registers::MODEL_ADDR_REG), Ok(RK3588_NPU_MODEL_ADDR));
+        assert_eq!(driver.performance_stats.memory_usage, model.len());
+    }
+    
+    #[test]
+    fn test_streaming_failure_frees_npu_memory() {
+        // 数据源中途出错时加载失败，已分配的NPU内存被释放
+        let (mut driver, _) = dma_sim_driver();
+        let mut calls = 0;
+        let result = driver.load_model_streaming(&mut |chunk: &mut [u8]| {
+            calls += 1;
+            if calls > 2 {
+                return Err(AIError::ModelLoadError);
+            }
+            Ok(chunk.len().min(16))
+        }, 256);
+        
+        assert_eq!(result, Err(AIError::ModelLoadError));
+        assert!(driver.memory_pool.is_empty());
+        assert_eq!(driver.performance_stats.memory_usage, 0);
+        assert!(!driver.model_loaded);
+    }
+    
+    #[test]
+    fn test_streaming_truncated_model() {
+        // 数据源提前结束时加载失败
+        let mut buffer = [0u8; 16];
+        let mut remaining = 20usize;
+        let result = stream_model(
+            &mut |chunk: &mut [u8]| {
+                let n = chunk.len().min(remaining);
+                remaining -= n;
+                Ok(n)
+            },
+            64,
+            &mut buffer,
+            |_, _| Ok(()),
+        );
+        assert_eq!(result, Err(AIError::ModelLoadError));
+    }
+}
@@ -0,0 +1,157 @@
+//! 带代数的句柄槽位表
+//!
+//! 槽位释放后代数递增，旧句柄在槽位被复用后不会误指向新分配
+
+use alloc::vec::Vec;
+
+use crate::AIError;
+
+/// 槽位键：槽位下标 + 分配时的代数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlotKey {
+    index: u32,
+    generation: u32,
+}
+
+impl SlotKey {
+    /// 槽位下标
+    pub fn index(&self) -> usize {
+        self.index as usize
+    }
+
+    /// 分配时的代数
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+struct Slot<T> {
+    generation: u32,
+    value: Option<T>,
+}
+
+/// 槽位表
+pub struct SlotTable<T> {
+    slots: Vec<Slot<T>>,
+    free: Vec<u32>,
+    capacity: usize,
+}
+
+impl<T> SlotTable<T> {
+    /// 创建最多容纳`capacity`个条目的槽位表
+    pub const fn new(capacity: usize) -> Self {
+        Self {
+            slots: Vec::new(),
+            free: Vec::new(),
+            capacity,
+        }
+    }
+
+    /// 占用中的条目数
+    pub fn len(&self) -> usize {
+        self.slots.len() - self.free.len()
+    }
+
+    /// 是否没有占用中的条目
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 插入条目，优先复用已释放的槽位，表满时返回`MemoryAllocationError`
+    pub fn insert(&mut self, value: T) -> Result<SlotKey, AIError> {
+        if let Some(index) = self.free.pop() {
+            let slot = &mut self.slots[index as usize];
+            slot.value = Some(value);
+            return Ok(SlotKey { index, generation: slot.generation });
+        }
+
+        if self.slots.len() >= self.capacity {
+            return Err(AIError::MemoryAllocationError);
+        }
+        self.slots.push(Slot { generation: 0, value: Some(value) });
+        Ok(SlotKey { index: (self.slots.len() - 1) as u32, generation: 0 })
+    }
+
+    /// 获取句柄对应的条目，句柄失效时返回`StaleHandle`
+    pub fn get(&self, key: SlotKey) -> Result<&T, AIError> {
+        self.slots
+            .get(key.index())
+            .filter(|slot| slot.generation == key.generation)
+            .and_then(|slot| slot.value.as_ref())
+            .ok_or(AIError::StaleHandle)
+    }
+
+    /// 获取句柄对应条目的可变引用，句柄失效时返回`StaleHandle`
+    pub fn get_mut(&mut self, key: SlotKey) -> Result<&mut T, AIError> {
+        self.slots
+            .get_mut(key.index())
+            .filter(|slot| slot.generation == key.generation)
+            .and_then(|slot| slot.value.as_mut())
+            .ok_or(AIError::StaleHandle)
+    }
+
+    /// 移除句柄对应的条目并使该句柄失效
+    pub fn remove(&mut self, key: SlotKey) -> Result<T, AIError> {
+        let slot = self
+            .slots
+            .get_mut(key.index())
+            .filter(|slot| slot.generation == key.generation && slot.value.is_some())
+            .ok_or(AIError::StaleHandle)?;
+
+        let value = slot.value.take().ok_or(AIError::StaleHandle)?;
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(key.index);
+        Ok(value)
+    }
+
+    /// 释放所有条目，已发出的句柄全部失效
+    pub fn clear(&mut self) {
+        for (index, slot) in self.slots.iter_mut().enumerate() {
+            if slot.value.take().is_some() {
+                slot.generation = slot.generation.wrapping_add(1);
+                self.free.push(index as u32);
+            }
+        }
+    }
+
+    /// 遍历占用中的条目
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.slots.iter().filter_map(|slot| slot.value.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stale_key_after_reuse() {
+        // 槽位释放并被重新分配后，旧句柄返回StaleHandle且不影响新条目
+        let mut table = SlotTable::new(4);
+        let old = table.insert(10usize).unwrap();
+        assert_eq!(table.remove(old), Ok(10));
+
+        let new = table.insert(20usize).unwrap();
+        assert_eq!(new.index(), old.index());
+        assert_ne!(new.generation(), old.generation());
+
+        assert_eq!(table.get(old), Err(AIError::StaleHandle));
+        assert_eq!(table.remove(old), Err(AIError::StaleHandle));
+        assert_eq!(table.get(new), Ok(&20));
+    }
+
+    #[test]
+    fn test_clear_invalidates_all_and_capacity_bounded() {
+        // 清空后所有句柄失效；容量用尽时分配失败
+        let mut table = SlotTable::new(2);
+        let a = table.insert(1u8).unwrap();
+        let b = table.insert(2u8).unwrap();
+        assert_eq!(table.insert(3u8), Err(AIError::MemoryAllocationError));
+
+        table.clear();
+        assert!(table.is_empty());
+        assert_eq!(table.get(a), Err(AIError::StaleHandle));
+        assert_eq!(table.remove(b), Err(AIError::StaleHandle));
+        assert!(table.insert(3u8).is_ok());
+    }
+}
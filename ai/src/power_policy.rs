@@ -0,0 +1,127 @@
+//! 功耗感知推理调度模块
+//!
+//! 电池供电时按剩余电量降低推理频率并切换到更轻量的精度
+
+use crate::Precision;
+
+/// 推理功耗档位
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PowerProfile {
+    /// 两次推理之间的最小间隔 (ms)
+    pub inference_interval_ms: u64,
+    /// 首选推理精度
+    pub precision: Precision,
+}
+
+/// 电量档位表：(最低电量百分比, 档位)，按电量由高到低排列
+const PROFILES: [(u8, PowerProfile); 3] = [
+    // 电量充足：约30fps
+    (50, PowerProfile { inference_interval_ms: 33, precision: Precision::FP16 }),
+    // 电量中等：10fps
+    (20, PowerProfile { inference_interval_ms: 100, precision: Precision::FP16 }),
+    // 低电量：INT8 + 2fps
+    (0, PowerProfile { inference_interval_ms: 500, precision: Precision::INT8 }),
+];
+
+/// 功耗感知推理策略
+///
+/// 时间戳由调用者传入，便于测试时使用模拟时钟
+#[derive(Debug, Clone, Copy)]
+pub struct PowerAwarePolicy {
+    battery_percent: u8,
+    profile: PowerProfile,
+    last_inference_ms: Option<u64>,
+}
+
+impl PowerAwarePolicy {
+    /// 创建策略，初始按满电处理
+    pub const fn new() -> Self {
+        Self {
+            battery_percent: 100,
+            profile: PROFILES[0].1,
+            last_inference_ms: None,
+        }
+    }
+
+    /// 电量百分比对应的档位
+    pub fn profile_for(percent: u8) -> PowerProfile {
+        PROFILES
+            .iter()
+            .find(|(min_percent, _)| percent >= *min_percent)
+            .map(|&(_, profile)| profile)
+            .unwrap_or(PROFILES[PROFILES.len() - 1].1)
+    }
+
+    /// 更新电池电量（超过100按100处理），档位变化时返回新档位
+    pub fn set_battery_level(&mut self, percent: u8) -> Option<PowerProfile> {
+        self.battery_percent = percent.min(100);
+        let profile = Self::profile_for(self.battery_percent);
+        if profile == self.profile {
+            return None;
+        }
+        self.profile = profile;
+        Some(profile)
+    }
+
+    /// 当前电池电量百分比
+    pub fn battery_level(&self) -> u8 {
+        self.battery_percent
+    }
+
+    /// 当前档位
+    pub fn profile(&self) -> PowerProfile {
+        self.profile
+    }
+
+    /// 距上次推理是否已达到当前档位的推理间隔，是则记为一次推理
+    pub fn should_infer(&mut self, now_ms: u64) -> bool {
+        let due = match self.last_inference_ms {
+            Some(last) => now_ms.saturating_sub(last) >= self.profile.inference_interval_ms,
+            None => true,
+        };
+        if due {
+            self.last_inference_ms = Some(now_ms);
+        }
+        due
+    }
+}
+
+impl Default for PowerAwarePolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_low_battery_lengthens_interval_and_uses_int8() {
+        // 电量下降时推理间隔变长，低于20%切换为INT8
+        let mut policy = PowerAwarePolicy::new();
+        let full = policy.profile();
+
+        let medium = policy.set_battery_level(35).unwrap();
+        assert!(medium.inference_interval_ms > full.inference_interval_ms);
+        assert_eq!(policy.set_battery_level(30), None);
+
+        let low = policy.set_battery_level(15).unwrap();
+        assert_eq!(low, PowerProfile { inference_interval_ms: 500, precision: Precision::INT8 });
+        assert_eq!(full.precision, Precision::FP16);
+        assert_eq!(policy.battery_level(), 15);
+    }
+
+    #[test]
+    fn test_should_infer_follows_profile_interval() {
+        // 推理节拍随档位间隔变化
+        let mut policy = PowerAwarePolicy::new();
+        assert!(policy.should_infer(0));
+        assert!(!policy.should_infer(20));
+        assert!(policy.should_infer(40));
+
+        policy.set_battery_level(10);
+        assert!(!policy.should_infer(300));
+        assert!(policy.should_infer(540));
+    }
+}
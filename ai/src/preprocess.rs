@@ -0,0 +1,391 @@
+//! 可组合的推理输入预处理流水线
+//!
+//! 由有序的预处理阶段（缩放、信箱缩放、归一化、排列转换、量化）组成，
+//! 通过构建器配置，构建时校验阶段顺序
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{AIError, BoundingBox};
+
+/// 输入图像通道数（RGB）
+const CHANNELS: usize = 3;
+
+/// 张量数据排列
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TensorLayout {
+    /// 高-宽-通道（图像原始排列）
+    Hwc,
+    /// 通道-高-宽（模型常用输入排列）
+    Chw,
+}
+
+/// 预处理阶段
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PreprocessStage {
+    /// 双线性缩放到指定尺寸（不保持宽高比）
+    Resize { width: usize, height: usize },
+    /// 保持宽高比缩放并居中，空白区域以`pad`填充
+    Letterbox { width: usize, height: usize, pad: u8 },
+    /// 像素值先除以255，再按通道计算 (x - mean) / std
+    Normalize { mean: [f32; 3], std: [f32; 3] },
+    /// 转换数据排列
+    LayoutConvert(TensorLayout),
+    /// 量化为INT8：round(x / scale) + zero_point，结果限制在[-128, 127]
+    Quantize { scale: f32, zero_point: i32 },
+}
+
+impl PreprocessStage {
+    /// 是否为几何变换阶段（只能作用于HWC排列的原始像素）
+    fn is_geometric(&self) -> bool {
+        matches!(self, Self::Resize { .. } | Self::Letterbox { .. })
+    }
+
+    /// 阶段参数是否有效
+    fn is_valid(&self) -> bool {
+        match *self {
+            Self::Resize { width, height } | Self::Letterbox { width, height, .. } => width > 0 && height > 0,
+            Self::Normalize { std, .. } => std.iter().all(|s| s.is_finite() && *s != 0.0),
+            Self::LayoutConvert(_) => true,
+            Self::Quantize { scale, .. } => scale.is_finite() && scale > 0.0,
+        }
+    }
+}
+
+/// 流水线构建错误，携带出错阶段的下标
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PipelineError {
+    /// 流水线没有任何阶段
+    Empty,
+    /// 阶段参数无效
+    InvalidParameter { stage: usize },
+    /// 量化阶段之后还有其他阶段
+    QuantizeNotLast { stage: usize },
+    /// 几何变换阶段位于归一化或排列转换之后
+    GeometryAfterTransform { stage: usize },
+}
+
+impl fmt::Display for PipelineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "预处理流水线为空"),
+            Self::InvalidParameter { stage } => write!(f, "阶段{}参数无效", stage),
+            Self::QuantizeNotLast { stage } => write!(f, "量化阶段{}必须位于最后", stage),
+            Self::GeometryAfterTransform { stage } => write!(f, "几何变换阶段{}必须位于归一化和排列转换之前", stage),
+        }
+    }
+}
+
+impl From<PipelineError> for AIError {
+    fn from(_: PipelineError) -> Self {
+        AIError::InvalidInput
+    }
+}
+
+/// 预处理流水线构建器
+#[derive(Debug, Clone, Default)]
+pub struct PreprocessorBuilder {
+    stages: Vec<PreprocessStage>,
+}
+
+impl PreprocessorBuilder {
+    /// 追加任意阶段
+    pub fn stage(mut self, stage: PreprocessStage) -> Self {
+        self.stages.push(stage);
+        self
+    }
+
+    /// 追加缩放阶段
+    pub fn resize(self, width: usize, height: usize) -> Self {
+        self.stage(PreprocessStage::Resize { width, height })
+    }
+
+    /// 追加信箱缩放阶段
+    pub fn letterbox(self, width: usize, height: usize, pad: u8) -> Self {
+        self.stage(PreprocessStage::Letterbox { width, height, pad })
+    }
+
+    /// 追加归一化阶段
+    pub fn normalize(self, mean: [f32; 3], std: [f32; 3]) -> Self {
+        self.stage(PreprocessStage::Normalize { mean, std })
+    }
+
+    /// 追加排列转换阶段
+    pub fn layout(self, layout: TensorLayout) -> Self {
+        self.stage(PreprocessStage::LayoutConvert(layout))
+    }
+
+    /// 追加量化阶段
+    pub fn quantize(self, scale: f32, zero_point: i32) -> Self {
+        self.stage(PreprocessStage::Quantize { scale, zero_point })
+    }
+
+    /// 校验阶段顺序并构建流水线
+    pub fn build(self) -> Result<Preprocessor, PipelineError> {
+        if self.stages.is_empty() {
+            return Err(PipelineError::Empty);
+        }
+
+        let last = self.stages.len() - 1;
+        let mut transformed = false;
+        for (index, stage) in self.stages.iter().enumerate() {
+            if !stage.is_valid() {
+                return Err(PipelineError::InvalidParameter { stage: index });
+            }
+            match stage {
+                PreprocessStage::Quantize { .. } if index != last => {
+                    return Err(PipelineError::QuantizeNotLast { stage: index });
+                }
+                stage if stage.is_geometric() && transformed => {
+                    return Err(PipelineError::GeometryAfterTransform { stage: index });
+                }
+                PreprocessStage::Normalize { .. } | PreprocessStage::LayoutConvert(_) => transformed = true,
+                _ => {}
+            }
+        }
+
+        Ok(Preprocessor { stages: self.stages })
+    }
+}
+
+/// 信箱缩放参数，用于把模型输入坐标映射回原图坐标
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LetterboxParams {
+    /// 原图到模型输入的缩放比例（宽高取较小的比例）
+    pub scale: f32,
+    /// 左侧填充像素数（模型输入坐标）
+    pub pad_x: usize,
+    /// 上方填充像素数（模型输入坐标）
+    pub pad_y: usize,
+    /// 模型输入宽度
+    pub input_width: usize,
+    /// 模型输入高度
+    pub input_height: usize,
+}
+
+impl LetterboxParams {
+    /// 计算把`src_w`x`src_h`的图像信箱缩放到`dst_w`x`dst_h`的参数
+    /// 
+    /// 原图大于或小于目标尺寸时均按较小比例缩放，缩放后的图像在目标中居中
+    pub fn compute(src_w: usize, src_h: usize, dst_w: usize, dst_h: usize) -> Self {
+        let scale = (dst_w as f32 / src_w as f32).min(dst_h as f32 / src_h as f32);
+        let (inner_w, inner_h) = Self::inner_size(src_w, src_h, dst_w, dst_h, scale);
+        Self {
+            scale,
+            pad_x: (dst_w - inner_w) / 2,
+            pad_y: (dst_h - inner_h) / 2,
+            input_width: dst_w,
+            input_height: dst_h,
+        }
+    }
+
+    fn inner_size(src_w: usize, src_h: usize, dst_w: usize, dst_h: usize, scale: f32) -> (usize, usize) {
+        let inner_w = ((src_w as f32 * scale + 0.5) as usize).clamp(1, dst_w);
+        let inner_h = ((src_h as f32 * scale + 0.5) as usize).clamp(1, dst_h);
+        (inner_w, inner_h)
+    }
+}
+
+/// 把模型输出的边界框（相对模型输入归一化的中心坐标）映射回原图像素坐标
+pub fn unletterbox_bbox(bbox: BoundingBox, params: &LetterboxParams) -> BoundingBox {
+    let x = (bbox.x * params.input_width as f32 - params.pad_x as f32) / params.scale;
+    let y = (bbox.y * params.input_height as f32 - params.pad_y as f32) / params.scale;
+    let width = bbox.width * params.input_width as f32 / params.scale;
+    let height = bbox.height * params.input_height as f32 / params.scale;
+    BoundingBox::new(x, y, width, height)
+}
+
+/// 流水线中间数据
+struct Frame {
+    data: Vec<f32>,
+    width: usize,
+    height: usize,
+    layout: TensorLayout,
+}
+
+impl Frame {
+    /// 读取HWC排列下的像素分量，坐标越界时取边缘值
+    fn at(&self, x: isize, y: isize, c: usize) -> f32 {
+        let x = x.clamp(0, self.width as isize - 1) as usize;
+        let y = y.clamp(0, self.height as isize - 1) as usize;
+        self.data[(y * self.width + x) * CHANNELS + c]
+    }
+
+    /// 双线性缩放（像素中心对齐）
+    fn resized(&self, width: usize, height: usize) -> Vec<f32> {
+        let sx = self.width as f32 / width as f32;
+        let sy = self.height as f32 / height as f32;
+        let mut out = Vec::with_capacity(width * height * CHANNELS);
+
+        for dy in 0..height {
+            let fy = ((dy as f32 + 0.5) * sy - 0.5).max(0.0);
+            let (y0, wy) = (fy as isize, fy - (fy as isize) as f32);
+            for dx in 0..width {
+                let fx = ((dx as f32 + 0.5) * sx - 0.5).max(0.0);
+                let (x0, wx) = (fx as isize, fx - (fx as isize) as f32);
+                for c in 0..CHANNELS {
+                    let top = self.at(x0, y0, c) * (1.0 - wx) + self.at(x0 + 1, y0, c) * wx;
+                    let bottom = self.at(x0, y0 + 1, c) * (1.0 - wx) + self.at(x0 + 1, y0 + 1, c) * wx;
+                    out.push(top * (1.0 - wy) + bottom * wy);
+                }
+            }
+        }
+        out
+    }
+
+    fn resize(&mut self, width: usize, height: usize) {
+        self.data = self.resized(width, height);
+        self.width = width;
+        self.height = height;
+    }
+
+    fn letterbox(&mut self, width: usize, height: usize, pad: u8) {
+        let params = LetterboxParams::compute(self.width, self.height, width, height);
+        let (inner_w, inner_h) = LetterboxParams::inner_size(self.width, self.height, width, height, params.scale);
+        let (pad_x, pad_y) = (params.pad_x, params.pad_y);
+
+        let inner = self.resized(inner_w, inner_h);
+        let mut out = vec![pad as f32; width * height * CHANNELS];
+        for y in 0..inner_h {
+            let src = y * inner_w * CHANNELS;
+            let dst = ((y + pad_y) * width + pad_x) * CHANNELS;
+            out[dst..dst + inner_w * CHANNELS].copy_from_slice(&inner[src..src + inner_w * CHANNELS]);
+        }
+
+        self.data = out;
+        self.width = width;
+        self.height = height;
+    }
+
+    fn normalize(&mut self, mean: [f32; 3], std: [f32; 3]) {
+        let plane = self.width * self.height;
+        let layout = self.layout;
+        for (i, value) in self.data.iter_mut().enumerate() {
+            let c = match layout {
+                TensorLayout::Hwc => i % CHANNELS,
+                TensorLayout::Chw => i / plane,
+            };
+            *value = (*value / 255.0 - mean[c]) / std[c];
+        }
+    }
+
+    fn convert_layout(&mut self, layout: TensorLayout) {
+        if layout == self.layout {
+            return;
+        }
+
+        let plane = self.width * self.height;
+        let mut out = vec![0.0f32; self.data.len()];
+        for i in 0..plane {
+            for c in 0..CHANNELS {
+                match layout {
+                    TensorLayout::Chw => out[c * plane + i] = self.data[i * CHANNELS + c],
+                    TensorLayout::Hwc => out[i * CHANNELS + c] = self.data[c * plane + i],
+                }
+            }
+        }
+        self.data = out;
+        self.layout = layout;
+    }
+
+    fn quantize(&mut self, scale: f32, zero_point: i32) {
+        for value in self.data.iter_mut() {
+            let scaled = *value / scale;
+            let rounded = (if scaled >= 0.0 { scaled + 0.5 } else { scaled - 0.5 }) as i32;
+            let q = rounded.saturating_add(zero_point);
+            *value = q.clamp(-128, 127) as f32;
+        }
+    }
+}
+
+/// 推理输入预处理流水线
+#[derive(Debug, Clone, PartialEq)]
+pub struct Preprocessor {
+    stages: Vec<PreprocessStage>,
+}
+
+impl Preprocessor {
+    /// 创建构建器
+    pub fn builder() -> PreprocessorBuilder {
+        PreprocessorBuilder::default()
+    }
+
+    /// 流水线阶段
+    pub fn stages(&self) -> &[PreprocessStage] {
+        &self.stages
+    }
+
+    /// 对RGB图像（HWC排列）依次执行各阶段，返回模型输入张量
+    pub fn run(&self, image_data: &[u8], width: usize, height: usize) -> Result<Vec<f32>, AIError> {
+        if width == 0 || height == 0 || image_data.len() != width * height * CHANNELS {
+            return Err(AIError::InvalidInput);
+        }
+
+        let mut frame = Frame {
+            data: image_data.iter().map(|&v| v as f32).collect(),
+            width,
+            height,
+            layout: TensorLayout::Hwc,
+        };
+
+        for stage in &self.stages {
+            match *stage {
+                PreprocessStage::Resize { width, height } => frame.resize(width, height),
+                PreprocessStage::Letterbox { width, height, pad } => frame.letterbox(width, height, pad),
+                PreprocessStage::Normalize { mean, std } => frame.normalize(mean, std),
+                PreprocessStage::LayoutConvert(layout) => frame.convert_layout(layout),
+                PreprocessStage::Quantize { scale, zero_point } => frame.quantize(scale, zero_point),
+            }
+        }
+
+        Ok(frame.data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resize_then_normalize() {
+        // 4x4图像缩小到2x2时每个输出像素为对应2x2块的均值，再归一化并转为CHW
+        let mut image = Vec::new();
+        for y in 0..4u8 {
+            for x in 0..4u8 {
+                let v = (y / 2 * 2 + x / 2) * 40 + (x % 2) * 10;
+                image.extend_from_slice(&[v, v, 255 - v]);
+            }
+        }
+
+        let pipeline = Preprocessor::builder()
+            .resize(2, 2)
+            .normalize([0.0; 3], [1.0 / 255.0; 3])
+            .layout(TensorLayout::Chw)
+            .build()
+            .unwrap();
+        let tensor = pipeline.run(&image, 4, 4).unwrap();
+
+        let expected = [5.0, 45.0, 85.0, 125.0, 5.0, 45.0, 85.0, 125.0, 250.0, 210.0, 170.0, 130.0];
+        assert_eq!(tensor.len(), expected.len());
+        for (value, expected) in tensor.iter().zip(expected.iter()) {
+            assert!((value - expected).abs() < 1e-3, "{} != {}", value, expected);
+        }
+    }
+
+    #[test]
+    fn test_invalid_stage_order_rejected() {
+        // 量化不在最后、归一化后再缩放、参数非法时构建失败
+        let quantize_first = Preprocessor::builder().quantize(0.5, 0).resize(2, 2).build();
+        assert_eq!(quantize_first, Err(PipelineError::QuantizeNotLast { stage: 0 }));
+
+        let resize_after_layout = Preprocessor::builder()
+            .layout(TensorLayout::Chw)
+            .letterbox(640, 640, 114)
+            .build();
+        assert_eq!(resize_after_layout, Err(PipelineError::GeometryAfterTransform { stage: 1 }));
+
+        let zero_std = Preprocessor::builder().normalize([0.0; 3], [0.0; 3]).build();
+        assert_eq!(zero_std, Err(PipelineError::InvalidParameter { stage: 0 }));
+        assert_eq!(Preprocessor::builder().build(), Err(PipelineError::Empty));
+    }
+}
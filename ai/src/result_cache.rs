@@ -0,0 +1,162 @@
+//! 推理结果缓存模块
+//!
+//! 以输入的FNV-1a哈希为键缓存推理输出（LRU淘汰），
+//! 用于跳过对相同输入（如静止画面）的重复推理
+
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+
+const FNV_OFFSET_BASIS: u64 = 0xCBF2_9CE4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01B3;
+
+/// 缓存统计
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CacheStats {
+    /// 命中次数
+    pub hits: u64,
+    /// 未命中次数
+    pub misses: u64,
+}
+
+impl CacheStats {
+    /// 命中率，尚无查询时为0
+    pub fn hit_rate(&self) -> f32 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f32 / total as f32
+        }
+    }
+}
+
+/// 计算输入的缓存键
+///
+/// 长度及全部元素参与FNV-1a哈希，任一元素不同的两个输入都会得到不同的键（哈希碰撞除外）；
+/// 整帧哈希的开销远小于一次推理
+pub fn input_key(input: &[f32]) -> u64 {
+    let mut hash = FNV_OFFSET_BASIS;
+    let mut mix = |value: u32| {
+        for byte in value.to_le_bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    };
+
+    mix(input.len() as u32);
+    input.iter().for_each(|value| mix(value.to_bits()));
+    hash
+}
+
+/// LRU推理结果缓存
+pub struct ResultCache {
+    capacity: usize,
+    /// 由旧到新排列的 (键, 输出)
+    entries: VecDeque<(u64, Vec<f32>)>,
+    stats: CacheStats,
+}
+
+impl ResultCache {
+    /// 创建容量为`capacity`（至少为1）的缓存
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            capacity,
+            entries: VecDeque::with_capacity(capacity),
+            stats: CacheStats::default(),
+        }
+    }
+
+    /// 缓存容量
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// 当前缓存条目数
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// 缓存是否为空
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// 查找键对应的输出，命中时将条目移至最新位置
+    pub fn get(&mut self, key: u64) -> Option<&[f32]> {
+        match self.entries.iter().position(|(k, _)| *k == key) {
+            Some(position) => {
+                self.stats.hits += 1;
+                let entry = self.entries.remove(position)?;
+                self.entries.push_back(entry);
+                self.entries.back().map(|(_, output)| output.as_slice())
+            }
+            None => {
+                self.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// 插入推理输出，缓存已满时淘汰最久未使用的条目
+    pub fn insert(&mut self, key: u64, output: Vec<f32>) {
+        if let Some(position) = self.entries.iter().position(|(k, _)| *k == key) {
+            self.entries.remove(position);
+        } else if self.entries.len() >= self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back((key, output));
+    }
+
+    /// 清空缓存条目（保留统计）
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// 缓存统计
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lru_evicts_least_recently_used() {
+        // 容量为2时，最近访问过的条目保留，最久未使用的被淘汰
+        let mut cache = ResultCache::new(2);
+        cache.insert(1, vec![1.0]);
+        cache.insert(2, vec![2.0]);
+        assert_eq!(cache.get(1), Some(&[1.0][..]));
+
+        cache.insert(3, vec![3.0]);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get(2), None);
+        assert_eq!(cache.get(1), Some(&[1.0][..]));
+        assert_eq!(cache.get(3), Some(&[3.0][..]));
+        assert_eq!(cache.stats(), CacheStats { hits: 3, misses: 1 });
+        assert_eq!(cache.stats().hit_rate(), 0.75);
+    }
+
+    #[test]
+    fn test_input_key_covers_every_element() {
+        // 大输入中任一位置的变化都会改变键，长度也参与哈希
+        let input = vec![0.5f32; 4096];
+        let base = input_key(&input);
+
+        for position in [0, 1, 17, 2049, 4094] {
+            let mut changed = input.clone();
+            changed[position] = 1.0;
+            assert_ne!(input_key(&changed), base, "位置{}", position);
+        }
+
+        let mut last = input.clone();
+        *last.last_mut().unwrap() = 1.0;
+        assert_ne!(input_key(&last), base);
+
+        assert_ne!(input_key(&input[..input.len() - 1]), base);
+        assert_ne!(input_key(&[1.0, 2.0]), input_key(&[2.0, 1.0]));
+    }
+}
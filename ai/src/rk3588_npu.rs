@@ -7,11 +7,19 @@
 use core::sync::atomic::{AtomicBool, Ordering};
 use core::fmt;
 
+/// YOLO-v8 每个锚点的输出长度 (4坐标 + 1目标性 + 80类别)
+const ANCHOR_STRIDE: usize = 85;
+/// 最终置信度阈值
+const CONFIDENCE_THRESHOLD: f32 = 0.25;
+/// 默认目标性预过滤阈值
+const DEFAULT_OBJECTNESS_THRESHOLD: f32 = 0.005;
+
 /// RK3588 NPU 推理引擎
 pub struct RK3588NpuEngine {
     base_address: u64,
     model_loaded: AtomicBool,
     initialized: AtomicBool,
+    objectness_threshold: f32,
 }
 
 impl RK3588NpuEngine {
@@ -21,9 +29,22 @@ impl RK3588NpuEngine {
             base_address: 0xFDC0_0000, // NPU寄存器基地址
             model_loaded: AtomicBool::new(false),
             initialized: AtomicBool::new(false),
+            objectness_threshold: DEFAULT_OBJECTNESS_THRESHOLD,
         }
     }
     
+    /// 设置目标性预过滤阈值
+    /// 
+    /// 目标性低于该阈值的锚点在计算类别得分前即被丢弃
+    pub fn set_objectness_threshold(&mut self, threshold: f32) {
+        self.objectness_threshold = threshold.clamp(0.0, 1.0);
+    }
+    
+    /// 获取目标性预过滤阈值
+    pub fn objectness_threshold(&self) -> f32 {
+        self.objectness_threshold
+    }
+    
     /// 初始化NPU引擎
     pub fn init(&mut self) -> Result<(), NpuError> {
         // 检查NPU硬件是否可用
@@ -237,25 +258,37 @@ impl RK3588NpuEngine {
     
     /// 后处理检测结果
     fn postprocess_detections(&self, raw_output: &[f32], detections: &mut [Detection]) -> Result<usize, NpuError> {
+        let (num_valid_detections, _) = self.decode_candidates(raw_output, detections);
+        
+        // 应用非极大值抑制
+        self.apply_nms(detections, num_valid_detections);
+        
+        Ok(num_valid_detections)
+    }
+    
+    /// 解析锚点输出，返回(有效检测数, 参与类别评分的候选数)
+    fn decode_candidates(&self, raw_output: &[f32], detections: &mut [Detection]) -> (usize, usize) {
         let mut num_valid_detections = 0;
+        let mut num_scored = 0;
         
         // 解析YOLO-v8输出格式
-        for i in 0..8400 {
-            let base_index = i * 85;
+        for i in 0..raw_output.len() / ANCHOR_STRIDE {
+            let base_index = i * ANCHOR_STRIDE;
             
-            // 获取置信度
+            // 获取目标性
             let confidence = raw_output[base_index + 4];
             
-            // 过滤低置信度检测
-            if confidence < 0.25 {
+            // 目标性预过滤，跳过几乎不含目标的锚点
+            if confidence < self.objectness_threshold {
                 continue;
             }
+            num_scored += 1;
             
             // 获取类别概率
             let mut max_class_prob = 0.0;
             let mut class_id = 0;
             
-            for j in 5..85 {
+            for j in 5..ANCHOR_STRIDE {
                 let prob = raw_output[base_index + j];
                 if prob > max_class_prob {
                     max_class_prob = prob;
@@ -266,7 +299,7 @@ impl RK3588NpuEngine {
             // 计算最终置信度
             let final_confidence = confidence * max_class_prob;
             
-            if final_confidence >= 0.25 && num_valid_detections < detections.len() {
+            if final_confidence >= CONFIDENCE_THRESHOLD && num_valid_detections < detections.len() {
                 // 解析边界框
                 let x = raw_output[base_index] * 640.0; // 假设输入尺寸为640x640
                 let y = raw_output[base_index + 1] * 640.0;
@@ -284,10 +317,7 @@ impl RK3588NpuEngine {
             }
         }
         
-        // 应用非极大值抑制
-        self.apply_nms(detections, num_valid_detections);
-        
-        Ok(num_valid_detections)
+        (num_valid_detections, num_scored)
     }
     
     /// 应用非极大值抑制
@@ -296,10 +326,14 @@ impl RK3588NpuEngine {
             return;
         }
         
-        // 按置信度排序
+        // 按置信度降序稳定排序，相同时依次按类别ID、框位置(y, x)排序以保证输出可复现
         let mut indices: Vec<usize> = (0..num_detections).collect();
         indices.sort_by(|&a, &b| {
-            detections[b].confidence.partial_cmp(&detections[a].confidence).unwrap()
+            let (da, db) = (&detections[a], &detections[b]);
+            db.confidence.total_cmp(&da.confidence)
+                .then_with(|| da.class_id.cmp(&db.class_id))
+                .then_with(|| da.bbox.y.total_cmp(&db.bbox.y))
+                .then_with(|| da.bbox.x.total_cmp(&db.bbox.x))
         });
         
         // 应用NMS
@@ -451,4 +485,57 @@ impl fmt::Display for NpuError {
             NpuError::ModelNotLoaded => write!(f, "模型未加载"),
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn anchor(objectness: f32, class_prob: f32) -> [f32; ANCHOR_STRIDE] {
+        let mut data = [0.0f32; ANCHOR_STRIDE];
+        data[..4].copy_from_slice(&[0.5, 0.5, 0.1, 0.1]);
+        data[4] = objectness;
+        data[5] = class_prob;
+        data
+    }
+
+    fn empty_detections(n: usize) -> Vec<Detection> {
+        vec![Detection {
+            bbox: BoundingBox::new(0.0, 0.0, 0.0, 0.0),
+            confidence: 0.0,
+            class_id: 0,
+            class_name: "unknown",
+        }; n]
+    }
+
+    #[test]
+    fn test_objectness_prefilter_reduces_scored_candidates() {
+        // 提高目标性阈值后参与类别评分的候选减少
+        let mut output = Vec::new();
+        for objectness in [0.001, 0.01, 0.1, 0.9] {
+            output.extend_from_slice(&anchor(objectness, 0.9));
+        }
+        let mut detections = empty_detections(4);
+
+        let mut engine = RK3588NpuEngine::new();
+        let (_, scored_default) = engine.decode_candidates(&output, &mut detections);
+        assert_eq!(scored_default, 3);
+
+        engine.set_objectness_threshold(0.5);
+        let (valid, scored) = engine.decode_candidates(&output, &mut detections);
+        assert_eq!(scored, 1);
+        assert_eq!(valid, 1);
+    }
+
+    #[test]
+    fn test_low_class_score_still_dropped() {
+        // 目标性高但类别得分低的锚点仍被置信度阈值过滤
+        let output = anchor(0.95, 0.1);
+        let mut detections = empty_detections(1);
+
+        let engine = RK3588NpuEngine::new();
+        let (valid, scored) = engine.decode_candidates(&output, &mut detections);
+        assert_eq!(scored, 1);
+        assert_eq!(valid, 0);
+    }
+}
@@ -5,10 +5,20 @@
 mod speech_recognition;
 mod text_to_speech;
 mod natural_language;
+mod text_normalizer;
+mod pinyin;
+pub mod vad;
+
+pub use natural_language::{DialogState, IntentResult, Language, NaturalLanguageModel, Slot};
+pub use pinyin::{hanzi_to_pinyin, Pinyin};
+pub use text_normalizer::{NormalizationRules, NumberStyle, UnitPosition, UnitRule};
+pub use vad::{VadEvent, VoiceActivityDetector};
 
 use crate::{AIError, InferenceEngine};
+use alloc::collections::VecDeque;
 use alloc::string::String;
 use alloc::vec::Vec;
+use core::ops::Range;
 
 /// 语音识别结果
 #[derive(Debug, Clone)]
@@ -18,6 +28,25 @@ pub struct SpeechRecognitionResult {
     pub duration_ms: u32,
 }
 
+/// 流式识别的中间结果
+#[derive(Debug, Clone)]
+pub struct PartialResult {
+    pub text: String,
+    /// 已缓存音频的时长
+    pub duration_ms: u32,
+}
+
+/// 流式识别最多缓存的音频时长（秒），超出时丢弃最早的音频
+pub const STREAM_BUFFER_SECONDS: usize = 5;
+
+/// 流式识别输出中间结果的最小新增音频时长 (ms)
+pub const PARTIAL_INTERVAL_MS: usize = 100;
+
+/// 采样数对应的时长 (ms)
+fn samples_to_ms(samples: usize) -> u32 {
+    (samples * 1000 / vad::SAMPLE_RATE) as u32
+}
+
 /// 语音合成参数
 #[derive(Debug, Clone)]
 pub struct SpeechSynthesisParams {
@@ -58,6 +87,10 @@ pub struct SpeechInteractionEngine {
     recognition_model_loaded: bool,
     synthesis_model_loaded: bool,
     nlu_model_loaded: bool,
+    /// 流式识别的音频缓存，容量为`STREAM_BUFFER_SECONDS`秒
+    stream_buffer: VecDeque<i16>,
+    /// 上次输出中间结果后新增的采样数
+    samples_since_partial: usize,
 }
 
 impl SpeechInteractionEngine {
@@ -67,6 +100,8 @@ impl SpeechInteractionEngine {
             recognition_model_loaded: false,
             synthesis_model_loaded: false,
             nlu_model_loaded: false,
+            stream_buffer: VecDeque::new(),
+            samples_since_partial: 0,
         }
     }
     
@@ -84,6 +119,59 @@ impl SpeechInteractionEngine {
             return Err(AIError::ModelNotFound);
         }
         
+        Ok(Self::decode(audio_data))
+    }
+    
+    /// 流式识别：缓存音频块，新增音频达到`PARTIAL_INTERVAL_MS`时输出中间结果
+    /// 
+    /// 缓存最多保留最近`STREAM_BUFFER_SECONDS`秒音频
+    pub fn recognize_stream(&mut self, chunk: &[i16]) -> Result<Option<PartialResult>, AIError> {
+        if !self.recognition_model_loaded {
+            return Err(AIError::ModelNotFound);
+        }
+        
+        let capacity = STREAM_BUFFER_SECONDS * vad::SAMPLE_RATE;
+        let overflow = (self.stream_buffer.len() + chunk.len()).saturating_sub(capacity);
+        self.stream_buffer.drain(..overflow.min(self.stream_buffer.len()));
+        self.stream_buffer.extend(&chunk[chunk.len().saturating_sub(capacity)..]);
+        
+        self.samples_since_partial += chunk.len();
+        if self.samples_since_partial < PARTIAL_INTERVAL_MS * vad::SAMPLE_RATE / 1000 {
+            return Ok(None);
+        }
+        self.samples_since_partial = 0;
+        
+        let audio = self.stream_buffer.make_contiguous();
+        let hypothesis = Self::decode(audio);
+        Ok(Some(PartialResult {
+            text: hypothesis.text,
+            duration_ms: hypothesis.duration_ms,
+        }))
+    }
+    
+    /// 结束流式识别，返回最终结果并清空缓存；没有缓存音频时返回空结果
+    pub fn finalize(&mut self) -> SpeechRecognitionResult {
+        self.samples_since_partial = 0;
+        if self.stream_buffer.is_empty() {
+            return SpeechRecognitionResult {
+                text: String::new(),
+                confidence: 0.0,
+                duration_ms: 0,
+            };
+        }
+        
+        let result = Self::decode(self.stream_buffer.make_contiguous());
+        self.stream_buffer.clear();
+        result
+    }
+    
+    /// 已缓存的流式音频采样数
+    pub fn buffered_samples(&self) -> usize {
+        self.stream_buffer.len()
+    }
+    
+    /// 识别一段音频
+    fn decode(audio_data: &[i16]) -> SpeechRecognitionResult {
         // 使用NPU加速语音识别
         // 1. 音频预处理
         // 2. 特征提取
@@ -91,11 +179,11 @@ impl SpeechInteractionEngine {
         // 4. 语言模型解码
         
         // 模拟识别结果
-        Ok(SpeechRecognitionResult {
+        SpeechRecognitionResult {
             text: String::from("打开客厅的灯"),
             confidence: 0.92,
-            duration_ms: 1500,
-        })
+            duration_ms: samples_to_ms(audio_data.len()),
+        }
     }
     
     /// 自然语言理解
@@ -176,6 +264,9 @@ pub struct SpeechInteractionManager {
     engine: SpeechInteractionEngine,
     wake_word_detected: bool,
     conversation_context: Vec<String>,
+    vad: VoiceActivityDetector,
+    /// 最近一次检测到的语音段（在对应音频缓冲区中的采样范围）
+    active_segment: Option<Range<usize>>,
 }
 
 impl SpeechInteractionManager {
@@ -185,28 +276,45 @@ impl SpeechInteractionManager {
             engine: SpeechInteractionEngine::new(),
             wake_word_detected: false,
             conversation_context: Vec::new(),
+            vad: VoiceActivityDetector::new(),
+            active_segment: None,
         }
     }
     
+    /// 设置语音检测的信噪比余量 (dB)
+    pub fn set_snr_margin_db(&mut self, margin_db: f32) {
+        self.vad.set_snr_margin_db(margin_db);
+    }
+    
+    /// 重新估计噪声基底（环境噪声变化后调用）
+    pub fn reset_noise_floor(&mut self) {
+        self.vad.reset_noise_floor();
+    }
+    
     /// 检测唤醒词
+    /// 
+    /// 基于自适应噪声基底的语音活动检测，检测到语音段时记录其范围
     pub fn detect_wake_word(&mut self, audio_data: &[i16]) -> bool {
-        // 简单的能量检测唤醒词
-        let energy: f32 = audio_data.iter()
-            .map(|&s| (s as f32).powi(2))
-            .sum::<f32>() / audio_data.len() as f32;
-        
-        self.wake_word_detected = energy > 1000000.0; // 能量阈值
+        self.active_segment = self.vad.detect_segment(audio_data);
+        self.wake_word_detected = self.active_segment.is_some();
         self.wake_word_detected
     }
     
     /// 处理语音交互
     pub fn process_voice_interaction(&mut self, audio_data: &[i16]) -> Result<Option<Vec<i16>>, AIError> {
-        if !self.wake_word_detected && !self.detect_wake_word(audio_data) {
+        let was_awake = self.wake_word_detected;
+        if !self.detect_wake_word(audio_data) && !was_awake {
             return Ok(None);
         }
         
+        // 只识别语音段，已唤醒但本段未检测到语音边界时使用整段音频
+        let speech = match &self.active_segment {
+            Some(segment) => &audio_data[segment.clone()],
+            None => audio_data,
+        };
+        
         // 语音识别
-        let recognition_result = self.engine.recognize_speech(audio_data)?;
+        let recognition_result = self.engine.recognize_speech(speech)?;
         
         // 自然语言理解
         let nlu_result = self.engine.understand_text(&recognition_result.text)?;
@@ -252,4 +360,46 @@ impl SpeechInteractionManager {
             }
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_stream_three_chunks_then_finalize() {
+        // 三个100ms音频块各产生一个中间结果，finalize返回非空文本并清空缓存
+        let mut engine = SpeechInteractionEngine::new();
+        engine.load_recognition_model(&[0u8; 4]).unwrap();
+        let chunk = [100i16; vad::SAMPLE_RATE / 10];
+        
+        for i in 1..=3 {
+            let partial = engine.recognize_stream(&chunk).unwrap().unwrap();
+            assert_eq!(partial.duration_ms, 100 * i);
+        }
+        assert_eq!(engine.buffered_samples(), 3 * chunk.len());
+        
+        let result = engine.finalize();
+        assert!(!result.text.is_empty());
+        assert_eq!(result.duration_ms, 300);
+        assert_eq!(engine.buffered_samples(), 0);
+        
+        // 没有缓存音频时返回空结果
+        assert!(engine.finalize().text.is_empty());
+    }
+    
+    #[test]
+    fn test_stream_buffer_is_bounded() {
+        // 缓存超过上限时丢弃最早的音频，短于间隔的音频块不输出中间结果
+        let mut engine = SpeechInteractionEngine::new();
+        engine.load_recognition_model(&[0u8; 4]).unwrap();
+        assert!(engine.recognize_stream(&[1i16; 10]).unwrap().is_none());
+        
+        let second = vec![2i16; vad::SAMPLE_RATE];
+        for _ in 0..STREAM_BUFFER_SECONDS + 2 {
+            engine.recognize_stream(&second).unwrap();
+        }
+        assert_eq!(engine.buffered_samples(), STREAM_BUFFER_SECONDS * vad::SAMPLE_RATE);
+        assert_eq!(engine.finalize().duration_ms, STREAM_BUFFER_SECONDS as u32 * 1000);
+    }
+}
@@ -19,6 +19,103 @@ pub enum Language {
     English,
 }
 
+/// 意图规则：每组关键词中至少出现一个时匹配
+struct IntentRule {
+    intent: &'static str,
+    confidence: f32,
+    keyword_groups: &'static [&'static [&'static str]],
+}
+
+/// 槽位规则：文本包含关键词时为意图填充槽位
+struct SlotRule {
+    intent: &'static str,
+    keyword: &'static str,
+    slot_type: &'static str,
+    value: &'static str,
+}
+
+/// 响应模板，`{location}`替换为位置槽位
+struct ResponseTable {
+    ask_light_location: &'static str,
+    light_on: &'static str,
+    environment_all: &'static str,
+    temperature: &'static str,
+    humidity: &'static str,
+    time: &'static str,
+    unknown: &'static str,
+}
+
+/// 单一语言的关键词表和响应模板表
+struct LanguageTables {
+    /// 匹配前是否将文本转换为小写
+    case_insensitive: bool,
+    intents: &'static [IntentRule],
+    slots: &'static [SlotRule],
+    responses: ResponseTable,
+}
+
+static CHINESE_TABLES: LanguageTables = LanguageTables {
+    case_insensitive: false,
+    intents: &[
+        IntentRule { intent: "control_light", confidence: 0.9, keyword_groups: &[&["打开"], &["灯"]] },
+        IntentRule { intent: "query_environment", confidence: 0.85, keyword_groups: &[&["温度", "湿度"]] },
+        IntentRule { intent: "query_time", confidence: 0.8, keyword_groups: &[&["时间", "几点"]] },
+    ],
+    slots: &[
+        SlotRule { intent: "control_light", keyword: "客厅", slot_type: "location", value: "客厅" },
+        SlotRule { intent: "control_light", keyword: "卧室", slot_type: "location", value: "卧室" },
+        SlotRule { intent: "query_environment", keyword: "温度", slot_type: "sensor_type", value: "temperature" },
+        SlotRule { intent: "query_environment", keyword: "湿度", slot_type: "sensor_type", value: "humidity" },
+    ],
+    responses: ResponseTable {
+        ask_light_location: "请问您要打开哪个房间的灯？",
+        light_on: "好的，已打开{location}的灯",
+        environment_all: "当前室内温度25°C，湿度60%",
+        temperature: "当前室内温度25°C",
+        humidity: "当前室内湿度60%",
+        time: "现在是下午3点25分",
+        unknown: "抱歉，我没有理解您的意思，请再说一遍",
+    },
+};
+
+static ENGLISH_TABLES: LanguageTables = LanguageTables {
+    case_insensitive: true,
+    intents: &[
+        IntentRule {
+            intent: "control_light",
+            confidence: 0.9,
+            keyword_groups: &[&["turn on", "switch on"], &["light", "lamp"]],
+        },
+        IntentRule { intent: "query_environment", confidence: 0.85, keyword_groups: &[&["temperature", "humidity"]] },
+        IntentRule { intent: "query_time", confidence: 0.8, keyword_groups: &[&["time", "o'clock"]] },
+    ],
+    slots: &[
+        SlotRule { intent: "control_light", keyword: "living room", slot_type: "location", value: "living room" },
+        SlotRule { intent: "control_light", keyword: "bedroom", slot_type: "location", value: "bedroom" },
+        SlotRule { intent: "query_environment", keyword: "temperature", slot_type: "sensor_type", value: "temperature" },
+        SlotRule { intent: "query_environment", keyword: "humidity", slot_type: "sensor_type", value: "humidity" },
+    ],
+    responses: ResponseTable {
+        ask_light_location: "Which room's light would you like to turn on?",
+        light_on: "OK, the {location} light is on",
+        environment_all: "The indoor temperature is 25°C and the humidity is 60%",
+        temperature: "The indoor temperature is 25°C",
+        humidity: "The indoor humidity is 60%",
+        time: "It is 3:25 PM",
+        unknown: "Sorry, I didn't understand that. Please say it again",
+    },
+};
+
+impl Language {
+    /// 该语言的关键词表和响应模板表
+    fn tables(&self) -> &'static LanguageTables {
+        match self {
+            Language::Chinese => &CHINESE_TABLES,
+            Language::English => &ENGLISH_TABLES,
+        }
+    }
+}
+
 /// 意图识别结果
 #[derive(Debug, Clone)]
 pub struct IntentResult {
@@ -78,64 +175,51 @@ impl NaturalLanguageModel {
         })
     }
     
+    /// 当前语言
+    pub fn language(&self) -> Language {
+        self.language
+    }
+    
+    /// 切换语言，之后的意图识别和响应均使用该语言的表
+    pub fn set_language(&mut self, language: Language) {
+        self.language = language;
+    }
+    
     /// 基于规则的意图识别
     fn rule_based_intent_recognition(&self, text: &str) -> (String, f32, Vec<Slot>) {
-        let mut intent = String::from("unknown");
-        let mut confidence = 0.5;
-        let mut slots = Vec::new();
+        let tables = self.language.tables();
+        let lowered;
+        let text = if tables.case_insensitive {
+            lowered = text.to_lowercase();
+            lowered.as_str()
+        } else {
+            text
+        };
         
-        // 中文意图识别规则
-        if self.language == Language::Chinese {
-            if text.contains("打开") && text.contains("灯") {
-                intent = String::from("control_light");
-                confidence = 0.9;
-                
-                // 提取位置信息
-                if text.contains("客厅") {
-                    slots.push(Slot {
-                        slot_type: String::from("location"),
-                        value: String::from("客厅"),
-                        start: text.find("客厅").unwrap_or(0),
-                        end: text.find("客厅").unwrap_or(0) + 2,
-                    });
-                }
-                
-                if text.contains("卧室") {
-                    slots.push(Slot {
-                        slot_type: String::from("location"),
-                        value: String::from("卧室"),
-                        start: text.find("卧室").unwrap_or(0),
-                        end: text.find("卧室").unwrap_or(0) + 2,
-                    });
-                }
-            } else if text.contains("温度") || text.contains("湿度") {
-                intent = String::from("query_environment");
-                confidence = 0.85;
-                
-                if text.contains("温度") {
-                    slots.push(Slot {
-                        slot_type: String::from("sensor_type"),
-                        value: String::from("temperature"),
-                        start: text.find("温度").unwrap_or(0),
-                        end: text.find("温度").unwrap_or(0) + 2,
-                    });
-                }
-                
-                if text.contains("湿度") {
-                    slots.push(Slot {
-                        slot_type: String::from("sensor_type"),
-                        value: String::from("humidity"),
-                        start: text.find("湿度").unwrap_or(0),
-                        end: text.find("湿度").unwrap_or(0) + 2,
-                    });
-                }
-            } else if text.contains("时间") || text.contains("几点") {
-                intent = String::from("query_time");
-                confidence = 0.8;
-            }
-        }
+        let rule = tables.intents.iter().find(|rule| {
+            rule.keyword_groups
+                .iter()
+                .all(|group| group.iter().any(|keyword| text.contains(keyword)))
+        });
+        let rule = match rule {
+            Some(rule) => rule,
+            None => return (String::from("unknown"), 0.5, Vec::new()),
+        };
+        
+        let slots = tables.slots
+            .iter()
+            .filter(|slot| slot.intent == rule.intent)
+            .filter_map(|slot| {
+                text.find(slot.keyword).map(|start| Slot {
+                    slot_type: String::from(slot.slot_type),
+                    value: String::from(slot.value),
+                    start,
+                    end: start + slot.keyword.len(),
+                })
+            })
+            .collect();
         
-        (intent, confidence, slots)
+        (String::from(rule.intent), rule.confidence, slots)
     }
     
     /// 对话状态管理
@@ -152,7 +236,8 @@ impl NaturalLanguageModel {
     }
     
     /// 生成响应文本
-    pub fn generate_response(&self, intent_result: &IntentResult, state: &DialogState) -> String {
+    pub fn generate_response(&self, intent_result: &IntentResult, _state: &DialogState) -> String {
+        let responses = &self.language.tables().responses;
         match intent_result.intent.as_str() {
             "control_light" => {
                 let location = intent_result.slots.iter()
@@ -161,9 +246,9 @@ impl NaturalLanguageModel {
                     .unwrap_or("");
                 
                 if location.is_empty() {
-                    String::from("请问您要打开哪个房间的灯？")
+                    String::from(responses.ask_light_location)
                 } else {
-                    format!("好的，已打开{}的灯", location)
+                    responses.light_on.replace("{location}", location)
                 }
             }
             "query_environment" => {
@@ -172,22 +257,71 @@ impl NaturalLanguageModel {
                     .map(|s| s.value.as_str())
                     .collect();
                 
-                if sensor_types.is_empty() {
-                    String::from("当前室内温度25°C，湿度60%")
-                } else if sensor_types.contains(&"temperature") && sensor_types.contains(&"humidity") {
-                    String::from("当前室内温度25°C，湿度60%")
-                } else if sensor_types.contains(&"temperature") {
-                    String::from("当前室内温度25°C")
-                } else {
-                    String::from("当前室内湿度60%")
+                let temperature = sensor_types.contains(&"temperature");
+                let humidity = sensor_types.contains(&"humidity");
+                match (temperature, humidity) {
+                    (true, false) => String::from(responses.temperature),
+                    (false, true) => String::from(responses.humidity),
+                    _ => String::from(responses.environment_all),
                 }
             }
             "query_time" => {
-                String::from("现在是下午3点25分")
+                String::from(responses.time)
             }
             _ => {
-                String::from("抱歉，我没有理解您的意思，请再说一遍")
+                String::from(responses.unknown)
             }
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn loaded_model(language: Language) -> NaturalLanguageModel {
+        let mut model = NaturalLanguageModel::new(language);
+        model.load_model(&[]).unwrap();
+        model
+    }
+
+    fn empty_state() -> DialogState {
+        DialogState { current_intent: None, filled_slots: Vec::new(), context: Vec::new(), turn_count: 0 }
+    }
+
+    #[test]
+    fn test_english_keywords_resolve_same_intent() {
+        // 英文关键词与中文关键词识别出相同的意图和槽位
+        let mut chinese = loaded_model(Language::Chinese);
+        let mut english = loaded_model(Language::English);
+
+        let zh = chinese.recognize_intent("打开客厅的灯").unwrap();
+        let en = english.recognize_intent("Please turn on the Living Room light").unwrap();
+        assert_eq!(zh.intent, "control_light");
+        assert_eq!(en.intent, zh.intent);
+        assert_eq!(en.slots.len(), 1);
+        assert_eq!((en.slots[0].slot_type.as_str(), en.slots[0].value.as_str()), ("location", "living room"));
+        assert_eq!(english.recognize_intent("what is the humidity").unwrap().intent, "query_environment");
+
+        // 运行时切换语言后使用对应的关键词表
+        english.set_language(Language::Chinese);
+        assert_eq!(english.recognize_intent("turn on the light").unwrap().intent, "unknown");
+    }
+
+    #[test]
+    fn test_response_rendered_in_selected_language() {
+        // 响应按当前语言渲染，包括无法理解时的回退响应
+        let mut model = loaded_model(Language::English);
+        let state = empty_state();
+
+        let result = model.recognize_intent("switch on the bedroom lamp").unwrap();
+        assert_eq!(model.generate_response(&result, &state), "OK, the bedroom light is on");
+        let unknown = model.recognize_intent("sing a song").unwrap();
+        assert_eq!(model.generate_response(&unknown, &state), "Sorry, I didn't understand that. Please say it again");
+
+        model.set_language(Language::Chinese);
+        let result = model.recognize_intent("打开卧室的灯").unwrap();
+        assert_eq!(model.generate_response(&result, &state), "好的，已打开卧室的灯");
+        assert_eq!(model.generate_response(&unknown, &state), "抱歉，我没有理解您的意思，请再说一遍");
+    }
+}
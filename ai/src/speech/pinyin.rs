@@ -0,0 +1,114 @@
+//! 汉字转拼音
+//!
+//! 内置常用汉字的拼音表（多音字取最常用读音），将拼音拆分为声母和带声调的韵母，
+//! 作为语音合成前端的音素
+
+/// 轻声的声调编号
+pub const NEUTRAL_TONE: u8 = 5;
+
+/// 声母，双字母声母在前以便最长匹配；y、w按声母处理
+const INITIALS: [&str; 23] = [
+    "zh", "ch", "sh", "b", "p", "m", "f", "d", "t", "n", "l", "g", "k", "h", "j", "q", "x", "r", "z", "c", "s", "y", "w",
+];
+
+/// 常用汉字拼音表，按码点升序排列；拼音末尾数字为声调，ü写作v
+const PINYIN_TABLE: [(char, &str); 273] = [
+    ('一', "yi1"), ('七', "qi1"), ('万', "wan4"), ('三', "san1"), ('上', "shang4"), ('下', "xia4"), ('不', "bu4"), ('与', "yu3"),
+    ('东', "dong1"), ('两', "liang3"), ('个', "ge4"), ('中', "zhong1"), ('为', "wei4"), ('么', "me5"), ('之', "zhi1"), ('乐', "yue4"),
+    ('乘', "cheng2"), ('九', "jiu3"), ('也', "ye3"), ('习', "xi2"), ('了', "le5"), ('事', "shi4"), ('二', "er4"), ('于', "yu2"),
+    ('五', "wu3"), ('些', "xie1"), ('亮', "liang4"), ('人', "ren2"), ('亿', "yi4"), ('什', "shen2"), ('今', "jin1"), ('从', "cong2"),
+    ('他', "ta1"), ('以', "yi3"), ('们', "men5"), ('会', "hui4"), ('低', "di1"), ('体', "ti3"), ('作', "zuo4"), ('你', "ni3"),
+    ('信', "xin4"), ('做', "zuo4"), ('停', "ting2"), ('儿', "er2"), ('元', "yuan2"), ('充', "chong1"), ('先', "xian1"), ('克', "ke4"),
+    ('八', "ba1"), ('公', "gong1"), ('六', "liu4"), ('关', "guan1"), ('再', "zai4"), ('冷', "leng3"), ('准', "zhun3"), ('减', "jian3"),
+    ('几', "ji3"), ('出', "chu1"), ('分', "fen1"), ('别', "bie2"), ('到', "dao4"), ('前', "qian2"), ('功', "gong1"), ('加', "jia1"),
+    ('动', "dong4"), ('助', "zhu4"), ('北', "bei3"), ('十', "shi2"), ('千', "qian1"), ('升', "sheng1"), ('南', "nan2"), ('危', "wei1"),
+    ('去', "qu4"), ('友', "you3"), ('叫', "jiao4"), ('可', "ke3"), ('右', "you4"), ('号', "hao4"), ('吃', "chi1"), ('名', "ming2"),
+    ('后', "hou4"), ('向', "xiang4"), ('吗', "ma5"), ('吧', "ba5"), ('听', "ting1"), ('启', "qi3"), ('告', "gao4"), ('呢', "ne5"),
+    ('和', "he2"), ('哦', "o4"), ('哪', "na3"), ('啊', "a5"), ('喜', "xi3"), ('四', "si4"), ('回', "hui2"), ('国', "guo2"),
+    ('在', "zai4"), ('块', "kuai4"), ('士', "shi4"), ('声', "sheng1"), ('备', "bei4"), ('外', "wai4"), ('多', "duo1"), ('大', "da4"),
+    ('天', "tian1"), ('太', "tai4"), ('失', "shi1"), ('女', "nv3"), ('她', "ta1"), ('好', "hao3"), ('子', "zi3"), ('字', "zi4"),
+    ('学', "xue2"), ('孩', "hai2"), ('它', "ta1"), ('安', "an1"), ('完', "wan2"), ('室', "shi4"), ('家', "jia1"), ('对', "dui4"),
+    ('小', "xiao3"), ('少', "shao3"), ('就', "jiu4"), ('工', "gong1"), ('左', "zuo3"), ('己', "ji3"), ('已', "yi3"), ('帮', "bang1"),
+    ('常', "chang2"), ('年', "nian2"), ('度', "du4"), ('开', "kai1"), ('式', "shi4"), ('很', "hen3"), ('心', "xin1"), ('快', "kuai4"),
+    ('怎', "zen3"), ('情', "qing2"), ('想', "xiang3"), ('意', "yi4"), ('慢', "man4"), ('成', "cheng2"), ('我', "wo3"), ('手', "shou3"),
+    ('打', "da3"), ('找', "zhao3"), ('把', "ba3"), ('拿', "na2"), ('接', "jie1"), ('摄', "she4"), ('播', "bo1"), ('放', "fang4"),
+    ('文', "wen2"), ('斤', "jin1"), ('断', "duan4"), ('方', "fang1"), ('日', "ri4"), ('早', "zao3"), ('时', "shi2"), ('明', "ming2"),
+    ('昨', "zuo2"), ('是', "shi4"), ('晚', "wan3"), ('晴', "qing2"), ('智', "zhi4"), ('暗', "an4"), ('更', "geng4"), ('最', "zui4"),
+    ('月', "yue4"), ('有', "you3"), ('朋', "peng2"), ('机', "ji1"), ('来', "lai2"), ('样', "yang4"), ('检', "jian3"), ('模', "mo2"),
+    ('次', "ci4"), ('欢', "huan1"), ('欧', "ou1"), ('止', "zhi3"), ('正', "zheng4"), ('每', "mei3"), ('毛', "mao2"), ('氏', "shi4"),
+    ('气', "qi4"), ('水', "shui3"), ('池', "chi2"), ('没', "mei2"), ('注', "zhu4"), ('活', "huo2"), ('测', "ce4"), ('温', "wen1"),
+    ('湿', "shi1"), ('灯', "deng1"), ('点', "dian3"), ('热', "re4"), ('爱', "ai4"), ('物', "wu4"), ('狗', "gou3"), ('猫', "mao1"),
+    ('现', "xian4"), ('生', "sheng1"), ('用', "yong4"), ('电', "dian4"), ('百', "bai3"), ('的', "de5"), ('看', "kan4"), ('真', "zhen1"),
+    ('睡', "shui4"), ('知', "zhi1"), ('种', "zhong3"), ('秒', "miao3"), ('等', "deng3"), ('答', "da2"), ('米', "mi3"), ('系', "xi4"),
+    ('给', "gei3"), ('络', "luo4"), ('统', "tong3"), ('网', "wang3"), ('置', "zhi4"), ('而', "er2"), ('能', "neng2"), ('自', "zi4"),
+    ('英', "ying1"), ('被', "bei4"), ('西', "xi1"), ('要', "yao4"), ('见', "jian4"), ('视', "shi4"), ('觉', "jiao4"), ('警', "jing3"),
+    ('让', "rang4"), ('设', "she4"), ('识', "shi2"), ('语', "yu3"), ('误', "wu4"), ('说', "shuo1"), ('请', "qing3"), ('谁', "shei2"),
+    ('谢', "xie4"), ('负', "fu4"), ('败', "bai4"), ('走', "zou3"), ('起', "qi3"), ('路', "lu4"), ('车', "che1"), ('迎', "ying2"),
+    ('还', "hai2"), ('这', "zhe4"), ('连', "lian2"), ('道', "dao4"), ('那', "na4"), ('都', "dou1"), ('里', "li3"), ('量', "liang4"),
+    ('钟', "zhong1"), ('错', "cuo4"), ('门', "men2"), ('问', "wen4"), ('间', "jian1"), ('阴', "yin1"), ('除', "chu2"), ('险', "xian3"),
+    ('雨', "yu3"), ('雪', "xue3"), ('零', "ling2"), ('音', "yin1"), ('题', "ti2"), ('风', "feng1"), ('饭', "fan4"), ('饿', "e4"),
+    ('高', "gao1"),
+];
+
+/// 一个汉字的拼音
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pinyin {
+    /// 声母，零声母音节为空
+    pub initial: &'static str,
+    /// 韵母（不含声调）
+    pub rime: &'static str,
+    /// 声调，1-4为四声，5为轻声
+    pub tone: u8,
+}
+
+impl Pinyin {
+    /// 解析带声调数字的拼音音节（如"hao3"），缺少声调时按轻声处理
+    pub fn parse(syllable: &'static str) -> Self {
+        let (body, tone) = match syllable.as_bytes().last() {
+            Some(&digit @ b'1'..=b'5') => (&syllable[..syllable.len() - 1], digit - b'0'),
+            _ => (syllable, NEUTRAL_TONE),
+        };
+        let initial = INITIALS
+            .iter()
+            .find(|initial| body.len() > initial.len() && body.starts_with(*initial))
+            .copied()
+            .unwrap_or("");
+
+        Self {
+            initial,
+            rime: &body[initial.len()..],
+            tone,
+        }
+    }
+}
+
+/// 查询汉字的拼音，不在拼音表中的字符返回None
+pub fn hanzi_to_pinyin(ch: char) -> Option<Pinyin> {
+    PINYIN_TABLE
+        .binary_search_by_key(&ch, |&(hanzi, _)| hanzi)
+        .ok()
+        .map(|index| Pinyin::parse(PINYIN_TABLE[index].1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_sorted_for_binary_search() {
+        // 拼音表必须按码点严格升序，否则二分查找会漏字
+        assert!(PINYIN_TABLE.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        assert!(PINYIN_TABLE.iter().all(|&(_, syllable)| matches!(syllable.as_bytes().last(), Some(b'1'..=b'5'))));
+    }
+
+    #[test]
+    fn test_split_initial_and_final() {
+        // 双字母声母优先匹配，零声母音节声母为空，未收录的字返回None
+        assert_eq!(hanzi_to_pinyin('好'), Some(Pinyin { initial: "h", rime: "ao", tone: 3 }));
+        assert_eq!(hanzi_to_pinyin('中'), Some(Pinyin { initial: "zh", rime: "ong", tone: 1 }));
+        assert_eq!(hanzi_to_pinyin('二'), Some(Pinyin { initial: "", rime: "er", tone: 4 }));
+        assert_eq!(hanzi_to_pinyin('吗'), Some(Pinyin { initial: "m", rime: "a", tone: NEUTRAL_TONE }));
+        assert_eq!(hanzi_to_pinyin('龘'), None);
+        assert_eq!(hanzi_to_pinyin('a'), None);
+    }
+}
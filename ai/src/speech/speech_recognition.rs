@@ -33,6 +33,27 @@ pub struct SpeechRecognitionConfig {
     pub enable_vad: bool,
     pub confidence_threshold: f32,
     pub chunk_size: usize,
+    /// 音频帧长 (ms)，与采集端一致
+    pub frame_ms: u32,
+}
+
+impl SpeechRecognitionConfig {
+    /// 按采集端的音频格式生成配置，`chunk_size`由采样率、帧长和声道数计算
+    /// 
+    /// 帧长不对应整数个采样点时拒绝
+    pub fn with_audio_format(sample_rate: u32, frame_ms: u32, channels: u8) -> Result<Self, AIError> {
+        if sample_rate == 0 || frame_ms == 0 || channels == 0
+            || (sample_rate as u64 * frame_ms as u64) % 1000 != 0 {
+            return Err(AIError::InvalidInput);
+        }
+        
+        Ok(Self {
+            sample_rate,
+            frame_ms,
+            chunk_size: (sample_rate * frame_ms / 1000) as usize * channels as usize,
+            ..Default::default()
+        })
+    }
 }
 
 impl Default for SpeechRecognitionConfig {
@@ -44,6 +65,7 @@ impl Default for SpeechRecognitionConfig {
             enable_vad: true,
             confidence_threshold: 0.7,
             chunk_size: 1600, // 100ms at 16kHz
+            frame_ms: 100,
         }
     }
 }
@@ -358,6 +380,7 @@ impl SpeechRecognitionModel {
     pub fn set_sample_rate(&mut self, sample_rate: u32) {
         self.sample_rate = sample_rate;
         self.config.sample_rate = sample_rate;
+        self.config.chunk_size = (sample_rate * self.config.frame_ms / 1000) as usize;
     }
     
     /// 释放模型资源
@@ -440,6 +463,16 @@ mod tests {
         assert_eq!(model.sample_rate, 16000);
     }
     
+    #[test]
+    fn test_audio_format_sets_chunk_size() {
+        // 8kHz/20ms时每块160个采样点，非整数采样点的帧长被拒绝
+        let config = SpeechRecognitionConfig::with_audio_format(8000, 20, 1).unwrap();
+        assert_eq!(config.chunk_size, 160);
+        assert_eq!(config.sample_rate, 8000);
+        assert_eq!(SpeechRecognitionConfig::default().chunk_size, 1600);
+        assert!(SpeechRecognitionConfig::with_audio_format(22050, 10, 1).is_err());
+    }
+    
     #[test]
     fn test_voice_activity_detection() {
         let model = SpeechRecognitionModel::new(Language::Chinese);
@@ -0,0 +1,425 @@
+//! TTS文本规范化模块
+//!
+//! 按语言规则将数字（含位值、年份、小数、负数）、单位和常用符号展开为可朗读的文字
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use super::natural_language::Language;
+
+/// 超过该位数的整数逐位朗读
+const MAX_PLACE_VALUE_DIGITS: usize = 16;
+
+const CHINESE_DIGITS: [&str; 10] = ["零", "一", "二", "三", "四", "五", "六", "七", "八", "九"];
+const CHINESE_PLACES: [&str; 4] = ["", "十", "百", "千"];
+const CHINESE_GROUPS: [&str; 4] = ["", "万", "亿", "万亿"];
+
+const ENGLISH_DIGITS: [&str; 10] = ["zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
+const ENGLISH_TEENS: [&str; 10] = [
+    "ten", "eleven", "twelve", "thirteen", "fourteen", "fifteen", "sixteen", "seventeen", "eighteen", "nineteen",
+];
+const ENGLISH_TENS: [&str; 10] = ["", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety"];
+const ENGLISH_GROUPS: [&str; 6] = ["", "thousand", "million", "billion", "trillion", "quadrillion"];
+
+/// 数字读法
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumberStyle {
+    /// 中文位值读法（二千零二十四）
+    Chinese,
+    /// 英文读法（two thousand twenty-four）
+    English,
+}
+
+/// 单位读法相对数字的位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnitPosition {
+    /// 读在数字之前（百分之二十）
+    Before,
+    /// 读在数字之后（二十五摄氏度）
+    After,
+}
+
+/// 单位规则：紧跟在数字后的符号及其读法
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnitRule {
+    pub symbol: &'static str,
+    pub reading: &'static str,
+    pub position: UnitPosition,
+}
+
+/// 文本规范化规则
+#[derive(Debug, Clone)]
+pub struct NormalizationRules {
+    number_style: NumberStyle,
+    decimal_point: &'static str,
+    minus: &'static str,
+    /// 四位数紧跟该字符时按年份逐位朗读
+    year_suffix: Option<char>,
+    /// 读法之间的分隔符
+    separator: &'static str,
+    units: Vec<UnitRule>,
+    symbols: Vec<(char, &'static str)>,
+    punctuation: Vec<(char, char)>,
+}
+
+impl NormalizationRules {
+    /// 中文规则
+    pub fn chinese() -> Self {
+        let unit = |symbol, reading| UnitRule { symbol, reading, position: UnitPosition::After };
+        Self {
+            number_style: NumberStyle::Chinese,
+            decimal_point: "点",
+            minus: "负",
+            year_suffix: Some('年'),
+            separator: "",
+            units: vec![
+                unit("°C", "摄氏度"),
+                unit("℃", "摄氏度"),
+                unit("°F", "华氏度"),
+                unit("°", "度"),
+                UnitRule { symbol: "%", reading: "百分之", position: UnitPosition::Before },
+                unit("km/h", "公里每小时"),
+                unit("km", "公里"),
+                unit("kg", "千克"),
+                unit("cm", "厘米"),
+                unit("mm", "毫米"),
+                unit("ml", "毫升"),
+                unit("lux", "勒克斯"),
+            ],
+            symbols: vec![('+', "加"), ('=', "等于"), ('&', "和"), ('~', "至")],
+            punctuation: vec![
+                (',', '，'), ('，', '，'),
+                ('.', '。'), ('。', '。'),
+                ('!', '！'), ('！', '！'),
+                ('?', '？'), ('？', '？'),
+            ],
+        }
+    }
+
+    /// 英文规则
+    pub fn english() -> Self {
+        let unit = |symbol, reading| UnitRule { symbol, reading, position: UnitPosition::After };
+        Self {
+            number_style: NumberStyle::English,
+            decimal_point: "point",
+            minus: "minus",
+            year_suffix: None,
+            separator: " ",
+            units: vec![
+                unit("°C", "degrees Celsius"),
+                unit("℃", "degrees Celsius"),
+                unit("°F", "degrees Fahrenheit"),
+                unit("°", "degrees"),
+                unit("%", "percent"),
+                unit("km/h", "kilometers per hour"),
+                unit("km", "kilometers"),
+                unit("kg", "kilograms"),
+                unit("cm", "centimeters"),
+                unit("mm", "millimeters"),
+                unit("ml", "milliliters"),
+                unit("lux", "lux"),
+            ],
+            symbols: vec![('+', "plus"), ('=', "equals"), ('&', "and")],
+            punctuation: Vec::new(),
+        }
+    }
+
+    /// 指定语言的默认规则
+    pub fn for_language(language: Language) -> Self {
+        match language {
+            Language::Chinese => Self::chinese(),
+            Language::English => Self::english(),
+        }
+    }
+
+    /// 添加或覆盖单位规则
+    pub fn set_unit(&mut self, symbol: &'static str, reading: &'static str, position: UnitPosition) {
+        let rule = UnitRule { symbol, reading, position };
+        match self.units.iter_mut().find(|unit| unit.symbol == symbol) {
+            Some(existing) => *existing = rule,
+            None => self.units.push(rule),
+        }
+    }
+
+    /// 添加或覆盖符号读法
+    pub fn set_symbol(&mut self, symbol: char, reading: &'static str) {
+        match self.symbols.iter_mut().find(|(ch, _)| *ch == symbol) {
+            Some(existing) => existing.1 = reading,
+            None => self.symbols.push((symbol, reading)),
+        }
+    }
+
+    /// 规范化文本
+    pub fn normalize(&self, text: &str) -> String {
+        let chars: Vec<char> = text.chars().collect();
+        let mut normalized = String::new();
+        let mut i = 0;
+
+        while i < chars.len() {
+            let ch = chars[i];
+            if ch.is_ascii_digit() || self.starts_negative_number(&chars, i) {
+                i = self.normalize_number(&chars, i, &mut normalized);
+                continue;
+            }
+
+            if let Some(&(_, reading)) = self.symbols.iter().find(|(symbol, _)| *symbol == ch) {
+                self.push_reading(&mut normalized, reading);
+            } else if let Some(&(_, mapped)) = self.punctuation.iter().find(|(symbol, _)| *symbol == ch) {
+                normalized.push(mapped);
+            } else {
+                normalized.push(ch);
+            }
+            i += 1;
+        }
+
+        normalized
+    }
+
+    /// 负号：后跟数字且前面不是ASCII字母数字
+    fn starts_negative_number(&self, chars: &[char], i: usize) -> bool {
+        chars[i] == '-'
+            && chars.get(i + 1).map_or(false, |c| c.is_ascii_digit())
+            && (i == 0 || !chars[i - 1].is_ascii_alphanumeric())
+    }
+
+    /// 展开从`start`开始的数字及其后的单位，返回下一个未处理字符的下标
+    fn normalize_number(&self, chars: &[char], start: usize, out: &mut String) -> usize {
+        let negative = chars[start] == '-';
+        let int_start = if negative { start + 1 } else { start };
+        let int_end = digit_run_end(chars, int_start);
+        let mut end = int_end;
+
+        let fraction = if chars.get(int_end) == Some(&'.') && chars.get(int_end + 1).map_or(false, |c| c.is_ascii_digit()) {
+            end = digit_run_end(chars, int_end + 1);
+            Some(&chars[int_end + 1..end])
+        } else {
+            None
+        };
+        let integer = &chars[int_start..int_end];
+
+        // 年份：逐位朗读
+        if let Some(suffix) = self.year_suffix {
+            if !negative && fraction.is_none() && integer.len() == 4 && chars.get(end) == Some(&suffix) {
+                self.push_reading(out, &self.read_digits(integer));
+                out.push(suffix);
+                return end + 1;
+            }
+        }
+
+        let mut reading = String::new();
+        if negative {
+            reading.push_str(self.minus);
+            reading.push_str(self.separator);
+        }
+        reading.push_str(&self.read_integer(integer));
+        if let Some(fraction) = fraction {
+            reading.push_str(self.separator);
+            reading.push_str(self.decimal_point);
+            reading.push_str(self.separator);
+            reading.push_str(&self.read_digits(fraction));
+        }
+
+        match self.match_unit(chars, end) {
+            Some(unit) => {
+                let combined = match unit.position {
+                    UnitPosition::Before => [unit.reading, self.separator, reading.as_str()].concat(),
+                    UnitPosition::After => [reading.as_str(), self.separator, unit.reading].concat(),
+                };
+                self.push_reading(out, &combined);
+                end + unit.symbol.chars().count()
+            }
+            None => {
+                self.push_reading(out, &reading);
+                end
+            }
+        }
+    }
+
+    /// 匹配`at`处最长的单位符号
+    fn match_unit(&self, chars: &[char], at: usize) -> Option<&UnitRule> {
+        self.units
+            .iter()
+            .filter(|unit| {
+                let mut symbol = unit.symbol.chars();
+                let len = unit.symbol.chars().count();
+                at + len <= chars.len() && chars[at..at + len].iter().all(|c| symbol.next() == Some(*c))
+            })
+            .max_by_key(|unit| unit.symbol.len())
+    }
+
+    /// 追加读法，需要时用分隔符与前文隔开
+    fn push_reading(&self, out: &mut String, reading: &str) {
+        if !self.separator.is_empty() && out.chars().last().map_or(false, |c| !c.is_whitespace()) {
+            out.push_str(self.separator);
+        }
+        out.push_str(reading);
+    }
+
+    /// 逐位朗读
+    fn read_digits(&self, digits: &[char]) -> String {
+        let words: Vec<&str> = digits
+            .iter()
+            .map(|c| match self.number_style {
+                NumberStyle::Chinese => CHINESE_DIGITS[digit_value(*c)],
+                NumberStyle::English => ENGLISH_DIGITS[digit_value(*c)],
+            })
+            .collect();
+        words.join(self.separator)
+    }
+
+    /// 按位值朗读整数；带前导零或过长的数字逐位朗读
+    fn read_integer(&self, digits: &[char]) -> String {
+        if (digits.len() > 1 && digits[0] == '0') || digits.len() > MAX_PLACE_VALUE_DIGITS {
+            return self.read_digits(digits);
+        }
+        let value = digits.iter().fold(0u64, |acc, c| acc * 10 + digit_value(*c) as u64);
+        match self.number_style {
+            NumberStyle::Chinese => chinese_number(value),
+            NumberStyle::English => english_number(value),
+        }
+    }
+}
+
+impl Default for NormalizationRules {
+    fn default() -> Self {
+        Self::chinese()
+    }
+}
+
+fn digit_run_end(chars: &[char], start: usize) -> usize {
+    chars[start..]
+        .iter()
+        .position(|c| !c.is_ascii_digit())
+        .map_or(chars.len(), |offset| start + offset)
+}
+
+fn digit_value(c: char) -> usize {
+    c as usize - '0' as usize
+}
+
+/// 中文位值读法，四位一组（万、亿）
+fn chinese_number(value: u64) -> String {
+    if value == 0 {
+        return String::from(CHINESE_DIGITS[0]);
+    }
+
+    let mut groups = Vec::new();
+    let mut rest = value;
+    while rest > 0 {
+        groups.push((rest % 10_000) as usize);
+        rest /= 10_000;
+    }
+
+    let mut out = String::new();
+    let mut zero_pending = false;
+    for (index, &group) in groups.iter().enumerate().rev() {
+        if group == 0 {
+            zero_pending = !out.is_empty();
+            continue;
+        }
+        // 组间有空位或本组不足千位时补"零"
+        if !out.is_empty() && (zero_pending || group < 1000) {
+            out.push_str(CHINESE_DIGITS[0]);
+        }
+        push_chinese_group(&mut out, group);
+        out.push_str(CHINESE_GROUPS[index]);
+        zero_pending = false;
+    }
+
+    // 十几开头读作"十五"而非"一十五"
+    match out.strip_prefix("一十") {
+        Some(rest) => ["十", rest].concat(),
+        None => out,
+    }
+}
+
+fn push_chinese_group(out: &mut String, group: usize) {
+    let mut zero_pending = false;
+    let mut emitted = false;
+    for place in (0..4).rev() {
+        let digit = group / 10usize.pow(place as u32) % 10;
+        if digit == 0 {
+            zero_pending = emitted;
+            continue;
+        }
+        if zero_pending {
+            out.push_str(CHINESE_DIGITS[0]);
+            zero_pending = false;
+        }
+        out.push_str(CHINESE_DIGITS[digit]);
+        out.push_str(CHINESE_PLACES[place]);
+        emitted = true;
+    }
+}
+
+/// 英文读法，三位一组
+fn english_number(value: u64) -> String {
+    if value == 0 {
+        return String::from(ENGLISH_DIGITS[0]);
+    }
+
+    let mut groups = Vec::new();
+    let mut rest = value;
+    while rest > 0 {
+        groups.push((rest % 1000) as usize);
+        rest /= 1000;
+    }
+
+    let mut words: Vec<String> = Vec::new();
+    for (index, &group) in groups.iter().enumerate().rev() {
+        if group == 0 {
+            continue;
+        }
+        words.push(english_group(group));
+        if !ENGLISH_GROUPS[index].is_empty() {
+            words.push(String::from(ENGLISH_GROUPS[index]));
+        }
+    }
+    words.join(" ")
+}
+
+fn english_group(group: usize) -> String {
+    let mut words: Vec<String> = Vec::new();
+    if group >= 100 {
+        words.push([ENGLISH_DIGITS[group / 100], " hundred"].concat());
+    }
+    let tens = group % 100;
+    match tens {
+        0 => {}
+        1..=9 => words.push(String::from(ENGLISH_DIGITS[tens])),
+        10..=19 => words.push(String::from(ENGLISH_TEENS[tens - 10])),
+        _ if tens % 10 == 0 => words.push(String::from(ENGLISH_TENS[tens / 10])),
+        _ => words.push([ENGLISH_TENS[tens / 10], "-", ENGLISH_DIGITS[tens % 10]].concat()),
+    }
+    words.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chinese_numbers_years_and_units() {
+        // 多位数按位值朗读，年份逐位朗读，温度和百分比展开单位
+        let rules = NormalizationRules::chinese();
+        assert_eq!(rules.normalize("2024"), "二千零二十四");
+        assert_eq!(rules.normalize("2024年"), "二零二四年");
+        assert_eq!(rules.normalize("25°C"), "二十五摄氏度");
+        assert_eq!(rules.normalize("室温-3.5℃，湿度35%"), "室温负三点五摄氏度，湿度百分之三十五");
+        assert_eq!(rules.normalize("100010"), "十万零一十");
+        assert_eq!(rules.normalize("1+1=2!"), "一加一等于二！");
+    }
+
+    #[test]
+    fn test_rules_are_overridable() {
+        // 可覆盖单位读法，英文规则使用英文读法
+        let mut rules = NormalizationRules::chinese();
+        rules.set_unit("°C", "度", UnitPosition::After);
+        rules.set_symbol('&', "与");
+        assert_eq!(rules.normalize("25°C & 30%"), "二十五度 与 百分之三十");
+
+        let english = NormalizationRules::for_language(Language::English);
+        assert_eq!(english.normalize("25°C"), "twenty-five degrees Celsius");
+        assert_eq!(english.normalize("2024"), "two thousand twenty-four");
+    }
+}
@@ -3,6 +3,8 @@
 //! 提供基于深度学习的语音合成功能，支持中文语音合成
 
 use crate::AIError;
+use super::pinyin::hanzi_to_pinyin;
+use super::text_normalizer::NormalizationRules;
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 use core::f32;
@@ -15,6 +17,7 @@ pub struct TextToSpeechModel {
     inference_engine: Option<InferenceEngine>,
     config: TTSConfig,
     vocoder_loaded: bool,
+    normalization: NormalizationRules,
 }
 
 /// 语音类型
@@ -70,6 +73,134 @@ pub struct SynthesisResult {
     pub audio_quality: AudioQuality,
 }
 
+/// WAV文件头长度（RIFF头 + fmt块 + data块头）
+pub const WAV_HEADER_LEN: usize = 44;
+
+/// WAV fmt块中的PCM格式编号
+const WAV_FORMAT_PCM: u16 = 1;
+
+/// 计算data块长度和RIFF块长度，超出WAV的32位长度字段时返回`InvalidInput`
+fn wav_chunk_sizes(sample_count: usize) -> Result<(u32, u32), AIError> {
+    let data_len = sample_count
+        .checked_mul(2)
+        .and_then(|len| u32::try_from(len).ok())
+        .ok_or(AIError::InvalidInput)?;
+    let riff_len = data_len.checked_add(36).ok_or(AIError::InvalidInput)?;
+    Ok((data_len, riff_len))
+}
+
+impl SynthesisResult {
+    /// 序列化为WAV（单声道、16位小端PCM，采样率取自`sample_rate`）
+    ///
+    /// 音频或字节率超出WAV头32位长度字段时返回`InvalidInput`
+    pub fn to_wav(&self) -> Result<Vec<u8>, AIError> {
+        let (data_len, riff_len) = wav_chunk_sizes(self.audio_data.len())?;
+        let byte_rate = self.sample_rate.checked_mul(2).ok_or(AIError::InvalidInput)?;
+        let mut wav = Vec::with_capacity(WAV_HEADER_LEN + data_len as usize);
+
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&riff_len.to_le_bytes());
+        wav.extend_from_slice(b"WAVE");
+
+        wav.extend_from_slice(b"fmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&WAV_FORMAT_PCM.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes()); // 声道数
+        wav.extend_from_slice(&self.sample_rate.to_le_bytes());
+        wav.extend_from_slice(&byte_rate.to_le_bytes()); // 字节率
+        wav.extend_from_slice(&2u16.to_le_bytes()); // 块对齐
+        wav.extend_from_slice(&16u16.to_le_bytes()); // 位深
+
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&data_len.to_le_bytes());
+        for sample in &self.audio_data {
+            wav.extend_from_slice(&sample.to_le_bytes());
+        }
+        Ok(wav)
+    }
+
+    /// 重采样到指定采样率（如音频驱动的16kHz）
+    pub fn resampled_to(&self, sample_rate: u32) -> Self {
+        let audio_data = resample(&self.audio_data, self.sample_rate, sample_rate);
+        let sample_rate = if sample_rate == 0 { self.sample_rate } else { sample_rate };
+        Self {
+            duration_ms: (audio_data.len() as u64 * 1000 / sample_rate as u64) as u32,
+            audio_data,
+            sample_rate,
+            audio_quality: self.audio_quality,
+        }
+    }
+
+    /// 从`to_wav`格式的WAV数据解析
+    ///
+    /// 只接受44字节标准头的单声道16位PCM，其他格式或头部不一致时返回`InvalidInput`；
+    /// WAV中没有质量信息，解析结果的质量记为`Medium`
+    pub fn from_wav(bytes: &[u8]) -> Result<Self, AIError> {
+        if bytes.len() < WAV_HEADER_LEN
+            || &bytes[0..4] != b"RIFF"
+            || &bytes[8..12] != b"WAVE"
+            || &bytes[12..16] != b"fmt "
+            || &bytes[36..40] != b"data"
+        {
+            return Err(AIError::InvalidInput);
+        }
+
+        let u16_at = |offset: usize| u16::from_le_bytes([bytes[offset], bytes[offset + 1]]);
+        let u32_at = |offset: usize| u32::from_le_bytes([bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]]);
+
+        let sample_rate = u32_at(24);
+        let supported = u32_at(16) == 16
+            && u16_at(20) == WAV_FORMAT_PCM
+            && u16_at(22) == 1
+            && u16_at(34) == 16
+            && u16_at(32) == 2
+            && u32_at(28) == sample_rate.wrapping_mul(2)
+            && sample_rate > 0;
+        if !supported {
+            return Err(AIError::InvalidInput);
+        }
+
+        let data_len = u32_at(40) as usize;
+        let data = bytes.get(WAV_HEADER_LEN..WAV_HEADER_LEN + data_len).ok_or(AIError::InvalidInput)?;
+        if data_len % 2 != 0 {
+            return Err(AIError::InvalidInput);
+        }
+
+        let audio_data: Vec<i16> = data.chunks_exact(2).map(|pair| i16::from_le_bytes([pair[0], pair[1]])).collect();
+        let duration_ms = (audio_data.len() as u64 * 1000 / sample_rate as u64) as u32;
+        Ok(Self {
+            audio_data,
+            sample_rate,
+            duration_ms,
+            audio_quality: AudioQuality::Medium,
+        })
+    }
+}
+
+/// 线性插值重采样
+///
+/// 输出长度为`input.len() * to_hz / from_hz`（向下取整），源位置用整数运算避免长音频累积误差；
+/// 采样率相同或为0时原样返回
+pub fn resample(input: &[i16], from_hz: u32, to_hz: u32) -> Vec<i16> {
+    if from_hz == to_hz || from_hz == 0 || to_hz == 0 || input.is_empty() {
+        return input.to_vec();
+    }
+
+    let (from_hz, to_hz) = (from_hz as u64, to_hz as u64);
+    let output_len = (input.len() as u64 * to_hz / from_hz) as usize;
+    let last = input.len() - 1;
+    (0..output_len as u64)
+        .map(|i| {
+            let position = i * from_hz;
+            let index = (position / to_hz) as usize;
+            let frac = (position % to_hz) as f32 / to_hz as f32;
+            let current = input[index.min(last)] as f32;
+            let next = input[(index + 1).min(last)] as f32;
+            (current + (next - current) * frac).round() as i16
+        })
+        .collect()
+}
+
 /// 音频质量评估
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AudioQuality {
@@ -126,6 +257,7 @@ impl TextToSpeechModel {
                 ..Default::default()
             },
             vocoder_loaded: false,
+            normalization: NormalizationRules::default(),
         }
     }
     
@@ -138,6 +270,7 @@ impl TextToSpeechModel {
             inference_engine: None,
             config,
             vocoder_loaded: false,
+            normalization: NormalizationRules::default(),
         }
     }
     
@@ -180,36 +313,17 @@ impl TextToSpeechModel {
     
     /// 文本规范化
     fn text_normalization(&self, text: &str) -> String {
-        let mut normalized = String::new();
-        
-        for ch in text.chars() {
-            match ch {
-                '0'..='9' => {
-                    // 数字转中文读法（简化）
-                    let digit_str = match ch {
-                        '0' => "零",
-                        '1' => "一",
-                        '2' => "二",
-                        '3' => "三",
-                        '4' => "四",
-                        '5' => "五",
-                        '6' => "六",
-                        '7' => "七",
-                        '8' => "八",
-                        '9' => "九",
-                        _ => "",
-                    };
-                    normalized.push_str(digit_str);
-                }
-                ',' | '，' => normalized.push('，'),
-                '.' | '。' => normalized.push('。'),
-                '!' | '！' => normalized.push('！'),
-                '?' | '？' => normalized.push('？'),
-                _ => normalized.push(ch),
-            }
-        }
-        
-        normalized
+        self.normalization.normalize(text)
+    }
+    
+    /// 设置文本规范化规则（如切换语言）
+    pub fn set_normalization_rules(&mut self, rules: NormalizationRules) {
+        self.normalization = rules;
+    }
+    
+    /// 文本规范化规则，用于覆盖单位或符号读法
+    pub fn normalization_rules_mut(&mut self) -> &mut NormalizationRules {
+        &mut self.normalization
     }
     
     /// 文本分词
@@ -221,22 +335,33 @@ impl TextToSpeechModel {
     }
     
     /// 转换为音素
+    ///
+    /// 汉字拆分为声母和带声调的韵母（如"你"为`n`、`i3`，零声母字只有韵母），
+    /// 英文字母为`ALPHA_X`，拼音表中没有的字符为`UNK`
     fn tokens_to_phonemes(&self, tokens: &[String]) -> Vec<String> {
-        // 简化的音素转换（实际应该使用拼音或专业音素集）
-        tokens.iter()
-            .map(|token| {
-                if token.chars().count() == 1 {
-                    let ch = token.chars().next().unwrap();
-                    if ch.is_ascii_alphabetic() {
-                        format!("ALPHA_{}", ch.to_uppercase())
-                    } else {
-                        format!("CHAR_{:04X}", ch as u32)
-                    }
-                } else {
-                    "UNK".to_string()
+        let mut phonemes = Vec::with_capacity(tokens.len() * 2);
+        for token in tokens {
+            let mut chars = token.chars();
+            let ch = match (chars.next(), chars.next()) {
+                (Some(ch), None) => ch,
+                _ => {
+                    phonemes.push("UNK".to_string());
+                    continue;
                 }
-            })
-            .collect()
+            };
+
+            if ch.is_ascii_alphabetic() {
+                phonemes.push(format!("ALPHA_{}", ch.to_ascii_uppercase()));
+            } else if let Some(pinyin) = hanzi_to_pinyin(ch) {
+                if !pinyin.initial.is_empty() {
+                    phonemes.push(pinyin.initial.to_string());
+                }
+                phonemes.push(format!("{}{}", pinyin.rime, pinyin.tone));
+            } else {
+                phonemes.push("UNK".to_string());
+            }
+        }
+        phonemes
     }
     
     /// 音素转特征
@@ -559,6 +684,7 @@ impl core::fmt::Display for Emotion {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use super::super::text_normalizer::UnitPosition;
     
     #[test]
     fn test_tts_creation() {
@@ -572,4 +698,98 @@ mod tests {
         let normalized = model.text_normalization("Hello 123!");
         assert!(!normalized.is_empty());
     }
+    
+    #[test]
+    fn test_text_normalization_uses_configured_rules() {
+        // 规范化按模型配置的规则展开数字和单位，规则可覆盖
+        let mut model = TextToSpeechModel::new(VoiceType::Female);
+        assert_eq!(model.text_normalization("今天25°C。"), "今天二十五摄氏度。");
+        
+        model.normalization_rules_mut().set_unit("°C", "度", UnitPosition::After);
+        assert_eq!(model.text_normalization("25°C"), "二十五度");
+    }
+    
+    #[test]
+    fn test_pinyin_phonemes_with_tones() {
+        // "你好"拆为两个声母和两个带三声的韵母；英文字母和未收录字符不受影响
+        let model = TextToSpeechModel::new(VoiceType::Female);
+        let phonemes = model.tokens_to_phonemes(&model.text_to_tokens("你好"));
+        assert_eq!(phonemes, vec!["n", "i3", "h", "ao3"]);
+        assert_eq!(phonemes.iter().filter(|p| p.ends_with(|c: char| c.is_ascii_digit())).count(), 2);
+        
+        let mixed = model.tokens_to_phonemes(&model.text_to_tokens("a二龘"));
+        assert_eq!(mixed, vec!["ALPHA_A", "er4", "UNK"]);
+    }
+    
+    #[test]
+    fn test_wav_round_trip() {
+        // 合成结果写成WAV后解析回来，采样数、采样率和采样值一致
+        let mut model = TextToSpeechModel::new(VoiceType::Female);
+        model.load_model(&[]).unwrap();
+        let result = model.synthesize("你好", TTSConfig::default()).unwrap();
+        
+        let wav = result.to_wav().unwrap();
+        assert_eq!(wav.len(), WAV_HEADER_LEN + result.audio_data.len() * 2);
+        let parsed = SynthesisResult::from_wav(&wav).unwrap();
+        assert_eq!(parsed.sample_rate, 22050);
+        assert_eq!(parsed.audio_data, result.audio_data);
+        
+        // 截断的数据和非PCM格式被拒绝
+        assert_eq!(SynthesisResult::from_wav(&wav[..wav.len() - 2]).unwrap_err(), AIError::InvalidInput);
+        let mut float_wav = wav.clone();
+        float_wav[20] = 3;
+        assert_eq!(SynthesisResult::from_wav(&float_wav).unwrap_err(), AIError::InvalidInput);
+    }
+    
+    #[test]
+    fn test_wav_sizes_reject_overflow() {
+        // data块或RIFF块长度超出u32、字节率溢出时返回错误而不是截断
+        assert_eq!(wav_chunk_sizes(100), Ok((200, 236)));
+        let max_samples = (u32::MAX as usize - 36) / 2;
+        assert!(wav_chunk_sizes(max_samples).is_ok());
+        assert_eq!(wav_chunk_sizes(max_samples + 1), Err(AIError::InvalidInput));
+        assert_eq!(wav_chunk_sizes(u32::MAX as usize), Err(AIError::InvalidInput));
+        
+        let result = SynthesisResult {
+            audio_data: vec![0; 4],
+            sample_rate: u32::MAX,
+            duration_ms: 0,
+            audio_quality: AudioQuality::Medium,
+        };
+        assert_eq!(result.to_wav().unwrap_err(), AIError::InvalidInput);
+    }
+    
+    /// 指定采样率下的1kHz正弦波
+    fn tone_1khz(sample_rate: u32, samples: usize) -> Vec<i16> {
+        (0..samples)
+            .map(|i| ((2.0 * core::f32::consts::PI * 1000.0 * i as f32 / sample_rate as f32).sin() * 10000.0) as i16)
+            .collect()
+    }
+    
+    #[test]
+    fn test_resample_22050_to_16000() {
+        // 非整数比降采样：长度按比例缩放（误差不超过1个采样），音调频率不变
+        let result = SynthesisResult {
+            audio_data: tone_1khz(22050, 2205),
+            sample_rate: 22050,
+            duration_ms: 100,
+            audio_quality: AudioQuality::Medium,
+        };
+        let resampled = result.resampled_to(16000);
+        assert_eq!(resampled.sample_rate, 16000);
+        assert!((resampled.audio_data.len() as i64 - 1600).abs() <= 1);
+        assert_eq!(resampled.duration_ms, 100);
+        
+        let expected = tone_1khz(16000, resampled.audio_data.len());
+        let max_error = resampled.audio_data.iter().zip(&expected).map(|(&a, &b)| (a as i32 - b as i32).abs()).max().unwrap();
+        assert!(max_error < 1000, "max_error = {}", max_error);
+    }
+    
+    #[test]
+    fn test_resample_upsample_integer_ratio() {
+        // 整数比升采样在相邻采样间插入中点，末尾不越界
+        assert_eq!(resample(&[0, 100, 200], 8000, 16000), vec![0, 50, 100, 150, 200, 200]);
+        assert_eq!(resample(&[1, 2, 3], 16000, 16000), vec![1, 2, 3]);
+        assert!(resample(&[], 22050, 16000).is_empty());
+    }
 }
\ No newline at end of file
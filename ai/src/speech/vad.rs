@@ -0,0 +1,249 @@
+//! 语音活动检测（VAD）
+//!
+//! 以10ms帧计算能量，噪声基底取最近约1秒帧能量的最小值，
+//! 帧能量超过基底一定信噪比余量时判定为语音，语音结束后保持若干帧以免截断尾音
+
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+use core::f32::consts::LN_10;
+use core::ops::Range;
+
+/// 采样率 (Hz)
+pub const SAMPLE_RATE: usize = 16_000;
+
+/// 每帧采样数（10ms）
+pub const FRAME_SAMPLES: usize = SAMPLE_RATE / 100;
+
+/// 噪声基底跟踪窗口帧数（约1秒）
+pub const NOISE_WINDOW_FRAMES: usize = 100;
+
+/// 默认信噪比余量 (dB)
+pub const DEFAULT_SNR_MARGIN_DB: f32 = 10.0;
+
+/// 信噪比余量上限 (dB)，更大的余量下任何语音都无法触发
+pub const MAX_SNR_MARGIN_DB: f32 = 60.0;
+
+/// 默认拖尾帧数（300ms）
+pub const DEFAULT_HANGOVER_FRAMES: usize = 30;
+
+/// 噪声基底下限，避免数字静音时任何微小噪声都被判为语音
+const MIN_NOISE_ENERGY: f32 = 100.0;
+
+/// 语音边界事件，位置为自检测器创建（或重置）以来的采样序号
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VadEvent {
+    SpeechStart(usize),
+    SpeechEnd(usize),
+}
+
+/// 分贝转换为功率比 10^(db/10)
+fn db_to_power_ratio(db: f32) -> f32 {
+    (db * LN_10 / 10.0).exp()
+}
+
+/// 自适应噪声基底的语音活动检测器
+pub struct VoiceActivityDetector {
+    /// 最近帧的能量，用于跟踪噪声基底
+    energies: VecDeque<f32>,
+    snr_margin_db: f32,
+    snr_ratio: f32,
+    hangover_frames: usize,
+    hangover_left: usize,
+    in_speech: bool,
+    /// 已处理的采样数
+    position: usize,
+}
+
+impl VoiceActivityDetector {
+    /// 使用默认信噪比余量和拖尾帧数创建检测器
+    pub fn new() -> Self {
+        Self {
+            energies: VecDeque::with_capacity(NOISE_WINDOW_FRAMES),
+            snr_margin_db: DEFAULT_SNR_MARGIN_DB,
+            snr_ratio: db_to_power_ratio(DEFAULT_SNR_MARGIN_DB),
+            hangover_frames: DEFAULT_HANGOVER_FRAMES,
+            hangover_left: 0,
+            in_speech: false,
+            position: 0,
+        }
+    }
+
+    /// 设置触发所需的信噪比余量 (dB)，截断到0至`MAX_SNR_MARGIN_DB`之间，NaN按默认余量处理
+    pub fn set_snr_margin_db(&mut self, margin_db: f32) {
+        let margin_db = if margin_db.is_nan() { DEFAULT_SNR_MARGIN_DB } else { margin_db.clamp(0.0, MAX_SNR_MARGIN_DB) };
+        self.snr_margin_db = margin_db;
+        self.snr_ratio = db_to_power_ratio(margin_db);
+    }
+
+    /// 当前信噪比余量 (dB)
+    pub fn snr_margin_db(&self) -> f32 {
+        self.snr_margin_db
+    }
+
+    /// 设置语音结束后保持的帧数
+    pub fn set_hangover_frames(&mut self, frames: usize) {
+        self.hangover_frames = frames;
+    }
+
+    /// 丢弃噪声基底估计和语音状态（环境变化后调用）
+    pub fn reset_noise_floor(&mut self) {
+        self.energies.clear();
+        self.in_speech = false;
+        self.hangover_left = 0;
+    }
+
+    /// 当前噪声基底（帧均方能量），尚无估计时为None
+    pub fn noise_floor(&self) -> Option<f32> {
+        self.energies
+            .iter()
+            .copied()
+            .reduce(f32::min)
+            .map(|floor| floor.max(MIN_NOISE_ENERGY))
+    }
+
+    /// 是否处于语音段（含拖尾）
+    pub fn is_speech(&self) -> bool {
+        self.in_speech
+    }
+
+    /// 处理一帧音频，返回该帧产生的语音边界
+    pub fn process_frame(&mut self, frame: &[i16]) -> Option<VadEvent> {
+        if frame.is_empty() {
+            return None;
+        }
+
+        let energy = frame.iter().map(|&s| (s as f32) * (s as f32)).sum::<f32>() / frame.len() as f32;
+        let active = self.noise_floor().is_some_and(|floor| energy > floor * self.snr_ratio);
+
+        if self.energies.len() == NOISE_WINDOW_FRAMES {
+            self.energies.pop_front();
+        }
+        self.energies.push_back(energy);
+
+        let start = self.position;
+        self.position += frame.len();
+
+        if active {
+            self.hangover_left = self.hangover_frames;
+            if !self.in_speech {
+                self.in_speech = true;
+                return Some(VadEvent::SpeechStart(start));
+            }
+        } else if self.in_speech {
+            if self.hangover_left == 0 {
+                self.in_speech = false;
+                return Some(VadEvent::SpeechEnd(start));
+            }
+            self.hangover_left -= 1;
+        }
+        None
+    }
+
+    /// 按帧处理一段音频，返回其中的语音边界
+    pub fn process(&mut self, audio: &[i16]) -> Vec<VadEvent> {
+        audio.chunks(FRAME_SAMPLES).filter_map(|frame| self.process_frame(frame)).collect()
+    }
+
+    /// 处理一段音频并返回其中第一个语音段在该段内的范围
+    ///
+    /// 进入时已处于语音段则从0开始；到末尾仍未结束则截止到末尾
+    pub fn detect_segment(&mut self, audio: &[i16]) -> Option<Range<usize>> {
+        let base = self.position;
+        let mut start = self.in_speech.then_some(0);
+        for event in self.process(audio) {
+            match event {
+                VadEvent::SpeechStart(at) if start.is_none() => start = Some(at - base),
+                VadEvent::SpeechEnd(at) => {
+                    if let Some(start) = start {
+                        return Some(start..at - base);
+                    }
+                }
+                _ => {}
+            }
+        }
+        start.map(|start| start..audio.len())
+    }
+}
+
+impl Default for VoiceActivityDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 幅度约±`amplitude`的伪随机噪声
+    fn noise(samples: usize, amplitude: i32, seed: &mut u32) -> Vec<i16> {
+        (0..samples)
+            .map(|_| {
+                *seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                ((*seed >> 16) as i32 % (2 * amplitude + 1) - amplitude) as i16
+            })
+            .collect()
+    }
+
+    /// 叠加在噪声上的方波音调（约444Hz）
+    fn noisy_tone(samples: usize, amplitude: i16, seed: &mut u32) -> Vec<i16> {
+        noise(samples, 300, seed)
+            .into_iter()
+            .enumerate()
+            .map(|(i, n)| n.saturating_add(if (i / 18) % 2 == 0 { amplitude } else { -amplitude }))
+            .collect()
+    }
+
+    #[test]
+    fn test_tone_in_noise_triggers_with_hangover() {
+        // 1秒噪声后0.5秒音调：音调开始处触发，音调结束后经拖尾帧才结束
+        let mut seed = 7;
+        let mut audio = noise(SAMPLE_RATE, 300, &mut seed);
+        audio.extend(noisy_tone(SAMPLE_RATE / 2, 4000, &mut seed));
+        audio.extend(noise(SAMPLE_RATE, 300, &mut seed));
+
+        let mut vad = VoiceActivityDetector::new();
+        let events = vad.process(&audio);
+        let tone_end = SAMPLE_RATE * 3 / 2;
+        let hangover_end = tone_end + (DEFAULT_HANGOVER_FRAMES + 1) * FRAME_SAMPLES;
+        assert_eq!(events, vec![VadEvent::SpeechStart(SAMPLE_RATE), VadEvent::SpeechEnd(hangover_end - FRAME_SAMPLES)]);
+
+        // 语音段范围覆盖音调和拖尾
+        let mut vad = VoiceActivityDetector::new();
+        assert_eq!(vad.detect_segment(&audio), Some(SAMPLE_RATE..hangover_end - FRAME_SAMPLES));
+    }
+
+    #[test]
+    fn test_pure_noise_never_triggers() {
+        // 持续噪声（含幅度变化在余量内的段）不触发；提高余量后弱音调不再触发
+        let mut seed = 11;
+        let mut audio = noise(SAMPLE_RATE, 300, &mut seed);
+        audio.extend(noise(SAMPLE_RATE, 500, &mut seed));
+        let mut vad = VoiceActivityDetector::new();
+        assert!(vad.process(&audio).is_empty());
+        assert!(!vad.is_speech());
+
+        let weak_tone = noisy_tone(SAMPLE_RATE / 4, 1000, &mut seed);
+        vad.reset_noise_floor();
+        vad.set_snr_margin_db(20.0);
+        vad.process(&noise(SAMPLE_RATE / 2, 300, &mut seed));
+        assert!(vad.process(&weak_tone).is_empty());
+        vad.set_snr_margin_db(6.0);
+        assert!(matches!(vad.process(&weak_tone)[..], [VadEvent::SpeechStart(_), ..]));
+    }
+
+    #[test]
+    fn test_snr_margin_clamped() {
+        // 余量截断到有效范围，功率比与10^(dB/10)一致
+        let mut vad = VoiceActivityDetector::new();
+        vad.set_snr_margin_db(1e9);
+        assert_eq!(vad.snr_margin_db(), MAX_SNR_MARGIN_DB);
+        vad.set_snr_margin_db(-5.0);
+        assert_eq!(vad.snr_margin_db(), 0.0);
+        vad.set_snr_margin_db(f32::NAN);
+        assert_eq!(vad.snr_margin_db(), DEFAULT_SNR_MARGIN_DB);
+
+        assert!((db_to_power_ratio(10.0) - 10.0).abs() < 1e-4);
+        assert!((db_to_power_ratio(-20.0) - 0.01).abs() < 1e-7);
+    }
+}
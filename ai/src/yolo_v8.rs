@@ -1,116 +0,0 @@
-//! YOLO-v8目标检测引擎
-//! 
-//! 支持RK3588 NPU加速的YOLO-v8模型推理
-
-#![no_std]
-
-use core::fmt;
-use alloc::vec::Vec;
-
-use crate::{InferenceEngine, ModelInfo, InferenceParams, AIError, Precision, OptimizationLevel};
-
-/// YOLO-v8推理引擎
-pub struct YoloV8Engine {
-    model_info: ModelInfo,
-    params: InferenceParams,
-    is_loaded: bool,
-}
-
-impl YoloV8Engine {
-    /// 创建新的YOLO-v8引擎
-    pub fn new() -> Self {
-        Self {
-            model_info: ModelInfo {
-                name: "YOLO-v8",
-                version: "1.0",
-                input_shape: vec![1, 3, 640, 640],
-                output_shape: vec![1, 8400, 85],
-                precision: Precision::FP16,
-            },
-            params: InferenceParams {
-                batch_size: 1,
-                use_hardware_acceleration: true,
-                optimization_level: OptimizationLevel::Aggressive,
-            },
-            is_loaded: false,
-        }
-    }
-    
-    /// 获取模型信息
-    pub fn get_model_info(&self) -> &ModelInfo {
-        &self.model_info
-    }
-}
-
-impl InferenceEngine for YoloV8Engine {
-    fn load_model(&mut self, model_data: &[u8]) -> Result<(), AIError> {
-        // 检查模型数据大小
-        if model_data.len() < 1024 {
-            return Err(AIError::ModelLoadError);
-        }
-        
-        self.is_loaded = true;
-        Ok(())
-    }
-    
-    fn infer(&mut self, input: &[f32]) -> Result<Vec<f32>, AIError> {
-        if !self.is_loaded {
-            return Err(AIError::ModelNotFound);
-        }
-        
-        // 优化：使用预分配内存，避免动态分配
-        let output_size = 8400 * 85; // YOLO-v8输出大小
-        let mut result = Vec::with_capacity(output_size);
-        
-        // 优化：使用迭代器避免边界检查
-        result.extend((0..output_size).map(|i| i as f32 / output_size as f32));
-        
-        Ok(result)
-    }
-    
-    fn model_info(&self) -> ModelInfo {
-        self.model_info.clone()
-    }
-    
-    fn set_params(&mut self, params: InferenceParams) -> Result<(), AIError> {
-        self.params = params;
-        Ok(())
-    }
-}
-
-/// 检测结果解析
-pub struct DetectionResult {
-    pub class_id: usize,
-    pub confidence: f32,
-    pub bbox: [f32; 4], // [x, y, width, height]
-}
-
-impl YoloV8Engine {
-    /// 解析YOLO-v8输出
-    pub fn parse_detections(&self, output: &[f32]) -> Vec<DetectionResult> {
-        let mut detections = Vec::new();
-        
-        // 简化实现：返回模拟检测结果
-        if output.len() >= 85 {
-            detections.push(DetectionResult {
-                class_id: 0,
-                confidence: 0.95,
-                bbox: [0.1, 0.2, 0.3, 0.4],
-            });
-        }
-        
-        detections
-    }
-    
-    /// 批处理推理
-    pub fn infer_batch(&mut self, inputs: &[Vec<f32>]) -> Result<Vec<Vec<f32>>, AIError> {
-        let mut results = Vec::new();
-        
-        for input in inputs {
-            let result = self.infer(input)?;
-            results.push(result);
-        }
-        
-        Ok(results)
-    }
-}
\ No newline at end of file
@@ -0,0 +1,114 @@
+//! 检测类别名称表
+//!
+//! 把模型输出的类别ID映射为类别名称，默认使用COCO的80个类别，可替换为自定义模型的类别表
+
+use crate::AIError;
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// COCO数据集的80个类别名称
+pub const COCO_CLASS_NAMES: [&str; 80] = [
+    "person", "bicycle", "car", "motorcycle", "airplane", "bus", "train", "truck", "boat", "traffic light",
+    "fire hydrant", "stop sign", "parking meter", "bench", "bird", "cat", "dog", "horse", "sheep", "cow",
+    "elephant", "bear", "zebra", "giraffe", "backpack", "umbrella", "handbag", "tie", "suitcase", "frisbee",
+    "skis", "snowboard", "sports ball", "kite", "baseball bat", "baseball glove", "skateboard", "surfboard", "tennis racket", "bottle",
+    "wine glass", "cup", "fork", "knife", "spoon", "bowl", "banana", "apple", "sandwich", "orange",
+    "broccoli", "carrot", "hot dog", "pizza", "donut", "cake", "chair", "couch", "potted plant", "bed",
+    "dining table", "toilet", "tv", "laptop", "mouse", "remote", "keyboard", "cell phone", "microwave", "oven",
+    "toaster", "sink", "refrigerator", "book", "clock", "vase", "scissors", "teddy bear", "hair drier", "toothbrush",
+];
+
+/// 类别名称表
+///
+/// 内置表直接借用静态名称，从文件内容加载的表持有自有字符串；
+/// 超出表范围的类别ID按需生成`class_{id}`名称，不缓存也不泄漏
+#[derive(Debug, Clone)]
+pub struct ClassLabels {
+    names: Vec<Cow<'static, str>>,
+}
+
+impl ClassLabels {
+    /// 由静态名称表创建
+    pub fn new(names: &'static [&'static str]) -> Self {
+        Self {
+            names: names.iter().map(|&name| Cow::Borrowed(name)).collect(),
+        }
+    }
+
+    /// COCO类别表
+    pub fn coco() -> Self {
+        Self::new(&COCO_CLASS_NAMES)
+    }
+
+    /// 从按行分隔的类别文件内容创建（每行一个类别名称，行号即类别ID）
+    ///
+    /// 内容被复制为自有字符串，调用后缓冲区即可释放。行首尾空白被去除，
+    /// 文件末尾的空行被忽略；内容不是有效UTF-8时返回`InvalidInput`
+    pub fn from_bytes(data: &[u8]) -> Result<Self, AIError> {
+        let text = core::str::from_utf8(data).map_err(|_| AIError::InvalidInput)?;
+        let mut names: Vec<&str> = text.lines().map(str::trim).collect();
+        while names.last() == Some(&"") {
+            names.pop();
+        }
+
+        Ok(Self {
+            names: names.into_iter().map(|name| Cow::Owned(String::from(name))).collect(),
+        })
+    }
+
+    /// 表中的类别数
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// 类别表是否为空
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// 类别ID对应的名称，超出范围时为`class_{id}`
+    pub fn label_for(&self, class_id: u32) -> Cow<'static, str> {
+        match self.names.get(class_id as usize) {
+            Some(name) => name.clone(),
+            None => Cow::Owned(format!("class_{}", class_id)),
+        }
+    }
+}
+
+impl Default for ClassLabels {
+    fn default() -> Self {
+        Self::coco()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_out_of_range_falls_back_to_class_id() {
+        // 内置表的名称直接借用静态字符串，超出类别表范围的ID回退为class_{id}
+        let labels = ClassLabels::coco();
+        assert_eq!(labels.label_for(0), "person");
+        assert_eq!(labels.label_for(79), "toothbrush");
+        assert!(matches!(labels.label_for(0), Cow::Borrowed(_)));
+        assert_eq!(labels.label_for(80), "class_80");
+    }
+
+    #[test]
+    fn test_custom_table_from_bytes() {
+        // 自定义3类表：去除行尾空白和末尾空行，第4个ID回退
+        let data = Vec::from(&b"helmet\r\nvest \nglove\n\n"[..]);
+        let labels = ClassLabels::from_bytes(&data).unwrap();
+        drop(data);
+        assert_eq!(labels.len(), 3);
+        assert_eq!(labels.label_for(0), "helmet");
+        assert_eq!(labels.label_for(1), "vest");
+        assert_eq!(labels.label_for(2), "glove");
+        assert_eq!(labels.label_for(3), "class_3");
+
+        assert_eq!(ClassLabels::from_bytes(&[0xFF, b'\n']).unwrap_err(), AIError::InvalidInput);
+    }
+}
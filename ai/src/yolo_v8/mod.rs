@@ -2,17 +2,33 @@
 //! 
 //! 提供Yolo-v8模型的加载、推理和后处理功能
 
-mod model;
+mod labels;
 mod postprocess;
 mod preprocess;
 
+use crate::preprocess::{Preprocessor, TensorLayout};
+pub use crate::preprocess::{unletterbox_bbox, LetterboxParams};
+pub use labels::{ClassLabels, COCO_CLASS_NAMES};
+pub use postprocess::{NmsMode, PostprocessConfig};
 use crate::{InferenceEngine, ModelInfo, InferenceParams, AIError, Detection, BoundingBox};
+use alloc::borrow::Cow;
 use alloc::vec::Vec;
+use core::any::Any;
+
+/// 信箱缩放填充灰度值（归一化后为114/255）
+const LETTERBOX_PAD: u8 = 114;
+
+/// 检测头下采样步长
+const STRIDES: [usize; 3] = [8, 16, 32];
 
 /// Yolo-v8推理引擎
 pub struct YoloV8Engine {
     model_info: ModelInfo,
     is_loaded: bool,
+    dynamic_shape: bool,
+    preprocessor: Option<Preprocessor>,
+    labels: ClassLabels,
+    postprocess_config: PostprocessConfig,
 }
 
 impl YoloV8Engine {
@@ -25,19 +41,127 @@ impl YoloV8Engine {
                 input_shape: vec![1, 3, 640, 640], // batch, channels, height, width
                 output_shape: vec![1, 84, 8400],   // batch, classes+4, detections
                 precision: crate::Precision::FP32,
+                quant: None,
             },
             is_loaded: false,
+            dynamic_shape: true,
+            preprocessor: None,
+            labels: ClassLabels::coco(),
+            postprocess_config: PostprocessConfig::default(),
         }
     }
     
-    /// 预处理图像
+    /// 设置模型是否支持动态输入尺寸（静态导出的模型应设为false）
+    pub fn set_dynamic_shape(&mut self, supported: bool) {
+        self.dynamic_shape = supported;
+    }
+    
+    /// 设置输入尺寸
+    /// 
+    /// 宽高须为最大步长(32)的整数倍，输出锚点数随之重新计算
+    pub fn set_input_size(&mut self, width: u32, height: u32) -> Result<(), AIError> {
+        let (width, height) = (width as usize, height as usize);
+        if width == self.model_info.input_shape[3] && height == self.model_info.input_shape[2] {
+            return Ok(());
+        }
+        
+        if !self.dynamic_shape {
+            return Err(AIError::ModelFormatError);
+        }
+        
+        let max_stride = STRIDES[STRIDES.len() - 1];
+        if width == 0 || height == 0 || width % max_stride != 0 || height % max_stride != 0 {
+            return Err(AIError::InvalidInput);
+        }
+        
+        self.model_info.input_shape[2] = height;
+        self.model_info.input_shape[3] = width;
+        self.model_info.output_shape[2] = Self::anchor_count(width, height);
+        Ok(())
+    }
+    
+    /// 计算给定输入尺寸下的锚点数量（各检测头网格数之和）
+    pub fn anchor_count(width: usize, height: usize) -> usize {
+        STRIDES.iter().map(|s| (width / s) * (height / s)).sum()
+    }
+    
+    /// 设置自定义预处理流水线（None恢复默认的CHW归一化）
+    pub fn set_preprocessor(&mut self, preprocessor: Option<Preprocessor>) {
+        self.preprocessor = preprocessor;
+    }
+    
+    /// 设置类别名称表（默认为COCO类别）
+    pub fn set_labels(&mut self, labels: ClassLabels) {
+        self.labels = labels;
+    }
+    
+    /// 类别ID对应的名称，超出类别表范围时为`class_{id}`
+    pub fn label_for(&self, class_id: u32) -> Cow<'static, str> {
+        self.labels.label_for(class_id)
+    }
+    
+    /// 设置后处理配置（置信度阈值、NMS的IoU阈值、最大检测数）
+    pub fn set_postprocess_config(&mut self, config: PostprocessConfig) {
+        self.postprocess_config = config;
+    }
+    
+    /// 当前后处理配置
+    pub fn postprocess_config(&self) -> &PostprocessConfig {
+        &self.postprocess_config
+    }
+    
+    /// 预处理图像（尺寸需与模型输入一致）
     pub fn preprocess_image(&self, image_data: &[u8]) -> Result<Vec<f32>, AIError> {
-        preprocess::preprocess(image_data, self.model_info.input_shape[2], self.model_info.input_shape[3])
+        self.preprocess_frame(image_data, self.model_info.input_shape[3], self.model_info.input_shape[2])
+    }
+    
+    /// 预处理任意尺寸的RGB图像
+    /// 
+    /// 设置了预处理流水线时由流水线负责缩放，输出长度须与模型输入一致
+    pub fn preprocess_frame(&self, image_data: &[u8], width: usize, height: usize) -> Result<Vec<f32>, AIError> {
+        let (input_h, input_w) = (self.model_info.input_shape[2], self.model_info.input_shape[3]);
+        match &self.preprocessor {
+            Some(preprocessor) => {
+                let tensor = preprocessor.run(image_data, width, height)?;
+                if tensor.len() != 3 * input_h * input_w {
+                    return Err(AIError::InvalidInput);
+                }
+                Ok(tensor)
+            }
+            None if width == input_w && height == input_h => preprocess::preprocess(image_data, height, width),
+            None => Err(AIError::InvalidInput),
+        }
+    }
+    
+    /// 信箱预处理任意尺寸的RGB图像
+    /// 
+    /// 按较小比例保持宽高比缩放到模型输入尺寸，空白区域以灰色填充，输出CHW排列、归一化到[0,1]的张量，
+    /// 同时返回用于`unletterbox_bbox`把检测框映射回原图的参数
+    pub fn preprocess_letterbox(&self, image_data: &[u8], src_w: usize, src_h: usize) -> Result<(Vec<f32>, LetterboxParams), AIError> {
+        let (input_h, input_w) = (self.model_info.input_shape[2], self.model_info.input_shape[3]);
+        let pipeline = Preprocessor::builder()
+            .letterbox(input_w, input_h, LETTERBOX_PAD)
+            .normalize([0.0; 3], [1.0; 3])
+            .layout(TensorLayout::Chw)
+            .build()?;
+        let tensor = pipeline.run(image_data, src_w, src_h)?;
+        Ok((tensor, LetterboxParams::compute(src_w, src_h, input_w, input_h)))
     }
     
     /// 后处理检测结果
+    /// 
+    /// 解码候选框、按置信度阈值过滤并做非极大值抑制，返回去重后的检测
     pub fn postprocess_detections(&self, output: &[f32]) -> Result<Vec<Detection>, AIError> {
-        postprocess::postprocess(output, self.model_info.output_shape.clone())
+        let input_size = (self.model_info.input_shape[3], self.model_info.input_shape[2]);
+        let mut detections = postprocess::postprocess(
+            output,
+            &self.model_info.output_shape,
+            input_size,
+            &self.labels,
+            &self.postprocess_config,
+        )?;
+        common::sanitize_detections(&mut detections);
+        Ok(detections)
     }
 }
 
@@ -98,9 +222,164 @@ impl InferenceEngine for YoloV8Engine {
         
         Ok(())
     }
+    
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
 }
 
 /// 创建Yolo-v8引擎实例
 pub fn create_yolo_v8_engine() -> YoloV8Engine {
     YoloV8Engine::new()
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_input_size_320() {
+        // 320x320输入对应2100个锚点
+        let mut engine = YoloV8Engine::new();
+        assert_eq!(YoloV8Engine::anchor_count(640, 640), 8400);
+
+        engine.set_input_size(320, 320).unwrap();
+        let info = engine.model_info();
+        assert_eq!(info.input_shape, vec![1, 3, 320, 320]);
+        assert_eq!(info.output_shape, vec![1, 84, 2100]);
+
+        // 预处理按新尺寸校验输入
+        assert_eq!(engine.preprocess_image(&[0u8; 320 * 320 * 3]).unwrap().len(), 3 * 320 * 320);
+        assert!(engine.preprocess_image(&[0u8; 640 * 640 * 3]).is_err());
+
+        // 匹配尺寸的推理成功
+        engine.load_model(&[1u8]).unwrap();
+        let output = engine.infer(&vec![0.0f32; 3 * 320 * 320]).unwrap();
+        assert_eq!(output.len(), 84 * 2100);
+        assert!(engine.infer(&vec![0.0f32; 3 * 640 * 640]).is_err());
+    }
+
+    #[test]
+    fn test_set_input_size_rejected() {
+        // 静态模型或非32倍数尺寸被拒绝
+        let mut engine = YoloV8Engine::new();
+        assert_eq!(engine.set_input_size(330, 320), Err(AIError::InvalidInput));
+
+        engine.set_dynamic_shape(false);
+        assert_eq!(engine.set_input_size(416, 416), Err(AIError::ModelFormatError));
+        assert_eq!(engine.model_info().input_shape, vec![1, 3, 640, 640]);
+    }
+
+    #[test]
+    fn test_preprocessor_pipeline_resizes_frame() {
+        // 设置流水线后任意尺寸的图像被缩放到模型输入尺寸
+        let mut engine = YoloV8Engine::new();
+        engine.set_input_size(32, 32).unwrap();
+        assert!(engine.preprocess_frame(&[0u8; 64 * 48 * 3], 64, 48).is_err());
+
+        let pipeline = Preprocessor::builder()
+            .letterbox(32, 32, 114)
+            .normalize([0.0; 3], [1.0; 3])
+            .layout(TensorLayout::Chw)
+            .build()
+            .unwrap();
+        engine.set_preprocessor(Some(pipeline));
+        assert_eq!(engine.preprocess_frame(&[0u8; 64 * 48 * 3], 64, 48).unwrap().len(), 3 * 32 * 32);
+    }
+
+    #[test]
+    fn test_letterbox_maps_center_back_to_original() {
+        // 1280x720缩放为640x360并上下各填充140行，检测中心映射回原图像素
+        let engine = YoloV8Engine::new();
+        let image = vec![200u8; 1280 * 720 * 3];
+        let (tensor, params) = engine.preprocess_letterbox(&image, 1280, 720).unwrap();
+        assert_eq!(tensor.len(), 3 * 640 * 640);
+        assert_eq!((params.scale, params.pad_x, params.pad_y), (0.5, 0, 140));
+        assert!((tensor[0] - 114.0 / 255.0).abs() < 1e-6);
+        assert!((tensor[320 * 640 + 320] - 200.0 / 255.0).abs() < 1e-6);
+
+        // 原图(200, 100)处20x40的目标在模型输入中位于(100, 190)，尺寸10x20
+        let detected = BoundingBox::new(100.0 / 640.0, 190.0 / 640.0, 10.0 / 640.0, 20.0 / 640.0);
+        let mapped = unletterbox_bbox(detected, &params);
+        assert!((mapped.x - 200.0).abs() < 1e-3 && (mapped.y - 100.0).abs() < 1e-3);
+        assert!((mapped.width - 20.0).abs() < 1e-3 && (mapped.height - 40.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_letterbox_upscales_small_image() {
+        // 小于目标尺寸的图像按较小比例放大，左右填充
+        let params = LetterboxParams::compute(160, 320, 640, 640);
+        assert_eq!((params.scale, params.pad_x, params.pad_y), (2.0, 160, 0));
+        let mapped = unletterbox_bbox(BoundingBox::new(0.5, 0.5, 0.5, 1.0), &params);
+        assert_eq!((mapped.x, mapped.y, mapped.width, mapped.height), (80.0, 160.0, 160.0, 320.0));
+    }
+
+    #[test]
+    fn test_postprocess_suppresses_overlapping_boxes() {
+        // 同类别的两个高置信度重叠框经NMS后只保留置信度较高的一个
+        let mut engine = YoloV8Engine::new();
+        engine.set_input_size(32, 32).unwrap();
+        let anchors = YoloV8Engine::anchor_count(32, 32);
+        let mut output = vec![0.0f32; 84 * anchors];
+        for (anchor, (cx, score)) in [(16.0, 0.9), (17.0, 0.8)].into_iter().enumerate() {
+            for (channel, value) in [cx, 16.0, 10.0, 10.0].into_iter().enumerate() {
+                output[channel * anchors + anchor] = value;
+            }
+            output[(4 + 2) * anchors + anchor] = score;
+        }
+
+        let detections = engine.postprocess_detections(&output).unwrap();
+        assert_eq!(detections.len(), 1);
+        assert_eq!((detections[0].class_id, detections[0].class_name.as_ref(), detections[0].confidence), (2, "car", 0.9));
+
+        // 提高IoU阈值后两个框都保留
+        engine.set_postprocess_config(PostprocessConfig { iou_threshold: 0.95, ..PostprocessConfig::default() });
+        assert_eq!(engine.postprocess_detections(&output).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_postprocess_sanitizes_detections() {
+        // 超出画面的框被裁剪到画面内，坐标为NaN的框被丢弃
+        let mut engine = YoloV8Engine::new();
+        engine.set_input_size(32, 32).unwrap();
+        let anchors = YoloV8Engine::anchor_count(32, 32);
+        let output_with = |bbox: [f32; 4]| {
+            let mut output = vec![0.0f32; 84 * anchors];
+            for (channel, value) in bbox.into_iter().enumerate() {
+                output[channel * anchors] = value;
+            }
+            output[4 * anchors] = 0.9;
+            output
+        };
+
+        let clipped = engine.postprocess_detections(&output_with([30.0, 16.0, 10.0, 10.0])).unwrap();
+        assert_eq!(clipped.len(), 1);
+        let bbox = clipped[0].bbox;
+        assert!((bbox.x + bbox.width / 2.0 - 1.0).abs() < 1e-6);
+        assert!((bbox.width - 7.0 / 32.0).abs() < 1e-6);
+
+        assert!(engine.postprocess_detections(&output_with([16.0, 16.0, f32::NAN, 10.0])).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_postprocess_per_class_nms_mode() {
+        // 重叠的不同类别框：默认Global模式只保留一个，PerClass模式下都保留
+        let mut engine = YoloV8Engine::new();
+        engine.set_input_size(32, 32).unwrap();
+        let anchors = YoloV8Engine::anchor_count(32, 32);
+        let mut output = vec![0.0f32; 84 * anchors];
+        for (anchor, (class_id, score)) in [(0, 0.9), (16, 0.8)].into_iter().enumerate() {
+            for (channel, value) in [16.0, 16.0, 10.0, 10.0].into_iter().enumerate() {
+                output[channel * anchors + anchor] = value;
+            }
+            output[(4 + class_id) * anchors + anchor] = score;
+        }
+
+        assert_eq!(engine.postprocess_detections(&output).unwrap().len(), 1);
+
+        engine.set_postprocess_config(PostprocessConfig { nms_mode: NmsMode::PerClass, ..PostprocessConfig::default() });
+        let detections = engine.postprocess_detections(&output).unwrap();
+        let classes: Vec<(u32, &str)> = detections.iter().map(|d| (d.class_id, d.class_name.as_ref())).collect();
+        assert_eq!(classes, vec![(0, "person"), (16, "dog")]);
+    }
+}
@@ -437,7 +437,13 @@ impl YoloV8Optimizer {
     /// 应用非极大值抑制
     fn apply_nms(&self, detections: &mut Vec<Detection>) {
         // 简单的NMS实现
-        detections.sort_by(|a, b| b.confidence.partial_cmp(&a.confidence).unwrap());
+        // 按置信度降序稳定排序，相同时依次按类别ID、框位置(y, x)排序以保证输出可复现
+        detections.sort_by(|a, b| {
+            b.confidence.total_cmp(&a.confidence)
+                .then_with(|| a.class_id.cmp(&b.class_id))
+                .then_with(|| a.bbox.y.total_cmp(&b.bbox.y))
+                .then_with(|| a.bbox.x.total_cmp(&b.bbox.x))
+        });
         
         let mut i = 0;
         while i < detections.len() {
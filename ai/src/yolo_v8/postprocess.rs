@@ -0,0 +1,153 @@
+//! Yolo-v8输出后处理
+//!
+//! 把`[batch, 4 + classes, anchors]`排列的模型输出解码为候选框，按置信度过滤后做非极大值抑制
+
+use super::ClassLabels;
+use crate::{AIError, BoundingBox, Detection};
+use alloc::vec::Vec;
+use common::{non_max_suppression, per_class_nms};
+
+/// 非极大值抑制模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NmsMode {
+    /// 不区分类别，所有候选框之间互相抑制（兼容旧行为）
+    #[default]
+    Global,
+    /// 只在相同类别的候选框之间抑制
+    PerClass,
+}
+
+/// 后处理配置
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PostprocessConfig {
+    /// 类别置信度阈值，低于阈值的候选框被丢弃
+    pub confidence_threshold: f32,
+    /// NMS的IoU阈值，与已保留框的IoU超过阈值的框被抑制
+    pub iou_threshold: f32,
+    /// 最多返回的检测数
+    pub max_detections: usize,
+    /// 非极大值抑制模式
+    pub nms_mode: NmsMode,
+}
+
+impl Default for PostprocessConfig {
+    fn default() -> Self {
+        Self {
+            confidence_threshold: 0.25,
+            iou_threshold: 0.45,
+            max_detections: 300,
+            nms_mode: NmsMode::Global,
+        }
+    }
+}
+
+/// 解码模型输出并做非极大值抑制
+///
+/// 输出中的框为模型输入像素坐标（中心点和宽高），转换为相对输入尺寸的归一化坐标；
+/// 批大小大于1时只解码第一张图像。返回的检测按置信度降序排列
+pub fn postprocess(
+    output: &[f32],
+    output_shape: &[usize],
+    input_size: (usize, usize),
+    labels: &ClassLabels,
+    config: &PostprocessConfig,
+) -> Result<Vec<Detection>, AIError> {
+    let [_, channels, anchors] = *output_shape else {
+        return Err(AIError::InvalidInput);
+    };
+    if channels <= 4 || output.len() != output_shape.iter().product::<usize>() {
+        return Err(AIError::InvalidInput);
+    }
+
+    let (input_w, input_h) = (input_size.0 as f32, input_size.1 as f32);
+    let at = |channel: usize, anchor: usize| output[channel * anchors + anchor];
+
+    let mut boxes = Vec::new();
+    let mut scores = Vec::new();
+    let mut classes = Vec::new();
+    for anchor in 0..anchors {
+        let (class_id, score) = (4..channels)
+            .map(|channel| (channel - 4, at(channel, anchor)))
+            .fold((0, f32::NEG_INFINITY), |best, candidate| if candidate.1 > best.1 { candidate } else { best });
+        if score.is_nan() || score < config.confidence_threshold {
+            continue;
+        }
+
+        boxes.push(BoundingBox::new(
+            at(0, anchor) / input_w,
+            at(1, anchor) / input_h,
+            at(2, anchor) / input_w,
+            at(3, anchor) / input_h,
+        ));
+        scores.push(score);
+        classes.push(class_id as u32);
+    }
+
+    let kept = match config.nms_mode {
+        NmsMode::Global => non_max_suppression(&boxes, &scores, config.iou_threshold),
+        NmsMode::PerClass => {
+            // 各类别的保留框合并后按置信度降序排列
+            let mut kept = per_class_nms(&boxes, &scores, &classes, config.iou_threshold);
+            kept.sort_by(|&a, &b| scores[b].total_cmp(&scores[a]).then_with(|| boxes[a].position_cmp(&boxes[b])));
+            kept
+        }
+    };
+
+    let detections = kept
+        .into_iter()
+        .take(config.max_detections)
+        .map(|i| Detection::new(classes[i], labels.label_for(classes[i]), scores[i], boxes[i]))
+        .collect();
+    Ok(detections)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_picks_best_class_above_threshold() {
+        // 每个锚点取得分最高的类别，低于置信度阈值的锚点被丢弃，坐标按输入尺寸归一化
+        let shape = [1, 7, 3];
+        #[rustfmt::skip]
+        let output = [
+            32.0, 64.0, 96.0,   // cx
+            32.0, 32.0, 32.0,   // cy
+            16.0, 16.0, 16.0,   // w
+            8.0,  8.0,  8.0,    // h
+            0.1,  0.9,  0.2,    // 类别0
+            0.6,  0.3,  0.1,    // 类别1
+            0.2,  0.1,  0.2,    // 类别2
+        ];
+        let detections = postprocess(&output, &shape, (128, 64), &ClassLabels::coco(), &PostprocessConfig::default()).unwrap();
+
+        let decoded: Vec<(u32, f32, f32)> = detections.iter().map(|d| (d.class_id, d.confidence, d.bbox.x)).collect();
+        assert_eq!(decoded, vec![(0, 0.9, 0.5), (1, 0.6, 0.25)]);
+        assert_eq!(detections[0].class_name, "person");
+        assert_eq!((detections[0].bbox.y, detections[0].bbox.width, detections[0].bbox.height), (0.5, 0.125, 0.125));
+
+        assert_eq!(postprocess(&output[..20], &shape, (128, 64), &ClassLabels::coco(), &PostprocessConfig::default()).unwrap_err(), AIError::InvalidInput);
+    }
+
+    #[test]
+    fn test_per_class_nms_keeps_overlapping_classes() {
+        // 重叠的人和狗：PerClass模式下都保留，Global模式下置信度较低的狗被抑制
+        let shape = [1, 4 + 17, 2];
+        let mut output = [0.0f32; 21 * 2];
+        for (anchor, (cx, class_id, score)) in [(50.0, 0, 0.9), (52.0, 16, 0.8)].into_iter().enumerate() {
+            for (channel, value) in [cx, 50.0, 20.0, 40.0].into_iter().enumerate() {
+                output[channel * 2 + anchor] = value;
+            }
+            output[(4 + class_id) * 2 + anchor] = score;
+        }
+
+        let global = postprocess(&output, &shape, (100, 100), &ClassLabels::coco(), &PostprocessConfig::default()).unwrap();
+        let names: Vec<&str> = global.iter().map(|d| d.class_name.as_ref()).collect();
+        assert_eq!(names, vec!["person"]);
+
+        let config = PostprocessConfig { nms_mode: NmsMode::PerClass, ..PostprocessConfig::default() };
+        let per_class = postprocess(&output, &shape, (100, 100), &ClassLabels::coco(), &config).unwrap();
+        let names: Vec<&str> = per_class.iter().map(|d| d.class_name.as_ref()).collect();
+        assert_eq!(names, vec!["person", "dog"]);
+    }
+}
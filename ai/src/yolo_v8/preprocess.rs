@@ -0,0 +1,23 @@
+//! Yolo-v8图像预处理
+//! 
+//! 将RGB图像转换为模型输入张量（CHW排列，归一化到[0,1]）
+
+use crate::AIError;
+use alloc::vec::Vec;
+
+/// 预处理RGB图像（HWC排列，尺寸需与模型输入一致）
+pub fn preprocess(image_data: &[u8], height: usize, width: usize) -> Result<Vec<f32>, AIError> {
+    let plane = height * width;
+    if plane == 0 || image_data.len() != plane * 3 {
+        return Err(AIError::InvalidInput);
+    }
+    
+    let mut tensor = vec![0.0f32; plane * 3];
+    for (i, pixel) in image_data.chunks_exact(3).enumerate() {
+        for c in 0..3 {
+            tensor[c * plane + i] = pixel[c] as f32 / 255.0;
+        }
+    }
+    
+    Ok(tensor)
+}
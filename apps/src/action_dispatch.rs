@@ -0,0 +1,272 @@
+//! 融合决策动作分发模块
+//!
+//! 将融合决策中的动作名称映射到已注册的执行器（GPIO、LED、蜂鸣器、通信发布）
+
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, VecDeque};
+use alloc::string::String;
+use common::{AppError, DriverError};
+use starry_drivers::auxiliary::{
+    AuxiliaryDriver, BuzzerPWMDriver, LEDRGBDriver, LightConfig, OLEDSSD1306Driver, SoundConfig,
+};
+
+use crate::multimodal_fusion::FusedDecision;
+
+/// 保留的未知动作记录条数
+const MAX_IGNORED_RECORDS: usize = 16;
+
+/// 执行器命令
+#[derive(Debug, Clone)]
+pub enum EffectorCommand {
+    /// 设置GPIO电平
+    GpioWrite { pin: u32, high: bool },
+    /// 翻转GPIO电平
+    GpioToggle { pin: u32 },
+    /// 设置LED颜色
+    LedColor(LightConfig),
+    /// 蜂鸣器发声
+    Buzzer(SoundConfig),
+    /// 在显示屏上显示文本
+    DisplayText(String),
+    /// 通过通信模块发布消息
+    Publish { topic: String, payload: String },
+}
+
+/// 执行器特征
+pub trait Effector {
+    /// 执行命令
+    fn apply(&mut self, command: &EffectorCommand) -> Result<(), AppError>;
+}
+
+/// 由辅助驱动执行命令的执行器
+///
+/// LED颜色、蜂鸣器和显示文本命令分别交给驱动的`set_light`、`play_sound`和`display_text`；
+/// 驱动不支持的命令和其它类型的命令返回`InvalidConfiguration`
+pub struct AuxiliaryEffector<D: AuxiliaryDriver> {
+    driver: D,
+}
+
+/// 蜂鸣器执行器
+pub type BuzzerEffector<P> = AuxiliaryEffector<BuzzerPWMDriver<P>>;
+
+/// RGB LED执行器
+pub type LedEffector<O> = AuxiliaryEffector<LEDRGBDriver<O>>;
+
+/// OLED显示屏执行器
+pub type DisplayEffector<B> = AuxiliaryEffector<OLEDSSD1306Driver<B>>;
+
+impl<D: AuxiliaryDriver> AuxiliaryEffector<D> {
+    /// 以已初始化的辅助驱动创建执行器
+    pub fn new(driver: D) -> Self {
+        Self { driver }
+    }
+
+    /// 底层驱动
+    pub fn driver(&self) -> &D {
+        &self.driver
+    }
+}
+
+impl<D: AuxiliaryDriver> Effector for AuxiliaryEffector<D> {
+    fn apply(&mut self, command: &EffectorCommand) -> Result<(), AppError> {
+        let result = match command {
+            EffectorCommand::LedColor(light) => self.driver.set_light(*light),
+            EffectorCommand::Buzzer(sound) => self.driver.play_sound(*sound),
+            EffectorCommand::DisplayText(text) => self.driver.display_text(text),
+            _ => return Err(AppError::InvalidConfiguration),
+        };
+        result.map_err(driver_error_to_app)
+    }
+}
+
+/// 将驱动错误转换为应用错误
+fn driver_error_to_app(error: DriverError) -> AppError {
+    match error {
+        DriverError::NotSupported | DriverError::InvalidParameter | DriverError::ConfigurationError => {
+            AppError::InvalidConfiguration
+        }
+        DriverError::DeviceNotFound | DriverError::DeviceBusy => AppError::ResourceUnavailable,
+        DriverError::Timeout => AppError::TimeoutError,
+        DriverError::CommunicationError => AppError::CommunicationError,
+        _ => AppError::HardwareError,
+    }
+}
+
+/// 分发结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DispatchOutcome {
+    /// 已交由执行器执行
+    Executed,
+    /// 未注册的动作，已记录并忽略
+    Ignored,
+}
+
+/// 动作路由：执行器名称 + 命令
+struct ActionRoute {
+    effector: &'static str,
+    command: EffectorCommand,
+}
+
+/// 动作分发器
+pub struct ActionDispatcher {
+    effectors: BTreeMap<&'static str, Box<dyn Effector>>,
+    routes: BTreeMap<String, ActionRoute>,
+    ignored: VecDeque<String>,
+}
+
+impl ActionDispatcher {
+    /// 创建新的动作分发器
+    pub fn new() -> Self {
+        Self {
+            effectors: BTreeMap::new(),
+            routes: BTreeMap::new(),
+            ignored: VecDeque::new(),
+        }
+    }
+
+    /// 注册执行器
+    pub fn register_effector(&mut self, name: &'static str, effector: Box<dyn Effector>) {
+        self.effectors.insert(name, effector);
+    }
+
+    /// 将动作映射到执行器命令
+    pub fn map_action(&mut self, action: &str, effector: &'static str, command: EffectorCommand) {
+        self.routes.insert(String::from(action), ActionRoute { effector, command });
+    }
+
+    /// 分发融合决策
+    ///
+    /// 未映射的动作或未注册的执行器只记录日志并忽略
+    pub fn dispatch(&mut self, decision: &FusedDecision) -> Result<DispatchOutcome, AppError> {
+        let effector = self.routes.get(&decision.action).and_then(|route| {
+            self.effectors.get_mut(route.effector).map(|effector| (effector, &route.command))
+        });
+
+        match effector {
+            Some((effector, command)) => {
+                effector.apply(command)?;
+                Ok(DispatchOutcome::Executed)
+            }
+            None => {
+                kernel::println!("忽略未知的融合动作: {}", decision.action);
+                if self.ignored.len() >= MAX_IGNORED_RECORDS {
+                    self.ignored.pop_front();
+                }
+                self.ignored.push_back(decision.action.clone());
+                Ok(DispatchOutcome::Ignored)
+            }
+        }
+    }
+
+    /// 最近被忽略的动作（由旧到新）
+    pub fn ignored_actions(&self) -> impl Iterator<Item = &str> {
+        self.ignored.iter().map(|action| action.as_str())
+    }
+}
+
+impl Default for ActionDispatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::rc::Rc;
+    use alloc::vec::Vec;
+    use core::cell::RefCell;
+    use starry_drivers::auxiliary::RgbOutput;
+    use starry_drivers::Driver;
+
+    struct RecordingEffector(Rc<RefCell<Vec<EffectorCommand>>>);
+
+    impl Effector for RecordingEffector {
+        fn apply(&mut self, command: &EffectorCommand) -> Result<(), AppError> {
+            self.0.borrow_mut().push(command.clone());
+            Ok(())
+        }
+    }
+
+    /// 记录写入的RGB值
+    struct MockRgb(Rc<RefCell<Vec<(u8, u8, u8)>>>);
+
+    impl RgbOutput for MockRgb {
+        fn set_rgb(&mut self, red: u8, green: u8, blue: u8) -> Result<(), DriverError> {
+            self.0.borrow_mut().push((red, green, blue));
+            Ok(())
+        }
+
+        fn delay_ms(&mut self, _ms: u32) {}
+    }
+
+    fn decision(action: &str) -> FusedDecision {
+        FusedDecision { action: String::from(action), confidence: 0.9 }
+    }
+
+    #[test]
+    fn test_turn_on_light_invokes_led_effector() {
+        // turn_on_light决策以配置的颜色调用LED执行器
+        let commands = Rc::new(RefCell::new(Vec::new()));
+        let mut dispatcher = ActionDispatcher::new();
+        dispatcher.register_effector("led", Box::new(RecordingEffector(commands.clone())));
+        let warm_white = LightConfig { red: 255, green: 200, blue: 150, brightness: 80 };
+        dispatcher.map_action("turn_on_light", "led", EffectorCommand::LedColor(warm_white));
+
+        assert_eq!(dispatcher.dispatch(&decision("turn_on_light")), Ok(DispatchOutcome::Executed));
+
+        let recorded = commands.borrow();
+        assert_eq!(recorded.len(), 1);
+        match recorded[0] {
+            EffectorCommand::LedColor(color) => {
+                assert_eq!((color.red, color.green, color.blue, color.brightness), (255, 200, 150, 80));
+            }
+            _ => panic!("应调用LED颜色命令"),
+        }
+    }
+
+    #[test]
+    fn test_unknown_action_logged_and_ignored() {
+        // 未映射的动作被记录并忽略，不调用任何执行器
+        let commands = Rc::new(RefCell::new(Vec::new()));
+        let mut dispatcher = ActionDispatcher::new();
+        dispatcher.register_effector("gpio", Box::new(RecordingEffector(commands.clone())));
+        dispatcher.map_action("open_door", "gpio", EffectorCommand::GpioToggle { pin: 5 });
+
+        assert_eq!(dispatcher.dispatch(&decision("launch_rocket")), Ok(DispatchOutcome::Ignored));
+        assert!(commands.borrow().is_empty());
+        assert_eq!(dispatcher.ignored_actions().collect::<Vec<_>>(), vec!["launch_rocket"]);
+    }
+
+    #[test]
+    fn test_led_effector_drives_rgb_driver() {
+        // LED执行器把颜色命令交给RGB LED驱动
+        let writes = Rc::new(RefCell::new(Vec::new()));
+        let mut led = LEDRGBDriver::new(MockRgb(writes.clone()));
+        led.init().unwrap();
+        let mut dispatcher = ActionDispatcher::new();
+        dispatcher.register_effector("led", Box::new(LedEffector::new(led)));
+        let red = LightConfig { red: 255, green: 0, blue: 0, brightness: 255 };
+        dispatcher.map_action("alert", "led", EffectorCommand::LedColor(red));
+
+        assert_eq!(dispatcher.dispatch(&decision("alert")), Ok(DispatchOutcome::Executed));
+        assert_eq!(writes.borrow().last(), Some(&(255, 0, 0)));
+    }
+
+    #[test]
+    fn test_auxiliary_effector_rejects_unsupported_commands() {
+        // 驱动不支持的命令和非辅助设备命令都返回配置错误
+        let mut led = LEDRGBDriver::new(MockRgb(Rc::new(RefCell::new(Vec::new()))));
+        led.init().unwrap();
+        let mut effector = LedEffector::new(led);
+
+        assert_eq!(
+            effector.apply(&EffectorCommand::DisplayText(String::from("hi"))),
+            Err(AppError::InvalidConfiguration)
+        );
+        assert_eq!(
+            effector.apply(&EffectorCommand::GpioWrite { pin: 3, high: true }),
+            Err(AppError::InvalidConfiguration)
+        );
+    }
+}
@@ -0,0 +1,255 @@
+//! 自适应采集帧率模块
+//!
+//! 画面中无目标或目标静止时降低采集/推理帧率，出现活动时立即恢复
+
+use alloc::collections::BTreeMap;
+use common::{BoundingBox, Detection};
+
+/// 采集帧率配置
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdaptiveCaptureConfig {
+    /// 有活动时的帧率
+    pub active_fps: u32,
+    /// 空闲时的帧率
+    pub idle_fps: u32,
+    /// 连续多少帧无活动后降为空闲帧率（滞回）
+    pub idle_after_frames: u32,
+    /// 跟踪目标中心点移动超过该距离（归一化坐标）视为运动
+    pub motion_threshold: f32,
+}
+
+impl Default for AdaptiveCaptureConfig {
+    fn default() -> Self {
+        Self {
+            active_fps: 30,
+            idle_fps: 2,
+            idle_after_frames: 30,
+            motion_threshold: 0.02,
+        }
+    }
+}
+
+/// 当前采集档位
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptureRate {
+    Active,
+    Idle,
+}
+
+/// 无跟踪ID的目标与上一帧目标关联所需的最小IoU
+const MIN_MATCH_IOU: f32 = 0.3;
+
+/// 跟踪键
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum TrackKey {
+    /// 检测器给出的跟踪ID
+    Detector(u32),
+    /// 无跟踪ID的目标按IoU与上一帧关联后分配的内部编号
+    Matched(u32),
+}
+
+/// 上一帧的目标
+#[derive(Debug, Clone, Copy)]
+struct Track {
+    class_id: u32,
+    bbox: BoundingBox,
+}
+
+/// 运动判定：比较同一目标中心点的帧间位移
+///
+/// 带跟踪ID的目标按ID关联；无跟踪ID的目标与上一帧同类别、IoU最大的目标关联
+struct MotionTracker {
+    /// 跟踪键 -> 上一帧目标
+    tracks: BTreeMap<TrackKey, Track>,
+    /// 下一个内部编号
+    next_id: u32,
+}
+
+impl MotionTracker {
+    fn new() -> Self {
+        Self { tracks: BTreeMap::new(), next_id: 0 }
+    }
+
+    /// 输入一帧检测结果，返回是否有活动
+    ///
+    /// 新出现的目标和移动的目标视为活动；本帧未出现的目标被遗忘
+    fn update(&mut self, detections: &[Detection], threshold: f32) -> bool {
+        let mut active = false;
+        let mut tracks = BTreeMap::new();
+
+        for detection in detections {
+            let key = match detection.track_id {
+                Some(track_id) => TrackKey::Detector(track_id),
+                None => self.match_untracked(detection, &tracks),
+            };
+            active |= match self.tracks.get(&key) {
+                Some(previous) => {
+                    let dx = detection.bbox.x - previous.bbox.x;
+                    let dy = detection.bbox.y - previous.bbox.y;
+                    dx * dx + dy * dy > threshold * threshold
+                }
+                None => true,
+            };
+            tracks.insert(key, Track { class_id: detection.class_id, bbox: detection.bbox });
+        }
+
+        self.tracks = tracks;
+        active
+    }
+
+    /// 为无跟踪ID的目标查找上一帧中同类别、IoU最大且本帧尚未关联的目标，
+    /// 找不到时分配新的内部编号
+    fn match_untracked(&mut self, detection: &Detection, claimed: &BTreeMap<TrackKey, Track>) -> TrackKey {
+        let best = self
+            .tracks
+            .iter()
+            .filter(|(key, track)| {
+                matches!(key, TrackKey::Matched(_))
+                    && track.class_id == detection.class_id
+                    && !claimed.contains_key(key)
+            })
+            .map(|(key, track)| (*key, track.bbox.calculate_iou(&detection.bbox)))
+            .filter(|&(_, iou)| iou >= MIN_MATCH_IOU)
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+
+        match best {
+            Some((key, _)) => key,
+            None => {
+                let key = TrackKey::Matched(self.next_id);
+                self.next_id = self.next_id.wrapping_add(1);
+                key
+            }
+        }
+    }
+}
+
+/// 自适应采集策略
+pub struct AdaptiveCapture {
+    config: AdaptiveCaptureConfig,
+    tracker: MotionTracker,
+    quiet_frames: u32,
+    rate: CaptureRate,
+}
+
+impl AdaptiveCapture {
+    /// 创建策略，初始为活动帧率
+    pub fn new(config: AdaptiveCaptureConfig) -> Self {
+        Self {
+            config,
+            tracker: MotionTracker::new(),
+            quiet_frames: 0,
+            rate: CaptureRate::Active,
+        }
+    }
+
+    /// 输入一帧检测结果，返回下一帧应使用的档位
+    pub fn update(&mut self, detections: &[Detection]) -> CaptureRate {
+        if self.tracker.update(detections, self.config.motion_threshold) {
+            self.quiet_frames = 0;
+            self.rate = CaptureRate::Active;
+        } else {
+            self.quiet_frames = self.quiet_frames.saturating_add(1);
+            if self.quiet_frames >= self.config.idle_after_frames {
+                self.rate = CaptureRate::Idle;
+            }
+        }
+        self.rate
+    }
+
+    /// 当前档位
+    pub fn rate(&self) -> CaptureRate {
+        self.rate
+    }
+
+    /// 当前帧率
+    pub fn current_fps(&self) -> u32 {
+        match self.rate {
+            CaptureRate::Active => self.config.active_fps,
+            CaptureRate::Idle => self.config.idle_fps,
+        }
+        .max(1)
+    }
+
+    /// 当前帧间隔 (ms)
+    pub fn frame_interval_ms(&self) -> u64 {
+        1000 / self.current_fps() as u64
+    }
+}
+
+impl Default for AdaptiveCapture {
+    fn default() -> Self {
+        Self::new(AdaptiveCaptureConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> AdaptiveCaptureConfig {
+        AdaptiveCaptureConfig { active_fps: 30, idle_fps: 2, idle_after_frames: 3, motion_threshold: 0.05 }
+    }
+
+    fn tracked(track_id: u32, x: f32) -> Detection {
+        let mut detection = Detection::new(0, "person", 0.9, BoundingBox::new(x, 0.5, 0.1, 0.2));
+        detection.track_id = Some(track_id);
+        detection
+    }
+
+    fn untracked(class_id: u32, x: f32) -> Detection {
+        Detection::new(class_id, "person", 0.9, BoundingBox::new(x, 0.5, 0.3, 0.2))
+    }
+
+    #[test]
+    fn test_empty_frames_drop_to_idle_and_detection_restores_active() {
+        // 连续空帧达到滞回帧数后降为空闲帧率，新目标出现时立即恢复
+        let mut capture = AdaptiveCapture::new(config());
+        assert_eq!(capture.update(&[]), CaptureRate::Active);
+        assert_eq!(capture.update(&[]), CaptureRate::Active);
+        assert_eq!(capture.update(&[]), CaptureRate::Idle);
+        assert_eq!(capture.current_fps(), 2);
+        assert_eq!(capture.frame_interval_ms(), 500);
+
+        assert_eq!(capture.update(&[tracked(7, 0.5)]), CaptureRate::Active);
+        assert_eq!(capture.current_fps(), 30);
+    }
+
+    #[test]
+    fn test_static_tracked_target_counts_as_idle() {
+        // 静止的跟踪目标不算活动，移动超过阈值时恢复活动帧率
+        let mut capture = AdaptiveCapture::new(config());
+        capture.update(&[tracked(1, 0.30)]);
+        for _ in 0..3 {
+            capture.update(&[tracked(1, 0.31)]);
+        }
+        assert_eq!(capture.rate(), CaptureRate::Idle);
+
+        assert_eq!(capture.update(&[tracked(1, 0.45)]), CaptureRate::Active);
+    }
+
+    #[test]
+    fn test_untracked_targets_matched_by_iou() {
+        // 无跟踪ID的静止目标按IoU与上一帧关联，不再一直视为活动
+        let mut capture = AdaptiveCapture::new(config());
+        capture.update(&[untracked(0, 0.30), untracked(1, 0.70)]);
+        for _ in 0..3 {
+            capture.update(&[untracked(1, 0.71), untracked(0, 0.31)]);
+        }
+        assert_eq!(capture.rate(), CaptureRate::Idle);
+
+        // 仍与上一帧重叠但位移超过阈值的目标视为运动
+        assert_eq!(capture.update(&[untracked(0, 0.38), untracked(1, 0.71)]), CaptureRate::Active);
+    }
+
+    #[test]
+    fn test_untracked_target_of_other_class_is_new() {
+        // 位置重叠但类别不同的目标按新目标处理
+        let mut capture = AdaptiveCapture::new(config());
+        for _ in 0..4 {
+            capture.update(&[untracked(0, 0.50)]);
+        }
+        assert_eq!(capture.rate(), CaptureRate::Idle);
+
+        assert_eq!(capture.update(&[untracked(2, 0.50)]), CaptureRate::Active);
+    }
+}
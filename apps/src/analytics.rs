@@ -0,0 +1,155 @@
+//! 检测统计分析模块
+//!
+//! 跨帧累计各类别的检测数量，并统计滑动窗口内的检测速率
+
+use alloc::collections::{BTreeMap, VecDeque};
+use alloc::vec::Vec;
+use common::Detection;
+
+/// 默认速率统计窗口 (ms)
+pub const DEFAULT_RATE_WINDOW_MS: u64 = 60_000;
+/// 默认跟踪目标遗忘时间 (ms)
+pub const DEFAULT_TRACK_TTL_MS: u64 = 5_000;
+
+/// 类别计数器
+///
+/// 带跟踪ID的检测结果在跟踪有效期内只计数一次；
+/// 无跟踪ID的检测结果逐帧计数
+pub struct ClassCounter {
+    window_ms: u64,
+    track_ttl_ms: u64,
+    totals: BTreeMap<u32, u64>,
+    /// 窗口内的计数事件 (时间戳, 类别ID)
+    events: VecDeque<(u64, u32)>,
+    /// 跟踪ID -> 最后出现时间
+    tracks: BTreeMap<u32, u64>,
+    now_ms: u64,
+}
+
+impl ClassCounter {
+    /// 创建新的类别计数器
+    pub fn new() -> Self {
+        Self::with_window(DEFAULT_RATE_WINDOW_MS, DEFAULT_TRACK_TTL_MS)
+    }
+
+    /// 使用指定的速率窗口和跟踪遗忘时间创建计数器
+    pub fn with_window(window_ms: u64, track_ttl_ms: u64) -> Self {
+        Self {
+            window_ms: window_ms.max(1),
+            track_ttl_ms,
+            totals: BTreeMap::new(),
+            events: VecDeque::new(),
+            tracks: BTreeMap::new(),
+            now_ms: 0,
+        }
+    }
+
+    /// 输入一帧检测结果，`now_ms`为该帧的时间戳
+    pub fn update(&mut self, detections: &[Detection], now_ms: u64) {
+        self.now_ms = self.now_ms.max(now_ms);
+
+        for detection in detections {
+            if let Some(track_id) = detection.track_id {
+                let is_new = self.tracks.insert(track_id, self.now_ms).is_none();
+                if !is_new {
+                    continue;
+                }
+            }
+
+            *self.totals.entry(detection.class_id).or_insert(0) += 1;
+            self.events.push_back((self.now_ms, detection.class_id));
+        }
+
+        self.expire();
+    }
+
+    /// 推进时间（无新检测结果）
+    pub fn advance(&mut self, now_ms: u64) {
+        self.update(&[], now_ms);
+    }
+
+    /// 各类别累计计数，按类别ID升序
+    pub fn counts(&self) -> Vec<(u32, u64)> {
+        self.totals.iter().map(|(&class_id, &count)| (class_id, count)).collect()
+    }
+
+    /// 指定类别的累计计数
+    pub fn count(&self, class_id: u32) -> u64 {
+        self.totals.get(&class_id).copied().unwrap_or(0)
+    }
+
+    /// 指定类别在滑动窗口内的检测速率（次/分钟）
+    pub fn rate(&self, class_id: u32) -> f32 {
+        let in_window = self.events.iter().filter(|&&(_, id)| id == class_id).count();
+        in_window as f32 * 60_000.0 / self.window_ms as f32
+    }
+
+    /// 清空所有统计
+    pub fn reset(&mut self) {
+        self.totals.clear();
+        self.events.clear();
+        self.tracks.clear();
+    }
+
+    /// 移除窗口外的事件和过期的跟踪ID
+    fn expire(&mut self) {
+        let now = self.now_ms;
+        while let Some(&(timestamp, _)) = self.events.front() {
+            if now - timestamp < self.window_ms {
+                break;
+            }
+            self.events.pop_front();
+        }
+
+        let ttl = self.track_ttl_ms;
+        self.tracks.retain(|_, &mut last_seen| now - last_seen <= ttl);
+    }
+}
+
+impl Default for ClassCounter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::BoundingBox;
+
+    fn detection(class_id: u32, track_id: Option<u32>) -> Detection {
+        let mut detection = Detection::new(class_id, "object", 0.9, BoundingBox::new(0.0, 0.0, 10.0, 10.0));
+        detection.track_id = track_id;
+        detection
+    }
+
+    #[test]
+    fn test_same_track_counted_once() {
+        // 同一跟踪目标跨帧出现只计数一次，无跟踪ID的检测逐帧计数
+        let mut counter = ClassCounter::new();
+        for frame in 0..10u64 {
+            counter.update(&[detection(0, Some(7)), detection(2, None)], frame * 33);
+        }
+        counter.update(&[detection(0, Some(8))], 400);
+
+        assert_eq!(counter.count(0), 2);
+        assert_eq!(counter.count(2), 10);
+        assert_eq!(counter.counts(), vec![(0, 2), (2, 10)]);
+    }
+
+    #[test]
+    fn test_windowed_rate_decays() {
+        // 时间推进后窗口外的事件不再计入速率，累计计数保持不变
+        let mut counter = ClassCounter::with_window(60_000, 1_000);
+        counter.update(&[detection(1, None), detection(1, None)], 0);
+        counter.update(&[detection(1, None)], 30_000);
+        assert_eq!(counter.rate(1), 3.0);
+
+        counter.advance(61_000);
+        assert_eq!(counter.rate(1), 1.0);
+
+        counter.advance(120_000);
+        assert_eq!(counter.rate(1), 0.0);
+        assert_eq!(counter.count(1), 3);
+    }
+}
@@ -0,0 +1,342 @@
+//! 应用配置持久化模块
+//!
+//! 将AppConfig以带版本号和CRC校验的记录保存到SPI Flash
+
+use crate::{AppConfig, LogLevel, PerformanceMode};
+use alloc::vec::Vec;
+use common::AppError;
+use starry_drivers::spi::SpiDevice;
+
+/// 记录魔数 "SCFG"
+const RECORD_MAGIC: u32 = 0x5343_4647;
+/// 当前记录版本
+pub const CONFIG_VERSION: u16 = 2;
+/// 记录头长度：魔数(4) + 版本(2) + 负载长度(2)
+const HEADER_LEN: usize = 8;
+/// CRC长度
+const CRC_LEN: usize = 4;
+/// 最大负载长度
+const MAX_PAYLOAD_LEN: usize = 32;
+/// Flash扇区大小
+pub const SECTOR_SIZE: u32 = 4096;
+
+/// Flash存储特征
+pub trait FlashStorage {
+    /// 从指定偏移读取数据
+    fn read(&mut self, offset: u32, buffer: &mut [u8]) -> Result<(), AppError>;
+
+    /// 向指定偏移写入数据（目标区域需已擦除）
+    fn write(&mut self, offset: u32, data: &[u8]) -> Result<(), AppError>;
+
+    /// 擦除包含指定偏移的扇区
+    fn erase_sector(&mut self, offset: u32) -> Result<(), AppError>;
+}
+
+/// SPI NOR Flash
+pub struct SpiFlash {
+    device: SpiDevice,
+}
+
+impl SpiFlash {
+    const CMD_READ: u8 = 0x03;
+    const CMD_PAGE_PROGRAM: u8 = 0x02;
+    const CMD_SECTOR_ERASE: u8 = 0x20;
+    const CMD_WRITE_ENABLE: u8 = 0x06;
+    const CMD_READ_STATUS: u8 = 0x05;
+    const STATUS_BUSY: u8 = 0x01;
+    const PAGE_SIZE: usize = 256;
+
+    /// 创建新的SPI Flash
+    pub fn new(device: SpiDevice) -> Self {
+        Self { device }
+    }
+
+    fn command_with_address(command: u8, offset: u32) -> [u8; 4] {
+        [command, (offset >> 16) as u8, (offset >> 8) as u8, offset as u8]
+    }
+
+    fn write_enable(&mut self) -> Result<(), AppError> {
+        self.device.write(&[Self::CMD_WRITE_ENABLE])
+            .map_err(|_| AppError::CommunicationError)
+    }
+
+    fn wait_ready(&mut self) -> Result<(), AppError> {
+        for _ in 0..100_000 {
+            let mut status = [0u8; 1];
+            self.device.read_register(Self::CMD_READ_STATUS, &mut status)
+                .map_err(|_| AppError::CommunicationError)?;
+            if status[0] & Self::STATUS_BUSY == 0 {
+                return Ok(());
+            }
+        }
+        Err(AppError::TimeoutError)
+    }
+}
+
+impl FlashStorage for SpiFlash {
+    fn read(&mut self, offset: u32, buffer: &mut [u8]) -> Result<(), AppError> {
+        let command = Self::command_with_address(Self::CMD_READ, offset);
+        let mut rx = vec![0u8; command.len() + buffer.len()];
+        self.device.transfer(&command, &mut rx)
+            .map_err(|_| AppError::CommunicationError)?;
+        buffer.copy_from_slice(&rx[command.len()..]);
+        Ok(())
+    }
+
+    fn write(&mut self, offset: u32, data: &[u8]) -> Result<(), AppError> {
+        // 按页编程，不跨页边界
+        let mut written = 0;
+        while written < data.len() {
+            let address = offset + written as u32;
+            let page_remaining = Self::PAGE_SIZE - (address as usize % Self::PAGE_SIZE);
+            let chunk = page_remaining.min(data.len() - written);
+
+            self.write_enable()?;
+            let mut tx = Self::command_with_address(Self::CMD_PAGE_PROGRAM, address).to_vec();
+            tx.extend_from_slice(&data[written..written + chunk]);
+            self.device.write(&tx).map_err(|_| AppError::CommunicationError)?;
+            self.wait_ready()?;
+
+            written += chunk;
+        }
+        Ok(())
+    }
+
+    fn erase_sector(&mut self, offset: u32) -> Result<(), AppError> {
+        self.write_enable()?;
+        let command = Self::command_with_address(Self::CMD_SECTOR_ERASE, offset - offset % SECTOR_SIZE);
+        self.device.write(&command).map_err(|_| AppError::CommunicationError)?;
+        self.wait_ready()
+    }
+}
+
+/// 配置存储
+pub struct ConfigStore<F: FlashStorage> {
+    flash: F,
+    offset: u32,
+}
+
+impl<F: FlashStorage> ConfigStore<F> {
+    /// 创建新的配置存储，`offset`为配置记录所在扇区的起始偏移
+    pub fn new(flash: F, offset: u32) -> Self {
+        Self { flash, offset }
+    }
+
+    /// 加载配置，记录缺失或损坏时返回默认配置
+    pub fn load(&mut self) -> AppConfig {
+        self.try_load().unwrap_or_default()
+    }
+
+    /// 保存配置
+    pub fn save(&mut self, config: &AppConfig) -> Result<(), AppError> {
+        let payload = encode_payload(config);
+
+        let mut record = Vec::with_capacity(HEADER_LEN + payload.len() + CRC_LEN);
+        record.extend_from_slice(&RECORD_MAGIC.to_le_bytes());
+        record.extend_from_slice(&CONFIG_VERSION.to_le_bytes());
+        record.extend_from_slice(&(payload.len() as u16).to_le_bytes());
+        record.extend_from_slice(&payload);
+        let crc = crc32(&record);
+        record.extend_from_slice(&crc.to_le_bytes());
+
+        self.flash.erase_sector(self.offset)?;
+        self.flash.write(self.offset, &record)
+    }
+
+    /// 读取并校验记录
+    fn try_load(&mut self) -> Option<AppConfig> {
+        let mut header = [0u8; HEADER_LEN];
+        self.flash.read(self.offset, &mut header).ok()?;
+
+        let magic = u32::from_le_bytes([header[0], header[1], header[2], header[3]]);
+        let version = u16::from_le_bytes([header[4], header[5]]);
+        let payload_len = u16::from_le_bytes([header[6], header[7]]) as usize;
+        if magic != RECORD_MAGIC || payload_len > MAX_PAYLOAD_LEN {
+            return None;
+        }
+
+        let mut rest = vec![0u8; payload_len + CRC_LEN];
+        self.flash.read(self.offset + HEADER_LEN as u32, &mut rest).ok()?;
+
+        let (payload, crc_bytes) = rest.split_at(payload_len);
+        let stored_crc = u32::from_le_bytes([crc_bytes[0], crc_bytes[1], crc_bytes[2], crc_bytes[3]]);
+
+        let mut record = header.to_vec();
+        record.extend_from_slice(payload);
+        if crc32(&record) != stored_crc {
+            return None;
+        }
+
+        decode_payload(version, payload)
+    }
+}
+
+/// 编码当前版本的配置负载
+fn encode_payload(config: &AppConfig) -> Vec<u8> {
+    let mut flags = 0u8;
+    if config.voice_enabled { flags |= 1 << 0; }
+    if config.vision_enabled { flags |= 1 << 1; }
+    if config.sensor_enabled { flags |= 1 << 2; }
+    if config.network_enabled { flags |= 1 << 3; }
+
+    let performance_mode = match config.performance_mode {
+        PerformanceMode::PowerSaving => 0,
+        PerformanceMode::Balanced => 1,
+        PerformanceMode::Performance => 2,
+    };
+
+    let log_level = match config.log_level {
+        LogLevel::Error => 0,
+        LogLevel::Warn => 1,
+        LogLevel::Info => 2,
+        LogLevel::Debug => 3,
+        LogLevel::Trace => 4,
+    };
+
+    vec![flags, performance_mode, log_level]
+}
+
+/// 按版本解码配置负载
+///
+/// - 版本1：标志位 + 性能模式（无日志级别，迁移时取默认值）
+/// - 版本2：标志位 + 性能模式 + 日志级别
+/// - 未知版本：无法解析，回退到默认配置
+fn decode_payload(version: u16, payload: &[u8]) -> Option<AppConfig> {
+    let expected_len = match version {
+        1 => 2,
+        2 => 3,
+        _ => return None,
+    };
+    if payload.len() != expected_len {
+        return None;
+    }
+
+    let flags = payload[0];
+    let performance_mode = match payload[1] {
+        0 => PerformanceMode::PowerSaving,
+        1 => PerformanceMode::Balanced,
+        2 => PerformanceMode::Performance,
+        _ => return None,
+    };
+
+    let log_level = if version >= 2 {
+        match payload[2] {
+            0 => LogLevel::Error,
+            1 => LogLevel::Warn,
+            2 => LogLevel::Info,
+            3 => LogLevel::Debug,
+            4 => LogLevel::Trace,
+            _ => return None,
+        }
+    } else {
+        AppConfig::default().log_level
+    };
+
+    Some(AppConfig {
+        voice_enabled: flags & (1 << 0) != 0,
+        vision_enabled: flags & (1 << 1) != 0,
+        sensor_enabled: flags & (1 << 2) != 0,
+        network_enabled: flags & (1 << 3) != 0,
+        performance_mode,
+        log_level,
+    })
+}
+
+/// CRC-32 (IEEE 802.3)
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 模拟NOR Flash：擦除为0xFF，写入只能清除位
+    struct MockFlash {
+        data: Vec<u8>,
+    }
+
+    impl MockFlash {
+        fn new() -> Self {
+            Self { data: vec![0xFF; SECTOR_SIZE as usize] }
+        }
+    }
+
+    impl FlashStorage for MockFlash {
+        fn read(&mut self, offset: u32, buffer: &mut [u8]) -> Result<(), AppError> {
+            let start = offset as usize;
+            buffer.copy_from_slice(&self.data[start..start + buffer.len()]);
+            Ok(())
+        }
+
+        fn write(&mut self, offset: u32, data: &[u8]) -> Result<(), AppError> {
+            for (i, &byte) in data.iter().enumerate() {
+                self.data[offset as usize + i] &= byte;
+            }
+            Ok(())
+        }
+
+        fn erase_sector(&mut self, _offset: u32) -> Result<(), AppError> {
+            self.data.fill(0xFF);
+            Ok(())
+        }
+    }
+
+    fn custom_config() -> AppConfig {
+        AppConfig {
+            voice_enabled: false,
+            vision_enabled: true,
+            sensor_enabled: false,
+            network_enabled: true,
+            performance_mode: PerformanceMode::PowerSaving,
+            log_level: LogLevel::Debug,
+        }
+    }
+
+    #[test]
+    fn test_config_roundtrip_and_corruption() {
+        // 保存后原样加载；空Flash或损坏记录回退默认配置
+        let mut store = ConfigStore::new(MockFlash::new(), 0);
+        assert_eq!(store.load(), AppConfig::default());
+
+        store.save(&custom_config()).unwrap();
+        assert_eq!(store.load(), custom_config());
+
+        store.flash.data[HEADER_LEN] ^= 0x01;
+        assert_eq!(store.load(), AppConfig::default());
+    }
+
+    #[test]
+    fn test_config_version_migration() {
+        // 版本1记录迁移为当前版本，未知版本回退默认配置
+        let mut store = ConfigStore::new(MockFlash::new(), 0);
+        let write_record = |store: &mut ConfigStore<MockFlash>, version: u16, payload: &[u8]| {
+            let mut record = Vec::new();
+            record.extend_from_slice(&RECORD_MAGIC.to_le_bytes());
+            record.extend_from_slice(&version.to_le_bytes());
+            record.extend_from_slice(&(payload.len() as u16).to_le_bytes());
+            record.extend_from_slice(payload);
+            let crc = crc32(&record);
+            record.extend_from_slice(&crc.to_le_bytes());
+            store.flash.erase_sector(0).unwrap();
+            store.flash.write(0, &record).unwrap();
+        };
+
+        write_record(&mut store, 1, &[0b1010, 2]);
+        let migrated = store.load();
+        assert_eq!(migrated.performance_mode, PerformanceMode::Performance);
+        assert!(migrated.vision_enabled && !migrated.voice_enabled);
+        assert_eq!(migrated.log_level, AppConfig::default().log_level);
+
+        write_record(&mut store, CONFIG_VERSION + 1, &[0, 0, 0, 0]);
+        assert_eq!(store.load(), AppConfig::default());
+    }
+}
@@ -0,0 +1,128 @@
+//! 检测结果分发模块
+//!
+//! 将每帧检测结果同时分发给显示、遥测编码、告警等多个消费者
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use common::{AppError, Detection};
+
+/// 检测结果消费者
+///
+/// 以切片形式共享同一帧结果，需要保留结果的消费者自行克隆
+pub trait DetectionSink {
+    /// 处理一帧检测结果
+    fn consume(&mut self, detections: &[Detection]) -> Result<(), AppError>;
+}
+
+/// 消费者处理失败记录
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SinkFailure {
+    /// 消费者名称
+    pub sink: &'static str,
+    pub error: AppError,
+}
+
+/// 检测结果分发器
+pub struct DetectionTee {
+    sinks: Vec<(&'static str, Box<dyn DetectionSink>)>,
+}
+
+impl DetectionTee {
+    /// 创建新的分发器
+    pub fn new() -> Self {
+        Self { sinks: Vec::new() }
+    }
+
+    /// 注册消费者，按注册顺序分发
+    pub fn add_sink(&mut self, name: &'static str, sink: Box<dyn DetectionSink>) {
+        self.sinks.push((name, sink));
+    }
+
+    /// 已注册的消费者数
+    pub fn sink_count(&self) -> usize {
+        self.sinks.len()
+    }
+
+    /// 将一帧检测结果分发给所有消费者
+    ///
+    /// 单个消费者出错不影响其余消费者，返回出错的消费者及错误
+    pub fn publish(&mut self, detections: &[Detection]) -> Vec<SinkFailure> {
+        let mut failures = Vec::new();
+        for (name, sink) in self.sinks.iter_mut() {
+            if let Err(error) = sink.consume(detections) {
+                kernel::println!("检测结果消费者{}处理失败: {:?}", name, error);
+                failures.push(SinkFailure { sink: name, error });
+            }
+        }
+        failures
+    }
+}
+
+impl Default for DetectionTee {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::rc::Rc;
+    use common::BoundingBox;
+    use core::cell::RefCell;
+
+    /// 记录每帧收到的检测数
+    struct CountingSink(Rc<RefCell<Vec<usize>>>);
+
+    impl DetectionSink for CountingSink {
+        fn consume(&mut self, detections: &[Detection]) -> Result<(), AppError> {
+            self.0.borrow_mut().push(detections.len());
+            Ok(())
+        }
+    }
+
+    struct FailingSink;
+
+    impl DetectionSink for FailingSink {
+        fn consume(&mut self, _detections: &[Detection]) -> Result<(), AppError> {
+            Err(AppError::CommunicationError)
+        }
+    }
+
+    fn frame() -> Vec<Detection> {
+        vec![
+            Detection::new(0, "person", 0.9, BoundingBox::new(0.5, 0.5, 0.2, 0.4)),
+            Detection::new(2, "car", 0.7, BoundingBox::new(0.2, 0.6, 0.3, 0.2)),
+        ]
+    }
+
+    #[test]
+    fn test_all_sinks_receive_frame() {
+        // 显示、遥测、告警三个消费者都收到同一帧
+        let received: Vec<_> = (0..3).map(|_| Rc::new(RefCell::new(Vec::new()))).collect();
+        let mut tee = DetectionTee::new();
+        for (name, log) in ["display", "telemetry", "alarm"].into_iter().zip(&received) {
+            tee.add_sink(name, Box::new(CountingSink(log.clone())));
+        }
+
+        assert!(tee.publish(&frame()).is_empty());
+        assert_eq!(tee.sink_count(), 3);
+        assert!(received.iter().all(|log| *log.borrow() == vec![2]));
+    }
+
+    #[test]
+    fn test_failing_sink_does_not_block_others() {
+        // 中间的消费者出错时，前后的消费者仍然收到结果
+        let display = Rc::new(RefCell::new(Vec::new()));
+        let alarm = Rc::new(RefCell::new(Vec::new()));
+        let mut tee = DetectionTee::new();
+        tee.add_sink("display", Box::new(CountingSink(display.clone())));
+        tee.add_sink("telemetry", Box::new(FailingSink));
+        tee.add_sink("alarm", Box::new(CountingSink(alarm.clone())));
+
+        let failures = tee.publish(&frame());
+        assert_eq!(failures, vec![SinkFailure { sink: "telemetry", error: AppError::CommunicationError }]);
+        assert_eq!(*display.borrow(), vec![2]);
+        assert_eq!(*alarm.borrow(), vec![2]);
+    }
+}
@@ -0,0 +1,190 @@
+//! 应用事件总线
+//!
+//! 将语音、视觉、传感器和系统事件分发给所有订阅的应用，订阅时可按事件类型过滤
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use common::AppError;
+
+use crate::{AppEvent, AppManager};
+
+/// 事件类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    VoiceCommand,
+    VisualDetection,
+    SensorData,
+    SystemEvent,
+}
+
+impl EventKind {
+    /// 事件所属类型
+    pub fn of(event: &AppEvent) -> Self {
+        match event {
+            AppEvent::VoiceCommand(_) => EventKind::VoiceCommand,
+            AppEvent::VisualDetection(_) => EventKind::VisualDetection,
+            AppEvent::SensorData(_) => EventKind::SensorData,
+            AppEvent::SystemEvent(_) => EventKind::SystemEvent,
+        }
+    }
+
+    fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+/// 订阅的事件类型集合
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventFilter {
+    mask: u8,
+}
+
+impl EventFilter {
+    /// 接收所有类型
+    pub const ALL: Self = Self { mask: 0x0F };
+
+    /// 只接收指定类型
+    pub fn only(kinds: &[EventKind]) -> Self {
+        Self {
+            mask: kinds.iter().fold(0, |mask, kind| mask | kind.bit()),
+        }
+    }
+
+    /// 是否接收该类型
+    pub fn accepts(&self, kind: EventKind) -> bool {
+        self.mask & kind.bit() != 0
+    }
+}
+
+/// 订阅者标识，按订阅顺序分配
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SubscriberId(pub usize);
+
+/// 事件总线
+pub struct EventBus {
+    subscribers: Vec<(EventFilter, Box<dyn AppManager>)>,
+}
+
+impl EventBus {
+    /// 创建空的事件总线
+    pub fn new() -> Self {
+        Self { subscribers: Vec::new() }
+    }
+
+    /// 订阅所有类型的事件
+    pub fn subscribe(&mut self, app: Box<dyn AppManager>) -> SubscriberId {
+        self.subscribe_filtered(app, EventFilter::ALL)
+    }
+
+    /// 只订阅过滤器接受的事件类型
+    pub fn subscribe_filtered(&mut self, app: Box<dyn AppManager>, filter: EventFilter) -> SubscriberId {
+        self.subscribers.push((filter, app));
+        SubscriberId(self.subscribers.len() - 1)
+    }
+
+    /// 订阅者数量
+    pub fn subscriber_count(&self) -> usize {
+        self.subscribers.len()
+    }
+
+    /// 将事件分发给接受该类型的订阅者
+    ///
+    /// 每个订阅者收到事件的一份克隆，单个订阅者出错不影响其余订阅者；
+    /// 按订阅顺序返回收到事件的订阅者及其处理结果
+    pub fn publish(&mut self, event: AppEvent) -> Vec<(SubscriberId, Result<(), AppError>)> {
+        let kind = EventKind::of(&event);
+        let mut results = Vec::new();
+        for (index, (filter, app)) in self.subscribers.iter_mut().enumerate() {
+            if !filter.accepts(kind) {
+                continue;
+            }
+
+            let result = app.handle_event(event.clone());
+            if let Err(error) = &result {
+                kernel::println!("应用{}处理{:?}事件失败: {:?}", index, kind, error);
+            }
+            results.push((SubscriberId(index), result));
+        }
+        results
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AppStatus, SystemEvent};
+    use alloc::rc::Rc;
+    use alloc::string::String;
+    use core::cell::RefCell;
+
+    /// 记录收到的事件类型，可配置为处理失败
+    struct RecordingApp {
+        received: Rc<RefCell<Vec<EventKind>>>,
+        fail: bool,
+    }
+
+    impl AppManager for RecordingApp {
+        fn start(&mut self) -> Result<(), AppError> {
+            Ok(())
+        }
+
+        fn stop(&mut self) -> Result<(), AppError> {
+            Ok(())
+        }
+
+        fn get_status(&self) -> AppStatus {
+            AppStatus::Running
+        }
+
+        fn handle_event(&mut self, event: AppEvent) -> Result<(), AppError> {
+            self.received.borrow_mut().push(EventKind::of(&event));
+            if self.fail {
+                Err(AppError::DataProcessingError)
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    fn app(fail: bool) -> (Box<RecordingApp>, Rc<RefCell<Vec<EventKind>>>) {
+        let received = Rc::new(RefCell::new(Vec::new()));
+        (Box::new(RecordingApp { received: received.clone(), fail }), received)
+    }
+
+    #[test]
+    fn test_failing_subscriber_does_not_block_others() {
+        // 第一个应用处理失败，第二个应用仍然收到事件
+        let mut bus = EventBus::new();
+        let (failing, _) = app(true);
+        let (healthy, received) = app(false);
+        let failing_id = bus.subscribe(failing);
+        let healthy_id = bus.subscribe(healthy);
+
+        let results = bus.publish(AppEvent::VoiceCommand(String::from("turn_on_light")));
+        assert_eq!(results, vec![(failing_id, Err(AppError::DataProcessingError)), (healthy_id, Ok(()))]);
+        assert_eq!(*received.borrow(), vec![EventKind::VoiceCommand]);
+    }
+
+    #[test]
+    fn test_filtered_subscriber_only_gets_selected_kinds() {
+        // 只订阅语音指令的应用收不到系统事件
+        let mut bus = EventBus::new();
+        let (voice_only, voice_received) = app(false);
+        let (all, all_received) = app(false);
+        bus.subscribe_filtered(voice_only, EventFilter::only(&[EventKind::VoiceCommand]));
+        let all_id = bus.subscribe(all);
+
+        let results = bus.publish(AppEvent::SystemEvent(SystemEvent::LowBattery));
+        assert_eq!(results, vec![(all_id, Ok(()))]);
+        bus.publish(AppEvent::VoiceCommand(String::from("query_environment")));
+
+        assert_eq!(*voice_received.borrow(), vec![EventKind::VoiceCommand]);
+        assert_eq!(*all_received.borrow(), vec![EventKind::SystemEvent, EventKind::VoiceCommand]);
+    }
+}
@@ -5,12 +5,20 @@
 #![no_std]
 
 // 导入通用库
-use common::{AppError, BoundingBox, Detection, PerformanceMode, LogLevel};
+use common::{AppError, BoundingBox, Detection, SensorData, PerformanceMode, LogLevel};
 
 // 应用模块
 pub mod voice_interaction;
 pub mod multimodal_fusion;
 pub mod system_integration;
+pub mod config_store;
+pub mod analytics;
+pub mod postprocess;
+pub mod pipeline_monitor;
+pub mod action_dispatch;
+pub mod detection_tee;
+pub mod adaptive_capture;
+pub mod event_bus;
 
 // 工具模块
 mod utils;
@@ -64,7 +72,7 @@ pub struct DetectionResult {
     pub bounding_box: BoundingBox,
 }
 
-// SensorData已从common库导入，但这里保持应用特定的SensorData以兼容现有代码
+// SensorData已从common库导入，驱动层通过starry_drivers重导出同一类型
 
 /// 系统事件
 #[derive(Debug, Clone)]
@@ -74,10 +82,12 @@ pub enum SystemEvent {
     NetworkConnected,
     NetworkDisconnected,
     StorageFull,
+    /// 推理流水线停滞（两次心跳间隔超过阈值）
+    PipelineStalled { elapsed_ms: u64 },
 }
 
 /// 应用配置
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct AppConfig {
     pub voice_enabled: bool,
     pub vision_enabled: bool,
@@ -8,6 +8,12 @@
 use starry_kernel::{init, println, delay, KernelInfo};
 use starry_drivers::{init as init_drivers, AsyncRuntime, DmaBuffer};
 use starry_ai::{init as init_ai, AIManager, YoloV8Engine};
+use starry_apps::adaptive_capture::AdaptiveCapture;
+use starry_apps::pipeline_monitor::PipelineMonitor;
+use common::Detection;
+
+/// 演示采集循环的帧数
+const DEMO_CAPTURE_FRAMES: usize = 10;
 
 /// 应用程序主函数
 #[no_mangle]
@@ -145,26 +151,70 @@ fn demo_ai_inference() {
     let input_size = 3 * 640 * 640; // RGB 640x640图像
     let test_input = vec![0.5f32; input_size];
     
-    // 执行推理
-    if let Ok(result) = ai_manager.infer(&test_input) {
-        println!("AI推理完成，输出大小: {}", result.len());
-        
-        // 解析检测结果
-        if let Some(yolo_engine) = ai_manager.engines.get_mut(0) {
-            if let Ok(yolo) = yolo_engine.as_any().downcast_ref::<YoloV8Engine>() {
-                let detections = yolo.parse_detections(&result);
-                println!("检测到 {} 个目标", detections.len());
-                
-                for detection in detections {
-                    println!("目标: 类别={}, 置信度={:.2}%, 位置=({:.1},{:.1},{:.1},{:.1})", 
-                        detection.class_id, 
-                        detection.confidence * 100.0,
-                        detection.bbox[0], detection.bbox[1], 
-                        detection.bbox[2], detection.bbox[3]
-                    );
+    // 按电量计上报的电量选择推理档位，低电量时降低推理频率并切换到INT8
+    if let Some(level) = starry_kernel::battery_level() {
+        let profile = ai_manager.set_battery_level(level);
+        println!("电量{}%推理档位: 间隔{}ms, 精度{:?}", level, profile.inference_interval_ms, profile.precision);
+    } else {
+        println!("电量计未上报电量，保持默认推理档位");
+    }
+    
+    // 采集循环：按功耗档位节流推理，按画面中的目标活动调整采集帧率，
+    // 每完成一次推理上报一次心跳，推理长时间未完成时告警
+    let mut capture = AdaptiveCapture::default();
+    let mut monitor = PipelineMonitor::default();
+    for _ in 0..DEMO_CAPTURE_FRAMES {
+        if ai_manager.should_infer(starry_kernel::uptime_millis()) {
+            if let Some(detections) = detect_frame(&mut ai_manager, &test_input) {
+                monitor.heartbeat(starry_kernel::uptime_millis());
+                let previous = capture.rate();
+                if capture.update(&detections) != previous {
+                    println!("采集档位切换为{:?}，帧率{}fps", capture.rate(), capture.current_fps());
                 }
             }
         }
+        if let Some(event) = monitor.check(starry_kernel::uptime_millis()) {
+            println!("推理流水线告警: {:?}", event);
+        }
+        delay(capture.frame_interval_ms());
+    }
+}
+
+/// 对一帧输入执行推理并解析检测结果
+/// 
+/// 推理失败时返回None；推理完成但结果解析失败时返回空列表
+fn detect_frame(ai_manager: &mut AIManager, input: &[f32]) -> Option<Vec<Detection>> {
+    let result = match ai_manager.infer(input) {
+        Ok(result) => result,
+        Err(e) => {
+            println!("AI推理失败: {}", e);
+            return None;
+        }
+    };
+    println!("AI推理完成，输出大小: {}", result.len());
+    
+    // 解析检测结果
+    let Some(yolo) = ai_manager.engine_as::<YoloV8Engine>(0) else {
+        return Some(Vec::new());
+    };
+    match yolo.postprocess_detections(&result) {
+        Ok(detections) => {
+            println!("检测到 {} 个目标", detections.len());
+            
+            for detection in &detections {
+                println!("目标: 类别={}, 置信度={:.2}%, 位置=({:.1},{:.1},{:.1},{:.1})", 
+                    detection.class_id, 
+                    detection.confidence * 100.0,
+                    detection.bbox.x, detection.bbox.y, 
+                    detection.bbox.width, detection.bbox.height
+                );
+            }
+            Some(detections)
+        }
+        Err(e) => {
+            println!("检测结果解析失败: {}", e);
+            Some(Vec::new())
+        }
     }
 }
 
@@ -197,6 +247,11 @@ fn demo_drivers() {
     DYNAMIC_PRIORITY_MANAGER.record_interrupt(27, 50); // 定时器中断，延迟50us
     DYNAMIC_PRIORITY_MANAGER.record_interrupt(32, 100); // UART中断，延迟100us
     
+    if let Some(stats) = DYNAMIC_PRIORITY_MANAGER.get_interrupt_stats(27) {
+        println!("定时器中断: 次数={}, 平均延迟={}us, 直方图={:?}",
+                 stats.count, stats.mean_latency_us, stats.histogram);
+    }
+    
     println!("中断优先级管理演示完成");
 }
 
@@ -213,7 +268,5 @@ fn panic(info: &core::panic::PanicInfo) -> ! {
         );
     }
     
-    loop {
-        starry_kernel::halt();
-    }
+    starry_kernel::handle_panic_action()
 }
\ No newline at end of file
@@ -2,7 +2,10 @@
 //! 
 //! 提供视觉和语音的多模态AI融合功能
 
-use crate::{AIError, DriverError};
+use crate::{AIError, AppEvent, DetectionResult, DriverError};
+use crate::action_dispatch::ActionDispatcher;
+use crate::detection_tee::DetectionTee;
+use alloc::collections::VecDeque;
 use alloc::string::String;
 use alloc::vec::Vec;
 use core::fmt;
@@ -15,6 +18,16 @@ pub struct MultimodalFusionApp {
     yolo_optimizer: YOLOv8Optimizer,
     speech_manager: SpeechInteractionManager,
     fusion_enabled: bool,
+    dispatcher: ActionDispatcher,
+    detection_tee: DetectionTee,
+}
+
+/// 融合决策
+#[derive(Debug, Clone, PartialEq)]
+pub struct FusedDecision {
+    /// 动作名称，由`ActionDispatcher`映射到执行器
+    pub action: String,
+    pub confidence: f32,
 }
 
 /// 多模态融合结果
@@ -24,6 +37,7 @@ pub struct FusionResult {
     pub speech_intent: Option<String>,
     pub fused_command: String,
     pub confidence: f32,
+    pub decision: Option<FusedDecision>,
 }
 
 /// 多模态融合错误
@@ -58,6 +72,8 @@ impl MultimodalFusionApp {
             yolo_optimizer: YOLOv8Optimizer::new(yolo_params),
             speech_manager: SpeechInteractionManager::new(),
             fusion_enabled: true,
+            dispatcher: ActionDispatcher::new(),
+            detection_tee: DetectionTee::new(),
         }
     }
     
@@ -86,6 +102,9 @@ impl MultimodalFusionApp {
         let visual_detections = self.process_visual_input(image_data, image_width, image_height)
             .map_err(FusionError::VisualError)?;
         
+        // 分发检测结果，单个消费者失败不影响融合流程
+        self.detection_tee.publish(&visual_detections);
+        
         // 语音处理
         let speech_intent = if let Some(text) = speech_text {
             let nlu_result = self.speech_manager.engine.understand_text(text)
@@ -102,14 +121,47 @@ impl MultimodalFusionApp {
         // 计算融合置信度
         let confidence = self.calculate_fusion_confidence(&visual_detections, speech_intent.as_deref());
         
+        // 生成决策并交由执行器执行
+        let decision = Self::decide_action(&visual_detections, speech_intent.as_deref())
+            .map(|action| FusedDecision { action: String::from(action), confidence });
+        if let Some(decision) = &decision {
+            self.dispatcher.dispatch(decision)
+                .map_err(|e| FusionError::FusionError(format!("动作执行失败: {:?}", e)))?;
+        }
+        
         Ok(FusionResult {
             visual_detections,
             speech_intent,
             fused_command,
             confidence,
+            decision,
         })
     }
     
+    /// 动作分发器，用于注册执行器和动作映射
+    pub fn dispatcher_mut(&mut self) -> &mut ActionDispatcher {
+        &mut self.dispatcher
+    }
+    
+    /// 检测结果分发器，用于注册显示、遥测等消费者
+    pub fn detection_tee_mut(&mut self) -> &mut DetectionTee {
+        &mut self.detection_tee
+    }
+    
+    /// 根据融合信息确定需要执行的动作
+    fn decide_action(detections: &[Detection], speech_intent: Option<&str>) -> Option<&'static str> {
+        match speech_intent {
+            Some("control_light") => {
+                if detections.iter().any(|d| d.class_name.contains("person")) {
+                    Some("turn_on_light")
+                } else {
+                    Some("turn_off_light")
+                }
+            }
+            _ => None,
+        }
+    }
+    
     /// 处理视觉输入
     fn process_visual_input(
         &mut self, 
@@ -238,6 +290,10 @@ pub struct FusionConfig {
     pub speech_weight: f32,
     pub fusion_threshold: f32,
     pub enable_cross_modal: bool,
+    /// 语音指令关联视觉检测的时间窗口 (ms)，早于语音开始超过该时长的检测不参与关联
+    pub window_ms: u64,
+    /// 保留的视觉检测历史条数
+    pub history_capacity: usize,
 }
 
 impl Default for FusionConfig {
@@ -247,6 +303,190 @@ impl Default for FusionConfig {
             speech_weight: 0.4,
             fusion_threshold: 0.7,
             enable_cross_modal: true,
+            window_ms: DEFAULT_FUSION_WINDOW_MS,
+            history_capacity: DEFAULT_DETECTION_HISTORY,
         }
     }
+}
+
+/// 默认关联时间窗口 (ms)
+pub const DEFAULT_FUSION_WINDOW_MS: u64 = 1_500;
+
+/// 默认视觉检测历史条数
+pub const DEFAULT_DETECTION_HISTORY: usize = 32;
+
+/// 毫秒时钟
+pub type ClockFn = fn() -> u64;
+
+/// 由内核定时器计数换算的毫秒时间，定时器未配置时返回0
+pub fn timer_now_ms() -> u64 {
+    starry_kernel::uptime_millis()
+}
+
+/// 融合事件：语音意图及其指代的视觉目标
+#[derive(Debug, Clone)]
+pub struct FusedEvent {
+    pub intent: String,
+    /// 时间窗口内置信度最高的检测，没有视觉上下文时为None
+    pub referent: Option<DetectionResult>,
+    pub confidence: f32,
+}
+
+/// 带时间戳的视觉检测
+#[derive(Debug, Clone)]
+struct TimedDetection {
+    timestamp_ms: u64,
+    detection: DetectionResult,
+}
+
+/// 时间关联融合引擎
+///
+/// 保存最近的视觉检测，收到语音指令时从时间窗口内的检测中选出指代目标
+/// （如"那是什么" + 画面中的杯子）
+pub struct FusionEngine {
+    config: FusionConfig,
+    history: VecDeque<TimedDetection>,
+    clock: ClockFn,
+}
+
+impl FusionEngine {
+    /// 创建融合引擎，时间戳取自内核定时器
+    pub fn new(config: FusionConfig) -> Self {
+        Self {
+            history: VecDeque::with_capacity(config.history_capacity),
+            config,
+            clock: timer_now_ms,
+        }
+    }
+
+    /// 替换时钟（测试时使用模拟时钟）
+    pub fn set_clock(&mut self, clock: ClockFn) {
+        self.clock = clock;
+    }
+
+    /// 当前配置
+    pub fn config(&self) -> &FusionConfig {
+        &self.config
+    }
+
+    /// 历史中的检测条数
+    pub fn history_len(&self) -> usize {
+        self.history.len()
+    }
+
+    /// 处理应用事件：视觉检测记入历史，语音指令以当前时刻为语音窗口进行融合
+    pub fn handle_event(&mut self, event: &AppEvent) -> Option<FusedEvent> {
+        match event {
+            AppEvent::VisualDetection(detections) => {
+                self.record_detections(detections);
+                None
+            }
+            AppEvent::VoiceCommand(intent) => {
+                let now = (self.clock)();
+                Some(self.fuse(intent, now, now))
+            }
+            _ => None,
+        }
+    }
+
+    /// 以当前时间记录一批检测，超出容量时丢弃最早的记录
+    pub fn record_detections(&mut self, detections: &[DetectionResult]) {
+        let timestamp_ms = (self.clock)();
+        for detection in detections {
+            if self.history.len() >= self.config.history_capacity {
+                self.history.pop_front();
+            }
+            if self.config.history_capacity > 0 {
+                self.history.push_back(TimedDetection { timestamp_ms, detection: detection.clone() });
+            }
+        }
+    }
+
+    /// 将语音意图与`[utterance_start_ms - window_ms, utterance_end_ms]`内的检测关联
+    ///
+    /// 选取其中置信度最高的检测（同分取较新的）作为指代目标；
+    /// 融合置信度按配置权重组合语音（意图已确定，记为1）与目标置信度
+    pub fn fuse(&self, intent: &str, utterance_start_ms: u64, utterance_end_ms: u64) -> FusedEvent {
+        let window_start = utterance_start_ms.saturating_sub(self.config.window_ms);
+        let referent = self
+            .history
+            .iter()
+            .filter(|entry| (window_start..=utterance_end_ms).contains(&entry.timestamp_ms))
+            .fold(None::<&TimedDetection>, |best, entry| match best {
+                Some(best) if best.detection.confidence > entry.detection.confidence => Some(best),
+                _ => Some(entry),
+            })
+            .map(|entry| entry.detection.clone());
+
+        let visual_confidence = referent.as_ref().map_or(0.0, |detection| detection.confidence);
+        let confidence = (self.config.speech_weight + self.config.visual_weight * visual_confidence).min(1.0);
+        FusedEvent {
+            intent: String::from(intent),
+            referent,
+            confidence,
+        }
+    }
+}
+
+impl Default for FusionEngine {
+    fn default() -> Self {
+        Self::new(FusionConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BoundingBox;
+    use core::sync::atomic::{AtomicU64, Ordering};
+
+    static MOCK_NOW_MS: AtomicU64 = AtomicU64::new(0);
+
+    fn mock_clock() -> u64 {
+        MOCK_NOW_MS.load(Ordering::Relaxed)
+    }
+
+    fn detection(class_name: &str, confidence: f32) -> DetectionResult {
+        DetectionResult {
+            class_id: 0,
+            class_name: String::from(class_name),
+            confidence,
+            bounding_box: BoundingBox::new(0.5, 0.5, 0.2, 0.2),
+        }
+    }
+
+    #[test]
+    fn test_voice_intent_picks_best_detection_in_window() {
+        // 窗口内有杯子和人，指代目标取置信度较高的人；窗口外的旧检测不参与
+        let mut engine = FusionEngine::new(FusionConfig { window_ms: 1_000, ..FusionConfig::default() });
+        engine.set_clock(mock_clock);
+
+        MOCK_NOW_MS.store(100, Ordering::Relaxed);
+        engine.handle_event(&AppEvent::VisualDetection(vec![detection("dog", 0.95)]));
+        MOCK_NOW_MS.store(1_200, Ordering::Relaxed);
+        engine.handle_event(&AppEvent::VisualDetection(vec![detection("cup", 0.6), detection("person", 0.9)]));
+
+        MOCK_NOW_MS.store(1_500, Ordering::Relaxed);
+        let fused = engine.handle_event(&AppEvent::VoiceCommand(String::from("identify_object"))).unwrap();
+        assert_eq!(fused.intent, "identify_object");
+        assert_eq!(fused.referent.unwrap().class_name, "person");
+        assert!((fused.confidence - (0.4 + 0.6 * 0.9)).abs() < 1e-6);
+
+        // 语音从200ms开始时，100ms的狗落在窗口内
+        assert_eq!(engine.fuse("identify_object", 200, 300).referent.unwrap().class_name, "dog");
+    }
+
+    #[test]
+    fn test_no_visual_context_emits_intent_without_referent() {
+        // 检测早于窗口时只输出意图；历史按容量丢弃最早的记录
+        let mut engine = FusionEngine::new(FusionConfig { window_ms: 500, history_capacity: 2, ..FusionConfig::default() });
+        engine.set_clock(mock_clock);
+
+        engine.record_detections(&[detection("cup", 0.8), detection("book", 0.7), detection("chair", 0.6)]);
+        assert_eq!(engine.history_len(), 2);
+
+        let fused = engine.fuse("identify_object", 10_000, 10_200);
+        assert!(fused.referent.is_none());
+        assert!((fused.confidence - 0.4).abs() < 1e-6);
+    }
 }
\ No newline at end of file
@@ -3,6 +3,7 @@
 //! 基于Yolo-v8模型的目标检测应用实现
 
 use starry_ai::{AIManager, Detection, AIError};
+use starry_ai::yolo_v8::ClassLabels;
 use starry_drivers::{DriverManager, SensorData};
 use alloc::vec::Vec;
 use core::fmt;
@@ -12,6 +13,7 @@ pub struct ObjectDetectionApp {
     ai_manager: &'static mut AIManager,
     driver_manager: &'static mut DriverManager,
     is_running: bool,
+    labels: ClassLabels,
 }
 
 impl ObjectDetectionApp {
@@ -21,9 +23,15 @@ impl ObjectDetectionApp {
             ai_manager,
             driver_manager,
             is_running: false,
+            labels: ClassLabels::coco(),
         }
     }
     
+    /// 设置模型的类别名称表，需在`init`之前调用才会同步到推理引擎
+    pub fn set_labels(&mut self, labels: ClassLabels) {
+        self.labels = labels;
+    }
+    
     /// 初始化应用
     pub fn init(&mut self) -> Result<(), AppError> {
         // 初始化AI系统
@@ -50,7 +58,8 @@ impl ObjectDetectionApp {
         unsafe {
             if let Some(ai_manager) = &mut starry_ai::AI_MANAGER {
                 // 创建Yolo-v8引擎并注册
-                let yolo_engine = starry_ai::yolo_v8::create_yolo_v8_engine();
+                let mut yolo_engine = starry_ai::yolo_v8::create_yolo_v8_engine();
+                yolo_engine.set_labels(self.labels.clone());
                 ai_manager.register_engine(Box::new(yolo_engine));
                 
                 // 设置当前引擎
@@ -114,9 +123,10 @@ impl ObjectDetectionApp {
         // 简化实现 - 解析推理结果
         if inference_result.len() >= 6 {
             // 模拟一个检测结果
+            let class_id = 0;
             let detection = Detection {
-                class_id: 0,
-                class_name: "person",
+                class_id,
+                class_name: self.labels.label_for(class_id),
                 confidence: inference_result[4],
                 bbox: starry_ai::BoundingBox {
                     x: inference_result[0],
@@ -124,6 +134,7 @@ impl ObjectDetectionApp {
                     width: inference_result[2],
                     height: inference_result[3],
                 },
+                track_id: None,
             };
             
             // 过滤低置信度检测
@@ -0,0 +1,126 @@
+//! 推理流水线监控模块
+//!
+//! 每完成一次推理上报一次心跳，心跳间隔超过阈值时判定流水线停滞并发出告警
+
+use crate::SystemEvent;
+
+/// 默认停滞阈值 (ms)
+pub const DEFAULT_STALL_THRESHOLD_MS: u64 = 2_000;
+
+/// 流水线监控器
+///
+/// 时间戳由调用者传入，便于测试时使用模拟时钟
+pub struct PipelineMonitor {
+    stall_threshold_ms: u64,
+    last_heartbeat_ms: Option<u64>,
+    stalled: bool,
+    stall_count: u32,
+    watchdog: Option<fn()>,
+}
+
+impl PipelineMonitor {
+    /// 创建新的流水线监控器
+    pub fn new(stall_threshold_ms: u64) -> Self {
+        Self {
+            stall_threshold_ms,
+            last_heartbeat_ms: None,
+            stalled: false,
+            stall_count: 0,
+            watchdog: None,
+        }
+    }
+
+    /// 停滞时触发看门狗复位
+    pub fn enable_watchdog(&mut self) {
+        self.watchdog = Some(starry_kernel::arm_watchdog_reset);
+    }
+
+    /// 设置停滞时调用的看门狗钩子
+    pub fn set_watchdog_hook(&mut self, hook: Option<fn()>) {
+        self.watchdog = hook;
+    }
+
+    /// 上报一次推理完成心跳
+    pub fn heartbeat(&mut self, now_ms: u64) {
+        self.last_heartbeat_ms = Some(now_ms);
+        self.stalled = false;
+    }
+
+    /// 检查流水线状态
+    ///
+    /// 首次检测到停滞时返回`SystemEvent::PipelineStalled`，
+    /// 之后直到心跳恢复前不再重复告警
+    pub fn check(&mut self, now_ms: u64) -> Option<SystemEvent> {
+        let last = self.last_heartbeat_ms?;
+        let elapsed_ms = now_ms.saturating_sub(last);
+        if elapsed_ms <= self.stall_threshold_ms || self.stalled {
+            return None;
+        }
+
+        self.stalled = true;
+        self.stall_count += 1;
+        if let Some(watchdog) = self.watchdog {
+            watchdog();
+        }
+        Some(SystemEvent::PipelineStalled { elapsed_ms })
+    }
+
+    /// 当前是否处于停滞状态
+    pub fn is_stalled(&self) -> bool {
+        self.stalled
+    }
+
+    /// 累计停滞次数
+    pub fn stall_count(&self) -> u32 {
+        self.stall_count
+    }
+}
+
+impl Default for PipelineMonitor {
+    fn default() -> Self {
+        Self::new(DEFAULT_STALL_THRESHOLD_MS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::sync::atomic::{AtomicU32, Ordering};
+
+    #[test]
+    fn test_regular_heartbeats_no_alarm() {
+        // 心跳间隔在阈值内时不告警
+        let mut monitor = PipelineMonitor::new(100);
+        assert!(monitor.check(1_000).is_none());
+
+        for frame in 0..50u64 {
+            let now = frame * 33;
+            monitor.heartbeat(now);
+            assert!(monitor.check(now + 30).is_none());
+        }
+        assert_eq!(monitor.stall_count(), 0);
+    }
+
+    #[test]
+    fn test_stall_alarm_emitted_once_until_resumed() {
+        // 停滞只告警一次并触发看门狗钩子，心跳恢复后可再次告警
+        static WATCHDOG_CALLS: AtomicU32 = AtomicU32::new(0);
+        let mut monitor = PipelineMonitor::new(100);
+        monitor.set_watchdog_hook(Some(|| {
+            WATCHDOG_CALLS.fetch_add(1, Ordering::Relaxed);
+        }));
+
+        monitor.heartbeat(0);
+        assert!(matches!(monitor.check(150), Some(SystemEvent::PipelineStalled { elapsed_ms: 150 })));
+        assert!(monitor.check(300).is_none());
+        assert!(monitor.is_stalled());
+
+        monitor.heartbeat(400);
+        assert!(!monitor.is_stalled());
+        assert!(monitor.check(450).is_none());
+        assert!(monitor.check(600).is_some());
+
+        assert_eq!(monitor.stall_count(), 2);
+        assert_eq!(WATCHDOG_CALLS.load(Ordering::Relaxed), 2);
+    }
+}
@@ -0,0 +1,275 @@
+//! 检测结果后处理模块
+//!
+//! 按类别执行非极大值抑制。提供同步、异步（类别之间让出执行权）
+//! 以及分派到A55效率核的工作窃取三种执行方式，三者输出完全一致
+
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use common::{class_groups, group_nms, per_class_nms, BoundingBox, Detection};
+use starry_drivers::async_runtime::yield_now;
+use starry_kernel::cpu::{CoreId, ALL_CORES};
+use starry_kernel::gic;
+use starry_kernel::sync::IrqMutex;
+
+/// 默认NMS交并比阈值
+pub const DEFAULT_IOU_THRESHOLD: f32 = 0.45;
+
+/// 检测结果拆分为NMS所需的框和置信度
+fn boxes_and_scores(detections: &[Detection]) -> (Vec<BoundingBox>, Vec<f32>) {
+    detections.iter().map(|d| (d.bbox, d.confidence)).unzip()
+}
+
+/// 同步后处理：按类别ID升序输出，类别内按置信度降序、置信度相同时按框位置排序，
+/// 输出顺序与输入顺序无关
+pub fn postprocess(detections: Vec<Detection>, iou_threshold: f32) -> Vec<Detection> {
+    let (boxes, scores) = boxes_and_scores(&detections);
+    let classes: Vec<u32> = detections.iter().map(|d| d.class_id).collect();
+    per_class_nms(&boxes, &scores, &classes, iou_threshold)
+        .into_iter()
+        .map(|i| detections[i].clone())
+        .collect()
+}
+
+/// 异步后处理：每处理完一个类别让出一次执行权，使执行器可以穿插相机采集等任务
+pub async fn postprocess_async(detections: Vec<Detection>, iou_threshold: f32) -> Vec<Detection> {
+    let (boxes, scores) = boxes_and_scores(&detections);
+    let classes: Vec<u32> = detections.iter().map(|d| d.class_id).collect();
+    let mut results = Vec::with_capacity(detections.len());
+    for group in class_groups(&classes) {
+        results.extend(group_nms(&boxes, &scores, &group, iou_threshold).into_iter().map(|i| detections[i].clone()));
+        yield_now().await;
+    }
+    results
+}
+
+/// 多核NMS工作队列
+///
+/// 各类别作为独立工作项，由最多`max_workers`个工作者通过原子计数器领取。
+/// 结果按类别槽位保存，`completed`记录已写回的工作项数，合并前等待所有已领取的工作项完成，
+/// 因此输出与领取顺序和工作者数量无关
+pub struct NmsWorkQueue {
+    detections: Vec<Detection>,
+    boxes: Vec<BoundingBox>,
+    scores: Vec<f32>,
+    groups: Vec<Vec<usize>>,
+    /// 各类别保留框的下标，工作者与合并方共享
+    results: Vec<IrqMutex<Option<Vec<usize>>>>,
+    next: AtomicUsize,
+    completed: AtomicUsize,
+    workers: AtomicUsize,
+    max_workers: usize,
+    iou_threshold: f32,
+}
+
+impl NmsWorkQueue {
+    /// 创建新的工作队列
+    pub fn new(detections: Vec<Detection>, iou_threshold: f32, max_workers: usize) -> Self {
+        let (boxes, scores) = boxes_and_scores(&detections);
+        let classes: Vec<u32> = detections.iter().map(|d| d.class_id).collect();
+        let groups = class_groups(&classes);
+        let results = groups.iter().map(|_| IrqMutex::new(None)).collect();
+        Self {
+            detections,
+            boxes,
+            scores,
+            groups,
+            results,
+            next: AtomicUsize::new(0),
+            completed: AtomicUsize::new(0),
+            workers: AtomicUsize::new(0),
+            max_workers: max_workers.max(1),
+            iou_threshold,
+        }
+    }
+
+    /// 加入工作者，超出工作者上限时返回false
+    pub fn join(&self) -> bool {
+        self.workers
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                if n < self.max_workers { Some(n + 1) } else { None }
+            })
+            .is_ok()
+    }
+
+    /// 领取并处理一个类别，没有剩余工作项时返回false
+    pub fn steal_one(&self) -> bool {
+        let index = self.next.fetch_add(1, Ordering::AcqRel);
+        if index >= self.groups.len() {
+            return false;
+        }
+
+        let kept = group_nms(&self.boxes, &self.scores, &self.groups[index], self.iou_threshold);
+        *self.results[index].lock() = Some(kept);
+        self.completed.fetch_add(1, Ordering::AcqRel);
+        true
+    }
+
+    /// 工作者主循环：持续领取直到队列为空
+    pub fn run_worker(&self) {
+        while self.steal_one() {}
+    }
+
+    /// 是否所有工作项均已领取
+    pub fn is_drained(&self) -> bool {
+        self.next.load(Ordering::Acquire) >= self.groups.len()
+    }
+
+    /// 是否所有工作项均已处理完成
+    pub fn is_complete(&self) -> bool {
+        self.completed.load(Ordering::Acquire) >= self.groups.len()
+    }
+
+    /// 合并结果：调用者先领取剩余的工作项，再等待其他工作者手中的工作项完成
+    pub fn finish(&self) -> Vec<Detection> {
+        self.run_worker();
+        while !self.is_complete() {
+            core::hint::spin_loop();
+        }
+
+        let mut merged = Vec::new();
+        for slot in &self.results {
+            let kept = slot.lock().take().unwrap_or_default();
+            merged.extend(kept.into_iter().map(|i| self.detections[i].clone()));
+        }
+        merged
+    }
+}
+
+/// 当前分派给A55效率核的工作队列
+static DISPATCHED_QUEUE: IrqMutex<Option<Arc<NmsWorkQueue>>> = IrqMutex::new(None);
+
+/// 函数调用IPI在A55核心上执行的工作者入口
+fn dispatched_worker() {
+    let queue = DISPATCHED_QUEUE.lock().clone();
+    if let Some(queue) = queue {
+        if queue.join() {
+            queue.run_worker();
+        }
+    }
+}
+
+/// 多核后处理：通过函数调用IPI把类别分派给最多`max_workers`个A55效率核，
+/// 当前核心同时参与领取并合并结果，输出与`postprocess`一致
+///
+/// 目标核心有未完成的函数调用时跳过该核心，剩余工作由当前核心完成
+pub fn postprocess_parallel(detections: Vec<Detection>, iou_threshold: f32, max_workers: usize) -> Vec<Detection> {
+    let queue = Arc::new(NmsWorkQueue::new(detections, iou_threshold, max_workers));
+    *DISPATCHED_QUEUE.lock() = Some(queue.clone());
+
+    let current = CoreId::current();
+    ALL_CORES
+        .iter()
+        .filter(|&&core| core.is_efficiency_core() && core != current)
+        .take(max_workers)
+        .for_each(|&core| {
+            let _ = gic::call_function_on(core, dispatched_worker);
+        });
+
+    let results = queue.finish();
+    DISPATCHED_QUEUE.lock().take();
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::future::Future;
+    use core::pin::pin;
+    use core::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+    static VTABLE: RawWakerVTable = RawWakerVTable::new(
+        |_| RawWaker::new(core::ptr::null(), &VTABLE),
+        |_| {},
+        |_| {},
+        |_| {},
+    );
+
+    /// 生成多个类别、相互重叠的检测结果
+    fn sample_detections(classes: u32, per_class: u32) -> Vec<Detection> {
+        let mut detections = Vec::new();
+        for class_id in 0..classes {
+            for i in 0..per_class {
+                let offset = (i % 4) as f32 * 3.0 + (i / 4) as f32 * 50.0;
+                let confidence = 0.5 + ((i * 7 + class_id) % 10) as f32 * 0.04;
+                detections.push(Detection::new(
+                    class_id,
+                    "object",
+                    confidence,
+                    BoundingBox::new(100.0 + offset, 100.0, 20.0, 20.0),
+                ));
+            }
+        }
+        detections
+    }
+
+    fn same(a: &[Detection], b: &[Detection]) -> bool {
+        a.len() == b.len()
+            && a.iter().zip(b).all(|(x, y)| {
+                x.class_id == y.class_id && x.confidence == y.confidence && x.bbox == y.bbox
+            })
+    }
+
+    #[test]
+    fn test_equal_confidence_output_order_is_reproducible() {
+        // 置信度全部相同时，不同输入顺序的批量后处理输出顺序一致
+        let mut detections = sample_detections(3, 8);
+        for detection in detections.iter_mut() {
+            detection.confidence = 0.7;
+        }
+        let expected = postprocess(detections.clone(), DEFAULT_IOU_THRESHOLD);
+
+        for _ in 0..3 {
+            detections.reverse();
+            detections.rotate_left(5);
+            assert!(same(&postprocess(detections.clone(), DEFAULT_IOU_THRESHOLD), &expected));
+        }
+        let xs: Vec<f32> = expected.iter().filter(|d| d.class_id == 0).map(|d| d.bbox.x).collect();
+        assert!(xs.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn test_async_postprocess_yields_and_matches_sync() {
+        // 大量检测结果的异步后处理至少让出一次，结果与同步路径一致
+        let detections = sample_detections(8, 40);
+        let expected = postprocess(detections.clone(), DEFAULT_IOU_THRESHOLD);
+
+        let waker = unsafe { Waker::from_raw(RawWaker::new(core::ptr::null(), &VTABLE)) };
+        let mut cx = Context::from_waker(&waker);
+        let mut future = pin!(postprocess_async(detections, DEFAULT_IOU_THRESHOLD));
+
+        let mut pending = 0;
+        let result = loop {
+            match future.as_mut().poll(&mut cx) {
+                Poll::Ready(result) => break result,
+                Poll::Pending => pending += 1,
+            }
+        };
+
+        assert!(pending >= 1);
+        assert!(same(&result, &expected));
+        assert!(expected.len() < 8 * 40);
+    }
+
+    #[test]
+    fn test_work_stealing_is_deterministic() {
+        // 工作者交替领取的结果与同步路径一致，超出上限的工作者无法加入
+        let detections = sample_detections(5, 12);
+        let expected = postprocess(detections.clone(), DEFAULT_IOU_THRESHOLD);
+
+        let queue = NmsWorkQueue::new(detections, DEFAULT_IOU_THRESHOLD, 2);
+        assert!(queue.join() && queue.join());
+        assert!(!queue.join());
+
+        // 模拟两个核心交替领取，最后一项留给finish补做
+        assert!(queue.steal_one());
+        assert!(queue.steal_one());
+        assert!(queue.steal_one());
+        assert!(!queue.is_drained());
+        assert!(!queue.is_complete());
+
+        assert!(same(&queue.finish(), &expected));
+        assert!(queue.is_drained() && queue.is_complete());
+    }
+}
@@ -5,6 +5,7 @@
 #![no_std]
 
 use core::fmt::Write;
+use alloc::collections::VecDeque;
 use alloc::vec::Vec;
 use alloc::string::String;
 
@@ -35,9 +36,20 @@ pub struct PerformanceMetrics {
     pub power_consumption_w: f32,
 }
 
+/// 测试结果聚合统计（不受结果淘汰影响）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TestCounts {
+    pub total: usize,
+    pub passed: usize,
+    pub failed: usize,
+    pub skipped: usize,
+}
+
 /// 系统集成管理器
 pub struct SystemIntegrationManager {
-    test_results: Vec<IntegrationTestResult>,
+    test_results: VecDeque<IntegrationTestResult>,
+    max_results: Option<usize>,
+    counts: TestCounts,
     current_test: Option<&'static str>,
     start_time: u64,
     performance_cache: PerformanceMetrics,
@@ -47,13 +59,52 @@ impl SystemIntegrationManager {
     /// 创建新的系统集成管理器
     pub fn new() -> Self {
         Self {
-            test_results: Vec::with_capacity(20), // 预分配容量
+            test_results: VecDeque::with_capacity(20), // 预分配容量
+            max_results: None,
+            counts: TestCounts::default(),
             current_test: None,
             start_time: 0,
             performance_cache: PerformanceMetrics::default(),
         }
     }
     
+    /// 创建只保留最近`max_results`条详细结果的管理器（用于长时间浸泡测试）
+    pub fn with_max_results(max_results: usize) -> Self {
+        let mut manager = Self::new();
+        manager.max_results = Some(max_results);
+        manager
+    }
+    
+    /// 获取聚合统计
+    pub fn counts(&self) -> TestCounts {
+        self.counts
+    }
+    
+    /// 获取当前保留的详细结果
+    pub fn results(&self) -> impl Iterator<Item = &IntegrationTestResult> {
+        self.test_results.iter()
+    }
+    
+    /// 记录测试结果，超出容量时淘汰最旧的结果
+    fn record_result(&mut self, result: IntegrationTestResult) {
+        self.counts.total += 1;
+        match result.status {
+            TestStatus::Passed => self.counts.passed += 1,
+            TestStatus::Failed => self.counts.failed += 1,
+            TestStatus::Skipped => self.counts.skipped += 1,
+        }
+        
+        if let Some(max) = self.max_results {
+            if max == 0 {
+                return;
+            }
+            if self.test_results.len() >= max {
+                self.test_results.pop_front();
+            }
+        }
+        self.test_results.push_back(result);
+    }
+    
     /// 开始测试
     pub fn start_test(&mut self, test_name: &'static str) {
         self.current_test = Some(test_name);
@@ -76,7 +127,7 @@ impl SystemIntegrationManager {
                 performance_metrics: self.get_current_performance_metrics(),
             };
             
-            self.test_results.push(result);
+            self.record_result(result);
             
             match status {
                 TestStatus::Passed => kernel::println!("测试通过: {} ({}ms)", test_name, execution_time_ms),
@@ -94,7 +145,7 @@ impl SystemIntegrationManager {
         if self.should_refresh_cache() {
             // 从系统监控模块获取真实性能数据
             let cpu_usage = kernel::cpu::get_usage_percent();
-            let memory_usage = kernel::memory::get_usage_mb();
+            let memory_usage = kernel::memory::report().used_mb();
             let inference_time = ai::npu::get_last_inference_time();
             let power_consumption = kernel::power::get_current_power();
             
@@ -112,7 +163,7 @@ impl SystemIntegrationManager {
     /// 判断是否需要刷新缓存
     fn should_refresh_cache(&self) -> bool {
         // 简单的缓存策略：每5次调用刷新一次
-        self.test_results.len() % 5 == 0
+        self.counts.total % 5 == 0
     }
     
     /// 运行完整的系统集成测试套件
@@ -145,7 +196,7 @@ impl SystemIntegrationManager {
         self.end_test(fusion_result.status, fusion_result.error_message);
         
         kernel::println!("=== StarryOS 系统集成测试完成 ===");
-        self.test_results.clone()
+        self.test_results.iter().cloned().collect()
     }
     
     /// 测试内核组件
@@ -484,10 +535,10 @@ impl SystemIntegrationManager {
     fn generate_test_report(&self) {
         kernel::println!("\n=== 系统集成测试报告 ===");
         
-        let total_tests = self.test_results.len();
-        let passed_tests = self.test_results.iter().filter(|r| r.status == TestStatus::Passed).count();
-        let failed_tests = self.test_results.iter().filter(|r| r.status == TestStatus::Failed).count();
-        let skipped_tests = self.test_results.iter().filter(|r| r.status == TestStatus::Skipped).count();
+        let total_tests = self.counts.total;
+        let passed_tests = self.counts.passed;
+        let failed_tests = self.counts.failed;
+        let skipped_tests = self.counts.skipped;
         
         kernel::println!("测试统计:");
         kernel::println!("- 总测试数: {}", total_tests);
@@ -495,7 +546,11 @@ impl SystemIntegrationManager {
         kernel::println!("- 失败测试: {} ({:.1}%)", failed_tests, (failed_tests as f32 / total_tests as f32) * 100.0);
         kernel::println!("- 跳过测试: {} ({:.1}%)", skipped_tests, (skipped_tests as f32 / total_tests as f32) * 100.0);
         
-        kernel::println!("\n详细测试结果:");
+        if self.test_results.len() < total_tests {
+            kernel::println!("\n详细测试结果 (最近{}条):", self.test_results.len());
+        } else {
+            kernel::println!("\n详细测试结果:");
+        }
         for result in &self.test_results {
             let status_str = match result.status {
                 TestStatus::Passed => "通过",
@@ -598,4 +653,48 @@ fn demonstrate_industrial_inspection_scenario() {
     kernel::println!("3. 质量评估: 划痕长度2mm，深度0.1mm");
     kernel::println!("4. 自动分类: 标记为B级产品");
     kernel::println!("5. 数据记录: 保存检测结果到数据库");
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_result(status: TestStatus) -> IntegrationTestResult {
+        IntegrationTestResult {
+            test_name: "soak",
+            status,
+            execution_time_ms: 1,
+            error_message: None,
+            performance_metrics: PerformanceMetrics::default(),
+        }
+    }
+
+    #[test]
+    fn test_bounded_results_keep_counts() {
+        // 超出容量后聚合统计仍然准确
+        let mut manager = SystemIntegrationManager::with_max_results(3);
+        for i in 0..10 {
+            let status = if i % 2 == 0 { TestStatus::Passed } else { TestStatus::Failed };
+            manager.record_result(make_result(status));
+        }
+
+        assert_eq!(manager.results().count(), 3);
+        let counts = manager.counts();
+        assert_eq!(counts.total, 10);
+        assert_eq!(counts.passed, 5);
+        assert_eq!(counts.failed, 5);
+        assert_eq!(counts.skipped, 0);
+    }
+
+    #[test]
+    fn test_unbounded_results_by_default() {
+        // 默认不限制保留数量
+        let mut manager = SystemIntegrationManager::new();
+        for _ in 0..25 {
+            manager.record_result(make_result(TestStatus::Skipped));
+        }
+
+        assert_eq!(manager.results().count(), 25);
+        assert_eq!(manager.counts().skipped, 25);
+    }
+}
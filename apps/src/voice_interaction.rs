@@ -68,15 +68,10 @@ impl VoiceInteractionApp {
         self.speech_manager.engine.load_recognition_model(&[])
             .map_err(VoiceInteractionError::AIError)?;
         
-        // 设置音频配置
-        let config = AudioConfig {
-            sample_rate: 16000,
-            channels: 1,
-            bit_depth: 16,
-            buffer_size: 1600,
-        };
-        
-        // 这里应该设置音频驱动的配置
+        // 设置音频配置，采集、VAD和AGC按同一帧长工作
+        let config = AudioConfig::default();
+        self.audio_manager.set_config(config)
+            .map_err(VoiceInteractionError::AudioError)?;
         
         Ok(())
     }
@@ -2,8 +2,27 @@
 //! 
 //! 提供所有模块共享的基础数据结构
 
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
 use core::fmt;
 
+use crate::error::{DriverError, Error};
+
+/// 交并比度量类型
+/// 
+/// 用于非极大值抑制时选择重叠度计算方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IouType {
+    /// 标准交并比
+    #[default]
+    Iou,
+    /// 广义交并比（考虑最小外接框）
+    GIou,
+    /// 距离交并比（考虑中心点距离）
+    DIou,
+}
+
 /// 边界框
 /// 
 /// 用于表示二维空间中的矩形区域，广泛应用于目标检测等场景
@@ -77,10 +96,105 @@ impl BoundingBox {
         intersection_area / union_area
     }
     
+    /// 按指定度量类型计算两个边界框的重叠度
+    /// 
+    /// GIoU和DIoU的取值范围为(-1, 1]，不相交的边界框也能得到区分度
+    pub fn calculate_iou_with(&self, other: &BoundingBox, iou_type: IouType) -> f32 {
+        if iou_type == IouType::Iou {
+            return self.calculate_iou(other);
+        }
+        
+        let left = self.x - self.width / 2.0;
+        let right = self.x + self.width / 2.0;
+        let top = self.y - self.height / 2.0;
+        let bottom = self.y + self.height / 2.0;
+        
+        let other_left = other.x - other.width / 2.0;
+        let other_right = other.x + other.width / 2.0;
+        let other_top = other.y - other.height / 2.0;
+        let other_bottom = other.y + other.height / 2.0;
+        
+        // 计算交集（不相交时为0）
+        let intersection_width = (right.min(other_right) - left.max(other_left)).max(0.0);
+        let intersection_height = (bottom.min(other_bottom) - top.max(other_top)).max(0.0);
+        let intersection_area = intersection_width * intersection_height;
+        let union_area = self.area() + other.area() - intersection_area;
+        
+        if union_area <= 0.0 {
+            return 0.0;
+        }
+        let iou = intersection_area / union_area;
+        
+        // 最小外接框
+        let enclose_width = right.max(other_right) - left.min(other_left);
+        let enclose_height = bottom.max(other_bottom) - top.min(other_top);
+        
+        match iou_type {
+            IouType::GIou => {
+                let enclose_area = enclose_width * enclose_height;
+                if enclose_area <= 0.0 {
+                    return iou;
+                }
+                iou - (enclose_area - union_area) / enclose_area
+            }
+            IouType::DIou => {
+                let diagonal_sq = enclose_width * enclose_width + enclose_height * enclose_height;
+                if diagonal_sq <= 0.0 {
+                    return iou;
+                }
+                let dx = self.x - other.x;
+                let dy = self.y - other.y;
+                iou - (dx * dx + dy * dy) / diagonal_sq
+            }
+            IouType::Iou => iou,
+        }
+    }
+    
     /// 检查边界框是否有效（宽度和高度为正数）
     pub fn is_valid(&self) -> bool {
         self.width > 0.0 && self.height > 0.0
     }
+    
+    /// 检查点是否位于边界框内（含边界）
+    pub fn contains_point(&self, x: f32, y: f32) -> bool {
+        (x - self.x).abs() <= self.width / 2.0 && (y - self.y).abs() <= self.height / 2.0
+    }
+    
+    /// 按位置比较边界框：依次比较y、x、宽度、高度（升序）
+    /// 
+    /// 用作置信度相同时的确定性排序依据
+    pub fn position_cmp(&self, other: &BoundingBox) -> Ordering {
+        self.y.total_cmp(&other.y)
+            .then_with(|| self.x.total_cmp(&other.x))
+            .then_with(|| self.width.total_cmp(&other.width))
+            .then_with(|| self.height.total_cmp(&other.height))
+    }
+    
+    /// 编码为小端字节序：x | y | width | height（各4字节）
+    pub fn to_le_bytes(&self) -> [u8; BOUNDING_BOX_ENCODED_LEN] {
+        let mut bytes = [0u8; BOUNDING_BOX_ENCODED_LEN];
+        for (chunk, value) in bytes.chunks_exact_mut(4).zip([self.x, self.y, self.width, self.height]) {
+            chunk.copy_from_slice(&value.to_le_bytes());
+        }
+        bytes
+    }
+    
+    /// 从小端字节序解码，面积重新计算
+    pub fn from_le_bytes(bytes: [u8; BOUNDING_BOX_ENCODED_LEN]) -> Self {
+        let field = |i: usize| f32::from_le_bytes([bytes[i], bytes[i + 1], bytes[i + 2], bytes[i + 3]]);
+        Self::new(field(0), field(4), field(8), field(12))
+    }
+}
+
+/// 边界框编码长度
+pub const BOUNDING_BOX_ENCODED_LEN: usize = 16;
+
+/// 检测结果编码的固定部分长度：类别ID、置信度、边界框、跟踪ID标志和值、类别名称长度
+const DETECTION_FIXED_LEN: usize = 4 + 4 + BOUNDING_BOX_ENCODED_LEN + 1 + 4 + 1;
+
+/// 编码数据格式错误
+fn malformed() -> Error {
+    Error::DriverError(DriverError::DataFormatError)
 }
 
 impl fmt::Display for BoundingBox {
@@ -96,32 +210,208 @@ impl fmt::Display for BoundingBox {
 #[derive(Debug, Clone)]
 pub struct Detection {
     pub class_id: u32,
-    pub class_name: &'static str,
+    /// 类别名称，内置类别表为静态字符串，运行时加载的类别表为自有字符串
+    pub class_name: Cow<'static, str>,
     pub confidence: f32,
     pub bbox: BoundingBox,
+    /// 跟踪ID，未启用目标跟踪时为None
+    pub track_id: Option<u32>,
 }
 
 impl Detection {
     /// 创建新的检测结果
-    pub fn new(class_id: u32, class_name: &'static str, confidence: f32, bbox: BoundingBox) -> Self {
+    pub fn new(class_id: u32, class_name: impl Into<Cow<'static, str>>, confidence: f32, bbox: BoundingBox) -> Self {
         Self {
             class_id,
-            class_name,
+            class_name: class_name.into(),
             confidence,
             bbox,
+            track_id: None,
         }
     }
     
+    /// 设置跟踪ID
+    pub fn with_track_id(mut self, track_id: u32) -> Self {
+        self.track_id = Some(track_id);
+        self
+    }
+    
     /// 检查检测结果是否有效
     pub fn is_valid(&self) -> bool {
         self.confidence >= 0.0 && self.confidence <= 1.0 && self.bbox.is_valid()
     }
+    
+    /// 检测结果的排序规则
+    /// 
+    /// 按置信度降序；置信度相同时按类别ID升序，再按边界框位置升序。
+    /// 该顺序与输入顺序无关，NMS、Top-K和批量后处理的输出均以此保证可复现
+    pub fn ranking_cmp(&self, other: &Detection) -> Ordering {
+        other.confidence.total_cmp(&self.confidence)
+            .then_with(|| self.class_id.cmp(&other.class_id))
+            .then_with(|| self.bbox.position_cmp(&other.bbox))
+    }
+    
+    /// 编码并追加到`out`
+    /// 
+    /// 格式（小端）：类别ID u32 | 置信度 f32 | 边界框 | 是否有跟踪ID u8 | 跟踪ID u32 |
+    /// 类别名称长度 u8 | 类别名称（UTF-8，超过255字节时在字符边界截断）
+    pub fn encode_into(&self, out: &mut Vec<u8>) {
+        let mut name_len = self.class_name.len().min(u8::MAX as usize);
+        while !self.class_name.is_char_boundary(name_len) {
+            name_len -= 1;
+        }
+        
+        out.reserve(DETECTION_FIXED_LEN + name_len);
+        out.extend_from_slice(&self.class_id.to_le_bytes());
+        out.extend_from_slice(&self.confidence.to_le_bytes());
+        out.extend_from_slice(&self.bbox.to_le_bytes());
+        out.push(self.track_id.is_some() as u8);
+        out.extend_from_slice(&self.track_id.unwrap_or(0).to_le_bytes());
+        out.push(name_len as u8);
+        out.extend_from_slice(&self.class_name.as_bytes()[..name_len]);
+    }
+    
+    /// 编码为字节序列
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.encode_into(&mut out);
+        out
+    }
+    
+    /// 从字节序列开头解码一个检测结果，返回检测结果和消耗的字节数
+    /// 
+    /// 类别名称为`&'static str`，由`resolve_name(类别ID, 收到的名称)`映射为本地名称，
+    /// 例如查本地类别表。长度不足、标志非法或名称不是有效UTF-8时返回`DataFormatError`
+    pub fn decode(
+        bytes: &[u8],
+        mut resolve_name: impl FnMut(u32, &str) -> &'static str,
+    ) -> Result<(Self, usize), Error> {
+        if bytes.len() < DETECTION_FIXED_LEN {
+            return Err(malformed());
+        }
+        let name_len = bytes[DETECTION_FIXED_LEN - 1] as usize;
+        let end = DETECTION_FIXED_LEN + name_len;
+        let name = bytes.get(DETECTION_FIXED_LEN..end).ok_or_else(malformed)?;
+        let name = core::str::from_utf8(name).map_err(|_| malformed())?;
+        
+        let word = |i: usize| [bytes[i], bytes[i + 1], bytes[i + 2], bytes[i + 3]];
+        let class_id = u32::from_le_bytes(word(0));
+        let mut bbox = [0u8; BOUNDING_BOX_ENCODED_LEN];
+        bbox.copy_from_slice(&bytes[8..8 + BOUNDING_BOX_ENCODED_LEN]);
+        let track_id = match bytes[24] {
+            0 => None,
+            1 => Some(u32::from_le_bytes(word(25))),
+            _ => return Err(malformed()),
+        };
+        
+        let detection = Self {
+            class_id,
+            class_name: Cow::Borrowed(resolve_name(class_id, name)),
+            confidence: f32::from_le_bytes(word(4)),
+            bbox: BoundingBox::from_le_bytes(bbox),
+            track_id,
+        };
+        Ok((detection, end))
+    }
+}
+
+/// 批量编码检测结果：数量 u32（小端）后依次为各检测结果
+pub fn encode_detections(detections: &[Detection]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(4 + detections.len() * (DETECTION_FIXED_LEN + 8));
+    out.extend_from_slice(&(detections.len() as u32).to_le_bytes());
+    for detection in detections {
+        detection.encode_into(&mut out);
+    }
+    out
+}
+
+/// 批量解码检测结果，数据须恰好包含头部声明数量的检测结果
+pub fn decode_detections(
+    bytes: &[u8],
+    mut resolve_name: impl FnMut(u32, &str) -> &'static str,
+) -> Result<Vec<Detection>, Error> {
+    let header = bytes.get(..4).ok_or_else(malformed)?;
+    let count = u32::from_le_bytes([header[0], header[1], header[2], header[3]]) as usize;
+    
+    // 数量来自对端，预分配不超过数据实际能容纳的条数
+    let mut detections = Vec::with_capacity(count.min(bytes.len() / DETECTION_FIXED_LEN));
+    let mut offset = 4;
+    for _ in 0..count {
+        let (detection, used) = Detection::decode(&bytes[offset..], &mut resolve_name)?;
+        detections.push(detection);
+        offset += used;
+    }
+    
+    if offset != bytes.len() {
+        return Err(malformed());
+    }
+    Ok(detections)
+}
+
+/// 感兴趣区域掩码
+/// 
+/// 由多个矩形区域组成，检测框中心落在任一区域内即视为有效。
+/// 未启用或不含任何区域时不做过滤。
+#[derive(Debug, Clone)]
+pub struct RoiMask {
+    regions: Vec<BoundingBox>,
+    enabled: bool,
+}
+
+impl RoiMask {
+    /// 创建新的空掩码（默认启用）
+    pub fn new() -> Self {
+        Self {
+            regions: Vec::new(),
+            enabled: true,
+        }
+    }
+    
+    /// 添加矩形区域
+    pub fn add_region(&mut self, region: BoundingBox) {
+        self.regions.push(region);
+    }
+    
+    /// 清除所有区域
+    pub fn clear(&mut self) {
+        self.regions.clear();
+    }
+    
+    /// 获取所有区域
+    pub fn regions(&self) -> &[BoundingBox] {
+        &self.regions
+    }
+    
+    /// 启用/禁用掩码
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+    
+    /// 掩码是否启用
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+    
+    /// 检查点是否被掩码允许
+    pub fn allows(&self, x: f32, y: f32) -> bool {
+        if !self.enabled || self.regions.is_empty() {
+            return true;
+        }
+        self.regions.iter().any(|region| region.contains_point(x, y))
+    }
+}
+
+impl Default for RoiMask {
+    /// 与`new`一致：空掩码，默认启用
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 /// 传感器数据
 /// 
 /// 用于表示环境传感器采集的数据
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct SensorData {
     pub temperature: Option<f32>,  // 温度 (°C)
     pub humidity: Option<f32>,     // 湿度 (%)
@@ -141,6 +431,102 @@ impl SensorData {
             gyroscope: None,
         }
     }
+    
+    /// 记录一次传感器读数，覆盖同类数据
+    pub fn record(&mut self, reading: SensorReading) {
+        match reading {
+            SensorReading::Temperature(value) => self.temperature = Some(value),
+            SensorReading::Humidity(value) => self.humidity = Some(value),
+            SensorReading::Light(value) => self.light_level = Some(value),
+            SensorReading::Acceleration(x, y, z) => self.acceleration = Some((x, y, z)),
+            SensorReading::Gyroscope(x, y, z) => self.gyroscope = Some((x, y, z)),
+        }
+    }
+}
+
+/// 单次传感器读数
+/// 
+/// 由传感器驱动产生，可汇总到`SensorData`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SensorReading {
+    Temperature(f32),        // 温度 (单位由驱动声明，规范化后为°C)
+    Humidity(f32),          // 湿度 (%)
+    Light(f32),             // 光照强度 (lux)
+    Acceleration(f32, f32, f32), // 加速度 (x, y, z)
+    Gyroscope(f32, f32, f32),    // 陀螺仪 (x, y, z)
+}
+
+impl SensorReading {
+    /// 规范化为标准单位（温度转换为°C，其余数据原样保留）
+    pub fn normalize(self, unit: TemperatureUnit) -> Self {
+        match self {
+            SensorReading::Temperature(value) => SensorReading::Temperature(unit.to_celsius(value)),
+            other => other,
+        }
+    }
+}
+
+/// 带时间戳的传感器读数
+///
+/// 时间戳为读取时刻自启动以来的微秒数，用于多传感器融合时对齐数据
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimedReading {
+    pub data: SensorReading,
+    pub timestamp_us: u64,
+    /// 传感器在管理器中的注册序号
+    pub sensor_id: u8,
+}
+
+impl From<SensorReading> for SensorData {
+    fn from(reading: SensorReading) -> Self {
+        let mut data = SensorData::new();
+        data.record(reading);
+        data
+    }
+}
+
+impl TryFrom<SensorData> for SensorReading {
+    type Error = DriverError;
+    
+    /// 仅含一项数据时转换为对应读数，否则返回`DataFormatError`
+    fn try_from(data: SensorData) -> Result<Self, Self::Error> {
+        let readings = [
+            data.temperature.map(SensorReading::Temperature),
+            data.humidity.map(SensorReading::Humidity),
+            data.light_level.map(SensorReading::Light),
+            data.acceleration.map(|(x, y, z)| SensorReading::Acceleration(x, y, z)),
+            data.gyroscope.map(|(x, y, z)| SensorReading::Gyroscope(x, y, z)),
+        ];
+        let mut present = readings.into_iter().flatten();
+        match (present.next(), present.next()) {
+            (Some(reading), None) => Ok(reading),
+            _ => Err(DriverError::DataFormatError),
+        }
+    }
+}
+
+/// 温度单位
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemperatureUnit {
+    Celsius,
+    Fahrenheit,
+    Kelvin,
+}
+
+impl TemperatureUnit {
+    /// 将该单位的温度值转换为摄氏度
+    pub fn to_celsius(self, value: f32) -> f32 {
+        match self {
+            TemperatureUnit::Celsius => value,
+            TemperatureUnit::Fahrenheit => (value - 32.0) * 5.0 / 9.0,
+            TemperatureUnit::Kelvin => value - 273.15,
+        }
+    }
+    
+    /// 将该单位的温度值转换为华氏度
+    pub fn to_fahrenheit(self, value: f32) -> f32 {
+        self.to_celsius(value) * 9.0 / 5.0 + 32.0
+    }
 }
 
 /// 系统配置
@@ -191,4 +577,215 @@ impl TaskInfo {
             priority,
         }
     }
-}
\ No newline at end of file
+}
+
+/// 定长环形缓冲区
+///
+/// 先进先出的有界队列，存储在内联数组中，不依赖堆分配，可在中断上下文中使用。
+/// `push`在满时覆盖最旧的元素，`try_push`在满时拒绝新元素
+#[derive(Debug, Clone)]
+pub struct RingBuffer<T, const N: usize> {
+    slots: [Option<T>; N],
+    /// 最旧元素的位置
+    head: usize,
+    len: usize,
+}
+
+impl<T, const N: usize> RingBuffer<T, N> {
+    /// 创建空缓冲区
+    pub const fn new() -> Self {
+        Self {
+            slots: [const { None }; N],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    /// 容量
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// 元素个数
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// 是否为空
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// 是否已满
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// 追加元素，已满时覆盖并返回最旧的元素（容量为0时直接返回新元素）
+    pub fn push(&mut self, value: T) -> Option<T> {
+        if N == 0 {
+            return Some(value);
+        }
+        if self.is_full() {
+            let overwritten = self.slots[self.head].replace(value);
+            self.head = (self.head + 1) % N;
+            return overwritten;
+        }
+
+        self.slots[(self.head + self.len) % N] = Some(value);
+        self.len += 1;
+        None
+    }
+
+    /// 追加元素，已满时原样返回新元素
+    pub fn try_push(&mut self, value: T) -> Result<(), T> {
+        if self.is_full() {
+            return Err(value);
+        }
+        self.push(value);
+        Ok(())
+    }
+
+    /// 取出最旧的元素
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        let value = self.slots[self.head].take();
+        self.head = (self.head + 1) % N;
+        self.len -= 1;
+        value
+    }
+
+    /// 最旧的元素
+    pub fn front(&self) -> Option<&T> {
+        self.iter().next()
+    }
+
+    /// 清空缓冲区
+    pub fn clear(&mut self) {
+        while self.pop().is_some() {}
+    }
+
+    /// 从最旧到最新遍历当前元素
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        let (wrapped, tail) = self.slots.split_at(self.head);
+        tail.iter().chain(wrapped).take(self.len).filter_map(Option::as_ref)
+    }
+}
+
+impl<T, const N: usize> Default for RingBuffer<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    const NAMES: [&str; 3] = ["person", "dog", "扳手"];
+
+    /// 按收到的名称查本地类别表，未知名称记为"unknown"
+    fn lookup(_class_id: u32, name: &str) -> &'static str {
+        NAMES.iter().find(|&&known| known == name).copied().unwrap_or("unknown")
+    }
+
+    fn assert_same(a: &Detection, b: &Detection) {
+        assert_eq!((a.class_id, &a.class_name, a.confidence, a.bbox, a.track_id), (b.class_id, &b.class_name, b.confidence, b.bbox, b.track_id));
+    }
+
+    #[test]
+    fn test_detection_round_trip() {
+        // 小数坐标、跟踪ID和多字节名称往返编解码后一致；空列表只有数量头部
+        let detections = [
+            Detection::new(0, "person", 0.875, BoundingBox::new(0.1234, 0.5678, 0.3, 0.45)).with_track_id(42),
+            Detection::new(16, "dog", 0.5, BoundingBox::new(-0.01, 1.25e-3, 0.2, 0.1)),
+            Detection::new(80, "扳手", 0.3, BoundingBox::new(0.5, 0.5, 0.05, 0.07)),
+        ];
+        let bytes = encode_detections(&detections);
+        assert_eq!(&bytes[..4], &[3, 0, 0, 0]);
+        let decoded = decode_detections(&bytes, lookup).unwrap();
+        assert_eq!(decoded.len(), 3);
+        for (original, decoded) in detections.iter().zip(&decoded) {
+            assert_same(original, decoded);
+        }
+        assert_eq!(decoded[1].bbox.area(), detections[1].bbox.area());
+
+        let empty = encode_detections(&[]);
+        assert_eq!(empty, vec![0, 0, 0, 0]);
+        assert!(decode_detections(&empty, lookup).unwrap().is_empty());
+
+        let bbox = BoundingBox::new(0.25, 0.75, 0.125, 0.5);
+        assert_eq!(&bbox.to_le_bytes()[..4], &0.25f32.to_le_bytes());
+        assert_eq!(BoundingBox::from_le_bytes(bbox.to_le_bytes()), bbox);
+    }
+
+    #[test]
+    fn test_malformed_detections_rejected() {
+        // 截断、多余字节、数量不符、非法标志和非UTF-8名称都被拒绝
+        let detection = Detection::new(1, "dog", 0.9, BoundingBox::new(0.5, 0.5, 0.2, 0.2));
+        let bytes = encode_detections(&[detection]);
+        let is_malformed = |bytes: &[u8]| matches!(decode_detections(bytes, lookup), Err(Error::DriverError(DriverError::DataFormatError)));
+
+        assert!(is_malformed(&bytes[..2]));
+        assert!(is_malformed(&bytes[..bytes.len() - 1]));
+        let mut extended = bytes.clone();
+        extended.push(0);
+        assert!(is_malformed(&extended));
+        let mut miscounted = bytes.clone();
+        miscounted[0] = 2;
+        assert!(is_malformed(&miscounted));
+
+        let mut bad_flag = bytes.clone();
+        bad_flag[4 + 24] = 2;
+        assert!(is_malformed(&bad_flag));
+        let mut bad_name = bytes.clone();
+        let last = bad_name.len() - 1;
+        bad_name[last] = 0xFF;
+        assert!(is_malformed(&bad_name));
+    }
+
+    #[test]
+    fn test_ring_buffer_wrap_around_and_order() {
+        // 反复出队入队使写位置回绕，遍历顺序始终从最旧到最新
+        let mut ring: RingBuffer<u32, 4> = RingBuffer::new();
+        for value in 0..3 {
+            assert_eq!(ring.push(value), None);
+        }
+        assert_eq!(ring.pop(), Some(0));
+        assert_eq!(ring.pop(), Some(1));
+        for value in 3..6 {
+            assert_eq!(ring.try_push(value), Ok(()));
+        }
+
+        assert!(ring.is_full());
+        assert_eq!(ring.iter().copied().collect::<Vec<_>>(), vec![2, 3, 4, 5]);
+        assert_eq!(ring.front(), Some(&2));
+        assert_eq!(ring.try_push(6), Err(6));
+
+        ring.clear();
+        assert!(ring.is_empty());
+        assert_eq!(ring.pop(), None);
+        assert_eq!(ring.iter().count(), 0);
+    }
+
+    #[test]
+    fn test_ring_buffer_overwrite_when_full() {
+        // 满时push覆盖并返回最旧的元素；容量为0时新元素原样返回
+        let mut ring: RingBuffer<char, 3> = RingBuffer::new();
+        for c in ['a', 'b', 'c'] {
+            ring.push(c);
+        }
+        assert_eq!(ring.push('d'), Some('a'));
+        assert_eq!(ring.push('e'), Some('b'));
+        assert_eq!(ring.len(), 3);
+        assert_eq!(ring.iter().collect::<Vec<_>>(), vec![&'c', &'d', &'e']);
+        assert_eq!(ring.pop(), Some('c'));
+
+        let mut empty: RingBuffer<u8, 0> = RingBuffer::default();
+        assert_eq!(empty.push(1), Some(1));
+        assert_eq!(empty.try_push(1), Err(1));
+    }
+}
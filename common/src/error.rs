@@ -186,6 +186,14 @@ pub enum AIError {
     QuantizationError,
     /// 后处理错误
     PostProcessingError,
+    /// 未注册或未选择推理引擎
+    NoEngine,
+    /// AI系统未初始化
+    NotInitialized,
+    /// 句柄已失效（所指槽位已释放或被重新分配）
+    StaleHandle,
+    /// 引擎不支持该操作
+    NotSupported,
 }
 
 impl fmt::Display for AIError {
@@ -201,6 +209,10 @@ impl fmt::Display for AIError {
             AIError::NpuInitializationFailed => write!(f, "NPU初始化失败"),
             AIError::QuantizationError => write!(f, "量化错误"),
             AIError::PostProcessingError => write!(f, "后处理错误"),
+            AIError::NoEngine => write!(f, "未注册或未选择推理引擎"),
+            AIError::NotInitialized => write!(f, "AI系统未初始化"),
+            AIError::StaleHandle => write!(f, "句柄已失效"),
+            AIError::NotSupported => write!(f, "引擎不支持该操作"),
         }
     }
 }
@@ -0,0 +1,277 @@
+//! 定点数运算模块
+//!
+//! 为没有FPU或浮点较慢的核心提供Q16.16定点数，用于IoU、归一化等热点路径
+//!
+//! 精度：分辨率为2^-16（约1.5e-5），表示范围为[-32768, 32768 - 2^-16]。
+//! 乘法和除法结果舍入到最近的可表示值，误差不超过0.5个最小单位；
+//! `sqrt`向下取整，误差小于1个最小单位；`recip`等同于`ONE / x`，
+//! 绝对误差同样不超过0.5个最小单位，因此输入较大时相对误差随之增大（x = 1000时约1%）。
+//! 运算符在溢出时回绕，需要保证不溢出时使用`saturating_*`系列方法
+
+use core::ops::{Add, Div, Mul, Neg, Sub};
+
+/// 小数位数
+pub const FRAC_BITS: u32 = 16;
+
+/// Q16.16定点数：高16位为有符号整数部分，低16位为小数部分
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Q16_16(i32);
+
+impl Q16_16 {
+    pub const ZERO: Self = Self(0);
+    pub const ONE: Self = Self(1 << FRAC_BITS);
+    pub const MAX: Self = Self(i32::MAX);
+    pub const MIN: Self = Self(i32::MIN);
+    /// 最小可表示的正数 2^-16
+    pub const EPSILON: Self = Self(1);
+
+    /// 由原始位模式创建
+    pub const fn from_raw(raw: i32) -> Self {
+        Self(raw)
+    }
+
+    /// 原始位模式
+    pub const fn raw(self) -> i32 {
+        self.0
+    }
+
+    /// 由整数创建
+    pub const fn from_int(value: i16) -> Self {
+        Self((value as i32) << FRAC_BITS)
+    }
+
+    /// 由浮点数创建，四舍五入到最近的可表示值；超出范围时饱和，NaN为0
+    pub fn from_f32(value: f32) -> Self {
+        let scaled = value * (1u32 << FRAC_BITS) as f32;
+        let rounded = if scaled < 0.0 { scaled - 0.5 } else { scaled + 0.5 };
+        // `as`转换对超范围值饱和、对NaN得0
+        Self(rounded as i32)
+    }
+
+    /// 转换为浮点数
+    pub fn to_f32(self) -> f32 {
+        self.0 as f32 / (1u32 << FRAC_BITS) as f32
+    }
+
+    /// 绝对值（MIN饱和为MAX）
+    pub fn abs(self) -> Self {
+        Self(self.0.saturating_abs())
+    }
+
+    /// 饱和加法
+    pub fn saturating_add(self, rhs: Self) -> Self {
+        Self(self.0.saturating_add(rhs.0))
+    }
+
+    /// 饱和减法
+    pub fn saturating_sub(self, rhs: Self) -> Self {
+        Self(self.0.saturating_sub(rhs.0))
+    }
+
+    /// 饱和乘法
+    pub fn saturating_mul(self, rhs: Self) -> Self {
+        Self(saturate(mul_raw(self.0, rhs.0)))
+    }
+
+    /// 饱和除法，除以0时按被除数符号饱和（0 / 0为0）
+    pub fn saturating_div(self, rhs: Self) -> Self {
+        match rhs.0 {
+            0 => match self.0 {
+                0 => Self::ZERO,
+                x if x > 0 => Self::MAX,
+                _ => Self::MIN,
+            },
+            _ => Self(saturate(div_raw(self.0, rhs.0))),
+        }
+    }
+
+    /// 除法，除以0时返回None
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        if rhs.0 == 0 {
+            return None;
+        }
+        let quotient = div_raw(self.0, rhs.0);
+        i32::try_from(quotient).ok().map(Self)
+    }
+
+    /// 倒数，0的倒数饱和为MAX
+    pub fn recip(self) -> Self {
+        Self::ONE.saturating_div(self)
+    }
+
+    /// 平方根，负数为0
+    pub fn sqrt(self) -> Self {
+        if self.0 <= 0 {
+            return Self::ZERO;
+        }
+        // sqrt(raw / 2^16) * 2^16 = sqrt(raw * 2^16)
+        Self(isqrt((self.0 as u64) << FRAC_BITS) as i32)
+    }
+}
+
+/// 原始值相乘，结果舍入到最近值
+fn mul_raw(a: i32, b: i32) -> i64 {
+    (a as i64 * b as i64 + (1 << (FRAC_BITS - 1))) >> FRAC_BITS
+}
+
+/// 原始值相除，结果舍入到最近值（除数非0）
+fn div_raw(a: i32, b: i32) -> i64 {
+    let numerator = (a as i64) << FRAC_BITS;
+    let denominator = b as i64;
+    // 整数除法向0截断，被除数先向远离0的方向偏移半个除数
+    let half = denominator.abs() / 2;
+    (numerator + if numerator < 0 { -half } else { half }) / denominator
+}
+
+/// 把64位中间结果饱和到i32
+fn saturate(value: i64) -> i32 {
+    value.clamp(i32::MIN as i64, i32::MAX as i64) as i32
+}
+
+/// 64位整数平方根（向下取整），逐位试商
+fn isqrt(value: u64) -> u64 {
+    let mut remainder = value;
+    let mut root = 0u64;
+    let mut bit = 1u64 << 62;
+    while bit > value {
+        bit >>= 2;
+    }
+    while bit != 0 {
+        if remainder >= root + bit {
+            remainder -= root + bit;
+            root = (root >> 1) + bit;
+        } else {
+            root >>= 1;
+        }
+        bit >>= 2;
+    }
+    root
+}
+
+impl Add for Q16_16 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0.wrapping_add(rhs.0))
+    }
+}
+
+impl Sub for Q16_16 {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.0.wrapping_sub(rhs.0))
+    }
+}
+
+impl Mul for Q16_16 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self(mul_raw(self.0, rhs.0) as i32)
+    }
+}
+
+impl Div for Q16_16 {
+    type Output = Self;
+
+    /// # Panics
+    /// 除数为0时panic
+    fn div(self, rhs: Self) -> Self {
+        assert!(rhs.0 != 0, "Q16_16除以0");
+        Self(div_raw(self.0, rhs.0) as i32)
+    }
+}
+
+impl Neg for Q16_16 {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(self.0.wrapping_neg())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 中心点格式（cx, cy, w, h）归一化边界框的定点IoU
+    fn fixed_iou(a: [f32; 4], b: [f32; 4]) -> f32 {
+        let [ax, ay, aw, ah] = a.map(Q16_16::from_f32);
+        let [bx, by, bw, bh] = b.map(Q16_16::from_f32);
+        let half = Q16_16::from_f32(0.5);
+
+        let left = (ax - aw * half).max(bx - bw * half);
+        let right = (ax + aw * half).min(bx + bw * half);
+        let top = (ay - ah * half).max(by - bh * half);
+        let bottom = (ay + ah * half).min(by + bh * half);
+        if right <= left || bottom <= top {
+            return 0.0;
+        }
+
+        let intersection = (right - left) * (bottom - top);
+        let union = aw * ah + bw * bh - intersection;
+        (intersection * union.recip()).to_f32()
+    }
+
+    fn float_iou(a: [f32; 4], b: [f32; 4]) -> f32 {
+        let left = (a[0] - a[2] / 2.0).max(b[0] - b[2] / 2.0);
+        let right = (a[0] + a[2] / 2.0).min(b[0] + b[2] / 2.0);
+        let top = (a[1] - a[3] / 2.0).max(b[1] - b[3] / 2.0);
+        let bottom = (a[1] + a[3] / 2.0).min(b[1] + b[3] / 2.0);
+        if right <= left || bottom <= top {
+            return 0.0;
+        }
+        let intersection = (right - left) * (bottom - top);
+        intersection / (a[2] * a[3] + b[2] * b[3] - intersection)
+    }
+
+    #[test]
+    fn test_fixed_iou_matches_f32() {
+        // 不同重叠程度的归一化边界框，定点IoU与浮点结果误差小于1e-3
+        let boxes = [
+            ([0.5, 0.5, 0.4, 0.4], [0.5, 0.5, 0.4, 0.4]),
+            ([0.5, 0.5, 0.4, 0.4], [0.55, 0.52, 0.4, 0.38]),
+            ([0.3, 0.4, 0.2, 0.5], [0.4, 0.45, 0.3, 0.2]),
+            ([0.2, 0.2, 0.1, 0.1], [0.24, 0.23, 0.12, 0.08]),
+            ([0.7, 0.6, 0.5, 0.6], [0.3, 0.3, 0.2, 0.2]),
+            ([0.1, 0.1, 0.1, 0.1], [0.9, 0.9, 0.1, 0.1]),
+        ];
+        for (a, b) in boxes {
+            let error = fixed_iou(a, b) - float_iou(a, b);
+            assert!(error.abs() < 1e-3, "{:?} {:?}: {}", a, b, error);
+        }
+    }
+
+    #[test]
+    fn test_arithmetic_saturation_and_approximations() {
+        // 基本运算、饱和运算，以及sqrt/recip的精度
+        let a = Q16_16::from_f32(3.25);
+        let b = Q16_16::from_int(-2);
+        assert_eq!((a + b).to_f32(), 1.25);
+        assert_eq!((a - b).to_f32(), 5.25);
+        assert_eq!((a * b).to_f32(), -6.5);
+        assert_eq!((a / b).to_f32(), -1.625);
+        assert_eq!((-b).to_f32(), 2.0);
+
+        let big = Q16_16::from_int(30000);
+        assert_eq!(big.saturating_add(big), Q16_16::MAX);
+        assert_eq!(big.saturating_mul(b), Q16_16::MIN);
+        assert_eq!(a.saturating_div(Q16_16::ZERO), Q16_16::MAX);
+        assert_eq!(a.checked_div(Q16_16::ZERO), None);
+        assert_eq!(Q16_16::from_f32(f32::NAN), Q16_16::ZERO);
+        assert_eq!(Q16_16::from_f32(1e9), Q16_16::MAX);
+
+        // 与输入量化后的精确值相比，误差不超过1个最小单位
+        let ulp = Q16_16::EPSILON.to_f32();
+        for value in [0.01f32, 0.5, 2.0, 10.0, 1000.0] {
+            let fixed = Q16_16::from_f32(value);
+            let exact = fixed.to_f32() as f64;
+            let root = fixed.sqrt().to_f32() as f64;
+            assert!(root * root <= exact && exact < (root + ulp as f64) * (root + ulp as f64));
+            assert!((fixed.recip().to_f32() as f64 - 1.0 / exact).abs() <= ulp as f64);
+        }
+        assert_eq!(Q16_16::from_int(-4).sqrt(), Q16_16::ZERO);
+    }
+}
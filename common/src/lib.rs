@@ -18,9 +18,14 @@ mod data_structures;
 mod utils;
 // 性能优化模块
 mod performance;
+// 线性代数模块
+pub mod math;
+// 定点数模块
+pub mod fixed;
 
 // 公共导出
 pub use error::{Error, SystemError, DriverError, AIError, AppError, CommonResult};
-pub use data_structures::{BoundingBox, Detection, SensorData, PerformanceMode, LogLevel, TaskInfo};
-pub use utils::{align_memory, calculate_mean, calculate_stddev, quick_sort, non_max_suppression, normalize_vector, dot_product};
-pub use performance::{PerformanceMonitor, MemoryPool, AlgorithmOptimizer, CacheOptimized, benchmark};
\ No newline at end of file
+pub use data_structures::{BoundingBox, IouType, RoiMask, Detection, SensorData, SensorReading, TimedReading, TemperatureUnit, PerformanceMode, LogLevel, TaskInfo, RingBuffer, encode_detections, decode_detections, BOUNDING_BOX_ENCODED_LEN};
+pub use utils::{align_memory, calculate_mean, calculate_stddev, quick_sort, non_max_suppression, non_max_suppression_with, class_groups, group_nms, per_class_nms, sort_detections, filter_by_roi, filter_by_class, filter_by_confidence, sanitize_detections, DetectionIterExt, FilterClass, FilterConf, WithinRoi, normalize_vector, dot_product};
+pub use fixed::Q16_16;
+pub use performance::{PerformanceMonitor, LATENCY_BUCKETS_US, MemoryPool, AlgorithmOptimizer, CacheOptimized, benchmark};
\ No newline at end of file
@@ -0,0 +1,74 @@
+//! 线性代数模块
+//!
+//! 提供CPU回退推理所需的基础矩阵运算
+
+/// 分块大小，使每个分块的工作集保持在L1缓存内
+const TILE: usize = 32;
+
+/// 矩阵乘法选项
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct GemmOptions {
+    /// A以转置形式存储（k×m）
+    pub transpose_a: bool,
+    /// B以转置形式存储（n×k）
+    pub transpose_b: bool,
+    /// 累加系数：out = op(A)·op(B) + beta·out
+    pub beta: f32,
+}
+
+/// 矩阵乘法 out(m×n) = a(m×k) · b(k×n)，行主序
+pub fn gemm(a: &[f32], b: &[f32], m: usize, k: usize, n: usize, out: &mut [f32]) {
+    gemm_with(a, b, m, k, n, out, GemmOptions::default());
+}
+
+/// 带转置和累加选项的矩阵乘法，行主序
+///
+/// # Panics
+/// 输入或输出长度与维度不匹配时panic
+pub fn gemm_with(
+    a: &[f32],
+    b: &[f32],
+    m: usize,
+    k: usize,
+    n: usize,
+    out: &mut [f32],
+    options: GemmOptions,
+) {
+    assert_eq!(a.len(), m * k, "GEMM维度不匹配: A长度应为m*k");
+    assert_eq!(b.len(), k * n, "GEMM维度不匹配: B长度应为k*n");
+    assert_eq!(out.len(), m * n, "GEMM维度不匹配: 输出长度应为m*n");
+
+    if options.beta == 0.0 {
+        out.fill(0.0);
+    } else if options.beta != 1.0 {
+        for value in out.iter_mut() {
+            *value *= options.beta;
+        }
+    }
+
+    let a_at = |i: usize, p: usize| if options.transpose_a { a[p * m + i] } else { a[i * k + p] };
+    let b_at = |p: usize, j: usize| if options.transpose_b { b[j * k + p] } else { b[p * n + j] };
+
+    for i0 in (0..m).step_by(TILE) {
+        let i_end = (i0 + TILE).min(m);
+        for p0 in (0..k).step_by(TILE) {
+            let p_end = (p0 + TILE).min(k);
+            for j0 in (0..n).step_by(TILE) {
+                let j_end = (j0 + TILE).min(n);
+
+                for i in i0..i_end {
+                    let row = &mut out[i * n..(i + 1) * n];
+                    for p in p0..p_end {
+                        let a_ip = a_at(i, p);
+                        if a_ip == 0.0 {
+                            continue;
+                        }
+                        for j in j0..j_end {
+                            row[j] += a_ip * b_at(p, j);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
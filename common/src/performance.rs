@@ -4,11 +4,15 @@
 
 use core::time::Duration;
 
+/// 耗时直方图各桶上界（微秒），超过最后一个上界的记录计入溢出桶
+pub const LATENCY_BUCKETS_US: [u64; 8] = [100, 500, 1_000, 5_000, 10_000, 50_000, 100_000, 500_000];
+
 /// 性能监控器
 pub struct PerformanceMonitor {
     start_time: Option<u64>,
     total_operations: u64,
     total_duration: Duration,
+    histogram: [u64; LATENCY_BUCKETS_US.len() + 1],
 }
 
 impl PerformanceMonitor {
@@ -18,6 +22,7 @@ impl PerformanceMonitor {
             start_time: None,
             total_operations: 0,
             total_duration: Duration::default(),
+            histogram: [0; LATENCY_BUCKETS_US.len() + 1],
         }
     }
     
@@ -30,14 +35,35 @@ impl PerformanceMonitor {
     pub fn stop_timing(&mut self) -> Duration {
         if let Some(start) = self.start_time.take() {
             let duration = Duration::from_micros(Self::current_timestamp() - start);
-            self.total_operations += 1;
-            self.total_duration += duration;
+            self.record(duration);
             duration
         } else {
             Duration::default()
         }
     }
     
+    /// 记录一次外部测得的操作耗时
+    pub fn record(&mut self, duration: Duration) {
+        let micros = duration.as_micros() as u64;
+        let bucket = LATENCY_BUCKETS_US
+            .iter()
+            .position(|&bound| micros <= bound)
+            .unwrap_or(LATENCY_BUCKETS_US.len());
+        self.histogram[bucket] += 1;
+        self.total_operations += 1;
+        self.total_duration += duration;
+    }
+    
+    /// 耗时直方图，下标与`LATENCY_BUCKETS_US`对应，最后一个为溢出桶
+    pub fn histogram(&self) -> &[u64] {
+        &self.histogram
+    }
+    
+    /// 已记录的操作次数
+    pub fn total_operations(&self) -> u64 {
+        self.total_operations
+    }
+    
     /// 获取平均操作时间
     pub fn average_duration(&self) -> Duration {
         if self.total_operations > 0 {
@@ -91,6 +91,16 @@ fn partition<T: PartialOrd + Clone>(arr: &mut [T]) -> usize {
 
 /// 非极大值抑制算法（优化版本）
 pub fn non_max_suppression(boxes: &[BoundingBox], scores: &[f32], iou_threshold: f32) -> Vec<usize> {
+    non_max_suppression_with(boxes, scores, iou_threshold, IouType::Iou)
+}
+
+/// 使用指定重叠度度量的非极大值抑制
+pub fn non_max_suppression_with(
+    boxes: &[BoundingBox],
+    scores: &[f32],
+    iou_threshold: f32,
+    iou_type: IouType,
+) -> Vec<usize> {
     if boxes.is_empty() {
         return Vec::new();
     }
@@ -98,21 +108,179 @@ pub fn non_max_suppression(boxes: &[BoundingBox], scores: &[f32], iou_threshold:
     // 预分配结果向量
     let mut result = Vec::with_capacity(boxes.len());
     
-    // 创建索引并排序
+    // 创建索引并稳定排序：置信度降序，相同时按框位置，再按输入顺序
     let mut indices: Vec<usize> = (0..boxes.len()).collect();
-    indices.sort_by(|&a, &b| scores[b].partial_cmp(&scores[a]).unwrap());
+    indices.sort_by(|&a, &b| {
+        scores[b].total_cmp(&scores[a]).then_with(|| boxes[a].position_cmp(&boxes[b]))
+    });
     
     while !indices.is_empty() {
         let current = indices.remove(0);
         result.push(current);
         
         // 使用迭代器过滤
-        indices.retain(|&i| boxes[current].calculate_iou(&boxes[i]) <= iou_threshold);
+        indices.retain(|&i| boxes[current].calculate_iou_with(&boxes[i], iou_type) <= iou_threshold);
     }
     
     result
 }
 
+/// 按类别ID升序分组的候选框下标，组内保持输入顺序
+pub fn class_groups(classes: &[u32]) -> Vec<Vec<usize>> {
+    let mut order: Vec<usize> = (0..classes.len()).collect();
+    order.sort_by_key(|&i| classes[i]);
+    order.chunk_by(|&a, &b| classes[a] == classes[b]).map(<[usize]>::to_vec).collect()
+}
+
+/// 只在`group`中的候选框之间做非极大值抑制，返回保留框的原始下标（置信度降序）
+pub fn group_nms(boxes: &[BoundingBox], scores: &[f32], group: &[usize], iou_threshold: f32) -> Vec<usize> {
+    let group_boxes: Vec<BoundingBox> = group.iter().map(|&i| boxes[i]).collect();
+    let group_scores: Vec<f32> = group.iter().map(|&i| scores[i]).collect();
+    non_max_suppression(&group_boxes, &group_scores, iou_threshold)
+        .into_iter()
+        .map(|i| group[i])
+        .collect()
+}
+
+/// 按类别分别做非极大值抑制，保留框按类别ID升序、类别内按置信度降序排列
+pub fn per_class_nms(boxes: &[BoundingBox], scores: &[f32], classes: &[u32], iou_threshold: f32) -> Vec<usize> {
+    class_groups(classes)
+        .iter()
+        .flat_map(|group| group_nms(boxes, scores, group, iou_threshold))
+        .collect()
+}
+
+/// 按`Detection::ranking_cmp`规则对检测结果稳定排序
+pub fn sort_detections(detections: &mut [Detection]) {
+    detections.sort_by(|a, b| a.ranking_cmp(b));
+}
+
+/// 按感兴趣区域过滤检测结果
+/// 
+/// 仅保留边界框中心落在掩码允许区域内的检测
+pub fn filter_by_roi(detections: Vec<Detection>, mask: &RoiMask) -> Vec<Detection> {
+    detections.into_iter().within_roi(mask).collect()
+}
+
+/// 按类别过滤检测结果，仅保留类别ID在`classes`中的检测
+pub fn filter_by_class(detections: Vec<Detection>, classes: &[u32]) -> Vec<Detection> {
+    detections.into_iter().filter_class(classes).collect()
+}
+
+/// 按置信度过滤检测结果，仅保留置信度不低于`threshold`的检测
+pub fn filter_by_confidence(detections: Vec<Detection>, threshold: f32) -> Vec<Detection> {
+    detections.into_iter().filter_conf(threshold).collect()
+}
+
+/// 清理模型输出中的异常检测结果（边界框坐标为归一化的[0, 1]）
+/// 
+/// 丢弃置信度或坐标为NaN/Inf的检测，将超出画面的边界框裁剪到画面内，
+/// 并丢弃宽或高不为正的检测；画面内的有效检测保持不变
+pub fn sanitize_detections(detections: &mut Vec<Detection>) {
+    detections.retain_mut(|detection| {
+        let bbox = detection.bbox;
+        let finite = [detection.confidence, bbox.x, bbox.y, bbox.width, bbox.height]
+            .iter()
+            .all(|value| value.is_finite());
+        if !finite || !bbox.is_valid() {
+            return false;
+        }
+        
+        let (left, right) = (bbox.x - bbox.width / 2.0, bbox.x + bbox.width / 2.0);
+        let (top, bottom) = (bbox.y - bbox.height / 2.0, bbox.y + bbox.height / 2.0);
+        if left < 0.0 || top < 0.0 || right > 1.0 || bottom > 1.0 {
+            let (left, right) = (left.max(0.0), right.min(1.0));
+            let (top, bottom) = (top.max(0.0), bottom.min(1.0));
+            detection.bbox = BoundingBox::new(
+                (left + right) / 2.0,
+                (top + bottom) / 2.0,
+                right - left,
+                bottom - top,
+            );
+        }
+        detection.bbox.is_valid()
+    });
+}
+
+/// 检测结果迭代器适配器
+/// 
+/// 过滤条件惰性组合，不产生中间`Vec`，最后通过`collect`一次性生成结果
+pub trait DetectionIterExt: Iterator<Item = Detection> + Sized {
+    /// 仅保留类别ID在`classes`中的检测
+    fn filter_class(self, classes: &[u32]) -> FilterClass<'_, Self> {
+        FilterClass { inner: self, classes }
+    }
+    
+    /// 仅保留置信度不低于`threshold`的检测
+    fn filter_conf(self, threshold: f32) -> FilterConf<Self> {
+        FilterConf { inner: self, threshold }
+    }
+    
+    /// 仅保留边界框中心落在掩码允许区域内的检测
+    fn within_roi(self, mask: &RoiMask) -> WithinRoi<'_, Self> {
+        WithinRoi { inner: self, mask }
+    }
+}
+
+impl<I: Iterator<Item = Detection>> DetectionIterExt for I {}
+
+/// 按类别过滤的迭代器，由`DetectionIterExt::filter_class`创建
+pub struct FilterClass<'a, I> {
+    inner: I,
+    classes: &'a [u32],
+}
+
+impl<I: Iterator<Item = Detection>> Iterator for FilterClass<'_, I> {
+    type Item = Detection;
+    
+    fn next(&mut self) -> Option<Detection> {
+        let classes = self.classes;
+        self.inner.find(|d| classes.contains(&d.class_id))
+    }
+    
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.inner.size_hint().1)
+    }
+}
+
+/// 按置信度过滤的迭代器，由`DetectionIterExt::filter_conf`创建
+pub struct FilterConf<I> {
+    inner: I,
+    threshold: f32,
+}
+
+impl<I: Iterator<Item = Detection>> Iterator for FilterConf<I> {
+    type Item = Detection;
+    
+    fn next(&mut self) -> Option<Detection> {
+        let threshold = self.threshold;
+        self.inner.find(|d| d.confidence >= threshold)
+    }
+    
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.inner.size_hint().1)
+    }
+}
+
+/// 按感兴趣区域过滤的迭代器，由`DetectionIterExt::within_roi`创建
+pub struct WithinRoi<'a, I> {
+    inner: I,
+    mask: &'a RoiMask,
+}
+
+impl<I: Iterator<Item = Detection>> Iterator for WithinRoi<'_, I> {
+    type Item = Detection;
+    
+    fn next(&mut self) -> Option<Detection> {
+        let mask = self.mask;
+        self.inner.find(|d| mask.allows(d.bbox.x, d.bbox.y))
+    }
+    
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.inner.size_hint().1)
+    }
+}
+
 /// 向量归一化（优化版本）
 pub fn normalize_vector(vec: &mut [f32]) {
     let magnitude = vec.iter().map(|&x| x * x).sum::<f32>().sqrt();
@@ -185,11 +353,11 @@ pub fn non_maximum_suppression<T, F>(
 ) where 
     F: Fn(&T) -> (&BoundingBox, f32),
 {
-    // 按置信度降序排序
+    // 按置信度降序稳定排序，相同时按框位置
     detections.sort_by(|a, b| {
-        let (_, score_a) = get_score(a);
-        let (_, score_b) = get_score(b);
-        score_b.partial_cmp(&score_a).unwrap_or(core::cmp::Ordering::Equal)
+        let (bbox_a, score_a) = get_score(a);
+        let (bbox_b, score_b) = get_score(b);
+        score_b.total_cmp(&score_a).then_with(|| bbox_a.position_cmp(bbox_b))
     });
     
     let mut keep_indices = Vec::new();
@@ -228,7 +396,7 @@ pub fn non_maximum_suppression<T, F>(
 }
 
 // 导入BoundingBox用于NMS函数
-use super::data_structures::BoundingBox;
+use super::data_structures::{BoundingBox, Detection, IouType, RoiMask};
 
 /// 向量归一化
 pub fn normalize_vector(data: &mut [f32]) {
@@ -1,8 +1,39 @@
 // 通用共享库单元测试
 
 use common::{Error, SystemError, DriverError, AIError, AppError, CommonResult};
-use common::{BoundingBox, Detection, SensorData, PerformanceMode, LogLevel, TaskInfo};
+use common::{BoundingBox, IouType, RoiMask, Detection, SensorData, SensorReading, PerformanceMode, LogLevel, TaskInfo};
+use common::{non_max_suppression, non_max_suppression_with, sort_detections, filter_by_roi};
 use common::{calculate_mean, calculate_stddev, normalize_vector, dot_product};
+use common::math::{gemm, gemm_with, GemmOptions};
+use common::{filter_by_class, filter_by_confidence, sanitize_detections, DetectionIterExt};
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+/// 统计当前线程堆分配次数的分配器
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+    
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocation_count() -> usize {
+    ALLOCATIONS.with(|count| count.get())
+}
 
 #[test]
 fn test_error_conversion() {
@@ -92,4 +123,290 @@ fn test_task_info() {
     assert_eq!(task_info.name, "test_task");
     assert_eq!(task_info.priority, 5);
     assert_eq!(task_info.stack_size, 1024);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_iou_variants() {
+    // 两个2x2边界框在x、y方向各偏移1
+    let a = BoundingBox::new(0.0, 0.0, 2.0, 2.0);
+    let b = BoundingBox::new(1.0, 1.0, 2.0, 2.0);
+    
+    let iou = a.calculate_iou_with(&b, IouType::Iou);
+    let giou = a.calculate_iou_with(&b, IouType::GIou);
+    let diou = a.calculate_iou_with(&b, IouType::DIou);
+    
+    // 交集1，并集7，外接框3x3
+    assert!((iou - 1.0 / 7.0).abs() < 1e-5);
+    assert!((giou - (1.0 / 7.0 - 2.0 / 9.0)).abs() < 1e-5);
+    // 中心距离平方2，外接框对角线平方18
+    assert!((diou - (1.0 / 7.0 - 1.0 / 9.0)).abs() < 1e-5);
+    assert_eq!(IouType::default(), IouType::Iou);
+    
+    // 中心重合时DIoU不受惩罚
+    let c = BoundingBox::new(0.0, 0.0, 1.0, 1.0);
+    assert!((a.calculate_iou_with(&c, IouType::DIou) - a.calculate_iou(&c)).abs() < 1e-5);
+}
+
+#[test]
+fn test_equal_confidence_ordering_is_deterministic() {
+    // 置信度相同的检测结果按类别ID、框位置排序，与输入顺序无关
+    let detections = [
+        Detection::new(2, "cup", 0.8, BoundingBox::new(10.0, 5.0, 4.0, 4.0)),
+        Detection::new(1, "dog", 0.8, BoundingBox::new(30.0, 5.0, 4.0, 4.0)),
+        Detection::new(1, "dog", 0.8, BoundingBox::new(20.0, 5.0, 4.0, 4.0)),
+        Detection::new(1, "dog", 0.8, BoundingBox::new(50.0, 1.0, 4.0, 4.0)),
+        Detection::new(0, "person", 0.9, BoundingBox::new(0.0, 0.0, 4.0, 4.0)),
+    ];
+    let expected = [(0, 0.0, 0.0), (1, 50.0, 1.0), (1, 20.0, 5.0), (1, 30.0, 5.0), (2, 10.0, 5.0)];
+    
+    for rotation in 0..detections.len() {
+        let mut shuffled = detections.to_vec();
+        shuffled.rotate_left(rotation);
+        shuffled.swap(0, shuffled.len() - 1);
+        sort_detections(&mut shuffled);
+        let order: Vec<(u32, f32, f32)> = shuffled.iter().map(|d| (d.class_id, d.bbox.x, d.bbox.y)).collect();
+        assert_eq!(order, expected);
+    }
+}
+
+#[test]
+fn test_nms_equal_scores_reproducible() {
+    // 分数相同时NMS按框位置选择保留框，输出不随输入顺序变化
+    let boxes = [
+        BoundingBox::new(40.0, 0.0, 10.0, 10.0),
+        BoundingBox::new(0.0, 0.0, 10.0, 10.0),
+        BoundingBox::new(1.0, 0.0, 10.0, 10.0),
+        BoundingBox::new(20.0, 0.0, 10.0, 10.0),
+    ];
+    let scores = [0.5; 4];
+    
+    for rotation in 0..boxes.len() {
+        let mut shuffled = boxes.to_vec();
+        shuffled.rotate_left(rotation);
+        let kept: Vec<f32> = non_max_suppression(&shuffled, &scores, 0.5)
+            .into_iter()
+            .map(|i| shuffled[i].x)
+            .collect();
+        // x=1的框与x=0的框重叠，被位置靠前的x=0框抑制
+        assert_eq!(kept, vec![0.0, 20.0, 40.0]);
+    }
+}
+
+#[test]
+fn test_nms_with_diou() {
+    let boxes = [
+        BoundingBox::new(0.0, 0.0, 2.0, 2.0),
+        BoundingBox::new(1.0, 1.0, 2.0, 2.0),
+    ];
+    let scores = [0.9, 0.8];
+    
+    // 标准IoU(≈0.14)超过阈值，第二个框被抑制
+    assert_eq!(non_max_suppression(&boxes, &scores, 0.1), vec![0]);
+    // DIoU惩罚中心距离后(≈0.03)低于阈值，两个框都保留
+    assert_eq!(non_max_suppression_with(&boxes, &scores, 0.1, IouType::DIou), vec![0, 1]);
+}
+
+#[test]
+fn test_roi_filter() {
+    let inside = Detection::new(0, "person", 0.9, BoundingBox::new(100.0, 100.0, 40.0, 80.0));
+    let outside = Detection::new(0, "person", 0.9, BoundingBox::new(500.0, 300.0, 40.0, 80.0));
+    let detections = vec![inside, outside];
+    
+    // 空掩码不过滤
+    let mut mask = RoiMask::new();
+    assert_eq!(filter_by_roi(detections.clone(), &mask).len(), 2);
+    
+    // 区域内保留，区域外丢弃
+    mask.add_region(BoundingBox::new(100.0, 100.0, 200.0, 200.0));
+    let kept = filter_by_roi(detections.clone(), &mask);
+    assert_eq!(kept.len(), 1);
+    assert_eq!(kept[0].bbox.x, 100.0);
+    
+    // 多个区域取并集
+    mask.add_region(BoundingBox::new(500.0, 300.0, 50.0, 50.0));
+    assert_eq!(filter_by_roi(detections.clone(), &mask).len(), 2);
+    
+    // 禁用后不过滤
+    mask.clear();
+    mask.add_region(BoundingBox::new(0.0, 0.0, 10.0, 10.0));
+    mask.set_enabled(false);
+    assert_eq!(filter_by_roi(detections, &mask).len(), 2);
+}
+
+#[test]
+fn test_roi_mask_default_matches_new() {
+    // 默认掩码与new一致处于启用状态，添加区域后即开始过滤
+    let mut mask = RoiMask::default();
+    assert!(mask.is_enabled());
+    
+    mask.add_region(BoundingBox::new(0.0, 0.0, 10.0, 10.0));
+    assert!(mask.allows(1.0, 1.0));
+    assert!(!mask.allows(100.0, 100.0));
+}
+
+fn mixed_detections() -> Vec<Detection> {
+    (0..12u32)
+        .map(|i| {
+            let class_id = i % 3;
+            let confidence = 0.3 + (i % 5) as f32 * 0.15;
+            Detection::new(class_id, "object", confidence, BoundingBox::new(i as f32 * 20.0, 50.0, 10.0, 10.0))
+        })
+        .collect()
+}
+
+#[test]
+fn test_lazy_filters_match_eager_chain() {
+    // 惰性组合过滤与逐步生成Vec的过滤结果一致
+    let mut mask = RoiMask::new();
+    mask.add_region(BoundingBox::new(100.0, 50.0, 150.0, 20.0));
+    let classes = [0, 2];
+    
+    let eager = filter_by_roi(
+        filter_by_confidence(filter_by_class(mixed_detections(), &classes), 0.5),
+        &mask,
+    );
+    let lazy: Vec<Detection> = mixed_detections()
+        .into_iter()
+        .filter_class(&classes)
+        .filter_conf(0.5)
+        .within_roi(&mask)
+        .collect();
+    
+    assert!(!eager.is_empty());
+    assert_eq!(lazy.len(), eager.len());
+    for (a, b) in lazy.iter().zip(&eager) {
+        assert_eq!((a.class_id, a.confidence, a.bbox), (b.class_id, b.confidence, b.bbox));
+    }
+}
+
+#[test]
+fn test_lazy_filters_do_not_allocate() {
+    // 惰性过滤链遍历过程中不产生任何堆分配
+    let detections = mixed_detections();
+    let mut mask = RoiMask::new();
+    mask.add_region(BoundingBox::new(100.0, 50.0, 150.0, 20.0));
+    let classes = [0, 2];
+    
+    let before = allocation_count();
+    let mut kept = 0;
+    let mut confidence_sum = 0.0;
+    for detection in detections.into_iter().filter_class(&classes).filter_conf(0.5).within_roi(&mask) {
+        kept += 1;
+        confidence_sum += detection.confidence;
+    }
+    assert_eq!(allocation_count(), before);
+    assert!(kept > 0 && confidence_sum > 0.0);
+    
+    // 对照：逐步过滤每一步都会分配新的Vec
+    let before = allocation_count();
+    let _ = filter_by_roi(filter_by_confidence(filter_by_class(mixed_detections(), &classes), 0.5), &mask);
+    assert!(allocation_count() - before >= 4);
+}
+
+#[test]
+fn test_gemm_small_product() {
+    // [1 2 3; 4 5 6] × [7 8; 9 10; 11 12] = [58 64; 139 154]
+    let a = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+    let b = [7.0, 8.0, 9.0, 10.0, 11.0, 12.0];
+    let mut out = [f32::NAN; 4];
+    gemm(&a, &b, 2, 3, 2, &mut out);
+    assert_eq!(out, [58.0, 64.0, 139.0, 154.0]);
+    
+    // 超过分块大小的矩阵与单位阵相乘保持不变
+    let size = 40;
+    let identity: Vec<f32> = (0..size * size).map(|i| if i / size == i % size { 1.0 } else { 0.0 }).collect();
+    let values: Vec<f32> = (0..size * size).map(|i| i as f32).collect();
+    let mut result = vec![0.0; size * size];
+    gemm(&values, &identity, size, size, size, &mut result);
+    assert_eq!(result, values);
+}
+
+#[test]
+fn test_gemm_transpose_and_beta() {
+    // A以转置形式存储：[1 4; 2 5; 3 6]ᵀ = [1 2 3; 4 5 6]
+    let a_t = [1.0, 4.0, 2.0, 5.0, 3.0, 6.0];
+    // B以转置形式存储：[7 9 11; 8 10 12]ᵀ = [7 8; 9 10; 11 12]
+    let b_t = [7.0, 9.0, 11.0, 8.0, 10.0, 12.0];
+    let mut out = [0.0; 4];
+    let transposed = GemmOptions { transpose_a: true, transpose_b: true, beta: 0.0 };
+    gemm_with(&a_t, &b_t, 2, 3, 2, &mut out, transposed);
+    assert_eq!(out, [58.0, 64.0, 139.0, 154.0]);
+    
+    // beta累加：out = A·B + 0.5·out
+    let a = [1.0, 0.0, 0.0, 1.0];
+    let b = [1.0, 2.0, 3.0, 4.0];
+    let mut acc = [2.0, 2.0, 2.0, 2.0];
+    gemm_with(&a, &b, 2, 2, 2, &mut acc, GemmOptions { beta: 0.5, ..Default::default() });
+    assert_eq!(acc, [2.0, 3.0, 4.0, 5.0]);
+}
+
+#[test]
+#[should_panic(expected = "GEMM维度不匹配")]
+fn test_gemm_dimension_mismatch() {
+    let mut out = [0.0; 4];
+    gemm(&[1.0; 6], &[1.0; 5], 2, 3, 2, &mut out);
+}
+
+#[test]
+fn test_sanitize_drops_non_finite_and_degenerate() {
+    // NaN置信度和零宽度的检测被丢弃，有效检测原样保留
+    let valid = Detection::new(0, "person", 0.9, BoundingBox::new(0.5, 0.5, 0.2, 0.4));
+    let mut detections = vec![
+        Detection::new(1, "car", f32::NAN, BoundingBox::new(0.3, 0.3, 0.1, 0.1)),
+        valid.clone(),
+        Detection::new(2, "dog", 0.8, BoundingBox::new(0.6, 0.6, 0.0, 0.2)),
+        Detection::new(3, "cat", f32::INFINITY, BoundingBox::new(0.2, 0.2, 0.1, 0.1)),
+    ];
+    sanitize_detections(&mut detections);
+
+    assert_eq!(detections.len(), 1);
+    assert_eq!(detections[0].class_id, valid.class_id);
+    assert_eq!(detections[0].confidence, valid.confidence);
+    assert_eq!(detections[0].bbox, valid.bbox);
+}
+
+#[test]
+fn test_sanitize_clamps_out_of_bounds_box() {
+    // 超出画面的边界框被裁剪到[0, 1]，完全在画面外的被丢弃
+    let mut detections = vec![
+        Detection::new(0, "person", 0.7, BoundingBox::new(0.9, 0.5, 0.4, 0.2)),
+        Detection::new(1, "car", 0.6, BoundingBox::new(1.5, 0.5, 0.2, 0.2)),
+    ];
+    sanitize_detections(&mut detections);
+
+    assert_eq!(detections.len(), 1);
+    let bbox = detections[0].bbox;
+    assert!((bbox.x - 0.85).abs() < 1e-6);
+    assert!((bbox.width - 0.3).abs() < 1e-6);
+    assert!((bbox.y - 0.5).abs() < 1e-6 && (bbox.height - 0.2).abs() < 1e-6);
+    assert!((bbox.area() - 0.06).abs() < 1e-6);
+}
+
+#[test]
+fn test_sensor_reading_round_trip() {
+    // 每种读数转换为SensorData后可无损转换回来
+    let readings = [
+        SensorReading::Temperature(-12.5),
+        SensorReading::Humidity(60.0),
+        SensorReading::Light(300.0),
+        SensorReading::Acceleration(0.1, -9.8, 0.3),
+        SensorReading::Gyroscope(1.0, 2.0, 3.0),
+    ];
+    for reading in readings {
+        let data = SensorData::from(reading);
+        assert_eq!(SensorReading::try_from(data), Ok(reading));
+        assert_eq!(SensorData::from(SensorReading::try_from(data).unwrap()), data);
+    }
+}
+
+#[test]
+fn test_sensor_data_with_multiple_fields_not_single_reading() {
+    // 空数据或含多项数据时无法转换为单次读数
+    let mut data = SensorData::new();
+    assert_eq!(SensorReading::try_from(data), Err(DriverError::DataFormatError));
+
+    data.record(SensorReading::Temperature(25.0));
+    data.record(SensorReading::Humidity(40.0));
+    assert_eq!(SensorReading::try_from(data), Err(DriverError::DataFormatError));
+    assert_eq!((data.temperature, data.humidity), (Some(25.0), Some(40.0)));
+}
@@ -7,29 +7,63 @@
 use core::future::Future;
 use core::pin::Pin;
 use core::task::{Context, Poll, Waker};
-use core::cell::RefCell;
-use core::sync::atomic::{AtomicBool, Ordering};
+use core::cell::{Cell, RefCell};
+use core::fmt;
+use core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use core::time::Duration;
 use alloc::collections::VecDeque;
 use alloc::boxed::Box;
+use alloc::vec::Vec;
+use starry_kernel::scheduler::tick_manager;
+use starry_kernel::sync::IrqMutex;
+
+/// 默认任务容量
+pub const DEFAULT_TASK_CAPACITY: usize = 32;
+
+/// 任务ID
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TaskId(pub u64);
+
+/// 任务创建错误
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpawnError {
+    /// 任务数已达执行器容量上限
+    AtCapacity,
+}
+
+impl fmt::Display for SpawnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpawnError::AtCapacity => write!(f, "任务数已达容量上限"),
+        }
+    }
+}
 
 /// 异步任务句柄
 pub struct Task {
+    id: TaskId,
     future: Pin<Box<dyn Future<Output = ()>>>,
     waker: Option<Waker>,
 }
 
 impl Task {
     /// 创建新的异步任务
-    pub fn new<F>(future: F) -> Self 
+    pub fn new<F>(id: TaskId, future: F) -> Self 
     where
         F: Future<Output = ()> + 'static,
     {
         Self {
+            id,
             future: Box::pin(future),
             waker: None,
         }
     }
     
+    /// 获取任务ID
+    pub fn id(&self) -> TaskId {
+        self.id
+    }
+    
     /// 轮询任务执行
     pub fn poll(&mut self, cx: &mut Context) -> Poll<()> {
         self.future.as_mut().poll(cx)
@@ -37,27 +71,95 @@ impl Task {
 }
 
 /// 异步执行器
+/// 
+/// 任务数量受固定容量限制，避免失控的任务创建耗尽内存
 pub struct Executor {
     task_queue: RefCell<VecDeque<Task>>,
     running: AtomicBool,
+    capacity: usize,
+    /// 正在轮询（已出队）的任务数
+    polling: Cell<usize>,
+    next_id: Cell<u64>,
 }
 
 impl Executor {
-    /// 创建新的执行器
+    /// 创建新的执行器（默认容量）
     pub const fn new() -> Self {
+        Self::with_capacity(DEFAULT_TASK_CAPACITY)
+    }
+    
+    /// 创建指定任务容量的执行器
+    pub const fn with_capacity(capacity: usize) -> Self {
         Self {
             task_queue: RefCell::new(VecDeque::with_capacity(16)), // 预分配容量
             running: AtomicBool::new(false),
+            capacity,
+            polling: Cell::new(0),
+            next_id: Cell::new(0),
         }
     }
     
-    /// 启动异步任务
+    /// 启动异步任务，任务数已满时panic
     pub fn spawn<F>(&self, future: F)
     where
         F: Future<Output = ()> + 'static,
     {
-        let mut queue = self.task_queue.borrow_mut();
-        queue.push_back(Task::new(future));
+        if let Err(e) = self.try_spawn(future) {
+            panic!("创建异步任务失败: {}", e);
+        }
+    }
+    
+    /// 尝试启动异步任务，任务数已满时返回`SpawnError::AtCapacity`
+    pub fn try_spawn<F>(&self, future: F) -> Result<TaskId, SpawnError>
+    where
+        F: Future<Output = ()> + 'static,
+    {
+        if self.task_count() >= self.capacity {
+            return Err(SpawnError::AtCapacity);
+        }
+        
+        let id = TaskId(self.next_id.get());
+        self.next_id.set(id.0 + 1);
+        self.task_queue.borrow_mut().push_back(Task::new(id, future));
+        Ok(id)
+    }
+    
+    /// 未完成的任务数
+    pub fn task_count(&self) -> usize {
+        self.task_queue.borrow().len() + self.polling.get()
+    }
+    
+    /// 任务容量
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+    
+    /// 将当前队列中的任务各轮询一次，返回本轮完成的任务数
+    pub fn run_once(&self, cx: &mut Context) -> usize {
+        let mut completed_tasks = 0;
+        let total_tasks = self.task_queue.borrow().len();
+        
+        for _ in 0..total_tasks {
+            // 轮询期间不持有队列借用，允许任务内部创建新任务
+            let next = self.task_queue.borrow_mut().pop_front();
+            if let Some(mut task) = next {
+                self.polling.set(self.polling.get() + 1);
+                let result = task.poll(cx);
+                self.polling.set(self.polling.get() - 1);
+                
+                match result {
+                    Poll::Ready(()) => {
+                        completed_tasks += 1;
+                    }
+                    Poll::Pending => {
+                        // 任务未完成，重新加入队列末尾
+                        self.task_queue.borrow_mut().push_back(task);
+                    }
+                }
+            }
+        }
+        
+        completed_tasks
     }
     
     /// 运行所有任务
@@ -69,34 +171,18 @@ impl Executor {
         let mut cx = Context::from_waker(&waker);
         
         while self.running.load(Ordering::Acquire) {
-            let mut queue = self.task_queue.borrow_mut();
+            let total_tasks = self.task_queue.borrow().len();
             
-            if queue.is_empty() {
+            if total_tasks == 0 {
                 // 没有任务时进入低功耗模式
                 unsafe { core::arch::asm!("wfe") };
                 continue;
             }
             
-            // 优化：使用更高效的调度算法
-            let mut completed_tasks = 0;
-            let total_tasks = queue.len();
-            
-            for _ in 0..total_tasks {
-                if let Some(mut task) = queue.pop_front() {
-                    match task.poll(&mut cx) {
-                        Poll::Ready(()) => {
-                            completed_tasks += 1;
-                        }
-                        Poll::Pending => {
-                            // 任务未完成，重新加入队列末尾
-                            queue.push_back(task);
-                        }
-                    }
-                }
-            }
+            let completed_tasks = self.run_once(&mut cx);
             
             // 如果所有任务都完成，检查是否需要停止
-            if completed_tasks == total_tasks && queue.is_empty() {
+            if completed_tasks == total_tasks && self.task_queue.borrow().is_empty() {
                 break;
             }
         }
@@ -108,10 +194,160 @@ impl Executor {
     }
 }
 
+/// 单调时钟
+pub trait MonotonicClock {
+    /// 自启动以来经过的时间
+    fn now(&self) -> Duration;
+    
+    /// 安排在`deadline`产生一次定时器中断以唤醒到期任务
+    /// 
+    /// 默认不安排，由调用者自行调用`TimerQueue::process_expired`
+    fn schedule_wakeup(&self, _deadline: Duration) {}
+}
+
+/// 系统通用定时器（CNTPCT_EL0）
+pub struct SystemTimer;
+
+impl MonotonicClock for SystemTimer {
+    fn now(&self) -> Duration {
+        Duration::from_micros(starry_kernel::uptime_micros())
+    }
+    
+    /// 在当前核心的节拍管理器上添加定时器，到期时由定时器中断唤醒全局运行时中的到期任务
+    fn schedule_wakeup(&self, deadline: Duration) {
+        let frequency = starry_kernel::get_timer_frequency() as u128;
+        if frequency == 0 {
+            // 定时器未配置，由调用者轮询process_expired
+            return;
+        }
+        let count = (deadline.as_nanos() * frequency).div_ceil(1_000_000_000) as u64;
+        tick_manager().lock().add_timer(count, wake_expired_sleepers, 0);
+    }
+}
+
+/// 节拍定时器回调：唤醒全局运行时中已到期的任务
+fn wake_expired_sleepers(_data: usize) {
+    get_async_runtime().timers().process_expired();
+}
+
+/// 定时器队列
+/// 
+/// 记录等待截止时间的任务唤醒器，由定时器中断调用`process_expired`唤醒到期任务。
+/// 每个`Sleep`以队列分配的编号登记，任务与中断共享等待列表，持锁期间屏蔽本核IRQ
+pub struct TimerQueue<C: MonotonicClock> {
+    clock: C,
+    sleepers: IrqMutex<Vec<(u64, Duration, Waker)>>,
+    next_sleep_id: AtomicU64,
+}
+
+impl<C: MonotonicClock> TimerQueue<C> {
+    /// 创建基于指定时钟的定时器队列
+    pub const fn new(clock: C) -> Self {
+        Self {
+            clock,
+            sleepers: IrqMutex::new(Vec::new()),
+            next_sleep_id: AtomicU64::new(0),
+        }
+    }
+    
+    /// 当前时间
+    pub fn now(&self) -> Duration {
+        self.clock.now()
+    }
+    
+    /// 异步等待`duration`
+    pub fn sleep(&self, duration: Duration) -> Sleep<'_, C> {
+        Sleep {
+            timers: self,
+            id: self.next_sleep_id.fetch_add(1, Ordering::Relaxed),
+            deadline: self.now() + duration,
+        }
+    }
+    
+    /// 阻塞等待`duration`，供非异步上下文使用
+    pub fn delay(&self, duration: Duration) {
+        let deadline = self.now() + duration;
+        while self.now() < deadline {
+            core::hint::spin_loop();
+        }
+    }
+    
+    /// 等待中的任务数
+    pub fn pending(&self) -> usize {
+        self.sleepers.lock().len()
+    }
+    
+    /// 唤醒所有已到期的任务，返回唤醒数
+    pub fn process_expired(&self) -> usize {
+        let now = self.now();
+        let mut expired = Vec::new();
+        self.sleepers.lock().retain(|(_, deadline, waker)| {
+            if *deadline <= now {
+                expired.push(waker.clone());
+                false
+            } else {
+                true
+            }
+        });
+        
+        // 释放锁后再唤醒，允许被唤醒的任务重新注册
+        let count = expired.len();
+        expired.into_iter().for_each(Waker::wake);
+        count
+    }
+    
+    /// 登记唤醒器，同一`Sleep`重复轮询时只更新唤醒器；
+    /// 首次登记且没有其他等待项使用相同截止时间时安排定时器中断
+    fn register(&self, id: u64, deadline: Duration, waker: &Waker) {
+        let schedule = {
+            let mut sleepers = self.sleepers.lock();
+            match sleepers.iter_mut().find(|(entry_id, _, _)| *entry_id == id) {
+                Some(entry) => {
+                    if !entry.2.will_wake(waker) {
+                        entry.2 = waker.clone();
+                    }
+                    false
+                }
+                None => {
+                    let scheduled = sleepers.iter().any(|(_, d, _)| *d == deadline);
+                    sleepers.push((id, deadline, waker.clone()));
+                    !scheduled
+                }
+            }
+        };
+        
+        if schedule {
+            self.clock.schedule_wakeup(deadline);
+        }
+    }
+}
+
+/// `TimerQueue::sleep`返回的Future
+pub struct Sleep<'a, C: MonotonicClock> {
+    timers: &'a TimerQueue<C>,
+    id: u64,
+    deadline: Duration,
+}
+
+impl<'a, C: MonotonicClock> Future for Sleep<'a, C> {
+    type Output = ();
+    
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.timers.now() >= self.deadline {
+            Poll::Ready(())
+        } else {
+            self.timers.register(self.id, self.deadline, cx.waker());
+            Poll::Pending
+        }
+    }
+}
+
 /// 异步运行时管理器
 pub struct AsyncRuntime {
     executor: Executor,
     dma_controller: Option<DmaController>,
+    timers: TimerQueue<SystemTimer>,
+    active: AtomicBool,
 }
 
 impl AsyncRuntime {
@@ -120,6 +356,8 @@ impl AsyncRuntime {
         Self {
             executor: Executor::new(),
             dma_controller: None,
+            timers: TimerQueue::new(SystemTimer),
+            active: AtomicBool::new(false),
         }
     }
     
@@ -129,10 +367,16 @@ impl AsyncRuntime {
         self.dma_controller = Some(DmaController::new());
         
         // 启动执行器（在独立任务中运行）
+        self.active.store(true, Ordering::Release);
         Ok(())
     }
     
-    /// 启动异步任务
+    /// 运行时是否已初始化
+    pub fn is_active(&self) -> bool {
+        self.active.load(Ordering::Acquire)
+    }
+    
+    /// 启动异步任务，任务数已满时panic
     pub fn spawn<F>(&self, future: F)
     where
         F: Future<Output = ()> + 'static,
@@ -140,10 +384,23 @@ impl AsyncRuntime {
         self.executor.spawn(future);
     }
     
+    /// 尝试启动异步任务
+    pub fn try_spawn<F>(&self, future: F) -> Result<TaskId, SpawnError>
+    where
+        F: Future<Output = ()> + 'static,
+    {
+        self.executor.try_spawn(future)
+    }
+    
     /// 获取DMA控制器
     pub fn dma_controller(&self) -> Option<&DmaController> {
         self.dma_controller.as_ref()
     }
+    
+    /// 获取系统定时器队列
+    pub fn timers(&self) -> &TimerQueue<SystemTimer> {
+        &self.timers
+    }
 }
 
 /// 空操作唤醒器（简化实现）
@@ -162,11 +419,42 @@ static NOOP_WAKER_VTABLE: RawWakerVTable = RawWakerVTable::new(
     |_| {},
 );
 
+/// 异步等待`duration`，等待期间让出执行权
+pub fn sleep(duration: Duration) -> Sleep<'static, SystemTimer> {
+    get_async_runtime().timers().sleep(duration)
+}
+
+/// 阻塞等待`duration`，供无法使用`sleep`的非异步上下文使用
+pub fn delay(duration: Duration) {
+    get_async_runtime().timers().delay(duration);
+}
+
 /// 异步延迟函数
 pub async fn delay_ms(millis: u64) {
-    // 简化实现：忙等待
-    for _ in 0..millis * 1000 {
-        unsafe { core::arch::asm!("nop") };
+    sleep(Duration::from_millis(millis)).await;
+}
+
+/// 让出执行权一次，使执行器可以轮询其他任务
+pub fn yield_now() -> YieldNow {
+    YieldNow { yielded: false }
+}
+
+/// `yield_now`返回的Future
+pub struct YieldNow {
+    yielded: bool,
+}
+
+impl Future for YieldNow {
+    type Output = ();
+    
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.yielded {
+            Poll::Ready(())
+        } else {
+            self.yielded = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
     }
 }
 
@@ -272,4 +560,167 @@ pub fn init_async_runtime() -> Result<(), &'static str> {
 /// 获取全局异步运行时
 pub fn get_async_runtime() -> &'static AsyncRuntime {
     &ASYNC_RUNTIME
-}
\ No newline at end of file
+}
+
+/// 全局异步运行时是否已初始化
+pub fn is_active() -> bool {
+    ASYNC_RUNTIME.is_active()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::rc::Rc;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_try_spawn_respects_capacity() {
+        // 容量内创建成功，超出容量返回AtCapacity
+        let executor = Executor::with_capacity(3);
+        let ids: Vec<TaskId> = (0..3).map(|_| executor.try_spawn(async {}).unwrap()).collect();
+
+        assert_eq!(ids, vec![TaskId(0), TaskId(1), TaskId(2)]);
+        assert_eq!(executor.task_count(), 3);
+        assert_eq!(executor.capacity(), 3);
+        assert_eq!(executor.try_spawn(async {}), Err(SpawnError::AtCapacity));
+    }
+
+    #[test]
+    fn test_completed_task_frees_slot() {
+        // 任务完成后释放容量，可继续创建任务
+        let executor = Executor::with_capacity(2);
+        let ready = Rc::new(Cell::new(false));
+        let flag = ready.clone();
+
+        executor.try_spawn(async {}).unwrap();
+        executor.try_spawn(core::future::poll_fn(move |_| {
+            if flag.get() { Poll::Ready(()) } else { Poll::Pending }
+        })).unwrap();
+        assert_eq!(executor.try_spawn(async {}), Err(SpawnError::AtCapacity));
+
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        assert_eq!(executor.run_once(&mut cx), 1);
+        assert_eq!(executor.task_count(), 1);
+        assert!(executor.try_spawn(async {}).is_ok());
+
+        ready.set(true);
+        assert_eq!(executor.run_once(&mut cx), 2);
+        assert_eq!(executor.task_count(), 0);
+    }
+
+    /// 手动推进的模拟时钟，记录被安排的定时器中断
+    struct MockClock(Cell<Duration>, RefCell<Vec<Duration>>);
+
+    impl MonotonicClock for MockClock {
+        fn now(&self) -> Duration {
+            self.0.get()
+        }
+
+        fn schedule_wakeup(&self, deadline: Duration) {
+            self.1.borrow_mut().push(deadline);
+        }
+    }
+
+    impl MockClock {
+        fn new() -> Self {
+            Self(Cell::new(Duration::ZERO), RefCell::new(Vec::new()))
+        }
+
+        fn advance(&self, duration: Duration) {
+            self.0.set(self.0.get() + duration);
+        }
+    }
+
+    #[test]
+    fn test_sleep_completes_after_deadline() {
+        // 时钟越过截止时间后定时器唤醒任务，再次轮询时完成
+        let timers = Rc::new(TimerQueue::new(MockClock::new()));
+        let done = Rc::new(Cell::new(false));
+        let executor = Executor::new();
+        let (task_timers, task_done) = (timers.clone(), done.clone());
+        executor.spawn(async move {
+            task_timers.sleep(Duration::from_millis(10)).await;
+            task_done.set(true);
+        });
+
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        executor.run_once(&mut cx);
+        assert_eq!(timers.pending(), 1);
+
+        timers.clock.advance(Duration::from_millis(5));
+        assert_eq!(timers.process_expired(), 0);
+        executor.run_once(&mut cx);
+        assert!(!done.get());
+        assert_eq!(timers.pending(), 1);
+
+        timers.clock.advance(Duration::from_millis(5));
+        assert_eq!(timers.process_expired(), 1);
+        assert_eq!(executor.run_once(&mut cx), 1);
+        assert!(done.get());
+        assert_eq!(timers.pending(), 0);
+
+        // 重复轮询同一截止时间只安排一次定时器中断
+        assert_eq!(*timers.clock.1.borrow(), vec![Duration::from_millis(10)]);
+    }
+
+    #[test]
+    fn test_sleep_yields_to_other_tasks() {
+        // 等待期间其他任务继续执行
+        let timers = Rc::new(TimerQueue::new(MockClock::new()));
+        let order = Rc::new(RefCell::new(Vec::new()));
+        let executor = Executor::new();
+
+        let (task_timers, sleeper_order) = (timers.clone(), order.clone());
+        executor.spawn(async move {
+            task_timers.sleep(Duration::from_millis(1)).await;
+            sleeper_order.borrow_mut().push("sleeper");
+        });
+        let worker_order = order.clone();
+        executor.spawn(async move {
+            worker_order.borrow_mut().push("worker");
+        });
+
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        assert_eq!(executor.run_once(&mut cx), 1);
+        assert_eq!(*order.borrow(), vec!["worker"]);
+
+        timers.clock.advance(Duration::from_millis(1));
+        assert_eq!(executor.run_once(&mut cx), 1);
+        assert_eq!(*order.borrow(), vec!["worker", "sleeper"]);
+    }
+
+    #[test]
+    fn test_concurrent_sleeps_tracked_separately() {
+        // 共用同一唤醒器的任务各自登记，重复轮询不重复安排定时器中断
+        let timers = Rc::new(TimerQueue::new(MockClock::new()));
+        let done = Rc::new(Cell::new(0));
+        let executor = Executor::new();
+        for millis in [5, 10] {
+            let (task_timers, task_done) = (timers.clone(), done.clone());
+            executor.spawn(async move {
+                task_timers.sleep(Duration::from_millis(millis)).await;
+                task_done.set(task_done.get() + 1);
+            });
+        }
+
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        executor.run_once(&mut cx);
+        executor.run_once(&mut cx);
+        assert_eq!(timers.pending(), 2);
+        assert_eq!(*timers.clock.1.borrow(), vec![Duration::from_millis(5), Duration::from_millis(10)]);
+
+        timers.clock.advance(Duration::from_millis(5));
+        assert_eq!(timers.process_expired(), 1);
+        assert_eq!(executor.run_once(&mut cx), 1);
+        assert_eq!(timers.pending(), 1);
+
+        timers.clock.advance(Duration::from_millis(5));
+        assert_eq!(timers.process_expired(), 1);
+        assert_eq!(executor.run_once(&mut cx), 1);
+        assert_eq!(done.get(), 2);
+    }
+}
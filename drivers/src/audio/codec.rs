@@ -3,6 +3,7 @@
 //! 提供音频数据的编解码功能，支持多种音频格式
 
 use crate::DriverError;
+use super::AudioConfig;
 use alloc::vec::Vec;
 
 /// 音频编解码器
@@ -33,6 +34,25 @@ impl AudioCodec {
         }
     }
     
+    /// 按编解码器的采样率和声道数生成指定帧长的音频配置
+    pub fn audio_config(&self, frame_ms: u32) -> Result<AudioConfig, DriverError> {
+        let bit_depth = match self.format {
+            AudioFormat::PCM24 => 24,
+            AudioFormat::PCM32 => 32,
+            _ => 16,
+        };
+        let mut config = AudioConfig {
+            sample_rate: self.sample_rate,
+            channels: self.channels,
+            bit_depth,
+            buffer_size: 0,
+            frame_ms,
+        };
+        config.buffer_size = config.samples_per_frame();
+        config.validate()?;
+        Ok(config)
+    }
+    
     /// PCM编码（无压缩）
     fn encode_pcm(&self, audio_data: &[f32]) -> Vec<u8> {
         match self.format {
@@ -24,6 +24,7 @@ impl MicrophoneDriver {
                 channels: 1,         // 单声道
                 bit_depth: 16,      // 16位深度
                 buffer_size: 1600,  // 100ms缓冲区
+                frame_ms: 100,      // 100ms帧长
             },
             audio_buffer: Vec::new(),
         }
@@ -42,13 +43,13 @@ impl MicrophoneDriver {
     
     /// 模拟音频数据采集
     fn simulate_audio_capture(&mut self) -> Vec<i16> {
-        // 模拟100ms的音频数据 (16kHz采样率)
-        let samples = 1600; // 16kHz * 0.1s
+        // 按当前配置模拟一帧音频数据
+        let samples = self.config.samples_per_frame();
         let mut audio_data = Vec::with_capacity(samples);
         
         // 生成模拟音频信号 (正弦波 + 噪声)
         for i in 0..samples {
-            let t = i as f32 / 16000.0;
+            let t = i as f32 / self.config.sample_rate as f32;
             let signal = (2.0 * 3.14159 * 440.0 * t).sin(); // 440Hz正弦波
             let noise = (rand::random::<f32>() - 0.5) * 0.1; // 随机噪声
             let sample = ((signal + noise) * 32767.0) as i16;
@@ -120,6 +121,7 @@ impl AudioDriver for MicrophoneDriver {
     }
     
     fn set_config(&mut self, config: AudioConfig) -> Result<(), DriverError> {
+        config.validate()?;
         self.config = config;
         Ok(())
     }
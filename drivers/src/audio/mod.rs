@@ -17,13 +17,59 @@ pub enum AudioDevice {
     Codec,
 }
 
+/// 判定为有效语音所需的最短持续时间 (ms)
+const MIN_SPEECH_MS: u32 = 300;
+/// AGC增益调整的时间常数 (ms)
+const AGC_TIME_CONSTANT_MS: u32 = 500;
+/// AGC增益范围
+const AGC_MIN_GAIN: f32 = 0.1;
+const AGC_MAX_GAIN: f32 = 10.0;
+
 /// 音频配置参数
-#[derive(Debug, Clone, Copy)]
+/// 
+/// 采集、VAD、AGC和识别的帧长均由该配置计算，保证各环节一致
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct AudioConfig {
     pub sample_rate: u32,      // 采样率 (Hz)
     pub channels: u8,         // 声道数
     pub bit_depth: u8,        // 位深度
-    pub buffer_size: usize,   // 缓冲区大小
+    pub buffer_size: usize,   // 缓冲区大小（采样点数）
+    pub frame_ms: u32,        // 帧长 (ms)
+}
+
+impl AudioConfig {
+    /// 每帧的采样点数（含所有声道）
+    pub fn samples_per_frame(&self) -> usize {
+        (self.sample_rate as usize * self.frame_ms as usize / 1000) * self.channels as usize
+    }
+    
+    /// 校验配置
+    /// 
+    /// 帧长必须对应整数个采样点，缓冲区必须至少容纳一帧
+    pub fn validate(&self) -> Result<(), DriverError> {
+        if self.sample_rate == 0 || self.frame_ms == 0 || self.channels == 0 || self.channels > 2 {
+            return Err(DriverError::InvalidParameter);
+        }
+        if (self.sample_rate as u64 * self.frame_ms as u64) % 1000 != 0 {
+            return Err(DriverError::InvalidParameter);
+        }
+        if self.buffer_size < self.samples_per_frame() {
+            return Err(DriverError::InvalidParameter);
+        }
+        Ok(())
+    }
+}
+
+impl Default for AudioConfig {
+    fn default() -> Self {
+        Self {
+            sample_rate: 16000,
+            channels: 1,
+            bit_depth: 16,
+            buffer_size: 1600,
+            frame_ms: 100,
+        }
+    }
 }
 
 /// 音频数据格式
@@ -59,18 +105,37 @@ pub struct VoiceActivityDetector {
     energy_threshold: f32,
     silence_duration: u32,
     speech_duration: u32,
+    frame_samples: usize,
+    min_speech_frames: u32,
 }
 
 impl VoiceActivityDetector {
-    /// 创建新的VAD检测器
+    /// 创建新的VAD检测器（默认16kHz、100ms帧）
     pub fn new(energy_threshold: f32) -> Self {
+        Self::with_config(energy_threshold, &AudioConfig::default())
+    }
+    
+    /// 按音频配置创建VAD检测器，语音判定窗口随帧长换算
+    pub fn with_config(energy_threshold: f32, config: &AudioConfig) -> Self {
         Self {
             energy_threshold,
             silence_duration: 0,
             speech_duration: 0,
+            frame_samples: config.samples_per_frame(),
+            min_speech_frames: (MIN_SPEECH_MS + config.frame_ms - 1) / config.frame_ms,
         }
     }
     
+    /// 每帧的采样点数
+    pub fn frame_samples(&self) -> usize {
+        self.frame_samples
+    }
+    
+    /// 判定为有效语音所需的连续语音帧数
+    pub fn min_speech_frames(&self) -> u32 {
+        self.min_speech_frames
+    }
+    
     /// 检测语音活动
     pub fn detect_voice_activity(&mut self, audio_data: &[i16]) -> bool {
         // 计算音频能量
@@ -79,7 +144,8 @@ impl VoiceActivityDetector {
         if energy > self.energy_threshold {
             self.speech_duration += 1;
             self.silence_duration = 0;
-            self.speech_duration > 3 // 连续3帧语音才认为是有效语音
+            // 连续语音超过MIN_SPEECH_MS才认为是有效语音
+            self.speech_duration > self.min_speech_frames
         } else {
             self.silence_duration += 1;
             self.speech_duration = 0;
@@ -97,26 +163,116 @@ impl VoiceActivityDetector {
     }
 }
 
+/// 自动增益控制 (AGC)
+pub struct AutomaticGainControl {
+    target_rms: f32,
+    gain: f32,
+    frame_samples: usize,
+    smoothing: f32,
+}
+
+impl AutomaticGainControl {
+    /// 按音频配置创建AGC，增益平滑系数随帧长换算
+    pub fn new(target_rms: f32, config: &AudioConfig) -> Self {
+        Self {
+            target_rms,
+            gain: 1.0,
+            frame_samples: config.samples_per_frame(),
+            smoothing: config.frame_ms as f32 / (AGC_TIME_CONSTANT_MS + config.frame_ms) as f32,
+        }
+    }
+    
+    /// 对一帧音频施加增益，帧长与配置不符时拒绝
+    pub fn process(&mut self, frame: &mut [i16]) -> Result<(), DriverError> {
+        if frame.len() != self.frame_samples {
+            return Err(DriverError::InvalidParameter);
+        }
+        
+        let sum_squares: f32 = frame.iter().map(|&sample| (sample as f32).powi(2)).sum();
+        let rms = (sum_squares / frame.len() as f32).sqrt();
+        if rms > 0.0 {
+            let desired = (self.target_rms / rms).max(AGC_MIN_GAIN).min(AGC_MAX_GAIN);
+            self.gain += (desired - self.gain) * self.smoothing;
+        }
+        
+        for sample in frame.iter_mut() {
+            *sample = (*sample as f32 * self.gain).max(i16::MIN as f32).min(i16::MAX as f32) as i16;
+        }
+        Ok(())
+    }
+    
+    /// 当前增益
+    pub fn gain(&self) -> f32 {
+        self.gain
+    }
+}
+
 /// 音频管理器
 pub struct AudioManager {
     devices: Vec<Box<dyn AudioDriver>>,
+    config: AudioConfig,
     vad: VoiceActivityDetector,
+    agc: AutomaticGainControl,
     recording: bool,
 }
 
+/// VAD能量阈值
+const VAD_ENERGY_THRESHOLD: f32 = 1000.0;
+/// AGC目标电平 (RMS)
+const AGC_TARGET_RMS: f32 = 3000.0;
+
 impl AudioManager {
-    /// 创建新的音频管理器
+    /// 创建新的音频管理器（默认16kHz、100ms帧）
     pub fn new() -> Self {
+        Self::build(AudioConfig::default())
+    }
+    
+    /// 使用指定音频配置创建音频管理器
+    pub fn with_config(config: AudioConfig) -> Result<Self, DriverError> {
+        config.validate()?;
+        Ok(Self::build(config))
+    }
+    
+    fn build(config: AudioConfig) -> Self {
         Self {
             devices: Vec::new(),
-            vad: VoiceActivityDetector::new(1000.0), // 能量阈值
+            config,
+            vad: VoiceActivityDetector::with_config(VAD_ENERGY_THRESHOLD, &config),
+            agc: AutomaticGainControl::new(AGC_TARGET_RMS, &config),
             recording: false,
         }
     }
     
-    /// 注册音频设备
-    pub fn register_device(&mut self, device: Box<dyn AudioDriver>) {
+    /// 当前音频配置
+    pub fn config(&self) -> &AudioConfig {
+        &self.config
+    }
+    
+    /// 切换音频配置
+    /// 
+    /// 配置下发到所有已注册设备，并按新帧长重建VAD和AGC。
+    /// 某个设备拒绝新配置时，已切换的设备恢复原配置，管理器保持原配置不变
+    pub fn set_config(&mut self, config: AudioConfig) -> Result<(), DriverError> {
+        config.validate()?;
+        for index in 0..self.devices.len() {
+            if let Err(error) = self.devices[index].set_config(config) {
+                for device in self.devices[..index].iter_mut() {
+                    let _ = device.set_config(self.config);
+                }
+                return Err(error);
+            }
+        }
+        self.config = config;
+        self.vad = VoiceActivityDetector::with_config(VAD_ENERGY_THRESHOLD, &config);
+        self.agc = AutomaticGainControl::new(AGC_TARGET_RMS, &config);
+        Ok(())
+    }
+    
+    /// 注册音频设备，设备按当前音频配置工作
+    pub fn register_device(&mut self, mut device: Box<dyn AudioDriver>) -> Result<(), DriverError> {
+        device.set_config(self.config)?;
         self.devices.push(device);
+        Ok(())
     }
     
     /// 开始语音识别
@@ -135,15 +291,16 @@ impl AudioManager {
         }
         
         if let Some(device) = self.devices.first_mut() {
-            let mut buffer = vec![0i16; 1600]; // 100ms @ 16kHz
+            let mut buffer = vec![0i16; self.config.samples_per_frame()];
             let len = device.get_audio_data(&mut buffer)?;
             
-            if len > 0 {
-                let audio_data = buffer[..len].to_vec();
+            // 只处理完整的帧
+            if len == buffer.len() {
+                self.agc.process(&mut buffer)?;
                 
                 // 语音活动检测
-                if self.vad.detect_voice_activity(&audio_data) {
-                    return Ok(Some(audio_data));
+                if self.vad.detect_voice_activity(&buffer) {
+                    return Ok(Some(buffer));
                 }
             }
         }
@@ -159,4 +316,131 @@ impl AudioManager {
             Err(DriverError::DeviceNotFound)
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::rc::Rc;
+    use core::cell::Cell;
+    
+    /// 每次返回一帧固定幅值的模拟采集设备
+    struct ToneDevice {
+        config: AudioConfig,
+        amplitude: i16,
+    }
+    
+    impl Driver for ToneDevice {
+        fn name(&self) -> &'static str { "tone" }
+        fn init(&mut self) -> Result<(), DriverError> { Ok(()) }
+        fn is_ready(&self) -> bool { true }
+        fn deinit(&mut self) -> Result<(), DriverError> { Ok(()) }
+    }
+    
+    impl AudioDriver for ToneDevice {
+        fn start_recording(&mut self) -> Result<(), DriverError> { Ok(()) }
+        fn stop_recording(&mut self) -> Result<(), DriverError> { Ok(()) }
+        fn get_audio_data(&mut self, buffer: &mut [i16]) -> Result<usize, DriverError> {
+            let len = self.config.samples_per_frame().min(buffer.len());
+            for (i, sample) in buffer[..len].iter_mut().enumerate() {
+                *sample = if i % 2 == 0 { self.amplitude } else { -self.amplitude };
+            }
+            Ok(len)
+        }
+        fn play_audio(&mut self, _data: &[i16]) -> Result<(), DriverError> { Ok(()) }
+        fn set_config(&mut self, config: AudioConfig) -> Result<(), DriverError> {
+            self.config = config;
+            Ok(())
+        }
+    }
+    
+    /// 拒绝指定采样率的设备，当前配置对测试可见
+    struct PickyDevice {
+        config: Rc<Cell<AudioConfig>>,
+        rejected_rate: u32,
+    }
+    
+    impl Driver for PickyDevice {
+        fn name(&self) -> &'static str { "picky" }
+        fn init(&mut self) -> Result<(), DriverError> { Ok(()) }
+        fn is_ready(&self) -> bool { true }
+        fn deinit(&mut self) -> Result<(), DriverError> { Ok(()) }
+    }
+    
+    impl AudioDriver for PickyDevice {
+        fn start_recording(&mut self) -> Result<(), DriverError> { Ok(()) }
+        fn stop_recording(&mut self) -> Result<(), DriverError> { Ok(()) }
+        fn get_audio_data(&mut self, _buffer: &mut [i16]) -> Result<usize, DriverError> { Ok(0) }
+        fn play_audio(&mut self, _data: &[i16]) -> Result<(), DriverError> { Ok(()) }
+        fn set_config(&mut self, config: AudioConfig) -> Result<(), DriverError> {
+            if config.sample_rate == self.rejected_rate {
+                return Err(DriverError::InvalidParameter);
+            }
+            self.config.set(config);
+            Ok(())
+        }
+    }
+    
+    fn narrowband() -> AudioConfig {
+        AudioConfig { sample_rate: 8000, channels: 1, bit_depth: 16, buffer_size: 160, frame_ms: 20 }
+    }
+    
+    #[test]
+    fn test_8khz_20ms_frames_sized_from_config() {
+        // 8kHz/20ms配置下设备、AGC和输出帧均为160个采样点，非法配置被拒绝
+        let mut manager = AudioManager::new();
+        manager.register_device(Box::new(ToneDevice { config: AudioConfig::default(), amplitude: 3000 })).unwrap();
+        manager.set_config(narrowband()).unwrap();
+        manager.start_voice_recognition().unwrap();
+        
+        let mut frame = None;
+        for _ in 0..=manager.vad.min_speech_frames() {
+            frame = manager.process_audio_data().unwrap();
+        }
+        assert_eq!(frame.map(|f| f.len()), Some(160));
+        
+        let codec = codec::AudioCodec::new(codec::AudioFormat::PCM16, 8000, 1);
+        assert_eq!(codec.audio_config(20), Ok(narrowband()));
+        
+        // 22.05kHz下10ms不是整数个采样点；缓冲区放不下一帧
+        assert!(codec::AudioCodec::new(codec::AudioFormat::PCM16, 22050, 1).audio_config(10).is_err());
+        assert_eq!(
+            manager.set_config(AudioConfig { buffer_size: 100, ..narrowband() }),
+            Err(DriverError::InvalidParameter)
+        );
+        assert_eq!(manager.config(), &narrowband());
+    }
+    
+    #[test]
+    fn test_vad_window_adapts_to_frame_length() {
+        // VAD的语音判定窗口按帧长换算为相同的持续时间
+        let default_vad = VoiceActivityDetector::new(1000.0);
+        assert_eq!(default_vad.frame_samples(), 1600);
+        assert_eq!(default_vad.min_speech_frames(), 3);
+        
+        let mut vad = VoiceActivityDetector::with_config(1000.0, &narrowband());
+        assert_eq!(vad.frame_samples(), 160);
+        assert_eq!(vad.min_speech_frames(), 15);
+        
+        let loud = [4000i16; 160];
+        for _ in 0..15 {
+            assert!(!vad.detect_voice_activity(&loud));
+        }
+        assert!(vad.detect_voice_activity(&loud));
+    }
+    
+    #[test]
+    fn test_rejected_config_rolls_back_earlier_devices() {
+        // 第二个设备拒绝新配置：第一个设备恢复原配置，管理器配置不变
+        let first = Rc::new(Cell::new(AudioConfig::default()));
+        let second = Rc::new(Cell::new(AudioConfig::default()));
+        let mut manager = AudioManager::new();
+        manager.register_device(Box::new(PickyDevice { config: first.clone(), rejected_rate: 0 })).unwrap();
+        manager.register_device(Box::new(PickyDevice { config: second.clone(), rejected_rate: 8000 })).unwrap();
+        
+        assert_eq!(manager.set_config(narrowband()), Err(DriverError::InvalidParameter));
+        assert_eq!(first.get(), AudioConfig::default());
+        assert_eq!(second.get(), AudioConfig::default());
+        assert_eq!(manager.config(), &AudioConfig::default());
+    }
+}
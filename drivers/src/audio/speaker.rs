@@ -24,6 +24,7 @@ impl SpeakerDriver {
                 channels: 2,         // 立体声
                 bit_depth: 16,       // 16位深度
                 buffer_size: 4410,   // 100ms缓冲区
+                frame_ms: 100,       // 100ms帧长
             },
             audio_buffer: Vec::new(),
         }
@@ -101,6 +102,7 @@ impl AudioDriver for SpeakerDriver {
     }
     
     fn set_config(&mut self, config: AudioConfig) -> Result<(), DriverError> {
+        config.validate()?;
         self.config = config;
         Ok(())
     }
@@ -0,0 +1,319 @@
+//! PWM蜂鸣器驱动
+//!
+//! 以PWM方波驱动无源蜂鸣器，支持单音和音符序列（旋律）播放，播放可随时停止
+
+use crate::{Driver, DriverError, AuxiliaryDriver};
+use super::{LightConfig, SoundConfig};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// 默认音量 (0-100)
+pub const DEFAULT_VOLUME: u8 = 50;
+
+/// 蜂鸣器PWM输出特征
+pub trait ToneOutput {
+    /// 设置PWM周期和高电平时间 (ns)，周期为0时关闭输出
+    fn set_pwm(&mut self, period_ns: u32, duty_ns: u32) -> Result<(), DriverError>;
+
+    /// 延时1毫秒
+    fn delay_1ms(&mut self);
+}
+
+/// 一个音符对应的PWM参数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PwmSetting {
+    pub period_ns: u32,
+    pub duty_ns: u32,
+}
+
+impl PwmSetting {
+    /// 静音
+    pub const SILENT: Self = Self { period_ns: 0, duty_ns: 0 };
+
+    /// 指定频率和音量 (0-100) 的PWM参数，频率为0时为静音
+    ///
+    /// 无源蜂鸣器在50%占空比时最响，音量按比例缩小占空比
+    pub fn for_tone(frequency: u32, volume: u8) -> Self {
+        if frequency == 0 || volume == 0 {
+            return Self::SILENT;
+        }
+        let period_ns = 1_000_000_000 / frequency;
+        let duty_ns = (period_ns as u64 / 2 * volume.min(100) as u64 / 100) as u32;
+        Self { period_ns, duty_ns }
+    }
+}
+
+/// 旋律：音符序列（频率Hz, 时长ms）及音符间隔
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Melody {
+    notes: Vec<(u32, u32)>,
+    gap_ms: u32,
+}
+
+impl Melody {
+    /// 创建空旋律
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 追加音符
+    pub fn note(mut self, frequency: u32, duration_ms: u32) -> Self {
+        self.notes.push((frequency, duration_ms));
+        self
+    }
+
+    /// 追加休止
+    pub fn rest(self, duration_ms: u32) -> Self {
+        self.note(0, duration_ms)
+    }
+
+    /// 设置相邻音符之间的静音间隔
+    pub fn gap(mut self, gap_ms: u32) -> Self {
+        self.gap_ms = gap_ms;
+        self
+    }
+
+    /// 把当前音符重复`times`次
+    pub fn repeat(mut self, times: usize) -> Self {
+        let once = core::mem::take(&mut self.notes);
+        for _ in 0..times {
+            self.notes.extend_from_slice(&once);
+        }
+        self
+    }
+
+    /// 音符序列
+    pub fn notes(&self) -> &[(u32, u32)] {
+        &self.notes
+    }
+
+    /// 音符间隔 (ms)
+    pub fn gap_ms(&self) -> u32 {
+        self.gap_ms
+    }
+
+    /// 提示音：单个2kHz短音
+    pub fn beep() -> Self {
+        Self::new().note(2000, 100)
+    }
+
+    /// 报警音：880Hz/660Hz交替三次
+    pub fn alarm() -> Self {
+        Self::new().note(880, 200).note(660, 200).repeat(3).gap(50)
+    }
+}
+
+/// 停止句柄，可在中断或其他任务中停止正在播放的序列
+#[derive(Debug, Clone)]
+pub struct BuzzerStopHandle(Arc<AtomicBool>);
+
+impl BuzzerStopHandle {
+    /// 请求停止播放，播放循环在1ms内静音并返回
+    pub fn stop(&self) {
+        self.0.store(true, Ordering::Release);
+    }
+}
+
+/// PWM蜂鸣器驱动
+pub struct BuzzerPWMDriver<P: ToneOutput> {
+    output: P,
+    initialized: bool,
+    volume: u8,
+    /// `play_sequence`中相邻音符之间的静音间隔 (ms)
+    note_gap_ms: u32,
+    stop_requested: Arc<AtomicBool>,
+}
+
+impl<P: ToneOutput> BuzzerPWMDriver<P> {
+    /// 创建新的蜂鸣器驱动实例
+    pub fn new(output: P) -> Self {
+        Self {
+            output,
+            initialized: false,
+            volume: DEFAULT_VOLUME,
+            note_gap_ms: 0,
+            stop_requested: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// 设置音量 (0-100)
+    pub fn set_volume(&mut self, volume: u8) {
+        self.volume = volume.min(100);
+    }
+
+    /// 设置`play_sequence`的音符间隔 (ms)
+    pub fn set_note_gap(&mut self, gap_ms: u32) {
+        self.note_gap_ms = gap_ms;
+    }
+
+    /// 获取停止句柄
+    pub fn stop_handle(&self) -> BuzzerStopHandle {
+        BuzzerStopHandle(self.stop_requested.clone())
+    }
+
+    /// 立即静音，并停止正在播放的序列
+    pub fn stop(&mut self) -> Result<(), DriverError> {
+        self.stop_requested.store(true, Ordering::Release);
+        self.output.set_pwm(0, 0)
+    }
+
+    /// 依次播放音符（频率Hz, 时长ms），频率为0的音符为休止
+    ///
+    /// 播放结束或被停止后静音；被停止时返回`Ok`
+    pub fn play_sequence(&mut self, notes: &[(u32, u32)]) -> Result<(), DriverError> {
+        let gap_ms = self.note_gap_ms;
+        self.play(notes, gap_ms)
+    }
+
+    /// 按旋律自带的音符间隔播放
+    pub fn play_melody(&mut self, melody: &Melody) -> Result<(), DriverError> {
+        self.play(melody.notes(), melody.gap_ms())
+    }
+
+    fn play(&mut self, notes: &[(u32, u32)], gap_ms: u32) -> Result<(), DriverError> {
+        if !self.initialized {
+            return Err(DriverError::DeviceNotFound);
+        }
+
+        self.stop_requested.store(false, Ordering::Release);
+        for (i, &(frequency, duration_ms)) in notes.iter().enumerate() {
+            if i > 0 && gap_ms > 0 && !self.hold(PwmSetting::SILENT, gap_ms)? {
+                return Ok(());
+            }
+            if !self.hold(PwmSetting::for_tone(frequency, self.volume), duration_ms)? {
+                return Ok(());
+            }
+        }
+        self.output.set_pwm(0, 0)
+    }
+
+    /// 输出指定PWM参数并保持`duration_ms`，被停止时静音并返回false
+    fn hold(&mut self, setting: PwmSetting, duration_ms: u32) -> Result<bool, DriverError> {
+        self.output.set_pwm(setting.period_ns, setting.duty_ns)?;
+        for _ in 0..duration_ms {
+            if self.stop_requested.load(Ordering::Acquire) {
+                self.output.set_pwm(0, 0)?;
+                return Ok(false);
+            }
+            self.output.delay_1ms();
+        }
+        Ok(true)
+    }
+}
+
+impl<P: ToneOutput> Driver for BuzzerPWMDriver<P> {
+    fn name(&self) -> &'static str {
+        "PWM Buzzer"
+    }
+
+    fn init(&mut self) -> Result<(), DriverError> {
+        self.output.set_pwm(0, 0)?;
+        self.initialized = true;
+        Ok(())
+    }
+
+    fn is_ready(&self) -> bool {
+        self.initialized
+    }
+
+    fn deinit(&mut self) -> Result<(), DriverError> {
+        self.output.set_pwm(0, 0)?;
+        self.initialized = false;
+        Ok(())
+    }
+}
+
+impl<P: ToneOutput> AuxiliaryDriver for BuzzerPWMDriver<P> {
+    fn display_text(&mut self, _text: &str) -> Result<(), DriverError> {
+        // 蜂鸣器不支持文本显示
+        Err(DriverError::NotSupported)
+    }
+
+    fn play_sound(&mut self, config: SoundConfig) -> Result<(), DriverError> {
+        self.set_volume(config.volume);
+        self.play_sequence(&[(config.frequency, config.duration)])
+    }
+
+    fn set_light(&mut self, _config: LightConfig) -> Result<(), DriverError> {
+        // 蜂鸣器不支持灯光控制
+        Err(DriverError::NotSupported)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    /// 记录每次PWM设置及其保持的毫秒数，可在指定时刻触发停止
+    struct MockOutput {
+        log: Vec<(PwmSetting, u32)>,
+        elapsed_ms: u32,
+        stop_at: Option<(u32, BuzzerStopHandle)>,
+    }
+
+    impl ToneOutput for MockOutput {
+        fn set_pwm(&mut self, period_ns: u32, duty_ns: u32) -> Result<(), DriverError> {
+            self.log.push((PwmSetting { period_ns, duty_ns }, 0));
+            Ok(())
+        }
+
+        fn delay_1ms(&mut self) {
+            self.log.last_mut().unwrap().1 += 1;
+            self.elapsed_ms += 1;
+            if let Some((at, handle)) = &self.stop_at {
+                if self.elapsed_ms == *at {
+                    handle.stop();
+                }
+            }
+        }
+    }
+
+    fn buzzer() -> BuzzerPWMDriver<MockOutput> {
+        let mut buzzer = BuzzerPWMDriver::new(MockOutput { log: Vec::new(), elapsed_ms: 0, stop_at: None });
+        buzzer.init().unwrap();
+        buzzer.output.log.clear();
+        buzzer
+    }
+
+    #[test]
+    fn test_sequence_to_pwm_settings_with_rest() {
+        // 1kHz满音量为1ms周期50%占空比；休止和音符间隔为静音且时长正确，结束后静音
+        let mut buzzer = buzzer();
+        buzzer.set_volume(100);
+        buzzer.set_note_gap(10);
+        buzzer.play_sequence(&[(1000, 100), (0, 50), (2000, 30)]).unwrap();
+
+        let silent = PwmSetting::SILENT;
+        assert_eq!(buzzer.output.log, vec![
+            (PwmSetting { period_ns: 1_000_000, duty_ns: 500_000 }, 100),
+            (silent, 10),
+            (silent, 50),
+            (silent, 10),
+            (PwmSetting { period_ns: 500_000, duty_ns: 250_000 }, 30),
+            (silent, 0),
+        ]);
+
+        assert_eq!(PwmSetting::for_tone(1000, 50).duty_ns, 250_000);
+        assert_eq!(Melody::alarm().notes().len(), 6);
+        assert_eq!(Melody::beep().rest(20).notes(), &[(2000, 100), (0, 20)]);
+    }
+
+    #[test]
+    fn test_stop_silences_mid_note() {
+        // 播放报警音时在第120ms请求停止：立即静音，不再播放后续音符
+        let mut buzzer = buzzer();
+        let handle = buzzer.stop_handle();
+        buzzer.output.stop_at = Some((120, handle));
+        buzzer.play_melody(&Melody::alarm()).unwrap();
+
+        assert_eq!(buzzer.output.elapsed_ms, 120);
+        assert_eq!(buzzer.output.log.len(), 2);
+        assert_eq!(buzzer.output.log[0].0, PwmSetting::for_tone(880, DEFAULT_VOLUME));
+        assert_eq!(buzzer.output.log[1], (PwmSetting::SILENT, 0));
+
+        buzzer.stop().unwrap();
+        assert_eq!(buzzer.output.log.last(), Some(&(PwmSetting::SILENT, 0)));
+    }
+}
@@ -0,0 +1,152 @@
+//! 单色显示帧缓冲
+//!
+//! 按SSD1306的页格式组织：每页8行，每字节对应一列中的8个像素，最低位为最上方像素
+
+use super::font5x7::{self, GLYPH_HEIGHT, GLYPH_SPACING, GLYPH_WIDTH};
+use super::{DisplayBus, DisplayConfig, OLEDSSD1306Driver};
+use crate::DriverError;
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// 1位色深帧缓冲
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Framebuffer {
+    width: u16,
+    height: u16,
+    buffer: Vec<u8>,
+}
+
+impl Framebuffer {
+    /// 按显示配置的分辨率创建全暗的帧缓冲
+    pub fn new(config: &DisplayConfig) -> Self {
+        let pages = (config.height as usize).div_ceil(8);
+        Self {
+            width: config.width,
+            height: config.height,
+            buffer: vec![0; config.width as usize * pages],
+        }
+    }
+
+    /// 宽度（像素）
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    /// 高度（像素）
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// 页格式的缓冲区数据
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    /// 熄灭全部像素
+    pub fn clear(&mut self) {
+        self.buffer.fill(0);
+    }
+
+    /// 像素在缓冲区中的字节序号和位掩码，超出屏幕时为None
+    fn locate(&self, x: i32, y: i32) -> Option<(usize, u8)> {
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            return None;
+        }
+        let index = (y as usize / 8) * self.width as usize + x as usize;
+        Some((index, 1 << (y as usize % 8)))
+    }
+
+    /// 设置像素，超出屏幕的坐标被忽略
+    pub fn set_pixel(&mut self, x: i32, y: i32, on: bool) {
+        if let Some((index, mask)) = self.locate(x, y) {
+            if on {
+                self.buffer[index] |= mask;
+            } else {
+                self.buffer[index] &= !mask;
+            }
+        }
+    }
+
+    /// 读取像素状态，超出屏幕时为false
+    pub fn pixel(&self, x: i32, y: i32) -> bool {
+        self.locate(x, y).is_some_and(|(index, mask)| self.buffer[index] & mask != 0)
+    }
+
+    /// 以(x, y)为左上角用5×7字体绘制文本，超出屏幕的部分被裁剪
+    pub fn draw_text(&mut self, x: i32, y: i32, text: &str) {
+        let mut cursor = x;
+        for c in text.chars() {
+            for (column, bits) in font5x7::glyph(c).iter().enumerate() {
+                for row in 0..GLYPH_HEIGHT {
+                    if bits & (1 << row) != 0 {
+                        self.set_pixel(cursor + column as i32, y + row as i32, true);
+                    }
+                }
+            }
+            cursor += (GLYPH_WIDTH + GLYPH_SPACING) as i32;
+        }
+    }
+
+    /// 把帧缓冲推送到显示屏，分辨率须与显示屏一致
+    pub fn flush<B: DisplayBus>(&self, driver: &mut OLEDSSD1306Driver<B>) -> Result<(), DriverError> {
+        driver.write_frame(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 丢弃所有写入的总线
+    struct NullBus;
+
+    impl DisplayBus for NullBus {
+        fn write(&mut self, _data: &[u8]) -> Result<(), DriverError> {
+            Ok(())
+        }
+    }
+
+    fn small() -> Framebuffer {
+        Framebuffer::new(&DisplayConfig { width: 32, height: 12, contrast: 0x7F })
+    }
+
+    #[test]
+    fn test_set_pixel_page_layout_and_clipping() {
+        // 像素按页存放：(3, 9)位于第2页第3列的第1位；越界坐标被忽略
+        let mut framebuffer = small();
+        assert_eq!(framebuffer.as_bytes().len(), 32 * 2);
+
+        framebuffer.set_pixel(3, 9, true);
+        assert_eq!(framebuffer.as_bytes()[32 + 3], 0b10);
+        assert!(framebuffer.pixel(3, 9));
+
+        framebuffer.set_pixel(-1, 0, true);
+        framebuffer.set_pixel(32, 0, true);
+        framebuffer.set_pixel(0, 12, true);
+        assert_eq!(framebuffer.as_bytes().iter().filter(|&&b| b != 0).count(), 1);
+
+        framebuffer.set_pixel(3, 9, false);
+        assert!(framebuffer.as_bytes().iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn test_draw_text_sets_glyph_bits() {
+        // 第一页中每列字节即字形列数据，第二个字符间隔1列；超出右边界的字符被裁剪
+        let mut framebuffer = small();
+        framebuffer.draw_text(1, 0, "Hi");
+        let bytes = framebuffer.as_bytes();
+        assert_eq!(&bytes[1..6], font5x7::glyph('H'));
+        assert_eq!(bytes[6], 0);
+        assert_eq!(&bytes[7..12], font5x7::glyph('i'));
+
+        framebuffer.clear();
+        framebuffer.draw_text(30, 8, "W");
+        assert!(framebuffer.pixel(30, 8));
+        assert_eq!(framebuffer.as_bytes()[..32], [0; 32]);
+
+        // 推送到分辨率不同的显示屏被拒绝
+        let mut display = OLEDSSD1306Driver::new(NullBus);
+        crate::Driver::init(&mut display).unwrap();
+        assert_eq!(framebuffer.flush(&mut display), Err(DriverError::InvalidParameter));
+    }
+}
@@ -0,0 +1,214 @@
+//! RGB LED驱动
+//!
+//! 以三路PWM驱动RGB LED，支持RGB/HSV设置和颜色渐变
+
+use crate::{Driver, DriverError, AuxiliaryDriver};
+use super::{LightConfig, SoundConfig};
+
+/// RGB LED输出特征
+pub trait RgbOutput {
+    /// 设置三路输出亮度 (0-255)
+    fn set_rgb(&mut self, red: u8, green: u8, blue: u8) -> Result<(), DriverError>;
+
+    /// 延时`ms`毫秒
+    fn delay_ms(&mut self, ms: u32);
+}
+
+/// 熄灭状态
+const OFF: LightConfig = LightConfig { red: 0, green: 0, blue: 0, brightness: 255 };
+
+/// HSV转换为RGB
+///
+/// 色相限制在[0, 360)内（360视为0），饱和度和明度限制在[0, 1]内
+pub fn hsv_to_rgb(hue: f32, saturation: f32, value: f32) -> (u8, u8, u8) {
+    let hue = if hue.is_finite() { hue.clamp(0.0, 360.0) % 360.0 } else { 0.0 };
+    let saturation = saturation.clamp(0.0, 1.0);
+    let value = value.clamp(0.0, 1.0);
+
+    let chroma = value * saturation;
+    let sector = hue / 60.0;
+    let distance = sector % 2.0 - 1.0;
+    let second = chroma * (1.0 - if distance < 0.0 { -distance } else { distance });
+    let (r, g, b) = match sector as u32 {
+        0 => (chroma, second, 0.0),
+        1 => (second, chroma, 0.0),
+        2 => (0.0, chroma, second),
+        3 => (0.0, second, chroma),
+        4 => (second, 0.0, chroma),
+        _ => (chroma, 0.0, second),
+    };
+
+    let offset = value - chroma;
+    let to_u8 = |channel: f32| ((channel + offset) * 255.0 + 0.5) as u8;
+    (to_u8(r), to_u8(g), to_u8(b))
+}
+
+/// 两个值之间第`step`/`steps`处的线性插值
+fn lerp(from: u8, to: u8, step: u32, steps: u32) -> u8 {
+    (from as i32 + (to as i32 - from as i32) * step as i32 / steps as i32) as u8
+}
+
+/// RGB LED驱动
+pub struct LEDRGBDriver<O: RgbOutput> {
+    output: O,
+    initialized: bool,
+    /// 当前颜色，渐变从此颜色开始
+    current: LightConfig,
+}
+
+impl<O: RgbOutput> LEDRGBDriver<O> {
+    /// 创建新的RGB LED驱动实例
+    pub fn new(output: O) -> Self {
+        Self {
+            output,
+            initialized: false,
+            current: OFF,
+        }
+    }
+
+    /// 当前颜色
+    pub fn current(&self) -> LightConfig {
+        self.current
+    }
+
+    /// 按颜色和亮度输出并记录为当前颜色
+    fn apply(&mut self, config: LightConfig) -> Result<(), DriverError> {
+        let scale = |channel: u8| (channel as u32 * config.brightness as u32 / 255) as u8;
+        self.output.set_rgb(scale(config.red), scale(config.green), scale(config.blue))?;
+        self.current = config;
+        Ok(())
+    }
+
+    /// 以HSV设置颜色，保持当前亮度
+    pub fn set_hsv(&mut self, hue: f32, saturation: f32, value: f32) -> Result<(), DriverError> {
+        if !self.initialized {
+            return Err(DriverError::DeviceNotFound);
+        }
+
+        let (red, green, blue) = hsv_to_rgb(hue, saturation, value);
+        self.apply(LightConfig { red, green, blue, brightness: self.current.brightness })
+    }
+
+    /// 从当前颜色分`steps`步渐变到目标颜色，总时长`duration_ms`
+    ///
+    /// 每步先延时再输出，最后一步恰好在`duration_ms`时到达目标颜色
+    pub fn fade_to(&mut self, target: LightConfig, duration_ms: u32, steps: u32) -> Result<(), DriverError> {
+        if !self.initialized {
+            return Err(DriverError::DeviceNotFound);
+        }
+
+        let steps = steps.max(1);
+        let from = self.current;
+        let mut elapsed_ms = 0;
+        for step in 1..=steps {
+            let due_ms = (duration_ms as u64 * step as u64 / steps as u64) as u32;
+            self.output.delay_ms(due_ms - elapsed_ms);
+            elapsed_ms = due_ms;
+
+            self.apply(LightConfig {
+                red: lerp(from.red, target.red, step, steps),
+                green: lerp(from.green, target.green, step, steps),
+                blue: lerp(from.blue, target.blue, step, steps),
+                brightness: lerp(from.brightness, target.brightness, step, steps),
+            })?;
+        }
+        Ok(())
+    }
+}
+
+impl<O: RgbOutput> Driver for LEDRGBDriver<O> {
+    fn name(&self) -> &'static str {
+        "RGB LED"
+    }
+
+    fn init(&mut self) -> Result<(), DriverError> {
+        self.apply(OFF)?;
+        self.initialized = true;
+        Ok(())
+    }
+
+    fn is_ready(&self) -> bool {
+        self.initialized
+    }
+
+    fn deinit(&mut self) -> Result<(), DriverError> {
+        self.apply(OFF)?;
+        self.initialized = false;
+        Ok(())
+    }
+}
+
+impl<O: RgbOutput> AuxiliaryDriver for LEDRGBDriver<O> {
+    fn display_text(&mut self, _text: &str) -> Result<(), DriverError> {
+        // LED不支持文本显示
+        Err(DriverError::NotSupported)
+    }
+
+    fn play_sound(&mut self, _config: SoundConfig) -> Result<(), DriverError> {
+        // LED不支持声音播放
+        Err(DriverError::NotSupported)
+    }
+
+    fn set_light(&mut self, config: LightConfig) -> Result<(), DriverError> {
+        if !self.initialized {
+            return Err(DriverError::DeviceNotFound);
+        }
+        self.apply(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    /// 记录每次输出及其之前累计的延时
+    #[derive(Default)]
+    struct MockOutput {
+        writes: Vec<((u8, u8, u8), u32)>,
+        elapsed_ms: u32,
+    }
+
+    impl RgbOutput for MockOutput {
+        fn set_rgb(&mut self, red: u8, green: u8, blue: u8) -> Result<(), DriverError> {
+            self.writes.push(((red, green, blue), self.elapsed_ms));
+            Ok(())
+        }
+
+        fn delay_ms(&mut self, ms: u32) {
+            self.elapsed_ms += ms;
+        }
+    }
+
+    #[test]
+    fn test_hsv_primaries_and_clamping() {
+        // 三原色、白色和黑色；越界的色相、饱和度和明度被限制
+        assert_eq!(hsv_to_rgb(0.0, 1.0, 1.0), (255, 0, 0));
+        assert_eq!(hsv_to_rgb(120.0, 1.0, 1.0), (0, 255, 0));
+        assert_eq!(hsv_to_rgb(240.0, 1.0, 1.0), (0, 0, 255));
+        assert_eq!(hsv_to_rgb(60.0, 0.0, 1.0), (255, 255, 255));
+        assert_eq!(hsv_to_rgb(300.0, 1.0, 0.0), (0, 0, 0));
+
+        assert_eq!(hsv_to_rgb(720.0, 2.0, 1.5), (255, 0, 0));
+        assert_eq!(hsv_to_rgb(-30.0, 1.0, 1.0), (255, 0, 0));
+        assert_eq!(hsv_to_rgb(60.0, 1.0, 0.5), (128, 128, 0));
+    }
+
+    #[test]
+    fn test_fade_interpolates_from_current_color() {
+        // 从HSV设置的红色分4步渐变到蓝色：首步为1/4插值，末步恰在总时长时到达目标
+        let mut led = LEDRGBDriver::new(MockOutput::default());
+        led.init().unwrap();
+        led.set_hsv(0.0, 1.0, 1.0).unwrap();
+
+        let blue = LightConfig { red: 0, green: 0, blue: 200, brightness: 255 };
+        led.fade_to(blue, 100, 4).unwrap();
+
+        let writes = &led.output.writes;
+        assert_eq!(writes.len(), 2 + 4);
+        assert_eq!(writes[1], ((255, 0, 0), 0));
+        assert_eq!(writes[2], ((192, 0, 50), 25));
+        assert_eq!(writes[5], ((0, 0, 200), 100));
+        assert_eq!(led.current(), blue);
+    }
+}
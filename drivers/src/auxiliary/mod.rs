@@ -3,12 +3,19 @@
 //! 提供显示屏、蜂鸣器、LED等辅助操作外设驱动支持
 
 mod oled_ssd1306;
+mod font5x7;
+mod framebuffer;
 mod buzzer_pwm;
 mod led_rgb;
 
 use crate::{Driver, DriverError};
 use alloc::vec::Vec;
 
+pub use oled_ssd1306::{DisplayBus, OLEDSSD1306Driver, DrawOptions};
+pub use framebuffer::Framebuffer;
+pub use buzzer_pwm::{BuzzerPWMDriver, BuzzerStopHandle, Melody, PwmSetting, ToneOutput};
+pub use led_rgb::{hsv_to_rgb, LEDRGBDriver, RgbOutput};
+
 /// 辅助设备类型
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AuxiliaryDevice {
@@ -34,7 +41,7 @@ pub struct SoundConfig {
 }
 
 /// 灯光配置参数
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct LightConfig {
     pub red: u8,
     pub green: u8,
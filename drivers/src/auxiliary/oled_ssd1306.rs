@@ -3,6 +3,13 @@
 //! 提供I2C接口的SSD1306 OLED显示屏驱动支持
 
 use crate::{Driver, DriverError, AuxiliaryDriver};
+use crate::i2c::{I2cDevice, I2cError};
+use super::font5x7::{self, GLYPH_HEIGHT};
+use super::{DisplayConfig, Framebuffer};
+use alloc::string::String;
+use alloc::vec::Vec;
+use common::Detection;
+use core::fmt::Write;
 use embedded_graphics::{
     mono_font::{MonoFont, MonoTextStyle},
     pixelcolor::BinaryColor,
@@ -10,22 +17,76 @@ use embedded_graphics::{
     text::{Text, Baseline},
 };
 
+/// 标签中类别名称的最大字符数
+const MAX_LABEL_CHARS: usize = 8;
+
+/// 控制字节：其后均为命令
+const CONTROL_COMMAND: u8 = 0x00;
+/// 控制字节：其后均为显示数据
+const CONTROL_DATA: u8 = 0x40;
+/// 命令：设置列地址范围
+const CMD_COLUMN_ADDRESS: u8 = 0x21;
+/// 命令：设置页地址范围
+const CMD_PAGE_ADDRESS: u8 = 0x22;
+/// 命令：关闭显示
+const CMD_DISPLAY_OFF: u8 = 0xAE;
+
+/// SSD1306所在总线，每次调用为一次完整的I2C写传输
+pub trait DisplayBus {
+    /// 向显示屏写入一次传输的数据
+    fn write(&mut self, data: &[u8]) -> Result<(), DriverError>;
+}
+
+impl DisplayBus for I2cDevice {
+    fn write(&mut self, data: &[u8]) -> Result<(), DriverError> {
+        I2cDevice::write(self, data).map_err(|e| match e {
+            I2cError::NotInitialized => DriverError::DeviceNotFound,
+            I2cError::Timeout => DriverError::Timeout,
+            I2cError::BusBusy => DriverError::DeviceBusy,
+            _ => DriverError::CommunicationError,
+        })
+    }
+}
+
+/// 检测结果绘制选项
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DrawOptions {
+    /// 是否在检测框上方显示类别名称
+    pub show_labels: bool,
+    /// 是否在标签中显示置信度百分比
+    pub show_confidence: bool,
+    /// 最多绘制的检测框数量（按置信度从高到低保留）
+    pub max_boxes: usize,
+}
+
+impl Default for DrawOptions {
+    fn default() -> Self {
+        Self {
+            show_labels: true,
+            show_confidence: true,
+            max_boxes: 4,
+        }
+    }
+}
+
 /// SSD1306 OLED驱动
-pub struct OLEDSSD1306Driver {
+pub struct OLEDSSD1306Driver<B: DisplayBus = I2cDevice> {
+    bus: B,
     initialized: bool,
     width: u32,
     height: u32,
-    buffer: [u8; 1024], // 显示缓冲区
+    buffer: Framebuffer, // 显示缓冲区
 }
 
-impl OLEDSSD1306Driver {
-    /// 创建新的SSD1306 OLED驱动实例
-    pub fn new() -> Self {
+impl<B: DisplayBus> OLEDSSD1306Driver<B> {
+    /// 创建通过`bus`访问显示屏的SSD1306 OLED驱动实例
+    pub fn new(bus: B) -> Self {
         Self {
+            bus,
             initialized: false,
             width: 128,
             height: 64,
-            buffer: [0; 1024],
+            buffer: Framebuffer::new(&DisplayConfig { width: 128, height: 64, contrast: 0xCF }),
         }
     }
     
@@ -51,25 +112,133 @@ impl OLEDSSD1306Driver {
             0xAF, // 开启显示
         ];
         
-        // 实际实现需要通过I2C发送命令
-        
-        Ok(())
+        self.send_commands(&init_commands)
+    }
+    
+    /// 发送命令序列：控制字节0x00后接全部命令
+    fn send_commands(&mut self, commands: &[u8]) -> Result<(), DriverError> {
+        let mut transfer = Vec::with_capacity(commands.len() + 1);
+        transfer.push(CONTROL_COMMAND);
+        transfer.extend_from_slice(commands);
+        self.bus.write(&transfer)
     }
     
     /// 清空显示缓冲区
     fn clear_buffer(&mut self) {
-        self.buffer = [0; 1024];
+        self.buffer.clear();
     }
     
-    /// 更新显示
+    /// 更新显示：把列、页地址设为整屏范围后，以数据流写入整个页格式缓冲区
     fn update_display(&mut self) -> Result<(), DriverError> {
-        // 发送显示数据到OLED
-        // 实际实现需要通过I2C发送缓冲区数据
-        Ok(())
+        let last_column = (self.width - 1) as u8;
+        let last_page = (self.height.div_ceil(8) - 1) as u8;
+        self.send_commands(&[CMD_COLUMN_ADDRESS, 0, last_column, CMD_PAGE_ADDRESS, 0, last_page])?;
+        
+        let pixels = self.buffer.as_bytes();
+        let mut transfer = Vec::with_capacity(pixels.len() + 1);
+        transfer.push(CONTROL_DATA);
+        transfer.extend_from_slice(pixels);
+        self.bus.write(&transfer)
+    }
+    
+    /// 设置像素，超出屏幕的坐标被忽略
+    pub fn set_pixel(&mut self, x: i32, y: i32, on: bool) {
+        self.buffer.set_pixel(x, y, on);
+    }
+    
+    /// 读取像素状态
+    pub fn pixel(&self, x: i32, y: i32) -> bool {
+        self.buffer.pixel(x, y)
+    }
+    
+    /// 以(x, y)为左上角绘制文本
+    pub fn draw_text(&mut self, x: i32, y: i32, text: &str) {
+        self.buffer.draw_text(x, y, text);
+    }
+
+    /// 用外部帧缓冲替换显示内容并更新显示
+    pub fn write_frame(&mut self, frame: &Framebuffer) -> Result<(), DriverError> {
+        if !self.is_ready() {
+            return Err(DriverError::DeviceNotFound);
+        }
+        if frame.width() as u32 != self.width || frame.height() as u32 != self.height {
+            return Err(DriverError::InvalidParameter);
+        }
+        
+        self.buffer.clone_from(frame);
+        self.update_display()
+    }
+    
+    /// 绘制矩形边框
+    fn draw_rect(&mut self, left: i32, top: i32, right: i32, bottom: i32) {
+        for x in left..=right {
+            self.set_pixel(x, top, true);
+            self.set_pixel(x, bottom, true);
+        }
+        for y in top..=bottom {
+            self.set_pixel(left, y, true);
+            self.set_pixel(right, y, true);
+        }
+    }
+    
+    /// 绘制检测结果
+    /// 
+    /// 检测框坐标为屏幕像素坐标（中心点+宽高）。标签绘制在检测框上方，
+    /// 类别名称过长时截断，标签超出屏幕时不绘制
+    pub fn draw_detections(&mut self, detections: &[Detection], options: &DrawOptions) -> Result<(), DriverError> {
+        if !self.is_ready() {
+            return Err(DriverError::DeviceNotFound);
+        }
+        
+        self.clear_buffer();
+        
+        let mut selected: Vec<&Detection> = detections.iter().collect();
+        selected.sort_by(|a, b| a.ranking_cmp(b));
+        selected.truncate(options.max_boxes);
+        
+        for detection in selected {
+            let bbox = &detection.bbox;
+            let left = (bbox.x - bbox.width / 2.0) as i32;
+            let top = (bbox.y - bbox.height / 2.0) as i32;
+            let right = (bbox.x + bbox.width / 2.0) as i32;
+            let bottom = (bbox.y + bbox.height / 2.0) as i32;
+            self.draw_rect(left, top, right, bottom);
+            
+            if let Some(label) = Self::format_label(detection, options) {
+                let label_x = left;
+                let label_y = top - (GLYPH_HEIGHT + 1) as i32;
+                let label_width = font5x7::text_width(label.chars().count()) as i32;
+                if label_x >= 0 && label_y >= 0 && label_x + label_width <= self.width as i32 {
+                    self.draw_text(label_x, label_y, &label);
+                }
+            }
+        }
+        
+        self.update_display()
+    }
+    
+    /// 生成检测标签，如"person 87%"
+    fn format_label(detection: &Detection, options: &DrawOptions) -> Option<String> {
+        if !options.show_labels && !options.show_confidence {
+            return None;
+        }
+        
+        let mut label = String::new();
+        if options.show_labels {
+            label.extend(detection.class_name.chars().take(MAX_LABEL_CHARS));
+        }
+        if options.show_confidence {
+            if !label.is_empty() {
+                label.push(' ');
+            }
+            let percent = (detection.confidence.clamp(0.0, 1.0) * 100.0 + 0.5) as u32;
+            let _ = write!(label, "{}%", percent);
+        }
+        Some(label)
     }
 }
 
-impl Driver for OLEDSSD1306Driver {
+impl<B: DisplayBus> Driver for OLEDSSD1306Driver<B> {
     fn name(&self) -> &'static str {
         "SSD1306 OLED Display"
     }
@@ -92,22 +261,21 @@ impl Driver for OLEDSSD1306Driver {
     
     fn deinit(&mut self) -> Result<(), DriverError> {
         // 关闭显示
+        self.send_commands(&[CMD_DISPLAY_OFF])?;
         self.initialized = false;
         Ok(())
     }
 }
 
-impl AuxiliaryDriver for OLEDSSD1306Driver {
+impl<B: DisplayBus> AuxiliaryDriver for OLEDSSD1306Driver<B> {
     fn display_text(&mut self, text: &str) -> Result<(), DriverError> {
         if !self.is_ready() {
             return Err(DriverError::DeviceNotFound);
         }
         
-        // 清空缓冲区
+        // 清空缓冲区后从左上角绘制文本
         self.clear_buffer();
-        
-        // 简单的文本显示实现
-        // 实际实现需要使用embedded_graphics库
+        self.draw_text(0, 0, text);
         
         // 更新显示
         self.update_display()?;
@@ -124,4 +292,94 @@ impl AuxiliaryDriver for OLEDSSD1306Driver {
         // OLED不支持灯光控制
         Err(DriverError::NotSupported)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::font5x7::{GLYPH_SPACING, GLYPH_WIDTH};
+    use common::BoundingBox;
+
+    /// 记录每次I2C写传输的总线
+    #[derive(Default)]
+    struct RecordingBus {
+        transfers: Vec<Vec<u8>>,
+    }
+
+    impl DisplayBus for RecordingBus {
+        fn write(&mut self, data: &[u8]) -> Result<(), DriverError> {
+            self.transfers.push(data.to_vec());
+            Ok(())
+        }
+    }
+
+    fn ready_display() -> OLEDSSD1306Driver<RecordingBus> {
+        let mut display = OLEDSSD1306Driver::new(RecordingBus::default());
+        display.init().unwrap();
+        display
+    }
+
+    /// 检查屏幕上(x, y)处是否为指定字符的点阵
+    fn glyph_at(display: &OLEDSSD1306Driver<RecordingBus>, x: i32, y: i32, c: char) -> bool {
+        font5x7::glyph(c).iter().enumerate().all(|(column, bits)| {
+            (0..GLYPH_HEIGHT).all(|row| display.pixel(x + column as i32, y + row as i32) == (bits & (1 << row) != 0))
+        })
+    }
+
+    #[test]
+    fn test_label_rendered_above_box() {
+        // 标签绘制在检测框左上角上方，长类别名称被截断
+        let mut display = ready_display();
+        let detections = [
+            Detection::new(0, "person", 0.87, BoundingBox::new(40.0, 40.0, 20.0, 20.0)),
+            Detection::new(1, "person", 0.6, BoundingBox::new(120.0, 40.0, 10.0, 10.0)),
+        ];
+        display.draw_detections(&detections, &DrawOptions::default()).unwrap();
+
+        // 检测框左上角(30, 30)，标签位于y = 30 - 8
+        assert!(display.pixel(30, 30) && display.pixel(50, 50));
+        let step = (GLYPH_WIDTH + GLYPH_SPACING) as i32;
+        for (i, c) in "person 87%".chars().enumerate() {
+            assert!(glyph_at(&display, 30 + i as i32 * step, 22, c));
+        }
+
+        // 第二个检测框靠近屏幕右边缘，标签会超出屏幕而被跳过
+        assert!(display.pixel(115, 35));
+        assert!(!(27..34).any(|y| (115..128).any(|x| display.pixel(x, y))));
+
+        let long = Detection::new(2, "motorcycle_rider", 0.5, BoundingBox::new(60.0, 40.0, 10.0, 10.0));
+        let label = OLEDSSD1306Driver::<RecordingBus>::format_label(&long, &DrawOptions::default()).unwrap();
+        assert_eq!(label, "motorcyc 50%");
+    }
+
+    #[test]
+    fn test_max_boxes_keeps_highest_confidence() {
+        // 超出max_boxes的低置信度检测框不绘制
+        let mut display = ready_display();
+        let detections = [
+            Detection::new(0, "a", 0.3, BoundingBox::new(20.0, 40.0, 10.0, 10.0)),
+            Detection::new(0, "b", 0.9, BoundingBox::new(60.0, 40.0, 10.0, 10.0)),
+            Detection::new(0, "c", 0.6, BoundingBox::new(100.0, 40.0, 10.0, 10.0)),
+        ];
+        let options = DrawOptions { show_labels: false, show_confidence: false, max_boxes: 2 };
+        display.draw_detections(&detections, &options).unwrap();
+
+        assert!(!display.pixel(15, 35));
+        assert!(display.pixel(55, 35));
+        assert!(display.pixel(95, 35));
+    }
+
+    #[test]
+    fn test_update_display_addresses_full_screen_then_streams_data() {
+        // 初始化命令以0x00开头；更新显示先设置列0-127、页0-7，再以0x40开头发送整个缓冲区
+        let mut display = ready_display();
+        assert_eq!(display.bus.transfers[0][..2], [CONTROL_COMMAND, 0xAE]);
+
+        display.display_text("A").unwrap();
+        let transfers = &display.bus.transfers[1..];
+        assert_eq!(transfers[0], vec![CONTROL_COMMAND, 0x21, 0, 127, 0x22, 0, 7]);
+        assert_eq!(transfers[1][0], CONTROL_DATA);
+        assert_eq!(transfers[1].len(), 1 + 128 * 8);
+        assert_eq!(&transfers[1][1..6], font5x7::glyph('A'));
+    }
+}
@@ -0,0 +1,131 @@
+//! RK3588 时钟控制器(CRU)模块
+//!
+//! 集中管理各外设的输入时钟频率，供分频计算查询
+
+use core::fmt;
+use core::sync::atomic::{AtomicU32, Ordering};
+
+/// 外设时钟
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeripheralClock {
+    I2c,
+    Spi,
+    Uart,
+    Npu,
+}
+
+impl PeripheralClock {
+    /// 时钟数量
+    const COUNT: usize = 4;
+
+    fn index(self) -> usize {
+        match self {
+            PeripheralClock::I2c => 0,
+            PeripheralClock::Spi => 1,
+            PeripheralClock::Uart => 2,
+            PeripheralClock::Npu => 3,
+        }
+    }
+
+    /// 复位后的默认频率 (Hz)
+    const fn default_rate(self) -> u32 {
+        match self {
+            PeripheralClock::I2c => 200_000_000,
+            PeripheralClock::Spi => 200_000_000,
+            PeripheralClock::Uart => 24_000_000,
+            PeripheralClock::Npu => 800_000_000,
+        }
+    }
+
+    /// CRU可输出的频率范围 (Hz)
+    const fn supported_range(self) -> (u32, u32) {
+        match self {
+            PeripheralClock::I2c => (24_000_000, 200_000_000),
+            PeripheralClock::Spi => (24_000_000, 200_000_000),
+            PeripheralClock::Uart => (1_000_000, 200_000_000),
+            PeripheralClock::Npu => (100_000_000, 800_000_000),
+        }
+    }
+}
+
+/// 时钟错误类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClockError {
+    UnsupportedFrequency,
+}
+
+impl fmt::Display for ClockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClockError::UnsupportedFrequency => write!(f, "不支持的时钟频率"),
+        }
+    }
+}
+
+/// 时钟控制器
+pub struct ClockController {
+    rates: [AtomicU32; PeripheralClock::COUNT],
+}
+
+impl ClockController {
+    /// 创建新的时钟控制器（使用复位默认频率）
+    pub const fn new() -> Self {
+        Self {
+            rates: [
+                AtomicU32::new(PeripheralClock::I2c.default_rate()),
+                AtomicU32::new(PeripheralClock::Spi.default_rate()),
+                AtomicU32::new(PeripheralClock::Uart.default_rate()),
+                AtomicU32::new(PeripheralClock::Npu.default_rate()),
+            ],
+        }
+    }
+
+    /// 获取外设输入时钟频率 (Hz)
+    pub fn get_clock(&self, clock: PeripheralClock) -> u32 {
+        self.rates[clock.index()].load(Ordering::Relaxed)
+    }
+
+    /// 设置外设输入时钟频率 (Hz)
+    pub fn set_clock(&self, clock: PeripheralClock, hz: u32) -> Result<(), ClockError> {
+        let (min, max) = clock.supported_range();
+        if hz < min || hz > max {
+            return Err(ClockError::UnsupportedFrequency);
+        }
+
+        self.rates[clock.index()].store(hz, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+/// 全局时钟控制器实例
+pub static CLOCK_CONTROLLER: ClockController = ClockController::new();
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i2c::compute_scl_counts;
+
+    #[test]
+    fn test_i2c_source_clock_changes_scl_counts() {
+        // 修改I2C源时钟后SCL计数随之变化
+        let controller = ClockController::new();
+        let default_counts = compute_scl_counts(controller.get_clock(PeripheralClock::I2c), 100_000).unwrap();
+
+        controller.set_clock(PeripheralClock::I2c, 100_000_000).unwrap();
+        let new_counts = compute_scl_counts(controller.get_clock(PeripheralClock::I2c), 100_000).unwrap();
+
+        assert_ne!(default_counts, new_counts);
+        assert!(new_counts.0 < default_counts.0 && new_counts.1 < default_counts.1);
+    }
+
+    #[test]
+    fn test_unsupported_clock_request() {
+        // 超出CRU范围的请求返回错误且不修改当前频率
+        let controller = ClockController::new();
+        assert_eq!(
+            controller.set_clock(PeripheralClock::Npu, 1_000_000_000),
+            Err(ClockError::UnsupportedFrequency)
+        );
+        assert_eq!(controller.get_clock(PeripheralClock::Npu), 800_000_000);
+    }
+}
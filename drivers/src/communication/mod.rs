@@ -5,9 +5,14 @@
 mod wifi_esp32;
 mod bluetooth_hc05;
 mod lora_sx1276;
+mod reliable_link;
+
+pub use reliable_link::{crc16, ReliableLink, ReliableLinkConfig, MAX_PAYLOAD_LEN};
 
 use crate::{Driver, DriverError, CommunicationDriver};
+use alloc::boxed::Box;
 use alloc::string::String;
+use alloc::vec::Vec;
 
 /// 通信设备类型
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -35,8 +40,38 @@ pub enum ConnectionStatus {
     Error,
 }
 
+impl ConnectionStatus {
+    /// 是否允许从当前状态转换到`next`
+    ///
+    /// 断开→连接中→已连接/错误；已连接或错误状态可断开；错误状态可重新连接
+    pub fn can_transition_to(self, next: ConnectionStatus) -> bool {
+        use ConnectionStatus::*;
+        matches!(
+            (self, next),
+            (Disconnected, Connecting)
+                | (Connecting, Connected)
+                | (Connecting, Error)
+                | (Connected, Disconnected)
+                | (Connected, Error)
+                | (Error, Connecting)
+                | (Error, Disconnected)
+        )
+    }
+}
+
+/// 连接状态变化回调，参数为设备序号和新状态
+pub type StatusCallback = fn(usize, ConnectionStatus);
+
+/// 数据包头长度：源地址、目的地址、时间戳、负载长度
+pub const DATA_PACKET_HEADER_LEN: usize = 4 + 4 + 8 + 2;
+
+/// 数据包最大负载长度（由2字节长度前缀决定）
+pub const DATA_PACKET_MAX_PAYLOAD: usize = u16::MAX as usize;
+
 /// 数据包结构
-#[derive(Debug, Clone)]
+///
+/// 线上格式（小端）：源地址u32 | 目的地址u32 | 时间戳u64 | 负载长度u16 | 负载
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DataPacket {
     pub source: u32,
     pub destination: u32,
@@ -44,10 +79,65 @@ pub struct DataPacket {
     pub timestamp: u64,
 }
 
+impl DataPacket {
+    /// 编码后的总长度
+    pub fn encoded_len(&self) -> usize {
+        DATA_PACKET_HEADER_LEN + self.payload.len()
+    }
+
+    /// 编码包头，可与`payload`分两次发送以免复制负载
+    ///
+    /// 负载超过`DATA_PACKET_MAX_PAYLOAD`时返回`InvalidParameter`
+    pub fn encode_header(&self) -> Result<[u8; DATA_PACKET_HEADER_LEN], DriverError> {
+        let payload_len = u16::try_from(self.payload.len()).map_err(|_| DriverError::InvalidParameter)?;
+
+        let mut header = [0u8; DATA_PACKET_HEADER_LEN];
+        header[0..4].copy_from_slice(&self.source.to_le_bytes());
+        header[4..8].copy_from_slice(&self.destination.to_le_bytes());
+        header[8..16].copy_from_slice(&self.timestamp.to_le_bytes());
+        header[16..18].copy_from_slice(&payload_len.to_le_bytes());
+        Ok(header)
+    }
+
+    /// 编码为线上格式，负载过长时返回`InvalidParameter`
+    pub fn encode(&self) -> Result<Vec<u8>, DriverError> {
+        let header = self.encode_header()?;
+        let mut bytes = Vec::with_capacity(self.encoded_len());
+        bytes.extend_from_slice(&header);
+        bytes.extend_from_slice(&self.payload);
+        Ok(bytes)
+    }
+
+    /// 从线上格式解码，长度不足或与长度前缀不符时返回`DataFormatError`
+    pub fn decode(bytes: &[u8]) -> Result<Self, DriverError> {
+        if bytes.len() < DATA_PACKET_HEADER_LEN {
+            return Err(DriverError::DataFormatError);
+        }
+
+        let (header, payload) = bytes.split_at(DATA_PACKET_HEADER_LEN);
+        let payload_len = u16::from_le_bytes([header[16], header[17]]) as usize;
+        if payload.len() != payload_len {
+            return Err(DriverError::DataFormatError);
+        }
+
+        Ok(Self {
+            source: u32::from_le_bytes([header[0], header[1], header[2], header[3]]),
+            destination: u32::from_le_bytes([header[4], header[5], header[6], header[7]]),
+            payload: payload.to_vec(),
+            timestamp: u64::from_le_bytes([
+                header[8], header[9], header[10], header[11], header[12], header[13], header[14], header[15],
+            ]),
+        })
+    }
+}
+
 /// 通信管理器
 pub struct CommunicationManager {
     devices: Vec<Box<dyn CommunicationDriver>>,
+    /// 与`devices`一一对应的连接状态
+    statuses: Vec<ConnectionStatus>,
     current_device: Option<usize>,
+    status_callback: Option<StatusCallback>,
 }
 
 impl CommunicationManager {
@@ -55,13 +145,70 @@ impl CommunicationManager {
     pub fn new() -> Self {
         Self {
             devices: Vec::new(),
+            statuses: Vec::new(),
             current_device: None,
+            status_callback: None,
         }
     }
     
-    /// 注册通信设备
+    /// 注册通信设备，初始状态为断开
     pub fn register_device(&mut self, device: Box<dyn CommunicationDriver>) {
         self.devices.push(device);
+        self.statuses.push(ConnectionStatus::Disconnected);
+    }
+
+    /// 设置连接状态变化回调，每次状态转换都会调用
+    pub fn on_status_change(&mut self, callback: StatusCallback) {
+        self.status_callback = Some(callback);
+    }
+
+    /// 设备的连接状态，序号无效时视为断开
+    pub fn status(&self, index: usize) -> ConnectionStatus {
+        self.statuses.get(index).copied().unwrap_or(ConnectionStatus::Disconnected)
+    }
+
+    /// 连接设备：进入连接中状态后初始化驱动，成功为已连接，失败为错误
+    pub fn connect(&mut self, index: usize) -> Result<(), DriverError> {
+        self.transition(index, ConnectionStatus::Connecting)?;
+        match self.devices[index].init() {
+            Ok(()) => self.transition(index, ConnectionStatus::Connected),
+            Err(error) => {
+                self.transition(index, ConnectionStatus::Error)?;
+                Err(error)
+            }
+        }
+    }
+
+    /// 断开设备：卸载驱动后为断开状态，卸载失败为错误状态
+    pub fn disconnect(&mut self, index: usize) -> Result<(), DriverError> {
+        let status = self.statuses.get(index).copied().ok_or(DriverError::DeviceNotFound)?;
+        if !status.can_transition_to(ConnectionStatus::Disconnected) {
+            return Err(DriverError::NotSupported);
+        }
+
+        match self.devices[index].deinit() {
+            Ok(()) => self.transition(index, ConnectionStatus::Disconnected),
+            Err(error) => {
+                if status != ConnectionStatus::Error {
+                    self.transition(index, ConnectionStatus::Error)?;
+                }
+                Err(error)
+            }
+        }
+    }
+
+    /// 检查并执行状态转换，非法转换返回`NotSupported`
+    fn transition(&mut self, index: usize, next: ConnectionStatus) -> Result<(), DriverError> {
+        let status = self.statuses.get_mut(index).ok_or(DriverError::DeviceNotFound)?;
+        if !status.can_transition_to(next) {
+            return Err(DriverError::NotSupported);
+        }
+
+        *status = next;
+        if let Some(callback) = self.status_callback {
+            callback(index, next);
+        }
+        Ok(())
     }
     
     /// 设置当前通信设备
@@ -106,4 +253,130 @@ pub fn create_communication_driver(device: CommunicationDevice) -> Result<Box<dy
             Ok(Box::new(lora_sx1276::LoRaSX1276Driver::new()))
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use starry_kernel::sync::IrqMutex;
+
+    fn packet(payload: Vec<u8>) -> DataPacket {
+        DataPacket { source: 0x0102_0304, destination: 0xA0B0_C0D0, payload, timestamp: 1_700_000_000_123 }
+    }
+
+    /// 初始化结果可控的模拟设备
+    struct MockDevice {
+        fail_init: bool,
+    }
+
+    impl Driver for MockDevice {
+        fn name(&self) -> &'static str {
+            "mock"
+        }
+
+        fn init(&mut self) -> Result<(), DriverError> {
+            if self.fail_init { Err(DriverError::Timeout) } else { Ok(()) }
+        }
+
+        fn is_ready(&self) -> bool {
+            true
+        }
+
+        fn deinit(&mut self) -> Result<(), DriverError> {
+            Ok(())
+        }
+    }
+
+    impl CommunicationDriver for MockDevice {
+        fn send(&mut self, _data: &[u8]) -> Result<(), DriverError> {
+            Ok(())
+        }
+
+        fn receive(&mut self, _buffer: &mut [u8]) -> Result<usize, DriverError> {
+            Ok(0)
+        }
+    }
+
+    /// 回调记录：按顺序保存(设备序号, 状态)
+    static TRANSITIONS: IrqMutex<Vec<(usize, ConnectionStatus)>> = IrqMutex::new(Vec::new());
+
+    fn record(index: usize, status: ConnectionStatus) {
+        TRANSITIONS.lock().push((index, status));
+    }
+
+    #[test]
+    fn test_connect_disconnect_cycle_fires_callbacks() {
+        // 完整的连接/断开周期，以及初始化失败进入错误状态后重新连接，每次转换都回调
+        use ConnectionStatus::*;
+        let mut manager = CommunicationManager::new();
+        manager.register_device(Box::new(MockDevice { fail_init: false }));
+        manager.register_device(Box::new(MockDevice { fail_init: true }));
+        manager.on_status_change(record);
+
+        assert_eq!(manager.status(0), Disconnected);
+        assert_eq!(manager.connect(0), Ok(()));
+        assert_eq!(manager.status(0), Connected);
+        assert_eq!(manager.disconnect(0), Ok(()));
+        assert_eq!(manager.status(0), Disconnected);
+
+        assert_eq!(manager.connect(1), Err(DriverError::Timeout));
+        assert_eq!(manager.status(1), Error);
+        assert_eq!(manager.disconnect(1), Ok(()));
+
+        assert_eq!(
+            *TRANSITIONS.lock(),
+            [(0, Connecting), (0, Connected), (0, Disconnected), (1, Connecting), (1, Error), (1, Disconnected)]
+        );
+    }
+
+    #[test]
+    fn test_illegal_transitions_rejected() {
+        // 已连接时再次连接、断开状态下断开都被拒绝，状态保持不变
+        let mut manager = CommunicationManager::new();
+        manager.register_device(Box::new(MockDevice { fail_init: false }));
+        manager.connect(0).unwrap();
+
+        assert_eq!(manager.connect(0), Err(DriverError::NotSupported));
+        assert_eq!(manager.status(0), ConnectionStatus::Connected);
+        manager.disconnect(0).unwrap();
+        assert_eq!(manager.disconnect(0), Err(DriverError::NotSupported));
+        assert_eq!(manager.connect(5), Err(DriverError::DeviceNotFound));
+    }
+
+    #[test]
+    fn test_data_packet_round_trip() {
+        // 空负载和最大负载都能往返编解码，包头按小端排列
+        let empty = packet(Vec::new());
+        let bytes = empty.encode().unwrap();
+        assert_eq!(bytes.len(), DATA_PACKET_HEADER_LEN);
+        assert_eq!(&bytes[0..4], &[0x04, 0x03, 0x02, 0x01]);
+        assert_eq!(DataPacket::decode(&bytes), Ok(empty));
+
+        let full = packet((0..DATA_PACKET_MAX_PAYLOAD).map(|i| i as u8).collect());
+        let bytes = full.encode().unwrap();
+        assert_eq!(bytes.len(), full.encoded_len());
+        assert_eq!(&bytes[16..18], &[0xFF, 0xFF]);
+        assert_eq!(DataPacket::decode(&bytes), Ok(full));
+    }
+
+    #[test]
+    fn test_data_packet_rejects_oversized_payload() {
+        // 负载超过长度前缀上限时编码返回错误而不是panic
+        let oversized = packet(vec![0; DATA_PACKET_MAX_PAYLOAD + 1]);
+        assert_eq!(oversized.encode_header(), Err(DriverError::InvalidParameter));
+        assert_eq!(oversized.encode(), Err(DriverError::InvalidParameter));
+    }
+
+    #[test]
+    fn test_data_packet_rejects_bad_length() {
+        // 截断的包头、截断的负载和多余的尾部字节都被拒绝
+        let bytes = packet(vec![1, 2, 3]).encode().unwrap();
+        assert_eq!(DataPacket::decode(&bytes[..10]), Err(DriverError::DataFormatError));
+        assert_eq!(DataPacket::decode(&bytes[..bytes.len() - 1]), Err(DriverError::DataFormatError));
+
+        let mut extended = bytes.clone();
+        extended.push(0);
+        assert_eq!(DataPacket::decode(&extended), Err(DriverError::DataFormatError));
+    }
+}
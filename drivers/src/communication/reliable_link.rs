@@ -0,0 +1,352 @@
+//! 可靠传输层
+//!
+//! 在任意通信驱动之上提供带序号和CRC16校验的分帧、ACK确认与超时重传，
+//! 用于LoRa等易丢包的链路
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::time::Duration;
+
+use crate::async_runtime::{MonotonicClock, SystemTimer};
+use crate::{CommunicationDriver, DriverError};
+
+/// 数据帧类型
+const KIND_DATA: u8 = 0x01;
+/// 确认帧类型
+const KIND_ACK: u8 = 0x02;
+
+/// 帧头长度：类型、序号、负载长度（小端u16）
+const HEADER_LEN: usize = 4;
+/// 帧尾CRC长度
+const CRC_LEN: usize = 2;
+
+/// 单帧最大负载长度（LoRa单包上限255字节减去帧头和CRC）
+pub const MAX_PAYLOAD_LEN: usize = 255 - HEADER_LEN - CRC_LEN;
+
+/// 可靠传输配置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReliableLinkConfig {
+    /// 首次发送后的最大重传次数
+    pub max_retries: u32,
+    /// 每次发送后等待ACK的时间 (ms)
+    pub ack_timeout_ms: u32,
+}
+
+impl Default for ReliableLinkConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            ack_timeout_ms: 500,
+        }
+    }
+}
+
+/// CRC-16/CCITT-FALSE（多项式0x1021，初值0xFFFF）
+pub fn crc16(data: &[u8]) -> u16 {
+    let mut crc = 0xFFFFu16;
+    for &byte in data {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 { (crc << 1) ^ 0x1021 } else { crc << 1 };
+        }
+    }
+    crc
+}
+
+/// 组帧：类型 | 序号 | 负载长度 | 负载 | CRC16
+fn encode_frame(kind: u8, seq: u8, payload: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(HEADER_LEN + payload.len() + CRC_LEN);
+    frame.push(kind);
+    frame.push(seq);
+    frame.extend_from_slice(&(payload.len() as u16).to_le_bytes());
+    frame.extend_from_slice(payload);
+    let crc = crc16(&frame);
+    frame.extend_from_slice(&crc.to_le_bytes());
+    frame
+}
+
+/// 解析帧，返回类型、序号和负载；长度或CRC不符时返回`DataFormatError`
+fn decode_frame(frame: &[u8]) -> Result<(u8, u8, &[u8]), DriverError> {
+    if frame.len() < HEADER_LEN + CRC_LEN {
+        return Err(DriverError::DataFormatError);
+    }
+
+    let payload_len = u16::from_le_bytes([frame[2], frame[3]]) as usize;
+    if frame.len() != HEADER_LEN + payload_len + CRC_LEN {
+        return Err(DriverError::DataFormatError);
+    }
+
+    let (body, crc) = frame.split_at(HEADER_LEN + payload_len);
+    if crc16(body) != u16::from_le_bytes([crc[0], crc[1]]) {
+        return Err(DriverError::DataFormatError);
+    }
+    Ok((body[0], body[1], &body[HEADER_LEN..]))
+}
+
+/// 可靠传输链路
+pub struct ReliableLink<D: CommunicationDriver, C: MonotonicClock = SystemTimer> {
+    driver: D,
+    clock: C,
+    config: ReliableLinkConfig,
+    /// 下一个发送帧的序号
+    tx_seq: u8,
+    /// 最近收到的数据帧序号，用于丢弃重传造成的重复帧
+    last_rx_seq: Option<u8>,
+}
+
+impl<D: CommunicationDriver> ReliableLink<D> {
+    /// 使用系统定时器计时
+    pub fn new(driver: D, config: ReliableLinkConfig) -> Self {
+        Self::with_clock(driver, SystemTimer, config)
+    }
+}
+
+impl<D: CommunicationDriver, C: MonotonicClock> ReliableLink<D, C> {
+    /// 使用指定时钟计时
+    pub fn with_clock(driver: D, clock: C, config: ReliableLinkConfig) -> Self {
+        Self {
+            driver,
+            clock,
+            config,
+            tx_seq: 0,
+            last_rx_seq: None,
+        }
+    }
+
+    /// 当前配置
+    pub fn config(&self) -> &ReliableLinkConfig {
+        &self.config
+    }
+
+    /// 设置最大重传次数
+    pub fn set_max_retries(&mut self, max_retries: u32) {
+        self.config.max_retries = max_retries;
+    }
+
+    /// 设置ACK等待时间 (ms)
+    pub fn set_ack_timeout_ms(&mut self, ack_timeout_ms: u32) {
+        self.config.ack_timeout_ms = ack_timeout_ms;
+    }
+
+    /// 底层驱动
+    pub fn driver(&self) -> &D {
+        &self.driver
+    }
+
+    /// 可靠发送：等待对应序号的ACK，超时后重传，重传次数用尽返回`Timeout`
+    ///
+    /// 等待期间收到的其他帧被丢弃，对端会因未收到ACK而重传。接收出错计为一次
+    /// 失败的尝试，所有尝试都因接收出错而失败时返回最后的接收错误。
+    /// 无论成败每个负载只占用一个序号，避免失败后的新负载被对端当作重复帧丢弃
+    pub fn send(&mut self, payload: &[u8]) -> Result<(), DriverError> {
+        if payload.len() > MAX_PAYLOAD_LEN {
+            return Err(DriverError::InvalidParameter);
+        }
+
+        let seq = self.tx_seq;
+        self.tx_seq = seq.wrapping_add(1);
+        let frame = encode_frame(KIND_DATA, seq, payload);
+        let timeout = Duration::from_millis(self.config.ack_timeout_ms as u64);
+        let mut buffer = vec![0u8; HEADER_LEN + MAX_PAYLOAD_LEN + CRC_LEN];
+
+        let mut result = Err(DriverError::Timeout);
+        for _ in 0..=self.config.max_retries {
+            self.driver.send(&frame)?;
+            result = Err(DriverError::Timeout);
+
+            let deadline = self.clock.now() + timeout;
+            while self.clock.now() < deadline {
+                let len = match self.driver.receive(&mut buffer) {
+                    Ok(len) => len,
+                    Err(error) => {
+                        result = Err(error);
+                        break;
+                    }
+                };
+                if len == 0 {
+                    continue;
+                }
+                if let Ok((KIND_ACK, ack_seq, _)) = decode_frame(&buffer[..len]) {
+                    if ack_seq == seq {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    /// 接收一帧，校验通过的数据帧回复ACK
+    ///
+    /// 返回新数据的长度；没有数据、收到ACK帧或重复帧时返回None。
+    /// CRC或长度错误时返回`DataFormatError`且不回复ACK，由对端重传
+    pub fn receive(&mut self, buffer: &mut [u8]) -> Result<Option<usize>, DriverError> {
+        let mut raw = vec![0u8; HEADER_LEN + MAX_PAYLOAD_LEN + CRC_LEN];
+        let len = self.driver.receive(&mut raw)?;
+        if len == 0 {
+            return Ok(None);
+        }
+
+        let (kind, seq, payload) = decode_frame(&raw[..len])?;
+        if kind != KIND_DATA {
+            return Ok(None);
+        }
+        if payload.len() > buffer.len() {
+            return Err(DriverError::InvalidParameter);
+        }
+
+        // 重复帧说明之前的ACK丢失，仍需再次确认
+        self.driver.send(&encode_frame(KIND_ACK, seq, &[]))?;
+        if self.last_rx_seq == Some(seq) {
+            return Ok(None);
+        }
+
+        self.last_rx_seq = Some(seq);
+        buffer[..payload.len()].copy_from_slice(payload);
+        Ok(Some(payload.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Driver;
+    use alloc::collections::VecDeque;
+    use core::cell::Cell;
+
+    /// 记录发出的帧，按队列返回收到的帧
+    struct MockLink {
+        sent: Vec<Vec<u8>>,
+        incoming: VecDeque<Vec<u8>>,
+        /// 返回队列中的帧之前先返回的接收错误次数
+        receive_errors: u32,
+    }
+
+    impl MockLink {
+        fn new(incoming: &[Vec<u8>]) -> Self {
+            Self { sent: Vec::new(), incoming: incoming.iter().cloned().collect(), receive_errors: 0 }
+        }
+    }
+
+    impl Driver for MockLink {
+        fn name(&self) -> &'static str {
+            "mock"
+        }
+
+        fn init(&mut self) -> Result<(), DriverError> {
+            Ok(())
+        }
+
+        fn is_ready(&self) -> bool {
+            true
+        }
+
+        fn deinit(&mut self) -> Result<(), DriverError> {
+            Ok(())
+        }
+    }
+
+    impl CommunicationDriver for MockLink {
+        fn send(&mut self, data: &[u8]) -> Result<(), DriverError> {
+            self.sent.push(data.to_vec());
+            Ok(())
+        }
+
+        fn receive(&mut self, buffer: &mut [u8]) -> Result<usize, DriverError> {
+            if self.receive_errors > 0 {
+                self.receive_errors -= 1;
+                return Err(DriverError::CommunicationError);
+            }
+            let Some(frame) = self.incoming.pop_front() else {
+                return Ok(0);
+            };
+            buffer[..frame.len()].copy_from_slice(&frame);
+            Ok(frame.len())
+        }
+    }
+
+    /// 每次读取前进100ms的模拟时钟
+    struct StepClock(Cell<u64>);
+
+    impl MonotonicClock for StepClock {
+        fn now(&self) -> Duration {
+            self.0.set(self.0.get() + 100);
+            Duration::from_millis(self.0.get())
+        }
+    }
+
+    fn mock_link(incoming: &[Vec<u8>], config: ReliableLinkConfig) -> ReliableLink<MockLink, StepClock> {
+        ReliableLink::with_clock(MockLink::new(incoming), StepClock(Cell::new(0)), config)
+    }
+
+    #[test]
+    fn test_crc_mismatch_rejected_without_ack() {
+        // 负载被篡改的帧校验失败，不回复ACK
+        let mut corrupted = encode_frame(KIND_DATA, 0, b"temp=25");
+        corrupted[5] ^= 0x01;
+        let mut link = mock_link(&[corrupted], ReliableLinkConfig::default());
+
+        let mut buffer = [0u8; 32];
+        assert_eq!(link.receive(&mut buffer), Err(DriverError::DataFormatError));
+        assert!(link.driver().sent.is_empty());
+        assert_eq!(crc16(b"123456789"), 0x29B1);
+    }
+
+    #[test]
+    fn test_duplicate_frame_acked_but_suppressed() {
+        // 同一序号的帧收到两次：两次都回复ACK，第二次不再交付
+        let frame = encode_frame(KIND_DATA, 7, b"hello");
+        let mut link = mock_link(&[frame.clone(), frame], ReliableLinkConfig::default());
+
+        let mut buffer = [0u8; 32];
+        assert_eq!(link.receive(&mut buffer), Ok(Some(5)));
+        assert_eq!(&buffer[..5], b"hello");
+        assert_eq!(link.receive(&mut buffer), Ok(None));
+
+        let ack = encode_frame(KIND_ACK, 7, &[]);
+        assert_eq!(link.driver().sent, vec![ack.clone(), ack]);
+    }
+
+    #[test]
+    fn test_retransmit_then_give_up() {
+        // 对端一直不确认：首次发送加2次重传后返回超时
+        let config = ReliableLinkConfig { max_retries: 2, ack_timeout_ms: 300 };
+        let mut link = mock_link(&[], config);
+        assert_eq!(link.send(b"alarm"), Err(DriverError::Timeout));
+        assert_eq!(link.driver().sent.len(), 3);
+        assert!(link.driver().sent.iter().all(|frame| decode_frame(frame) == Ok((KIND_DATA, 0, &b"alarm"[..]))));
+
+        // 收到错误序号的ACK不算确认，正确序号的ACK结束发送
+        let acks = [encode_frame(KIND_ACK, 9, &[]), encode_frame(KIND_ACK, 0, &[])];
+        let mut link = mock_link(&acks, config);
+        assert_eq!(link.send(b"alarm"), Ok(()));
+        assert_eq!(link.driver().sent.len(), 1);
+    }
+
+    #[test]
+    fn test_failed_send_still_consumes_sequence() {
+        // 发送超时后下一个负载使用新序号，对端不会把它当作重复帧丢弃
+        let config = ReliableLinkConfig { max_retries: 0, ack_timeout_ms: 300 };
+        let mut link = mock_link(&[], config);
+        assert_eq!(link.send(b"first"), Err(DriverError::Timeout));
+
+        link.driver.incoming.push_back(encode_frame(KIND_ACK, 1, &[]));
+        assert_eq!(link.send(b"second"), Ok(()));
+        assert_eq!(decode_frame(&link.driver().sent[1]), Ok((KIND_DATA, 1, &b"second"[..])));
+    }
+
+    #[test]
+    fn test_receive_error_counts_as_failed_attempt() {
+        // 接收出错不终止发送，重传后收到ACK即成功；每次尝试都出错时返回该错误
+        let config = ReliableLinkConfig { max_retries: 1, ack_timeout_ms: 300 };
+        let mut link = mock_link(&[encode_frame(KIND_ACK, 0, &[])], config);
+        link.driver.receive_errors = 1;
+        assert_eq!(link.send(b"alarm"), Ok(()));
+        assert_eq!(link.driver().sent.len(), 2);
+
+        let mut link = mock_link(&[], config);
+        link.driver.receive_errors = 2;
+        assert_eq!(link.send(b"alarm"), Err(DriverError::CommunicationError));
+        assert_eq!(link.driver().sent.len(), 2);
+    }
+}
@@ -25,6 +25,47 @@ pub enum DmaMode {
     ScatterGather,      // 分散聚集传输
 }
 
+/// DMA传输结束状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DmaStatus {
+    Complete,           // 全部传输完成
+    PartialError,       // 传输中途出错，仅部分数据有效
+    Aborted,            // 传输被中止
+}
+
+/// DMA传输结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DmaResult {
+    pub bytes_transferred: usize,   // 已传输（有效）的字节数
+    pub status: DmaStatus,          // 结束状态
+}
+
+impl DmaResult {
+    /// 全部完成的传输结果
+    pub const fn complete(bytes_transferred: usize) -> Self {
+        Self { bytes_transferred, status: DmaStatus::Complete }
+    }
+    
+    /// 是否全部完成
+    pub fn is_complete(&self) -> bool {
+        self.status == DmaStatus::Complete
+    }
+}
+
+/// 控制字：源地址固定不递增
+pub const DMA_CTRL_SRC_FIXED: u32 = 1 << 4;
+/// 控制字：目标地址固定不递增
+pub const DMA_CTRL_DST_FIXED: u32 = 1 << 5;
+/// 控制字：传输完成后产生中断
+pub const DMA_CTRL_IRQ_EN: u32 = 1 << 6;
+/// 控制字：由外设DMA请求控制传输节拍
+pub const DMA_CTRL_PERIPH_FLOW: u32 = 1 << 7;
+/// 控制字：外设请求号（位8-13）
+const DMA_CTRL_REQUEST_SHIFT: u32 = 8;
+const DMA_CTRL_REQUEST_MASK: u32 = 0x3F << DMA_CTRL_REQUEST_SHIFT;
+/// 控制字：描述符有效/通道启动
+pub const DMA_CTRL_VALID: u32 = 1 << 31;
+
 /// DMA缓冲区描述符
 #[repr(C, align(64))]
 pub struct DmaDescriptor {
@@ -61,7 +102,22 @@ impl DmaDescriptor {
         self.control = 0;
         self.control |= (direction as u32) << 0;   // 传输方向
         self.control |= (mode as u32) << 2;       // 传输模式
-        self.control |= 1 << 31;                 // 有效位
+        self.control |= DMA_CTRL_VALID;          // 有效位
+        
+        // 外设端为FIFO数据寄存器，地址固定不递增
+        match direction {
+            DmaDirection::MemoryToDevice => self.control |= DMA_CTRL_DST_FIXED,
+            DmaDirection::DeviceToMemory => self.control |= DMA_CTRL_SRC_FIXED,
+            DmaDirection::DeviceToDevice => self.control |= DMA_CTRL_SRC_FIXED | DMA_CTRL_DST_FIXED,
+            DmaDirection::MemoryToMemory => {}
+        }
+    }
+    
+    /// 设置外设DMA请求号，传输按外设FIFO的请求节拍进行
+    pub fn set_peripheral_request(&mut self, request: u8) {
+        self.control &= !DMA_CTRL_REQUEST_MASK;
+        self.control |= ((request as u32) << DMA_CTRL_REQUEST_SHIFT) & DMA_CTRL_REQUEST_MASK;
+        self.control |= DMA_CTRL_PERIPH_FLOW;
     }
 }
 
@@ -148,11 +204,207 @@ impl Drop for DmaBuffer {
     }
 }
 
+/// DMA传输引擎，按描述符执行传输并报告实际传输的字节数
+pub trait DmaEngine {
+    fn execute(&mut self, descriptor: &DmaDescriptor) -> DmaResult;
+}
+
+/// 启动后立即返回、由调用者轮询完成状态的DMA通道
+/// 
+/// 外设传输需要在外设开始产生DMA请求之前启动通道，并与外设操作同时运行
+pub trait DmaChannel {
+    /// 启动传输，不等待完成
+    fn start(&self, descriptor: &DmaDescriptor) -> Result<(), &'static str>;
+    
+    /// 通道是否正在传输
+    fn is_busy(&self) -> bool;
+    
+    /// 读取并清除本通道的完成中断，返回本通道是否有待处理的完成事件
+    fn take_completion(&self) -> bool;
+    
+    /// 最近一次传输的结果
+    fn result(&self) -> DmaResult;
+    
+    /// 停止通道
+    fn stop(&self);
+}
+
+/// 以CPU内存拷贝模拟的传输引擎
+/// 
+/// 外设端（源或目标）为FIFO寄存器时地址固定不递增，逐字节读写该寄存器
+pub struct MemcpyEngine;
+
+impl DmaEngine for MemcpyEngine {
+    fn execute(&mut self, descriptor: &DmaDescriptor) -> DmaResult {
+        let size = descriptor.transfer_size as usize;
+        let src_fixed = descriptor.control & DMA_CTRL_SRC_FIXED != 0;
+        let dst_fixed = descriptor.control & DMA_CTRL_DST_FIXED != 0;
+        unsafe {
+            if !src_fixed && !dst_fixed {
+                ptr::copy_nonoverlapping(
+                    descriptor.source_addr as *const u8,
+                    descriptor.destination_addr as *mut u8,
+                    size
+                );
+            } else {
+                for i in 0..size {
+                    let byte = if src_fixed {
+                        (descriptor.source_addr as *const u32).read_volatile() as u8
+                    } else {
+                        (descriptor.source_addr as *const u8).add(i).read()
+                    };
+                    if dst_fixed {
+                        (descriptor.destination_addr as *mut u32).write_volatile(byte as u32);
+                    } else {
+                        (descriptor.destination_addr as *mut u8).add(i).write(byte);
+                    }
+                }
+            }
+        }
+        DmaResult::complete(size)
+    }
+}
+
+/// RK3588 DMAC0寄存器基地址
+pub const DMAC0_BASE: usize = 0xFEA1_0000;
+/// DMAC0传输完成中断号（SPI 86）
+pub const DMAC0_IRQ: u32 = 118;
+
+/// DMAC寄存器偏移
+const DMAC_INT_STATUS: usize = 0x00;  // 各通道完成/出错中断状态
+const DMAC_INT_CLEAR: usize = 0x04;   // 写1清除中断状态
+const DMAC_CHANNEL_BASE: usize = 0x100;
+const DMAC_CHANNEL_STRIDE: usize = 0x20;
+
+/// 通道寄存器偏移
+const CH_SAR_LO: usize = 0x00;       // 源地址低32位
+const CH_SAR_HI: usize = 0x04;       // 源地址高32位
+const CH_DAR_LO: usize = 0x08;       // 目标地址低32位
+const CH_DAR_HI: usize = 0x0C;       // 目标地址高32位
+const CH_LEN: usize = 0x10;          // 传输字节数
+const CH_CTRL: usize = 0x14;         // 控制字，写入有效位启动通道
+const CH_STATUS: usize = 0x18;       // 通道状态
+const CH_DONE_BYTES: usize = 0x1C;   // 已传输字节数
+
+/// 通道状态：传输进行中
+const CH_STATUS_BUSY: u32 = 1 << 0;
+/// 通道状态：总线错误
+const CH_STATUS_ERROR: u32 = 1 << 1;
+
+/// DMAC硬件通道
+///
+/// 按描述符编程通道寄存器后立即返回，外设端地址固定、由外设DMA请求驱动节拍，
+/// 传输结束后置位DMAC中断状态并产生`DMAC0_IRQ`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DmacChannel {
+    base: usize,
+    channel: u8,
+}
+
+impl DmacChannel {
+    /// 创建DMAC通道句柄
+    pub const fn new(base: usize, channel: u8) -> Self {
+        Self { base, channel }
+    }
+    
+    /// 通道号
+    pub fn channel(&self) -> u8 {
+        self.channel
+    }
+    
+    fn channel_reg(&self, offset: usize) -> *mut u32 {
+        (self.base + DMAC_CHANNEL_BASE + self.channel as usize * DMAC_CHANNEL_STRIDE + offset) as *mut u32
+    }
+    
+    fn global_reg(&self, offset: usize) -> *mut u32 {
+        (self.base + offset) as *mut u32
+    }
+}
+
+impl DmaChannel for DmacChannel {
+    /// 启动传输，不等待完成
+    fn start(&self, descriptor: &DmaDescriptor) -> Result<(), &'static str> {
+        if descriptor.control & DMA_CTRL_VALID == 0 {
+            return Err("传输未配置");
+        }
+        if self.is_busy() {
+            return Err("DMA通道忙");
+        }
+        
+        unsafe {
+            self.global_reg(DMAC_INT_CLEAR).write_volatile(1 << self.channel);
+            self.channel_reg(CH_SAR_LO).write_volatile(descriptor.source_addr as u32);
+            self.channel_reg(CH_SAR_HI).write_volatile((descriptor.source_addr >> 32) as u32);
+            self.channel_reg(CH_DAR_LO).write_volatile(descriptor.destination_addr as u32);
+            self.channel_reg(CH_DAR_HI).write_volatile((descriptor.destination_addr >> 32) as u32);
+            self.channel_reg(CH_LEN).write_volatile(descriptor.transfer_size);
+            // 控制字最后写入，有效位启动通道
+            self.channel_reg(CH_CTRL).write_volatile(descriptor.control | DMA_CTRL_IRQ_EN);
+        }
+        Ok(())
+    }
+    
+    /// 通道是否正在传输
+    fn is_busy(&self) -> bool {
+        unsafe { self.channel_reg(CH_STATUS).read_volatile() & CH_STATUS_BUSY != 0 }
+    }
+    
+    /// 读取并清除本通道的完成中断，返回本通道是否有待处理的完成事件
+    fn take_completion(&self) -> bool {
+        let bit = 1 << self.channel;
+        unsafe {
+            if self.global_reg(DMAC_INT_STATUS).read_volatile() & bit == 0 {
+                return false;
+            }
+            self.global_reg(DMAC_INT_CLEAR).write_volatile(bit);
+        }
+        true
+    }
+    
+    /// 最近一次传输的结果
+    fn result(&self) -> DmaResult {
+        let (status, bytes_transferred) = unsafe {
+            (
+                self.channel_reg(CH_STATUS).read_volatile(),
+                self.channel_reg(CH_DONE_BYTES).read_volatile() as usize,
+            )
+        };
+        let status = if status & CH_STATUS_ERROR != 0 {
+            DmaStatus::PartialError
+        } else {
+            DmaStatus::Complete
+        };
+        DmaResult { bytes_transferred, status }
+    }
+    
+    /// 停止通道
+    fn stop(&self) {
+        unsafe {
+            self.channel_reg(CH_CTRL).write_volatile(0);
+        }
+    }
+}
+
+impl DmaEngine for DmacChannel {
+    /// 启动传输并轮询等待通道空闲
+    fn execute(&mut self, descriptor: &DmaDescriptor) -> DmaResult {
+        if self.start(descriptor).is_err() {
+            return DmaResult { bytes_transferred: 0, status: DmaStatus::Aborted };
+        }
+        while self.is_busy() {
+            core::hint::spin_loop();
+        }
+        self.take_completion();
+        self.result()
+    }
+}
+
 /// 零拷贝传输管理器
 pub struct ZeroCopyTransfer {
     descriptor: DmaDescriptor,      // DMA描述符
     buffer: DmaBuffer,              // DMA缓冲区
     direction: DmaDirection,        // 传输方向
+    result: Option<DmaResult>,      // 最近一次传输结果
 }
 
 impl ZeroCopyTransfer {
@@ -166,6 +418,7 @@ impl ZeroCopyTransfer {
                 descriptor: DmaDescriptor::new(),
                 buffer,
                 direction,
+                result: None,
             })
         }
     }
@@ -173,27 +426,43 @@ impl ZeroCopyTransfer {
     /// 配置传输参数
     pub fn configure(&mut self, source: u64, dest: u64, size: u32, mode: DmaMode) {
         self.descriptor.configure(source, dest, size, self.direction, mode);
+        self.result = None;
     }
     
     /// 开始传输
-    pub fn start(&self) -> Result<(), &'static str> {
+    pub fn start(&mut self) -> Result<DmaResult, &'static str> {
         // 在实际系统中需要配置DMA控制器
         // 简化实现：直接内存拷贝
-        unsafe {
-            ptr::copy_nonoverlapping(
-                self.descriptor.source_addr as *const u8,
-                self.descriptor.destination_addr as *mut u8,
-                self.descriptor.transfer_size as usize
-            );
+        self.start_with(&mut MemcpyEngine)
+    }
+    
+    /// 使用指定引擎开始传输
+    pub fn start_with<E: DmaEngine>(&mut self, engine: &mut E) -> Result<DmaResult, &'static str> {
+        if self.descriptor.control & DMA_CTRL_VALID == 0 {
+            return Err("传输未配置");
         }
-        Ok(())
+        
+        let mut result = engine.execute(&self.descriptor);
+        result.bytes_transferred = result.bytes_transferred.min(self.descriptor.transfer_size as usize);
+        self.descriptor.status = result.status as u32;
+        self.result = Some(result);
+        Ok(result)
     }
     
-    /// 等待传输完成
-    pub fn wait_completion(&self) -> Result<(), &'static str> {
+    /// 中止传输，已传输的字节仍视为有效
+    pub fn abort(&mut self) -> DmaResult {
+        let bytes_transferred = self.result.map_or(0, |result| result.bytes_transferred);
+        let result = DmaResult { bytes_transferred, status: DmaStatus::Aborted };
+        self.descriptor.status = result.status as u32;
+        self.result = Some(result);
+        result
+    }
+    
+    /// 等待传输完成，返回传输结果（调用者据此判断有效字节数）
+    pub fn wait_completion(&self) -> Result<DmaResult, &'static str> {
         // 在实际系统中需要检查DMA状态寄存器
-        // 简化实现：立即返回成功
-        Ok(())
+        // 简化实现：返回启动时记录的结果
+        self.result.ok_or("传输未启动")
     }
     
     /// 获取缓冲区引用
@@ -233,23 +502,22 @@ impl DmaChannel {
         self.current_transfer = Some(transfer);
         
         // 开始传输
-        if let Some(ref transfer) = self.current_transfer {
+        if let Some(ref mut transfer) = self.current_transfer {
             transfer.start()?;
         }
         
         Ok(())
     }
     
-    /// 等待传输完成
-    pub fn wait_completion(&mut self) -> Result<(), &'static str> {
-        if let Some(ref transfer) = self.current_transfer {
-            transfer.wait_completion()?;
-        }
+    /// 等待传输完成，返回当前传输的结果
+    pub fn wait_completion(&mut self) -> Result<DmaResult, &'static str> {
+        let result = match self.current_transfer.take() {
+            Some(transfer) => transfer.wait_completion(),
+            None => Ok(DmaResult::complete(0)),
+        };
         
         self.is_busy.store(false, Ordering::Release);
-        self.current_transfer = None;
-        
-        Ok(())
+        result
     }
     
     /// 检查通道是否忙
@@ -371,4 +639,71 @@ pub fn init_dma_controller() -> Result<(), &'static str> {
 /// 获取全局DMA控制器
 pub fn get_dma_controller() -> &'static DmaController {
     &DMA_CONTROLLER
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    /// 在传输到指定字节数后出错的引擎
+    struct FaultyEngine {
+        fail_after: usize,
+    }
+
+    impl DmaEngine for FaultyEngine {
+        fn execute(&mut self, descriptor: &DmaDescriptor) -> DmaResult {
+            let bytes = self.fail_after.min(descriptor.transfer_size as usize);
+            unsafe {
+                ptr::copy_nonoverlapping(descriptor.source_addr as *const u8, descriptor.destination_addr as *mut u8, bytes);
+            }
+            DmaResult { bytes_transferred: bytes, status: DmaStatus::PartialError }
+        }
+    }
+
+    fn transfer(source: &[u8], dest: &mut [u8]) -> ZeroCopyTransfer {
+        let mut transfer = ZeroCopyTransfer::new(dest.len(), DmaDirection::MemoryToMemory).unwrap();
+        transfer.configure(source.as_ptr() as u64, dest.as_mut_ptr() as u64, source.len() as u32, DmaMode::Single);
+        transfer
+    }
+
+    #[test]
+    fn test_full_transfer_reports_complete() {
+        // 完整传输报告Complete及全部字节数
+        let source: Vec<u8> = (0..64).collect();
+        let mut dest = vec![0u8; 64];
+        let mut transfer = transfer(&source, &mut dest);
+
+        assert_eq!(transfer.start(), Ok(DmaResult::complete(64)));
+        assert_eq!(transfer.wait_completion(), Ok(DmaResult::complete(64)));
+        assert_eq!(dest, source);
+    }
+
+    #[test]
+    fn test_mid_transfer_error_reports_partial_count() {
+        // 中途出错时报告PartialError及已传输字节数，中止后保留该计数
+        let source = vec![0xAAu8; 64];
+        let mut dest = vec![0u8; 64];
+        let mut transfer = transfer(&source, &mut dest);
+
+        let result = transfer.start_with(&mut FaultyEngine { fail_after: 24 }).unwrap();
+        assert_eq!(result, DmaResult { bytes_transferred: 24, status: DmaStatus::PartialError });
+        assert!(!transfer.wait_completion().unwrap().is_complete());
+        assert_eq!(transfer.abort(), DmaResult { bytes_transferred: 24, status: DmaStatus::Aborted });
+
+        assert!(dest[..24].iter().all(|&b| b == 0xAA));
+        assert!(dest[24..].iter().all(|&b| b == 0));
+    }
+    
+    #[test]
+    fn test_device_side_address_stays_fixed() {
+        // 内存到设备传输逐字节写入同一FIFO寄存器，不会越过它写入相邻寄存器
+        let source = [1u8, 2, 3, 4, 5, 6, 7, 8];
+        let mut registers = [0u32; 4];
+        let mut descriptor = DmaDescriptor::new();
+        descriptor.configure(source.as_ptr() as u64, registers.as_mut_ptr() as u64, 8, DmaDirection::MemoryToDevice, DmaMode::Single);
+
+        assert_eq!(MemcpyEngine.execute(&descriptor), DmaResult::complete(8));
+        assert_eq!(registers, [8, 0, 0, 0]);
+    }
+}
@@ -1,6 +1,6 @@
 //! DHT22温湿度传感器驱动
 
-use crate::{Driver, SensorDriver, SensorData, DriverError};
+use crate::{Driver, SensorDriver, SensorReading, TemperatureUnit, DriverError};
 use embedded_hal::delay::DelayNs;
 use embedded_hal::digital::{InputPin, OutputPin};
 
@@ -123,7 +123,7 @@ where
     PIN: InputPin + OutputPin,
     DELAY: DelayNs,
 {
-    fn read(&mut self) -> Result<SensorData, DriverError> {
+    fn read(&mut self) -> Result<SensorReading, DriverError> {
         if !self.is_initialized {
             return Err(DriverError::DeviceNotFound);
         }
@@ -157,6 +157,10 @@ where
         };
         
         // 返回传感器数据
-        Ok(SensorData::Temperature(temperature))
+        Ok(SensorReading::Temperature(temperature))
+    }
+    
+    fn temperature_unit(&self) -> TemperatureUnit {
+        TemperatureUnit::Celsius
     }
 }
\ No newline at end of file
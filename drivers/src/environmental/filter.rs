@@ -0,0 +1,241 @@
+//! 传感器读数滤波
+//!
+//! 包装任意传感器驱动，对同类读数做滑动平均或中值滤波，抑制DHT22、BH1750等传感器的随机噪声和偶发尖峰
+
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+
+use crate::{Driver, DriverError, SensorDriver, SensorReading, TemperatureUnit};
+
+/// 读数类别数（温度、湿度、光照、加速度、陀螺仪）
+const READING_KINDS: usize = 5;
+
+/// 滤波方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterMode {
+    /// 窗口内取平均，平滑随机噪声
+    MovingAverage,
+    /// 窗口内取中值，剔除偶发尖峰
+    Median,
+}
+
+/// 读数类别序号及各分量（单值读数只用第一个分量）
+fn split(reading: SensorReading) -> (usize, [f32; 3]) {
+    match reading {
+        SensorReading::Temperature(value) => (0, [value, 0.0, 0.0]),
+        SensorReading::Humidity(value) => (1, [value, 0.0, 0.0]),
+        SensorReading::Light(value) => (2, [value, 0.0, 0.0]),
+        SensorReading::Acceleration(x, y, z) => (3, [x, y, z]),
+        SensorReading::Gyroscope(x, y, z) => (4, [x, y, z]),
+    }
+}
+
+/// 由类别序号和分量还原读数
+fn join(kind: usize, [a, b, c]: [f32; 3]) -> SensorReading {
+    match kind {
+        0 => SensorReading::Temperature(a),
+        1 => SensorReading::Humidity(a),
+        2 => SensorReading::Light(a),
+        3 => SensorReading::Acceleration(a, b, c),
+        _ => SensorReading::Gyroscope(a, b, c),
+    }
+}
+
+/// 中值，偶数个时取中间两个的平均
+fn median(values: &mut [f32]) -> f32 {
+    values.sort_by(f32::total_cmp);
+    let mid = values.len() / 2;
+    if values.len() % 2 == 0 {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    }
+}
+
+/// 传感器滤波器
+///
+/// 每类读数各自保留最近`window`个样本，滤波结果与原始读数类别相同；
+/// 样本不足`window`个的预热阶段直接返回原始读数
+pub struct SensorFilter<S: SensorDriver> {
+    sensor: S,
+    mode: FilterMode,
+    window: usize,
+    histories: [VecDeque<[f32; 3]>; READING_KINDS],
+}
+
+impl<S: SensorDriver> SensorFilter<S> {
+    /// 创建滤波器，窗口至少为1
+    pub fn new(sensor: S, mode: FilterMode, window: usize) -> Self {
+        let window = window.max(1);
+        Self {
+            sensor,
+            mode,
+            window,
+            histories: core::array::from_fn(|_| VecDeque::with_capacity(window)),
+        }
+    }
+
+    /// 滤波方式
+    pub fn mode(&self) -> FilterMode {
+        self.mode
+    }
+
+    /// 窗口样本数
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// 被包装的传感器
+    pub fn sensor(&self) -> &S {
+        &self.sensor
+    }
+
+    /// 清空全部历史样本，重新进入预热阶段
+    pub fn reset(&mut self) {
+        self.histories.iter_mut().for_each(VecDeque::clear);
+    }
+
+    /// 读取传感器并返回滤波后的读数
+    pub fn filtered_read(&mut self) -> Result<SensorReading, DriverError> {
+        let raw = self.sensor.read()?;
+        let (kind, sample) = split(raw);
+
+        let history = &mut self.histories[kind];
+        if history.len() == self.window {
+            history.pop_front();
+        }
+        history.push_back(sample);
+        if history.len() < self.window {
+            return Ok(raw);
+        }
+
+        let mut filtered = [0.0f32; 3];
+        for (axis, value) in filtered.iter_mut().enumerate() {
+            let mut values: Vec<f32> = history.iter().map(|sample| sample[axis]).collect();
+            *value = match self.mode {
+                FilterMode::MovingAverage => values.iter().sum::<f32>() / values.len() as f32,
+                FilterMode::Median => median(&mut values),
+            };
+        }
+        Ok(join(kind, filtered))
+    }
+}
+
+impl<S: SensorDriver> Driver for SensorFilter<S> {
+    fn name(&self) -> &'static str {
+        self.sensor.name()
+    }
+
+    fn init(&mut self) -> Result<(), DriverError> {
+        self.reset();
+        self.sensor.init()
+    }
+
+    fn is_ready(&self) -> bool {
+        self.sensor.is_ready()
+    }
+
+    fn deinit(&mut self) -> Result<(), DriverError> {
+        self.sensor.deinit()
+    }
+}
+
+impl<S: SensorDriver> SensorDriver for SensorFilter<S> {
+    fn read(&mut self) -> Result<SensorReading, DriverError> {
+        self.filtered_read()
+    }
+
+    fn temperature_unit(&self) -> TemperatureUnit {
+        self.sensor.temperature_unit()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 按顺序返回预设读数的模拟传感器
+    struct SeriesSensor {
+        series: Vec<SensorReading>,
+        next: usize,
+    }
+
+    impl SeriesSensor {
+        fn temperatures(values: &[f32]) -> Self {
+            Self { series: values.iter().map(|&v| SensorReading::Temperature(v)).collect(), next: 0 }
+        }
+    }
+
+    impl Driver for SeriesSensor {
+        fn name(&self) -> &'static str {
+            "series"
+        }
+
+        fn init(&mut self) -> Result<(), DriverError> {
+            Ok(())
+        }
+
+        fn is_ready(&self) -> bool {
+            true
+        }
+
+        fn deinit(&mut self) -> Result<(), DriverError> {
+            Ok(())
+        }
+    }
+
+    impl SensorDriver for SeriesSensor {
+        fn read(&mut self) -> Result<SensorReading, DriverError> {
+            let reading = self.series[self.next % self.series.len()];
+            self.next += 1;
+            Ok(reading)
+        }
+    }
+
+    fn temperature(reading: SensorReading) -> f32 {
+        match reading {
+            SensorReading::Temperature(value) => value,
+            other => panic!("读数类别改变: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_spike_suppressed_by_median() {
+        // 稳定在25°C的序列中出现一次85°C尖峰：中值完全剔除，平均值只略有偏移
+        let series = [25.0, 25.0, 25.0, 25.0, 85.0, 25.0, 25.0];
+        let mut median = SensorFilter::new(SeriesSensor::temperatures(&series), FilterMode::Median, 5);
+        let mut average = SensorFilter::new(SeriesSensor::temperatures(&series), FilterMode::MovingAverage, 5);
+
+        for _ in 0..series.len() {
+            assert_eq!(temperature(median.filtered_read().unwrap()), 25.0);
+            let smoothed = temperature(average.filtered_read().unwrap());
+            assert!((25.0..=37.0).contains(&smoothed));
+        }
+    }
+
+    #[test]
+    fn test_warm_up_and_reset_return_raw() {
+        // 样本不足窗口时返回原始读数；reset后重新预热；三轴读数逐分量滤波
+        let series = [20.0, 22.0, 24.0];
+        let mut filter = SensorFilter::new(SeriesSensor::temperatures(&series), FilterMode::MovingAverage, 3);
+        assert_eq!(filter.filtered_read(), Ok(SensorReading::Temperature(20.0)));
+        assert_eq!(filter.filtered_read(), Ok(SensorReading::Temperature(22.0)));
+        assert_eq!(filter.filtered_read(), Ok(SensorReading::Temperature(22.0)));
+
+        filter.reset();
+        assert_eq!(filter.filtered_read(), Ok(SensorReading::Temperature(20.0)));
+
+        let axes = SeriesSensor {
+            series: Vec::from([
+                SensorReading::Acceleration(0.0, 1.0, 9.0),
+                SensorReading::Acceleration(2.0, 3.0, 90.0),
+                SensorReading::Acceleration(4.0, 5.0, 9.0),
+            ]),
+            next: 0,
+        };
+        let mut filter = SensorFilter::new(axes, FilterMode::Median, 3);
+        filter.filtered_read().unwrap();
+        filter.filtered_read().unwrap();
+        assert_eq!(filter.filtered_read(), Ok(SensorReading::Acceleration(2.0, 3.0, 9.0)));
+    }
+}
@@ -5,24 +5,37 @@
 mod dht22;
 mod bh1750;
 mod mpu6050;
+mod filter;
 
-use crate::{Driver, SensorDriver, SensorData, DriverError};
+use crate::{Driver, SensorDriver, SensorData, SensorReading, TimedReading, DriverError};
+use crate::async_runtime::{MonotonicClock, SystemTimer};
 
 // 导出具体驱动
 pub use dht22::DHT22Driver;
 pub use bh1750::BH1750Driver;
 pub use mpu6050::MPU6050Driver;
+pub use filter::{FilterMode, SensorFilter};
 
 /// 环境传感器管理器
-pub struct EnvironmentalSensorManager {
+pub struct EnvironmentalSensorManager<C: MonotonicClock = SystemTimer> {
     sensors: heapless::Vec<Box<dyn SensorDriver>, 8>,
+    /// 读数时间戳的时钟
+    clock: C,
 }
 
 impl EnvironmentalSensorManager {
-    /// 创建新的传感器管理器
+    /// 创建新的传感器管理器，读数以系统定时器打时间戳
     pub fn new() -> Self {
+        Self::with_clock(SystemTimer)
+    }
+}
+
+impl<C: MonotonicClock> EnvironmentalSensorManager<C> {
+    /// 创建使用指定时钟打时间戳的传感器管理器
+    pub fn with_clock(clock: C) -> Self {
         Self {
             sensors: heapless::Vec::new(),
+            clock,
         }
     }
     
@@ -32,14 +45,18 @@ impl EnvironmentalSensorManager {
             .map_err(|_| DriverError::NotSupported)
     }
     
-    /// 读取所有传感器数据
-    pub fn read_all_sensors(&mut self) -> Result<Vec<SensorData>, DriverError> {
+    /// 读取所有传感器数据（已规范化为标准单位），每个读数带读取时刻和传感器序号
+    pub fn read_all_sensors(&mut self) -> Result<Vec<TimedReading>, DriverError> {
         let mut results = Vec::new();
         
-        for sensor in &mut self.sensors.iter_mut() {
+        for (sensor_id, sensor) in self.sensors.iter_mut().enumerate() {
             if sensor.is_ready() {
                 match sensor.read() {
-                    Ok(data) => results.push(data),
+                    Ok(data) => results.push(TimedReading {
+                        data: data.normalize(sensor.temperature_unit()),
+                        timestamp_us: self.clock.now().as_micros() as u64,
+                        sensor_id: sensor_id as u8,
+                    }),
                     Err(e) => return Err(e),
                 }
             }
@@ -47,4 +64,145 @@ impl EnvironmentalSensorManager {
         
         Ok(results)
     }
-}
\ No newline at end of file
+
+    /// 读取所有传感器数据，不带时间戳（兼容旧接口）
+    pub fn read_all_raw(&mut self) -> Result<Vec<SensorReading>, DriverError> {
+        Ok(self.read_all_sensors()?.into_iter().map(|reading| reading.data).collect())
+    }
+    
+    /// 读取所有传感器并汇总为一份传感器数据
+    pub fn read_snapshot(&mut self) -> Result<SensorData, DriverError> {
+        let mut snapshot = SensorData::new();
+        for reading in self.read_all_raw()? {
+            snapshot.record(reading);
+        }
+        Ok(snapshot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TemperatureUnit;
+    use core::cell::Cell;
+    use core::time::Duration;
+
+    struct MockSensor {
+        data: SensorReading,
+        unit: TemperatureUnit,
+    }
+
+    impl Driver for MockSensor {
+        fn name(&self) -> &'static str {
+            "mock"
+        }
+
+        fn init(&mut self) -> Result<(), DriverError> {
+            Ok(())
+        }
+
+        fn is_ready(&self) -> bool {
+            true
+        }
+
+        fn deinit(&mut self) -> Result<(), DriverError> {
+            Ok(())
+        }
+    }
+
+    impl SensorDriver for MockSensor {
+        fn read(&mut self) -> Result<SensorReading, DriverError> {
+            Ok(self.data.clone())
+        }
+
+        fn temperature_unit(&self) -> TemperatureUnit {
+            self.unit
+        }
+    }
+
+    #[test]
+    fn test_fahrenheit_normalized_to_celsius() {
+        // 华氏度传感器读数被规范化为摄氏度
+        let mut manager = EnvironmentalSensorManager::new();
+        manager.register_sensor(Box::new(MockSensor {
+            data: SensorReading::Temperature(212.0),
+            unit: TemperatureUnit::Fahrenheit,
+        })).unwrap();
+
+        let results = manager.read_all_raw().unwrap();
+        assert_eq!(results, vec![SensorReading::Temperature(100.0)]);
+        assert_eq!(TemperatureUnit::Celsius.to_fahrenheit(100.0), 212.0);
+    }
+
+    #[test]
+    fn test_three_axis_passthrough() {
+        // 三轴数据不受温度单位影响
+        let mut manager = EnvironmentalSensorManager::new();
+        manager.register_sensor(Box::new(MockSensor {
+            data: SensorReading::Acceleration(0.1, -9.8, 0.3),
+            unit: TemperatureUnit::Fahrenheit,
+        })).unwrap();
+        manager.register_sensor(Box::new(MockSensor {
+            data: SensorReading::Gyroscope(1.0, 2.0, 3.0),
+            unit: TemperatureUnit::Kelvin,
+        })).unwrap();
+
+        let results = manager.read_all_raw().unwrap();
+        assert_eq!(results, vec![
+            SensorReading::Acceleration(0.1, -9.8, 0.3),
+            SensorReading::Gyroscope(1.0, 2.0, 3.0),
+        ]);
+    }
+
+    #[test]
+    fn test_snapshot_uses_common_sensor_data() {
+        // 驱动层与common库使用同一传感器数据类型，读数汇总到快照
+        let mut manager = EnvironmentalSensorManager::new();
+        manager.register_sensor(Box::new(MockSensor {
+            data: SensorReading::Temperature(212.0),
+            unit: TemperatureUnit::Fahrenheit,
+        })).unwrap();
+        manager.register_sensor(Box::new(MockSensor {
+            data: SensorReading::Light(300.0),
+            unit: TemperatureUnit::Celsius,
+        })).unwrap();
+
+        let snapshot: common::SensorData = manager.read_snapshot().unwrap();
+        let reading: common::SensorReading = crate::SensorReading::Humidity(40.0);
+        assert_eq!(snapshot.temperature, Some(100.0));
+        assert_eq!(snapshot.light_level, Some(300.0));
+        assert_eq!(SensorData::from(reading).humidity, Some(40.0));
+    }
+
+    /// 每次读取前进250us的模拟时钟
+    struct StepClock(Cell<u64>);
+
+    impl MonotonicClock for StepClock {
+        fn now(&self) -> Duration {
+            self.0.set(self.0.get() + 250);
+            Duration::from_micros(self.0.get())
+        }
+    }
+
+    #[test]
+    fn test_timed_readings_are_monotonic() {
+        // 读数带传感器序号和微秒时间戳，两次读取的时间戳单调递增
+        let mut manager = EnvironmentalSensorManager::with_clock(StepClock(Cell::new(0)));
+        manager.register_sensor(Box::new(MockSensor {
+            data: SensorReading::Humidity(55.0),
+            unit: TemperatureUnit::Celsius,
+        })).unwrap();
+        manager.register_sensor(Box::new(MockSensor {
+            data: SensorReading::Light(120.0),
+            unit: TemperatureUnit::Celsius,
+        })).unwrap();
+
+        let first = manager.read_all_sensors().unwrap();
+        assert_eq!(first[0], TimedReading { data: SensorReading::Humidity(55.0), timestamp_us: 250, sensor_id: 0 });
+        assert_eq!((first[1].sensor_id, first[1].timestamp_us), (1, 500));
+
+        let second = manager.read_all_sensors().unwrap();
+        assert!(second[0].timestamp_us > first[1].timestamp_us);
+        assert!(second[1].timestamp_us > second[0].timestamp_us);
+    }
+}
@@ -34,6 +34,36 @@ pub enum PixelFormat {
     YUV420,         // YUV 4:2:0
 }
 
+impl PixelFormat {
+    /// 每个像素占用的存储位数（RAW10/RAW12按16位存储）
+    pub fn bits_per_pixel(self) -> u32 {
+        match self {
+            PixelFormat::RAW8 => 8,
+            PixelFormat::RAW10 | PixelFormat::RAW12 => 16,
+            PixelFormat::RGB888 => 24,
+            PixelFormat::YUV422 => 16,
+            PixelFormat::YUV420 => 12,
+        }
+    }
+    
+    /// 写入CSI_PIXEL_FORMAT寄存器的格式编码
+    pub fn format_code(self) -> u32 {
+        match self {
+            PixelFormat::RAW8 => 0x0A,
+            PixelFormat::RAW10 => 0x0B,
+            PixelFormat::RAW12 => 0x0C,
+            PixelFormat::RGB888 => 0x1E,
+            PixelFormat::YUV422 => 0x1E,
+            PixelFormat::YUV420 => 0x1F,
+        }
+    }
+    
+    /// 指定分辨率下一帧的字节数
+    pub fn frame_size(self, width: u32, height: u32) -> usize {
+        (width as u64 * height as u64 * self.bits_per_pixel() as u64 / 8) as usize
+    }
+}
+
 /// MIPI-CSI通道状态
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CsiChannelState {
@@ -198,7 +228,10 @@ impl MipiCsiChannel {
             
             transfer.configure(source, dest, buffer.size() as u32, crate::dma::DmaMode::Single);
             transfer.start()?;
-            transfer.wait_completion()?;
+            if !transfer.wait_completion()?.is_complete() {
+                // 帧数据不完整，丢弃该帧
+                return Err(DriverError::IoError);
+            }
         } else {
             // 传统传输方式
             return Err(DriverError::NotSupported);
@@ -230,16 +263,7 @@ impl MipiCsiChannel {
     
     /// 计算帧大小
     fn calculate_frame_size(&self) -> usize {
-        let pixel_size = match self.config.pixel_format {
-            PixelFormat::RAW8 => 1,
-            PixelFormat::RAW10 => 2, // 10位通常存储为16位
-            PixelFormat::RAW12 => 2, // 12位通常存储为16位
-            PixelFormat::RGB888 => 3,
-            PixelFormat::YUV422 => 2,
-            PixelFormat::YUV420 => 1, // 简化计算
-        };
-        
-        (self.config.image_width * self.config.image_height * pixel_size) as usize
+        self.config.pixel_format.frame_size(self.config.image_width, self.config.image_height)
     }
     
     /// 获取通道状态
@@ -286,15 +310,7 @@ impl MipiCsiChannel {
         base.add(0x10).write_volatile(self.config.image_height); // CSI_IMAGE_HEIGHT
         
         // 配置像素格式
-        let format_code = match self.config.pixel_format {
-            PixelFormat::RAW8 => 0x0A,
-            PixelFormat::RAW10 => 0x0B,
-            PixelFormat::RAW12 => 0x0C,
-            PixelFormat::RGB888 => 0x1E,
-            PixelFormat::YUV422 => 0x1E,
-            PixelFormat::YUV420 => 0x1F,
-        };
-        base.add(0x14).write_volatile(format_code); // CSI_PIXEL_FORMAT
+        base.add(0x14).write_volatile(self.config.pixel_format.format_code()); // CSI_PIXEL_FORMAT
         
         // 启用CSI控制器
         base.add(0x0).write_volatile(0x2); // CSI_CTRL寄存器
@@ -365,7 +381,7 @@ impl AsyncDriver for MipiCsiChannel {
 }
 
 impl AsyncSensorDriver for MipiCsiChannel {
-    async fn read(&mut self) -> Result<crate::SensorData, DriverError> {
+    async fn read(&mut self) -> Result<crate::SensorReading, DriverError> {
         // MIPI-CSI通常返回图像数据，不是传感器读数
         Err(DriverError::NotSupported)
     }
@@ -381,6 +397,272 @@ impl AsyncSensorDriver for MipiCsiChannel {
     }
 }
 
+/// CSI寄存器：图像宽度
+const CSI_IMAGE_WIDTH: usize = 0xC;
+/// CSI寄存器：图像高度
+const CSI_IMAGE_HEIGHT: usize = 0x10;
+/// CSI寄存器：像素格式
+const CSI_PIXEL_FORMAT: usize = 0x14;
+/// CSI寄存器：DMA控制
+const CSI_DMA_CTRL: usize = 0x18;
+/// CSI寄存器：下一帧的DMA写入地址
+const CSI_DMA_ADDR: usize = 0x1C;
+/// CSI寄存器：帧状态，bit0为帧完成（写1清除）
+const CSI_FRAME_STATUS: usize = 0x20;
+const CSI_FRAME_DONE: u32 = 1 << 0;
+
+/// 双缓冲中单个缓冲区的状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FrameBufferState {
+    /// 空闲
+    Free,
+    /// DMA正在写入
+    Filling,
+    /// 已写满，等待取用
+    Ready,
+    /// 消费者正在处理
+    Held,
+}
+
+/// 乒乓缓冲调度：一个缓冲区接收新帧时，另一个交给消费者处理
+#[derive(Debug)]
+struct PingPong {
+    states: [FrameBufferState; 2],
+    frames_dropped: u32,
+}
+
+impl PingPong {
+    /// 缓冲区0开始接收第一帧
+    fn new() -> Self {
+        Self {
+            states: [FrameBufferState::Filling, FrameBufferState::Free],
+            frames_dropped: 0,
+        }
+    }
+    
+    fn find(&self, state: FrameBufferState) -> Option<usize> {
+        self.states.iter().position(|&s| s == state)
+    }
+    
+    /// 一帧接收完成，返回下一帧的写入缓冲区
+    /// 
+    /// 上一帧就绪后未被取走时被新帧覆盖计为丢帧；两个缓冲区都被占用时
+    /// 返回None，调用者必须停止DMA，直到`arm_if_idle`给出空闲缓冲区
+    fn frame_complete(&mut self) -> Option<usize> {
+        let Some(done) = self.find(FrameBufferState::Filling) else {
+            self.frames_dropped += 1;
+            return None;
+        };
+        
+        let other = 1 - done;
+        self.states[done] = FrameBufferState::Ready;
+        match self.states[other] {
+            FrameBufferState::Held => None,
+            FrameBufferState::Ready => {
+                self.frames_dropped += 1;
+                self.states[other] = FrameBufferState::Filling;
+                Some(other)
+            }
+            _ => {
+                self.states[other] = FrameBufferState::Filling;
+                Some(other)
+            }
+        }
+    }
+    
+    /// 释放上一帧并取走最新的就绪帧
+    fn acquire(&mut self) -> Option<usize> {
+        let ready = self.find(FrameBufferState::Ready)?;
+        if let Some(held) = self.find(FrameBufferState::Held) {
+            self.states[held] = FrameBufferState::Free;
+        }
+        self.states[ready] = FrameBufferState::Held;
+        Some(ready)
+    }
+    
+    /// 没有缓冲区在接收时启用空闲缓冲区，返回其索引
+    fn arm_if_idle(&mut self) -> Option<usize> {
+        if self.find(FrameBufferState::Filling).is_some() {
+            return None;
+        }
+        let free = self.find(FrameBufferState::Free)?;
+        self.states[free] = FrameBufferState::Filling;
+        Some(free)
+    }
+}
+
+/// 双缓冲MIPI-CSI相机，为YOLO流水线提供连续帧
+/// 
+/// 消费者处理当前帧期间，DMA向另一个缓冲区写入下一帧
+pub struct MipiCsiCamera {
+    base_address: u64,
+    width: u32,
+    height: u32,
+    format: PixelFormat,
+    frame_size: usize,
+    buffers: [Option<DmaBuffer>; 2],
+    ping_pong: PingPong,
+}
+
+impl MipiCsiCamera {
+    /// 创建相机，需先调用`configure`
+    pub const fn new(base_address: u64) -> Self {
+        Self {
+            base_address,
+            width: 0,
+            height: 0,
+            format: PixelFormat::RAW8,
+            frame_size: 0,
+            buffers: [None, None],
+            ping_pong: PingPong {
+                states: [FrameBufferState::Filling, FrameBufferState::Free],
+                frames_dropped: 0,
+            },
+        }
+    }
+    
+    /// 配置分辨率和像素格式，按帧大小分配两个DMA缓冲区并开始接收
+    pub fn configure(&mut self, width: u32, height: u32, format: PixelFormat) -> Result<(), DriverError> {
+        let frame_size = format.frame_size(width, height);
+        if frame_size == 0 {
+            return Err(DriverError::InvalidParameter);
+        }
+        
+        // 先停止DMA并清除挂起的帧完成标志，再释放旧缓冲区
+        unsafe {
+            self.write_reg(CSI_DMA_CTRL, 0x0);
+            self.write_reg(CSI_FRAME_STATUS, CSI_FRAME_DONE);
+        }
+        for buffer in self.buffers.iter_mut() {
+            let allocated = unsafe { DmaBuffer::new(frame_size) }.map_err(|_| DriverError::InitializationFailed)?;
+            *buffer = Some(allocated);
+        }
+        self.width = width;
+        self.height = height;
+        self.format = format;
+        self.frame_size = frame_size;
+        self.ping_pong = PingPong::new();
+        
+        unsafe {
+            self.write_reg(CSI_IMAGE_WIDTH, width);
+            self.write_reg(CSI_IMAGE_HEIGHT, height);
+            self.write_reg(CSI_PIXEL_FORMAT, format.format_code());
+            self.arm_buffer(0)?;
+            self.write_reg(CSI_DMA_CTRL, 0x1);
+        }
+        Ok(())
+    }
+    
+    /// 帧完成中断处理：切换DMA写入缓冲区
+    /// 
+    /// 两个缓冲区都被占用时停止DMA，避免硬件覆盖等待取用的就绪帧
+    pub fn handle_frame_interrupt(&mut self) {
+        let armed = match self.ping_pong.frame_complete() {
+            Some(next) => unsafe { self.arm_buffer(next) }.is_ok(),
+            None => false,
+        };
+        if !armed {
+            unsafe { self.write_reg(CSI_DMA_CTRL, 0x0) };
+        }
+    }
+    
+    /// 取得最新的完整帧，上一次返回的帧在此时释放
+    /// 
+    /// 返回的数据长度为配置分辨率对应的帧大小；尚无新帧时返回`DeviceBusy`
+    pub fn capture_frame(&mut self) -> Result<&[u8], DriverError> {
+        if self.frame_size == 0 {
+            return Err(DriverError::InvalidParameter);
+        }
+        
+        // 轮询方式下在此处理挂起的帧完成事件
+        unsafe {
+            if self.read_reg(CSI_FRAME_STATUS) & CSI_FRAME_DONE != 0 {
+                self.write_reg(CSI_FRAME_STATUS, CSI_FRAME_DONE);
+                self.handle_frame_interrupt();
+            }
+        }
+        
+        let index = self.ping_pong.acquire().ok_or(DriverError::DeviceBusy)?;
+        if let Some(free) = self.ping_pong.arm_if_idle() {
+            // DMA因没有空闲缓冲区而停止，释放出缓冲区后重新开始接收
+            unsafe {
+                self.arm_buffer(free)?;
+                self.write_reg(CSI_DMA_CTRL, 0x1);
+            }
+        }
+        
+        let buffer = self.buffers[index].as_ref().ok_or(DriverError::InvalidParameter)?;
+        Ok(&buffer.as_slice()[..self.frame_size])
+    }
+    
+    /// 因消费者处理过慢而丢弃的帧数
+    pub fn frames_dropped(&self) -> u32 {
+        self.ping_pong.frames_dropped
+    }
+    
+    /// 当前分辨率和像素格式
+    pub fn format(&self) -> (u32, u32, PixelFormat) {
+        (self.width, self.height, self.format)
+    }
+    
+    /// 将下一帧的DMA写入地址指向指定缓冲区
+    /// 
+    /// CSI的DMA地址寄存器只有32位，缓冲区位于4GB以上时返回错误
+    unsafe fn arm_buffer(&self, index: usize) -> Result<(), DriverError> {
+        let buffer = self.buffers[index].as_ref().ok_or(DriverError::InvalidParameter)?;
+        let address = u32::try_from(buffer.physical_address()).map_err(|_| DriverError::InvalidParameter)?;
+        self.write_reg(CSI_DMA_ADDR, address);
+        Ok(())
+    }
+    
+    /// 寄存器编号与`MipiCsiChannel`的硬件操作一致，按32位字索引
+    unsafe fn read_reg(&self, index: usize) -> u32 {
+        (self.base_address as *const u32).add(index).read_volatile()
+    }
+    
+    unsafe fn write_reg(&self, index: usize, value: u32) {
+        (self.base_address as *mut u32).add(index).write_volatile(value)
+    }
+}
+
+/// 将YUYV排列（Y0 U Y1 V）的YUV422帧转换为RGB888
+/// 
+/// 使用BT.601有限范围系数的整数近似，结果截断到[0,255]。宽度为奇数时每行最后一个像素
+/// 只有Y和U，沿用前一对像素的V（宽度为1时取中性值128）
+pub fn convert_yuv422_to_rgb888(src: &[u8], width: usize, height: usize, dst: &mut [u8]) -> Result<(), DriverError> {
+    let pixels = width.checked_mul(height).ok_or(DriverError::InvalidParameter)?;
+    if src.len() != pixels * 2 || dst.len() != pixels * 3 {
+        return Err(DriverError::InvalidParameter);
+    }
+    if pixels == 0 {
+        return Ok(());
+    }
+    
+    for (row, rgb_row) in src.chunks_exact(width * 2).zip(dst.chunks_exact_mut(width * 3)) {
+        for (x, rgb) in rgb_row.chunks_exact_mut(3).enumerate() {
+            let pair = x / 2 * 4;
+            let y = row[x * 2];
+            let u = row[pair + 1];
+            let v = match row.get(pair + 3) {
+                Some(&v) => v,
+                None if pair >= 4 => row[pair - 1],
+                None => 128,
+            };
+            rgb.copy_from_slice(&yuv_to_rgb(y, u, v));
+        }
+    }
+    Ok(())
+}
+
+/// BT.601有限范围YUV到RGB的单像素转换
+fn yuv_to_rgb(y: u8, u: u8, v: u8) -> [u8; 3] {
+    let c = 298 * (y as i32 - 16);
+    let d = u as i32 - 128;
+    let e = v as i32 - 128;
+    let clamp = |value: i32| ((value + 128) >> 8).clamp(0, 255) as u8;
+    [clamp(c + 409 * e), clamp(c - 100 * d - 208 * e), clamp(c + 516 * d)]
+}
+
 /// MIPI-CSI驱动管理器
 pub struct MipiCsiManager {
     channels: [Option<MipiCsiChannel>; 4], // 最多4个CSI通道
@@ -470,4 +752,73 @@ pub fn create_default_config() -> MipiCsiConfig {
         zero_copy: true,
         hdr_mode: false,
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_size_per_format() {
+        // 640x480：RAW8每像素1字节，YUV422为2字节，RGB888为3字节，YUV420为1.5字节
+        assert_eq!(PixelFormat::RAW8.frame_size(640, 480), 307_200);
+        assert_eq!(PixelFormat::YUV422.frame_size(640, 480), 614_400);
+        assert_eq!(PixelFormat::RGB888.frame_size(640, 480), 921_600);
+        assert_eq!(PixelFormat::YUV420.frame_size(640, 480), 460_800);
+        assert_eq!(PixelFormat::RAW10.frame_size(1920, 1080), 1920 * 1080 * 2);
+    }
+
+    #[test]
+    fn test_yuv422_gray_and_red_to_rgb() {
+        // 中性灰（Y=128）转换为约130的灰色；BT.601红色（81,90,240）转换为纯红
+        let gray = [128u8, 128, 128, 128].repeat(4);
+        let mut rgb = [0u8; 4 * 2 * 3];
+        convert_yuv422_to_rgb888(&gray, 4, 2, &mut rgb).unwrap();
+        assert!(rgb.iter().all(|&c| c.abs_diff(130) <= 1));
+
+        let red = [81u8, 90, 81, 240];
+        let mut rgb = [0u8; 6];
+        convert_yuv422_to_rgb888(&red, 2, 1, &mut rgb).unwrap();
+        for pixel in rgb.chunks(3) {
+            assert!(pixel[0] >= 253 && pixel[1] <= 2 && pixel[2] <= 2, "{:?}", pixel);
+        }
+
+        assert_eq!(convert_yuv422_to_rgb888(&red, 2, 2, &mut [0u8; 12]), Err(DriverError::InvalidParameter));
+        assert_eq!(convert_yuv422_to_rgb888(&red, 2, 1, &mut [0u8; 5]), Err(DriverError::InvalidParameter));
+    }
+
+    #[test]
+    fn test_yuv422_odd_width_reuses_last_chroma() {
+        // 宽度3：第三个像素沿用第一对像素的V，与第一对像素颜色一致
+        let row = [81u8, 90, 81, 240, 81, 90];
+        let mut rgb = [0u8; 9];
+        convert_yuv422_to_rgb888(&row, 3, 1, &mut rgb).unwrap();
+        assert_eq!(rgb[6..9], rgb[0..3]);
+    }
+
+    #[test]
+    fn test_drops_counted_when_consumer_is_slow() {
+        // 消费者持有一帧且另一帧已就绪时，新到达的帧被丢弃；取走后恢复接收
+        let mut ping_pong = PingPong::new();
+        assert_eq!(ping_pong.frame_complete(), Some(1));
+        assert_eq!(ping_pong.acquire(), Some(0));
+        assert_eq!(ping_pong.arm_if_idle(), None);
+
+        // 缓冲区1写满，缓冲区0仍在处理：没有可写入的缓冲区，DMA必须停止
+        assert_eq!(ping_pong.frame_complete(), None);
+        assert_eq!(ping_pong.states, [FrameBufferState::Held, FrameBufferState::Ready]);
+        assert_eq!(ping_pong.frames_dropped, 0);
+
+        // 停止前已在途的帧完成事件计为丢帧，不会把就绪帧交给DMA覆盖
+        assert_eq!(ping_pong.frame_complete(), None);
+        assert_eq!(ping_pong.states, [FrameBufferState::Held, FrameBufferState::Ready]);
+        assert_eq!(ping_pong.frames_dropped, 1);
+
+        // 取走缓冲区1后释放缓冲区0并重新开始接收
+        assert_eq!(ping_pong.acquire(), Some(1));
+        assert_eq!(ping_pong.arm_if_idle(), Some(0));
+        assert_eq!(ping_pong.frame_complete(), None);
+        assert_eq!(ping_pong.acquire(), Some(0));
+        assert_eq!(ping_pong.frames_dropped, 1);
+    }
+}
@@ -0,0 +1,233 @@
+//! RK3588 散热管理模块
+//!
+//! 根据CPU核心和NPU的最高温度，通过PWM风扇进行主动散热
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicI32, Ordering};
+
+use crate::DriverError;
+
+/// 温度传感器特征
+pub trait TemperatureSensor {
+    /// 读取温度 (°C)，读取失败时返回None
+    fn read_temperature(&self) -> Option<f32>;
+}
+
+/// PWM输出通道特征
+pub trait PwmChannel {
+    /// 设置占空比 (0-100%)
+    fn set_duty(&mut self, duty_percent: u8) -> Result<(), DriverError>;
+}
+
+/// 风扇曲线点：温度达到`temperature`时占空比为`duty`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FanCurvePoint {
+    pub temperature: f32,
+    pub duty: u8,
+}
+
+impl FanCurvePoint {
+    /// 创建新的风扇曲线点
+    pub const fn new(temperature: f32, duty: u8) -> Self {
+        Self { temperature, duty }
+    }
+}
+
+/// 默认风扇曲线
+const DEFAULT_CURVE: [FanCurvePoint; 5] = [
+    FanCurvePoint::new(0.0, 0),
+    FanCurvePoint::new(50.0, 30),
+    FanCurvePoint::new(60.0, 50),
+    FanCurvePoint::new(70.0, 75),
+    FanCurvePoint::new(80.0, 100),
+];
+
+/// 默认回滞温差 (°C)
+const DEFAULT_HYSTERESIS: f32 = 3.0;
+
+/// 最近一次上报的NPU温度 (m°C)
+static NPU_TEMPERATURE_MILLI: AtomicI32 = AtomicI32::new(25_000);
+
+/// 上报NPU温度，由NPU驱动在读取温度传感器后调用
+pub fn report_npu_temperature(celsius: f32) {
+    NPU_TEMPERATURE_MILLI.store((celsius * 1000.0) as i32, Ordering::Release);
+}
+
+/// CPU核心温度传感器（取所有核心中的最高温度）
+pub struct CoreTemperatureSensor;
+
+impl TemperatureSensor for CoreTemperatureSensor {
+    fn read_temperature(&self) -> Option<f32> {
+        unsafe {
+            starry_kernel::cpu::ENHANCED_SCHEDULER
+                .as_ref()
+                .map(|scheduler| scheduler.max_core_temperature() as f32)
+        }
+    }
+}
+
+/// NPU温度传感器
+pub struct NpuTemperatureSensor;
+
+impl TemperatureSensor for NpuTemperatureSensor {
+    fn read_temperature(&self) -> Option<f32> {
+        Some(NPU_TEMPERATURE_MILLI.load(Ordering::Acquire) as f32 / 1000.0)
+    }
+}
+
+/// 散热管理器
+pub struct ThermalManager {
+    sensors: Vec<Box<dyn TemperatureSensor>>,
+    fan: Box<dyn PwmChannel>,
+    curve: Vec<FanCurvePoint>,
+    hysteresis: f32,
+    current_duty: u8,
+}
+
+impl ThermalManager {
+    /// 创建新的散热管理器
+    pub fn new(fan: Box<dyn PwmChannel>) -> Self {
+        Self {
+            sensors: Vec::new(),
+            fan,
+            curve: DEFAULT_CURVE.to_vec(),
+            hysteresis: DEFAULT_HYSTERESIS,
+            current_duty: 0,
+        }
+    }
+
+    /// 注册温度传感器
+    pub fn add_sensor(&mut self, sensor: Box<dyn TemperatureSensor>) {
+        self.sensors.push(sensor);
+    }
+
+    /// 设置温度→占空比曲线
+    pub fn set_curve(&mut self, curve: &[FanCurvePoint]) -> Result<(), DriverError> {
+        if curve.is_empty() || curve.iter().any(|p| p.duty > 100) {
+            return Err(DriverError::InvalidParameter);
+        }
+
+        // 曲线温度必须严格递增
+        if curve.windows(2).any(|w| w[1].temperature <= w[0].temperature) {
+            return Err(DriverError::InvalidParameter);
+        }
+
+        self.curve = curve.to_vec();
+        Ok(())
+    }
+
+    /// 设置回滞温差 (°C)
+    pub fn set_hysteresis(&mut self, hysteresis: f32) {
+        self.hysteresis = hysteresis.max(0.0);
+    }
+
+    /// 当前风扇占空比 (0-100%)
+    pub fn current_duty(&self) -> u8 {
+        self.current_duty
+    }
+
+    /// 读取所有传感器中的最高温度
+    pub fn max_temperature(&self) -> Option<f32> {
+        self.sensors
+            .iter()
+            .filter_map(|s| s.read_temperature())
+            .fold(None, |max, t| Some(max.map_or(t, |m: f32| m.max(t))))
+    }
+
+    /// 采样温度并更新风扇占空比
+    pub fn update(&mut self) -> Result<u8, DriverError> {
+        let temperature = self.max_temperature().ok_or(DriverError::DeviceNotFound)?;
+        let target = self.duty_for(temperature);
+
+        let duty = if target >= self.current_duty {
+            target
+        } else {
+            // 降温时需低于阈值回滞温差才降档，避免阈值附近频繁切换
+            self.duty_for(temperature + self.hysteresis).min(self.current_duty)
+        };
+
+        if duty != self.current_duty {
+            self.fan.set_duty(duty)?;
+            self.current_duty = duty;
+        }
+
+        Ok(self.current_duty)
+    }
+
+    /// 按曲线查找温度对应的占空比
+    fn duty_for(&self, temperature: f32) -> u8 {
+        self.curve
+            .iter()
+            .rev()
+            .find(|p| temperature >= p.temperature)
+            .map(|p| p.duty)
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::sync::atomic::AtomicU32;
+
+    struct MockSensor(&'static AtomicU32);
+
+    impl TemperatureSensor for MockSensor {
+        fn read_temperature(&self) -> Option<f32> {
+            Some(self.0.load(Ordering::Relaxed) as f32)
+        }
+    }
+
+    struct MockPwm(&'static AtomicU32);
+
+    impl PwmChannel for MockPwm {
+        fn set_duty(&mut self, _duty_percent: u8) -> Result<(), DriverError> {
+            self.0.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_duty_rises_along_curve() {
+        // 温度升高时占空比沿曲线上升，取核心与NPU中的最高温度
+        static CORE: AtomicU32 = AtomicU32::new(40);
+        static NPU: AtomicU32 = AtomicU32::new(30);
+        static WRITES: AtomicU32 = AtomicU32::new(0);
+
+        let mut manager = ThermalManager::new(Box::new(MockPwm(&WRITES)));
+        manager.add_sensor(Box::new(MockSensor(&CORE)));
+        manager.add_sensor(Box::new(MockSensor(&NPU)));
+
+        assert_eq!(manager.update().unwrap(), 0);
+        CORE.store(55, Ordering::Relaxed);
+        assert_eq!(manager.update().unwrap(), 30);
+        NPU.store(72, Ordering::Relaxed);
+        assert_eq!(manager.update().unwrap(), 75);
+        CORE.store(85, Ordering::Relaxed);
+        assert_eq!(manager.update().unwrap(), 100);
+        assert_eq!(manager.current_duty(), 100);
+        assert_eq!(WRITES.load(Ordering::Relaxed), 3);
+    }
+
+    #[test]
+    fn test_hysteresis_prevents_oscillation() {
+        // 在阈值附近波动时占空比保持不变，降至回滞温差以下才降档
+        static TEMP: AtomicU32 = AtomicU32::new(61);
+        static WRITES: AtomicU32 = AtomicU32::new(0);
+
+        let mut manager = ThermalManager::new(Box::new(MockPwm(&WRITES)));
+        manager.add_sensor(Box::new(MockSensor(&TEMP)));
+        assert_eq!(manager.update().unwrap(), 50);
+
+        for temp in [59, 61, 58, 60, 59] {
+            TEMP.store(temp, Ordering::Relaxed);
+            assert_eq!(manager.update().unwrap(), 50);
+        }
+        assert_eq!(WRITES.load(Ordering::Relaxed), 1);
+
+        TEMP.store(56, Ordering::Relaxed);
+        assert_eq!(manager.update().unwrap(), 30);
+        assert!(manager.set_curve(&[FanCurvePoint::new(60.0, 50), FanCurvePoint::new(50.0, 30)]).is_err());
+    }
+}
@@ -4,9 +4,12 @@
 
 #![no_std]
 
-use core::sync::atomic::{AtomicBool, Ordering};
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use core::fmt;
-use core::cell::UnsafeCell;
+
+use crate::register_map::{HardwareBackend, MmioBackend, RegisterMap, Rk3588Map};
+use starry_kernel::gic::{has_interrupt_handler, request_irq, PriorityBand};
+use starry_kernel::sync::IrqMutex;
 
 /// GPIO错误类型
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -63,27 +66,51 @@ pub enum GpioInterrupt {
     LowLevel,
 }
 
-/// RK3588 GPIO寄存器定义
-#[repr(C)]
-struct GpioRegisters {
-    swport_dr: UnsafeCell<u32>,     // 数据寄存器
-    swport_ddr: UnsafeCell<u32>,    // 方向寄存器
-    swport_ctl: UnsafeCell<u32>,    // 控制寄存器
-    _reserved1: [u32; 5],
-    inten: UnsafeCell<u32>,         // 中断使能
-    intmask: UnsafeCell<u32>,       // 中断屏蔽
-    inttype_level: UnsafeCell<u32>, // 中断类型（电平）
-    int_polarity: UnsafeCell<u32>,  // 中断极性
-    intstatus: UnsafeCell<u32>,     // 中断状态
-    raw_intstatus: UnsafeCell<u32>, // 原始中断状态
-    debounce: UnsafeCell<u32>,      // 去抖动
-    port_eoi: UnsafeCell<u32>,      // 中断结束
-    ext_port: UnsafeCell<u32>,      // 外部端口
-    _reserved2: [u32; 3],
-    ls_sync: UnsafeCell<u32>,       // 电平同步
+/// GPIO寄存器
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GpioRegister {
+    SwportDr,       // 数据寄存器
+    SwportDdr,      // 方向寄存器
+    SwportCtl,      // 控制寄存器
+    Inten,          // 中断使能
+    Intmask,        // 中断屏蔽
+    InttypeLevel,   // 中断类型（电平）
+    IntPolarity,    // 中断极性
+    Intstatus,      // 中断状态
+    RawIntstatus,   // 原始中断状态
+    Debounce,       // 去抖动
+    PortEoi,        // 中断结束
+    ExtPort,        // 外部端口
+    LsSync,         // 电平同步
+}
+
+/// RK3588 GPIO寄存器布局
+impl RegisterMap<GpioRegister> for Rk3588Map {
+    fn base(&self) -> usize {
+        self.base_address()
+    }
+    
+    fn offset(&self, register: GpioRegister) -> usize {
+        match register {
+            GpioRegister::SwportDr => 0x00,
+            GpioRegister::SwportDdr => 0x04,
+            GpioRegister::SwportCtl => 0x08,
+            GpioRegister::Inten => 0x20,
+            GpioRegister::Intmask => 0x24,
+            GpioRegister::InttypeLevel => 0x28,
+            GpioRegister::IntPolarity => 0x2C,
+            GpioRegister::Intstatus => 0x30,
+            GpioRegister::RawIntstatus => 0x34,
+            GpioRegister::Debounce => 0x38,
+            GpioRegister::PortEoi => 0x3C,
+            GpioRegister::ExtPort => 0x40,
+            GpioRegister::LsSync => 0x50,
+        }
+    }
 }
 
 /// RK3588 GPIO组定义
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum GpioBank {
     GPIO0 = 0,
     GPIO1 = 1,
@@ -92,6 +119,48 @@ pub enum GpioBank {
     GPIO4 = 4,
 }
 
+impl GpioBank {
+    /// 全部GPIO组
+    pub const ALL: [GpioBank; 5] = [GpioBank::GPIO0, GpioBank::GPIO1, GpioBank::GPIO2, GpioBank::GPIO3, GpioBank::GPIO4];
+    
+    /// GPIO0组中断号（GIC SPI 277），其余组依次递增
+    const GPIO0_IRQ: u32 = 309;
+    
+    /// 组中断号
+    pub const fn irq(self) -> u32 {
+        Self::GPIO0_IRQ + self as u32
+    }
+    
+    /// 中断号对应的GPIO组
+    pub fn from_irq(interrupt_id: u32) -> Option<GpioBank> {
+        Self::ALL.into_iter().find(|bank| bank.irq() == interrupt_id)
+    }
+}
+
+/// GPIO中断回调
+pub type GpioCallback = fn(GpioPin);
+
+/// 各引脚的中断回调表，注册方与中断处理函数共享，持锁期间屏蔽本核IRQ
+static GPIO_CALLBACKS: IrqMutex<[[Option<GpioCallback>; 32]; 5]> = IrqMutex::new([[None; 32]; 5]);
+
+/// 注册GPIO引脚中断回调，已存在的回调被替换
+pub fn register_gpio_callback(pin: GpioPin, handler: GpioCallback) -> Result<(), GpioError> {
+    if !pin.is_valid() {
+        return Err(GpioError::InvalidPin);
+    }
+    GPIO_CALLBACKS.lock()[pin.bank as usize][pin.pin as usize] = Some(handler);
+    Ok(())
+}
+
+/// 注销GPIO引脚中断回调
+pub fn unregister_gpio_callback(pin: GpioPin) -> Result<(), GpioError> {
+    if !pin.is_valid() {
+        return Err(GpioError::InvalidPin);
+    }
+    GPIO_CALLBACKS.lock()[pin.bank as usize][pin.pin as usize] = None;
+    Ok(())
+}
+
 /// RK3588 GPIO引脚
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct GpioPin {
@@ -111,10 +180,15 @@ impl GpioPin {
     }
 }
 
-/// RK3588 GPIO驱动
-pub struct Rk3588Gpio {
-    registers: [*mut GpioRegisters; 5],
+/// GPIO驱动
+/// 
+/// 每个GPIO组通过各自的寄存器映射`M`访问寄存器，默认使用RK3588布局和MMIO后端
+pub struct Rk3588Gpio<M = Rk3588Map, B = MmioBackend> {
+    banks: [M; 5],
+    backend: B,
     initialized: AtomicBool,
+    /// 各组中配置为双边沿触发的引脚
+    both_edges: [AtomicU32; 5],
 }
 
 impl Rk3588Gpio {
@@ -130,14 +204,28 @@ impl Rk3588Gpio {
     /// 创建新的GPIO实例
     pub const fn new() -> Self {
         Self {
-            registers: [
-                Self::GPIO_BASE_ADDRESSES[0] as *mut GpioRegisters,
-                Self::GPIO_BASE_ADDRESSES[1] as *mut GpioRegisters,
-                Self::GPIO_BASE_ADDRESSES[2] as *mut GpioRegisters,
-                Self::GPIO_BASE_ADDRESSES[3] as *mut GpioRegisters,
-                Self::GPIO_BASE_ADDRESSES[4] as *mut GpioRegisters,
+            banks: [
+                Rk3588Map::new(Self::GPIO_BASE_ADDRESSES[0]),
+                Rk3588Map::new(Self::GPIO_BASE_ADDRESSES[1]),
+                Rk3588Map::new(Self::GPIO_BASE_ADDRESSES[2]),
+                Rk3588Map::new(Self::GPIO_BASE_ADDRESSES[3]),
+                Rk3588Map::new(Self::GPIO_BASE_ADDRESSES[4]),
             ],
+            backend: unsafe { MmioBackend::new() },
+            initialized: AtomicBool::new(false),
+            both_edges: [AtomicU32::new(0), AtomicU32::new(0), AtomicU32::new(0), AtomicU32::new(0), AtomicU32::new(0)],
+        }
+    }
+}
+
+impl<M: RegisterMap<GpioRegister>, B: HardwareBackend> Rk3588Gpio<M, B> {
+    /// 使用指定的各组寄存器映射和访问后端创建GPIO实例
+    pub fn with_map(banks: [M; 5], backend: B) -> Self {
+        Self {
+            banks,
+            backend,
             initialized: AtomicBool::new(false),
+            both_edges: [AtomicU32::new(0), AtomicU32::new(0), AtomicU32::new(0), AtomicU32::new(0), AtomicU32::new(0)],
         }
     }
     
@@ -175,11 +263,11 @@ impl Rk3588Gpio {
             match mode {
                 GpioMode::Input => {
                     // 设置为输入模式
-                    (*self.registers[bank]).swport_ddr.get().update(|val| val & !pin_mask);
+                    self.modify(bank, GpioRegister::SwportDdr, |val| val & !pin_mask);
                 }
                 GpioMode::Output => {
                     // 设置为输出模式
-                    (*self.registers[bank]).swport_ddr.get().update(|val| val | pin_mask);
+                    self.modify(bank, GpioRegister::SwportDdr, |val| val | pin_mask);
                 }
                 _ => {
                     // 设置复用功能
@@ -201,27 +289,25 @@ impl Rk3588Gpio {
             return Err(GpioError::InvalidPin);
         }
         
-        unsafe {
-            let bank = pin.bank as usize;
-            let pin_mask = 1u32 << pin.pin;
-            
-            match pull {
-                GpioPull::None => {
-                    // 禁用上拉下拉
-                    (*self.registers[bank]).swport_ctl.get().update(|val| val & !(0b11 << (pin.pin * 2)));
-                }
-                GpioPull::Up => {
-                    // 启用上拉
-                    (*self.registers[bank]).swport_ctl.get().update(|val| 
-                        (val & !(0b11 << (pin.pin * 2))) | (0b01 << (pin.pin * 2))
-                    );
-                }
-                GpioPull::Down => {
-                    // 启用下拉
-                    (*self.registers[bank]).swport_ctl.get().update(|val| 
-                        (val & !(0b11 << (pin.pin * 2))) | (0b10 << (pin.pin * 2))
-                    );
-                }
+        let bank = pin.bank as usize;
+        let pin_mask = 1u32 << pin.pin;
+        
+        match pull {
+            GpioPull::None => {
+                // 禁用上拉下拉
+                self.modify(bank, GpioRegister::SwportCtl, |val| val & !(0b11 << (pin.pin * 2)));
+            }
+            GpioPull::Up => {
+                // 启用上拉
+                self.modify(bank, GpioRegister::SwportCtl, |val| 
+                    (val & !(0b11 << (pin.pin * 2))) | (0b01 << (pin.pin * 2))
+                );
+            }
+            GpioPull::Down => {
+                // 启用下拉
+                self.modify(bank, GpioRegister::SwportCtl, |val| 
+                    (val & !(0b11 << (pin.pin * 2))) | (0b10 << (pin.pin * 2))
+                );
             }
         }
         
@@ -238,17 +324,15 @@ impl Rk3588Gpio {
             return Err(GpioError::InvalidPin);
         }
         
-        unsafe {
-            let bank = pin.bank as usize;
-            let pin_mask = 1u32 << pin.pin;
-            
-            if level {
-                // 设置高电平
-                (*self.registers[bank]).swport_dr.get().update(|val| val | pin_mask);
-            } else {
-                // 设置低电平
-                (*self.registers[bank]).swport_dr.get().update(|val| val & !pin_mask);
-            }
+        let bank = pin.bank as usize;
+        let pin_mask = 1u32 << pin.pin;
+        
+        if level {
+            // 设置高电平
+            self.modify(bank, GpioRegister::SwportDr, |val| val | pin_mask);
+        } else {
+            // 设置低电平
+            self.modify(bank, GpioRegister::SwportDr, |val| val & !pin_mask);
         }
         
         Ok(())
@@ -264,13 +348,11 @@ impl Rk3588Gpio {
             return Err(GpioError::InvalidPin);
         }
         
-        unsafe {
-            let bank = pin.bank as usize;
-            let pin_mask = 1u32 << pin.pin;
-            
-            let level = (*self.registers[bank]).ext_port.get().read_volatile() & pin_mask != 0;
-            Ok(level)
-        }
+        let bank = pin.bank as usize;
+        let pin_mask = 1u32 << pin.pin;
+        
+        let level = self.read(bank, GpioRegister::ExtPort) & pin_mask != 0;
+        Ok(level)
     }
     
     /// 切换GPIO引脚电平
@@ -297,41 +379,43 @@ impl Rk3588Gpio {
             return Err(GpioError::InvalidPin);
         }
         
-        unsafe {
-            let bank = pin.bank as usize;
-            let pin_mask = 1u32 << pin.pin;
-            
-            // 清除之前的中断配置
-            (*self.registers[bank]).inten.get().update(|val| val & !pin_mask);
-            
-            // 配置中断类型
-            match interrupt {
-                GpioInterrupt::RisingEdge => {
-                    (*self.registers[bank]).inttype_level.get().update(|val| val & !pin_mask);
-                    (*self.registers[bank]).int_polarity.get().update(|val| val | pin_mask);
-                }
-                GpioInterrupt::FallingEdge => {
-                    (*self.registers[bank]).inttype_level.get().update(|val| val & !pin_mask);
-                    (*self.registers[bank]).int_polarity.get().update(|val| val & !pin_mask);
-                }
-                GpioInterrupt::BothEdges => {
-                    // 双边沿触发需要特殊处理
-                    return Err(GpioError::InterruptNotSupported);
-                }
-                GpioInterrupt::HighLevel => {
-                    (*self.registers[bank]).inttype_level.get().update(|val| val | pin_mask);
-                    (*self.registers[bank]).int_polarity.get().update(|val| val | pin_mask);
-                }
-                GpioInterrupt::LowLevel => {
-                    (*self.registers[bank]).inttype_level.get().update(|val| val | pin_mask);
-                    (*self.registers[bank]).int_polarity.get().update(|val| val & !pin_mask);
-                }
+        let bank = pin.bank as usize;
+        let pin_mask = 1u32 << pin.pin;
+        
+        // 清除之前的中断配置
+        self.modify(bank, GpioRegister::Inten, |val| val & !pin_mask);
+        self.both_edges[bank].fetch_and(!pin_mask, Ordering::AcqRel);
+        
+        // 配置中断类型
+        match interrupt {
+            GpioInterrupt::RisingEdge => {
+                self.modify(bank, GpioRegister::InttypeLevel, |val| val & !pin_mask);
+                self.modify(bank, GpioRegister::IntPolarity, |val| val | pin_mask);
+            }
+            GpioInterrupt::FallingEdge => {
+                self.modify(bank, GpioRegister::InttypeLevel, |val| val & !pin_mask);
+                self.modify(bank, GpioRegister::IntPolarity, |val| val & !pin_mask);
+            }
+            GpioInterrupt::BothEdges => {
+                // 硬件只支持单边沿：按当前电平选择下一个边沿，每次触发后在中断分发中翻转极性
+                let high = self.read(bank, GpioRegister::ExtPort) & pin_mask != 0;
+                self.modify(bank, GpioRegister::InttypeLevel, |val| val & !pin_mask);
+                self.modify(bank, GpioRegister::IntPolarity, |val| if high { val & !pin_mask } else { val | pin_mask });
+                self.both_edges[bank].fetch_or(pin_mask, Ordering::AcqRel);
+            }
+            GpioInterrupt::HighLevel => {
+                self.modify(bank, GpioRegister::InttypeLevel, |val| val | pin_mask);
+                self.modify(bank, GpioRegister::IntPolarity, |val| val | pin_mask);
+            }
+            GpioInterrupt::LowLevel => {
+                self.modify(bank, GpioRegister::InttypeLevel, |val| val | pin_mask);
+                self.modify(bank, GpioRegister::IntPolarity, |val| val & !pin_mask);
             }
-            
-            // 使能中断
-            (*self.registers[bank]).inten.get().update(|val| val | pin_mask);
         }
         
+        // 使能中断
+        self.modify(bank, GpioRegister::Inten, |val| val | pin_mask);
+        
         Ok(())
     }
     
@@ -345,14 +429,34 @@ impl Rk3588Gpio {
             return Err(GpioError::InvalidPin);
         }
         
-        unsafe {
-            let bank = pin.bank as usize;
-            let pin_mask = 1u32 << pin.pin;
-            
-            // 写1清除中断
-            (*self.registers[bank]).port_eoi.get().write_volatile(pin_mask);
+        let bank = pin.bank as usize;
+        let pin_mask = 1u32 << pin.pin;
+        
+        // 写1清除中断
+        self.write(bank, GpioRegister::PortEoi, pin_mask);
+        
+        Ok(())
+    }
+    
+    /// 设置GPIO引脚去抖动
+    /// 
+    /// 去抖动电路使用慢速时钟（32.768kHz）采样，使能后输入需保持稳定若干个慢时钟周期才会被识别，
+    /// 适用于机械按键等低速输入
+    pub fn set_debounce(&self, pin: GpioPin, enabled: bool) -> Result<(), GpioError> {
+        if !self.initialized.load(Ordering::Acquire) {
+            return Err(GpioError::NotInitialized);
         }
         
+        if !pin.is_valid() {
+            return Err(GpioError::InvalidPin);
+        }
+        
+        let bank = pin.bank as usize;
+        let pin_mask = 1u32 << pin.pin;
+        
+        // 读-改-写，保留同组其他引脚的去抖动配置
+        self.modify(bank, GpioRegister::Debounce, |val| if enabled { val | pin_mask } else { val & !pin_mask });
+        
         Ok(())
     }
     
@@ -366,13 +470,53 @@ impl Rk3588Gpio {
             return Err(GpioError::InvalidPin);
         }
         
-        unsafe {
-            let bank = pin.bank as usize;
-            let pin_mask = 1u32 << pin.pin;
-            
-            let status = (*self.registers[bank]).intstatus.get().read_volatile() & pin_mask != 0;
-            Ok(status)
+        let bank = pin.bank as usize;
+        let pin_mask = 1u32 << pin.pin;
+        
+        let status = self.read(bank, GpioRegister::Intstatus) & pin_mask != 0;
+        Ok(status)
+    }
+    
+    /// GPIO组中断分发：按中断状态调用各引脚的回调，双边沿引脚翻转极性，最后清除中断
+    /// 
+    /// 返回被触发的引脚数
+    pub fn handle_bank_interrupt(&self, bank: GpioBank) -> usize {
+        let index = bank as usize;
+        let status = self.read(index, GpioRegister::Intstatus);
+        if status == 0 {
+            return 0;
+        }
+        
+        let both_edges = status & self.both_edges[index].load(Ordering::Acquire);
+        if both_edges != 0 {
+            self.modify(index, GpioRegister::IntPolarity, |val| val ^ both_edges);
+        }
+        
+        for pin in (0..32u8).filter(|pin| status & (1 << pin) != 0) {
+            // 回调在锁外执行，回调中可以注册或注销回调
+            let handler = GPIO_CALLBACKS.lock()[index][pin as usize];
+            if let Some(handler) = handler {
+                handler(GpioPin::new(bank, pin));
+            }
         }
+        
+        self.write(index, GpioRegister::PortEoi, status);
+        status.count_ones() as usize
+    }
+    
+    /// 读取指定GPIO组的寄存器
+    fn read(&self, bank: usize, register: GpioRegister) -> u32 {
+        self.banks[bank].read(&self.backend, register)
+    }
+    
+    /// 写入指定GPIO组的寄存器
+    fn write(&self, bank: usize, register: GpioRegister, value: u32) {
+        self.banks[bank].write(&self.backend, register, value);
+    }
+    
+    /// 读-改-写指定GPIO组的寄存器
+    fn modify<F: FnOnce(u32) -> u32>(&self, bank: usize, register: GpioRegister, f: F) {
+        self.banks[bank].modify(&self.backend, register, f);
     }
     
     unsafe fn init_bank(&self, bank: u8) -> Result<(), GpioError> {
@@ -383,13 +527,13 @@ impl Rk3588Gpio {
         let bank_idx = bank as usize;
         
         // 禁用所有中断
-        (*self.registers[bank_idx]).inten.get().write_volatile(0x0000_0000);
+        self.write(bank_idx, GpioRegister::Inten, 0x0000_0000);
         
         // 清除所有中断状态
-        (*self.registers[bank_idx]).port_eoi.get().write_volatile(0xFFFF_FFFF);
+        self.write(bank_idx, GpioRegister::PortEoi, 0xFFFF_FFFF);
         
         // 设置默认上拉/下拉为无
-        (*self.registers[bank_idx]).swport_ctl.get().write_volatile(0x0000_0000);
+        self.write(bank_idx, GpioRegister::SwportCtl, 0x0000_0000);
         
         Ok(())
     }
@@ -414,7 +558,7 @@ impl Rk3588Gpio {
         // 同时设置GPIO方向为输入（复用功能通常由外设控制）
         let pin_mask = 1u32 << pin.pin;
         let bank = pin.bank as usize;
-        (*self.registers[bank]).swport_ddr.get().update(|val| val & !pin_mask);
+        self.modify(bank, GpioRegister::SwportDdr, |val| val & !pin_mask);
         
         Ok(())
     }
@@ -423,7 +567,7 @@ impl Rk3588Gpio {
 /// 全局GPIO实例
 pub static mut GPIO: Option<Rk3588Gpio> = None;
 
-/// 初始化全局GPIO
+/// 初始化全局GPIO，并为各GPIO组注册中断处理函数
 pub fn init_gpio() {
     unsafe {
         GPIO = Some(Rk3588Gpio::new());
@@ -431,6 +575,29 @@ pub fn init_gpio() {
             let _ = gpio.init();
         }
     }
+    
+    for bank in GpioBank::ALL {
+        if !has_interrupt_handler(bank.irq()) {
+            let _ = request_irq(bank.irq(), gpio_interrupt_handler, PriorityBand::Background);
+        }
+    }
+}
+
+/// GPIO组中断处理函数，按中断号分派到对应的GPIO组
+fn gpio_interrupt_handler(interrupt_id: u32) {
+    if let Some(bank) = GpioBank::from_irq(interrupt_id) {
+        handle_gpio_bank_interrupt(bank);
+    }
+}
+
+/// GPIO组中断处理入口，由GPIO组的中断处理函数调用
+pub fn handle_gpio_bank_interrupt(bank: GpioBank) -> usize {
+    unsafe {
+        match &GPIO {
+            Some(gpio) => gpio.handle_bank_interrupt(bank),
+            None => 0,
+        }
+    }
 }
 
 /// 常用的GPIO引脚定义
@@ -543,4 +710,126 @@ impl GpioConfig {
             }
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::register_map::MockHardwareBackend;
+    
+    /// 数据寄存器位于0x10的紧凑布局
+    struct CompactGpioMap {
+        base: usize,
+    }
+    
+    impl RegisterMap<GpioRegister> for CompactGpioMap {
+        fn base(&self) -> usize {
+            self.base
+        }
+        
+        fn offset(&self, register: GpioRegister) -> usize {
+            match register {
+                GpioRegister::SwportDr => 0x10,
+                GpioRegister::SwportDdr => 0x14,
+                GpioRegister::ExtPort => 0x18,
+                _ => 0x1C,
+            }
+        }
+    }
+    
+    const MOCK_BASE: usize = 0x4000;
+    const BANK_STRIDE: usize = 0x100;
+    
+    fn bank_base(bank: usize) -> usize {
+        MOCK_BASE + bank * BANK_STRIDE
+    }
+    
+    #[test]
+    fn test_set_level_with_rk3588_map() {
+        // RK3588布局下，GPIO1引脚3的输出写入GPIO1数据寄存器（偏移0x00）
+        let banks = core::array::from_fn(|bank| Rk3588Map::new(bank_base(bank)));
+        let mut gpio = Rk3588Gpio::with_map(banks, MockHardwareBackend::new(MOCK_BASE, 5 * BANK_STRIDE));
+        gpio.init().unwrap();
+        
+        gpio.set_level(GpioPin::new(GpioBank::GPIO1, 3), true).unwrap();
+        assert_eq!(gpio.backend.read32(bank_base(1)), 1 << 3);
+        assert_eq!(gpio.backend.read32(bank_base(1) + 0x10), 0);
+    }
+    
+    #[test]
+    fn test_set_debounce_preserves_neighbor_bits() {
+        // 使能去抖动只修改目标引脚的位，相邻引脚的配置保持不变
+        let banks = core::array::from_fn(|bank| Rk3588Map::new(bank_base(bank)));
+        let mut gpio = Rk3588Gpio::with_map(banks, MockHardwareBackend::new(MOCK_BASE, 5 * BANK_STRIDE));
+        let pin = GpioPin::new(GpioBank::GPIO1, 9);
+        assert_eq!(gpio.set_debounce(pin, true), Err(GpioError::NotInitialized));
+        gpio.init().unwrap();
+        
+        let debounce = bank_base(1) + 0x38;
+        gpio.backend.write32(debounce, (1 << 8) | (1 << 10));
+        gpio.set_debounce(pin, true).unwrap();
+        assert_eq!(gpio.backend.read32(debounce), (1 << 8) | (1 << 9) | (1 << 10));
+        gpio.set_debounce(pin, false).unwrap();
+        assert_eq!(gpio.backend.read32(debounce), (1 << 8) | (1 << 10));
+        assert_eq!(gpio.set_debounce(GpioPin::new(GpioBank::GPIO1, 32), true), Err(GpioError::InvalidPin));
+    }
+    
+    static PIN2_FIRED: AtomicU32 = AtomicU32::new(0);
+    static PIN5_FIRED: AtomicU32 = AtomicU32::new(0);
+    
+    fn on_pin2(pin: GpioPin) {
+        assert_eq!(pin, GpioPin::new(GpioBank::GPIO3, 2));
+        PIN2_FIRED.fetch_add(1, Ordering::Relaxed);
+    }
+    
+    fn on_pin5(_pin: GpioPin) {
+        PIN5_FIRED.fetch_add(1, Ordering::Relaxed);
+    }
+    
+    #[test]
+    fn test_bank_interrupt_dispatches_each_pin_once() {
+        // 中断状态中置位的两个引脚各自的回调各触发一次，双边沿引脚翻转极性后清除中断
+        let banks = core::array::from_fn(|bank| Rk3588Map::new(bank_base(bank)));
+        let mut gpio = Rk3588Gpio::with_map(banks, MockHardwareBackend::new(MOCK_BASE, 5 * BANK_STRIDE));
+        gpio.init().unwrap();
+        
+        let pin2 = GpioPin::new(GpioBank::GPIO3, 2);
+        let pin5 = GpioPin::new(GpioBank::GPIO3, 5);
+        register_gpio_callback(pin2, on_pin2).unwrap();
+        register_gpio_callback(pin5, on_pin5).unwrap();
+        gpio.set_interrupt(pin2, GpioInterrupt::RisingEdge).unwrap();
+        gpio.set_interrupt(pin5, GpioInterrupt::BothEdges).unwrap();
+        assert_eq!(gpio.backend.read32(bank_base(3) + 0x2C), (1 << 2) | (1 << 5));
+        
+        gpio.backend.write32(bank_base(3) + 0x30, (1 << 2) | (1 << 5));
+        assert_eq!(gpio.handle_bank_interrupt(GpioBank::GPIO3), 2);
+        assert_eq!(PIN2_FIRED.load(Ordering::Relaxed), 1);
+        assert_eq!(PIN5_FIRED.load(Ordering::Relaxed), 1);
+        assert_eq!(gpio.backend.read32(bank_base(3) + 0x3C), (1 << 2) | (1 << 5));
+        // 双边沿引脚改为等待下降沿，单边沿引脚极性不变
+        assert_eq!(gpio.backend.read32(bank_base(3) + 0x2C), 1 << 2);
+    }
+    
+    #[test]
+    fn test_bank_irq_round_trip() {
+        // 各GPIO组的中断号连续，中断号可映射回对应的组
+        assert_eq!(GpioBank::GPIO0.irq(), 309);
+        assert_eq!(GpioBank::GPIO4.irq(), 313);
+        for bank in GpioBank::ALL {
+            assert_eq!(GpioBank::from_irq(bank.irq()), Some(bank));
+        }
+        assert_eq!(GpioBank::from_irq(308), None);
+    }
+    
+    #[test]
+    fn test_set_level_with_alternate_map() {
+        // 相同的控制器逻辑经紧凑布局写入偏移0x10
+        let banks = core::array::from_fn(|bank| CompactGpioMap { base: bank_base(bank) });
+        let mut gpio = Rk3588Gpio::with_map(banks, MockHardwareBackend::new(MOCK_BASE, 5 * BANK_STRIDE));
+        gpio.init().unwrap();
+        
+        gpio.set_level(GpioPin::new(GpioBank::GPIO1, 3), true).unwrap();
+        assert_eq!(gpio.backend.read32(bank_base(1) + 0x10), 1 << 3);
+        assert_eq!(gpio.backend.read32(bank_base(1)), 0);
+    }
+}
@@ -4,9 +4,19 @@
 
 #![no_std]
 
-use core::sync::atomic::{AtomicBool, Ordering};
+use core::future::Future;
+use core::pin::Pin;
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use core::task::{Context, Poll, Waker};
 use core::fmt;
-use core::cell::UnsafeCell;
+
+use starry_kernel::gic::{has_interrupt_handler, request_irq, PriorityBand};
+use starry_kernel::sync::IrqMutex;
+
+use crate::async_runtime;
+use crate::clock::{PeripheralClock, CLOCK_CONTROLLER};
+use crate::dma::{DmaChannel, DmaDescriptor, DmaDirection, DmaMode, DmaResult, DmaStatus, DmacChannel};
+use crate::register_map::{HardwareBackend, MmioBackend, RegisterMap, Rk3588Map};
 
 /// I2C错误类型
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -36,6 +46,107 @@ impl fmt::Display for I2cError {
     }
 }
 
+/// FIFO深度
+const I2C_FIFO_DEPTH: usize = 32;
+
+/// 中断位：RX FIFO达到阈值
+const INTR_RX_FULL: u32 = 1 << 2;
+/// 中断位：TX FIFO不高于阈值
+const INTR_TX_EMPTY: u32 = 1 << 4;
+/// 中断位：传输中止（NACK、仲裁失败等）
+const INTR_TX_ABRT: u32 = 1 << 6;
+
+/// 中断位：检测到停止条件
+const INTR_STOP_DET: u32 = 1 << 9;
+
+/// I2C0中断号（GIC SPI 317）
+pub const I2C0_IRQ: u32 = 349;
+/// I2C1中断号（GIC SPI 318）
+pub const I2C1_IRQ: u32 = 350;
+
+/// 中止源：仲裁失败
+const ABRT_ARB_LOST: u32 = 1 << 12;
+
+/// 总线扫描的首个非保留7位地址（0x00-0x07为保留地址）
+pub const SCAN_FIRST_ADDRESS: u16 = 0x08;
+/// 总线扫描的最后一个非保留7位地址（0x78-0x7F为保留地址）
+pub const SCAN_LAST_ADDRESS: u16 = 0x77;
+
+/// 是否为I2C规范保留的7位地址
+pub const fn is_reserved_address(address: u16) -> bool {
+    address < SCAN_FIRST_ADDRESS || (address > SCAN_LAST_ADDRESS && address <= 0x7F)
+}
+
+/// 数据命令寄存器：读命令位
+const DATA_CMD_READ: u32 = 1 << 8;
+/// 数据命令寄存器：本字节后发送停止条件
+const DATA_CMD_STOP: u32 = 1 << 9;
+/// 数据命令寄存器：本字节前发送重复起始条件
+const DATA_CMD_RESTART: u32 = 1 << 10;
+
+/// 控制寄存器：主机模式使用10位地址
+const CON_10BITADDR_MASTER: u32 = 1 << 4;
+/// 控制寄存器：允许重复起始条件（10位地址读取和组合传输必需）
+const CON_RESTART_EN: u32 = 1 << 5;
+
+/// 目标地址寄存器：以10位地址帧寻址
+const TAR_10BITADDR_MASTER: u32 = 1 << 12;
+
+/// 使能状态寄存器：控制器仍处于使能状态
+const ENABLE_STATUS_IC_EN: u32 = 1 << 0;
+
+/// 7位地址的最大值
+const MAX_7BIT_ADDRESS: u16 = 0x7F;
+/// 10位地址的最大值
+const MAX_10BIT_ADDRESS: u16 = 0x3FF;
+
+/// DMA控制寄存器：接收DMA使能
+const DMA_CR_RDMAE: u32 = 1 << 0;
+
+/// 低于该长度的读取使用PIO，DMA的配置开销得不偿失
+pub const I2C_DMA_THRESHOLD: usize = 8;
+
+/// 等待RX DMA完成的最大轮询次数
+const I2C_DMA_TIMEOUT: u32 = 1_000_000;
+
+/// I2C的DMAC接收通道，外设端固定为DATA_CMD寄存器，由RX FIFO的DMA请求驱动
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct I2cDmaChannel {
+    /// 接收通道
+    pub channel: DmacChannel,
+    /// RX FIFO的DMAC外设请求号
+    pub rx_request: u8,
+}
+
+/// 读取传输方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadPath {
+    /// 逐字节经FIFO读取
+    Pio,
+    /// DMA接收，`watermark`为写入DMA_RDLR的请求水位（RX FIFO数据数减1）
+    Dma { watermark: u32 },
+}
+
+impl ReadPath {
+    /// 根据读取长度选择传输方式
+    pub fn for_length(len: usize) -> Self {
+        if len < I2C_DMA_THRESHOLD {
+            ReadPath::Pio
+        } else {
+            ReadPath::Dma { watermark: (len.min(I2C_FIFO_DEPTH / 2) - 1) as u32 }
+        }
+    }
+}
+
+/// 中止源对应的错误
+fn abort_error(source: u32) -> I2cError {
+    if source & ABRT_ARB_LOST != 0 {
+        I2cError::ArbitrationLost
+    } else {
+        I2cError::NackReceived
+    }
+}
+
 /// I2C配置参数
 #[derive(Debug, Clone, Copy)]
 pub struct I2cConfig {
@@ -68,60 +179,92 @@ pub enum I2cDirection {
     Read,
 }
 
-/// RK3588 I2C寄存器定义
-#[repr(C)]
-struct I2cRegisters {
-    con: UnsafeCell<u32>,      // 控制寄存器
-    tar: UnsafeCell<u32>,      // 目标地址寄存器
-    sar: UnsafeCell<u32>,      // 从机地址寄存器
-    _reserved1: [u32; 1],
-    data_cmd: UnsafeCell<u32>, // 数据命令寄存器
-    ss_scl_hcnt: UnsafeCell<u32>, // 标准模式SCL高电平计数
-    ss_scl_lcnt: UnsafeCell<u32>, // 标准模式SCL低电平计数
-    fs_scl_hcnt: UnsafeCell<u32>, // 快速模式SCL高电平计数
-    fs_scl_lcnt: UnsafeCell<u32>, // 快速模式SCL低电平计数
-    _reserved2: [u32; 2],
-    intr_stat: UnsafeCell<u32>, // 中断状态寄存器
-    intr_mask: UnsafeCell<u32>, // 中断屏蔽寄存器
-    raw_intr_stat: UnsafeCell<u32>, // 原始中断状态
-    rx_tl: UnsafeCell<u32>,    // RX FIFO阈值
-    tx_tl: UnsafeCell<u32>,     // TX FIFO阈值
-    clr_intr: UnsafeCell<u32>, // 清除中断
-    clr_rx_under: UnsafeCell<u32>, // 清除RX下溢
-    clr_rx_over: UnsafeCell<u32>,  // 清除RX溢出
-    clr_tx_over: UnsafeCell<u32>,  // 清除TX溢出
-    clr_rd_req: UnsafeCell<u32>,   // 清除读请求
-    clr_tx_abrt: UnsafeCell<u32>,  // 清除TX中止
-    clr_det_act: UnsafeCell<u32>,  // 清除检测活动
-    clr_activity: UnsafeCell<u32>,  // 清除活动
-    clr_stop_det: UnsafeCell<u32>, // 清除停止检测
-    clr_start_det: UnsafeCell<u32>, // 清除开始检测
-    clr_gen_call: UnsafeCell<u32>,  // 清除通用调用
-    enable: UnsafeCell<u32>,    // 使能寄存器
-    status: UnsafeCell<u32>,    // 状态寄存器
-    txflr: UnsafeCell<u32>,     // TX FIFO级别
-    rxflr: UnsafeCell<u32>,     // RX FIFO级别
-    sda_hold: UnsafeCell<u32>,  // SDA保持时间
-    tx_abrt_source: UnsafeCell<u32>, // TX中止源
-    slv_data_nack_only: UnsafeCell<u32>, // 从机数据NACK
-    dma_cr: UnsafeCell<u32>,    // DMA控制寄存器
-    dma_tdlr: UnsafeCell<u32>,  // DMA TX数据级别
-    dma_rdlr: UnsafeCell<u32>,  // DMA RX数据级别
-    sda_setup: UnsafeCell<u32>, // SDA建立时间
-    ack_general_call: UnsafeCell<u32>, // ACK通用调用
-    enable_status: UnsafeCell<u32>, // 使能状态
-    fs_spklen: UnsafeCell<u32>, // 快速模式尖峰长度
-    _reserved3: [u32; 19],
-    comp_param_1: UnsafeCell<u32>, // 组件参数1
-    comp_version: UnsafeCell<u32>,  // 组件版本
-    comp_type: UnsafeCell<u32>,      // 组件类型
+/// I2C寄存器
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum I2cRegister {
+    Con,            // 控制寄存器
+    Tar,            // 目标地址寄存器
+    Sar,            // 从机地址寄存器
+    DataCmd,        // 数据命令寄存器
+    SsSclHcnt,      // 标准模式SCL高电平计数
+    SsSclLcnt,      // 标准模式SCL低电平计数
+    FsSclHcnt,      // 快速模式SCL高电平计数
+    FsSclLcnt,      // 快速模式SCL低电平计数
+    IntrStat,       // 中断状态寄存器
+    IntrMask,       // 中断屏蔽寄存器
+    RawIntrStat,    // 原始中断状态
+    RxTl,           // RX FIFO阈值
+    TxTl,           // TX FIFO阈值
+    ClrIntr,        // 清除中断
+    ClrTxAbrt,      // 清除TX中止
+    ClrStopDet,     // 清除停止检测
+    ClrStartDet,    // 清除开始检测
+    Enable,         // 使能寄存器
+    Status,         // 状态寄存器
+    Txflr,          // TX FIFO级别
+    Rxflr,          // RX FIFO级别
+    SdaHold,        // SDA保持时间
+    TxAbrtSource,   // TX中止源
+    DmaCr,          // DMA控制寄存器
+    DmaTdlr,        // DMA TX数据级别
+    DmaRdlr,        // DMA RX数据级别
+    EnableStatus,   // 使能状态
+}
+
+/// RK3588 I2C寄存器布局
+impl RegisterMap<I2cRegister> for Rk3588Map {
+    fn base(&self) -> usize {
+        self.base_address()
+    }
+    
+    fn offset(&self, register: I2cRegister) -> usize {
+        match register {
+            I2cRegister::Con => 0x00,
+            I2cRegister::Tar => 0x04,
+            I2cRegister::Sar => 0x08,
+            I2cRegister::DataCmd => 0x10,
+            I2cRegister::SsSclHcnt => 0x14,
+            I2cRegister::SsSclLcnt => 0x18,
+            I2cRegister::FsSclHcnt => 0x1C,
+            I2cRegister::FsSclLcnt => 0x20,
+            I2cRegister::IntrStat => 0x2C,
+            I2cRegister::IntrMask => 0x30,
+            I2cRegister::RawIntrStat => 0x34,
+            I2cRegister::RxTl => 0x38,
+            I2cRegister::TxTl => 0x3C,
+            I2cRegister::ClrIntr => 0x40,
+            I2cRegister::ClrTxAbrt => 0x54,
+            I2cRegister::ClrStopDet => 0x60,
+            I2cRegister::ClrStartDet => 0x64,
+            I2cRegister::Enable => 0x6C,
+            I2cRegister::Status => 0x70,
+            I2cRegister::Txflr => 0x74,
+            I2cRegister::Rxflr => 0x78,
+            I2cRegister::SdaHold => 0x7C,
+            I2cRegister::TxAbrtSource => 0x80,
+            I2cRegister::DmaCr => 0x88,
+            I2cRegister::DmaTdlr => 0x8C,
+            I2cRegister::DmaRdlr => 0x90,
+            I2cRegister::EnableStatus => 0x9C,
+        }
+    }
 }
 
-/// RK3588 I2C控制器
-pub struct Rk3588I2c {
-    registers: *mut I2cRegisters,
+/// I2C控制器
+/// 
+/// 控制器逻辑通过寄存器映射`M`访问寄存器，默认使用RK3588布局和MMIO后端
+pub struct Rk3588I2c<M = Rk3588Map, B = MmioBackend> {
+    map: M,
+    backend: B,
     config: I2cConfig,
     initialized: AtomicBool,
+    irq_enabled: AtomicBool,
+    /// 中断服务程序记录、尚未被传输任务处理的中断状态
+    irq_status: AtomicU32,
+    /// 等待中断的传输任务，中断服务程序与任务共享，持锁期间屏蔽本核IRQ
+    irq_waker: IrqMutex<Option<Waker>>,
+    /// DMA接收通道，未配置时DMA读取回退为PIO
+    dma: Option<I2cDmaChannel>,
 }
 
 impl Rk3588I2c {
@@ -136,9 +279,30 @@ impl Rk3588I2c {
     /// 创建新的I2C实例
     pub const fn new(base_address: usize, config: I2cConfig) -> Self {
         Self {
-            registers: base_address as *mut I2cRegisters,
+            map: Rk3588Map::new(base_address),
+            backend: unsafe { MmioBackend::new() },
+            config,
+            initialized: AtomicBool::new(false),
+            irq_enabled: AtomicBool::new(false),
+            irq_status: AtomicU32::new(0),
+            irq_waker: IrqMutex::new(None),
+            dma: None,
+        }
+    }
+}
+
+impl<M: RegisterMap<I2cRegister>, B: HardwareBackend> Rk3588I2c<M, B> {
+    /// 使用指定的寄存器映射和访问后端创建I2C实例
+    pub fn with_map(map: M, backend: B, config: I2cConfig) -> Self {
+        Self {
+            map,
+            backend,
             config,
             initialized: AtomicBool::new(false),
+            irq_enabled: AtomicBool::new(false),
+            irq_status: AtomicU32::new(0),
+            irq_waker: IrqMutex::new(None),
+            dma: None,
         }
     }
     
@@ -221,13 +385,8 @@ impl Rk3588I2c {
             // 发送开始条件
             self.send_start()?;
             
-            // 发送读命令
-            self.send_read_command()?;
-            
-            // 读取数据
-            for byte in buffer.iter_mut() {
-                *byte = self.read_byte()?;
-            }
+            // 逐字节下发读命令并读取数据
+            self.read_bytes(buffer, false)?;
             
             // 发送停止条件
             self.send_stop()?;
@@ -236,6 +395,180 @@ impl Rk3588I2c {
         Ok(())
     }
     
+    /// 配置DMA接收通道
+    pub fn set_dma_channel(&mut self, dma: I2cDmaChannel) {
+        self.dma = Some(dma);
+    }
+    
+    /// DMA批量读取，长度低于`I2C_DMA_THRESHOLD`或未配置DMA通道时回退为PIO读取
+    pub fn read_dma(&self, address: u16, buffer: &mut [u8]) -> Result<(), I2cError> {
+        match self.dma {
+            Some(dma) => self.read_dma_with(address, buffer, &dma.channel, dma.rx_request),
+            None => self.read(address, buffer),
+        }
+    }
+    
+    /// 使用指定DMA通道批量读取
+    /// 
+    /// 接收通道先于读命令启动，RX FIFO随收随被DMA取走，读取长度不受FIFO深度限制；
+    /// 传输中途出现仲裁丢失或NACK时停止通道并返回对应错误，通道超时未完成时返回`Timeout`
+    pub fn read_dma_with<C: DmaChannel>(&self, address: u16, buffer: &mut [u8], channel: &C, rx_request: u8) -> Result<(), I2cError> {
+        let watermark = match ReadPath::for_length(buffer.len()) {
+            ReadPath::Pio => return self.read(address, buffer),
+            ReadPath::Dma { watermark } => watermark,
+        };
+        
+        if !self.initialized.load(Ordering::Acquire) {
+            return Err(I2cError::NotInitialized);
+        }
+        
+        if !self.validate_address(address) {
+            return Err(I2cError::InvalidAddress);
+        }
+        
+        unsafe {
+            self.wait_for_bus_idle()?;
+            self.set_target_address(address)?;
+        }
+        
+        let total = buffer.len();
+        let fifo = (self.map.base() + self.map.offset(I2cRegister::DataCmd)) as u64;
+        let mut descriptor = DmaDescriptor::new();
+        descriptor.configure(fifo, buffer.as_mut_ptr() as u64, total as u32, DmaDirection::DeviceToMemory, DmaMode::Single);
+        descriptor.set_peripheral_request(rx_request);
+        
+        // 配置接收水位并使能RX DMA，在下发读命令之前启动接收通道
+        self.map.write(&self.backend, I2cRegister::DmaRdlr, watermark);
+        self.map.modify(&self.backend, I2cRegister::DmaCr, |cr| cr | DMA_CR_RDMAE);
+        if channel.start(&descriptor).is_err() {
+            self.stop_rx_dma();
+            return Err(I2cError::HardwareError);
+        }
+        
+        let outcome = self.issue_reads(total).and_then(|_| Self::wait_rx_dma(channel));
+        if outcome.is_err() {
+            channel.stop();
+        }
+        self.stop_rx_dma();
+        let result = outcome?;
+        self.check_abort()?;
+        if result.status != DmaStatus::Complete {
+            return Err(I2cError::HardwareError);
+        }
+        if result.bytes_transferred < total {
+            return Err(I2cError::Timeout);
+        }
+        
+        Ok(())
+    }
+    
+    /// 下发`total`个读命令，最后一个字节后发送停止条件
+    fn issue_reads(&self, total: usize) -> Result<(), I2cError> {
+        for i in 0..total {
+            self.wait_tx_space()?;
+            self.check_abort()?;
+            let stop = if i + 1 == total { DATA_CMD_STOP } else { 0 };
+            self.map.write(&self.backend, I2cRegister::DataCmd, DATA_CMD_READ | stop);
+        }
+        Ok(())
+    }
+    
+    /// 等待接收通道结束，超过`I2C_DMA_TIMEOUT`次轮询仍未结束时返回`Timeout`
+    fn wait_rx_dma<C: DmaChannel>(channel: &C) -> Result<DmaResult, I2cError> {
+        let mut timeout = I2C_DMA_TIMEOUT;
+        while channel.is_busy() {
+            if timeout == 0 {
+                return Err(I2cError::Timeout);
+            }
+            timeout -= 1;
+            core::hint::spin_loop();
+        }
+        
+        channel.take_completion();
+        Ok(channel.result())
+    }
+    
+    /// 关闭RX DMA
+    fn stop_rx_dma(&self) {
+        self.map.modify(&self.backend, I2cRegister::DmaCr, |cr| cr & !DMA_CR_RDMAE);
+    }
+    
+    /// 检查传输中止，已中止时清除中止状态并返回对应错误
+    fn check_abort(&self) -> Result<(), I2cError> {
+        let status = self.map.read(&self.backend, I2cRegister::RawIntrStat);
+        if status & INTR_TX_ABRT == 0 {
+            return Ok(());
+        }
+        let source = self.map.read(&self.backend, I2cRegister::TxAbrtSource);
+        self.map.write(&self.backend, I2cRegister::ClrTxAbrt, 0x1);
+        Err(abort_error(source))
+    }
+    
+    /// 等待TX FIFO有空间，在配置的超时内未就绪时返回超时
+    fn wait_tx_space(&self) -> Result<(), I2cError> {
+        let mut timeout = self.config.timeout_ms * 1000;
+        while self.map.read(&self.backend, I2cRegister::Txflr) as usize >= I2C_FIFO_DEPTH {
+            if timeout == 0 {
+                return Err(I2cError::Timeout);
+            }
+            timeout -= 1;
+        }
+        Ok(())
+    }
+    
+    /// 扫描总线，返回应答的7位设备地址
+    /// 
+    /// 对每个非保留地址发送起始条件和地址后立即停止，未收到中止（NACK）即视为应答；
+    /// 每个地址的等待不超过配置的超时，超时的地址视为无设备
+    pub fn scan(&self) -> Result<heapless::Vec<u16, 128>, I2cError> {
+        if !self.initialized.load(Ordering::Acquire) {
+            return Err(I2cError::NotInitialized);
+        }
+        
+        let mut found = heapless::Vec::new();
+        for address in SCAN_FIRST_ADDRESS..=SCAN_LAST_ADDRESS {
+            if self.probe(address)? {
+                let _ = found.push(address);
+            }
+        }
+        Ok(found)
+    }
+    
+    /// 扫描总线并输出应答的设备地址
+    pub fn scan_print(&self) -> Result<usize, I2cError> {
+        let found = self.scan()?;
+        starry_kernel::println!("I2C总线扫描: 发现{}个设备", found.len());
+        for address in &found {
+            starry_kernel::println!("  0x{:02X}", address);
+        }
+        Ok(found.len())
+    }
+    
+    /// 探测单个7位地址是否应答
+    fn probe(&self, address: u16) -> Result<bool, I2cError> {
+        unsafe { self.wait_for_bus_idle()? };
+        
+        // 控制器不支持零长度帧：仅下发带停止位的空写命令，地址阶段未应答时产生中止
+        unsafe { self.set_target_address(address)? };
+        self.map.write(&self.backend, I2cRegister::DataCmd, DATA_CMD_STOP);
+        
+        let mut timeout = self.config.timeout_ms * 1000;
+        while timeout > 0 {
+            let status = self.map.read(&self.backend, I2cRegister::RawIntrStat);
+            if status & INTR_TX_ABRT != 0 {
+                self.map.write(&self.backend, I2cRegister::ClrTxAbrt, 0x1);
+                self.map.write(&self.backend, I2cRegister::ClrStopDet, 0x1);
+                return Ok(false);
+            }
+            if status & INTR_STOP_DET != 0 {
+                self.map.write(&self.backend, I2cRegister::ClrStopDet, 0x1);
+                return Ok(true);
+            }
+            timeout -= 1;
+        }
+        Ok(false)
+    }
+    
     /// 写入后读取（组合传输）
     pub fn write_then_read(&self, address: u16, write_data: &[u8], read_buffer: &mut [u8]) -> Result<(), I2cError> {
         if !self.initialized.load(Ordering::Acquire) {
@@ -261,34 +594,147 @@ impl Rk3588I2c {
                 self.write_byte(byte)?;
             }
             
-            // 发送重复开始条件
-            self.send_restart()?;
+            // 方向切换：首个读命令携带重复起始条件，10位地址时控制器在此重发地址高字节
+            self.read_bytes(read_buffer, true)?;
             
-            // 发送读命令
-            self.send_read_command()?;
+            // 发送停止条件
+            self.send_stop()?;
+        }
+        
+        Ok(())
+    }
+    
+    /// 启用中断驱动传输
+    /// 
+    /// 中断在传输等待时按需解除屏蔽，`init_i2c`注册的中断处理函数调用`handle_interrupt`
+    pub fn enable_interrupts(&self) {
+        self.map.write(&self.backend, I2cRegister::IntrMask, 0);
+        self.irq_status.store(0, Ordering::Release);
+        self.irq_enabled.store(true, Ordering::Release);
+    }
+    
+    /// 是否已启用中断驱动传输
+    pub fn interrupts_enabled(&self) -> bool {
+        self.irq_enabled.load(Ordering::Acquire)
+    }
+    
+    /// I2C中断服务程序：记录中断状态并唤醒等待的传输任务
+    /// 
+    /// 返回是否有待处理的中断
+    pub fn handle_interrupt(&self) -> bool {
+        let status = self.map.read(&self.backend, I2cRegister::IntrStat);
+        if status == 0 {
+            return false;
+        }
+        
+        self.irq_status.fetch_or(status, Ordering::AcqRel);
+        // 屏蔽已发生的中断，避免电平中断在任务处理FIFO前重复触发
+        self.map.modify(&self.backend, I2cRegister::IntrMask, |mask| mask & !status);
+        
+        let waker = self.irq_waker.lock().take();
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+        true
+    }
+    
+    /// 异步写入：启用中断且异步运行时已初始化时使用中断驱动传输，否则回退为轮询
+    pub async fn write_async(&self, address: u16, data: &[u8]) -> Result<(), I2cError> {
+        if self.interrupts_enabled() && async_runtime::is_active() {
+            self.write_irq(address, data).await
+        } else {
+            self.write(address, data)
+        }
+    }
+    
+    /// 异步读取：启用中断且异步运行时已初始化时使用中断驱动传输，否则回退为轮询
+    pub async fn read_async(&self, address: u16, buffer: &mut [u8]) -> Result<(), I2cError> {
+        if self.interrupts_enabled() && async_runtime::is_active() {
+            self.read_irq(address, buffer).await
+        } else {
+            self.read(address, buffer)
+        }
+    }
+    
+    /// 中断驱动写入：每次填满TX FIFO后等待TX_EMPTY中断，末块写入后等待STOP_DET
+    /// 
+    /// TX_EMPTY只表示数据已移出FIFO，末字节的NACK在其后才上报，
+    /// 因此以停止条件作为完成标志，期间的TX_ABRT作为错误返回
+    pub async fn write_irq(&self, address: u16, data: &[u8]) -> Result<(), I2cError> {
+        self.begin_irq_transfer(address)?;
+        self.map.write(&self.backend, I2cRegister::TxTl, 0); // TX FIFO空时触发
+        
+        let total = data.len();
+        for (chunk_index, chunk) in data.chunks(I2C_FIFO_DEPTH).enumerate() {
+            let mut last = false;
+            for (i, &byte) in chunk.iter().enumerate() {
+                last = chunk_index * I2C_FIFO_DEPTH + i + 1 == total;
+                let stop = if last { DATA_CMD_STOP } else { 0 };
+                self.map.write(&self.backend, I2cRegister::DataCmd, byte as u32 | stop);
+            }
             
-            // 读取数据
-            for byte in read_buffer.iter_mut() {
-                *byte = self.read_byte()?;
+            if last {
+                self.wait_interrupt(INTR_STOP_DET).await?;
+                self.map.write(&self.backend, I2cRegister::ClrStopDet, 0x1);
+            } else {
+                self.wait_interrupt(INTR_TX_EMPTY).await?;
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// 中断驱动读取：每次下发不超过FIFO深度的读命令，RX FIFO达到阈值后一次取出
+    pub async fn read_irq(&self, address: u16, buffer: &mut [u8]) -> Result<(), I2cError> {
+        self.begin_irq_transfer(address)?;
+        
+        let total = buffer.len();
+        let mut received = 0;
+        while received < total {
+            let chunk = (total - received).min(I2C_FIFO_DEPTH);
+            self.map.write(&self.backend, I2cRegister::RxTl, (chunk - 1) as u32);
+            for i in 0..chunk {
+                let stop = if received + i + 1 == total { DATA_CMD_STOP } else { 0 };
+                self.map.write(&self.backend, I2cRegister::DataCmd, DATA_CMD_READ | stop);
             }
             
-            // 发送停止条件
-            self.send_stop()?;
+            self.wait_interrupt(INTR_RX_FULL).await?;
+            for byte in buffer[received..received + chunk].iter_mut() {
+                *byte = self.map.read(&self.backend, I2cRegister::DataCmd) as u8;
+            }
+            received += chunk;
         }
         
         Ok(())
     }
     
+    /// 中断驱动传输的公共前置检查和地址设置
+    fn begin_irq_transfer(&self, address: u16) -> Result<(), I2cError> {
+        if !self.initialized.load(Ordering::Acquire) {
+            return Err(I2cError::NotInitialized);
+        }
+        
+        if !self.validate_address(address) {
+            return Err(I2cError::InvalidAddress);
+        }
+        
+        self.irq_status.store(0, Ordering::Release);
+        unsafe { self.set_target_address(address) }
+    }
+    
+    /// 等待`events`中的任一中断，传输中止时返回对应错误
+    fn wait_interrupt(&self, events: u32) -> InterruptWait<'_, M, B> {
+        InterruptWait { i2c: self, events }
+    }
+    
     /// 检查总线是否繁忙
     pub fn is_bus_busy(&self) -> Result<bool, I2cError> {
         if !self.initialized.load(Ordering::Acquire) {
             return Err(I2cError::NotInitialized);
         }
         
-        unsafe {
-            let status = (*self.registers).status.get().read_volatile();
-            Ok((status & (1 << 5)) != 0) // BUSY位
-        }
+        let status = self.map.read(&self.backend, I2cRegister::Status);
+        Ok((status & (1 << 5)) != 0) // BUSY位
     }
     
     /// 检查传输是否完成
@@ -297,54 +743,45 @@ impl Rk3588I2c {
             return Err(I2cError::NotInitialized);
         }
         
-        unsafe {
-            let status = (*self.registers).raw_intr_stat.get().read_volatile();
-            Ok((status & (1 << 6)) != 0) // TX_EMPTY位
-        }
+        let status = self.map.read(&self.backend, I2cRegister::RawIntrStat);
+        Ok((status & (1 << 6)) != 0) // TX_EMPTY位
     }
     
     unsafe fn disable(&self) {
-        (*self.registers).enable.get().write_volatile(0x0);
+        self.map.write(&self.backend, I2cRegister::Enable, 0x0);
+    }
+    
+    /// 禁用控制器并等待使能状态寄存器确认，当前传输结束后控制器才真正停止
+    unsafe fn disable_and_wait(&self) -> Result<(), I2cError> {
+        self.disable();
+        let mut timeout = self.config.timeout_ms * 1000;
+        while self.map.read(&self.backend, I2cRegister::EnableStatus) & ENABLE_STATUS_IC_EN != 0 {
+            if timeout == 0 {
+                return Err(I2cError::Timeout);
+            }
+            timeout -= 1;
+        }
+        Ok(())
     }
     
     unsafe fn enable(&self) {
-        (*self.registers).enable.get().write_volatile(0x1);
+        self.map.write(&self.backend, I2cRegister::Enable, 0x1);
     }
     
     unsafe fn configure_clock(&self) -> Result<(), I2cError> {
-        let ic_clk = 200_000_000; // I2C控制器时钟频率 (200MHz)
-        let target_speed = self.config.clock_speed;
-        
-        if target_speed > 400_000 {
-            return Err(I2cError::HardwareError); // 不支持高速模式
-        }
-        
-        // 计算SCL高低电平计数
-        let scl_hcnt: u32;
-        let scl_lcnt: u32;
+        let ic_clk = CLOCK_CONTROLLER.get_clock(PeripheralClock::I2c); // I2C控制器时钟频率
+        let (scl_hcnt, scl_lcnt) = compute_scl_counts(ic_clk, self.config.clock_speed)?;
         
-        if target_speed <= 100_000 {
-            // 标准模式
-            let period = ic_clk / target_speed;
-            scl_hcnt = (period * 3) / 7; // 高电平时间占3/7周期
-            scl_lcnt = (period * 4) / 7; // 低电平时间占4/7周期
-        } else {
-            // 快速模式
-            let period = ic_clk / target_speed;
-            scl_hcnt = (period * 1) / 3; // 高电平时间占1/3周期
-            scl_lcnt = (period * 2) / 3; // 低电平时间占2/3周期
-        }
-        
-        (*self.registers).ss_scl_hcnt.get().write_volatile(scl_hcnt);
-        (*self.registers).ss_scl_lcnt.get().write_volatile(scl_lcnt);
+        self.map.write(&self.backend, I2cRegister::SsSclHcnt, scl_hcnt);
+        self.map.write(&self.backend, I2cRegister::SsSclLcnt, scl_lcnt);
         
         Ok(())
     }
     
     unsafe fn configure_fifo(&self) {
         // 设置FIFO阈值
-        (*self.registers).tx_tl.get().write_volatile(0); // TX FIFO空时触发
-        (*self.registers).rx_tl.get().write_volatile(0); // RX FIFO有1字节时触发
+        self.map.write(&self.backend, I2cRegister::TxTl, 0); // TX FIFO空时触发
+        self.map.write(&self.backend, I2cRegister::RxTl, 0); // RX FIFO有1字节时触发
     }
     
     unsafe fn configure_sda_hold(&self) {
@@ -353,7 +790,7 @@ impl Rk3588I2c {
         let ic_clk_period = 5; // 5ns (200MHz)
         let hold_cycles = hold_time / ic_clk_period;
         
-        (*self.registers).sda_hold.get().write_volatile(hold_cycles);
+        self.map.write(&self.backend, I2cRegister::SdaHold, hold_cycles);
     }
     
     unsafe fn wait_for_bus_idle(&self) -> Result<(), I2cError> {
@@ -369,25 +806,34 @@ impl Rk3588I2c {
         Err(I2cError::BusBusy)
     }
     
-    unsafe fn set_target_address(&self, address: u16) -> Result<(), I2cError> {
-        let mut tar_value = 0u32;
-        
+    /// 目标地址寄存器的值
+    /// 
+    /// 10位模式下不超过0x7F的地址按7位设备寻址，以便同一控制器访问7位设备；
+    /// 更大的地址设置`IC_10BITADDR_MASTER`位，以两字节地址帧寻址
+    pub fn tar_value(&self, address: u16) -> Result<u32, I2cError> {
         match self.config.addressing_mode {
-            AddressingMode::SevenBit => {
-                if address > 0x7F {
-                    return Err(I2cError::InvalidAddress);
-                }
-                tar_value = address as u32;
-            }
-            AddressingMode::TenBit => {
-                if address > 0x3FF {
-                    return Err(I2cError::InvalidAddress);
-                }
-                tar_value = (address as u32) | (1 << 12); // 设置10位地址模式
-            }
+            AddressingMode::SevenBit if address <= MAX_7BIT_ADDRESS => Ok(address as u32),
+            AddressingMode::TenBit if address <= MAX_7BIT_ADDRESS => Ok(address as u32),
+            AddressingMode::TenBit if address <= MAX_10BIT_ADDRESS => Ok(address as u32 | TAR_10BITADDR_MASTER),
+            _ => Err(I2cError::InvalidAddress),
         }
+    }
+    
+    unsafe fn set_target_address(&self, address: u16) -> Result<(), I2cError> {
+        let tar_value = self.tar_value(address)?;
+        let ten_bit = tar_value & TAR_10BITADDR_MASTER != 0;
         
-        (*self.registers).tar.get().write_volatile(tar_value);
+        // CON和TAR只能在控制器禁用时写入
+        if let Err(error) = self.disable_and_wait() {
+            self.enable();
+            return Err(error);
+        }
+        self.map.modify(&self.backend, I2cRegister::Con, |con| {
+            let con = con | CON_RESTART_EN;
+            if ten_bit { con | CON_10BITADDR_MASTER } else { con & !CON_10BITADDR_MASTER }
+        });
+        self.map.write(&self.backend, I2cRegister::Tar, tar_value);
+        self.enable();
         Ok(())
     }
     
@@ -397,9 +843,9 @@ impl Rk3588I2c {
         let mut timeout = self.config.timeout_ms * 1000;
         
         while timeout > 0 {
-            let status = (*self.registers).raw_intr_stat.get().read_volatile();
+            let status = self.map.read(&self.backend, I2cRegister::RawIntrStat);
             if (status & (1 << 10)) != 0 { // START_DET位
-                (*self.registers).clr_start_det.get().write_volatile(0x1);
+                self.map.write(&self.backend, I2cRegister::ClrStartDet, 0x1);
                 return Ok(());
             }
             timeout -= 1;
@@ -408,21 +854,15 @@ impl Rk3588I2c {
         Err(I2cError::Timeout)
     }
     
-    unsafe fn send_restart(&self) -> Result<(), I2cError> {
-        // 重复开始条件由硬件自动处理
-        // 实现与send_start相同
-        self.send_start()
-    }
-    
     unsafe fn send_stop(&self) -> Result<(), I2cError> {
         // 停止条件由硬件自动处理
         // 等待停止条件完成
         let mut timeout = self.config.timeout_ms * 1000;
         
         while timeout > 0 {
-            let status = (*self.registers).raw_intr_stat.get().read_volatile();
+            let status = self.map.read(&self.backend, I2cRegister::RawIntrStat);
             if (status & (1 << 9)) != 0 { // STOP_DET位
-                (*self.registers).clr_stop_det.get().write_volatile(0x1);
+                self.map.write(&self.backend, I2cRegister::ClrStopDet, 0x1);
                 return Ok(());
             }
             timeout -= 1;
@@ -436,7 +876,7 @@ impl Rk3588I2c {
         let mut timeout = self.config.timeout_ms * 1000;
         
         while timeout > 0 {
-            let txflr = (*self.registers).txflr.get().read_volatile();
+            let txflr = self.map.read(&self.backend, I2cRegister::Txflr);
             if txflr < 32 { // TX FIFO深度为32
                 break;
             }
@@ -448,15 +888,15 @@ impl Rk3588I2c {
         }
         
         // 写入数据
-        (*self.registers).data_cmd.get().write_volatile(byte as u32);
+        self.map.write(&self.backend, I2cRegister::DataCmd, byte as u32);
         
         // 检查ACK/NACK
         timeout = self.config.timeout_ms * 1000;
         
         while timeout > 0 {
-            let status = (*self.registers).raw_intr_stat.get().read_volatile();
+            let status = self.map.read(&self.backend, I2cRegister::RawIntrStat);
             if (status & (1 << 1)) != 0 { // TX_ABRT位
-                (*self.registers).clr_tx_abrt.get().write_volatile(0x1);
+                self.map.write(&self.backend, I2cRegister::ClrTxAbrt, 0x1);
                 return Err(I2cError::NackReceived);
             }
             if (status & (1 << 7)) != 0 { // TX_EMPTY位
@@ -468,9 +908,21 @@ impl Rk3588I2c {
         Err(I2cError::Timeout)
     }
     
-    unsafe fn send_read_command(&self) -> Result<(), I2cError> {
-        // 发送读命令（写入数据命令寄存器，设置读位）
-        (*self.registers).data_cmd.get().write_volatile(1 << 8); // 设置CMD位为读
+    /// 每个字节下发一个读命令后读取，`restart`时首个命令携带重复起始条件，最后一个命令携带停止条件
+    unsafe fn read_bytes(&self, buffer: &mut [u8], restart: bool) -> Result<(), I2cError> {
+        let total = buffer.len();
+        for (i, byte) in buffer.iter_mut().enumerate() {
+            let mut command = DATA_CMD_READ;
+            if restart && i == 0 {
+                command |= DATA_CMD_RESTART;
+            }
+            if i + 1 == total {
+                command |= DATA_CMD_STOP;
+            }
+            self.wait_tx_space()?;
+            self.map.write(&self.backend, I2cRegister::DataCmd, command);
+            *byte = self.read_byte()?;
+        }
         Ok(())
     }
     
@@ -479,7 +931,7 @@ impl Rk3588I2c {
         let mut timeout = self.config.timeout_ms * 1000;
         
         while timeout > 0 {
-            let rxflr = (*self.registers).rxflr.get().read_volatile();
+            let rxflr = self.map.read(&self.backend, I2cRegister::Rxflr);
             if rxflr > 0 {
                 break;
             }
@@ -491,15 +943,45 @@ impl Rk3588I2c {
         }
         
         // 读取数据
-        let data = (*self.registers).data_cmd.get().read_volatile() as u8;
+        let data = self.map.read(&self.backend, I2cRegister::DataCmd) as u8;
         Ok(data)
     }
     
     fn validate_address(&self, address: u16) -> bool {
-        match self.config.addressing_mode {
-            AddressingMode::SevenBit => address <= 0x7F,
-            AddressingMode::TenBit => address <= 0x3FF,
+        self.tar_value(address).is_ok()
+    }
+}
+
+/// `Rk3588I2c::wait_interrupt`返回的Future
+struct InterruptWait<'a, M, B> {
+    i2c: &'a Rk3588I2c<M, B>,
+    events: u32,
+}
+
+impl<'a, M: RegisterMap<I2cRegister>, B: HardwareBackend> Future for InterruptWait<'a, M, B> {
+    type Output = Result<(), I2cError>;
+    
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let i2c = self.i2c;
+        let status = i2c.irq_status.load(Ordering::Acquire);
+        
+        if status & INTR_TX_ABRT != 0 {
+            i2c.irq_status.fetch_and(!INTR_TX_ABRT, Ordering::AcqRel);
+            let source = i2c.map.read(&i2c.backend, I2cRegister::TxAbrtSource);
+            i2c.map.write(&i2c.backend, I2cRegister::ClrTxAbrt, 0x1);
+            return Poll::Ready(Err(abort_error(source)));
+        }
+        
+        if status & self.events != 0 {
+            i2c.irq_status.fetch_and(!self.events, Ordering::AcqRel);
+            return Poll::Ready(Ok(()));
         }
+        
+        // 先登记唤醒器再解除屏蔽，避免丢失在两者之间到达的中断
+        *i2c.irq_waker.lock() = Some(cx.waker().clone());
+        let events = self.events | INTR_TX_ABRT;
+        i2c.map.modify(&i2c.backend, I2cRegister::IntrMask, |mask| mask | events);
+        Poll::Pending
     }
 }
 
@@ -507,7 +989,7 @@ impl Rk3588I2c {
 pub static mut I2C0: Option<Rk3588I2c> = None;
 pub static mut I2C1: Option<Rk3588I2c> = None;
 
-/// 初始化I2C控制器
+/// 初始化I2C控制器并注册控制器中断
 pub fn init_i2c() {
     let config = I2cConfig::default();
     
@@ -522,6 +1004,53 @@ pub fn init_i2c() {
             let _ = i2c.init();
         }
     }
+    
+    for irq in [I2C0_IRQ, I2C1_IRQ] {
+        if !has_interrupt_handler(irq) {
+            let _ = request_irq(irq, i2c_interrupt_handler, PriorityBand::Background);
+        }
+    }
+    
+    unsafe {
+        if let Some(i2c) = &I2C0 {
+            i2c.enable_interrupts();
+        }
+        if let Some(i2c) = &I2C1 {
+            i2c.enable_interrupts();
+        }
+    }
+}
+
+/// I2C控制器中断处理函数，按中断号分发到对应控制器
+fn i2c_interrupt_handler(interrupt_id: u32) {
+    let controller = unsafe {
+        match interrupt_id {
+            I2C0_IRQ => I2C0.as_ref(),
+            I2C1_IRQ => I2C1.as_ref(),
+            _ => None,
+        }
+    };
+    
+    if let Some(i2c) = controller {
+        i2c.handle_interrupt();
+    }
+}
+
+/// 根据控制器输入时钟计算SCL高低电平计数
+pub fn compute_scl_counts(ic_clk: u32, target_speed: u32) -> Result<(u32, u32), I2cError> {
+    if target_speed == 0 || target_speed > 400_000 {
+        return Err(I2cError::HardwareError); // 不支持高速模式
+    }
+    
+    let period = ic_clk / target_speed;
+    
+    if target_speed <= 100_000 {
+        // 标准模式：高电平时间占3/7周期，低电平时间占4/7周期
+        Ok(((period * 3) / 7, (period * 4) / 7))
+    } else {
+        // 快速模式：高电平时间占1/3周期，低电平时间占2/3周期
+        Ok((period / 3, (period * 2) / 3))
+    }
 }
 
 /// I2C设备抽象
@@ -566,4 +1095,398 @@ impl I2cDevice {
         write_data.extend_from_slice(data);
         self.write(&write_data)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::register_map::{Endianness, MockHardwareBackend};
+    use core::pin::pin;
+    use core::task::{RawWaker, RawWakerVTable};
+    
+    /// 寄存器布局与RK3588不同的大端序映射
+    struct PackedBigEndianMap {
+        base: usize,
+    }
+    
+    impl RegisterMap<I2cRegister> for PackedBigEndianMap {
+        fn base(&self) -> usize {
+            self.base
+        }
+        
+        fn offset(&self, register: I2cRegister) -> usize {
+            match register {
+                I2cRegister::Enable => 0x00,
+                I2cRegister::SdaHold => 0x04,
+                I2cRegister::SsSclHcnt => 0x08,
+                I2cRegister::SsSclLcnt => 0x0C,
+                I2cRegister::TxTl => 0x10,
+                I2cRegister::RxTl => 0x14,
+                _ => 0x18,
+            }
+        }
+        
+        fn endianness(&self) -> Endianness {
+            Endianness::Big
+        }
+    }
+    
+    const MOCK_BASE: usize = 0x1000;
+    
+    static VTABLE: RawWakerVTable = RawWakerVTable::new(
+        |_| RawWaker::new(core::ptr::null(), &VTABLE),
+        |_| {},
+        |_| {},
+        |_| {},
+    );
+    
+    fn noop_waker() -> Waker {
+        unsafe { Waker::from_raw(RawWaker::new(core::ptr::null(), &VTABLE)) }
+    }
+    
+    fn irq_controller() -> Rk3588I2c<Rk3588Map, MockHardwareBackend> {
+        let mut i2c = Rk3588I2c::with_map(
+            Rk3588Map::new(MOCK_BASE),
+            MockHardwareBackend::new(MOCK_BASE, 0x100),
+            I2cConfig::default(),
+        );
+        i2c.init().unwrap();
+        i2c.enable_interrupts();
+        i2c
+    }
+    
+    /// 模拟硬件置位中断状态并进入中断服务程序
+    fn raise_interrupt(i2c: &Rk3588I2c<Rk3588Map, MockHardwareBackend>, status: u32) {
+        i2c.backend.write32(MOCK_BASE + 0x2C, status);
+        assert!(i2c.handle_interrupt());
+        i2c.backend.write32(MOCK_BASE + 0x2C, 0);
+    }
+    
+    #[test]
+    fn test_rk3588_map_init_writes_expected_offsets() {
+        // 使用RK3588布局初始化，SDA保持时间和使能位写入RK3588偏移
+        let mut i2c = Rk3588I2c::with_map(
+            Rk3588Map::new(MOCK_BASE),
+            MockHardwareBackend::new(MOCK_BASE, 0x100),
+            I2cConfig::default(),
+        );
+        i2c.init().unwrap();
+        
+        assert_eq!(i2c.backend.read32(MOCK_BASE + 0x7C), 60);
+        assert_eq!(i2c.backend.read32(MOCK_BASE + 0x6C), 1);
+        assert!(!i2c.is_bus_busy().unwrap());
+    }
+    
+    #[test]
+    fn test_alternate_map_drives_same_logic_to_other_offsets() {
+        // 相同的初始化逻辑经另一映射写入不同偏移，并按大端序存储
+        let mut i2c = Rk3588I2c::with_map(
+            PackedBigEndianMap { base: MOCK_BASE },
+            MockHardwareBackend::new(MOCK_BASE, 0x100),
+            I2cConfig::default(),
+        );
+        i2c.init().unwrap();
+        
+        assert_eq!(i2c.backend.read32(MOCK_BASE + 0x04), 60u32.to_be());
+        assert_eq!(i2c.backend.read32(MOCK_BASE + 0x00), 1u32.to_be());
+        assert_eq!(i2c.map.read(&i2c.backend, I2cRegister::SdaHold), 60);
+        assert_eq!(i2c.backend.read32(MOCK_BASE + 0x7C), 0);
+    }
+    
+    #[test]
+    fn test_irq_read_completes_on_fifo_ready() {
+        // 读取在中断服务程序报告RX FIFO就绪后完成，期间解除RX_FULL中断屏蔽
+        let i2c = irq_controller();
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        let mut buffer = [0u8; 4];
+        
+        {
+            let mut read = pin!(i2c.read_irq(0x50, &mut buffer));
+            assert!(read.as_mut().poll(&mut cx).is_pending());
+            assert_eq!(i2c.backend.read32(MOCK_BASE + 0x38), 3);
+            assert_ne!(i2c.backend.read32(MOCK_BASE + 0x30) & INTR_RX_FULL, 0);
+            assert!(read.as_mut().poll(&mut cx).is_pending());
+            
+            // 硬件接收完成：数据寄存器可读，置位RX_FULL
+            i2c.backend.write32(MOCK_BASE + 0x10, 0xA5);
+            raise_interrupt(&i2c, INTR_RX_FULL);
+            assert_eq!(i2c.backend.read32(MOCK_BASE + 0x30) & INTR_RX_FULL, 0);
+            assert_eq!(read.as_mut().poll(&mut cx), Poll::Ready(Ok(())));
+        }
+        assert_eq!(buffer, [0xA5; 4]);
+        assert_eq!(i2c.backend.read32(MOCK_BASE + 0x04), 0x50);
+    }
+    
+    #[test]
+    fn test_irq_nack_surfaces_as_error() {
+        // 传输中止中断（地址NACK）使写入以NackReceived结束
+        let i2c = irq_controller();
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        
+        let mut write = pin!(i2c.write_irq(0x3C, &[0x00, 0xAF]));
+        assert!(write.as_mut().poll(&mut cx).is_pending());
+        assert_eq!(i2c.backend.read32(MOCK_BASE + 0x10), 0xAF | DATA_CMD_STOP);
+        
+        i2c.backend.write32(MOCK_BASE + 0x80, 1 << 0); // 7位地址未应答
+        raise_interrupt(&i2c, INTR_TX_ABRT);
+        assert_eq!(write.as_mut().poll(&mut cx), Poll::Ready(Err(I2cError::NackReceived)));
+        assert_eq!(i2c.backend.read32(MOCK_BASE + 0x54), 1);
+    }
+    
+    #[test]
+    fn test_irq_write_waits_for_stop_and_reports_last_byte_nack() {
+        // TX FIFO排空后写入仍未完成，末字节NACK引起的中止报告为错误；正常传输在STOP_DET后成功
+        let i2c = irq_controller();
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        
+        {
+            let mut write = pin!(i2c.write_irq(0x3C, &[0x00, 0xAF]));
+            assert!(write.as_mut().poll(&mut cx).is_pending());
+            raise_interrupt(&i2c, INTR_TX_EMPTY);
+            assert!(write.as_mut().poll(&mut cx).is_pending());
+            
+            i2c.backend.write32(MOCK_BASE + 0x80, 1 << 3); // 数据字节未应答
+            raise_interrupt(&i2c, INTR_TX_ABRT | INTR_STOP_DET);
+            assert_eq!(write.as_mut().poll(&mut cx), Poll::Ready(Err(I2cError::NackReceived)));
+        }
+        
+        let mut write = pin!(i2c.write_irq(0x3C, &[0x00, 0xAF]));
+        assert!(write.as_mut().poll(&mut cx).is_pending());
+        assert_ne!(i2c.backend.read32(MOCK_BASE + 0x30) & INTR_STOP_DET, 0);
+        raise_interrupt(&i2c, INTR_STOP_DET);
+        assert_eq!(write.as_mut().poll(&mut cx), Poll::Ready(Ok(())));
+        assert_eq!(i2c.backend.read32(MOCK_BASE + 0x60), 1);
+    }
+    
+    /// 模拟DMAC接收通道：启动时记录控制字与已下发的读命令，按固定模式填充目标缓冲区
+    struct MockRxChannel<'a> {
+        backend: &'a MockHardwareBackend,
+        hang: bool,
+        control: AtomicU32,
+        data_cmd_at_start: AtomicU32,
+        stopped: AtomicBool,
+    }
+    
+    impl<'a> MockRxChannel<'a> {
+        fn new(backend: &'a MockHardwareBackend, hang: bool) -> Self {
+            Self {
+                backend,
+                hang,
+                control: AtomicU32::new(0),
+                data_cmd_at_start: AtomicU32::new(u32::MAX),
+                stopped: AtomicBool::new(false),
+            }
+        }
+    }
+    
+    impl DmaChannel for MockRxChannel<'_> {
+        fn start(&self, descriptor: &DmaDescriptor) -> Result<(), &'static str> {
+            self.control.store(descriptor.control, Ordering::Relaxed);
+            self.data_cmd_at_start.store(self.backend.read32(MOCK_BASE + 0x10), Ordering::Relaxed);
+            if !self.hang {
+                let size = descriptor.transfer_size as usize;
+                let dest = unsafe { core::slice::from_raw_parts_mut(descriptor.destination_addr as *mut u8, size) };
+                for (i, byte) in dest.iter_mut().enumerate() {
+                    *byte = i as u8;
+                }
+            }
+            Ok(())
+        }
+        
+        fn is_busy(&self) -> bool {
+            self.hang && !self.stopped.load(Ordering::Relaxed)
+        }
+        
+        fn take_completion(&self) -> bool {
+            !self.hang
+        }
+        
+        fn result(&self) -> DmaResult {
+            DmaResult::complete(16)
+        }
+        
+        fn stop(&self) {
+            self.stopped.store(true, Ordering::Relaxed);
+        }
+    }
+    
+    /// 仅在地址0x50应答的总线，记录被探测过的地址
+    struct ScanBus {
+        registers: MockHardwareBackend,
+        tar: AtomicU32,
+        probed: [AtomicU32; 4],
+    }
+    
+    impl HardwareBackend for ScanBus {
+        fn read32(&self, addr: usize) -> u32 {
+            if addr == MOCK_BASE + 0x34 {
+                let ack = self.tar.load(Ordering::Relaxed) == 0x50;
+                return INTR_STOP_DET | if ack { 0 } else { INTR_TX_ABRT };
+            }
+            self.registers.read32(addr)
+        }
+        
+        fn write32(&self, addr: usize, value: u32) {
+            if addr == MOCK_BASE + 0x04 {
+                self.tar.store(value, Ordering::Relaxed);
+                self.probed[value as usize / 32].fetch_or(1 << (value % 32), Ordering::Relaxed);
+            }
+            self.registers.write32(addr, value);
+        }
+    }
+    
+    #[test]
+    fn test_scan_skips_reserved_addresses() {
+        // 扫描只探测0x08-0x77，保留地址从不出现在目标地址寄存器中
+        const ZERO: AtomicU32 = AtomicU32::new(0);
+        let mut i2c = Rk3588I2c::with_map(
+            Rk3588Map::new(MOCK_BASE),
+            ScanBus { registers: MockHardwareBackend::new(MOCK_BASE, 0x100), tar: ZERO, probed: [ZERO; 4] },
+            I2cConfig::default(),
+        );
+        i2c.init().unwrap();
+        
+        let found = i2c.scan().unwrap();
+        assert_eq!(found.as_slice(), &[0x50]);
+        for address in 0..=0x7Fu16 {
+            let probed = i2c.backend.probed[address as usize / 32].load(Ordering::Relaxed) & (1 << (address % 32)) != 0;
+            assert_eq!(probed, !is_reserved_address(address), "地址0x{:02X}", address);
+        }
+    }
+    
+    /// 记录控制器使能期间对CON/TAR写入的总线，使能状态跟随使能寄存器
+    struct EnableTrackingBus {
+        registers: MockHardwareBackend,
+        writes_while_enabled: AtomicU32,
+    }
+    
+    impl HardwareBackend for EnableTrackingBus {
+        fn read32(&self, addr: usize) -> u32 {
+            if addr == MOCK_BASE + 0x9C {
+                return self.registers.read32(MOCK_BASE + 0x6C) & ENABLE_STATUS_IC_EN;
+            }
+            self.registers.read32(addr)
+        }
+        
+        fn write32(&self, addr: usize, value: u32) {
+            let enabled = self.registers.read32(MOCK_BASE + 0x6C) != 0;
+            if enabled && (addr == MOCK_BASE + 0x00 || addr == MOCK_BASE + 0x04) {
+                self.writes_while_enabled.fetch_add(1, Ordering::Relaxed);
+            }
+            self.registers.write32(addr, value);
+        }
+    }
+    
+    #[test]
+    fn test_target_address_written_while_disabled() {
+        // 切换目标地址时先禁用控制器，写入CON/TAR后重新使能
+        let mut i2c = Rk3588I2c::with_map(
+            Rk3588Map::new(MOCK_BASE),
+            EnableTrackingBus { registers: MockHardwareBackend::new(MOCK_BASE, 0x100), writes_while_enabled: AtomicU32::new(0) },
+            I2cConfig::default(),
+        );
+        i2c.init().unwrap();
+        i2c.backend.write32(MOCK_BASE + 0x34, (1 << 10) | (1 << 9) | (1 << 7)); // START_DET | STOP_DET | TX_EMPTY
+        i2c.backend.write32(MOCK_BASE + 0x78, 1); // RX FIFO有数据
+        
+        let mut buffer = [0u8; 1];
+        assert_eq!(i2c.write_then_read(0x50, &[0x10], &mut buffer), Ok(()));
+        assert_eq!(i2c.backend.read32(MOCK_BASE + 0x04), 0x50);
+        assert_eq!(i2c.backend.read32(MOCK_BASE + 0x6C), 0x1);
+        assert_eq!(i2c.backend.writes_while_enabled.load(Ordering::Relaxed), 0);
+    }
+    
+    fn addressed_controller(mode: AddressingMode) -> Rk3588I2c<Rk3588Map, MockHardwareBackend> {
+        let config = I2cConfig { addressing_mode: mode, ..I2cConfig::default() };
+        let mut i2c = Rk3588I2c::with_map(Rk3588Map::new(MOCK_BASE), MockHardwareBackend::new(MOCK_BASE, 0x100), config);
+        i2c.init().unwrap();
+        i2c
+    }
+    
+    #[test]
+    fn test_tar_value_for_7bit_and_10bit_addresses() {
+        // 7位模式只接受0x7F以内的地址；10位模式下大地址带10位标志，小地址仍按7位设备寻址
+        let seven = addressed_controller(AddressingMode::SevenBit);
+        assert_eq!(seven.tar_value(0x50), Ok(0x50));
+        assert_eq!(seven.tar_value(0x80), Err(I2cError::InvalidAddress));
+        
+        let ten = addressed_controller(AddressingMode::TenBit);
+        assert_eq!(ten.tar_value(0x50), Ok(0x50));
+        assert_eq!(ten.tar_value(0x2A5), Ok(0x2A5 | TAR_10BITADDR_MASTER));
+        assert_eq!(ten.tar_value(0x3FF), Ok(0x3FF | TAR_10BITADDR_MASTER));
+        assert_eq!(ten.tar_value(0x400), Err(I2cError::InvalidAddress));
+        assert_eq!(ten.write(0x400, &[0]), Err(I2cError::InvalidAddress));
+    }
+    
+    #[test]
+    fn test_10bit_write_then_read_restarts_on_direction_change() {
+        // 10位组合传输：TAR与CON带10位标志，方向切换时读命令携带重复起始条件
+        let i2c = addressed_controller(AddressingMode::TenBit);
+        i2c.backend.write32(MOCK_BASE + 0x34, (1 << 10) | (1 << 9) | (1 << 7)); // START_DET | STOP_DET | TX_EMPTY
+        i2c.backend.write32(MOCK_BASE + 0x78, 1); // RX FIFO有数据
+        
+        let mut buffer = [0u8; 1];
+        assert_eq!(i2c.write_then_read(0x2A5, &[0x10], &mut buffer), Ok(()));
+        assert_eq!(i2c.backend.read32(MOCK_BASE + 0x04), 0x2A5 | TAR_10BITADDR_MASTER);
+        assert_eq!(i2c.backend.read32(MOCK_BASE + 0x00), CON_10BITADDR_MASTER | CON_RESTART_EN);
+        assert_eq!(i2c.backend.read32(MOCK_BASE + 0x10), DATA_CMD_READ | DATA_CMD_RESTART | DATA_CMD_STOP);
+        
+        // 同一控制器访问7位设备时清除10位标志
+        assert_eq!(i2c.write_then_read(0x50, &[0x10], &mut buffer), Ok(()));
+        assert_eq!(i2c.backend.read32(MOCK_BASE + 0x04), 0x50);
+        assert_eq!(i2c.backend.read32(MOCK_BASE + 0x00), CON_RESTART_EN);
+    }
+    
+    #[test]
+    fn test_read_path_threshold() {
+        // 短读取走PIO，达到阈值后走DMA，水位不超过半个FIFO
+        assert_eq!(ReadPath::for_length(0), ReadPath::Pio);
+        assert_eq!(ReadPath::for_length(I2C_DMA_THRESHOLD - 1), ReadPath::Pio);
+        assert_eq!(ReadPath::for_length(I2C_DMA_THRESHOLD), ReadPath::Dma { watermark: 7 });
+        assert_eq!(ReadPath::for_length(12), ReadPath::Dma { watermark: 11 });
+        assert_eq!(ReadPath::for_length(4096), ReadPath::Dma { watermark: 15 });
+        
+        // DMA读取完成后关闭RX DMA，最后一个读命令带停止位
+        let i2c = irq_controller();
+        let channel = MockRxChannel::new(&i2c.backend, false);
+        let mut buffer = [0u8; 16];
+        assert_eq!(i2c.read_dma_with(0x36, &mut buffer, &channel, 21), Ok(()));
+        assert_eq!(buffer[15], 15);
+        assert_eq!((channel.control.load(Ordering::Relaxed) >> 8) & 0x3F, 21);
+        assert_eq!(channel.data_cmd_at_start.load(Ordering::Relaxed), 0);
+        assert_eq!(i2c.backend.read32(MOCK_BASE + 0x90), 15);
+        assert_eq!(i2c.backend.read32(MOCK_BASE + 0x88) & DMA_CR_RDMAE, 0);
+        assert_eq!(i2c.backend.read32(MOCK_BASE + 0x10), DATA_CMD_READ | DATA_CMD_STOP);
+    }
+    
+    #[test]
+    fn test_read_dma_aborts_on_arbitration_lost() {
+        // 传输中途仲裁丢失：中止DMA并返回ArbitrationLost
+        let i2c = irq_controller();
+        i2c.backend.write32(MOCK_BASE + 0x80, ABRT_ARB_LOST);
+        i2c.backend.write32(MOCK_BASE + 0x34, INTR_TX_ABRT);
+        
+        let channel = MockRxChannel::new(&i2c.backend, true);
+        let mut buffer = [0u8; 32];
+        assert_eq!(i2c.read_dma_with(0x36, &mut buffer, &channel, 21), Err(I2cError::ArbitrationLost));
+        assert!(channel.stopped.load(Ordering::Relaxed));
+        assert_eq!(i2c.backend.read32(MOCK_BASE + 0x88) & DMA_CR_RDMAE, 0);
+        assert_eq!(i2c.backend.read32(MOCK_BASE + 0x54), 1);
+        assert_eq!(buffer, [0; 32]);
+    }
+    
+    #[test]
+    fn test_read_dma_times_out_when_channel_hangs() {
+        // 接收通道一直未结束：超时后停止通道、关闭RX DMA并返回Timeout
+        let i2c = irq_controller();
+        let channel = MockRxChannel::new(&i2c.backend, true);
+        let mut buffer = [0u8; 16];
+        assert_eq!(i2c.read_dma_with(0x36, &mut buffer, &channel, 21), Err(I2cError::Timeout));
+        assert!(channel.stopped.load(Ordering::Relaxed));
+        assert_eq!(i2c.backend.read32(MOCK_BASE + 0x88) & DMA_CR_RDMAE, 0);
+        assert_eq!(i2c.backend.read32(MOCK_BASE + 0x10), DATA_CMD_READ | DATA_CMD_STOP);
+    }
+}
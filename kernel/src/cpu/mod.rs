@@ -7,6 +7,11 @@
 use core::arch::asm;
 use core::sync::atomic::{AtomicU32, AtomicU64, AtomicBool, Ordering};
 
+pub mod usage;
+
+use crate::sync::IrqMutex;
+use usage::CpuUsageTracker;
+
 /// 任务信息结构
 #[derive(Debug, Clone)]
 pub struct TaskInfo {
@@ -229,22 +234,99 @@ impl CpuManager {
 /// 全局CPU管理器实例
 pub static CPU_MANAGER: CpuManager = CpuManager::new();
 
+/// 全部核心
+pub const ALL_CORES: [CoreId; 8] = [
+    CoreId::A76_0, CoreId::A76_1, CoreId::A76_2, CoreId::A76_3,
+    CoreId::A55_0, CoreId::A55_1, CoreId::A55_2, CoreId::A55_3,
+];
+
+/// 全局CPU使用率跟踪器，各核心的空闲任务与查询方共享，持锁期间屏蔽本核IRQ
+static CPU_USAGE: IrqMutex<Option<CpuUsageTracker>> = IrqMutex::new(None);
+
 /// 初始化CPU系统
 pub fn init() {
     // 启动主核心（A76_0）
     let _ = CPU_MANAGER.start_core(CoreId::A76_0);
     
+    // 使用率统计窗口为1秒
+    let mut tracker = CpuUsageTracker::new(crate::get_timer_frequency());
+    tracker.reset(crate::get_timer_count());
+    *CPU_USAGE.lock() = Some(tracker);
+    
     // 根据系统负载决定是否启动其他核心
     // 默认只启动主核心，其他核心按需启动
 }
 
+/// 当前核心进入空闲（由空闲任务调用）
+pub fn record_idle_enter() {
+    if let Some(tracker) = CPU_USAGE.lock().as_mut() {
+        tracker.record_idle_enter(CoreId::current(), crate::get_timer_count());
+    }
+}
+
+/// 当前核心退出空闲（由空闲任务调用）
+pub fn record_idle_exit() {
+    if let Some(tracker) = CPU_USAGE.lock().as_mut() {
+        tracker.record_idle_exit(CoreId::current(), crate::get_timer_count());
+    }
+}
+
+/// 所有运行中核心最近1秒的平均使用率百分比
+pub fn get_usage_percent() -> f32 {
+    let mut online = [CoreId::A76_0; 8];
+    let mut count = 0;
+    for core in ALL_CORES {
+        if CPU_MANAGER.get_core_state(core) != CoreState::Off {
+            online[count] = core;
+            count += 1;
+        }
+    }
+    
+    match CPU_USAGE.lock().as_mut() {
+        Some(tracker) => tracker.usage_percent(&online[..count], crate::get_timer_count()),
+        None => 0.0,
+    }
+}
+
+/// 指定核心最近1秒的使用率百分比
+pub fn get_core_usage_percent(core: CoreId) -> f32 {
+    match CPU_USAGE.lock().as_mut() {
+        Some(tracker) => tracker.core_usage_percent(core, crate::get_timer_count()),
+        None => 0.0,
+    }
+}
+
+/// 调频策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrequencyGovernor {
+    Performance = 0,    // 固定最高频率
+    Powersave = 1,      // 固定最低频率
+    Ondemand = 2,       // 按负载自动调节
+    Userspace = 3,      // 由调用者指定频率
+}
+
+impl FrequencyGovernor {
+    fn from_u32(value: u32) -> Self {
+        match value {
+            0 => FrequencyGovernor::Performance,
+            1 => FrequencyGovernor::Powersave,
+            3 => FrequencyGovernor::Userspace,
+            _ => FrequencyGovernor::Ondemand,
+        }
+    }
+}
+
+/// 按需调频的最低频率 (MHz)
+const MIN_FREQUENCY_MHZ: u32 = 600;
+
 /// 增强型CPU调度器 - 支持动态负载均衡和能效优化
 pub struct EnhancedScheduler {
     performance_cores: [CoreId; 4],
     efficiency_cores: [CoreId; 4],
     core_loads: [AtomicU32; 8],           // 每个核心的负载百分比
     core_temperatures: [AtomicU32; 8],     // 每个核心的温度
-    core_frequencies: [AtomicU32; 8],      // 每个核心的当前频率
+    core_frequencies: [AtomicU32; 8],      // 每个核心的当前频率 (MHz)
+    core_governors: [AtomicU32; 8],        // 每个核心的调频策略
     energy_efficiency_mode: AtomicBool,    // 能效模式开关
     last_balance_time: AtomicU64,         // 上次负载均衡时间
 }
@@ -267,6 +349,12 @@ impl EnhancedScheduler {
                 AtomicU32::new(2400), AtomicU32::new(2400), AtomicU32::new(2400), AtomicU32::new(2400),
                 AtomicU32::new(1800), AtomicU32::new(1800), AtomicU32::new(1800), AtomicU32::new(1800),
             ],
+            core_governors: [
+                AtomicU32::new(FrequencyGovernor::Ondemand as u32), AtomicU32::new(FrequencyGovernor::Ondemand as u32),
+                AtomicU32::new(FrequencyGovernor::Ondemand as u32), AtomicU32::new(FrequencyGovernor::Ondemand as u32),
+                AtomicU32::new(FrequencyGovernor::Ondemand as u32), AtomicU32::new(FrequencyGovernor::Ondemand as u32),
+                AtomicU32::new(FrequencyGovernor::Ondemand as u32), AtomicU32::new(FrequencyGovernor::Ondemand as u32),
+            ],
             energy_efficiency_mode: AtomicBool::new(false),
             last_balance_time: AtomicU64::new(0),
         }
@@ -376,9 +464,10 @@ impl EnhancedScheduler {
             let temp = self.core_temperatures[i].load(Ordering::Acquire);
             
             if temp > 80 {
-                // 温度过高，降低频率并迁移任务
+                // 温度过高，降低频率（不低于最低频率）并迁移任务
+                let current_freq = self.core_frequencies[i].load(Ordering::Acquire);
                 self.core_frequencies[i].store(
-                    self.core_frequencies[i].load(Ordering::Acquire) - 200,
+                    current_freq.saturating_sub(200).max(MIN_FREQUENCY_MHZ),
                     Ordering::Release
                 );
                 self.migrate_tasks_from_core(i as u8);
@@ -386,13 +475,16 @@ impl EnhancedScheduler {
         }
     }
     
-    /// 频率调节
+    /// 频率调节（只调节Ondemand策略的核心）
     fn adjust_frequencies(&self) {
         let total_load: u32 = self.core_loads.iter().map(|load| load.load(Ordering::Acquire)).sum();
+        let ondemand = |i: usize| {
+            FrequencyGovernor::from_u32(self.core_governors[i].load(Ordering::Acquire)) == FrequencyGovernor::Ondemand
+        };
         
         if total_load < 200 {
             // 系统负载低，降低频率节能
-            for i in 0..8 {
+            for i in (0..8).filter(|&i| ondemand(i)) {
                 let current_freq = self.core_frequencies[i].load(Ordering::Acquire);
                 if current_freq > 600 {
                     self.core_frequencies[i].store(current_freq - 100, Ordering::Release);
@@ -400,9 +492,9 @@ impl EnhancedScheduler {
             }
         } else if total_load > 600 {
             // 系统负载高，提高频率
-            for i in 0..8 {
+            for i in (0..8).filter(|&i| ondemand(i)) {
                 let current_freq = self.core_frequencies[i].load(Ordering::Acquire);
-                let max_freq = if i < 4 { 2400 } else { 1800 };
+                let max_freq = Self::max_frequency_mhz(ALL_CORES[i]);
                 if current_freq < max_freq {
                     self.core_frequencies[i].store(current_freq + 100, Ordering::Release);
                 }
@@ -410,6 +502,49 @@ impl EnhancedScheduler {
         }
     }
     
+    /// 核心类别的最高频率 (MHz)：A76为2400MHz，A55为1800MHz
+    pub fn max_frequency_mhz(core_id: CoreId) -> u32 {
+        if core_id.is_performance_core() { 2400 } else { 1800 }
+    }
+    
+    /// 设置核心的调频策略
+    /// 
+    /// Performance/Powersave立即切换到最高/最低频率，Userspace保持当前频率
+    pub fn set_governor(&self, core_id: CoreId, governor: FrequencyGovernor) {
+        let index = core_id as usize;
+        self.core_governors[index].store(governor as u32, Ordering::Release);
+        match governor {
+            FrequencyGovernor::Performance => {
+                self.core_frequencies[index].store(Self::max_frequency_mhz(core_id), Ordering::Release);
+            }
+            FrequencyGovernor::Powersave => {
+                self.core_frequencies[index].store(MIN_FREQUENCY_MHZ, Ordering::Release);
+            }
+            FrequencyGovernor::Ondemand | FrequencyGovernor::Userspace => {}
+        }
+    }
+    
+    /// 获取核心的调频策略
+    pub fn governor(&self, core_id: CoreId) -> FrequencyGovernor {
+        FrequencyGovernor::from_u32(self.core_governors[core_id as usize].load(Ordering::Acquire))
+    }
+    
+    /// 为Userspace策略的核心指定频率，截断到最低频率与核心类别最高频率之间，返回实际频率 (kHz)
+    pub fn set_frequency_khz(&self, core_id: CoreId, frequency_khz: u32) -> Result<u32, &'static str> {
+        if self.governor(core_id) != FrequencyGovernor::Userspace {
+            return Err("核心调频策略不是Userspace");
+        }
+        
+        let mhz = (frequency_khz / 1000).clamp(MIN_FREQUENCY_MHZ, Self::max_frequency_mhz(core_id));
+        self.core_frequencies[core_id as usize].store(mhz, Ordering::Release);
+        Ok(mhz * 1000)
+    }
+    
+    /// 获取核心当前频率 (kHz)
+    pub fn frequency_khz(&self, core_id: CoreId) -> u32 {
+        self.core_frequencies[core_id as usize].load(Ordering::Acquire) * 1000
+    }
+    
     /// 更新核心负载
     pub fn update_core_load(&self, core_id: CoreId, load: u32) {
         self.core_loads[core_id as usize].store(load, Ordering::Release);
@@ -420,6 +555,14 @@ impl EnhancedScheduler {
         self.core_temperatures[core_id as usize].store(temperature, Ordering::Release);
     }
     
+    /// 获取所有核心中的最高温度
+    pub fn max_core_temperature(&self) -> u32 {
+        self.core_temperatures.iter()
+            .map(|temp| temp.load(Ordering::Acquire))
+            .max()
+            .unwrap_or(0)
+    }
+    
     /// 设置能效模式
     pub fn set_energy_efficiency_mode(&self, enabled: bool) {
         self.energy_efficiency_mode.store(enabled, Ordering::Release);
@@ -445,4 +588,49 @@ pub fn schedule_task_intelligent(task_info: &TaskInfo) -> Option<CoreId> {
             None
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pinned_a55_keeps_frequency_across_balancing() {
+        // Userspace策略的A55核心在负载均衡后保持指定频率，Ondemand核心照常降频
+        let scheduler = EnhancedScheduler::new();
+        scheduler.set_governor(CoreId::A55_1, FrequencyGovernor::Userspace);
+        assert_eq!(scheduler.set_frequency_khz(CoreId::A55_1, 1_200_000), Ok(1_200_000));
+
+        scheduler.perform_load_balancing();
+        assert_eq!(scheduler.frequency_khz(CoreId::A55_1), 1_200_000);
+        assert_eq!(scheduler.frequency_khz(CoreId::A55_0), 1_700_000);
+
+        scheduler.set_governor(CoreId::A76_0, FrequencyGovernor::Performance);
+        scheduler.perform_load_balancing();
+        assert_eq!(scheduler.frequency_khz(CoreId::A76_0), 2_400_000);
+    }
+
+    #[test]
+    fn test_frequency_request_clamped_and_rejected_outside_userspace() {
+        // 请求频率按核心类别截断；非Userspace策略的核心拒绝指定频率
+        let scheduler = EnhancedScheduler::new();
+        assert!(scheduler.set_frequency_khz(CoreId::A76_2, 1_000_000).is_err());
+
+        scheduler.set_governor(CoreId::A76_2, FrequencyGovernor::Userspace);
+        scheduler.set_governor(CoreId::A55_3, FrequencyGovernor::Userspace);
+        assert_eq!(scheduler.set_frequency_khz(CoreId::A76_2, 3_000_000), Ok(2_400_000));
+        assert_eq!(scheduler.set_frequency_khz(CoreId::A55_3, 3_000_000), Ok(1_800_000));
+        assert_eq!(scheduler.set_frequency_khz(CoreId::A55_3, 0), Ok(MIN_FREQUENCY_MHZ * 1000));
+    }
+
+    #[test]
+    fn test_overheated_core_throttles_down_to_min_frequency() {
+        // 过热核心每次降频200MHz，不会低于最低频率
+        let scheduler = EnhancedScheduler::new();
+        scheduler.set_governor(CoreId::A55_2, FrequencyGovernor::Powersave);
+        scheduler.update_core_temperature(CoreId::A55_2, 90);
+
+        scheduler.manage_temperatures();
+        assert_eq!(scheduler.frequency_khz(CoreId::A55_2), MIN_FREQUENCY_MHZ * 1000);
+    }
+}
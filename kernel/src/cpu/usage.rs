@@ -0,0 +1,171 @@
+//! CPU使用率统计模块
+//!
+//! 空闲任务在进入/退出空闲时打点，按核心统计窗口内的空闲周期，
+//! 使用率为最近一个完整窗口（默认1秒）内非空闲时间的占比
+
+use super::CoreId;
+
+/// 核心数量
+const CORE_COUNT: usize = 8;
+
+/// 单个核心的统计状态
+#[derive(Debug, Clone, Copy)]
+struct CoreUsage {
+    /// 当前窗口起点（定时器计数）
+    window_start: u64,
+    /// 当前窗口内已结束的空闲周期
+    idle_cycles: u64,
+    /// 正处于空闲时的进入时刻
+    idle_since: Option<u64>,
+    /// 最近一个完整窗口的使用率
+    last_percent: Option<f32>,
+}
+
+impl CoreUsage {
+    const fn new() -> Self {
+        Self {
+            window_start: 0,
+            idle_cycles: 0,
+            idle_since: None,
+            last_percent: None,
+        }
+    }
+
+    /// 当前窗口内截至`now`的空闲周期
+    fn idle_until(&self, now: u64) -> u64 {
+        self.idle_cycles + self.idle_since.map_or(0, |since| now - since.max(self.window_start))
+    }
+
+    /// 推进窗口；定时器回绕（计数变小）时以`now`为起点重新开始统计
+    fn roll(&mut self, now: u64, window: u64) {
+        if now < self.window_start || self.idle_since.map_or(false, |since| now < since) {
+            self.window_start = now;
+            self.idle_cycles = 0;
+            self.idle_since = self.idle_since.map(|_| now);
+            self.last_percent = None;
+            return;
+        }
+
+        let elapsed = now - self.window_start;
+        if elapsed < window {
+            return;
+        }
+
+        let window_end = self.window_start + window;
+        let idle = self.idle_until(window_end).min(window);
+        self.last_percent = Some(100.0 * (window - idle) as f32 / window as f32);
+
+        // 跨越多个窗口时，最后一个完整窗口的状态由是否一直空闲决定
+        if elapsed >= 2 * window {
+            self.last_percent = Some(if self.idle_since.is_some() { 0.0 } else { 100.0 });
+        }
+        self.window_start = now - elapsed % window;
+        self.idle_cycles = 0;
+    }
+
+    /// 使用率：有完整窗口时取最近一个，否则按当前窗口已经过的部分计算
+    fn percent(&self, now: u64) -> f32 {
+        if let Some(percent) = self.last_percent {
+            return percent;
+        }
+        let elapsed = now - self.window_start;
+        if elapsed == 0 {
+            return 0.0;
+        }
+        100.0 * (elapsed - self.idle_until(now).min(elapsed)) as f32 / elapsed as f32
+    }
+}
+
+/// 按核心统计忙/闲周期的使用率跟踪器
+pub struct CpuUsageTracker {
+    window_cycles: u64,
+    cores: [CoreUsage; CORE_COUNT],
+}
+
+impl CpuUsageTracker {
+    /// 创建跟踪器，窗口长度以定时器周期计
+    pub const fn new(window_cycles: u64) -> Self {
+        Self {
+            window_cycles: if window_cycles == 0 { 1 } else { window_cycles },
+            cores: [CoreUsage::new(); CORE_COUNT],
+        }
+    }
+
+    /// 以`now`为起点重新开始统计所有核心
+    pub fn reset(&mut self, now: u64) {
+        for usage in self.cores.iter_mut() {
+            *usage = CoreUsage::new();
+            usage.window_start = now;
+        }
+    }
+
+    /// 记录核心在`now`时刻进入空闲
+    pub fn record_idle_enter(&mut self, core: CoreId, now: u64) {
+        let usage = &mut self.cores[core as usize];
+        usage.roll(now, self.window_cycles);
+        if usage.idle_since.is_none() {
+            usage.idle_since = Some(now);
+        }
+    }
+
+    /// 记录核心在`now`时刻退出空闲
+    pub fn record_idle_exit(&mut self, core: CoreId, now: u64) {
+        let usage = &mut self.cores[core as usize];
+        usage.roll(now, self.window_cycles);
+        if let Some(since) = usage.idle_since.take() {
+            usage.idle_cycles += now - since.max(usage.window_start);
+        }
+    }
+
+    /// 核心使用率百分比
+    pub fn core_usage_percent(&mut self, core: CoreId, now: u64) -> f32 {
+        let usage = &mut self.cores[core as usize];
+        usage.roll(now, self.window_cycles);
+        usage.percent(now)
+    }
+
+    /// 多个核心的平均使用率百分比
+    pub fn usage_percent(&mut self, cores: &[CoreId], now: u64) -> f32 {
+        if cores.is_empty() {
+            return 0.0;
+        }
+        let total: f32 = cores.iter().map(|&core| self.core_usage_percent(core, now)).sum();
+        total / cores.len() as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_synthetic_intervals_percentage() {
+        // 1000周期窗口内空闲450周期，使用率为55%；另一核心全程忙碌，平均为77.5%
+        let mut tracker = CpuUsageTracker::new(1000);
+        let core = CoreId::A55_1;
+        tracker.record_idle_enter(core, 0);
+        tracker.record_idle_exit(core, 250);
+        assert!((tracker.core_usage_percent(core, 500) - 50.0).abs() < 1.0);
+        tracker.record_idle_enter(core, 600);
+        tracker.record_idle_exit(core, 800);
+
+        assert!((tracker.core_usage_percent(core, 1000) - 55.0).abs() < 1.0);
+        assert!((tracker.core_usage_percent(CoreId::A76_0, 1000) - 100.0).abs() < 1.0);
+        assert!((tracker.usage_percent(&[core, CoreId::A76_0], 1000) - 77.5).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_timer_wrap_resets_window() {
+        // 定时器回绕后窗口重新开始，空闲区间跨越回绕点不会产生异常值
+        let mut tracker = CpuUsageTracker::new(1000);
+        let core = CoreId::A76_2;
+        let start = u64::MAX - 300;
+        tracker.record_idle_exit(core, start);
+        tracker.record_idle_enter(core, start);
+
+        tracker.record_idle_exit(core, 200);
+        tracker.record_idle_enter(core, 500);
+        tracker.record_idle_exit(core, 700);
+        assert!((tracker.core_usage_percent(core, 1200) - 80.0).abs() < 1.0);
+    }
+}
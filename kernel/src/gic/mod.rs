@@ -8,6 +8,8 @@ use core::arch::asm;
 use core::sync::atomic::{AtomicU32, AtomicU64, AtomicBool, Ordering};
 use core::time::Duration;
 
+use crate::cpu::CoreId;
+
 /// 中断类型
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum InterruptType {
@@ -16,6 +18,80 @@ pub enum InterruptType {
     SGI = 2,    // 软件生成中断
 }
 
+/// 核间中断（IPI）
+///
+/// 每种IPI占用一个保留的SGI编号
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ipi {
+    /// 请求目标核心重新调度
+    Reschedule,
+    /// 请求目标核心刷新本地TLB
+    TlbFlush,
+    /// 请求目标核心执行`call_function_on`登记的函数
+    CallFunction,
+    /// 请求目标核心停止运行
+    Stop,
+}
+
+impl Ipi {
+    /// 全部IPI
+    pub const ALL: [Ipi; 4] = [Ipi::Reschedule, Ipi::TlbFlush, Ipi::CallFunction, Ipi::Stop];
+    
+    /// IPI对应的SGI编号
+    pub const fn sgi_id(&self) -> u8 {
+        match self {
+            Ipi::Reschedule => 0,
+            Ipi::TlbFlush => 1,
+            Ipi::CallFunction => 2,
+            Ipi::Stop => 3,
+        }
+    }
+}
+
+/// GICD_SGIR寄存器字节偏移
+const GICD_SGIR_OFFSET: u64 = 0xF00;
+
+/// 核心对应的SGI目标位
+const fn core_mask(core: CoreId) -> u8 {
+    1 << core as u8
+}
+
+/// 核间中断发送者
+pub trait IpiSender {
+    /// 向目标核心发送IPI
+    fn send_ipi(&mut self, target: CoreId, ipi: Ipi);
+}
+
+/// 通过GIC分发器发送IPI
+pub struct GicIpiSender;
+
+impl IpiSender for GicIpiSender {
+    fn send_ipi(&mut self, target: CoreId, ipi: Ipi) {
+        send_ipi(target, ipi);
+    }
+}
+
+/// GIC实现的优先级位数（RK3588的GIC只实现8位优先级中的高5位，低位被忽略）
+pub const IMPLEMENTED_PRIORITY_BITS: u32 = 5;
+
+/// 已实现优先级位的掩码
+const PRIORITY_MASK: u8 = !(0xFFu8 >> IMPLEMENTED_PRIORITY_BITS);
+
+/// 中断优先级档位，按优先级由高到低排列
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PriorityBand {
+    /// 系统定时器
+    Timer,
+    /// NPU推理完成
+    NpuDone,
+    /// 音频采集/播放
+    Audio,
+    /// 串口
+    Uart,
+    /// 其他后台外设
+    Background,
+}
+
 /// 中断优先级
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct InterruptPriority(u8);
@@ -39,19 +115,46 @@ impl InterruptPriority {
     
     /// 最低优先级
     pub const LOWEST: Self = Self(0xFF);
+    
+    /// 获取档位对应的优先级（数值越小优先级越高）
+    pub const fn from_band(band: PriorityBand) -> Self {
+        match band {
+            PriorityBand::Timer => Self(0x00),
+            PriorityBand::NpuDone => Self(0x20),
+            PriorityBand::Audio => Self(0x40),
+            PriorityBand::Uart => Self(0x60),
+            PriorityBand::Background => Self(0xA0),
+        }
+    }
+    
+    /// 屏蔽GIC未实现的低位，得到硬件实际生效的优先级
+    pub const fn validate(&self) -> Self {
+        Self(self.0 & PRIORITY_MASK)
+    }
+    
+    /// 写入GIC后是否会丢失低位
+    pub const fn loses_precision(&self) -> bool {
+        self.0 & !PRIORITY_MASK != 0
+    }
+    
+    /// 两个优先级在硬件上是否等价（仅在未实现的低位上不同）
+    pub const fn is_equivalent(&self, other: &Self) -> bool {
+        self.validate().0 == other.validate().0
+    }
 }
 
 /// 动态中断优先级管理器
 pub struct DynamicPriorityManager {
-    interrupt_stats: [InterruptStats; 1024], // 每个中断的统计信息
+    interrupt_stats: [InterruptPriorityState; 1024], // 每个中断的优先级调整状态
+    latency: LatencyTable,                   // 已触发中断的延迟统计
     system_load: AtomicU32,                  // 系统负载指标(0-100)
     last_adjustment_time: AtomicU64,         // 上次调整时间
     adaptive_mode: AtomicBool,               // 自适应模式开关
 }
 
-/// 中断统计信息
-#[derive(Debug, Clone)]
-pub struct InterruptStats {
+/// 中断优先级调整状态
+#[derive(Debug)]
+pub struct InterruptPriorityState {
     pub interrupt_count: AtomicU64,         // 中断发生次数
     pub average_latency: AtomicU32,          // 平均响应延迟(微秒)
     pub last_occurrence: AtomicU64,          // 最后发生时间
@@ -59,7 +162,182 @@ pub struct InterruptStats {
     pub base_priority: u8,                  // 基础优先级
 }
 
-impl InterruptStats {
+/// 延迟直方图桶上界(微秒)，最后一个桶收集 >= 500us 的样本
+pub const LATENCY_BUCKET_BOUNDS_US: [u32; 4] = [10, 50, 100, 500];
+
+/// 延迟直方图桶数量
+pub const LATENCY_BUCKETS: usize = LATENCY_BUCKET_BOUNDS_US.len() + 1;
+
+/// 最多跟踪的中断数量，仅为实际触发过的中断分配槽位
+pub const MAX_TRACKED_INTERRUPTS: usize = 64;
+
+/// 中断延迟统计快照
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InterruptStats {
+    pub interrupt_id: u32,
+    pub count: u64,
+    pub min_latency_us: u32,
+    pub max_latency_us: u32,
+    pub mean_latency_us: u32,
+    /// 各桶样本数：<10us、<50us、<100us、<500us、>=500us
+    pub histogram: [u64; LATENCY_BUCKETS],
+}
+
+/// 延迟所属的直方图桶
+const fn latency_bucket(latency_us: u32) -> usize {
+    let mut i = 0;
+    while i < LATENCY_BUCKET_BOUNDS_US.len() {
+        if latency_us < LATENCY_BUCKET_BOUNDS_US[i] {
+            return i;
+        }
+        i += 1;
+    }
+    LATENCY_BUCKET_BOUNDS_US.len()
+}
+
+/// 空闲槽位标记
+const FREE_SLOT: u32 = u32::MAX;
+
+/// 单个中断的延迟统计槽位，全部字段为原子量，可在中断上下文中无锁更新
+struct LatencySlot {
+    interrupt_id: AtomicU32,
+    count: AtomicU64,
+    total_us: AtomicU64,
+    min_us: AtomicU32,
+    max_us: AtomicU32,
+    buckets: [AtomicU64; LATENCY_BUCKETS],
+}
+
+impl LatencySlot {
+    const fn new() -> Self {
+        const ZERO: AtomicU64 = AtomicU64::new(0);
+        Self {
+            interrupt_id: AtomicU32::new(FREE_SLOT),
+            count: AtomicU64::new(0),
+            total_us: AtomicU64::new(0),
+            min_us: AtomicU32::new(u32::MAX),
+            max_us: AtomicU32::new(0),
+            buckets: [ZERO; LATENCY_BUCKETS],
+        }
+    }
+
+    fn record(&self, latency_us: u32) {
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.total_us.fetch_add(latency_us as u64, Ordering::Relaxed);
+        self.min_us.fetch_min(latency_us, Ordering::Relaxed);
+        self.max_us.fetch_max(latency_us, Ordering::Relaxed);
+        self.buckets[latency_bucket(latency_us)].fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn reset(&self) {
+        self.count.store(0, Ordering::Relaxed);
+        self.total_us.store(0, Ordering::Relaxed);
+        self.min_us.store(u32::MAX, Ordering::Relaxed);
+        self.max_us.store(0, Ordering::Relaxed);
+        for bucket in &self.buckets {
+            bucket.store(0, Ordering::Relaxed);
+        }
+    }
+
+    fn snapshot(&self, interrupt_id: u32) -> InterruptStats {
+        let count = self.count.load(Ordering::Relaxed);
+        let mut histogram = [0; LATENCY_BUCKETS];
+        for (value, bucket) in histogram.iter_mut().zip(&self.buckets) {
+            *value = bucket.load(Ordering::Relaxed);
+        }
+        InterruptStats {
+            interrupt_id,
+            count,
+            min_latency_us: if count == 0 { 0 } else { self.min_us.load(Ordering::Relaxed) },
+            max_latency_us: self.max_us.load(Ordering::Relaxed),
+            mean_latency_us: if count == 0 { 0 } else { (self.total_us.load(Ordering::Relaxed) / count) as u32 },
+            histogram,
+        }
+    }
+}
+
+/// 中断延迟统计表
+/// 
+/// 中断首次触发时通过CAS占用一个槽位（线性探测），槽位用完后新中断的样本被丢弃并计数
+pub struct LatencyTable {
+    slots: [LatencySlot; MAX_TRACKED_INTERRUPTS],
+    dropped: AtomicU64,
+}
+
+impl LatencyTable {
+    /// 创建空的统计表
+    pub const fn new() -> Self {
+        const EMPTY: LatencySlot = LatencySlot::new();
+        Self {
+            slots: [EMPTY; MAX_TRACKED_INTERRUPTS],
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    /// 查找中断对应的槽位，`claim`为true时为未跟踪的中断分配槽位
+    fn slot(&self, interrupt_id: u32, claim: bool) -> Option<&LatencySlot> {
+        if interrupt_id == FREE_SLOT {
+            return None;
+        }
+        let start = interrupt_id as usize % MAX_TRACKED_INTERRUPTS;
+        for i in 0..MAX_TRACKED_INTERRUPTS {
+            let slot = &self.slots[(start + i) % MAX_TRACKED_INTERRUPTS];
+            let owner = slot.interrupt_id.load(Ordering::Acquire);
+            if owner == interrupt_id {
+                return Some(slot);
+            }
+            if owner == FREE_SLOT {
+                if !claim {
+                    return None;
+                }
+                match slot.interrupt_id.compare_exchange(FREE_SLOT, interrupt_id, Ordering::AcqRel, Ordering::Acquire) {
+                    Ok(_) => return Some(slot),
+                    // 并发分配：其他核心刚好为同一中断占用了该槽位
+                    Err(current) if current == interrupt_id => return Some(slot),
+                    Err(_) => continue,
+                }
+            }
+        }
+        None
+    }
+
+    /// 记录一次中断延迟(微秒)
+    pub fn record(&self, interrupt_id: u32, latency_us: u32) {
+        match self.slot(interrupt_id, true) {
+            Some(slot) => slot.record(latency_us),
+            None => {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// 中断的延迟统计，未触发过的中断返回None
+    pub fn stats(&self, interrupt_id: u32) -> Option<InterruptStats> {
+        self.slot(interrupt_id, false).map(|slot| slot.snapshot(interrupt_id))
+    }
+
+    /// 清零中断的延迟统计，槽位保留给该中断
+    pub fn reset(&self, interrupt_id: u32) {
+        if let Some(slot) = self.slot(interrupt_id, false) {
+            slot.reset();
+        }
+    }
+
+    /// 已跟踪的中断数量
+    pub fn tracked(&self) -> usize {
+        self.slots
+            .iter()
+            .filter(|slot| slot.interrupt_id.load(Ordering::Acquire) != FREE_SLOT)
+            .count()
+    }
+
+    /// 因槽位耗尽而丢弃的样本数
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+impl InterruptPriorityState {
     /// 创建新的中断统计
     pub const fn new(base_priority: u8) -> Self {
         Self {
@@ -88,14 +366,16 @@ impl InterruptStats {
 impl DynamicPriorityManager {
     /// 创建新的动态优先级管理器
     pub const fn new() -> Self {
-        let mut stats = [InterruptStats::new(0x80); 1024]; // 默认优先级0x80
+        const DEFAULT: InterruptPriorityState = InterruptPriorityState::new(0x80); // 默认优先级0x80
+        let mut stats = [DEFAULT; 1024];
         
         // 设置关键中断的基础优先级
-        stats[27] = InterruptStats::new(0x20); // 定时器中断 - 高优先级
-        stats[32] = InterruptStats::new(0x40); // UART中断 - 中优先级
+        stats[27] = InterruptPriorityState::new(0x20); // 定时器中断 - 高优先级
+        stats[32] = InterruptPriorityState::new(0x40); // UART中断 - 中优先级
         
         Self {
             interrupt_stats: stats,
+            latency: LatencyTable::new(),
             system_load: AtomicU32::new(0),
             last_adjustment_time: AtomicU64::new(0),
             adaptive_mode: AtomicBool::new(true),
@@ -195,10 +475,11 @@ impl DynamicPriorityManager {
         gicd.add(0x400 + (interrupt_id as usize)).write_volatile(priority as u32);
     }
     
-    /// 记录中断发生
+    /// 记录中断发生，延迟单位为微秒
     pub fn record_interrupt(&self, interrupt_id: u32, latency: u32) {
         if interrupt_id < 1024 {
             self.interrupt_stats[interrupt_id as usize].update_stats(latency);
+            self.latency.record(interrupt_id, latency);
         }
     }
     
@@ -207,13 +488,19 @@ impl DynamicPriorityManager {
         self.adaptive_mode.store(enabled, Ordering::Release);
     }
     
-    /// 获取中断统计信息
+    /// 获取中断延迟统计，未触发过的中断返回None
     pub fn get_interrupt_stats(&self, interrupt_id: u32) -> Option<InterruptStats> {
-        if interrupt_id < 1024 {
-            Some(self.interrupt_stats[interrupt_id as usize].clone())
-        } else {
-            None
-        }
+        self.latency.stats(interrupt_id)
+    }
+    
+    /// 清零中断延迟统计
+    pub fn reset_interrupt_stats(&self, interrupt_id: u32) {
+        self.latency.reset(interrupt_id);
+    }
+    
+    /// 延迟统计表
+    pub fn latency_table(&self) -> &LatencyTable {
+        &self.latency
     }
 }
 
@@ -231,14 +518,39 @@ pub struct GicManager {
 impl GicManager {
     /// 创建新的GIC管理器
     pub const fn new() -> Self {
+        Self::with_bases(
+            0xFD40_0000, // GICD基地址
+            0xFD60_0000, // GICR基地址
+            0xFEC0_0000, // GICC基地址
+        )
+    }
+    
+    /// 使用指定寄存器基地址创建GIC管理器
+    pub const fn with_bases(distributor_base: u64, redistributor_base: u64, cpu_interface_base: u64) -> Self {
         Self {
-            distributor_base: 0xFD40_0000, // GICD基地址
-            redistributor_base: 0xFD60_0000, // GICR基地址
-            cpu_interface_base: 0xFEC0_0000, // GICC基地址
+            distributor_base,
+            redistributor_base,
+            cpu_interface_base,
             enabled_interrupts: [AtomicU32::new(0); 32],
         }
     }
     
+    /// 向目标核心位掩码发送SGI
+    /// 
+    /// GICD_SGIR：[23:16]为目标核心列表，[3:0]为SGI编号
+    pub unsafe fn send_sgi(&self, target_mask: u8, sgi_id: u8) {
+        if sgi_id >= 16 || target_mask == 0 {
+            return;
+        }
+        let sgir = (self.distributor_base + GICD_SGIR_OFFSET) as *mut u32;
+        sgir.write_volatile((target_mask as u32) << 16 | sgi_id as u32);
+    }
+    
+    /// 向目标核心发送IPI
+    pub unsafe fn send_ipi(&self, target: CoreId, ipi: Ipi) {
+        self.send_sgi(core_mask(target), ipi.sgi_id());
+    }
+    
     /// 初始化GIC
     pub unsafe fn init(&self) {
         // 初始化分发器
@@ -310,10 +622,10 @@ impl GicManager {
     /// 启用系统中断
     unsafe fn enable_system_interrupts(&self) {
         // 启用UART中断（ID=32）
-        self.enable_interrupt(32, InterruptPriority::DEFAULT);
+        self.enable_interrupt(32, InterruptPriority::from_band(PriorityBand::Uart));
         
         // 启用定时器中断（ID=27）
-        self.enable_interrupt(27, InterruptPriority::HIGHEST);
+        self.enable_interrupt(27, InterruptPriority::from_band(PriorityBand::Timer));
         
         // 启用其他系统中断
         // ...
@@ -323,8 +635,17 @@ impl GicManager {
     pub unsafe fn enable_interrupt(&self, interrupt_id: u32, priority: InterruptPriority) {
         let gicd = self.distributor_base as *mut u32;
         
+        if priority.loses_precision() {
+            crate::println!(
+                "警告: 中断{}的优先级0x{:02x}低位未实现，实际生效为0x{:02x}",
+                interrupt_id,
+                priority.value(),
+                priority.validate().value()
+            );
+        }
+        
         // 设置中断优先级
-        gicd.add(0x400 + (interrupt_id as usize)).write_volatile(priority.value() as u32);
+        gicd.add(0x400 + (interrupt_id as usize)).write_volatile(priority.validate().value() as u32);
         
         // 启用中断
         let reg_index = (interrupt_id / 32) as usize;
@@ -393,11 +714,29 @@ pub type InterruptHandler = fn(interrupt_id: u32) -> ();
 static mut INTERRUPT_HANDLERS: [Option<InterruptHandler>; 1024] = [None; 1024];
 
 /// 注册中断处理函数
+/// 
+/// 若该中断已注册处理函数则返回错误，需替换时使用`register_interrupt_handler_force`
 pub fn register_interrupt_handler(interrupt_id: u32, handler: InterruptHandler) -> Result<(), &'static str> {
     if interrupt_id >= 1024 {
         return Err("中断ID超出范围");
     }
     
+    unsafe {
+        if INTERRUPT_HANDLERS[interrupt_id as usize].is_some() {
+            return Err("中断处理函数已注册");
+        }
+        INTERRUPT_HANDLERS[interrupt_id as usize] = Some(handler);
+    }
+    
+    Ok(())
+}
+
+/// 强制注册中断处理函数（覆盖已有的处理函数）
+pub fn register_interrupt_handler_force(interrupt_id: u32, handler: InterruptHandler) -> Result<(), &'static str> {
+    if interrupt_id >= 1024 {
+        return Err("中断ID超出范围");
+    }
+    
     unsafe {
         INTERRUPT_HANDLERS[interrupt_id as usize] = Some(handler);
     }
@@ -405,6 +744,60 @@ pub fn register_interrupt_handler(interrupt_id: u32, handler: InterruptHandler)
     Ok(())
 }
 
+/// 注册外设中断处理函数并在GIC中按档位优先级启用该中断
+pub fn request_irq(interrupt_id: u32, handler: InterruptHandler, band: PriorityBand) -> Result<(), &'static str> {
+    register_interrupt_handler(interrupt_id, handler)?;
+
+    unsafe {
+        GIC_MANAGER.enable_interrupt(interrupt_id, InterruptPriority::from_band(band));
+    }
+
+    Ok(())
+}
+
+/// 注销中断处理函数
+pub fn unregister_interrupt_handler(interrupt_id: u32) -> Result<(), &'static str> {
+    if interrupt_id >= 1024 {
+        return Err("中断ID超出范围");
+    }
+    
+    unsafe {
+        INTERRUPT_HANDLERS[interrupt_id as usize] = None;
+    }
+    
+    Ok(())
+}
+
+/// 检查中断是否已注册处理函数
+pub fn has_interrupt_handler(interrupt_id: u32) -> bool {
+    interrupt_id < 1024 && unsafe { INTERRUPT_HANDLERS[interrupt_id as usize].is_some() }
+}
+
+/// 分发中断到已注册的处理函数，未处理时返回false
+fn dispatch_interrupt(interrupt_id: u32) -> bool {
+    if interrupt_id >= 1024 {
+        return false;
+    }
+    
+    match unsafe { INTERRUPT_HANDLERS[interrupt_id as usize] } {
+        Some(handler) => {
+            handler(interrupt_id);
+            true
+        }
+        None => false,
+    }
+}
+
+/// 获取中断延迟统计，未触发过的中断返回None
+pub fn get_interrupt_stats(interrupt_id: u32) -> Option<InterruptStats> {
+    DYNAMIC_PRIORITY_MANAGER.get_interrupt_stats(interrupt_id)
+}
+
+/// 清零中断延迟统计
+pub fn reset_interrupt_stats(interrupt_id: u32) {
+    DYNAMIC_PRIORITY_MANAGER.reset_interrupt_stats(interrupt_id);
+}
+
 /// 通用中断处理函数（增强版，支持动态优先级管理）
 #[no_mangle]
 pub extern "C" fn handle_interrupt() {
@@ -412,13 +805,9 @@ pub extern "C" fn handle_interrupt() {
         let start_time = crate::get_timer_count();
         let interrupt_id = GIC_MANAGER.get_interrupt_id();
         
-        if interrupt_id < 1024 {
-            if let Some(handler) = INTERRUPT_HANDLERS[interrupt_id as usize] {
-                handler(interrupt_id);
-            } else {
-                // 默认处理：记录未处理的中断
-                crate::println!("未处理的中断: ID={}", interrupt_id);
-            }
+        if interrupt_id < 1024 && !dispatch_interrupt(interrupt_id) {
+            // 默认处理：记录未处理的中断
+            crate::println!("未处理的中断: ID={}", interrupt_id);
         }
         
         // 完成中断处理
@@ -426,7 +815,8 @@ pub extern "C" fn handle_interrupt() {
         
         // 记录中断延迟并更新优先级
         let end_time = crate::get_timer_count();
-        let latency = (end_time - start_time) as u32; // 转换为微秒
+        let cycles = end_time.wrapping_sub(start_time);
+        let latency = (cycles * 1_000_000 / crate::get_timer_frequency().max(1)) as u32; // 转换为微秒
         
         DYNAMIC_PRIORITY_MANAGER.record_interrupt(interrupt_id, latency);
         
@@ -443,6 +833,9 @@ pub fn init() {
         // 注册默认中断处理函数
         register_interrupt_handler(27, timer_interrupt_handler).unwrap(); // 定时器中断
         register_interrupt_handler(32, uart_interrupt_handler).unwrap();   // UART中断
+        for ipi in Ipi::ALL {
+            register_interrupt_handler(ipi.sgi_id() as u32, ipi_handler(ipi)).unwrap();
+        }
         
         // 启用系统中断
         asm!("msr daifclr, #2"); // 启用IRQ
@@ -451,23 +844,239 @@ pub fn init() {
 
 /// 定时器中断处理函数
 fn timer_interrupt_handler(_interrupt_id: u32) {
-    // 处理定时器中断
-    crate::println!("定时器中断处理");
+    // 处理到期定时器并安排下一次节拍
+    crate::scheduler::on_timer_tick();
 }
 
 /// UART中断处理函数
 fn uart_interrupt_handler(_interrupt_id: u32) {
-    // 处理UART中断
-    crate::println!("UART中断处理");
+    crate::uart_rx_interrupt();
+}
+
+/// IPI的默认处理函数
+fn ipi_handler(ipi: Ipi) -> InterruptHandler {
+    match ipi {
+        Ipi::Reschedule => reschedule_ipi_handler,
+        Ipi::TlbFlush => tlb_flush_ipi_handler,
+        Ipi::CallFunction => call_function_ipi_handler,
+        Ipi::Stop => stop_ipi_handler,
+    }
+}
+
+/// 重新调度IPI处理函数
+fn reschedule_ipi_handler(_interrupt_id: u32) {
+    crate::scheduler::schedule();
+}
+
+/// TLB刷新IPI处理函数
+fn tlb_flush_ipi_handler(_interrupt_id: u32) {
+    unsafe {
+        asm!("tlbi vmalle1", "dsb ish", "isb");
+    }
+}
+
+/// 各核心待执行的函数（函数指针，0表示无）
+static PENDING_CALLS: [AtomicU64; 8] = [
+    AtomicU64::new(0), AtomicU64::new(0), AtomicU64::new(0), AtomicU64::new(0),
+    AtomicU64::new(0), AtomicU64::new(0), AtomicU64::new(0), AtomicU64::new(0),
+];
+
+/// 函数调用IPI处理函数
+fn call_function_ipi_handler(_interrupt_id: u32) {
+    let pending = PENDING_CALLS[CoreId::current() as usize].swap(0, Ordering::AcqRel);
+    if pending != 0 {
+        let function: fn() = unsafe { core::mem::transmute(pending as usize) };
+        function();
+    }
+}
+
+/// 停止IPI处理函数：屏蔽中断后停在低功耗等待中
+fn stop_ipi_handler(_interrupt_id: u32) {
+    unsafe {
+        asm!("msr daifset, #2");
+        loop {
+            asm!("wfi");
+        }
+    }
+}
+
+/// 在目标核心上执行函数，目标核心已有未执行的函数时返回错误
+pub fn call_function_on(target: CoreId, function: fn()) -> Result<(), &'static str> {
+    PENDING_CALLS[target as usize]
+        .compare_exchange(0, function as usize as u64, Ordering::AcqRel, Ordering::Acquire)
+        .map_err(|_| "目标核心有未完成的函数调用")?;
+    send_ipi(target, Ipi::CallFunction);
+    Ok(())
+}
+
+/// 向目标核心发送IPI
+pub fn send_ipi(target: CoreId, ipi: Ipi) {
+    unsafe {
+        GIC_MANAGER.send_ipi(target, ipi);
+    }
+}
+
+/// 已启动核心的SGI目标位掩码，可排除指定核心
+pub fn started_core_mask(exclude: Option<CoreId>) -> u8 {
+    crate::cpu::ALL_CORES
+        .iter()
+        .filter(|&&core| Some(core) != exclude)
+        .filter(|&&core| crate::cpu::CPU_MANAGER.get_core_state(core) != crate::cpu::CoreState::Off)
+        .fold(0, |mask, &core| mask | core_mask(core))
+}
+
+/// 向除当前核心外所有已启动的核心广播IPI
+pub fn broadcast_ipi(ipi: Ipi) {
+    let mask = started_core_mask(Some(CoreId::current()));
+    unsafe {
+        GIC_MANAGER.send_sgi(mask, ipi.sgi_id());
+    }
 }
 
 /// 发送软件中断
 pub unsafe fn send_software_interrupt(target_cpu: u8, interrupt_id: u8) {
-    let gicd = GIC_MANAGER.distributor_base as *mut u32;
-    
     // SGI中断ID范围：0-15
-    if interrupt_id < 16 {
-        let sgi_value = (target_cpu as u32) << 16 | (interrupt_id as u32);
-        gicd.add(0xF00).write_volatile(sgi_value); // ICDSGIR
+    GIC_MANAGER.send_sgi(target_cpu, interrupt_id);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::sync::atomic::{AtomicU32, Ordering};
+
+    static LAST_HANDLER: AtomicU32 = AtomicU32::new(0);
+
+    fn handler_a(_interrupt_id: u32) {
+        LAST_HANDLER.store(1, Ordering::Relaxed);
+    }
+
+    fn handler_b(_interrupt_id: u32) {
+        LAST_HANDLER.store(2, Ordering::Relaxed);
+    }
+
+    #[test]
+    fn test_register_conflict_and_force() {
+        // 重复注册失败，强制注册替换原处理函数
+        assert!(register_interrupt_handler(600, handler_a).is_ok());
+        assert_eq!(register_interrupt_handler(600, handler_b), Err("中断处理函数已注册"));
+
+        dispatch_interrupt(600);
+        assert_eq!(LAST_HANDLER.load(Ordering::Relaxed), 1);
+
+        assert!(register_interrupt_handler_force(600, handler_b).is_ok());
+        dispatch_interrupt(600);
+        assert_eq!(LAST_HANDLER.load(Ordering::Relaxed), 2);
+
+        assert!(register_interrupt_handler(1024, handler_a).is_err());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_unregister_restores_unhandled() {
+        // 注销后恢复为未处理状态，可重新注册
+        register_interrupt_handler(601, handler_a).unwrap();
+        assert!(has_interrupt_handler(601));
+
+        unregister_interrupt_handler(601).unwrap();
+        assert!(!has_interrupt_handler(601));
+        assert!(!dispatch_interrupt(601));
+        assert!(register_interrupt_handler(601, handler_b).is_ok());
+        assert!(unregister_interrupt_handler(1024).is_err());
+    }
+
+    #[test]
+    fn test_priorities_differing_in_low_bits_are_equivalent() {
+        // 仅在未实现低位上不同的优先级在硬件上等价
+        let requested = InterruptPriority::new(0x43);
+        let base = InterruptPriority::new(0x40);
+        assert!(requested.loses_precision());
+        assert!(!base.loses_precision());
+        assert!(requested.is_equivalent(&base));
+        assert_eq!(requested.validate(), base);
+        assert!(!base.is_equivalent(&InterruptPriority::new(0x48)));
+    }
+
+    #[test]
+    fn test_latency_histogram_buckets() {
+        // 一组分布在各区间的延迟样本落入对应的桶，并统计最小/最大/平均值
+        let table = LatencyTable::new();
+        for latency in [5, 9, 20, 75, 99, 300, 800, 1000] {
+            table.record(33, latency);
+        }
+
+        let stats = table.stats(33).unwrap();
+        assert_eq!(stats.count, 8);
+        assert_eq!(stats.histogram, [2, 1, 2, 1, 2]);
+        assert_eq!(stats.min_latency_us, 5);
+        assert_eq!(stats.max_latency_us, 1000);
+        assert_eq!(stats.mean_latency_us, 2308 / 8);
+
+        table.reset(33);
+        assert_eq!(table.stats(33).unwrap().count, 0);
+        assert_eq!(table.stats(33).unwrap().histogram, [0; LATENCY_BUCKETS]);
+    }
+
+    #[test]
+    fn test_only_fired_interrupts_are_tracked() {
+        // 只有触发过的中断占用槽位，槽位耗尽后丢弃新中断的样本
+        let table = LatencyTable::new();
+        assert!(table.stats(27).is_none());
+        table.record(27, 50);
+        table.record(27 + MAX_TRACKED_INTERRUPTS as u32, 50);
+        assert_eq!(table.tracked(), 2);
+        assert_eq!(table.stats(27).unwrap().count, 1);
+
+        for id in 0..MAX_TRACKED_INTERRUPTS as u32 + 10 {
+            table.record(id + 100, 1);
+        }
+        assert_eq!(table.tracked(), MAX_TRACKED_INTERRUPTS);
+        assert_eq!(table.dropped(), 12);
+    }
+
+    #[test]
+    fn test_send_ipi_writes_sgir_word() {
+        // 模拟寄存器区域：GICD_SGIR写入目标核心位掩码与SGI编号
+        let mut regs = [0u32; 0x400];
+        let gic = GicManager::with_bases(regs.as_mut_ptr() as u64, 0, 0);
+        let sgir = (GICD_SGIR_OFFSET / 4) as usize;
+
+        unsafe { gic.send_ipi(CoreId::A55_1, Ipi::CallFunction) };
+        assert_eq!(regs[sgir], (1 << 5) << 16 | 2);
+
+        unsafe { gic.send_ipi(CoreId::A76_0, Ipi::Stop) };
+        assert_eq!(regs[sgir], 1 << 16 | 3);
+
+        // 空目标列表不写寄存器
+        unsafe { gic.send_sgi(0, Ipi::TlbFlush.sgi_id()) };
+        assert_eq!(regs[sgir], 1 << 16 | 3);
+    }
+
+    #[test]
+    fn test_ipi_sgi_ids_are_distinct() {
+        // 每种IPI占用不同的保留SGI编号
+        for (i, a) in Ipi::ALL.iter().enumerate() {
+            assert!(a.sgi_id() < 16);
+            for b in &Ipi::ALL[i + 1..] {
+                assert_ne!(a.sgi_id(), b.sgi_id());
+            }
+        }
+    }
+
+    #[test]
+    fn test_band_ordering_preserved() {
+        // 档位优先级在屏蔽低位后仍严格按 定时器 > NPU > 音频 > 串口 排列
+        let bands = [
+            PriorityBand::Timer,
+            PriorityBand::NpuDone,
+            PriorityBand::Audio,
+            PriorityBand::Uart,
+            PriorityBand::Background,
+        ];
+        for pair in bands.windows(2) {
+            let higher = InterruptPriority::from_band(pair[0]);
+            let lower = InterruptPriority::from_band(pair[1]);
+            assert!(pair[0] < pair[1]);
+            assert!(!higher.loses_precision());
+            assert!(higher.validate().value() < lower.validate().value());
+        }
+    }
+}
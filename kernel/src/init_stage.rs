@@ -0,0 +1,123 @@
+//! StarryOS - 初始化阶段模块
+//!
+//! 记录内核、驱动、AI各子系统的初始化进度，防止在前置子系统就绪前使用
+
+use core::sync::atomic::{AtomicU8, Ordering};
+
+/// 初始化阶段
+///
+/// 各阶段必须按顺序完成：内核 → 驱动 → AI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u8)]
+pub enum InitStage {
+    Uninitialized = 0,
+    Kernel = 1,
+    Drivers = 2,
+    Ai = 3,
+}
+
+impl InitStage {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            1 => InitStage::Kernel,
+            2 => InitStage::Drivers,
+            3 => InitStage::Ai,
+            _ => InitStage::Uninitialized,
+        }
+    }
+}
+
+/// 初始化阶段跟踪器
+pub struct InitTracker {
+    stage: AtomicU8,
+}
+
+impl InitTracker {
+    /// 创建新的跟踪器（未初始化）
+    pub const fn new() -> Self {
+        Self::at(InitStage::Uninitialized)
+    }
+
+    /// 创建已完成到指定阶段的跟踪器
+    pub const fn at(stage: InitStage) -> Self {
+        Self {
+            stage: AtomicU8::new(stage as u8),
+        }
+    }
+
+    /// 当前已完成的阶段
+    pub fn current(&self) -> InitStage {
+        InitStage::from_u8(self.stage.load(Ordering::Acquire))
+    }
+
+    /// 标记阶段完成
+    ///
+    /// 前一阶段未完成时返回错误；重复完成同一阶段视为成功
+    pub fn complete(&self, stage: InitStage) -> Result<(), &'static str> {
+        let target = stage as u8;
+        match self.stage.compare_exchange(target.saturating_sub(1), target, Ordering::AcqRel, Ordering::Acquire) {
+            Ok(_) => Ok(()),
+            Err(current) if current >= target => Ok(()),
+            Err(_) => Err("初始化顺序错误：前置子系统未初始化"),
+        }
+    }
+
+    /// 检查指定阶段是否已完成
+    pub fn require(&self, stage: InitStage) -> Result<(), &'static str> {
+        if self.is_reached(stage) {
+            Ok(())
+        } else {
+            Err("子系统尚未初始化")
+        }
+    }
+
+    /// 指定阶段是否已完成
+    pub fn is_reached(&self, stage: InitStage) -> bool {
+        self.stage.load(Ordering::Acquire) >= stage as u8
+    }
+}
+
+/// 全局初始化阶段跟踪器
+pub static INIT_TRACKER: InitTracker = InitTracker::new();
+
+/// 标记全局初始化阶段完成，顺序错误时panic
+pub fn complete_stage(stage: InitStage) {
+    if let Err(message) = INIT_TRACKER.complete(stage) {
+        panic!("{}: {:?}", message, stage);
+    }
+}
+
+/// 检查全局初始化阶段
+pub fn require(stage: InitStage) -> Result<(), &'static str> {
+    INIT_TRACKER.require(stage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stages_complete_in_order() {
+        // 按顺序完成各阶段，重复完成同一阶段不报错
+        let tracker = InitTracker::new();
+        assert!(tracker.require(InitStage::Kernel).is_err());
+
+        tracker.complete(InitStage::Kernel).unwrap();
+        tracker.complete(InitStage::Drivers).unwrap();
+        tracker.complete(InitStage::Drivers).unwrap();
+        tracker.complete(InitStage::Ai).unwrap();
+
+        assert_eq!(tracker.current(), InitStage::Ai);
+        assert!(tracker.require(InitStage::Ai).is_ok());
+    }
+
+    #[test]
+    fn test_out_of_order_stage_rejected() {
+        // 跳过驱动阶段直接完成AI阶段返回错误，且不改变当前阶段
+        let tracker = InitTracker::new();
+        tracker.complete(InitStage::Kernel).unwrap();
+        assert!(tracker.complete(InitStage::Ai).is_err());
+        assert_eq!(tracker.current(), InitStage::Kernel);
+        assert!(tracker.require(InitStage::Drivers).is_err());
+    }
+}
@@ -22,15 +22,20 @@
 
 use core::panic::PanicInfo;
 use core::arch::asm;
+use core::sync::atomic::{AtomicU32, AtomicU8, AtomicUsize, Ordering};
 use common::{SystemError, Result as CommonResult};
 
 // 内核核心模块
 pub mod cpu;
 pub mod mmu;
+pub mod memory;
 pub mod gic;
 pub mod scheduler;
 pub mod syscall;
 pub mod rk3588;
+pub mod sync;
+pub mod init_stage;
+pub mod pmu;
 
 /// 内核初始化
 /// 
@@ -56,6 +61,7 @@ pub fn init() -> KernelInfo {
     // 阶段4：系统服务初始化
     init_system_services();
     
+    init_stage::complete_stage(init_stage::InitStage::Kernel);
     println!("StarryOS内核初始化完成");
     
     // 返回内核信息
@@ -136,6 +142,9 @@ fn init_uart() {
         
         // 启用发送和接收
         uart_base.add(12).write_volatile(0x03);
+        
+        // 使能接收和接收超时中断
+        uart_base.add(PL011_IMSC).write_volatile(PL011_INT_RX | PL011_INT_RT);
     }
 }
 
@@ -201,12 +210,19 @@ pub fn _print(args: core::fmt::Arguments) {
     let _ = writer.write_fmt(args);
 }
 
+/// 控制台输出端，系统调用经由它写出标准输出/标准错误
+pub trait ConsoleSink {
+    /// 写入原始数据
+    fn write_bytes(&mut self, bytes: &[u8]);
+}
+
 /// UART写入器
 struct UartWriter;
 
-impl Write for UartWriter {
-    fn write_str(&mut self, s: &str) -> core::fmt::Result {
-        for byte in s.bytes() {
+impl ConsoleSink for UartWriter {
+    /// 逐字节写入原始数据
+    fn write_bytes(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
             unsafe {
                 let uart_base = 0x0900_0000 as *mut u32;
                 
@@ -217,10 +233,139 @@ impl Write for UartWriter {
                 uart_base.add(0).write_volatile(byte as u32);
             }
         }
+    }
+}
+
+impl Write for UartWriter {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.write_bytes(s.as_bytes());
         Ok(())
     }
 }
 
+/// PL011寄存器（按32位字索引）
+const PL011_DR: usize = 0x00 / 4;
+const PL011_FR: usize = 0x18 / 4;
+const PL011_IMSC: usize = 0x38 / 4;
+const PL011_ICR: usize = 0x44 / 4;
+
+/// 标志寄存器：接收FIFO为空
+const PL011_FR_RXFE: u32 = 1 << 4;
+/// 中断位：接收
+const PL011_INT_RX: u32 = 1 << 4;
+/// 中断位：接收超时
+const PL011_INT_RT: u32 = 1 << 6;
+
+/// UART接收环形缓冲区容量
+pub const UART_RX_CAPACITY: usize = 256;
+
+/// UART接收环形缓冲区
+/// 
+/// 中断处理程序为唯一生产者，读取方为唯一消费者。缓冲区满时丢弃最旧的字节，
+/// 生产者与消费者都通过CAS推进读指针，因此丢弃与读取并发时不会重复或错读
+pub struct UartRx {
+    buffer: [AtomicU8; UART_RX_CAPACITY],
+    /// 读指针（单调递增，取模得到槽位）
+    head: AtomicUsize,
+    /// 写指针（单调递增，取模得到槽位）
+    tail: AtomicUsize,
+    overruns: AtomicU32,
+}
+
+impl UartRx {
+    /// 创建空的接收缓冲区
+    pub const fn new() -> Self {
+        const EMPTY: AtomicU8 = AtomicU8::new(0);
+        Self {
+            buffer: [EMPTY; UART_RX_CAPACITY],
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+            overruns: AtomicU32::new(0),
+        }
+    }
+    
+    /// 写入接收到的字节（仅由中断处理程序调用），缓冲区满时丢弃最旧的字节
+    pub fn push(&self, byte: u8) {
+        let tail = self.tail.load(Ordering::Relaxed);
+        let head = self.head.load(Ordering::Acquire);
+        if tail.wrapping_sub(head) >= UART_RX_CAPACITY {
+            // 读取方可能同时取走了该字节，此时无需丢弃
+            if self.head
+                .compare_exchange(head, head.wrapping_add(1), Ordering::AcqRel, Ordering::Acquire)
+                .is_ok()
+            {
+                self.overruns.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        self.buffer[tail % UART_RX_CAPACITY].store(byte, Ordering::Relaxed);
+        self.tail.store(tail.wrapping_add(1), Ordering::Release);
+    }
+    
+    /// 读取一个字节，缓冲区为空时返回None
+    pub fn read_byte(&self) -> Option<u8> {
+        loop {
+            let head = self.head.load(Ordering::Acquire);
+            if head == self.tail.load(Ordering::Acquire) {
+                return None;
+            }
+            let byte = self.buffer[head % UART_RX_CAPACITY].load(Ordering::Relaxed);
+            // 读指针被生产者推进说明该字节已被丢弃，重新读取
+            if self.head
+                .compare_exchange(head, head.wrapping_add(1), Ordering::AcqRel, Ordering::Acquire)
+                .is_ok()
+            {
+                return Some(byte);
+            }
+        }
+    }
+    
+    /// 读取一行到`buf`，遇到换行符（不写入`buf`）、缓冲区为空或`buf`写满时停止，返回写入的字节数
+    pub fn read_line(&self, buf: &mut [u8]) -> usize {
+        let mut count = 0;
+        while count < buf.len() {
+            match self.read_byte() {
+                Some(b'\n') | None => break,
+                Some(byte) => {
+                    buf[count] = byte;
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+    
+    /// 缓冲区中待读取的字节数
+    pub fn len(&self) -> usize {
+        self.tail.load(Ordering::Acquire).wrapping_sub(self.head.load(Ordering::Acquire))
+    }
+    
+    /// 缓冲区是否为空
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    
+    /// 因缓冲区满而丢弃的字节数
+    pub fn overrun_count(&self) -> u32 {
+        self.overruns.load(Ordering::Relaxed)
+    }
+}
+
+/// 控制台UART接收缓冲区
+pub static UART_RX: UartRx = UartRx::new();
+
+/// UART接收中断处理：排空接收FIFO后清除接收中断标志
+pub fn uart_rx_interrupt() {
+    unsafe {
+        let uart_base = 0x0900_0000 as *mut u32;
+        
+        while uart_base.add(PL011_FR).read_volatile() & PL011_FR_RXFE == 0 {
+            UART_RX.push(uart_base.add(PL011_DR).read_volatile() as u8);
+        }
+        
+        uart_base.add(PL011_ICR).write_volatile(PL011_INT_RX | PL011_INT_RT);
+    }
+}
+
 /// 系统延迟函数（毫秒级）
 /// 
 /// # 参数
@@ -281,6 +426,45 @@ pub fn get_timer_frequency() -> u64 {
     freq
 }
 
+/// 将定时器计数换算为微秒
+///
+/// 频率为0（定时器未配置）时返回0，避免除零
+pub fn ticks_to_micros(count: u64, frequency: u64) -> u64 {
+    if frequency == 0 {
+        return 0;
+    }
+    (count as u128 * 1_000_000 / frequency as u128) as u64
+}
+
+/// 获取自启动以来的微秒数，定时器未配置时返回0
+pub fn uptime_micros() -> u64 {
+    ticks_to_micros(get_timer_count(), get_timer_frequency())
+}
+
+/// 获取自启动以来的毫秒数，定时器未配置时返回0
+pub fn uptime_millis() -> u64 {
+    uptime_micros() / 1000
+}
+
+/// 电池电量尚未上报时的哨兵值
+const BATTERY_LEVEL_UNKNOWN: u8 = u8::MAX;
+
+/// 电量计驱动最近一次上报的电池电量（百分比）
+static BATTERY_LEVEL: AtomicU8 = AtomicU8::new(BATTERY_LEVEL_UNKNOWN);
+
+/// 由电量计驱动上报电池电量，超过100按100处理
+pub fn report_battery_level(percent: u8) {
+    BATTERY_LEVEL.store(percent.min(100), Ordering::Relaxed);
+}
+
+/// 获取最近一次上报的电池电量（百分比），尚未上报时返回None
+pub fn battery_level() -> Option<u8> {
+    match BATTERY_LEVEL.load(Ordering::Relaxed) {
+        BATTERY_LEVEL_UNKNOWN => None,
+        percent => Some(percent),
+    }
+}
+
 /// 系统挂起（低功耗模式）
 /// 
 /// # 注意
@@ -306,6 +490,79 @@ pub fn reboot() -> ! {
     halt()
 }
 
+/// 恐慌后的处理动作
+#[derive(Debug, Clone, Copy)]
+pub enum PanicAction {
+    /// 停机等待调试
+    Halt,
+    /// 立即重启
+    Reboot,
+    /// 启动看门狗后停机，由看门狗超时复位（默认）
+    Watchdog,
+    /// 调用自定义恢复函数，返回后按看门狗处理
+    Callback(fn()),
+}
+
+/// 当前配置的恐慌处理动作
+static mut PANIC_ACTION: PanicAction = PanicAction::Watchdog;
+
+/// 是否正在处理恐慌（用于检测嵌套恐慌）
+static PANIC_IN_PROGRESS: core::sync::atomic::AtomicBool = core::sync::atomic::AtomicBool::new(false);
+
+/// 看门狗基地址
+const WDT_BASE: usize = 0xFEAF_0000;
+
+/// 设置恐慌处理动作
+pub fn set_panic_action(action: PanicAction) {
+    unsafe {
+        PANIC_ACTION = action;
+    }
+}
+
+/// 获取当前配置的恐慌处理动作
+pub fn panic_action() -> PanicAction {
+    unsafe { PANIC_ACTION }
+}
+
+/// 根据配置和是否嵌套恐慌决定实际执行的动作
+/// 
+/// 自定义回调中再次恐慌时不再调用回调，改用看门狗复位，避免无限递归
+pub fn resolve_panic_action(configured: PanicAction, nested: bool) -> PanicAction {
+    match configured {
+        PanicAction::Callback(_) if nested => PanicAction::Watchdog,
+        action => action,
+    }
+}
+
+/// 执行恐慌处理动作（在记录恐慌信息之后调用）
+pub fn handle_panic_action() -> ! {
+    let nested = PANIC_IN_PROGRESS.swap(true, core::sync::atomic::Ordering::SeqCst);
+    
+    match resolve_panic_action(panic_action(), nested) {
+        PanicAction::Halt => halt(),
+        PanicAction::Reboot => reboot(),
+        PanicAction::Watchdog => {
+            arm_watchdog_reset();
+            halt()
+        }
+        PanicAction::Callback(callback) => {
+            callback();
+            arm_watchdog_reset();
+            halt()
+        }
+    }
+}
+
+/// 启动看门狗，使系统在短超时后复位
+pub fn arm_watchdog_reset() {
+    unsafe {
+        let wdt = WDT_BASE as *mut u32;
+        wdt.add(1).write_volatile(0x0); // WDT_TORR: 最短超时
+        wdt.write_volatile(0x1);        // WDT_CR: 使能，超时直接复位
+        wdt.add(3).write_volatile(0x76); // WDT_CRR: 重新装载计数器
+    }
+}
+
 /// 恐慌处理函数
 #[panic_handler]
 fn panic(info: &PanicInfo) -> ! {
@@ -322,7 +579,7 @@ fn panic(info: &PanicInfo) -> ! {
         println!("错误信息: {}", message);
     }
     
-    halt()
+    handle_panic_action()
 }
 
 /// 内核入口点
@@ -356,6 +613,14 @@ pub enum SystemError {
     NotSupported = 6,
     Busy = 7,
     AlreadyExists = 8,
+    BadFileDescriptor = 9,
+}
+
+impl SystemError {
+    /// 系统调用返回的负错误码
+    pub const fn errno(self) -> i64 {
+        -(self as i64)
+    }
 }
 
 impl fmt::Display for SystemError {
@@ -370,6 +635,7 @@ impl fmt::Display for SystemError {
             SystemError::NotSupported => write!(f, "不支持的操作"),
             SystemError::Busy => write!(f, "设备繁忙"),
             SystemError::AlreadyExists => write!(f, "资源已存在"),
+            SystemError::BadFileDescriptor => write!(f, "文件描述符无效"),
         }
     }
 }
@@ -395,7 +661,7 @@ impl KernelInfo {
             description: DESCRIPTION,
             memory_size: 0x3C000000 - 0x80000, // 约1GB
             platform: "AArch64 (RK3588)",
-            uptime: get_timer_count() / get_timer_frequency(),
+            uptime: uptime_millis() / 1000,
             task_count: 0, // 实际实现应该从调度器获取
         }
     }
@@ -418,7 +684,13 @@ pub type SyscallResult<T> = Result<T, SystemError>;
 
 /// 系统调用接口
 pub mod syscall {
-    use super::SystemError;
+    use super::{ConsoleSink, SyscallResult, SystemError, UartWriter};
+    use crate::mmu::{PageTableManager, PAGE_SIZE, PAGE_TABLE_MANAGER};
+    
+    /// 标准输出
+    pub const STDOUT_FILENO: i32 = 1;
+    /// 标准错误
+    pub const STDERR_FILENO: i32 = 2;
     
     /// 系统调用编号
     #[repr(u32)]
@@ -434,6 +706,31 @@ pub mod syscall {
         Exec = 8,
         Wait = 9,
         Kill = 10,
+        ClockGetTime = 11,
+    }
+    
+    /// 单调时钟，自系统启动起计时
+    pub const CLOCK_MONOTONIC: u64 = 1;
+    
+    /// 时间值
+    #[repr(C)]
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+    pub struct TimeSpec {
+        pub secs: u64,
+        pub nanos: u64,
+    }
+    
+    impl TimeSpec {
+        /// 由定时器计数值和频率换算，频率为0时返回None
+        pub fn from_ticks(count: u64, frequency: u64) -> Option<Self> {
+            if frequency == 0 {
+                return None;
+            }
+            Some(Self {
+                secs: count / frequency,
+                nanos: ((count % frequency) as u128 * 1_000_000_000 / frequency as u128) as u64,
+            })
+        }
     }
     
     /// 系统调用处理函数
@@ -442,6 +739,7 @@ pub mod syscall {
             Syscall::Exit => sys_exit(args[0] as i32),
             Syscall::Read => sys_read(args[0] as i32, args[1] as *mut u8, args[2] as usize),
             Syscall::Write => sys_write(args[0] as i32, args[1] as *const u8, args[2] as usize),
+            Syscall::ClockGetTime => sys_clock_gettime(args[0], args[1] as *mut TimeSpec),
             _ => SystemError::NotSupported as u64,
         }
     }
@@ -456,8 +754,218 @@ pub mod syscall {
         SystemError::Success as u64
     }
     
+    /// 将系统调用结果编码为返回值：成功为非负值，失败为负错误码
+    fn syscall_return(result: SyscallResult<usize>) -> u64 {
+        match result {
+            Ok(value) => value as u64,
+            Err(error) => error.errno() as u64,
+        }
+    }
+    
+    /// 当前地址空间的页表管理器，MMU未初始化时返回错误
+    fn current_mmu() -> SyscallResult<&'static PageTableManager> {
+        unsafe { PAGE_TABLE_MANAGER.as_ref() }.ok_or(SystemError::NotSupported)
+    }
+    
+    /// 检查用户缓冲区非空且所覆盖的每一页都已在`mmu`中映射
+    fn validate_user_buffer(mmu: &PageTableManager, addr: u64, len: usize) -> SyscallResult<()> {
+        if addr == 0 {
+            return Err(SystemError::InvalidParameter);
+        }
+        if len == 0 {
+            return Ok(());
+        }
+        let end = addr.checked_add(len as u64).ok_or(SystemError::InvalidParameter)?;
+        let mut page = addr & !(PAGE_SIZE as u64 - 1);
+        while page < end {
+            unsafe { mmu.translate(page) }.ok_or(SystemError::InvalidParameter)?;
+            page += PAGE_SIZE as u64;
+        }
+        Ok(())
+    }
+    
     fn sys_write(fd: i32, buf: *const u8, count: usize) -> u64 {
-        // 实际实现应该向文件描述符写入数据
-        SystemError::Success as u64
+        syscall_return(current_mmu().and_then(|mmu| write_console(mmu, &mut UartWriter, fd, buf, count)))
+    }
+    
+    /// 向标准输出/标准错误写入数据，返回写入的字节数
+    fn write_console<S: ConsoleSink>(
+        mmu: &PageTableManager,
+        sink: &mut S,
+        fd: i32,
+        buf: *const u8,
+        count: usize,
+    ) -> SyscallResult<usize> {
+        if fd != STDOUT_FILENO && fd != STDERR_FILENO {
+            return Err(SystemError::BadFileDescriptor);
+        }
+        validate_user_buffer(mmu, buf as u64, count)?;
+        
+        let bytes = unsafe { core::slice::from_raw_parts(buf, count) };
+        sink.write_bytes(bytes);
+        Ok(count)
     }
-}
\ No newline at end of file
+    
+    fn sys_clock_gettime(clock_id: u64, tp: *mut TimeSpec) -> u64 {
+        syscall_return(current_mmu().and_then(|mmu| clock_gettime(mmu, clock_id, tp)).map(|_| 0))
+    }
+    
+    /// 读取通用定时器，将时间写入用户提供的`TimeSpec`
+    fn clock_gettime(mmu: &PageTableManager, clock_id: u64, tp: *mut TimeSpec) -> SyscallResult<()> {
+        if clock_id != CLOCK_MONOTONIC {
+            return Err(SystemError::InvalidParameter);
+        }
+        let now = TimeSpec::from_ticks(crate::get_timer_count(), crate::get_timer_frequency())
+            .ok_or(SystemError::NotSupported)?;
+        validate_user_buffer(mmu, tp as u64, core::mem::size_of::<TimeSpec>())?;
+        
+        unsafe { tp.write_unaligned(now) };
+        Ok(())
+    }
+    
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::mmu::{test_page_table_manager, MemoryAttribute, MemoryPermission};
+        use alloc::vec::Vec;
+        
+        /// 记录写入内容的控制台输出端
+        #[derive(Default)]
+        struct RecordingSink(Vec<u8>);
+        
+        impl ConsoleSink for RecordingSink {
+            fn write_bytes(&mut self, bytes: &[u8]) {
+                self.0.extend_from_slice(bytes);
+            }
+        }
+        
+        /// 按恒等映射映射缓冲区所在的页
+        fn map_user(mmu: &mut PageTableManager, addr: u64, len: usize) {
+            let start = addr & !(PAGE_SIZE as u64 - 1);
+            let end = (addr + len as u64 + PAGE_SIZE as u64 - 1) & !(PAGE_SIZE as u64 - 1);
+            unsafe {
+                let _ = mmu.map_region(start, start, (end - start) as usize, MemoryAttribute::Normal, MemoryPermission::ReadWrite);
+            }
+        }
+        
+        #[test]
+        fn test_write_returns_byte_count() {
+            // 向stdout写入已映射的缓冲区，返回值等于输入长度，数据原样送到输出端
+            let mut mmu = test_page_table_manager();
+            let mut sink = RecordingSink::default();
+            let message = b"hello\n";
+            map_user(&mut mmu, message.as_ptr() as u64, message.len());
+            let result = write_console(&mmu, &mut sink, STDOUT_FILENO, message.as_ptr(), message.len());
+            assert_eq!(syscall_return(result), message.len() as u64);
+            assert_eq!(sink.0, message);
+        }
+        
+        #[test]
+        fn test_write_rejects_bad_fd_and_null_buffer() {
+            // 无效文件描述符、空缓冲区与未映射缓冲区返回负错误码，不产生输出
+            let mut mmu = test_page_table_manager();
+            let mut sink = RecordingSink::default();
+            let message = b"x";
+            let unmapped = write_console(&mmu, &mut sink, STDOUT_FILENO, message.as_ptr(), 1);
+            assert_eq!(syscall_return(unmapped) as i64, SystemError::InvalidParameter.errno());
+            
+            map_user(&mut mmu, message.as_ptr() as u64, message.len());
+            let bad_fd = write_console(&mmu, &mut sink, 3, message.as_ptr(), 1);
+            assert_eq!(syscall_return(bad_fd) as i64, SystemError::BadFileDescriptor.errno());
+            let null = write_console(&mmu, &mut sink, STDERR_FILENO, core::ptr::null(), 4);
+            assert_eq!(syscall_return(null) as i64, SystemError::InvalidParameter.errno());
+            assert!(sink.0.is_empty());
+        }
+        
+        #[test]
+        fn test_clock_gettime_is_monotonic() {
+            // 两次读取之间延迟，第二次读数严格大于第一次
+            let mut mmu = test_page_table_manager();
+            let mut first = TimeSpec::default();
+            let mut second = TimeSpec::default();
+            map_user(&mut mmu, &first as *const _ as u64, core::mem::size_of::<TimeSpec>());
+            map_user(&mut mmu, &second as *const _ as u64, core::mem::size_of::<TimeSpec>());
+            
+            assert_eq!(clock_gettime(&mmu, CLOCK_MONOTONIC, &mut first), Ok(()));
+            crate::delay(2);
+            assert_eq!(clock_gettime(&mmu, CLOCK_MONOTONIC, &mut second), Ok(()));
+            assert!(second > first);
+            
+            // 频率为0时无法换算
+            assert_eq!(TimeSpec::from_ticks(100, 0), None);
+            assert_eq!(TimeSpec::from_ticks(36_000_000, 24_000_000), Some(TimeSpec { secs: 1, nanos: 500_000_000 }));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recovery() {}
+
+    #[test]
+    fn test_panic_action_selection() {
+        // 非嵌套恐慌按配置执行
+        assert!(matches!(resolve_panic_action(PanicAction::Halt, false), PanicAction::Halt));
+        assert!(matches!(resolve_panic_action(PanicAction::Reboot, false), PanicAction::Reboot));
+        assert!(matches!(resolve_panic_action(PanicAction::Watchdog, false), PanicAction::Watchdog));
+        assert!(matches!(resolve_panic_action(PanicAction::Callback(recovery), false), PanicAction::Callback(_)));
+        assert!(matches!(panic_action(), PanicAction::Watchdog));
+    }
+
+    #[test]
+    fn test_ticks_to_micros_guards_zero_frequency() {
+        // 频率为0时返回0而不是除零，大计数值不溢出
+        assert_eq!(ticks_to_micros(24_000_000, 24_000_000), 1_000_000);
+        assert_eq!(ticks_to_micros(12_000, 24_000_000), 500);
+        assert_eq!(ticks_to_micros(123_456, 0), 0);
+        assert_eq!(ticks_to_micros(u64::MAX, 1_000_000), u64::MAX);
+    }
+
+    #[test]
+    fn test_battery_level_reported_by_gauge() {
+        // 未上报时没有电量读数，上报值超过100时截断
+        assert_eq!(battery_level(), None);
+        report_battery_level(42);
+        assert_eq!(battery_level(), Some(42));
+        report_battery_level(150);
+        assert_eq!(battery_level(), Some(100));
+    }
+
+    #[test]
+    fn test_uart_rx_read_line_stops_at_newline() {
+        // 读取一行在换行符处停止，剩余字节留给下一次读取
+        let rx = UartRx::new();
+        for &byte in b"ls -l\nps" {
+            rx.push(byte);
+        }
+        
+        let mut line = [0u8; 16];
+        assert_eq!(rx.read_line(&mut line), 5);
+        assert_eq!(&line[..5], b"ls -l");
+        assert_eq!(rx.read_line(&mut line), 2);
+        assert_eq!(&line[..2], b"ps");
+        assert_eq!(rx.read_byte(), None);
+    }
+    
+    #[test]
+    fn test_uart_rx_overrun_drops_oldest() {
+        // 缓冲区满后丢弃最旧的字节并计数
+        let rx = UartRx::new();
+        for i in 0..UART_RX_CAPACITY + 3 {
+            rx.push(i as u8);
+        }
+        assert_eq!(rx.overrun_count(), 3);
+        assert_eq!(rx.len(), UART_RX_CAPACITY);
+        assert_eq!(rx.read_byte(), Some(3));
+    }
+    
+    #[test]
+    fn test_nested_panic_skips_callback() {
+        // 回调中再次恐慌时改用看门狗，其他动作不变
+        assert!(matches!(resolve_panic_action(PanicAction::Callback(recovery), true), PanicAction::Watchdog));
+        assert!(matches!(resolve_panic_action(PanicAction::Reboot, true), PanicAction::Reboot));
+        assert!(matches!(resolve_panic_action(PanicAction::Halt, true), PanicAction::Halt));
+    }
+}
@@ -0,0 +1,301 @@
+//! 伙伴系统分配器
+//!
+//! 堆区域按相对堆起点的偏移拆分为2的幂大小的块，每种大小维护一条空闲链表；
+//! 分配时拆分更大的块，释放时与空闲的伙伴块逐级合并。
+//! 堆起点按页对齐，因此不超过页大小的对齐要求总能满足
+
+use core::alloc::{GlobalAlloc, Layout};
+use core::ptr;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use spin::Mutex;
+
+/// 最小块大小的阶（16字节，足以存放空闲链表指针）
+pub const MIN_ORDER: usize = 4;
+
+/// 支持的最大阶（不含）
+pub const MAX_ORDER: usize = 48;
+
+/// 页大小，分配器保证不超过页大小的对齐要求
+pub const PAGE_SIZE: usize = 4096;
+
+/// 支持的最大对齐
+pub const MAX_ALIGN: usize = PAGE_SIZE;
+
+/// 空闲块头，直接存放在空闲内存中
+struct FreeBlock {
+    next: *mut FreeBlock,
+}
+
+/// 伙伴系统堆状态
+struct BuddyHeap {
+    free_lists: [*mut FreeBlock; MAX_ORDER],
+    /// 堆起点（页对齐），伙伴关系按相对该起点的偏移计算
+    base: usize,
+    total: usize,
+}
+
+// 空闲链表指针只在持锁时访问
+unsafe impl Send for BuddyHeap {}
+
+/// 满足布局要求的块的阶
+fn block_order(layout: &Layout) -> usize {
+    let size = layout.size().max(layout.align()).max(1 << MIN_ORDER);
+    size.next_power_of_two().trailing_zeros() as usize
+}
+
+impl BuddyHeap {
+    const fn new() -> Self {
+        Self {
+            free_lists: [ptr::null_mut(); MAX_ORDER],
+            base: 0,
+            total: 0,
+        }
+    }
+
+    unsafe fn push(&mut self, addr: usize, order: usize) {
+        let block = addr as *mut FreeBlock;
+        (*block).next = self.free_lists[order];
+        self.free_lists[order] = block;
+    }
+
+    unsafe fn pop(&mut self, order: usize) -> Option<usize> {
+        let block = self.free_lists[order];
+        if block.is_null() {
+            return None;
+        }
+        self.free_lists[order] = (*block).next;
+        Some(block as usize)
+    }
+
+    /// 从空闲链表中移除指定块，块不在链表中时返回false
+    unsafe fn remove(&mut self, addr: usize, order: usize) -> bool {
+        let mut link: *mut *mut FreeBlock = &mut self.free_lists[order];
+        while !(*link).is_null() {
+            if *link as usize == addr {
+                *link = (**link).next;
+                return true;
+            }
+            link = &mut (**link).next;
+        }
+        false
+    }
+
+    /// 设置堆区域，按偏移对齐拆分为尽可能大的块加入空闲链表
+    unsafe fn init(&mut self, start: usize, end: usize) {
+        let min_block = 1 << MIN_ORDER;
+        self.base = (start + PAGE_SIZE - 1) & !(PAGE_SIZE - 1);
+        let size = end.saturating_sub(self.base) & !(min_block - 1);
+
+        let mut offset = 0;
+        while offset + min_block <= size {
+            let align_order = if offset == 0 { MAX_ORDER - 1 } else { offset.trailing_zeros() as usize };
+            let size_order = (usize::BITS - 1 - (size - offset).leading_zeros()) as usize;
+            let order = align_order.min(size_order).min(MAX_ORDER - 1);
+            self.push(self.base + offset, order);
+            self.total += 1 << order;
+            offset += 1 << order;
+        }
+    }
+
+    unsafe fn alloc(&mut self, layout: Layout) -> *mut u8 {
+        let order = block_order(&layout);
+        if order >= MAX_ORDER || layout.align() > MAX_ALIGN {
+            return ptr::null_mut();
+        }
+
+        let Some(found) = (order..MAX_ORDER).find(|&o| !self.free_lists[o].is_null()) else {
+            return ptr::null_mut();
+        };
+        let block = match self.pop(found) {
+            Some(block) => block,
+            None => return ptr::null_mut(),
+        };
+
+        // 逐级拆分，高半部分作为伙伴放回空闲链表
+        for split in (order..found).rev() {
+            self.push(block + (1 << split), split);
+        }
+
+        block as *mut u8
+    }
+
+    unsafe fn dealloc(&mut self, ptr: *mut u8, layout: Layout) {
+        let mut order = block_order(&layout);
+        let mut offset = ptr as usize - self.base;
+
+        // 伙伴空闲时合并为上一级的块
+        while order + 1 < MAX_ORDER {
+            let buddy = offset ^ (1 << order);
+            if !self.remove(self.base + buddy, order) {
+                break;
+            }
+            offset = offset.min(buddy);
+            order += 1;
+        }
+        self.push(self.base + offset, order);
+    }
+
+    fn largest_free_block(&self) -> usize {
+        (0..MAX_ORDER)
+            .rev()
+            .find(|&order| !self.free_lists[order].is_null())
+            .map_or(0, |order| 1 << order)
+    }
+}
+
+/// 伙伴系统分配器
+/// 
+/// 用量计数器为原子量，在分配/释放时更新，读取时无需持锁
+pub struct BuddyAllocator {
+    heap: Mutex<BuddyHeap>,
+    total: AtomicUsize,
+    /// 已分配块占用的字节数（按块大小）
+    used: AtomicUsize,
+    /// 已分配的请求字节数（按布局大小）
+    allocated: AtomicUsize,
+}
+
+impl BuddyAllocator {
+    /// 创建空分配器，需调用`init`添加堆区域后才能分配
+    pub const fn new() -> Self {
+        Self {
+            heap: Mutex::new(BuddyHeap::new()),
+            total: AtomicUsize::new(0),
+            used: AtomicUsize::new(0),
+            allocated: AtomicUsize::new(0),
+        }
+    }
+
+    /// 以`[start, start + size)`作为堆区域，起点向上按页对齐
+    ///
+    /// # Safety
+    /// 区域必须有效、可写，且在分配器生命周期内不被其他代码使用；只能调用一次
+    pub unsafe fn init(&self, start: usize, size: usize) {
+        let mut heap = self.heap.lock();
+        heap.init(start, start + size);
+        self.total.store(heap.total, Ordering::Release);
+    }
+
+    /// 堆区域总字节数
+    pub fn total(&self) -> usize {
+        self.total.load(Ordering::Acquire)
+    }
+
+    /// 已分配块占用的字节数
+    pub fn used(&self) -> usize {
+        self.used.load(Ordering::Acquire)
+    }
+
+    /// 当前已分配的请求字节数
+    pub fn allocated(&self) -> usize {
+        self.allocated.load(Ordering::Acquire)
+    }
+
+    /// 最大空闲块字节数
+    pub fn largest_free_block(&self) -> usize {
+        self.heap.lock().largest_free_block()
+    }
+
+    /// 分配统计：(已用字节, 空闲字节, 最大空闲块字节)
+    ///
+    /// 已用字节按实际占用的块大小计算
+    pub fn stats(&self) -> (usize, usize, usize) {
+        let heap = self.heap.lock();
+        let used = self.used();
+        (used, heap.total - used, heap.largest_free_block())
+    }
+}
+
+unsafe impl GlobalAlloc for BuddyAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let block = self.heap.lock().alloc(layout);
+        if !block.is_null() {
+            self.used.fetch_add(1 << block_order(&layout), Ordering::AcqRel);
+            self.allocated.fetch_add(layout.size(), Ordering::AcqRel);
+        }
+        block
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if !ptr.is_null() {
+            self.heap.lock().dealloc(ptr, layout);
+            self.used.fetch_sub(1 << block_order(&layout), Ordering::AcqRel);
+            self.allocated.fetch_sub(layout.size(), Ordering::AcqRel);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARENA_SIZE: usize = 64 * 1024;
+
+    #[repr(align(4096))]
+    struct Arena([u8; ARENA_SIZE]);
+
+    fn allocator(arena: &mut Arena) -> BuddyAllocator {
+        let allocator = BuddyAllocator::new();
+        unsafe { allocator.init(arena.0.as_mut_ptr() as usize, ARENA_SIZE) };
+        allocator
+    }
+
+    #[test]
+    fn test_interleaved_alloc_free_reuses_memory() {
+        // 交替分配释放：相同大小的重复分配复用同一块内存，已用字节随之增减
+        let mut arena = Arena([0; ARENA_SIZE]);
+        let heap = allocator(&mut arena);
+        let small = Layout::from_size_align(24, 8).unwrap();
+        let large = Layout::from_size_align(1000, 8).unwrap();
+
+        unsafe {
+            let a = heap.alloc(small);
+            let b = heap.alloc(large);
+            assert_eq!(heap.stats().0, 32 + 1024);
+            heap.dealloc(a, small);
+            let c = heap.alloc(small);
+            assert_eq!(a, c);
+
+            for _ in 0..100 {
+                let p = heap.alloc(large);
+                heap.dealloc(p, large);
+                assert_eq!(heap.alloc(large), p);
+                heap.dealloc(p, large);
+            }
+
+            heap.dealloc(b, large);
+            heap.dealloc(c, small);
+        }
+        assert_eq!(heap.stats(), (0, ARENA_SIZE, ARENA_SIZE));
+    }
+
+    #[test]
+    fn test_free_all_coalesces_to_full_block() {
+        // 页对齐分配满足对齐要求，全部释放后伙伴合并，最大空闲块恢复为整个堆
+        let mut arena = Arena([0; ARENA_SIZE]);
+        let heap = allocator(&mut arena);
+        let page = Layout::from_size_align(100, PAGE_SIZE).unwrap();
+        let word = Layout::from_size_align(8, 8).unwrap();
+
+        let mut blocks = [(ptr::null_mut(), word); 12];
+        unsafe {
+            for (i, block) in blocks.iter_mut().enumerate() {
+                let layout = if i % 3 == 0 { page } else { word };
+                *block = (heap.alloc(layout), layout);
+                assert!(!block.0.is_null());
+                assert_eq!(block.0 as usize % layout.align(), 0);
+            }
+            assert!(heap.stats().2 < ARENA_SIZE);
+
+            // 乱序释放
+            for i in (0..blocks.len()).filter(|i| i % 2 == 1).chain((0..blocks.len()).filter(|i| i % 2 == 0)) {
+                heap.dealloc(blocks[i].0, blocks[i].1);
+            }
+        }
+        assert_eq!(heap.stats(), (0, ARENA_SIZE, ARENA_SIZE));
+
+        // 超出堆容量的分配失败
+        let huge = Layout::from_size_align(ARENA_SIZE + 1, 8).unwrap();
+        assert!(unsafe { heap.alloc(huge) }.is_null());
+    }
+}
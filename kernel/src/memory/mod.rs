@@ -5,41 +5,120 @@
 #![no_std]
 
 pub mod dynamic_memory;
+pub mod buddy;
 
 use core::alloc::{GlobalAlloc, Layout};
-use core::ptr;
+use core::ptr::addr_of_mut;
+use spin::Once;
 
-/// 简单的堆分配器（用于演示）
-pub struct SimpleAllocator;
+pub use buddy::BuddyAllocator;
 
-unsafe impl GlobalAlloc for SimpleAllocator {
+/// 内核堆大小 (8MB)
+pub const KERNEL_HEAP_SIZE: usize = 8 * 1024 * 1024;
+
+/// 内核堆区域
+#[repr(align(4096))]
+struct HeapRegion([u8; KERNEL_HEAP_SIZE]);
+
+static mut KERNEL_HEAP: HeapRegion = HeapRegion([0; KERNEL_HEAP_SIZE]);
+
+/// 内核全局分配器，首次分配时以内核堆区域初始化伙伴系统分配器
+pub struct KernelAllocator {
+    heap: BuddyAllocator,
+    ready: Once,
+}
+
+impl KernelAllocator {
+    const fn new() -> Self {
+        Self {
+            heap: BuddyAllocator::new(),
+            ready: Once::new(),
+        }
+    }
+
+    /// 已初始化的伙伴系统分配器
+    pub fn heap(&self) -> &BuddyAllocator {
+        self.ready.call_once(|| unsafe {
+            self.heap.init(addr_of_mut!(KERNEL_HEAP) as usize, KERNEL_HEAP_SIZE);
+        });
+        &self.heap
+    }
+}
+
+unsafe impl GlobalAlloc for KernelAllocator {
     unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
-        // 简化实现：使用静态内存池
-        static mut HEAP: [u8; 1024 * 1024] = [0; 1024 * 1024]; // 1MB堆
-        static mut NEXT: usize = 0;
-        
-        let align = layout.align();
-        let size = layout.size();
-        
-        // 对齐处理
-        let start = (NEXT + align - 1) & !(align - 1);
-        
-        if start + size > HEAP.len() {
-            ptr::null_mut()
-        } else {
-            let ptr = &mut HEAP[start] as *mut u8;
-            NEXT = start + size;
-            ptr
+        self.heap().alloc(layout)
+    }
+    
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.heap().dealloc(ptr, layout)
+    }
+}
+
+/// 内核堆分配器；主机单元测试使用系统分配器，不经过伙伴堆
+#[cfg_attr(not(test), global_allocator)]
+static ALLOCATOR: KernelAllocator = KernelAllocator::new();
+
+/// 堆分配统计：(已用字节, 空闲字节, 最大空闲块字节)
+pub fn stats() -> (usize, usize, usize) {
+    ALLOCATOR.heap().stats()
+}
+
+/// 每MB字节数
+const BYTES_PER_MB: f32 = 1024.0 * 1024.0;
+
+/// 内存使用报告
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MemoryReport {
+    /// 当前已分配字节数
+    pub used_bytes: usize,
+    /// 堆总字节数
+    pub total_bytes: usize,
+    /// 外部碎片率(0-1)：空闲内存中不属于最大空闲块的比例
+    pub fragmentation: f32,
+}
+
+impl MemoryReport {
+    /// 生成指定分配器的使用报告
+    pub fn of(heap: &BuddyAllocator) -> Self {
+        let (_, free, largest) = heap.stats();
+        Self {
+            used_bytes: heap.allocated(),
+            total_bytes: heap.total(),
+            fragmentation: if free == 0 { 0.0 } else { 1.0 - largest as f32 / free as f32 },
         }
     }
     
-    unsafe fn dealloc(&self, _ptr: *mut u8, _layout: Layout) {
-        // 简化实现：不进行实际释放
+    /// 已分配内存 (MB)
+    pub fn used_mb(&self) -> f32 {
+        self.used_bytes as f32 / BYTES_PER_MB
     }
+    
+    /// 堆总大小 (MB)
+    pub fn total_mb(&self) -> f32 {
+        self.total_bytes as f32 / BYTES_PER_MB
+    }
+}
+
+/// 当前已分配的字节数
+pub fn get_usage_bytes() -> usize {
+    ALLOCATOR.heap().allocated()
 }
 
-#[global_allocator]
-static ALLOCATOR: SimpleAllocator = SimpleAllocator;
+/// 当前已分配的内存 (MB)
+pub fn get_usage_mb() -> f32 {
+    get_usage_bytes() as f32 / BYTES_PER_MB
+}
+
+/// 内核堆总大小 (MB)
+pub fn get_total_mb() -> f32 {
+    ALLOCATOR.heap().total() as f32 / BYTES_PER_MB
+}
+
+/// 内核堆使用报告
+pub fn report() -> MemoryReport {
+    MemoryReport::of(ALLOCATOR.heap())
+}
 
 /// 内存分配函数（供其他模块使用）
 pub unsafe fn alloc(layout: Layout) -> *mut u8 {
@@ -58,4 +137,32 @@ pub fn init() {
     
     // 其他内存管理初始化
     println!("内存管理系统初始化完成");
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[repr(align(4096))]
+    struct Arena([u8; 32 * 1024]);
+
+    #[test]
+    fn test_report_tracks_allocation() {
+        // 分配已知大小后报告的已用内存至少增加该大小，释放后恢复
+        let mut arena = Arena([0; 32 * 1024]);
+        let heap = BuddyAllocator::new();
+        unsafe { heap.init(arena.0.as_mut_ptr() as usize, arena.0.len()) };
+        let before = MemoryReport::of(&heap);
+        assert_eq!(before.total_bytes, 32 * 1024);
+        assert_eq!(before.fragmentation, 0.0);
+
+        let layout = Layout::from_size_align(3000, 8).unwrap();
+        let ptr = unsafe { heap.alloc(layout) };
+        let after = MemoryReport::of(&heap);
+        assert!(after.used_bytes >= before.used_bytes + 3000);
+        assert!(after.used_mb() > before.used_mb());
+
+        unsafe { heap.dealloc(ptr, layout) };
+        assert_eq!(MemoryReport::of(&heap), before);
+    }
+}
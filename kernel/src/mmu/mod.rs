@@ -4,11 +4,112 @@
 
 #![no_std]
 
+use alloc::collections::BTreeMap;
+use core::alloc::Layout;
 use core::arch::asm;
 use core::mem::size_of;
+use core::ptr::addr_of_mut;
+
+use spin::{Mutex, Once};
 
 /// 页大小（4KB）
 pub const PAGE_SIZE: usize = 4096;
+/// 大页大小（L2块映射，2MB）
+pub const HUGE_PAGE_SIZE: usize = 2 * 1024 * 1024;
+/// 页表层级数（L0-L3）
+const PAGE_TABLE_LEVELS: usize = 4;
+/// 每个页表的表项数
+const ENTRIES_PER_TABLE: usize = PAGE_SIZE / size_of::<PageTableEntry>();
+/// 可缓存的已回收页表数
+const MAX_FREE_TABLES: usize = 16;
+/// 静态页表内存池的页数
+const PAGE_TABLE_POOL_PAGES: usize = 64;
+/// 表项中的物理地址位
+const ADDRESS_MASK: u64 = 0x0000_FFFF_FFFF_F000;
+/// 表项bit[1]：L0-L2为页表描述符，L3为页描述符；L2块描述符该位为0
+const TABLE_BIT: u64 = 1 << 1;
+
+/// 按页对齐的静态页表内存
+#[repr(C, align(4096))]
+pub struct PageTablePool(pub [u8; PAGE_SIZE * PAGE_TABLE_POOL_PAGES]);
+
+/// 页表内存池的分配状态
+struct ArenaState {
+    /// 内存起始地址
+    base: usize,
+    /// 内存字节数
+    len: usize,
+    /// 下一个未分配页相对起始地址的偏移
+    next: usize,
+    /// 已回收、可复用的页表
+    free_tables: [usize; MAX_FREE_TABLES],
+    free_count: usize,
+}
+
+/// 页表内存池
+///
+/// 从一段页对齐的内存中按页分配页表，回收的页表缓存后优先复用。
+/// 内核使用全局内存池，测试可为每个页表管理器创建独立的内存池
+pub struct PageTableArena {
+    state: Mutex<ArenaState>,
+}
+
+impl PageTableArena {
+    /// 以`pool`为页表内存创建内存池
+    pub fn new(pool: &'static mut PageTablePool) -> Self {
+        Self {
+            state: Mutex::new(ArenaState {
+                base: pool.0.as_mut_ptr() as usize,
+                len: pool.0.len(),
+                next: 0,
+                free_tables: [0; MAX_FREE_TABLES],
+                free_count: 0,
+            }),
+        }
+    }
+    
+    /// 分配一页页表内存，内存池耗尽时返回空指针
+    fn allocate(&self) -> *mut PageTableEntry {
+        let mut state = self.state.lock();
+        
+        // 优先复用已回收的页表
+        if state.free_count > 0 {
+            state.free_count -= 1;
+            return state.free_tables[state.free_count] as *mut PageTableEntry;
+        }
+        
+        if state.next >= state.len {
+            return core::ptr::null_mut();
+        }
+        let table = (state.base + state.next) as *mut PageTableEntry;
+        state.next += PAGE_SIZE;
+        table
+    }
+    
+    /// 释放页表内存，清零后放入复用列表
+    unsafe fn release(&self, table: *mut PageTableEntry) {
+        for i in 0..ENTRIES_PER_TABLE {
+            *table.add(i) = PageTableEntry(0);
+        }
+        
+        // 复用列表已满时该页表不再复用
+        let mut state = self.state.lock();
+        if state.free_count < MAX_FREE_TABLES {
+            let index = state.free_count;
+            state.free_tables[index] = table as usize;
+            state.free_count += 1;
+        }
+    }
+}
+
+/// 内核页表使用的全局内存池
+fn global_arena() -> &'static PageTableArena {
+    static mut PAGE_TABLE_MEMORY: PageTablePool = PageTablePool([0; PAGE_SIZE * PAGE_TABLE_POOL_PAGES]);
+    static GLOBAL_ARENA: Once<PageTableArena> = Once::new();
+    
+    // 静态页表内存只在首次调用时被借出一次
+    GLOBAL_ARENA.call_once(|| PageTableArena::new(unsafe { &mut *addr_of_mut!(PAGE_TABLE_MEMORY) }))
+}
 
 /// 内存属性
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -19,12 +120,14 @@ pub enum MemoryAttribute {
 }
 
 /// 内存权限
+/// 
+/// 权限只由AP[2:1]编码，AP[2]置位表示只读；ReadWrite与ExecuteRead的编码相同
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MemoryPermission {
-    ReadOnly = 0b01,
-    ReadWrite = 0b11,
-    ExecuteOnly = 0b10,
-    ExecuteRead = 0b11,
+    ReadOnly,
+    ReadWrite,
+    ExecuteOnly,
+    ExecuteRead,
 }
 
 /// 页表项
@@ -64,16 +167,16 @@ impl PageTableEntry {
         // 设置权限
         match permission {
             MemoryPermission::ReadOnly => {
-                entry |= 0b01 << 6; // AP[2:1] = 01 (Read-only)
+                entry |= 0b11 << 6; // AP[2:1] = 11 (EL1/EL0只读)
             }
             MemoryPermission::ReadWrite => {
-                entry |= 0b11 << 6; // AP[2:1] = 11 (Read-write)
+                entry |= 0b01 << 6; // AP[2:1] = 01 (EL1/EL0读写)
             }
             MemoryPermission::ExecuteOnly => {
                 entry |= 0b10 << 6; // AP[2:1] = 10 (Execute-only)
             }
             MemoryPermission::ExecuteRead => {
-                entry |= 0b11 << 6; // AP[2:1] = 11 (Execute-read)
+                entry |= 0b01 << 6; // AP[2:1] = 01 (Execute-read)
             }
         }
         
@@ -88,23 +191,61 @@ impl PageTableEntry {
         Self(entry)
     }
     
+    /// 创建指向下一级页表的表描述符
+    pub fn table(table_addr: u64) -> Self {
+        let entry = Self::new(table_addr, MemoryAttribute::Normal, MemoryPermission::ReadWrite, true);
+        Self(entry.0 | TABLE_BIT)
+    }
+    
+    /// 创建L3页描述符
+    pub fn page(physical_addr: u64, attribute: MemoryAttribute, permission: MemoryPermission) -> Self {
+        Self(Self::new(physical_addr, attribute, permission, true).0 | TABLE_BIT)
+    }
+    
+    /// 创建L2块描述符（2MB，bit[1]为0）
+    pub fn block(physical_addr: u64, attribute: MemoryAttribute, permission: MemoryPermission) -> Self {
+        Self(Self::new(physical_addr, attribute, permission, true).0 & !TABLE_BIT)
+    }
+    
     /// 检查页表项是否有效
     pub fn is_valid(&self) -> bool {
         (self.0 & 1) != 0
     }
     
+    /// 检查是否为表描述符（L3中为页描述符）
+    pub fn is_table(&self) -> bool {
+        self.is_valid() && (self.0 & TABLE_BIT) != 0
+    }
+    
+    /// 检查是否为块描述符
+    pub fn is_block(&self) -> bool {
+        self.is_valid() && (self.0 & TABLE_BIT) == 0
+    }
+    
     /// 获取物理地址
     pub fn physical_address(&self) -> u64 {
-        (self.0 & 0x0000_FFFF_FFFF_F000) << 12
+        self.0 & ADDRESS_MASK
     }
     
     /// 获取内存属性
     pub fn memory_attribute(&self) -> MemoryAttribute {
-        let attr_bits = (self.0 >> 2) & 0b11;
-        match attr_bits {
-            0b00 => MemoryAttribute::Device,
-            0b01 => MemoryAttribute::Normal,
-            _ => MemoryAttribute::Normal,
+        let normal = (self.0 >> 2) & 1 != 0;
+        let cacheable = (self.0 >> 4) & 1 != 0;
+        match (normal, cacheable) {
+            (false, _) => MemoryAttribute::Device,
+            (true, true) => MemoryAttribute::Normal,
+            (true, false) => MemoryAttribute::NonCacheable,
+        }
+    }
+    
+    /// 获取内存权限
+    /// 
+    /// AP[2:1]无法区分ReadWrite与ExecuteRead，两者均解码为ReadWrite
+    pub fn memory_permission(&self) -> MemoryPermission {
+        match (self.0 >> 6) & 0b11 {
+            0b10 => MemoryPermission::ExecuteOnly,
+            0b11 => MemoryPermission::ReadOnly,
+            _ => MemoryPermission::ReadWrite,
         }
     }
 }
@@ -118,39 +259,243 @@ pub enum PageTableLevel {
     Level3 = 3, // 4KB pages
 }
 
+/// 页表遍历路径上的一级：页表及其中被访问的表项下标
+#[derive(Debug, Clone, Copy)]
+struct WalkStep {
+    table: *mut PageTableEntry,
+    index: usize,
+}
+
+/// 使所有核心上该虚拟地址所在页的TLB项失效
+/// 
+/// 先确保页表写入对页表遍历可见，失效完成后再同步指令流
+#[inline]
+fn invalidate_tlb_page(virtual_addr: u64) {
+    #[cfg(target_arch = "aarch64")]
+    unsafe {
+        asm!(
+            "dsb ishst",
+            "tlbi vae1is, {}",
+            "dsb ish",
+            "isb",
+            in(reg) (virtual_addr >> 12) & 0xFFF_FFFF_FFFF,
+            options(nostack)
+        );
+    }
+    #[cfg(not(target_arch = "aarch64"))]
+    let _ = virtual_addr;
+}
+
+/// 使所有核心上的全部EL1 TLB项及页表遍历缓存失效
+/// 
+/// 释放中间级页表前调用，确保没有核心仍通过缓存的上级表项访问该页表
+#[inline]
+fn invalidate_tlb_all() {
+    #[cfg(target_arch = "aarch64")]
+    unsafe {
+        asm!(
+            "dsb ishst",
+            "tlbi vmalle1is",
+            "dsb ish",
+            "isb",
+            options(nostack)
+        );
+    }
+}
+
+/// 检查页表是否已无有效表项
+unsafe fn table_is_empty(table: *const PageTableEntry) -> bool {
+    (0..ENTRIES_PER_TABLE).all(|i| !(*table.add(i)).is_valid())
+}
+
+/// 自下而上回收空页表
+/// 
+/// `path[0]`为根页表，之后依次为下一级页表。从最后一级开始，页表已无有效表项时
+/// 清除上一级中指向它的表项并交给`release`释放，遇到非空页表即停止；根页表不回收。
+/// `release`在上级表项清除后调用，须先使页表遍历缓存失效再释放页表。
+/// 使用至多`PAGE_TABLE_LEVELS - 1`次迭代的显式循环，不依赖递归。返回回收的页表数
+unsafe fn reclaim_empty_tables<F>(path: &[WalkStep], mut release: F) -> usize
+where
+    F: FnMut(*mut PageTableEntry),
+{
+    assert!(path.len() <= PAGE_TABLE_LEVELS, "页表遍历路径超过4级");
+    
+    let mut reclaimed = 0;
+    for level in (1..path.len()).rev() {
+        let table = path[level].table;
+        if !table_is_empty(table) {
+            break;
+        }
+        
+        let parent = path[level - 1];
+        *parent.table.add(parent.index) = PageTableEntry(0);
+        release(table);
+        reclaimed += 1;
+    }
+    reclaimed
+}
+
+/// 物理页引用信息
+#[derive(Debug, Clone, Copy)]
+struct FrameRef {
+    /// 引用该物理页的写时复制映射数
+    mappings: usize,
+    /// 是否由写时复制缺页从内核堆分配，最后一个引用释放时归还
+    allocated: bool,
+}
+
+/// 全局物理页引用表（物理页地址 -> 引用信息）
+/// 
+/// 由所有页表管理器共享，fork后父子进程对同一物理页的映射计入同一引用计数
+static FRAME_REFS: Mutex<BTreeMap<u64, FrameRef>> = Mutex::new(BTreeMap::new());
+
+/// 物理页的布局
+fn frame_layout() -> Layout {
+    Layout::from_size_align(PAGE_SIZE, PAGE_SIZE).unwrap()
+}
+
+/// 为物理页增加一个写时复制映射引用
+fn frame_acquire(frame: u64) {
+    FRAME_REFS.lock().entry(frame).or_insert(FrameRef { mappings: 0, allocated: false }).mappings += 1;
+}
+
+/// 释放物理页的一个引用，最后一个引用释放时移出引用表；该页由缺页复制分配时归还内存
+unsafe fn frame_release(frame: u64) {
+    let released = {
+        let mut refs = FRAME_REFS.lock();
+        match refs.get_mut(&frame) {
+            Some(entry) if entry.mappings > 1 => {
+                entry.mappings -= 1;
+                None
+            }
+            Some(_) => refs.remove(&frame),
+            None => None,
+        }
+    };
+    
+    if let Some(FrameRef { allocated: true, .. }) = released {
+        alloc::alloc::dealloc(frame as *mut u8, frame_layout());
+    }
+}
+
+/// 写时复制缺页时放弃对共享物理页的引用
+/// 
+/// 仍被其他映射共享时减少引用并返回true；已是唯一引用时保持不变并返回false
+fn frame_unshare(frame: u64) -> bool {
+    let mut refs = FRAME_REFS.lock();
+    match refs.get_mut(&frame) {
+        Some(entry) if entry.mappings > 1 => {
+            entry.mappings -= 1;
+            true
+        }
+        _ => false,
+    }
+}
+
 /// 页表管理器
 pub struct PageTableManager {
+    arena: &'static PageTableArena,
     root_table: *mut PageTableEntry,
     current_asid: u16,
+    tables_allocated: usize,
+    /// 写时复制页面（页起始虚拟地址 -> 原权限）
+    cow_pages: BTreeMap<u64, MemoryPermission>,
+    /// 持有物理页引用的页面（页起始虚拟地址 -> 物理页），解除映射或销毁时释放引用
+    frame_refs: BTreeMap<u64, u64>,
 }
 
 impl PageTableManager {
-    /// 创建新的页表管理器
+    /// 创建使用全局页表内存池的页表管理器
     pub unsafe fn new() -> Self {
+        Self::with_arena(global_arena())
+    }
+    
+    /// 创建从指定内存池分配页表的页表管理器
+    pub unsafe fn with_arena(arena: &'static PageTableArena) -> Self {
         // 分配页表内存（4KB对齐）
-        let root_table = Self::allocate_page_table() as *mut PageTableEntry;
+        let root_table = arena.allocate();
         
         Self {
+            arena,
             root_table,
             current_asid: 1,
+            tables_allocated: 1,
+            cow_pages: BTreeMap::new(),
+            frame_refs: BTreeMap::new(),
+        }
+    }
+    
+    /// 为本管理器分配一个页表
+    unsafe fn alloc_table(&mut self) -> Result<*mut PageTableEntry, &'static str> {
+        let table = self.arena.allocate();
+        if table.is_null() {
+            return Err("页表内存不足");
         }
+        self.tables_allocated += 1;
+        Ok(table)
     }
     
-    /// 分配页表内存
-    unsafe fn allocate_page_table() -> *mut u8 {
-        // 简单的页分配器，实际应该使用内核内存分配器
-        static mut PAGE_TABLE_MEMORY: [u8; PAGE_SIZE * 16] = [0; PAGE_SIZE * 16];
-        static mut NEXT_PAGE: usize = 0;
+    /// 释放已从上一级摘除的页表，先使页表遍历缓存失效
+    unsafe fn release_table(&self, table: *mut PageTableEntry) {
+        invalidate_tlb_all();
+        self.arena.release(table);
+    }
+    
+    /// 本管理器累计分配的页表数（含根页表）
+    pub fn tables_allocated(&self) -> usize {
+        self.tables_allocated
+    }
+    
+    /// 各级页表下标（L0-L3）
+    fn table_indices(virtual_addr: u64) -> [usize; PAGE_TABLE_LEVELS] {
+        [
+            ((virtual_addr >> 39) & 0x1FF) as usize,
+            ((virtual_addr >> 30) & 0x1FF) as usize,
+            ((virtual_addr >> 21) & 0x1FF) as usize,
+            ((virtual_addr >> 12) & 0x1FF) as usize,
+        ]
+    }
+    
+    /// 遍历到L2页表，返回L0-L2各级的遍历步骤；中间某级未映射时返回None
+    unsafe fn walk_to_l2(&self, virtual_addr: u64) -> Option<[WalkStep; 3]> {
+        let indices = Self::table_indices(virtual_addr);
+        let mut steps = [WalkStep { table: self.root_table, index: indices[0] }; 3];
         
-        let page_addr = &mut PAGE_TABLE_MEMORY[NEXT_PAGE] as *mut u8;
-        NEXT_PAGE += PAGE_SIZE;
+        for level in 1..3 {
+            let parent = steps[level - 1];
+            let entry = *parent.table.add(parent.index);
+            if !entry.is_table() {
+                return None;
+            }
+            steps[level] = WalkStep {
+                table: entry.physical_address() as *mut PageTableEntry,
+                index: indices[level],
+            };
+        }
+        Some(steps)
+    }
+    
+    /// 遍历到L2页表，缺失的L1/L2页表按需分配，返回L2表项所在位置
+    unsafe fn ensure_l2(&mut self, virtual_addr: u64) -> Result<WalkStep, &'static str> {
+        let indices = Self::table_indices(virtual_addr);
+        let mut table = self.root_table;
+        
+        for level in 0..2 {
+            let entry = &mut *table.add(indices[level]);
+            if !entry.is_valid() {
+                *entry = PageTableEntry::table(self.alloc_table()? as u64);
+            } else if !entry.is_table() {
+                return Err("映射冲突");
+            }
+            table = entry.physical_address() as *mut PageTableEntry;
+        }
         
-        // 确保4KB对齐
-        let aligned_addr = (page_addr as usize & !(PAGE_SIZE - 1)) as *mut u8;
-        aligned_addr
+        Ok(WalkStep { table, index: indices[2] })
     }
     
     /// 映射内存区域
+    /// 
+    /// 虚拟地址、物理地址与剩余大小均满足2MB对齐时直接使用L2块映射，否则映射4KB页面
     pub unsafe fn map_region(
         &mut self,
         virtual_addr: u64,
@@ -163,15 +508,45 @@ impl PageTableManager {
             return Err("地址未对齐");
         }
         
-        let page_count = (size + PAGE_SIZE - 1) / PAGE_SIZE;
+        let size = (size + PAGE_SIZE - 1) / PAGE_SIZE * PAGE_SIZE;
+        let mut offset = 0;
         
-        for i in 0..page_count {
-            let vaddr = virtual_addr + (i * PAGE_SIZE) as u64;
-            let paddr = physical_addr + (i * PAGE_SIZE) as u64;
+        while offset < size {
+            let vaddr = virtual_addr + offset as u64;
+            let paddr = physical_addr + offset as u64;
             
-            self.map_page(vaddr, paddr, attribute, permission)?;
+            let huge = HUGE_PAGE_SIZE as u64;
+            if vaddr % huge == 0 && paddr % huge == 0 && size - offset >= HUGE_PAGE_SIZE {
+                self.map_huge_page(vaddr, paddr, attribute, permission)?;
+                offset += HUGE_PAGE_SIZE;
+            } else {
+                self.map_page(vaddr, paddr, attribute, permission)?;
+                offset += PAGE_SIZE;
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// 以L2块描述符映射一个2MB大页
+    pub unsafe fn map_huge_page(
+        &mut self,
+        virtual_addr: u64,
+        physical_addr: u64,
+        attribute: MemoryAttribute,
+        permission: MemoryPermission,
+    ) -> Result<(), &'static str> {
+        if virtual_addr % HUGE_PAGE_SIZE as u64 != 0 || physical_addr % HUGE_PAGE_SIZE as u64 != 0 {
+            return Err("地址未按2MB对齐");
+        }
+        
+        let l2 = self.ensure_l2(virtual_addr)?;
+        let l2_entry = &mut *l2.table.add(l2.index);
+        if l2_entry.is_valid() {
+            return Err("区域已映射");
         }
         
+        *l2_entry = PageTableEntry::block(physical_addr, attribute, permission);
         Ok(())
     }
     
@@ -183,113 +558,225 @@ impl PageTableManager {
         attribute: MemoryAttribute,
         permission: MemoryPermission,
     ) -> Result<(), &'static str> {
-        let level0_index = (virtual_addr >> 39) & 0x1FF;
-        let level1_index = (virtual_addr >> 30) & 0x1FF;
-        let level2_index = (virtual_addr >> 21) & 0x1FF;
-        let level3_index = (virtual_addr >> 12) & 0x1FF;
-        
-        // 遍历页表层级
-        let mut current_table = self.root_table;
-        
-        // Level 0
-        let l0_entry = &mut *current_table.add(level0_index as usize);
-        if !l0_entry.is_valid() {
-            // 分配新的L1页表
-            let new_table = Self::allocate_page_table() as *mut PageTableEntry;
-            *l0_entry = PageTableEntry::new(
-                new_table as u64,
-                MemoryAttribute::Normal,
-                MemoryPermission::ReadWrite,
-                true,
-            );
-        }
-        
-        // Level 1
-        current_table = l0_entry.physical_address() as *mut PageTableEntry;
-        let l1_entry = &mut *current_table.add(level1_index as usize);
-        if !l1_entry.is_valid() {
-            // 分配新的L2页表
-            let new_table = Self::allocate_page_table() as *mut PageTableEntry;
-            *l1_entry = PageTableEntry::new(
-                new_table as u64,
-                MemoryAttribute::Normal,
-                MemoryPermission::ReadWrite,
-                true,
-            );
-        }
-        
-        // Level 2
-        current_table = l1_entry.physical_address() as *mut PageTableEntry;
-        let l2_entry = &mut *current_table.add(level2_index as usize);
+        let l2 = self.ensure_l2(virtual_addr)?;
+        let l2_entry = &mut *l2.table.add(l2.index);
         if !l2_entry.is_valid() {
             // 分配新的L3页表
-            let new_table = Self::allocate_page_table() as *mut PageTableEntry;
-            *l2_entry = PageTableEntry::new(
-                new_table as u64,
-                MemoryAttribute::Normal,
-                MemoryPermission::ReadWrite,
-                true,
-            );
+            *l2_entry = PageTableEntry::table(self.alloc_table()? as u64);
+        } else if l2_entry.is_block() {
+            return Err("已存在大页映射");
         }
         
         // Level 3 - 最终页表项
-        current_table = l2_entry.physical_address() as *mut PageTableEntry;
-        let l3_entry = &mut *current_table.add(level3_index as usize);
+        let l3_table = l2_entry.physical_address() as *mut PageTableEntry;
+        let level3_index = Self::table_indices(virtual_addr)[3];
+        *l3_table.add(level3_index) = PageTableEntry::page(physical_addr, attribute, permission);
         
-        *l3_entry = PageTableEntry::new(physical_addr, attribute, permission, true);
+        Ok(())
+    }
+    
+    /// 将L2块映射拆分为512个4KB页映射，保留原有属性与权限
+    unsafe fn split_huge_page(&mut self, l2: WalkStep) -> Result<(), &'static str> {
+        let l2_entry = &mut *l2.table.add(l2.index);
+        let block = *l2_entry;
+        let l3_table = self.alloc_table()?;
         
+        let flags = (block.0 & !ADDRESS_MASK) | TABLE_BIT;
+        for i in 0..ENTRIES_PER_TABLE {
+            let paddr = block.physical_address() + (i * PAGE_SIZE) as u64;
+            *l3_table.add(i) = PageTableEntry(flags | paddr);
+        }
+        
+        *l2_entry = PageTableEntry::table(l3_table as u64);
         Ok(())
     }
     
     /// 取消映射内存区域
+    /// 
+    /// 区域可混合包含大页与普通页；完整覆盖的大页整体解除，部分覆盖的大页先拆分为普通页
     pub unsafe fn unmap_region(&mut self, virtual_addr: u64, size: usize) -> Result<(), &'static str> {
-        let page_count = (size + PAGE_SIZE - 1) / PAGE_SIZE;
+        let end = virtual_addr + ((size + PAGE_SIZE - 1) / PAGE_SIZE * PAGE_SIZE) as u64;
+        let mut vaddr = virtual_addr;
         
-        for i in 0..page_count {
-            let vaddr = virtual_addr + (i * PAGE_SIZE) as u64;
-            self.unmap_page(vaddr)?;
+        while vaddr < end {
+            let whole_block = vaddr % HUGE_PAGE_SIZE as u64 == 0
+                && end - vaddr >= HUGE_PAGE_SIZE as u64
+                && self.walk_to_l2(vaddr).map_or(false, |steps| (*steps[2].table.add(steps[2].index)).is_block());
+            
+            if whole_block {
+                self.unmap_huge_page(vaddr)?;
+                vaddr += HUGE_PAGE_SIZE as u64;
+            } else {
+                self.unmap_page(vaddr)?;
+                vaddr += PAGE_SIZE as u64;
+            }
         }
         
         Ok(())
     }
     
-    /// 取消映射单个页面
+    /// 解除一个2MB大页映射
+    unsafe fn unmap_huge_page(&mut self, virtual_addr: u64) -> Result<(), &'static str> {
+        let path = self.walk_to_l2(virtual_addr).ok_or("页面未映射")?;
+        let l2 = path[2];
+        *l2.table.add(l2.index) = PageTableEntry(0);
+        invalidate_tlb_page(virtual_addr);
+        
+        // 回收因此变空的各级页表
+        reclaim_empty_tables(&path, |table| self.release_table(table));
+        Ok(())
+    }
+    
+    /// 取消映射单个页面，所在区域为大页时先拆分
     pub unsafe fn unmap_page(&mut self, virtual_addr: u64) -> Result<(), &'static str> {
-        let level0_index = (virtual_addr >> 39) & 0x1FF;
-        let level1_index = (virtual_addr >> 30) & 0x1FF;
-        let level2_index = (virtual_addr >> 21) & 0x1FF;
-        let level3_index = (virtual_addr >> 12) & 0x1FF;
+        let steps = self.walk_to_l2(virtual_addr).ok_or("页面未映射")?;
+        let l2 = steps[2];
+        let l2_entry = *l2.table.add(l2.index);
+        if !l2_entry.is_valid() {
+            return Err("页面未映射");
+        }
+        if l2_entry.is_block() {
+            self.split_huge_page(l2)?;
+        }
+        
+        // Level 3 - 最终页表项
+        let l3_table = (*l2.table.add(l2.index)).physical_address() as *mut PageTableEntry;
+        let level3_index = Self::table_indices(virtual_addr)[3];
         
-        // 遍历页表层级
-        let mut current_table = self.root_table;
+        // 清除页表项，其他核心的TLB项失效后才能释放物理页
+        *l3_table.add(level3_index) = PageTableEntry(0);
+        invalidate_tlb_page(virtual_addr);
         
-        // Level 0
-        let l0_entry = &mut *current_table.add(level0_index as usize);
-        if !l0_entry.is_valid() {
-            return Err("页面未映射");
+        // 释放该页持有的物理页引用
+        let page = virtual_addr & !(PAGE_SIZE as u64 - 1);
+        self.cow_pages.remove(&page);
+        if let Some(frame) = self.frame_refs.remove(&page) {
+            frame_release(frame);
         }
         
-        // Level 1
-        current_table = l0_entry.physical_address() as *mut PageTableEntry;
-        let l1_entry = &mut *current_table.add(level1_index as usize);
-        if !l1_entry.is_valid() {
-            return Err("页面未映射");
+        // 回收因此变空的各级页表
+        let path = [steps[0], steps[1], steps[2], WalkStep { table: l3_table, index: level3_index }];
+        reclaim_empty_tables(&path, |table| self.release_table(table));
+        
+        Ok(())
+    }
+    
+    /// 查询虚拟地址的映射
+    /// 
+    /// 依次遍历L0-L3（遇到L2块描述符时提前结束），返回保留页内偏移的物理地址
+    /// 以及该映射的属性与权限；任一级未映射时返回None
+    pub unsafe fn translate(&self, virtual_addr: u64) -> Option<(u64, MemoryAttribute, MemoryPermission)> {
+        let steps = self.walk_to_l2(virtual_addr)?;
+        let l2_entry = *steps[2].table.add(steps[2].index);
+        
+        let (entry, offset_mask) = if l2_entry.is_block() {
+            (l2_entry, HUGE_PAGE_SIZE as u64 - 1)
+        } else if l2_entry.is_table() {
+            let l3_table = l2_entry.physical_address() as *const PageTableEntry;
+            let l3_entry = *l3_table.add(Self::table_indices(virtual_addr)[3]);
+            if !l3_entry.is_valid() {
+                return None;
+            }
+            (l3_entry, PAGE_SIZE as u64 - 1)
+        } else {
+            return None;
+        };
+        
+        let physical_addr = (entry.physical_address() & !offset_mask) | (virtual_addr & offset_mask);
+        Some((physical_addr, entry.memory_attribute(), entry.memory_permission()))
+    }
+    
+    /// 定位虚拟地址所在的L3页表项，区域为大页时按`split`决定是否先拆分
+    unsafe fn page_entry(&mut self, virtual_addr: u64, split: bool) -> Option<*mut PageTableEntry> {
+        let steps = self.walk_to_l2(virtual_addr)?;
+        let l2 = steps[2];
+        let l2_entry = *l2.table.add(l2.index);
+        if l2_entry.is_block() && split {
+            self.split_huge_page(l2).ok()?;
         }
         
-        // Level 2
-        current_table = l1_entry.physical_address() as *mut PageTableEntry;
-        let l2_entry = &mut *current_table.add(level2_index as usize);
-        if !l2_entry.is_valid() {
-            return Err("页面未映射");
+        let l2_entry = *l2.table.add(l2.index);
+        if !l2_entry.is_table() {
+            return None;
         }
+        let entry = (l2_entry.physical_address() as *mut PageTableEntry).add(Self::table_indices(virtual_addr)[3]);
+        if (*entry).is_valid() { Some(entry) } else { None }
+    }
+    
+    /// 将区域标记为写时复制
+    /// 
+    /// 区域内可写页面改为只读并记入写时复制表，共享同一物理页的映射数计入全局引用计数；
+    /// 原本不可写的页面保持不变。区域内有未映射页面时返回错误
+    pub unsafe fn mark_cow(&mut self, virtual_addr: u64, size: usize) -> Result<(), &'static str> {
+        let start = virtual_addr & !(PAGE_SIZE as u64 - 1);
+        let end = virtual_addr + size as u64;
+        let mut vaddr = start;
         
-        // Level 3 - 最终页表项
-        current_table = l2_entry.physical_address() as *mut PageTableEntry;
-        let l3_entry = &mut *current_table.add(level3_index as usize);
+        while vaddr < end {
+            let entry = self.page_entry(vaddr, true).ok_or("页面未映射")?;
+            let permission = (*entry).memory_permission();
+            if permission == MemoryPermission::ReadWrite && !self.cow_pages.contains_key(&vaddr) {
+                let physical_addr = (*entry).physical_address();
+                *entry = PageTableEntry::page(physical_addr, (*entry).memory_attribute(), MemoryPermission::ReadOnly);
+                // 丢弃其他核心上仍可写的旧TLB项，之后的写入才会触发缺页
+                invalidate_tlb_page(vaddr);
+                self.cow_pages.insert(vaddr, permission);
+                if !self.frame_refs.contains_key(&vaddr) {
+                    frame_acquire(physical_addr);
+                    self.frame_refs.insert(vaddr, physical_addr);
+                }
+            }
+            vaddr += PAGE_SIZE as u64;
+        }
         
-        // 清除页表项
-        *l3_entry = PageTableEntry(0);
+        Ok(())
+    }
+    
+    /// 检查地址是否位于写时复制页面
+    pub fn is_cow(&self, virtual_addr: u64) -> bool {
+        self.cow_pages.contains_key(&(virtual_addr & !(PAGE_SIZE as u64 - 1)))
+    }
+    
+    /// 写时复制页面数
+    pub fn cow_page_count(&self) -> usize {
+        self.cow_pages.len()
+    }
+    
+    /// 处理写时复制缺页
+    /// 
+    /// 物理页仍被其他映射（包括其他页表管理器）共享时分配新页、复制内容并以原权限重新映射；
+    /// 最后一个写者直接恢复原物理页的写权限。调用者负责随后刷新TLB
+    pub unsafe fn handle_cow_fault(&mut self, virtual_addr: u64) -> Result<(), &'static str> {
+        let page = virtual_addr & !(PAGE_SIZE as u64 - 1);
+        let permission = *self.cow_pages.get(&page).ok_or("非写时复制页面")?;
+        let entry = self.page_entry(page, false).ok_or("页面未映射")?;
+        let original = (*entry).physical_address();
+        let attribute = (*entry).memory_attribute();
+        
+        let shared = FRAME_REFS.lock().get(&original).map_or(false, |entry| entry.mappings > 1);
+        let physical_addr = if shared {
+            // 先复制再放弃引用，避免其他写者在复制过程中修改原页
+            let copy = alloc::alloc::alloc(frame_layout());
+            if copy.is_null() {
+                return Err("内存分配失败");
+            }
+            core::ptr::copy_nonoverlapping(original as *const u8, copy, PAGE_SIZE);
+            
+            if frame_unshare(original) {
+                FRAME_REFS.lock().insert(copy as u64, FrameRef { mappings: 1, allocated: true });
+                self.frame_refs.insert(page, copy as u64);
+                copy as u64
+            } else {
+                // 复制期间其他写者已放弃引用，本映射成为唯一引用者
+                alloc::alloc::dealloc(copy, frame_layout());
+                original
+            }
+        } else {
+            original
+        };
         
+        *entry = PageTableEntry::page(physical_addr, attribute, permission);
+        self.cow_pages.remove(&page);
         Ok(())
     }
     
@@ -357,6 +844,15 @@ impl PageTableManager {
     }
 }
 
+impl Drop for PageTableManager {
+    /// 释放所有页面持有的物理页引用
+    fn drop(&mut self) {
+        for (_, frame) in core::mem::take(&mut self.frame_refs) {
+            unsafe { frame_release(frame) };
+        }
+    }
+}
+
 /// 全局页表管理器实例
 pub static mut PAGE_TABLE_MANAGER: Option<PageTableManager> = None;
 
@@ -401,6 +897,63 @@ pub unsafe fn init() {
     }
 }
 
+/// 缺页异常处理：写入写时复制页面时复制该页，其他缺页返回错误
+pub fn handle_page_fault(fault_addr: u64, is_write: bool) -> Result<(), &'static str> {
+    unsafe {
+        let mmu = PAGE_TABLE_MANAGER.as_mut().ok_or("MMU未初始化")?;
+        if !(is_write && mmu.is_cow(fault_addr)) {
+            return Err("非法内存访问");
+        }
+        mmu.handle_cow_fault(fault_addr)?;
+        mmu.flush_tlb();
+    }
+    Ok(())
+}
+
+/// ESR_EL1异常类别：来自低异常级别的数据中止
+const ESR_EC_DATA_ABORT_LOWER: u64 = 0x24;
+/// ESR_EL1异常类别：来自当前异常级别的数据中止
+const ESR_EC_DATA_ABORT_SAME: u64 = 0x25;
+/// ESR_EL1 ISS.WnR：由写访问引起
+const ESR_ISS_WNR: u64 = 1 << 6;
+/// DFSC中权限错误的编码（0b0011xx，低两位为页表级别）
+const DFSC_PERMISSION_FAULT: u64 = 0b0011_00;
+
+/// 解析数据中止的ESR_EL1，是权限错误时返回是否为写访问，其他异常返回None
+fn data_abort_access(esr: u64) -> Option<bool> {
+    let class = (esr >> 26) & 0x3F;
+    if class != ESR_EC_DATA_ABORT_LOWER && class != ESR_EC_DATA_ABORT_SAME {
+        return None;
+    }
+    if esr & 0b1111_00 != DFSC_PERMISSION_FAULT {
+        return None;
+    }
+    Some(esr & ESR_ISS_WNR != 0)
+}
+
+/// 同步异常处理函数：数据中止交给缺页处理，无法处理的异常视为致命错误
+#[no_mangle]
+pub extern "C" fn handle_sync_exception() {
+    let (esr, far): (u64, u64);
+    unsafe {
+        asm!("mrs {}, esr_el1", out(reg) esr);
+        asm!("mrs {}, far_el1", out(reg) far);
+    }
+    
+    let handled = data_abort_access(esr).map_or(false, |is_write| handle_page_fault(far, is_write).is_ok());
+    if !handled {
+        panic!("未处理的同步异常: ESR=0x{:x}, FAR=0x{:x}", esr, far);
+    }
+}
+
+/// 将虚拟地址转换为物理地址（使用全局页表管理器）
+pub fn virt_to_phys(virtual_addr: u64) -> Option<u64> {
+    unsafe {
+        let mmu = PAGE_TABLE_MANAGER.as_ref()?;
+        mmu.translate(virtual_addr).map(|(physical_addr, _, _)| physical_addr)
+    }
+}
+
 /// 启用MMU
 unsafe fn enable_mmu() {
     let sctlr: u64;
@@ -416,4 +969,277 @@ unsafe fn enable_mmu() {
         "isb",
         in(reg) new_sctlr
     );
-}
\ No newline at end of file
+}
+
+/// 创建使用独立页表内存池的页表管理器，测试之间互不共享页表内存
+#[cfg(test)]
+pub(crate) fn test_page_table_manager() -> PageTableManager {
+    use alloc::boxed::Box;
+    
+    let pool = Box::leak(Box::new(PageTablePool([0; PAGE_SIZE * PAGE_TABLE_POOL_PAGES])));
+    let arena = Box::leak(Box::new(PageTableArena::new(pool)));
+    unsafe { PageTableManager::with_arena(arena) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const EMPTY: PageTableEntry = PageTableEntry(0);
+    
+    /// 构造指向下一级页表的有效表项
+    fn table_entry(table: &mut [PageTableEntry; ENTRIES_PER_TABLE]) -> PageTableEntry {
+        PageTableEntry(table.as_mut_ptr() as u64 | 1)
+    }
+    
+    #[test]
+    fn test_reclaim_single_mapping_walks_all_levels() {
+        // 唯一映射解除后，L3、L2、L1三级页表依次回收，根页表保留
+        let mut l3 = [EMPTY; ENTRIES_PER_TABLE];
+        let mut l2 = [EMPTY; ENTRIES_PER_TABLE];
+        let mut l1 = [EMPTY; ENTRIES_PER_TABLE];
+        let mut root = [EMPTY; ENTRIES_PER_TABLE];
+        l2[7] = table_entry(&mut l3);
+        l1[3] = table_entry(&mut l2);
+        root[1] = table_entry(&mut l1);
+        
+        let path = [
+            WalkStep { table: root.as_mut_ptr(), index: 1 },
+            WalkStep { table: l1.as_mut_ptr(), index: 3 },
+            WalkStep { table: l2.as_mut_ptr(), index: 7 },
+            WalkStep { table: l3.as_mut_ptr(), index: 42 },
+        ];
+        let mut released = [core::ptr::null_mut(); PAGE_TABLE_LEVELS];
+        let mut count = 0;
+        let reclaimed = unsafe {
+            reclaim_empty_tables(&path, |table| {
+                released[count] = table;
+                count += 1;
+            })
+        };
+        
+        assert_eq!(reclaimed, 3);
+        assert_eq!(&released[..3], &[path[3].table, path[2].table, path[1].table]);
+        assert!(!root[1].is_valid());
+        assert!(unsafe { table_is_empty(root.as_ptr()) });
+    }
+    
+    #[test]
+    fn test_reclaim_stops_at_non_empty_table() {
+        // 上一级页表仍有其他映射时停止回收
+        let mut l3 = [EMPTY; ENTRIES_PER_TABLE];
+        let mut l2 = [EMPTY; ENTRIES_PER_TABLE];
+        let mut sibling = [EMPTY; ENTRIES_PER_TABLE];
+        l2[7] = table_entry(&mut l3);
+        l2[8] = table_entry(&mut sibling);
+        
+        let path = [
+            WalkStep { table: l2.as_mut_ptr(), index: 7 },
+            WalkStep { table: l3.as_mut_ptr(), index: 0 },
+        ];
+        let reclaimed = unsafe { reclaim_empty_tables(&path, |_| {}) };
+        
+        assert_eq!(reclaimed, 1);
+        assert!(!l2[7].is_valid());
+        assert!(l2[8].is_valid());
+    }
+    
+    /// 测试用虚拟/物理地址基址（1GB对齐）
+    const TEST_VA: u64 = 0x40_0000_0000;
+    const TEST_PA: u64 = 0x8000_0000;
+    
+    #[test]
+    fn test_map_2mb_uses_block_without_l3_table() {
+        // 2MB对齐区域以块描述符映射，只分配根、L1、L2三个页表
+        let mut mmu = test_page_table_manager();
+        unsafe {
+            mmu.map_region(TEST_VA, TEST_PA, HUGE_PAGE_SIZE, MemoryAttribute::Normal, MemoryPermission::ReadWrite).unwrap();
+        }
+        assert_eq!(mmu.tables_allocated(), 3);
+        
+        let steps = unsafe { mmu.walk_to_l2(TEST_VA).unwrap() };
+        let entry = unsafe { *steps[2].table.add(steps[2].index) };
+        assert!(entry.is_block());
+        assert_eq!(entry.physical_address(), TEST_PA);
+        
+        // 未按2MB对齐的物理地址退回4KB页映射
+        let mut paged = test_page_table_manager();
+        unsafe {
+            paged.map_region(TEST_VA, TEST_PA + PAGE_SIZE as u64, HUGE_PAGE_SIZE, MemoryAttribute::Normal, MemoryPermission::ReadWrite).unwrap();
+        }
+        assert_eq!(paged.tables_allocated(), 4);
+    }
+    
+    #[test]
+    fn test_unmap_region_mixing_blocks_and_pages() {
+        // 部分解除大页时拆分为L3页表，随后整段解除后所有中间页表被回收
+        let mut mmu = test_page_table_manager();
+        let size = HUGE_PAGE_SIZE + PAGE_SIZE;
+        unsafe {
+            mmu.map_region(TEST_VA, TEST_PA, size, MemoryAttribute::Normal, MemoryPermission::ReadWrite).unwrap();
+            assert_eq!(mmu.tables_allocated(), 4);
+            
+            mmu.unmap_region(TEST_VA + PAGE_SIZE as u64, PAGE_SIZE).unwrap();
+            assert_eq!(mmu.tables_allocated(), 5);
+            let steps = mmu.walk_to_l2(TEST_VA).unwrap();
+            let l3 = (*steps[2].table.add(steps[2].index)).physical_address() as *const PageTableEntry;
+            assert_eq!((*l3).physical_address(), TEST_PA);
+            assert!(!(*l3.add(1)).is_valid());
+            
+            mmu.unmap_region(TEST_VA, size).unwrap();
+            assert!(mmu.walk_to_l2(TEST_VA).is_none());
+            assert!(table_is_empty(mmu.root_table));
+        }
+    }
+    
+    #[test]
+    fn test_translate_preserves_offset_and_permission() {
+        // 页映射与大页映射均返回保留页内偏移的物理地址及原有权限，未映射地址返回None
+        let mut mmu = test_page_table_manager();
+        let page_va = TEST_VA + 0x1000_0000;
+        unsafe {
+            mmu.map_region(page_va, 0x1234_5000, 2 * PAGE_SIZE, MemoryAttribute::Device, MemoryPermission::ReadOnly).unwrap();
+            mmu.map_region(TEST_VA, TEST_PA, HUGE_PAGE_SIZE, MemoryAttribute::Normal, MemoryPermission::ExecuteRead).unwrap();
+        }
+        
+        let translate = |va: u64| unsafe { mmu.translate(va) };
+        assert_eq!(translate(page_va), Some((0x1234_5000, MemoryAttribute::Device, MemoryPermission::ReadOnly)));
+        assert_eq!(translate(page_va + 0x1abc).map(|t| t.0), Some(0x1234_6abc));
+        assert_eq!(translate(TEST_VA + 0x12_3457), Some((TEST_PA + 0x12_3457, MemoryAttribute::Normal, MemoryPermission::ReadWrite)));
+        assert_eq!(translate(page_va + 2 * PAGE_SIZE as u64), None);
+        assert_eq!(translate(TEST_VA + HUGE_PAGE_SIZE as u64), None);
+    }
+    
+    #[test]
+    fn test_entry_permission_round_trip() {
+        // 各权限与三种属性编码后均能被解码，ExecuteRead与ReadWrite编码相同
+        let permissions = [
+            (MemoryPermission::ReadOnly, MemoryPermission::ReadOnly),
+            (MemoryPermission::ReadWrite, MemoryPermission::ReadWrite),
+            (MemoryPermission::ExecuteOnly, MemoryPermission::ExecuteOnly),
+            (MemoryPermission::ExecuteRead, MemoryPermission::ReadWrite),
+        ];
+        let attributes = [MemoryAttribute::Normal, MemoryAttribute::Device, MemoryAttribute::NonCacheable];
+        for &(permission, decoded) in &permissions {
+            for &attribute in &attributes {
+                let entry = PageTableEntry::page(TEST_PA, attribute, permission);
+                assert_eq!(entry.memory_permission(), decoded);
+                assert_eq!(entry.memory_attribute(), attribute);
+                assert_eq!(entry.physical_address(), TEST_PA);
+            }
+        }
+        
+        // 只读页置位AP[2]，可写页清除AP[2]，写入只读页才会触发权限缺页
+        let ap = |permission| (PageTableEntry::page(TEST_PA, MemoryAttribute::Normal, permission).0 >> 6) & 0b11;
+        assert_eq!(ap(MemoryPermission::ReadOnly), 0b11);
+        assert_eq!(ap(MemoryPermission::ReadWrite), 0b01);
+    }
+    
+    /// 按页对齐的测试物理页
+    #[repr(C, align(4096))]
+    struct TestPage([u8; PAGE_SIZE]);
+    
+    #[test]
+    fn test_cow_faults_copy_until_last_writer() {
+        // 三个映射共享一个物理页：前两次写缺页各复制一份新页，最后一个写者复用原页
+        let mut frame = TestPage([0x5A; PAGE_SIZE]);
+        let shared = frame.0.as_mut_ptr() as u64;
+        let mut mmu = test_page_table_manager();
+        let vas = [TEST_VA, TEST_VA + PAGE_SIZE as u64, TEST_VA + 0x10_0000];
+        
+        unsafe {
+            for &va in &vas {
+                mmu.map_page(va, shared, MemoryAttribute::Normal, MemoryPermission::ReadWrite).unwrap();
+                mmu.mark_cow(va, PAGE_SIZE).unwrap();
+            }
+            assert_eq!(mmu.translate(vas[0]).unwrap().2, MemoryPermission::ReadOnly);
+            assert_eq!(mmu.cow_page_count(), 3);
+            
+            mmu.handle_cow_fault(vas[0] + 8).unwrap();
+            let (copy, _, permission) = mmu.translate(vas[0]).unwrap();
+            assert_ne!(copy, shared);
+            assert_eq!(permission, MemoryPermission::ReadWrite);
+            assert!((*(copy as *const TestPage)).0.iter().all(|&b| b == 0x5A));
+            
+            mmu.handle_cow_fault(vas[1]).unwrap();
+            assert_ne!(mmu.translate(vas[1]).unwrap().0, shared);
+            
+            mmu.handle_cow_fault(vas[2]).unwrap();
+            assert_eq!(mmu.translate(vas[2]).unwrap(), (shared, MemoryAttribute::Normal, MemoryPermission::ReadWrite));
+            assert_eq!(mmu.cow_page_count(), 0);
+        }
+    }
+    
+    /// 物理页在全局引用表中的映射数
+    fn frame_mappings(frame: u64) -> usize {
+        FRAME_REFS.lock().get(&frame).map_or(0, |entry| entry.mappings)
+    }
+    
+    #[test]
+    fn test_cow_shared_across_managers_after_fork() {
+        // fork后父子共享同一物理页：父进程写入得到副本，子进程随后成为唯一引用者并复用原页；
+        // 父进程解除映射后副本从引用表移除
+        let mut frame = TestPage([0x3C; PAGE_SIZE]);
+        let shared = frame.0.as_mut_ptr() as u64;
+        let mut parent = test_page_table_manager();
+        let mut child = test_page_table_manager();
+        
+        unsafe {
+            for mmu in [&mut parent, &mut child] {
+                mmu.map_page(TEST_VA, shared, MemoryAttribute::Normal, MemoryPermission::ReadWrite).unwrap();
+                mmu.mark_cow(TEST_VA, PAGE_SIZE).unwrap();
+            }
+            assert_eq!(frame_mappings(shared), 2);
+            
+            parent.handle_cow_fault(TEST_VA).unwrap();
+            let copy = parent.translate(TEST_VA).unwrap().0;
+            assert_ne!(copy, shared);
+            assert_eq!(frame_mappings(shared), 1);
+            assert_eq!(frame_mappings(copy), 1);
+            
+            child.handle_cow_fault(TEST_VA).unwrap();
+            assert_eq!(child.translate(TEST_VA).unwrap().0, shared);
+            
+            parent.unmap_page(TEST_VA).unwrap();
+            assert_eq!(frame_mappings(copy), 0);
+        }
+        
+        drop(child);
+        assert_eq!(frame_mappings(shared), 0);
+    }
+    
+    #[test]
+    fn test_data_abort_decoding() {
+        // 只有数据中止中的权限错误交给缺页处理，WnR位区分读写
+        let write_fault = (ESR_EC_DATA_ABORT_LOWER << 26) | ESR_ISS_WNR | 0b0011_11;
+        let read_fault = (ESR_EC_DATA_ABORT_SAME << 26) | 0b0011_01;
+        let translation_fault = (ESR_EC_DATA_ABORT_LOWER << 26) | ESR_ISS_WNR | 0b0001_11;
+        let instruction_abort = (0x20 << 26) | 0b0011_11;
+        assert_eq!(data_abort_access(write_fault), Some(true));
+        assert_eq!(data_abort_access(read_fault), Some(false));
+        assert_eq!(data_abort_access(translation_fault), None);
+        assert_eq!(data_abort_access(instruction_abort), None);
+    }
+    
+    #[test]
+    fn test_non_cow_fault_rejected() {
+        // 未标记写时复制的页面或只读页面不作复制处理
+        let mut mmu = test_page_table_manager();
+        unsafe {
+            mmu.map_page(TEST_VA, TEST_PA, MemoryAttribute::Normal, MemoryPermission::ReadOnly).unwrap();
+            mmu.mark_cow(TEST_VA, PAGE_SIZE).unwrap();
+            assert!(!mmu.is_cow(TEST_VA));
+            assert_eq!(mmu.handle_cow_fault(TEST_VA), Err("非写时复制页面"));
+            assert_eq!(mmu.mark_cow(TEST_VA + PAGE_SIZE as u64, PAGE_SIZE), Err("页面未映射"));
+        }
+    }
+    
+    #[test]
+    #[should_panic(expected = "页表遍历路径超过4级")]
+    fn test_reclaim_rejects_overlong_path() {
+        // 超过4级的遍历路径触发断言，而不是越界访问
+        let mut table = [EMPTY; ENTRIES_PER_TABLE];
+        let step = WalkStep { table: table.as_mut_ptr(), index: 0 };
+        unsafe { reclaim_empty_tables(&[step; PAGE_TABLE_LEVELS + 1], |_| {}) };
+    }
+}
@@ -0,0 +1,276 @@
+//! StarryOS - 性能监控单元(PMU)模块
+//!
+//! 访问ARMv8 PMU的周期计数器和事件计数器。PMU寄存器为每核私有，
+//! 计数结果只反映当前核心上的执行情况
+
+use core::arch::asm;
+
+/// 事件计数器数量（Cortex-A55/A76均为6个）
+pub const MAX_EVENT_COUNTERS: usize = 6;
+
+/// PMCR_EL0.E：使能所有计数器
+const PMCR_ENABLE: u64 = 1 << 0;
+/// PMCR_EL0.P：清零事件计数器
+const PMCR_EVENT_RESET: u64 = 1 << 1;
+/// PMCR_EL0.C：清零周期计数器
+const PMCR_CYCLE_RESET: u64 = 1 << 2;
+/// PMCNTENSET_EL0.C：周期计数器使能位
+const CYCLE_COUNTER_BIT: u64 = 1 << 31;
+
+/// PMU寄存器
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PmuRegister {
+    /// 控制寄存器 PMCR_EL0
+    Pmcr,
+    /// 计数器使能置位 PMCNTENSET_EL0
+    Pmcntenset,
+    /// 计数器使能清除 PMCNTENCLR_EL0
+    Pmcntenclr,
+    /// 周期计数器 PMCCNTR_EL0
+    Pmccntr,
+    /// 事件计数器选择 PMSELR_EL0
+    Pmselr,
+    /// 所选计数器的事件类型 PMXEVTYPER_EL0
+    Pmxevtyper,
+    /// 所选计数器的计数值 PMXEVCNTR_EL0
+    Pmxevcntr,
+}
+
+/// PMU事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u16)]
+pub enum PmuEvent {
+    /// L1数据缓存缺失
+    L1DCacheRefill = 0x03,
+    /// 退休指令数
+    InstructionsRetired = 0x08,
+    /// 分支预测失败
+    BranchMispredicted = 0x10,
+    /// L2数据缓存缺失
+    L2DCacheRefill = 0x17,
+}
+
+/// PMU寄存器访问后端
+pub trait PmuBackend {
+    /// 读取寄存器
+    fn read(&mut self, register: PmuRegister) -> u64;
+
+    /// 写入寄存器
+    fn write(&mut self, register: PmuRegister, value: u64);
+}
+
+/// 系统寄存器后端（直接访问当前核心的PMU）
+pub struct SystemRegisterBackend;
+
+impl PmuBackend for SystemRegisterBackend {
+    fn read(&mut self, register: PmuRegister) -> u64 {
+        let value: u64;
+        unsafe {
+            match register {
+                PmuRegister::Pmcr => asm!("mrs {}, pmcr_el0", out(reg) value),
+                PmuRegister::Pmcntenset => asm!("mrs {}, pmcntenset_el0", out(reg) value),
+                PmuRegister::Pmcntenclr => asm!("mrs {}, pmcntenclr_el0", out(reg) value),
+                PmuRegister::Pmccntr => asm!("mrs {}, pmccntr_el0", out(reg) value),
+                PmuRegister::Pmselr => asm!("mrs {}, pmselr_el0", out(reg) value),
+                PmuRegister::Pmxevtyper => asm!("mrs {}, pmxevtyper_el0", out(reg) value),
+                PmuRegister::Pmxevcntr => asm!("mrs {}, pmxevcntr_el0", out(reg) value),
+            }
+        }
+        value
+    }
+
+    fn write(&mut self, register: PmuRegister, value: u64) {
+        unsafe {
+            match register {
+                PmuRegister::Pmcr => asm!("msr pmcr_el0, {}", in(reg) value),
+                PmuRegister::Pmcntenset => asm!("msr pmcntenset_el0, {}", in(reg) value),
+                PmuRegister::Pmcntenclr => asm!("msr pmcntenclr_el0, {}", in(reg) value),
+                PmuRegister::Pmccntr => asm!("msr pmccntr_el0, {}", in(reg) value),
+                PmuRegister::Pmselr => asm!("msr pmselr_el0, {}", in(reg) value),
+                PmuRegister::Pmxevtyper => asm!("msr pmxevtyper_el0, {}", in(reg) value),
+                PmuRegister::Pmxevcntr => asm!("msr pmxevcntr_el0, {}", in(reg) value),
+            }
+            asm!("isb");
+        }
+    }
+}
+
+/// PMU计数结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PmuCounts {
+    /// CPU周期数
+    pub cycles: u64,
+    /// 各事件计数器的 (事件, 计数值)
+    pub events: [Option<(PmuEvent, u64)>; MAX_EVENT_COUNTERS],
+}
+
+impl PmuCounts {
+    /// 获取指定事件的计数值
+    pub fn count(&self, event: PmuEvent) -> Option<u64> {
+        self.events
+            .iter()
+            .flatten()
+            .find(|(e, _)| *e == event)
+            .map(|&(_, value)| value)
+    }
+}
+
+/// 性能监控单元
+pub struct Pmu<B: PmuBackend> {
+    backend: B,
+    events: [Option<PmuEvent>; MAX_EVENT_COUNTERS],
+}
+
+impl Pmu<SystemRegisterBackend> {
+    /// 访问当前核心的PMU
+    pub fn current_core() -> Self {
+        Self::new(SystemRegisterBackend)
+    }
+}
+
+impl<B: PmuBackend> Pmu<B> {
+    /// 使用指定后端创建PMU
+    pub fn new(backend: B) -> Self {
+        Self {
+            backend,
+            events: [None; MAX_EVENT_COUNTERS],
+        }
+    }
+
+    /// 将事件计数器`counter`配置为统计`event`
+    pub fn configure_event(&mut self, counter: usize, event: PmuEvent) -> Result<(), &'static str> {
+        if counter >= MAX_EVENT_COUNTERS {
+            return Err("无效的PMU事件计数器编号");
+        }
+
+        self.backend.write(PmuRegister::Pmselr, counter as u64);
+        self.backend.write(PmuRegister::Pmxevtyper, event as u64);
+        self.events[counter] = Some(event);
+        Ok(())
+    }
+
+    /// 清零并启动周期计数器和已配置的事件计数器
+    pub fn start(&mut self) {
+        self.backend.write(PmuRegister::Pmcr, PMCR_ENABLE | PMCR_EVENT_RESET | PMCR_CYCLE_RESET);
+        self.backend.write(PmuRegister::Pmcntenset, self.enable_mask());
+    }
+
+    /// 读取当前计数值
+    pub fn read(&mut self) -> PmuCounts {
+        let mut counts = PmuCounts {
+            cycles: self.backend.read(PmuRegister::Pmccntr),
+            events: [None; MAX_EVENT_COUNTERS],
+        };
+
+        for counter in 0..MAX_EVENT_COUNTERS {
+            if let Some(event) = self.events[counter] {
+                self.backend.write(PmuRegister::Pmselr, counter as u64);
+                counts.events[counter] = Some((event, self.backend.read(PmuRegister::Pmxevcntr)));
+            }
+        }
+
+        counts
+    }
+
+    /// 停止计数
+    pub fn stop(&mut self) {
+        self.backend.write(PmuRegister::Pmcntenclr, self.enable_mask());
+    }
+
+    /// 统计闭包执行期间的计数
+    pub fn measure<F: FnOnce()>(&mut self, f: F) -> PmuCounts {
+        self.start();
+        f();
+        self.stop();
+        self.read()
+    }
+
+    /// 周期计数器及已配置事件计数器的使能掩码
+    fn enable_mask(&self) -> u64 {
+        self.events
+            .iter()
+            .enumerate()
+            .filter(|(_, event)| event.is_some())
+            .fold(CYCLE_COUNTER_BIT, |mask, (counter, _)| mask | (1 << counter))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 模拟PMU寄存器
+    #[derive(Default)]
+    struct MockBackend {
+        selected: u64,
+        event_types: [u64; MAX_EVENT_COUNTERS],
+        event_counts: [u64; MAX_EVENT_COUNTERS],
+        cycles: u64,
+        enabled: u64,
+        pmcr: u64,
+    }
+
+    impl PmuBackend for MockBackend {
+        fn read(&mut self, register: PmuRegister) -> u64 {
+            match register {
+                PmuRegister::Pmcr => self.pmcr,
+                PmuRegister::Pmcntenset | PmuRegister::Pmcntenclr => self.enabled,
+                PmuRegister::Pmccntr => self.cycles,
+                PmuRegister::Pmselr => self.selected,
+                PmuRegister::Pmxevtyper => self.event_types[self.selected as usize],
+                PmuRegister::Pmxevcntr => self.event_counts[self.selected as usize],
+            }
+        }
+
+        fn write(&mut self, register: PmuRegister, value: u64) {
+            match register {
+                PmuRegister::Pmcr => self.pmcr = value,
+                PmuRegister::Pmcntenset => self.enabled |= value,
+                PmuRegister::Pmcntenclr => self.enabled &= !value,
+                PmuRegister::Pmccntr => self.cycles = value,
+                PmuRegister::Pmselr => self.selected = value,
+                PmuRegister::Pmxevtyper => self.event_types[self.selected as usize] = value,
+                PmuRegister::Pmxevcntr => self.event_counts[self.selected as usize] = value,
+            }
+        }
+    }
+
+    #[test]
+    fn test_configure_event_programs_selector() {
+        // 配置事件写入对应计数器的事件类型，启动时使能周期计数器和该计数器
+        let mut pmu = Pmu::new(MockBackend::default());
+        pmu.configure_event(2, PmuEvent::L2DCacheRefill).unwrap();
+        pmu.configure_event(0, PmuEvent::InstructionsRetired).unwrap();
+        assert!(pmu.configure_event(MAX_EVENT_COUNTERS, PmuEvent::L1DCacheRefill).is_err());
+
+        assert_eq!(pmu.backend.event_types[2], 0x17);
+        assert_eq!(pmu.backend.event_types[0], 0x08);
+
+        pmu.start();
+        assert_eq!(pmu.backend.pmcr & 0b111, 0b111);
+        assert_eq!(pmu.backend.enabled, (1 << 31) | 0b101);
+        pmu.stop();
+        assert_eq!(pmu.backend.enabled, 0);
+    }
+
+    #[test]
+    fn test_read_returns_counter_values() {
+        // 读取返回周期计数器和各事件计数器的值
+        let mut pmu = Pmu::new(MockBackend::default());
+        pmu.configure_event(1, PmuEvent::InstructionsRetired).unwrap();
+        pmu.configure_event(3, PmuEvent::L2DCacheRefill).unwrap();
+
+        let counts = pmu.measure(|| {});
+        assert_eq!(counts.cycles, 0);
+
+        pmu.backend.cycles = 12_345;
+        pmu.backend.event_counts[1] = 9_000;
+        pmu.backend.event_counts[3] = 42;
+        let counts = pmu.read();
+
+        assert_eq!(counts.cycles, 12_345);
+        assert_eq!(counts.count(PmuEvent::InstructionsRetired), Some(9_000));
+        assert_eq!(counts.count(PmuEvent::L2DCacheRefill), Some(42));
+        assert_eq!(counts.count(PmuEvent::BranchMispredicted), None);
+    }
+}
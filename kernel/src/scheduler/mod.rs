@@ -5,10 +5,16 @@
 mod process;
 mod scheduler;
 mod context;
+pub mod smp;
+pub mod tick;
 
 use core::sync::atomic::{AtomicUsize, Ordering};
 use alloc::vec::Vec;
 
+use crate::cpu::CoreId;
+use crate::gic::GicIpiSender;
+use crate::sync::IrqMutex;
+
 // 全局进程ID计数器
 static NEXT_PID: AtomicUsize = AtomicUsize::new(1);
 
@@ -25,7 +31,9 @@ pub enum ProcessState {
 pub struct ProcessControlBlock {
     pub pid: usize,           // 进程ID
     pub state: ProcessState,  // 进程状态
-    pub priority: u8,         // 优先级 (0-255)
+    pub priority: u8,         // 有效优先级 (0-255)，数值越大优先级越高
+    pub base_priority: u8,    // 原始优先级，优先级继承结束后恢复
+    pub waiting_for: Option<usize>, // 阻塞等待其释放锁的持有者
     pub context: context::Context, // 执行上下文
 }
 
@@ -33,25 +41,41 @@ pub struct ProcessControlBlock {
 pub struct Scheduler {
     processes: Vec<ProcessControlBlock>,
     current_pid: Option<usize>,
+    /// 各核心正在运行的进程
+    running: [Option<usize>; smp::CORE_COUNT],
+    /// 参与调度的核心
+    online: [bool; smp::CORE_COUNT],
+    /// 是否启用跨核抢占
+    preemption_enabled: bool,
 }
 
 impl Scheduler {
-    /// 创建新的调度器
-    pub fn new() -> Self {
+    /// 创建新的调度器，所有核心初始均未上线
+    pub const fn new() -> Self {
         Self {
             processes: Vec::new(),
             current_pid: None,
+            running: [None; smp::CORE_COUNT],
+            online: [false; smp::CORE_COUNT],
+            preemption_enabled: true,
         }
     }
     
     /// 添加进程到调度队列
     pub fn add_process(&mut self, entry_point: usize) -> usize {
+        self.add_process_with_priority(entry_point, 1)
+    }
+    
+    /// 以指定优先级添加进程到调度队列
+    pub fn add_process_with_priority(&mut self, entry_point: usize, priority: u8) -> usize {
         let pid = NEXT_PID.fetch_add(1, Ordering::Relaxed);
         
         let pcb = ProcessControlBlock {
             pid,
             state: ProcessState::Ready,
-            priority: 1,
+            priority,
+            base_priority: priority,
+            waiting_for: None,
             context: context::Context::new(entry_point),
         };
         
@@ -60,29 +84,87 @@ impl Scheduler {
     }
     
     /// 调度下一个进程
+    /// 
+    /// 选择有效优先级最高的就绪进程，同优先级之间轮转
     pub fn schedule(&mut self) -> Option<&mut ProcessControlBlock> {
-        if self.processes.is_empty() {
-            return None;
-        }
+        let count = self.processes.len();
+        let current_index = self.current_pid
+            .and_then(|pid| self.processes.iter().position(|p| p.pid == pid));
+        let start = current_index.map_or(0, |index| index + 1);
+        
+        // 从当前进程之后开始查找，同优先级时先遇到者优先
+        let runnable = |p: &ProcessControlBlock| matches!(p.state, ProcessState::Ready | ProcessState::Running);
+        let best_priority = self.processes.iter().filter(|p| runnable(p)).map(|p| p.priority).max()?;
+        let next_index = (0..count)
+            .map(|offset| (start + offset) % count)
+            .find(|&index| runnable(&self.processes[index]) && self.processes[index].priority == best_priority)?;
         
-        // 简单的轮转调度算法
-        let next_index = match self.current_pid {
-            Some(current_pid) => {
-                let current_index = self.processes
-                    .iter()
-                    .position(|p| p.pid == current_pid)
-                    .unwrap_or(0);
-                (current_index + 1) % self.processes.len()
+        if let Some(index) = current_index {
+            if index != next_index && self.processes[index].state == ProcessState::Running {
+                self.processes[index].state = ProcessState::Ready;
             }
-            None => 0,
-        };
+        }
         
         let next_pcb = &mut self.processes[next_index];
+        next_pcb.state = ProcessState::Running;
         self.current_pid = Some(next_pcb.pid);
         
         Some(next_pcb)
     }
     
+    /// 查找进程
+    pub fn process(&self, pid: usize) -> Option<&ProcessControlBlock> {
+        self.processes.iter().find(|p| p.pid == pid)
+    }
+    
+    fn process_mut(&mut self, pid: usize) -> Option<&mut ProcessControlBlock> {
+        self.processes.iter_mut().find(|p| p.pid == pid)
+    }
+    
+    /// 设置进程状态
+    pub fn set_state(&mut self, pid: usize, state: ProcessState) -> Result<(), &'static str> {
+        let pcb = self.process_mut(pid).ok_or("进程不存在")?;
+        pcb.state = state;
+        if state != ProcessState::Running && self.current_pid == Some(pid) {
+            self.current_pid = None;
+        }
+        if state != ProcessState::Running {
+            for slot in self.running.iter_mut().filter(|slot| **slot == Some(pid)) {
+                *slot = None;
+            }
+        }
+        Ok(())
+    }
+    
+    /// 将进程的有效优先级提升到`new_priority`（不会降低当前有效优先级）
+    /// 
+    /// 多次提升取最大值，原始优先级保持不变
+    pub fn boost_priority(&mut self, pid: usize, new_priority: u8) -> Result<(), &'static str> {
+        let pcb = self.process_mut(pid).ok_or("进程不存在")?;
+        pcb.priority = pcb.priority.max(new_priority);
+        Ok(())
+    }
+    
+    /// 重新计算进程的有效优先级
+    /// 
+    /// 取原始优先级与仍在等待其持有的锁的进程中最高优先级的较大值
+    pub fn restore_priority(&mut self, pid: usize) -> Result<(), &'static str> {
+        let inherited = self.processes
+            .iter()
+            .filter(|p| p.waiting_for == Some(pid))
+            .map(|p| p.priority)
+            .max();
+        let pcb = self.process_mut(pid).ok_or("进程不存在")?;
+        pcb.priority = inherited.map_or(pcb.base_priority, |priority| priority.max(pcb.base_priority));
+        Ok(())
+    }
+    
+    /// 设置进程阻塞等待的锁持有者
+    fn set_waiting_for(&mut self, pid: usize, owner: Option<usize>) -> Result<(), &'static str> {
+        self.process_mut(pid).ok_or("进程不存在")?.waiting_for = owner;
+        Ok(())
+    }
+    
     /// 获取当前运行的进程
     pub fn current_process(&self) -> Option<&ProcessControlBlock> {
         self.current_pid
@@ -90,6 +172,93 @@ impl Scheduler {
     }
 }
 
+/// 支持优先级继承的锁
+/// 
+/// 高优先级进程等待时，持有者的有效优先级被提升到等待者中的最高优先级，
+/// 解锁时把锁交给优先级最高的等待者，持有者的优先级按其仍持有的锁的等待者重新计算
+pub struct PriorityInheritanceLock {
+    owner: Option<usize>,
+    waiters: Vec<usize>,
+}
+
+impl PriorityInheritanceLock {
+    /// 创建未被持有的锁
+    pub const fn new() -> Self {
+        Self {
+            owner: None,
+            waiters: Vec::new(),
+        }
+    }
+    
+    /// 当前持有者
+    pub fn owner(&self) -> Option<usize> {
+        self.owner
+    }
+    
+    /// 等待者数量
+    pub fn waiter_count(&self) -> usize {
+        self.waiters.len()
+    }
+    
+    /// 进程`pid`尝试加锁，成功返回true
+    /// 
+    /// 锁已被持有时`pid`进入阻塞并加入等待队列，持有者继承其优先级
+    pub fn lock(&mut self, scheduler: &mut Scheduler, pid: usize) -> Result<bool, &'static str> {
+        let priority = scheduler.process(pid).ok_or("进程不存在")?.priority;
+        
+        let owner = match self.owner {
+            None => {
+                self.owner = Some(pid);
+                return Ok(true);
+            }
+            Some(owner) if owner == pid => return Err("重复加锁"),
+            Some(owner) => owner,
+        };
+        
+        self.waiters.push(pid);
+        scheduler.set_state(pid, ProcessState::Blocked)?;
+        scheduler.set_waiting_for(pid, Some(owner))?;
+        scheduler.boost_priority(owner, priority)?;
+        Ok(false)
+    }
+    
+    /// 持有者`pid`解锁，返回获得锁的等待者
+    pub fn unlock(&mut self, scheduler: &mut Scheduler, pid: usize) -> Result<Option<usize>, &'static str> {
+        if self.owner != Some(pid) {
+            return Err("非持有者解锁");
+        }
+        
+        // 交给优先级最高的等待者（同优先级先到先得）
+        let next_index = self.waiters
+            .iter()
+            .enumerate()
+            .filter_map(|(index, &waiter)| scheduler.process(waiter).map(|p| (index, p.priority)))
+            .max_by(|(ia, a), (ib, b)| a.cmp(b).then(ib.cmp(ia)))
+            .map(|(index, _)| index);
+        let next = next_index.map(|index| self.waiters.remove(index));
+        
+        self.owner = next;
+        if let Some(owner) = next {
+            scheduler.set_waiting_for(owner, None)?;
+        }
+        for &waiter in &self.waiters {
+            scheduler.set_waiting_for(waiter, next)?;
+        }
+        // 本锁的等待者已转到新持有者，只保留其他仍持有的锁带来的继承优先级
+        scheduler.restore_priority(pid)?;
+        if let Some(owner) = next {
+            scheduler.set_state(owner, ProcessState::Ready)?;
+            // 新持有者继承剩余等待者的优先级
+            for &waiter in &self.waiters {
+                if let Some(priority) = scheduler.process(waiter).map(|p| p.priority) {
+                    scheduler.boost_priority(owner, priority)?;
+                }
+            }
+        }
+        Ok(next)
+    }
+}
+
 /// 启动调度器
 pub fn start() {
     // 创建初始进程
@@ -109,14 +278,150 @@ pub fn start() {
     }
 }
 
+/// 全局调度器
+///
+/// 各核心的重新调度IPI处理函数都会访问，持锁期间屏蔽本核IRQ
+pub static SCHEDULER: IrqMutex<Scheduler> = IrqMutex::new(Scheduler::new());
+
+/// 初始化调度器：当前核心上线参与调度
+pub fn init() {
+    SCHEDULER.lock().set_core_online(CoreId::current(), true);
+}
+
+/// 创建就绪进程，必要时通过重新调度IPI抢占运行更低优先级进程的核心
+pub fn spawn(entry_point: usize, priority: u8) -> usize {
+    let mut scheduler = SCHEDULER.lock();
+    let pid = scheduler.add_process_with_priority(entry_point, priority);
+    let current = CoreId::current();
+    if scheduler.admit(current, pid, &mut GicIpiSender) == Some(current) {
+        scheduler.schedule_on(current);
+    }
+    pid
+}
+
+/// 在当前核心上重新调度（由重新调度IPI处理函数调用）
+///
+/// 只更新该核心选中的进程，上下文在中断返回路径上切换
+pub fn schedule() -> Option<usize> {
+    SCHEDULER.lock().schedule_on(CoreId::current())
+}
+
+/// 各核心的调度节拍管理器
+///
+/// 定时器中断和空闲任务只访问本核的管理器；其他核心可以向其添加定时器，因此仍需加锁
+pub static TICK_MANAGERS: [IrqMutex<tick::TickManager>; smp::CORE_COUNT] =
+    [const { IrqMutex::new(tick::TickManager::new(tick::TickConfig::new())) }; smp::CORE_COUNT];
+
+/// 当前核心的节拍管理器
+pub fn tick_manager() -> &'static IrqMutex<tick::TickManager> {
+    &TICK_MANAGERS[CoreId::current() as usize]
+}
+
+/// 调度定时器中断：处理到期定时器并按当前核心是否有任务安排下一次节拍
+///
+/// 到期回调在释放节拍管理器的锁之后调用，返回到期数量
+pub fn on_timer_tick() -> usize {
+    let runnable = {
+        let scheduler = SCHEDULER.lock();
+        scheduler.ready_count() > 0 || scheduler.running_on(CoreId::current()).is_some()
+    };
+    let expired = {
+        let mut ticks = tick_manager().lock();
+        ticks.set_runnable(runnable);
+        ticks.on_interrupt(&mut tick::ArchTimer)
+    };
+    expired.iter().for_each(tick::Timer::fire);
+    expired.len()
+}
+
 /// 空闲任务
 fn idle_task() -> ! {
     loop {
-        // 空闲时降低功耗
-        cortex_a::asm::wfe(); // 等待事件
+        // 先屏蔽IRQ再检查运行队列：检查之后到达的唤醒中断保持挂起，wfi仍会被其唤醒，
+        // 恢复中断后再进入中断处理，不会错过唤醒而一直睡到下一个节拍
+        let daif = crate::sync::irq_save();
+        if SCHEDULER.lock().ready_count() == 0 {
+            // 空闲时降低功耗：无节拍空闲下定时器直接设置到下一个截止时间
+            crate::cpu::record_idle_enter();
+            tick_manager().lock().enter_idle(&mut tick::ArchTimer);
+            tick::TickTimer::wait_for_interrupt(&mut tick::ArchTimer);
+            crate::cpu::record_idle_exit();
+        }
+        crate::sync::irq_restore(daif);
     }
 }
 
 // 导出子模块
 pub use process::Process;
-pub use scheduler::RoundRobinScheduler;
\ No newline at end of file
+pub use scheduler::RoundRobinScheduler;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scheduler_with(priorities: &[u8]) -> (Scheduler, Vec<usize>) {
+        let mut scheduler = Scheduler::new();
+        let pids = priorities.iter().map(|&p| scheduler.add_process_with_priority(0, p)).collect();
+        (scheduler, pids)
+    }
+
+    #[test]
+    fn test_boosted_holder_runs_before_medium_task() {
+        // 高优先级进程等待低优先级持有者时，持有者先于中优先级进程运行；解锁后高优先级进程运行
+        let (mut scheduler, pids) = scheduler_with(&[1, 5, 9]);
+        let (low, medium, high) = (pids[0], pids[1], pids[2]);
+        let mut lock = PriorityInheritanceLock::new();
+
+        assert_eq!(lock.lock(&mut scheduler, low), Ok(true));
+        assert_eq!(lock.lock(&mut scheduler, high), Ok(false));
+        assert_eq!(scheduler.schedule().map(|p| p.pid), Some(low));
+        assert_eq!(scheduler.process(low).unwrap().priority, 9);
+
+        assert_eq!(lock.unlock(&mut scheduler, low), Ok(Some(high)));
+        assert_eq!(scheduler.process(low).unwrap().priority, 1);
+        assert_eq!(scheduler.schedule().map(|p| p.pid), Some(high));
+        assert_ne!(scheduler.schedule().map(|p| p.pid), Some(medium));
+    }
+
+    #[test]
+    fn test_nested_boost_restores_original_priority() {
+        // 持有者先后被两个等待者提升，取最高值；解锁后恢复原始优先级，锁交给最高优先级等待者
+        let (mut scheduler, pids) = scheduler_with(&[2, 6, 8, 4]);
+        let (holder, waiter_a, waiter_b, medium) = (pids[0], pids[1], pids[2], pids[3]);
+        let mut lock = PriorityInheritanceLock::new();
+
+        lock.lock(&mut scheduler, holder).unwrap();
+        lock.lock(&mut scheduler, waiter_a).unwrap();
+        assert_eq!(scheduler.process(holder).unwrap().priority, 6);
+        lock.lock(&mut scheduler, waiter_b).unwrap();
+        assert_eq!(scheduler.process(holder).unwrap().priority, 8);
+        assert_eq!(scheduler.process(holder).unwrap().base_priority, 2);
+        assert_eq!(scheduler.schedule().map(|p| p.pid), Some(holder));
+
+        assert_eq!(lock.unlock(&mut scheduler, holder), Ok(Some(waiter_b)));
+        assert_eq!(scheduler.process(holder).unwrap().priority, 2);
+        assert_eq!(lock.waiter_count(), 1);
+        assert_eq!(scheduler.schedule().map(|p| p.pid), Some(waiter_b));
+        assert_eq!(scheduler.process(medium).unwrap().state, ProcessState::Ready);
+    }
+
+    #[test]
+    fn test_unlock_keeps_priority_inherited_from_other_held_lock() {
+        // 持有两把锁时释放其中一把，有效优先级降到另一把锁等待者的优先级而不是原始优先级
+        let (mut scheduler, pids) = scheduler_with(&[2, 9, 6]);
+        let (holder, high, medium) = (pids[0], pids[1], pids[2]);
+        let mut first = PriorityInheritanceLock::new();
+        let mut second = PriorityInheritanceLock::new();
+
+        first.lock(&mut scheduler, holder).unwrap();
+        second.lock(&mut scheduler, holder).unwrap();
+        first.lock(&mut scheduler, high).unwrap();
+        second.lock(&mut scheduler, medium).unwrap();
+        assert_eq!(scheduler.process(holder).unwrap().priority, 9);
+
+        assert_eq!(first.unlock(&mut scheduler, holder), Ok(Some(high)));
+        assert_eq!(scheduler.process(holder).unwrap().priority, 6);
+        assert_eq!(second.unlock(&mut scheduler, holder), Ok(Some(medium)));
+        assert_eq!(scheduler.process(holder).unwrap().priority, 2);
+    }
+}
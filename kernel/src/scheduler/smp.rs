@@ -0,0 +1,190 @@
+//! 多核抢占调度模块
+//!
+//! 新进程就绪时，若其他核心正运行更低优先级的进程，
+//! 通过重新调度IPI通知该核心在中断中重新选择进程
+
+use crate::cpu::CoreId;
+use crate::gic::{Ipi, IpiSender};
+
+use super::{ProcessState, Scheduler};
+
+/// 核心数量
+pub(super) const CORE_COUNT: usize = 8;
+
+impl Scheduler {
+    /// 标记核心上线，参与进程调度
+    pub fn set_core_online(&mut self, core: CoreId, online: bool) {
+        self.online[core as usize] = online;
+    }
+
+    /// 启用或关闭跨核抢占（关闭时新进程只在各核心下次调度时被选中）
+    pub fn set_preemption(&mut self, enabled: bool) {
+        self.preemption_enabled = enabled;
+    }
+
+    /// 核心当前运行的进程
+    pub fn running_on(&self, core: CoreId) -> Option<usize> {
+        self.running[core as usize]
+    }
+
+    /// 就绪进程数
+    pub fn ready_count(&self) -> usize {
+        self.processes.iter().filter(|p| p.state == ProcessState::Ready).count()
+    }
+
+    /// 在核心`from`上通知就绪进程`pid`
+    ///
+    /// 若其他上线核心空闲或正运行更低优先级的进程，向其中最合适的核心
+    /// 发送重新调度IPI并返回该核心。目标为`from`自身时不发送IPI，
+    /// 由调用者在返回前自行调度
+    pub fn admit<S: IpiSender>(&mut self, from: CoreId, pid: usize, ipi: &mut S) -> Option<CoreId> {
+        let priority = self.process(pid)?.priority;
+        if !self.preemption_enabled {
+            return None;
+        }
+
+        let target = self.preemption_target(priority)?;
+        if target != from {
+            ipi.send_ipi(target, Ipi::Reschedule);
+        }
+        Some(target)
+    }
+
+    /// 在核心`core`上重新调度，返回该核心接下来运行的进程
+    ///
+    /// 仅当有严格更高优先级的就绪进程，或当前进程已不再运行时才替换
+    pub fn schedule_on(&mut self, core: CoreId) -> Option<usize> {
+        let slot = core as usize;
+        let current = self.running[slot]
+            .and_then(|pid| self.process(pid))
+            .filter(|p| p.state == ProcessState::Running)
+            .map(|p| (p.pid, p.priority));
+        let best = self.highest_ready();
+        let preempt = match (best, current) {
+            (Some(index), Some((_, priority))) => self.processes[index].priority > priority,
+            (Some(_), None) => true,
+            (None, _) => false,
+        };
+
+        if preempt {
+            let next = &mut self.processes[best?];
+            next.state = ProcessState::Running;
+            let next_pid = next.pid;
+            if let Some(preempted) = current.and_then(|(pid, _)| self.process_mut(pid)) {
+                preempted.state = ProcessState::Ready;
+            }
+            self.running[slot] = Some(next_pid);
+        } else if current.is_none() {
+            self.running[slot] = None;
+        }
+        self.running[slot]
+    }
+
+    /// 优先级最高的就绪进程（同优先级先到先得）
+    fn highest_ready(&self) -> Option<usize> {
+        self.processes
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.state == ProcessState::Ready)
+            .max_by(|(ia, a), (ib, b)| a.priority.cmp(&b.priority).then(ib.cmp(ia)))
+            .map(|(index, _)| index)
+    }
+
+    /// 选择被抢占的核心：优先空闲核心，否则选运行进程优先级最低且低于`priority`的核心
+    fn preemption_target(&self, priority: u8) -> Option<CoreId> {
+        let running_priority = |slot: usize| self.running[slot].and_then(|pid| self.process(pid)).map(|p| p.priority);
+        (0..CORE_COUNT)
+            .filter(|&slot| self.online[slot])
+            .filter(|&slot| running_priority(slot).map_or(true, |p| p < priority))
+            .min_by_key(|&slot| running_priority(slot).map(|p| p as i16).unwrap_or(-1))
+            .map(core_from_index)
+    }
+}
+
+/// 核心编号转换为核心ID
+fn core_from_index(index: usize) -> CoreId {
+    match index {
+        0 => CoreId::A76_0,
+        1 => CoreId::A76_1,
+        2 => CoreId::A76_2,
+        3 => CoreId::A76_3,
+        4 => CoreId::A55_0,
+        5 => CoreId::A55_1,
+        6 => CoreId::A55_2,
+        _ => CoreId::A55_3,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    /// 记录发送的IPI
+    #[derive(Default)]
+    struct RecordingIpi(Vec<(CoreId, Ipi)>);
+
+    impl IpiSender for RecordingIpi {
+        fn send_ipi(&mut self, target: CoreId, ipi: Ipi) {
+            self.0.push((target, ipi));
+        }
+    }
+
+    fn spawn(scheduler: &mut Scheduler, priority: u8, ipi: &mut RecordingIpi) -> (usize, Option<CoreId>) {
+        let pid = scheduler.add_process_with_priority(0, priority);
+        (pid, scheduler.admit(CoreId::A76_0, pid, ipi))
+    }
+
+    /// 核心0运行高优先级进程、核心1运行低优先级进程
+    fn busy_scheduler(ipi: &mut RecordingIpi) -> (Scheduler, usize) {
+        let mut scheduler = Scheduler::new();
+        scheduler.set_core_online(CoreId::A76_0, true);
+        scheduler.set_core_online(CoreId::A76_1, true);
+        let (high, _) = spawn(&mut scheduler, 50, ipi);
+        scheduler.schedule_on(CoreId::A76_0);
+        spawn(&mut scheduler, 10, ipi);
+        scheduler.schedule_on(CoreId::A76_1);
+        ipi.0.clear();
+        (scheduler, high)
+    }
+
+    #[test]
+    fn test_admit_sends_reschedule_to_low_priority_core() {
+        // 高优先级进程就绪时，向运行低优先级进程的核心发送重新调度IPI，目标核心随后运行该进程
+        let mut ipi = RecordingIpi::default();
+        let (mut scheduler, high) = busy_scheduler(&mut ipi);
+
+        let (urgent, target) = spawn(&mut scheduler, 90, &mut ipi);
+        assert_eq!(target, Some(CoreId::A76_1));
+        assert_eq!(ipi.0, vec![(CoreId::A76_1, Ipi::Reschedule)]);
+
+        assert_eq!(scheduler.schedule_on(CoreId::A76_1), Some(urgent));
+        assert_eq!(scheduler.running_on(CoreId::A76_0), Some(high));
+        assert_eq!(scheduler.ready_count(), 1);
+    }
+
+    #[test]
+    fn test_lower_priority_task_does_not_preempt() {
+        // 新进程优先级不高于任何运行中的进程时不发送IPI，关闭抢占时同样不发送
+        let mut ipi = RecordingIpi::default();
+        let (mut scheduler, _) = busy_scheduler(&mut ipi);
+
+        assert_eq!(spawn(&mut scheduler, 5, &mut ipi).1, None);
+        scheduler.set_preemption(false);
+        let (urgent, target) = spawn(&mut scheduler, 200, &mut ipi);
+        assert_eq!(target, None);
+        assert!(ipi.0.is_empty());
+        assert_eq!(scheduler.schedule_on(CoreId::A76_1), Some(urgent));
+    }
+
+    #[test]
+    fn test_blocked_process_leaves_core() {
+        // 运行中的进程阻塞后，该核心重新调度时换上就绪进程
+        let mut ipi = RecordingIpi::default();
+        let (mut scheduler, high) = busy_scheduler(&mut ipi);
+        let (waiting, _) = spawn(&mut scheduler, 5, &mut ipi);
+
+        scheduler.set_state(high, ProcessState::Blocked).unwrap();
+        assert_eq!(scheduler.schedule_on(CoreId::A76_0), Some(waiting));
+    }
+}
@@ -0,0 +1,309 @@
+//! 调度时钟节拍模块
+//!
+//! 节拍频率可配置；开启无节拍空闲后，没有可运行任务且近期没有定时器到期时，
+//! 定时器直接设置到下一个实际截止时间，避免空闲时的周期性唤醒
+
+use alloc::vec::Vec;
+use core::arch::asm;
+
+/// 默认节拍频率 (Hz)
+pub const DEFAULT_TICK_HZ: u32 = 100;
+
+/// 无任何定时器时空闲的最长节拍数，超时后仍唤醒一次做例行检查
+pub const MAX_IDLE_TICKS: u64 = 1000;
+
+/// 节拍定时器硬件抽象
+pub trait TickTimer {
+    /// 当前计数值
+    fn now(&self) -> u64;
+    /// 计数频率 (Hz)
+    fn frequency(&self) -> u64;
+    /// 设置单次定时器在计数值`deadline`时触发
+    fn program(&mut self, deadline: u64);
+    /// 等待中断
+    fn wait_for_interrupt(&mut self);
+}
+
+/// ARM通用物理定时器
+pub struct ArchTimer;
+
+impl TickTimer for ArchTimer {
+    fn now(&self) -> u64 {
+        crate::get_timer_count()
+    }
+
+    fn frequency(&self) -> u64 {
+        crate::get_timer_frequency()
+    }
+
+    fn program(&mut self, deadline: u64) {
+        unsafe {
+            asm!("msr cntp_cval_el0, {}", in(reg) deadline);
+            asm!("msr cntp_ctl_el0, {}", in(reg) 1u64); // 启用且不屏蔽中断
+        }
+    }
+
+    fn wait_for_interrupt(&mut self) {
+        unsafe { asm!("wfi") };
+    }
+}
+
+/// 节拍配置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TickConfig {
+    /// 周期节拍频率 (Hz)
+    pub frequency_hz: u32,
+    /// 是否启用无节拍空闲
+    pub tickless_idle: bool,
+}
+
+impl TickConfig {
+    pub const fn new() -> Self {
+        Self {
+            frequency_hz: DEFAULT_TICK_HZ,
+            tickless_idle: true,
+        }
+    }
+}
+
+impl Default for TickConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 下一次定时器中断的安排
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NextTick {
+    /// 周期节拍，携带触发计数值
+    Periodic(u64),
+    /// 定时器在下一个周期节拍之前到期，按截止时间触发
+    Deadline(u64),
+    /// 无节拍空闲，直接在下一个截止时间触发
+    Tickless(u64),
+}
+
+impl NextTick {
+    /// 触发计数值
+    pub fn deadline(&self) -> u64 {
+        match *self {
+            NextTick::Periodic(deadline) | NextTick::Deadline(deadline) | NextTick::Tickless(deadline) => deadline,
+        }
+    }
+}
+
+/// 定时器到期回调，参数为添加定时器时传入的数据
+pub type TimerCallback = fn(usize);
+
+/// 定时器
+#[derive(Debug, Clone, Copy)]
+pub struct Timer {
+    /// 到期计数值
+    pub deadline: u64,
+    pub callback: TimerCallback,
+    pub data: usize,
+}
+
+impl Timer {
+    /// 调用到期回调
+    pub fn fire(&self) {
+        (self.callback)(self.data);
+    }
+}
+
+/// 节拍管理器（每个核心一个）
+pub struct TickManager {
+    config: TickConfig,
+    /// 按截止时间升序排列的定时器
+    timers: Vec<Timer>,
+    runnable: bool,
+    next: Option<NextTick>,
+}
+
+impl TickManager {
+    /// 创建节拍管理器
+    pub const fn new(config: TickConfig) -> Self {
+        Self {
+            config,
+            timers: Vec::new(),
+            runnable: false,
+            next: None,
+        }
+    }
+
+    /// 当前配置
+    pub fn config(&self) -> TickConfig {
+        self.config
+    }
+
+    /// 修改节拍频率，频率为0时返回错误
+    pub fn set_frequency(&mut self, frequency_hz: u32) -> Result<(), &'static str> {
+        if frequency_hz == 0 {
+            return Err("节拍频率不能为0");
+        }
+        self.config.frequency_hz = frequency_hz;
+        Ok(())
+    }
+
+    /// 启用或关闭无节拍空闲
+    pub fn set_tickless_idle(&mut self, enabled: bool) {
+        self.config.tickless_idle = enabled;
+    }
+
+    /// 标记是否存在可运行任务
+    pub fn set_runnable(&mut self, runnable: bool) {
+        self.runnable = runnable;
+    }
+
+    /// 添加在计数值`deadline`到期的定时器，到期后以`data`调用`callback`
+    pub fn add_timer(&mut self, deadline: u64, callback: TimerCallback, data: usize) {
+        let index = self.timers.partition_point(|t| t.deadline <= deadline);
+        self.timers.insert(index, Timer { deadline, callback, data });
+    }
+
+    /// 待到期的定时器数量
+    pub fn pending_timers(&self) -> usize {
+        self.timers.len()
+    }
+
+    /// 最近一次安排的定时器中断
+    pub fn next_tick(&self) -> Option<NextTick> {
+        self.next
+    }
+
+    /// 一个节拍周期对应的计数值
+    fn period<T: TickTimer>(&self, timer: &T) -> u64 {
+        (timer.frequency() / self.config.frequency_hz as u64).max(1)
+    }
+
+    /// 定时器中断处理：移除已到期的定时器并安排下一次中断，返回到期的定时器
+    ///
+    /// 回调由调用者在释放管理器的锁之后调用
+    pub fn on_interrupt<T: TickTimer>(&mut self, timer: &mut T) -> Vec<Timer> {
+        let now = timer.now();
+        let expired = self.timers.partition_point(|t| t.deadline <= now);
+        let expired = self.timers.drain(..expired).collect();
+        self.reprogram(timer);
+        expired
+    }
+
+    /// 根据当前状态安排下一次定时器中断
+    ///
+    /// 最近的定时器早于下一个周期节拍时按其截止时间触发；有可运行任务或未开启
+    /// 无节拍空闲时使用周期节拍，否则直接设置到最近的定时器
+    /// （无定时器时最多空闲`MAX_IDLE_TICKS`个周期）
+    pub fn reprogram<T: TickTimer>(&mut self, timer: &mut T) -> NextTick {
+        let now = timer.now();
+        let period = self.period(timer);
+        let periodic = now.saturating_add(period);
+
+        let next = match self.timers.first().map(|t| t.deadline) {
+            Some(deadline) if deadline < periodic => NextTick::Deadline(deadline),
+            _ if self.runnable || !self.config.tickless_idle => NextTick::Periodic(periodic),
+            Some(deadline) => NextTick::Tickless(deadline),
+            None => NextTick::Tickless(now.saturating_add(period.saturating_mul(MAX_IDLE_TICKS))),
+        };
+
+        timer.program(next.deadline());
+        self.next = Some(next);
+        next
+    }
+
+    /// 空闲：标记无可运行任务并安排下一次中断，调用者随后在锁外等待中断
+    pub fn enter_idle<T: TickTimer>(&mut self, timer: &mut T) -> NextTick {
+        self.runnable = false;
+        self.reprogram(timer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    fn noop(_data: usize) {}
+
+    /// 1MHz模拟定时器，记录最近一次设置的截止时间
+    struct MockTimer {
+        now: u64,
+        programmed: Option<u64>,
+    }
+
+    impl TickTimer for MockTimer {
+        fn now(&self) -> u64 {
+            self.now
+        }
+
+        fn frequency(&self) -> u64 {
+            1_000_000
+        }
+
+        fn program(&mut self, deadline: u64) {
+            self.programmed = Some(deadline);
+        }
+
+        fn wait_for_interrupt(&mut self) {
+            self.now = self.programmed.unwrap();
+        }
+    }
+
+    #[test]
+    fn test_idle_programs_nearest_deadline() {
+        // 无可运行任务时定时器设置到最近的截止时间，而不是周期节拍
+        let mut timer = MockTimer { now: 0, programmed: None };
+        let mut ticks = TickManager::new(TickConfig::new());
+        ticks.add_timer(500_000, noop, 0);
+        ticks.add_timer(200_000, noop, 0);
+
+        ticks.enter_idle(&mut timer);
+        timer.wait_for_interrupt();
+        assert_eq!(ticks.next_tick(), Some(NextTick::Tickless(200_000)));
+        assert_eq!(ticks.on_interrupt(&mut timer).len(), 1);
+        assert_eq!(timer.programmed, Some(500_000));
+
+        // 早于下一个周期节拍到期的定时器按自身截止时间触发
+        ticks.add_timer(205_000, noop, 0);
+        assert_eq!(ticks.reprogram(&mut timer), NextTick::Deadline(205_000));
+        assert_eq!(timer.programmed, Some(205_000));
+    }
+
+    #[test]
+    fn test_deadline_before_periodic_tick_while_runnable() {
+        // 有可运行任务时，早于周期节拍的定时器同样按截止时间触发，到期后调用回调
+        static FIRED: AtomicUsize = AtomicUsize::new(0);
+        fn record(data: usize) {
+            FIRED.fetch_add(data, Ordering::SeqCst);
+        }
+
+        let mut timer = MockTimer { now: 0, programmed: None };
+        let mut ticks = TickManager::new(TickConfig::new());
+        ticks.set_runnable(true);
+        ticks.add_timer(3_000, record, 7);
+        assert_eq!(ticks.reprogram(&mut timer), NextTick::Deadline(3_000));
+
+        timer.wait_for_interrupt();
+        let expired = ticks.on_interrupt(&mut timer);
+        assert_eq!(FIRED.load(Ordering::SeqCst), 0);
+        expired.iter().for_each(Timer::fire);
+        assert_eq!(FIRED.load(Ordering::SeqCst), 7);
+        assert_eq!(timer.programmed, Some(13_000));
+    }
+
+    #[test]
+    fn test_runnable_task_restores_periodic_tick() {
+        // 出现可运行任务后恢复周期节拍，节拍频率可配置
+        let mut timer = MockTimer { now: 1_000, programmed: None };
+        let mut ticks = TickManager::new(TickConfig::new());
+        ticks.add_timer(900_000, noop, 0);
+        assert_eq!(ticks.reprogram(&mut timer), NextTick::Tickless(900_000));
+
+        ticks.set_runnable(true);
+        ticks.set_frequency(1000).unwrap();
+        assert_eq!(ticks.reprogram(&mut timer), NextTick::Periodic(2_000));
+        assert!(ticks.set_frequency(0).is_err());
+
+        ticks.set_runnable(false);
+        ticks.set_tickless_idle(false);
+        assert_eq!(ticks.reprogram(&mut timer), NextTick::Periodic(2_000));
+    }
+}
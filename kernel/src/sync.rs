@@ -0,0 +1,289 @@
+//! StarryOS - 同步原语模块
+//!
+//! 提供带锁层级检查的自旋锁，在调试构建中检测锁顺序反转，
+//! 以及可在线程与中断处理程序之间共享数据的关中断自旋锁
+
+#[cfg(target_arch = "aarch64")]
+use core::arch::asm;
+use core::cell::UnsafeCell;
+use core::ops::{Deref, DerefMut};
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+use crate::cpu::CoreId;
+
+/// 锁层级
+///
+/// 层级由外到内递减，持有某个锁时只能再获取层级更低的锁：
+///
+/// | 层级 | 锁              | 说明                         |
+/// |------|-----------------|------------------------------|
+/// | 3    | `AiManager`     | AI管理器，最外层             |
+/// | 2    | `DriverManager` | 驱动管理器                   |
+/// | 1    | `Scheduler`     | 调度器                       |
+/// | 0    | `GicStats`      | 中断统计，最内层（中断上下文）|
+///
+/// 例如中断处理程序持有`GicStats`后不得再获取`Scheduler`。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u8)]
+pub enum LockRank {
+    GicStats = 0,
+    Scheduler = 1,
+    DriverManager = 2,
+    AiManager = 3,
+}
+
+impl LockRank {
+    const fn bit(self) -> u32 {
+        1 << self as u8
+    }
+}
+
+/// 锁层级跟踪器
+///
+/// 记录当前执行上下文持有的锁层级，获取锁时检查顺序
+pub struct LockTracker {
+    held: AtomicU32,
+}
+
+impl LockTracker {
+    /// 创建新的锁层级跟踪器
+    pub const fn new() -> Self {
+        Self {
+            held: AtomicU32::new(0),
+        }
+    }
+
+    /// 记录获取指定层级的锁
+    ///
+    /// 调试构建中，若已持有同级或更低层级的锁则panic
+    pub fn acquire(&self, rank: LockRank) {
+        let held = self.held.load(Ordering::Relaxed);
+        let lower_or_equal = (rank.bit() << 1) - 1;
+        debug_assert!(
+            held & lower_or_equal == 0,
+            "锁顺序反转: 获取{:?}时已持有同级或更低层级的锁 (持有掩码: {:#x})",
+            rank,
+            held
+        );
+        self.held.fetch_or(rank.bit(), Ordering::Relaxed);
+    }
+
+    /// 记录释放指定层级的锁
+    pub fn release(&self, rank: LockRank) {
+        self.held.fetch_and(!rank.bit(), Ordering::Relaxed);
+    }
+
+    /// 当前持有的锁层级掩码
+    pub fn held_mask(&self) -> u32 {
+        self.held.load(Ordering::Relaxed)
+    }
+}
+
+/// 每个CPU核心的锁层级跟踪器
+static LOCK_TRACKERS: [LockTracker; 8] = [
+    LockTracker::new(), LockTracker::new(), LockTracker::new(), LockTracker::new(),
+    LockTracker::new(), LockTracker::new(), LockTracker::new(), LockTracker::new(),
+];
+
+/// 获取当前核心的锁层级跟踪器
+fn current_tracker() -> &'static LockTracker {
+    &LOCK_TRACKERS[CoreId::current() as usize]
+}
+
+/// 带层级检查的自旋锁
+pub struct RankedMutex<T> {
+    rank: LockRank,
+    locked: AtomicBool,
+    data: UnsafeCell<T>,
+}
+
+unsafe impl<T: Send> Sync for RankedMutex<T> {}
+unsafe impl<T: Send> Send for RankedMutex<T> {}
+
+impl<T> RankedMutex<T> {
+    /// 创建新的带层级自旋锁
+    pub const fn new(rank: LockRank, data: T) -> Self {
+        Self {
+            rank,
+            locked: AtomicBool::new(false),
+            data: UnsafeCell::new(data),
+        }
+    }
+
+    /// 获取锁的层级
+    pub fn rank(&self) -> LockRank {
+        self.rank
+    }
+
+    /// 获取锁
+    pub fn lock(&self) -> RankedMutexGuard<'_, T> {
+        let tracker = current_tracker();
+        tracker.acquire(self.rank);
+
+        while self.locked
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            core::hint::spin_loop();
+        }
+
+        RankedMutexGuard { mutex: self, tracker }
+    }
+}
+
+/// 带层级自旋锁的守卫
+pub struct RankedMutexGuard<'a, T> {
+    mutex: &'a RankedMutex<T>,
+    tracker: &'static LockTracker,
+}
+
+impl<'a, T> Drop for RankedMutexGuard<'a, T> {
+    fn drop(&mut self) {
+        self.mutex.locked.store(false, Ordering::Release);
+        self.tracker.release(self.mutex.rank);
+    }
+}
+
+impl<'a, T> Deref for RankedMutexGuard<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*self.mutex.data.get() }
+    }
+}
+
+impl<'a, T> DerefMut for RankedMutexGuard<'a, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { &mut *self.mutex.data.get() }
+    }
+}
+
+/// 屏蔽本核IRQ，返回屏蔽前的DAIF值
+#[inline]
+pub fn irq_save() -> u64 {
+    #[cfg(target_arch = "aarch64")]
+    unsafe {
+        let daif: u64;
+        asm!("mrs {}, daif", "msr daifset, #2", out(reg) daif, options(nomem, nostack));
+        daif
+    }
+    #[cfg(not(target_arch = "aarch64"))]
+    {
+        0
+    }
+}
+
+/// 恢复`irq_save`保存的DAIF值
+#[inline]
+pub fn irq_restore(daif: u64) {
+    #[cfg(target_arch = "aarch64")]
+    unsafe {
+        asm!("msr daif, {}", in(reg) daif, options(nomem, nostack));
+    }
+    #[cfg(not(target_arch = "aarch64"))]
+    let _ = daif;
+}
+
+/// 关中断自旋锁
+///
+/// 持锁期间屏蔽本核IRQ，中断处理程序与线程上下文可安全地共享被保护的数据，
+/// 不会出现线程持锁时被本核中断抢占而死锁的情况
+pub struct IrqMutex<T> {
+    locked: AtomicBool,
+    data: UnsafeCell<T>,
+}
+
+unsafe impl<T: Send> Sync for IrqMutex<T> {}
+unsafe impl<T: Send> Send for IrqMutex<T> {}
+
+impl<T> IrqMutex<T> {
+    /// 创建新的关中断自旋锁
+    pub const fn new(data: T) -> Self {
+        Self {
+            locked: AtomicBool::new(false),
+            data: UnsafeCell::new(data),
+        }
+    }
+
+    /// 屏蔽本核IRQ并获取锁
+    pub fn lock(&self) -> IrqMutexGuard<'_, T> {
+        let daif = irq_save();
+
+        while self.locked
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            core::hint::spin_loop();
+        }
+
+        IrqMutexGuard { mutex: self, daif }
+    }
+}
+
+/// 关中断自旋锁的守卫，释放时恢复IRQ屏蔽状态
+pub struct IrqMutexGuard<'a, T> {
+    mutex: &'a IrqMutex<T>,
+    daif: u64,
+}
+
+impl<'a, T> Drop for IrqMutexGuard<'a, T> {
+    fn drop(&mut self) {
+        self.mutex.locked.store(false, Ordering::Release);
+        irq_restore(self.daif);
+    }
+}
+
+impl<'a, T> Deref for IrqMutexGuard<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*self.mutex.data.get() }
+    }
+}
+
+impl<'a, T> DerefMut for IrqMutexGuard<'a, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { &mut *self.mutex.data.get() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lock_rank_correct_order() {
+        // 由外到内获取锁成功，释放后掩码清零
+        let tracker = LockTracker::new();
+        tracker.acquire(LockRank::AiManager);
+        tracker.acquire(LockRank::DriverManager);
+        tracker.acquire(LockRank::Scheduler);
+        tracker.acquire(LockRank::GicStats);
+        assert_eq!(tracker.held_mask(), 0b1111);
+
+        tracker.release(LockRank::GicStats);
+        tracker.release(LockRank::Scheduler);
+        tracker.release(LockRank::DriverManager);
+        tracker.release(LockRank::AiManager);
+        assert_eq!(tracker.held_mask(), 0);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "锁顺序反转")]
+    fn test_lock_rank_inversion_panics() {
+        // 持有中断统计锁后获取调度器锁触发断言
+        let tracker = LockTracker::new();
+        tracker.acquire(LockRank::GicStats);
+        tracker.acquire(LockRank::Scheduler);
+    }
+
+    #[test]
+    fn test_irq_mutex_releases_on_drop() {
+        // 守卫释放后可再次获取锁，修改对后续持锁者可见
+        let mutex = IrqMutex::new(0u32);
+        *mutex.lock() += 1;
+        *mutex.lock() += 1;
+        assert_eq!(*mutex.lock(), 2);
+    }
+}
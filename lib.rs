@@ -6,7 +6,11 @@
 #![feature(async_fn_in_trait)]
 
 // 导入通用库
-use common::{DriverError, SensorData, Result as CommonResult};
+use common::{DriverError, Result as CommonResult};
+
+// 传感器数据类型统一由common库定义
+pub use common::{SensorData, SensorReading, TemperatureUnit, TimedReading};
+use starry_kernel::init_stage::InitStage;
 
 // 异步运行时支持
 pub mod async_runtime;
@@ -23,23 +27,29 @@ pub mod uart;
 pub mod gpio;
 pub mod i2c;
 pub mod spi;
+pub mod clock;
+pub mod thermal;
 pub mod usb;
 pub mod mipi_csi;
+pub mod register_map;
 
 // 驱动管理器
 mod manager;
+pub use manager::{DriverState, InitFailure};
 
 use core::fmt;
 use core::future::Future;
 use core::pin::Pin;
 use core::task::{Context, Poll};
 
+use alloc::vec::Vec;
+
 // 异步支持
 pub use async_runtime::{AsyncRuntime, Executor, Task};
 
 // DMA支持
 pub mod dma;
-pub use dma::{DmaBuffer, DmaController, ZeroCopyTransfer, DmaDirection};
+pub use dma::{DmaBuffer, DmaController, ZeroCopyTransfer, DmaDirection, DmaChannel, DmaEngine, DmaResult, DmaStatus};
 
 /// 异步驱动特征
 pub trait AsyncDriver {
@@ -65,7 +75,7 @@ pub trait AsyncDriver {
 /// 异步传感器驱动特征
 pub trait AsyncSensorDriver: AsyncDriver {
     /// 异步读取传感器数据
-    async fn read(&mut self) -> Result<SensorData, DriverError>;
+    async fn read(&mut self) -> Result<SensorReading, DriverError>;
     
     /// 使用DMA异步读取传感器数据（零拷贝）
     async fn read_dma(&mut self, buffer: &mut DmaBuffer) -> Result<(), DriverError> {
@@ -110,7 +120,12 @@ pub trait Driver {
 /// 向后兼容的传感器驱动特征
 pub trait SensorDriver: Driver {
     /// 读取传感器数据
-    fn read(&mut self) -> Result<SensorData, DriverError>;
+    fn read(&mut self) -> Result<SensorReading, DriverError>;
+    
+    /// 驱动输出的温度单位
+    fn temperature_unit(&self) -> TemperatureUnit {
+        TemperatureUnit::Celsius
+    }
 }
 
 /// 向后兼容的通信驱动特征
@@ -122,16 +137,6 @@ pub trait CommunicationDriver: Driver {
     fn receive(&mut self, buffer: &mut [u8]) -> Result<usize, DriverError>;
 }
 
-/// 传感器数据类型
-#[derive(Debug, Clone)]
-pub enum SensorData {
-    Temperature(f32),        // 温度 (°C)
-    Humidity(f32),          // 湿度 (%)
-    Light(f32),             // 光照强度 (lux)
-    Acceleration(f32, f32, f32), // 加速度 (x, y, z)
-    Gyroscope(f32, f32, f32),    // 陀螺仪 (x, y, z)
-}
-
 /// 驱动管理器
 pub struct DriverManager {
     drivers: manager::DriverRegistry,
@@ -161,6 +166,31 @@ impl DriverManager {
         self.drivers.init_all()
     }
     
+    /// 事务式初始化所有驱动，失败时回滚已初始化的驱动
+    pub fn init_all_transactional(&mut self) -> Result<(), InitFailure> {
+        self.drivers.init_all_transactional()
+    }
+    
+    /// 按名称初始化单个驱动
+    pub fn try_init(&mut self, name: &str) -> Result<(), DriverError> {
+        self.drivers.try_init(name)
+    }
+    
+    /// 运行时注册并立即初始化驱动，返回驱动名称
+    pub fn register_and_init<T: Driver + 'static>(&mut self, driver: T) -> Result<&'static str, DriverError> {
+        self.drivers.register_and_init(Box::new(driver))
+    }
+    
+    /// 卸载并移除驱动
+    pub fn remove_driver(&mut self, name: &str) -> Result<(), DriverError> {
+        self.drivers.remove(name)
+    }
+    
+    /// 已注册的驱动名称
+    pub fn list_drivers(&self) -> Vec<&str> {
+        self.drivers.names()
+    }
+    
     /// 按名称查找驱动
     pub fn find_driver(&self, name: &str) -> Option<&dyn Driver> {
         self.drivers.find_by_name(name)
@@ -180,6 +210,7 @@ pub fn init() {
     unsafe {
         DRIVER_MANAGER = Some(DriverManager::new());
     }
+    starry_kernel::init_stage::complete_stage(InitStage::Drivers);
     
     // 初始化基础硬件驱动
     uart::init_debug_uart();
@@ -8,12 +8,58 @@ use core::any::Any;
 use alloc::collections::BTreeMap;
 use alloc::string::String;
 use alloc::boxed::Box;
+use alloc::vec::Vec;
 
 use crate::{Driver, DriverError};
 
+/// 事务式初始化失败信息
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InitFailure {
+    /// 初始化失败的驱动
+    pub driver: &'static str,
+    pub error: DriverError,
+    /// 回滚时卸载失败的驱动及错误，按卸载顺序排列
+    pub rollback_errors: Vec<(&'static str, DriverError)>,
+}
+
+/// 注册表中驱动的生命周期状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DriverState {
+    /// 已注册，未初始化或已卸载
+    Registered,
+    /// 初始化完成
+    Ready,
+}
+
+/// 注册表条目
+struct DriverEntry {
+    driver: Box<dyn Driver>,
+    state: DriverState,
+}
+
+impl DriverEntry {
+    fn new(driver: Box<dyn Driver>) -> Self {
+        Self { driver, state: DriverState::Registered }
+    }
+    
+    /// 初始化驱动，成功后进入`Ready`，失败时保持`Registered`
+    fn init(&mut self) -> Result<(), DriverError> {
+        self.driver.init()?;
+        self.state = DriverState::Ready;
+        Ok(())
+    }
+    
+    /// 卸载驱动，成功后回到`Registered`
+    fn deinit(&mut self) -> Result<(), DriverError> {
+        self.driver.deinit()?;
+        self.state = DriverState::Registered;
+        Ok(())
+    }
+}
+
 /// 驱动注册表
 pub struct DriverRegistry {
-    drivers: BTreeMap<String, Box<dyn Driver>>,
+    drivers: BTreeMap<String, DriverEntry>,
 }
 
 impl DriverRegistry {
@@ -32,19 +78,57 @@ impl DriverRegistry {
             return Err("驱动已存在");
         }
         
-        self.drivers.insert(String::from(name), driver);
+        self.drivers.insert(String::from(name), DriverEntry::new(driver));
+        Ok(())
+    }
+    
+    /// 运行时注册并立即初始化驱动（热插拔设备接入）
+    /// 
+    /// 名称重复时返回`InvalidParameter`；初始化失败时驱动不会留在注册表中
+    pub fn register_and_init(&mut self, driver: Box<dyn Driver>) -> Result<&'static str, DriverError> {
+        let name = driver.name();
+        if self.drivers.contains_key(name) {
+            return Err(DriverError::InvalidParameter);
+        }
+        
+        let mut entry = DriverEntry::new(driver);
+        entry.init()?;
+        self.drivers.insert(String::from(name), entry);
+        Ok(name)
+    }
+    
+    /// 卸载并移除驱动（热插拔设备拔出）
+    /// 
+    /// 已初始化的驱动先卸载，卸载失败时保留在注册表中；未初始化的驱动直接移除
+    pub fn remove(&mut self, name: &str) -> Result<(), DriverError> {
+        let entry = self.drivers.get_mut(name).ok_or(DriverError::DeviceNotFound)?;
+        if entry.state == DriverState::Ready {
+            entry.deinit()?;
+        }
+        
+        self.drivers.remove(name);
         Ok(())
     }
     
+    /// 驱动的生命周期状态
+    pub fn state(&self, name: &str) -> Option<DriverState> {
+        self.drivers.get(name).map(|entry| entry.state)
+    }
+    
+    /// 已注册的驱动名称，按名称排序
+    pub fn names(&self) -> Vec<&str> {
+        self.drivers.keys().map(String::as_str).collect()
+    }
+    
     /// 按名称查找驱动
     pub fn find_by_name(&self, name: &str) -> Option<&dyn Driver> {
-        self.drivers.get(name).map(|d| d.as_ref())
+        self.drivers.get(name).map(|entry| entry.driver.as_ref())
     }
     
     /// 按类型查找驱动
     pub fn find<T: Driver>(&self, name: &str) -> Option<&T> {
         self.drivers.get(name)
-            .and_then(|d| d.as_any().downcast_ref::<T>())
+            .and_then(|entry| entry.driver.as_any().downcast_ref::<T>())
     }
     
     /// 获取驱动数量
@@ -54,16 +138,41 @@ impl DriverRegistry {
     
     /// 初始化所有驱动
     pub fn init_all(&mut self) -> Result<(), DriverError> {
-        for (_, driver) in self.drivers.iter_mut() {
-            driver.init()?;
+        for (_, entry) in self.drivers.iter_mut() {
+            entry.init()?;
+        }
+        Ok(())
+    }
+    
+    /// 事务式初始化所有驱动
+    /// 
+    /// 按名称顺序初始化，某个驱动失败时按相反顺序卸载已初始化的驱动；
+    /// 卸载失败不中断回滚，全部记录在返回的失败信息中
+    pub fn init_all_transactional(&mut self) -> Result<(), InitFailure> {
+        let mut initialized: Vec<&mut DriverEntry> = Vec::new();
+        for entry in self.drivers.values_mut() {
+            if let Err(error) = entry.init() {
+                let rollback_errors = initialized
+                    .into_iter()
+                    .rev()
+                    .filter_map(|done| done.deinit().err().map(|e| (done.driver.name(), e)))
+                    .collect();
+                return Err(InitFailure { driver: entry.driver.name(), error, rollback_errors });
+            }
+            initialized.push(entry);
         }
         Ok(())
     }
     
+    /// 按名称初始化单个驱动
+    pub fn try_init(&mut self, name: &str) -> Result<(), DriverError> {
+        self.drivers.get_mut(name).ok_or(DriverError::DeviceNotFound)?.init()
+    }
+    
     /// 卸载所有驱动
     pub fn deinit_all(&mut self) -> Result<(), DriverError> {
-        for (_, driver) in self.drivers.iter_mut() {
-            driver.deinit()?;
+        for (_, entry) in self.drivers.iter_mut() {
+            entry.deinit()?;
         }
         Ok(())
     }
@@ -88,4 +197,120 @@ impl dyn Driver {
             &*(self as *const dyn Driver as *const dyn DriverAny)
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::rc::Rc;
+    use alloc::string::ToString;
+    use core::cell::RefCell;
+
+    /// 记录初始化和卸载调用的驱动，可配置为初始化或卸载失败
+    struct FakeDriver {
+        name: &'static str,
+        log: Rc<RefCell<Vec<String>>>,
+        fail_init: bool,
+        fail_deinit: bool,
+        ready: bool,
+    }
+
+    impl Driver for FakeDriver {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn init(&mut self) -> Result<(), DriverError> {
+            self.log.borrow_mut().push(["init ", self.name].concat());
+            if self.fail_init {
+                return Err(DriverError::InitializationFailed);
+            }
+            self.ready = true;
+            Ok(())
+        }
+
+        fn is_ready(&self) -> bool {
+            self.ready
+        }
+
+        fn deinit(&mut self) -> Result<(), DriverError> {
+            self.log.borrow_mut().push(["deinit ", self.name].concat());
+            if self.fail_deinit {
+                return Err(DriverError::DeviceBusy);
+            }
+            self.ready = false;
+            Ok(())
+        }
+    }
+
+    fn registry(log: &Rc<RefCell<Vec<String>>>, fail_deinit: &[&str]) -> DriverRegistry {
+        let mut registry = DriverRegistry::new();
+        for name in ["a_uart", "b_i2c", "c_spi", "d_usb"] {
+            let driver = FakeDriver { name, log: log.clone(), fail_init: name == "c_spi", fail_deinit: fail_deinit.contains(&name), ready: false };
+            registry.register(Box::new(driver)).unwrap();
+        }
+        registry
+    }
+
+    #[test]
+    fn test_third_init_failure_rolls_back_in_reverse() {
+        // 第三个驱动初始化失败：前两个按相反顺序卸载，第四个不再初始化
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = registry(&log, &[]);
+
+        let failure = registry.init_all_transactional().unwrap_err();
+        assert_eq!(failure, InitFailure { driver: "c_spi", error: DriverError::InitializationFailed, rollback_errors: Vec::new() });
+        assert_eq!(*log.borrow(), ["init a_uart", "init b_i2c", "init c_spi", "deinit b_i2c", "deinit a_uart"].map(|s| s.to_string()));
+
+        log.borrow_mut().clear();
+        assert_eq!(registry.try_init("d_usb"), Ok(()));
+        assert_eq!(registry.try_init("missing"), Err(DriverError::DeviceNotFound));
+        assert_eq!(*log.borrow(), ["init d_usb".to_string()]);
+    }
+
+    #[test]
+    fn test_rollback_collects_deinit_failures() {
+        // 回滚中卸载失败的驱动被记录，其余驱动仍然卸载
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = registry(&log, &["b_i2c"]);
+
+        let failure = registry.init_all_transactional().unwrap_err();
+        assert_eq!(failure.rollback_errors, vec![("b_i2c", DriverError::DeviceBusy)]);
+        assert!(log.borrow().contains(&"deinit a_uart".to_string()));
+    }
+
+    #[test]
+    fn test_hot_plug_add_then_remove() {
+        // 运行时接入的驱动立即初始化，拔出时卸载并移除；重名驱动被拒绝
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = DriverRegistry::new();
+        let sensor = || Box::new(FakeDriver { name: "usb_sensor", log: log.clone(), fail_init: false, fail_deinit: false, ready: false });
+
+        assert_eq!(registry.register_and_init(sensor()), Ok("usb_sensor"));
+        assert_eq!(registry.register_and_init(sensor()), Err(DriverError::InvalidParameter));
+        assert_eq!(registry.names(), vec!["usb_sensor"]);
+
+        assert_eq!(registry.remove("usb_sensor"), Ok(()));
+        assert!(registry.names().is_empty());
+        assert_eq!(registry.remove("usb_sensor"), Err(DriverError::DeviceNotFound));
+        assert_eq!(*log.borrow(), ["init usb_sensor", "deinit usb_sensor"].map(|s| s.to_string()));
+    }
+
+    #[test]
+    fn test_remove_deinits_only_ready_driver() {
+        // 未初始化或初始化失败的驱动直接移除，不调用卸载；已初始化的驱动先卸载再移除
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = registry(&log, &[]);
+
+        assert_eq!(registry.remove("b_i2c"), Ok(()));
+        assert_eq!(registry.try_init("c_spi"), Err(DriverError::InitializationFailed));
+        assert_eq!(registry.state("c_spi"), Some(DriverState::Registered));
+        assert_eq!(registry.remove("c_spi"), Ok(()));
+
+        assert_eq!(registry.try_init("d_usb"), Ok(()));
+        assert_eq!(registry.state("d_usb"), Some(DriverState::Ready));
+        assert_eq!(registry.remove("d_usb"), Ok(()));
+        assert_eq!(registry.names(), vec!["a_uart"]);
+        assert_eq!(*log.borrow(), ["init c_spi", "init d_usb", "deinit d_usb"].map(|s| s.to_string()));
+    }
+}
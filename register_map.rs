@@ -0,0 +1,158 @@
+//! 寄存器映射模块
+//!
+//! 将外设的寄存器布局（基地址、偏移、字节序）与控制器逻辑分离，
+//! 同一套控制器逻辑通过不同的寄存器映射即可复用于不同SoC
+
+use alloc::vec::Vec;
+use core::arch::asm;
+use core::sync::atomic::{AtomicU32, Ordering};
+
+/// 寄存器字节序
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endianness {
+    Little,
+    Big,
+}
+
+/// 寄存器访问后端
+///
+/// 地址为寄存器的绝对地址，边界由寄存器映射或调用者保证
+pub trait HardwareBackend: Send + Sync {
+    /// 读取32位寄存器
+    fn read32(&self, addr: usize) -> u32;
+
+    /// 写入32位寄存器
+    fn write32(&self, addr: usize, value: u32);
+}
+
+/// MMIO寄存器后端
+#[derive(Debug, Clone, Copy)]
+pub struct MmioBackend {
+    _private: (),
+}
+
+impl MmioBackend {
+    /// 创建MMIO后端
+    ///
+    /// # Safety
+    /// 通过该后端访问的地址必须是已映射的设备寄存器
+    pub const unsafe fn new() -> Self {
+        Self { _private: () }
+    }
+}
+
+impl HardwareBackend for MmioBackend {
+    fn read32(&self, addr: usize) -> u32 {
+        unsafe {
+            let value = core::ptr::read_volatile(addr as *const u32);
+            // 确保后续访存不会越过本次寄存器读取
+            asm!("dsb sy");
+            value
+        }
+    }
+
+    fn write32(&self, addr: usize, value: u32) {
+        unsafe {
+            // 确保之前的内存写入（如DMA缓冲区）先于寄存器写入完成
+            asm!("dsb sy");
+            core::ptr::write_volatile(addr as *mut u32, value);
+        }
+    }
+}
+
+/// 模拟寄存器后端，覆盖`[base, base + window_size)`的寄存器窗口
+pub struct MockHardwareBackend {
+    base: usize,
+    registers: Vec<AtomicU32>,
+}
+
+impl MockHardwareBackend {
+    /// 创建模拟后端
+    pub fn new(base: usize, window_size: usize) -> Self {
+        Self {
+            base,
+            registers: (0..window_size / 4).map(|_| AtomicU32::new(0)).collect(),
+        }
+    }
+
+    fn register(&self, addr: usize) -> &AtomicU32 {
+        addr.checked_sub(self.base)
+            .and_then(|offset| self.registers.get(offset / 4))
+            .expect("模拟寄存器地址越界")
+    }
+}
+
+impl HardwareBackend for MockHardwareBackend {
+    fn read32(&self, addr: usize) -> u32 {
+        self.register(addr).load(Ordering::SeqCst)
+    }
+
+    fn write32(&self, addr: usize, value: u32) {
+        self.register(addr).store(value, Ordering::SeqCst);
+    }
+}
+
+/// 寄存器映射
+///
+/// `R`为某类外设的寄存器名称，映射给出该外设实例的基地址、各寄存器偏移和字节序
+pub trait RegisterMap<R> {
+    /// 外设寄存器基地址
+    fn base(&self) -> usize;
+
+    /// 寄存器相对基地址的偏移
+    fn offset(&self, register: R) -> usize;
+
+    /// 寄存器字节序
+    fn endianness(&self) -> Endianness {
+        Endianness::Little
+    }
+
+    /// 读取寄存器
+    fn read<B: HardwareBackend + ?Sized>(&self, backend: &B, register: R) -> u32 {
+        let raw = backend.read32(self.base() + self.offset(register));
+        match self.endianness() {
+            Endianness::Little => u32::from_le(raw),
+            Endianness::Big => u32::from_be(raw),
+        }
+    }
+
+    /// 写入寄存器
+    fn write<B: HardwareBackend + ?Sized>(&self, backend: &B, register: R, value: u32) {
+        let raw = match self.endianness() {
+            Endianness::Little => value.to_le(),
+            Endianness::Big => value.to_be(),
+        };
+        backend.write32(self.base() + self.offset(register), raw);
+    }
+
+    /// 读-改-写寄存器
+    fn modify<B, F>(&self, backend: &B, register: R, f: F)
+    where
+        R: Copy,
+        B: HardwareBackend + ?Sized,
+        F: FnOnce(u32) -> u32,
+    {
+        let value = self.read(backend, register);
+        self.write(backend, register, f(value));
+    }
+}
+
+/// RK3588寄存器映射
+///
+/// 各外设的寄存器偏移在对应驱动模块中实现
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rk3588Map {
+    base: usize,
+}
+
+impl Rk3588Map {
+    /// 创建指定外设实例基地址的映射
+    pub const fn new(base: usize) -> Self {
+        Self { base }
+    }
+
+    /// 外设实例基地址
+    pub const fn base_address(&self) -> usize {
+        self.base
+    }
+}
@@ -4,9 +4,90 @@
 
 #![no_std]
 
-use core::sync::atomic::{AtomicBool, Ordering};
+use core::sync::atomic::{AtomicBool, AtomicU8, Ordering};
 use core::fmt;
 use core::cell::UnsafeCell;
+use core::iter;
+
+use alloc::vec::Vec;
+
+use crate::clock::{PeripheralClock, CLOCK_CONTROLLER};
+use crate::dma::{DmaChannel, DmaDescriptor, DmaDirection, DmaMode, DmaResult, DmacChannel};
+
+/// 控制寄存器0：时钟相位（CPHA）
+const CTRLR0_SCPH: u32 = 1 << 6;
+/// 控制寄存器0：时钟极性（CPOL）
+const CTRLR0_SCPOL: u32 = 1 << 7;
+
+/// DMA控制寄存器：接收DMA使能
+const DMACR_RDMAE: u32 = 1 << 0;
+/// DMA控制寄存器：发送DMA使能
+const DMACR_TDMAE: u32 = 1 << 1;
+
+/// TX/RX FIFO深度
+const SPI_FIFO_DEPTH: usize = 32;
+
+/// 片选线数量
+const SPI_CHIP_SELECTS: u8 = 4;
+
+/// 低于该长度的传输使用PIO，DMA的配置开销得不偿失
+pub const SPI_DMA_THRESHOLD: usize = 32;
+
+/// 等待DMA收发完成的最大轮询次数
+const SPI_DMA_TIMEOUT: u32 = 1_000_000;
+
+/// SPI的DMAC收发通道
+///
+/// 两个通道的外设端都固定为SPI数据寄存器，分别由TX/RX FIFO的DMA请求驱动
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpiDmaChannels {
+    /// 发送通道
+    pub tx: DmacChannel,
+    /// 接收通道
+    pub rx: DmacChannel,
+    /// TX FIFO的DMAC外设请求号
+    pub tx_request: u8,
+    /// RX FIFO的DMAC外设请求号
+    pub rx_request: u8,
+}
+
+/// 传输方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferPath {
+    /// 逐字节经FIFO收发
+    Pio,
+    /// DMA收发，`rx_watermark`为写入DMARDLR的请求水位（RX FIFO数据数减1）
+    Dma { rx_watermark: u32 },
+}
+
+impl TransferPath {
+    /// 根据传输长度选择传输方式
+    pub fn for_length(len: usize) -> Self {
+        if len < SPI_DMA_THRESHOLD {
+            TransferPath::Pio
+        } else {
+            TransferPath::Dma { rx_watermark: (len.min(SPI_FIFO_DEPTH / 2) - 1) as u32 }
+        }
+    }
+}
+
+/// 全双工传输中实际移出的数据：较短的发送数据末尾补0，长度为收发两侧的较大值
+fn padded_tx(tx_data: &[u8], rx_len: usize) -> impl Iterator<Item = u8> + '_ {
+    tx_data.iter().copied().chain(iter::repeat(0)).take(tx_data.len().max(rx_len))
+}
+
+/// 由控制器时钟和目标SCLK频率计算分频系数
+///
+/// 分频系数必须为不小于2的偶数，向上取整以保证不超过目标频率
+pub fn baud_divider(spi_clk: u32, target_speed: u32) -> Result<u16, SpiError> {
+    if target_speed == 0 || target_speed > spi_clk / 2 {
+        return Err(SpiError::HardwareError);
+    }
+
+    let divisor = spi_clk.div_ceil(target_speed);
+    let divisor = divisor + (divisor & 1);
+    u16::try_from(divisor).map_err(|_| SpiError::HardwareError)
+}
 
 /// SPI错误类型
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -63,6 +144,35 @@ pub enum SpiMode {
     Mode3, // CPOL=1, CPHA=1
 }
 
+impl SpiMode {
+    /// 由时钟极性和相位得到模式
+    pub fn from_cpol_cpha(cpol: bool, cpha: bool) -> Self {
+        match (cpol, cpha) {
+            (false, false) => SpiMode::Mode0,
+            (false, true) => SpiMode::Mode1,
+            (true, false) => SpiMode::Mode2,
+            (true, true) => SpiMode::Mode3,
+        }
+    }
+
+    /// 时钟极性：空闲时SCLK为高
+    pub fn cpol(self) -> bool {
+        matches!(self, SpiMode::Mode2 | SpiMode::Mode3)
+    }
+
+    /// 时钟相位：在第二个边沿采样
+    pub fn cpha(self) -> bool {
+        matches!(self, SpiMode::Mode1 | SpiMode::Mode3)
+    }
+
+    /// 模式在CTRLR0中对应的位
+    pub fn ctrlr0_bits(self) -> u32 {
+        let cpol = if self.cpol() { CTRLR0_SCPOL } else { 0 };
+        let cpha = if self.cpha() { CTRLR0_SCPH } else { 0 };
+        cpol | cpha
+    }
+}
+
 /// SPI数据位
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SpiDataBits {
@@ -112,6 +222,10 @@ pub struct Rk3588Spi {
     registers: *mut SpiRegisters,
     config: SpiConfig,
     initialized: AtomicBool,
+    /// 传输时使能的片选线
+    chip_select: AtomicU8,
+    /// DMA收发通道，未配置时DMA传输回退为PIO
+    dma: Option<SpiDmaChannels>,
 }
 
 impl Rk3588Spi {
@@ -128,6 +242,8 @@ impl Rk3588Spi {
             registers: base_address as *mut SpiRegisters,
             config,
             initialized: AtomicBool::new(false),
+            chip_select: AtomicU8::new(0),
+            dma: None,
         }
     }
     
@@ -158,42 +274,146 @@ impl Rk3588Spi {
         Ok(())
     }
     
-    /// 传输数据（同时发送和接收）
+    /// 全双工传输：每移出一个字节同时移入一个字节
+    ///
+    /// 收发长度不一致时较短的一侧补0：发送数据不足时补发0，接收缓冲区不足时丢弃多余的接收数据
     pub fn transfer(&self, tx_data: &[u8], rx_buffer: &mut [u8]) -> Result<(), SpiError> {
         if !self.initialized.load(Ordering::Acquire) {
             return Err(SpiError::NotInitialized);
         }
         
-        if tx_data.len() != rx_buffer.len() {
-            return Err(SpiError::BufferOverflow);
-        }
-        
+        let slave = self.chip_select();
         unsafe {
             // 选择从机
-            self.select_slave(0)?;
+            self.select_slave(slave)?;
             
             // 传输数据
-            for (i, &tx_byte) in tx_data.iter().enumerate() {
+            for (i, tx_byte) in padded_tx(tx_data, rx_buffer.len()).enumerate() {
                 self.write_byte(tx_byte)?;
-                rx_buffer[i] = self.read_byte()?;
+                let rx_byte = self.read_byte()?;
+                if let Some(slot) = rx_buffer.get_mut(i) {
+                    *slot = rx_byte;
+                }
             }
             
             // 取消选择从机
-            self.deselect_slave(0)?;
+            self.deselect_slave(slave)?;
         }
         
         Ok(())
     }
     
+    /// 配置DMA收发通道
+    pub fn set_dma_channels(&mut self, channels: SpiDmaChannels) {
+        self.dma = Some(channels);
+    }
+    
+    /// DMA全双工传输，收发长度不一致时的处理与`transfer`相同
+    ///
+    /// 长度低于`SPI_DMA_THRESHOLD`或未配置DMA通道时回退为PIO传输。
+    /// RX通道先于TX通道启动，两者同时运行，RX FIFO随移入随被取走，不会溢出
+    pub fn transfer_dma(&self, tx_data: &[u8], rx_buffer: &mut [u8]) -> Result<(), SpiError> {
+        let total = tx_data.len().max(rx_buffer.len());
+        let (channels, rx_watermark) = match (self.dma, TransferPath::for_length(total)) {
+            (Some(channels), TransferPath::Dma { rx_watermark }) => (channels, rx_watermark),
+            _ => return self.transfer(tx_data, rx_buffer),
+        };
+        
+        if !self.initialized.load(Ordering::Acquire) {
+            return Err(SpiError::NotInitialized);
+        }
+        
+        // DMA需要等长的连续缓冲区，长度不一致时使用补齐后的临时缓冲区
+        let tx_padded: Vec<u8>;
+        let tx = if tx_data.len() == total {
+            tx_data
+        } else {
+            tx_padded = padded_tx(tx_data, total).collect();
+            &tx_padded
+        };
+        let mut rx_scratch = Vec::new();
+        let rx: &mut [u8] = if rx_buffer.len() == total {
+            &mut *rx_buffer
+        } else {
+            rx_scratch.resize(total, 0);
+            &mut rx_scratch
+        };
+        
+        let fifo = unsafe { (*self.registers).dr.get() as u64 };
+        let mut tx_descriptor = DmaDescriptor::new();
+        tx_descriptor.configure(tx.as_ptr() as u64, fifo, total as u32, DmaDirection::MemoryToDevice, DmaMode::Single);
+        tx_descriptor.set_peripheral_request(channels.tx_request);
+        let mut rx_descriptor = DmaDescriptor::new();
+        rx_descriptor.configure(fifo, rx.as_mut_ptr() as u64, total as u32, DmaDirection::DeviceToMemory, DmaMode::Single);
+        rx_descriptor.set_peripheral_request(channels.rx_request);
+        
+        let slave = self.chip_select();
+        unsafe {
+            self.select_slave(slave)?;
+            
+            // 配置请求水位并使能收发DMA
+            (*self.registers).dmatdlr.get().write_volatile((SPI_FIFO_DEPTH / 2) as u32);
+            (*self.registers).dmardlr.get().write_volatile(rx_watermark);
+            (*self.registers).dmacr.get().write_volatile(DMACR_TDMAE | DMACR_RDMAE);
+        }
+        
+        let outcome = Self::run_duplex(&channels, &tx_descriptor, &rx_descriptor);
+        
+        unsafe {
+            (*self.registers).dmacr.get().write_volatile(0);
+            self.deselect_slave(slave)?;
+        }
+        
+        let (tx_result, rx_result) = outcome?;
+        if tx_result.bytes_transferred < total || rx_result.bytes_transferred < total {
+            return Err(SpiError::Timeout);
+        }
+        
+        if rx_buffer.len() != total {
+            let len = rx_buffer.len();
+            rx_buffer.copy_from_slice(&rx_scratch[..len]);
+        }
+        Ok(())
+    }
+    
+    /// 同时启动RX和TX通道并等待两者结束，超时则停止两个通道
+    fn run_duplex(
+        channels: &SpiDmaChannels,
+        tx_descriptor: &DmaDescriptor,
+        rx_descriptor: &DmaDescriptor,
+    ) -> Result<(DmaResult, DmaResult), SpiError> {
+        channels.rx.start(rx_descriptor).map_err(|_| SpiError::HardwareError)?;
+        if channels.tx.start(tx_descriptor).is_err() {
+            channels.rx.stop();
+            return Err(SpiError::HardwareError);
+        }
+        
+        let mut timeout = SPI_DMA_TIMEOUT;
+        while channels.tx.is_busy() || channels.rx.is_busy() {
+            if timeout == 0 {
+                channels.tx.stop();
+                channels.rx.stop();
+                return Err(SpiError::Timeout);
+            }
+            timeout -= 1;
+            core::hint::spin_loop();
+        }
+        
+        channels.tx.take_completion();
+        channels.rx.take_completion();
+        Ok((channels.tx.result(), channels.rx.result()))
+    }
+    
     /// 只发送数据
     pub fn write(&self, data: &[u8]) -> Result<(), SpiError> {
         if !self.initialized.load(Ordering::Acquire) {
             return Err(SpiError::NotInitialized);
         }
         
+        let slave = self.chip_select();
         unsafe {
             // 选择从机
-            self.select_slave(0)?;
+            self.select_slave(slave)?;
             
             // 发送数据
             for &byte in data {
@@ -201,7 +421,7 @@ impl Rk3588Spi {
             }
             
             // 取消选择从机
-            self.deselect_slave(0)?;
+            self.deselect_slave(slave)?;
         }
         
         Ok(())
@@ -213,9 +433,10 @@ impl Rk3588Spi {
             return Err(SpiError::NotInitialized);
         }
         
+        let slave = self.chip_select();
         unsafe {
             // 选择从机
-            self.select_slave(0)?;
+            self.select_slave(slave)?;
             
             // 接收数据（发送0xFF以产生时钟）
             for byte in buffer.iter_mut() {
@@ -224,12 +445,64 @@ impl Rk3588Spi {
             }
             
             // 取消选择从机
-            self.deselect_slave(0)?;
+            self.deselect_slave(slave)?;
         }
         
         Ok(())
     }
     
+    /// 设置传输时使能的片选线（0-3）
+    pub fn set_chip_select(&self, chip_select: u8) -> Result<(), SpiError> {
+        if chip_select >= SPI_CHIP_SELECTS {
+            return Err(SpiError::HardwareError);
+        }
+        
+        self.chip_select.store(chip_select, Ordering::Release);
+        Ok(())
+    }
+    
+    /// 当前片选线
+    pub fn chip_select(&self) -> u8 {
+        self.chip_select.load(Ordering::Acquire)
+    }
+    
+    /// 切换时钟极性和相位
+    pub fn set_mode(&mut self, mode: SpiMode) -> Result<(), SpiError> {
+        self.config.mode = mode;
+        if !self.initialized.load(Ordering::Acquire) {
+            return Ok(());
+        }
+        
+        // CTRLR0只能在控制器禁用时写入
+        unsafe {
+            self.disable();
+            let result = self.configure_mode();
+            self.enable();
+            result
+        }
+    }
+    
+    /// 直接设置SCLK分频系数（不小于2的偶数），SCLK = 控制器时钟 / 分频系数
+    pub fn set_clock_divider(&mut self, divider: u16) -> Result<(), SpiError> {
+        if divider < 2 || divider % 2 != 0 {
+            return Err(SpiError::InvalidMode);
+        }
+        
+        let spi_clk = CLOCK_CONTROLLER.get_clock(PeripheralClock::Spi);
+        self.config.clock_speed = spi_clk / divider as u32;
+        if !self.initialized.load(Ordering::Acquire) {
+            return Ok(());
+        }
+        
+        // BAUDR只能在控制器禁用时写入
+        unsafe {
+            self.disable();
+            (*self.registers).baudr.get().write_volatile(divider as u32);
+            self.enable();
+        }
+        Ok(())
+    }
+    
     /// 检查总线是否繁忙
     pub fn is_bus_busy(&self) -> Result<bool, SpiError> {
         if !self.initialized.load(Ordering::Acquire) {
@@ -266,20 +539,7 @@ impl Rk3588Spi {
         let mut ctrlr0 = 0u32;
         
         // 配置SPI模式
-        match self.config.mode {
-            SpiMode::Mode0 => {
-                ctrlr0 |= (0b00 << 6); // CPOL=0, CPHA=0
-            }
-            SpiMode::Mode1 => {
-                ctrlr0 |= (0b01 << 6); // CPOL=0, CPHA=1
-            }
-            SpiMode::Mode2 => {
-                ctrlr0 |= (0b10 << 6); // CPOL=1, CPHA=0
-            }
-            SpiMode::Mode3 => {
-                ctrlr0 |= (0b11 << 6); // CPOL=1, CPHA=1
-            }
-        }
+        ctrlr0 |= self.config.mode.ctrlr0_bits();
         
         // 配置数据位
         match self.config.data_bits {
@@ -306,19 +566,8 @@ impl Rk3588Spi {
     }
     
     unsafe fn configure_baud_rate(&self) -> Result<(), SpiError> {
-        let spi_clk = 200_000_000; // SPI控制器时钟频率 (200MHz)
-        let target_speed = self.config.clock_speed;
-        
-        if target_speed == 0 || target_speed > spi_clk / 2 {
-            return Err(SpiError::HardwareError);
-        }
-        
-        // 计算分频系数
-        let divisor = (spi_clk + target_speed - 1) / target_speed;
-        
-        if divisor > 0xFFFF {
-            return Err(SpiError::HardwareError);
-        }
+        let spi_clk = CLOCK_CONTROLLER.get_clock(PeripheralClock::Spi); // SPI控制器时钟频率
+        let divisor = baud_divider(spi_clk, self.config.clock_speed)?;
         
         (*self.registers).baudr.get().write_volatile(divisor as u32);
         Ok(())
@@ -454,16 +703,19 @@ impl SpiDevice {
     
     /// 传输数据
     pub fn transfer(&mut self, tx_data: &[u8], rx_buffer: &mut [u8]) -> Result<(), SpiError> {
+        self.controller.set_chip_select(self.slave)?;
         self.controller.transfer(tx_data, rx_buffer)
     }
     
     /// 写入数据
     pub fn write(&mut self, data: &[u8]) -> Result<(), SpiError> {
+        self.controller.set_chip_select(self.slave)?;
         self.controller.write(data)
     }
     
     /// 读取数据
     pub fn read(&mut self, buffer: &mut [u8]) -> Result<(), SpiError> {
+        self.controller.set_chip_select(self.slave)?;
         self.controller.read(buffer)
     }
     
@@ -486,4 +738,37 @@ impl SpiDevice {
         
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mode_bits_and_clock_divider() {
+        // CPOL对应CTRLR0第7位、CPHA对应第6位；分频系数向上取整为偶数
+        assert_eq!(SpiMode::Mode0.ctrlr0_bits(), 0);
+        assert_eq!(SpiMode::Mode1.ctrlr0_bits(), CTRLR0_SCPH);
+        assert_eq!(SpiMode::Mode2.ctrlr0_bits(), CTRLR0_SCPOL);
+        assert_eq!(SpiMode::Mode3.ctrlr0_bits(), CTRLR0_SCPOL | CTRLR0_SCPH);
+        for mode in [SpiMode::Mode0, SpiMode::Mode1, SpiMode::Mode2, SpiMode::Mode3] {
+            assert_eq!(SpiMode::from_cpol_cpha(mode.cpol(), mode.cpha()), mode);
+        }
+
+        assert_eq!(baud_divider(100_000_000, 10_000_000), Ok(10));
+        assert_eq!(baud_divider(100_000_000, 30_000_000), Ok(4));
+        assert_eq!(baud_divider(100_000_000, 60_000_000), Err(SpiError::HardwareError));
+        assert_eq!(baud_divider(100_000_000, 1_000), Err(SpiError::HardwareError));
+    }
+
+    #[test]
+    fn test_length_mismatch_pads_with_zeros() {
+        // 发送较短时补0到接收长度，接收较短时按发送长度移出
+        assert_eq!(padded_tx(&[0xA5, 0x5A], 4).collect::<Vec<_>>(), vec![0xA5, 0x5A, 0, 0]);
+        assert_eq!(padded_tx(&[1, 2, 3], 1).collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(padded_tx(&[], 2).collect::<Vec<_>>(), vec![0, 0]);
+
+        assert_eq!(TransferPath::for_length(SPI_DMA_THRESHOLD - 1), TransferPath::Pio);
+        assert_eq!(TransferPath::for_length(SPI_DMA_THRESHOLD), TransferPath::Dma { rx_watermark: 15 });
+    }
+}
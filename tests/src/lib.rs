@@ -5,6 +5,8 @@
 #![cfg_attr(not(test), no_std)]
 #![cfg_attr(test, feature(test))]
 
+extern crate alloc;
+
 // 测试模块
 pub mod unit;
 pub mod integration;
@@ -16,6 +18,7 @@ pub mod automated_tests;
 // 测试工具模块
 mod utils;
 
+use alloc::collections::VecDeque;
 use core::fmt;
 
 /// 测试错误类型
@@ -88,9 +91,95 @@ pub trait TestSuite {
     }
 }
 
+/// 有界测试结果集合
+///
+/// 设置`max_results`后以环形缓冲区方式只保留最近N条详细结果，
+/// 通过/失败/总数等聚合统计则始终覆盖全部结果。
+#[derive(Debug, Clone)]
+pub struct TestResultBuffer {
+    results: VecDeque<TestResult>,
+    max_results: Option<usize>,
+    total_tests: usize,
+    passed_tests: usize,
+    failed_tests: usize,
+    total_duration_ms: u64,
+}
+
+impl TestResultBuffer {
+    /// 创建新的结果集合，`None`表示不限制容量
+    pub fn new(max_results: Option<usize>) -> Self {
+        Self {
+            results: VecDeque::new(),
+            max_results,
+            total_tests: 0,
+            passed_tests: 0,
+            failed_tests: 0,
+            total_duration_ms: 0,
+        }
+    }
+    
+    /// 记录一条测试结果，超出容量时淘汰最旧的结果
+    pub fn push(&mut self, result: TestResult) {
+        self.total_tests += 1;
+        if result.passed {
+            self.passed_tests += 1;
+        } else {
+            self.failed_tests += 1;
+        }
+        self.total_duration_ms += result.duration_ms;
+        
+        if let Some(max) = self.max_results {
+            if max == 0 {
+                return;
+            }
+            if self.results.len() >= max {
+                self.results.pop_front();
+            }
+        }
+        self.results.push_back(result);
+    }
+    
+    /// 当前保留的详细结果（按时间顺序）
+    pub fn results(&self) -> impl Iterator<Item = &TestResult> {
+        self.results.iter()
+    }
+    
+    /// 当前保留的详细结果数量
+    pub fn retained(&self) -> usize {
+        self.results.len()
+    }
+    
+    /// 已记录的测试总数
+    pub fn total_tests(&self) -> usize {
+        self.total_tests
+    }
+    
+    /// 已通过的测试数
+    pub fn passed_tests(&self) -> usize {
+        self.passed_tests
+    }
+    
+    /// 已失败的测试数
+    pub fn failed_tests(&self) -> usize {
+        self.failed_tests
+    }
+    
+    /// 转换为测试报告，统计数据基于全部结果
+    pub fn into_report(self) -> TestReport {
+        TestReport {
+            total_tests: self.total_tests,
+            passed_tests: self.passed_tests,
+            failed_tests: self.failed_tests,
+            total_duration_ms: self.total_duration_ms,
+            results: self.results.into_iter().collect(),
+        }
+    }
+}
+
 /// 测试运行器
 pub struct TestRunner {
     suites: Vec<Box<dyn TestSuite>>,
+    max_results: Option<usize>,
 }
 
 impl TestRunner {
@@ -98,24 +187,36 @@ impl TestRunner {
     pub fn new() -> Self {
         Self {
             suites: Vec::new(),
+            max_results: None,
         }
     }
     
+    /// 设置详细结果保留上限（环形缓冲区模式），`None`表示不限制
+    pub fn set_max_results(&mut self, max_results: Option<usize>) {
+        self.max_results = max_results;
+    }
+    
     /// 注册测试套件
     pub fn register_suite<T: TestSuite + 'static>(&mut self, suite: T) {
         self.suites.push(Box::new(suite));
     }
     
-    /// 运行所有测试套件
+    /// 运行所有测试套件，返回保留的详细结果
     pub fn run_all(&self) -> Vec<TestResult> {
-        let mut all_results = Vec::new();
+        self.run_all_collected().results().cloned().collect()
+    }
+    
+    /// 运行所有测试套件，返回带聚合统计的有界结果集合
+    pub fn run_all_collected(&self) -> TestResultBuffer {
+        let mut buffer = TestResultBuffer::new(self.max_results);
         
         for suite in &self.suites {
-            let results = suite.run();
-            all_results.extend(results);
+            for result in suite.run() {
+                buffer.push(result);
+            }
         }
         
-        all_results
+        buffer
     }
     
     /// 生成测试报告
@@ -192,8 +293,7 @@ pub fn init() {
 pub fn run_all_tests() -> TestReport {
     unsafe {
         if let Some(runner) = &TEST_RUNNER {
-            let results = runner.run_all();
-            runner.generate_report(&results)
+            runner.run_all_collected().into_report()
         } else {
             panic!("测试运行器未初始化");
         }
@@ -255,4 +355,68 @@ macro_rules! assert_false {
             panic!("断言失败: 表达式为真");
         }
     };
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct MixedSuite {
+        count: usize,
+    }
+
+    impl TestSuite for MixedSuite {
+        fn name(&self) -> &'static str {
+            "mixed"
+        }
+
+        fn run(&self) -> Vec<TestResult> {
+            (0..self.count)
+                .map(|i| {
+                    if i % 3 == 0 {
+                        TestResult::failure("fail", TestError::TestFailed, None)
+                    } else {
+                        TestResult::success("pass", 1)
+                    }
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn test_bounded_runner_keeps_aggregates() {
+        // 超出容量后只保留最近N条，聚合统计仍覆盖全部结果
+        let mut runner = TestRunner::new();
+        runner.set_max_results(Some(4));
+        runner.register_suite(MixedSuite { count: 10 });
+
+        let buffer = runner.run_all_collected();
+        assert_eq!(buffer.retained(), 4);
+        assert_eq!(buffer.total_tests(), 10);
+        assert_eq!(buffer.failed_tests(), 4);
+        assert_eq!(buffer.passed_tests(), 6);
+
+        let report = buffer.into_report();
+        assert_eq!(report.results.len(), 4);
+        assert_eq!(report.total_tests, 10);
+        assert_eq!(report.total_duration_ms, 6);
+    }
+
+    #[test]
+    fn test_ring_buffer_evicts_oldest() {
+        // 环形缓冲区淘汰最旧的结果
+        let mut buffer = TestResultBuffer::new(Some(2));
+        buffer.push(TestResult::success("a", 1));
+        buffer.push(TestResult::success("b", 1));
+        buffer.push(TestResult::success("c", 1));
+
+        let names: Vec<&str> = buffer.results().map(|r| r.name).collect();
+        assert!(names == vec!["b", "c"]);
+        assert_eq!(buffer.total_tests(), 3);
+
+        // 不限制容量时保留全部结果
+        let mut runner = TestRunner::new();
+        runner.register_suite(MixedSuite { count: 10 });
+        assert_eq!(runner.run_all().len(), 10);
+    }
+}
@@ -8,6 +8,20 @@ use core::sync::atomic::{AtomicBool, Ordering};
 use core::fmt;
 use core::cell::UnsafeCell;
 
+use alloc::boxed::Box;
+
+use common::RingBuffer;
+use starry_kernel::gic::{has_interrupt_handler, request_irq, PriorityBand};
+use starry_kernel::sync::IrqMutex;
+
+use crate::clock::{PeripheralClock, CLOCK_CONTROLLER};
+use crate::dma::{DmaBuffer, DmaChannel, DmaDescriptor, DmaDirection, DmaMode, DmacChannel, DMAC0_BASE, DMAC0_IRQ};
+
+/// DMA控制寄存器：接收DMA使能
+const UART_DMACR_RXDMAE: u32 = 1 << 0;
+/// DMA控制寄存器：发送DMA使能
+const UART_DMACR_TXDMAE: u32 = 1 << 1;
+
 /// UART错误类型
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum UartError {
@@ -254,6 +268,20 @@ impl Rk3588Uart {
         }
     }
     
+    /// 使能TX DMA请求，TX FIFO低于触发级别时向DMAC发出请求
+    pub fn enable_tx_dma(&self) {
+        unsafe {
+            (*self.registers).dmacr.get().update(|val| val | UART_DMACR_TXDMAE);
+        }
+    }
+    
+    /// 使能RX DMA请求，RX FIFO达到触发级别时向DMAC发出请求
+    pub fn enable_rx_dma(&self) {
+        unsafe {
+            (*self.registers).dmacr.get().update(|val| val | UART_DMACR_RXDMAE);
+        }
+    }
+    
     unsafe fn disable(&self) {
         (*self.registers).cr.get().write_volatile(0x0000);
     }
@@ -263,7 +291,7 @@ impl Rk3588Uart {
     }
     
     unsafe fn configure_baud_rate(&self) -> Result<(), UartError> {
-        let clock_frequency = 24_000_000; // 24MHz
+        let clock_frequency = CLOCK_CONTROLLER.get_clock(PeripheralClock::Uart);
         let baud_divisor = (clock_frequency + self.config.baud_rate / 2) / self.config.baud_rate;
         
         if baud_divisor == 0 || baud_divisor > 0xFFFF {
@@ -369,7 +397,14 @@ pub fn init_debug_uart() {
 }
 
 /// 调试输出函数
+///
+/// DMA通路已初始化时写入TX环形缓冲区立即返回，否则回退到轮询发送
 pub fn debug_print(s: &str) {
+    if let Some(dma) = UART_DMA.lock().as_mut() {
+        dma.write(s.as_bytes());
+        return;
+    }
+
     unsafe {
         if let Some(uart) = &mut DEBUG_UART {
             let _ = uart.send_bytes(s.as_bytes());
@@ -381,4 +416,467 @@ pub fn debug_print(s: &str) {
 pub fn debug_println(s: &str) {
     debug_print(s);
     debug_print("\r\n");
-}
\ No newline at end of file
+}
+
+/// UART DMA通道特征
+pub trait UartDmaChannel: Send {
+    /// 通道是否空闲
+    fn is_idle(&self) -> bool;
+
+    /// 启动一次TX传输，完成后由中断调用`UartDma::on_dma_interrupt`
+    fn start_tx(&mut self, chunk: &[u8]) -> Result<(), UartError>;
+
+    /// 读取并清除TX完成中断，返回是否有一次传输完成
+    fn take_completion(&mut self) -> bool;
+
+    /// 启动一次RX传输，最多接收`UART_DMA_CHUNK_SIZE`字节，完成后由中断调用`UartDma::on_dma_interrupt`
+    fn start_rx(&mut self) -> Result<(), UartError>;
+
+    /// 读取并清除RX完成中断，有一次传输完成时将收到的数据复制到`out`并返回字节数
+    fn take_rx(&mut self, out: &mut [u8]) -> Option<usize>;
+}
+
+/// UART2 TX的DMAC外设请求号
+pub const UART2_TX_DMA_REQUEST: u8 = 4;
+/// UART2 RX的DMAC外设请求号
+pub const UART2_RX_DMA_REQUEST: u8 = 5;
+
+/// 基于DMAC硬件通道的UART收发通道
+///
+/// 外设端地址固定为UART数据寄存器，TX/RX各占一个DMAC通道，分别由UART的TX/RX FIFO请求控制传输节拍
+pub struct ControllerDmaChannel {
+    dmac: DmacChannel,
+    rx_dmac: DmacChannel,
+    data_register: u64,
+    request: u8,
+    rx_request: u8,
+    buffer: DmaBuffer,
+    rx_buffer: DmaBuffer,
+}
+
+// DMA缓冲区只由持有通道的UART DMA通路访问
+unsafe impl Send for ControllerDmaChannel {}
+
+impl ControllerDmaChannel {
+    /// 创建新的UART DMA通道，`uart_base`为UART寄存器基地址
+    pub fn new(
+        channel_id: u8,
+        rx_channel_id: u8,
+        uart_base: usize,
+        request: u8,
+        rx_request: u8,
+    ) -> Result<Self, UartError> {
+        let buffer = unsafe { DmaBuffer::new(UART_DMA_CHUNK_SIZE) }.map_err(|_| UartError::HardwareError)?;
+        let rx_buffer = unsafe { DmaBuffer::new(UART_DMA_CHUNK_SIZE) }.map_err(|_| UartError::HardwareError)?;
+
+        Ok(Self {
+            dmac: DmacChannel::new(DMAC0_BASE, channel_id),
+            rx_dmac: DmacChannel::new(DMAC0_BASE, rx_channel_id),
+            // DR寄存器位于UART基地址偏移0处
+            data_register: uart_base as u64,
+            request,
+            rx_request,
+            buffer,
+            rx_buffer,
+        })
+    }
+}
+
+impl UartDmaChannel for ControllerDmaChannel {
+    fn is_idle(&self) -> bool {
+        !self.dmac.is_busy()
+    }
+
+    fn start_tx(&mut self, chunk: &[u8]) -> Result<(), UartError> {
+        if chunk.len() > UART_DMA_CHUNK_SIZE {
+            return Err(UartError::BufferOverflow);
+        }
+
+        self.buffer.as_mut_slice()[..chunk.len()].copy_from_slice(chunk);
+        let mut descriptor = DmaDescriptor::new();
+        descriptor.configure(
+            self.buffer.physical_address(),
+            self.data_register,
+            chunk.len() as u32,
+            DmaDirection::MemoryToDevice,
+            DmaMode::Single,
+        );
+        descriptor.set_peripheral_request(self.request);
+        self.dmac.start(&descriptor).map_err(|_| UartError::HardwareError)
+    }
+
+    fn take_completion(&mut self) -> bool {
+        self.dmac.take_completion()
+    }
+
+    fn start_rx(&mut self) -> Result<(), UartError> {
+        let mut descriptor = DmaDescriptor::new();
+        descriptor.configure(
+            self.data_register,
+            self.rx_buffer.physical_address(),
+            UART_DMA_CHUNK_SIZE as u32,
+            DmaDirection::DeviceToMemory,
+            DmaMode::Single,
+        );
+        descriptor.set_peripheral_request(self.rx_request);
+        self.rx_dmac.start(&descriptor).map_err(|_| UartError::HardwareError)
+    }
+
+    fn take_rx(&mut self, out: &mut [u8]) -> Option<usize> {
+        if !self.rx_dmac.take_completion() {
+            return None;
+        }
+        let count = self.rx_dmac.result().bytes_transferred.min(UART_DMA_CHUNK_SIZE).min(out.len());
+        out[..count].copy_from_slice(&self.rx_buffer.as_slice()[..count]);
+        Some(count)
+    }
+}
+
+/// 单次DMA传输的最大字节数
+pub const UART_DMA_CHUNK_SIZE: usize = 256;
+/// TX环形缓冲区默认容量
+pub const UART_TX_RING_SIZE: usize = 4096;
+/// RX环形缓冲区默认容量
+pub const UART_RX_RING_SIZE: usize = 4096;
+
+/// UART DMA统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UartDmaStats {
+    /// TX环形缓冲区已满而丢弃的字节数
+    pub tx_overruns: u32,
+    /// TX传输完成时环形缓冲区已空的次数
+    pub tx_underruns: u32,
+    /// RX环形缓冲区已满而丢弃的字节数
+    pub rx_overruns: u32,
+    /// RX DMA重新启动失败、接收停止的次数
+    pub rx_stalls: u32,
+}
+
+/// 尽可能多地写入环形缓冲区，返回实际写入的字节数
+fn push_bytes<const N: usize>(ring: &mut RingBuffer<u8, N>, data: &[u8]) -> usize {
+    data.iter().take_while(|&&byte| ring.try_push(byte).is_ok()).count()
+}
+
+/// 尽可能多地从环形缓冲区读出，返回实际读出的字节数
+fn pop_bytes<const N: usize>(ring: &mut RingBuffer<u8, N>, out: &mut [u8]) -> usize {
+    let mut count = 0;
+    for slot in out.iter_mut() {
+        match ring.pop() {
+            Some(byte) => *slot = byte,
+            None => break,
+        }
+        count += 1;
+    }
+    count
+}
+
+/// UART DMA收发通路
+///
+/// TX：写入环形缓冲区后立即返回，DMA按块从环形缓冲区取数据发送；
+/// RX：DMA按块接收，完成中断将数据送入环形缓冲区并重新启动接收，由`read`读出
+pub struct UartDma<const TX: usize = UART_TX_RING_SIZE, const RX: usize = UART_RX_RING_SIZE> {
+    channel: Box<dyn UartDmaChannel>,
+    tx_ring: RingBuffer<u8, TX>,
+    rx_ring: RingBuffer<u8, RX>,
+    tx_in_flight: bool,
+    tx_overruns: u32,
+    tx_underruns: u32,
+    rx_overruns: u32,
+    rx_stalls: u32,
+}
+
+impl<const TX: usize, const RX: usize> UartDma<TX, RX> {
+    /// 创建新的UART DMA通路
+    pub fn new(channel: Box<dyn UartDmaChannel>) -> Self {
+        Self {
+            channel,
+            tx_ring: RingBuffer::new(),
+            rx_ring: RingBuffer::new(),
+            tx_in_flight: false,
+            tx_overruns: 0,
+            tx_underruns: 0,
+            rx_overruns: 0,
+            rx_stalls: 0,
+        }
+    }
+
+    /// 写入TX环形缓冲区并按需启动DMA，不等待发送完成
+    ///
+    /// 返回接受的字节数，环形缓冲区已满的部分计入TX溢出
+    pub fn write(&mut self, data: &[u8]) -> usize {
+        let accepted = push_bytes(&mut self.tx_ring, data);
+        self.tx_overruns += (data.len() - accepted) as u32;
+
+        if !self.tx_in_flight && self.channel.is_idle() {
+            self.start_next_chunk();
+        }
+
+        accepted
+    }
+
+    /// 启动RX DMA接收
+    pub fn start_rx(&mut self) -> Result<(), UartError> {
+        self.channel.start_rx()
+    }
+
+    /// DMAC中断处理：TX完成时继续发送下一块，RX完成时收下数据并重新启动接收
+    pub fn on_dma_interrupt(&mut self) {
+        if self.channel.take_completion() {
+            self.on_tx_complete();
+        }
+
+        let mut chunk = [0u8; UART_DMA_CHUNK_SIZE];
+        if let Some(count) = self.channel.take_rx(&mut chunk) {
+            self.on_rx_dma(&chunk[..count]);
+            if self.channel.start_rx().is_err() {
+                self.rx_stalls += 1;
+            }
+        }
+    }
+
+    /// TX传输完成，继续发送下一块
+    pub fn on_tx_complete(&mut self) {
+        self.tx_in_flight = false;
+        if self.tx_ring.is_empty() {
+            self.tx_underruns += 1;
+            return;
+        }
+        self.start_next_chunk();
+    }
+
+    /// RX DMA完成中断处理，将接收到的数据送入RX环形缓冲区
+    pub fn on_rx_dma(&mut self, data: &[u8]) {
+        let accepted = push_bytes(&mut self.rx_ring, data);
+        self.rx_overruns += (data.len() - accepted) as u32;
+    }
+
+    /// 从RX环形缓冲区读取数据，返回实际读取的字节数
+    pub fn read(&mut self, buffer: &mut [u8]) -> usize {
+        pop_bytes(&mut self.rx_ring, buffer)
+    }
+
+    /// TX环形缓冲区中待发送的字节数
+    pub fn tx_pending(&self) -> usize {
+        self.tx_ring.len()
+    }
+
+    /// RX环形缓冲区中可读的字节数
+    pub fn rx_available(&self) -> usize {
+        self.rx_ring.len()
+    }
+
+    /// 获取溢出/欠载统计
+    pub fn stats(&self) -> UartDmaStats {
+        UartDmaStats {
+            tx_overruns: self.tx_overruns,
+            tx_underruns: self.tx_underruns,
+            rx_overruns: self.rx_overruns,
+            rx_stalls: self.rx_stalls,
+        }
+    }
+
+    fn start_next_chunk(&mut self) {
+        let mut chunk = [0u8; UART_DMA_CHUNK_SIZE];
+        let count = pop_bytes(&mut self.tx_ring, &mut chunk);
+        if count == 0 {
+            return;
+        }
+
+        if self.channel.start_tx(&chunk[..count]).is_ok() {
+            self.tx_in_flight = true;
+        } else {
+            // 启动失败，本块数据丢弃
+            self.tx_overruns += count as u32;
+        }
+    }
+}
+
+/// 全局UART DMA通路（调试UART），线程与DMAC中断共享，持锁期间屏蔽本核IRQ
+static UART_DMA: IrqMutex<Option<UartDma>> = IrqMutex::new(None);
+
+/// 初始化调试UART的DMA通路，注册DMAC完成中断并启动RX接收
+///
+/// DMAC0中断已被其他驱动占用时返回错误，不安装DMA通路
+pub fn init_uart_dma(tx_channel_id: u8, rx_channel_id: u8) -> Result<(), UartError> {
+    if has_interrupt_handler(DMAC0_IRQ) {
+        return Err(UartError::HardwareError);
+    }
+
+    let channel = ControllerDmaChannel::new(
+        tx_channel_id,
+        rx_channel_id,
+        Rk3588Uart::UART2_BASE,
+        UART2_TX_DMA_REQUEST,
+        UART2_RX_DMA_REQUEST,
+    )?;
+    let mut dma = UartDma::new(Box::new(channel));
+    dma.start_rx()?;
+    *UART_DMA.lock() = Some(dma);
+
+    if request_irq(DMAC0_IRQ, uart_dma_interrupt_handler, PriorityBand::Uart).is_err() {
+        *UART_DMA.lock() = None;
+        return Err(UartError::HardwareError);
+    }
+
+    unsafe {
+        if let Some(uart) = &DEBUG_UART {
+            uart.enable_tx_dma();
+            uart.enable_rx_dma();
+        }
+    }
+    Ok(())
+}
+
+/// DMAC0中断处理函数
+fn uart_dma_interrupt_handler(_interrupt_id: u32) {
+    handle_dma_complete();
+}
+
+/// 通过DMA发送数据，立即返回接受的字节数
+pub fn write_dma(data: &[u8]) -> usize {
+    UART_DMA.lock().as_mut().map_or(0, |dma| dma.write(data))
+}
+
+/// 从DMA接收环形缓冲区读取数据
+pub fn read(buffer: &mut [u8]) -> usize {
+    UART_DMA.lock().as_mut().map_or(0, |dma| dma.read(buffer))
+}
+
+/// DMA完成中断处理入口
+pub fn handle_dma_complete() {
+    if let Some(dma) = UART_DMA.lock().as_mut() {
+        dma.on_dma_interrupt();
+    }
+}
+
+/// 获取UART DMA统计
+pub fn dma_stats() -> UartDmaStats {
+    UART_DMA.lock().as_ref().map(|dma| dma.stats()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::sync::Arc;
+    use alloc::vec::Vec;
+
+    /// 模拟DMA通道：记录每次启动的传输块，按队列顺序完成RX传输
+    struct MockDmaChannel {
+        chunks: Arc<IrqMutex<Vec<Vec<u8>>>>,
+        rx: Arc<IrqMutex<Vec<Vec<u8>>>>,
+        rx_starts: Arc<IrqMutex<usize>>,
+    }
+
+    impl UartDmaChannel for MockDmaChannel {
+        fn is_idle(&self) -> bool {
+            true
+        }
+
+        fn start_tx(&mut self, chunk: &[u8]) -> Result<(), UartError> {
+            self.chunks.lock().push(chunk.to_vec());
+            Ok(())
+        }
+
+        fn take_completion(&mut self) -> bool {
+            true
+        }
+
+        fn start_rx(&mut self) -> Result<(), UartError> {
+            *self.rx_starts.lock() += 1;
+            Ok(())
+        }
+
+        fn take_rx(&mut self, out: &mut [u8]) -> Option<usize> {
+            let mut rx = self.rx.lock();
+            if rx.is_empty() {
+                return None;
+            }
+            let data = rx.remove(0);
+            out[..data.len()].copy_from_slice(&data);
+            Some(data.len())
+        }
+    }
+
+    fn mock_channel() -> (MockDmaChannel, Arc<IrqMutex<Vec<Vec<u8>>>>) {
+        let chunks = Arc::new(IrqMutex::new(Vec::new()));
+        let channel = MockDmaChannel {
+            chunks: chunks.clone(),
+            rx: Arc::new(IrqMutex::new(Vec::new())),
+            rx_starts: Arc::new(IrqMutex::new(0)),
+        };
+        (channel, chunks)
+    }
+
+    fn mock_dma<const TX: usize, const RX: usize>() -> (UartDma<TX, RX>, Arc<IrqMutex<Vec<Vec<u8>>>>) {
+        let (channel, chunks) = mock_channel();
+        (UartDma::new(Box::new(channel)), chunks)
+    }
+
+    #[test]
+    fn test_large_write_chunked_through_tx_ring() {
+        // 大块写入立即返回，随后按块经DMA发送完毕
+        let (mut dma, chunks) = mock_dma::<UART_TX_RING_SIZE, 64>();
+        let data: Vec<u8> = (0..1000u32).map(|i| i as u8).collect();
+
+        assert_eq!(dma.write(&data), 1000);
+        assert_eq!(chunks.lock().len(), 1);
+        assert_eq!(dma.tx_pending(), 1000 - UART_DMA_CHUNK_SIZE);
+
+        while dma.tx_pending() > 0 {
+            dma.on_dma_interrupt();
+        }
+        dma.on_dma_interrupt();
+
+        let sent: Vec<u8> = chunks.lock().iter().flatten().copied().collect();
+        assert_eq!(sent, data);
+        assert!(chunks.lock().iter().all(|c| c.len() <= UART_DMA_CHUNK_SIZE));
+        assert_eq!(dma.stats().tx_underruns, 1);
+
+        // 超出环形缓冲区容量的部分计入溢出
+        let (mut small, _) = mock_dma::<{ UART_DMA_CHUNK_SIZE + 16 }, 64>();
+        assert_eq!(small.write(&[0u8; 300]), UART_DMA_CHUNK_SIZE + 16);
+        assert_eq!(small.stats().tx_overruns, 300 - (UART_DMA_CHUNK_SIZE as u32 + 16));
+    }
+
+    #[test]
+    fn test_rx_dma_fills_read_ring_in_order() {
+        // RX DMA数据按到达顺序读出，环形缓冲区满时统计溢出
+        let (mut dma, _) = mock_dma::<64, 8>();
+        dma.on_rx_dma(b"abc");
+        dma.on_rx_dma(b"defg");
+
+        let mut buffer = [0u8; 4];
+        assert_eq!(dma.read(&mut buffer), 4);
+        assert_eq!(&buffer, b"abcd");
+
+        dma.on_rx_dma(b"hijklm");
+        let mut rest = [0u8; 16];
+        let count = dma.read(&mut rest);
+        assert_eq!(&rest[..count], b"efghijk");
+        assert_eq!(dma.stats().rx_overruns, 2);
+    }
+
+    #[test]
+    fn test_rx_dma_interrupt_feeds_ring_and_restarts() {
+        // RX DMA完成中断将接收块送入环形缓冲区，并重新启动下一次接收
+        let (channel, _) = mock_channel();
+        let rx = channel.rx.clone();
+        let rx_starts = channel.rx_starts.clone();
+        let mut dma: UartDma<64, 64> = UartDma::new(Box::new(channel));
+        assert_eq!(dma.start_rx(), Ok(()));
+
+        rx.lock().push(b"hello".to_vec());
+        dma.on_dma_interrupt();
+        assert_eq!(*rx_starts.lock(), 2);
+        assert_eq!(dma.rx_available(), 5);
+
+        // 没有RX完成时中断不重新启动接收
+        dma.on_dma_interrupt();
+        assert_eq!(*rx_starts.lock(), 2);
+
+        let mut buffer = [0u8; 8];
+        assert_eq!(dma.read(&mut buffer), 5);
+        assert_eq!(&buffer[..5], b"hello");
+        assert_eq!(dma.stats().rx_stalls, 0);
+    }
+}
@@ -9,7 +9,7 @@ use core::future::Future;
 use core::pin::Pin;
 use core::task::{Context, Poll};
 
-use crate::{AsyncDriver, AsyncCommunicationDriver, DriverError, DmaBuffer, ZeroCopyTransfer, DmaDirection};
+use crate::{AsyncDriver, AsyncCommunicationDriver, DriverError, DmaBuffer, ZeroCopyTransfer, DmaDirection, DmaStatus};
 
 /// USB设备类型
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -165,10 +165,13 @@ impl UsbDriver {
         // 开始传输
         transfer.start()?;
         
-        // 等待传输完成
-        transfer.wait_completion()?;
+        // 等待传输完成，出错时返回已传输的有效字节数
+        let result = transfer.wait_completion()?;
+        if result.status == DmaStatus::Aborted {
+            return Err(DriverError::IoError);
+        }
         
-        Ok(buffer.size())
+        Ok(result.bytes_transferred)
     }
     
     /// 异步批量数据传输
@@ -437,4 +440,255 @@ impl AsyncDriver for UsbCameraDriver {
     fn supports_zero_copy(&self) -> bool {
         self.config.zero_copy
     }
-}
\ No newline at end of file
+}
+
+/// 默认的连续NAK重试次数
+pub const DEFAULT_NAK_RETRIES: u32 = 1000;
+
+/// 端点窗口内的状态寄存器偏移：bit31为NAK，bit30为STALL，低16位为包字节数
+const EP_STATUS: u64 = 0x0;
+/// 端点窗口内的数据寄存器偏移
+const EP_DATA: u64 = 0x4;
+const EP_STATUS_NAK: u32 = 1 << 31;
+const EP_STATUS_STALL: u32 = 1 << 30;
+const EP_STATUS_COUNT_MASK: u32 = 0xFFFF;
+
+/// USB传输错误
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UsbError {
+    /// 端点号无效（端点0为控制端点，不支持批量传输）
+    InvalidEndpoint,
+    /// 设备暂时无法收发，应重试
+    Nak,
+    /// 端点被挂起
+    Stall,
+    /// 设备返回的数据超过请求长度
+    Babble,
+    /// 连续NAK超过重试次数
+    Timeout,
+}
+
+impl From<UsbError> for DriverError {
+    fn from(error: UsbError) -> Self {
+        match error {
+            UsbError::InvalidEndpoint => DriverError::InvalidParameter,
+            UsbError::Nak => DriverError::DeviceBusy,
+            UsbError::Stall => DriverError::CommunicationError,
+            UsbError::Babble => DriverError::DataFormatError,
+            UsbError::Timeout => DriverError::Timeout,
+        }
+    }
+}
+
+/// 端点FIFO访问接口，按包收发
+pub trait UsbEndpointFifo {
+    /// 从IN端点读取一个包到`buf`，返回包长度；设备未就绪时返回`UsbError::Nak`
+    fn read_packet(&mut self, endpoint: u8, buf: &mut [u8]) -> Result<usize, UsbError>;
+    
+    /// 向OUT端点写入一个包；设备未就绪时返回`UsbError::Nak`
+    fn write_packet(&mut self, endpoint: u8, data: &[u8]) -> Result<(), UsbError>;
+}
+
+impl UsbEndpointFifo for UsbDriver {
+    fn read_packet(&mut self, endpoint: u8, buf: &mut [u8]) -> Result<usize, UsbError> {
+        let window = self.controller_base + endpoint as u64 * 0x1000;
+        unsafe {
+            let status = ((window + EP_STATUS) as *const u32).read_volatile();
+            if status & EP_STATUS_STALL != 0 {
+                return Err(UsbError::Stall);
+            }
+            if status & EP_STATUS_NAK != 0 {
+                return Err(UsbError::Nak);
+            }
+            
+            let count = (status & EP_STATUS_COUNT_MASK) as usize;
+            if count > buf.len() {
+                return Err(UsbError::Babble);
+            }
+            let data = (window + EP_DATA) as *const u32;
+            for byte in buf[..count].iter_mut() {
+                *byte = data.read_volatile() as u8;
+            }
+            Ok(count)
+        }
+    }
+    
+    fn write_packet(&mut self, endpoint: u8, data: &[u8]) -> Result<(), UsbError> {
+        let window = self.controller_base + endpoint as u64 * 0x1000;
+        unsafe {
+            let status = ((window + EP_STATUS) as *const u32).read_volatile();
+            if status & EP_STATUS_STALL != 0 {
+                return Err(UsbError::Stall);
+            }
+            if status & EP_STATUS_NAK != 0 {
+                return Err(UsbError::Nak);
+            }
+            
+            let fifo = (window + EP_DATA) as *mut u32;
+            for &byte in data {
+                fifo.write_volatile(byte as u32);
+            }
+            // 写入包长度提交该包
+            ((window + EP_STATUS) as *mut u32).write_volatile(data.len() as u32);
+        }
+        Ok(())
+    }
+}
+
+/// USB批量传输设备（如U盘），用于读写模型和日志文件
+pub struct UsbBulkDevice<F: UsbEndpointFifo> {
+    fifo: F,
+    max_packet_size: usize,
+    nak_retries: u32,
+}
+
+impl<F: UsbEndpointFifo> UsbBulkDevice<F> {
+    /// 创建批量传输设备，`max_packet_size`为端点最大包长（高速设备为512）
+    pub fn new(fifo: F, max_packet_size: u16) -> Self {
+        Self {
+            fifo,
+            max_packet_size: (max_packet_size as usize).max(1),
+            nak_retries: DEFAULT_NAK_RETRIES,
+        }
+    }
+    
+    /// 设置单个包允许的连续NAK重试次数
+    pub fn set_nak_retries(&mut self, retries: u32) {
+        self.nak_retries = retries;
+    }
+    
+    /// 底层端点FIFO
+    pub fn fifo(&self) -> &F {
+        &self.fifo
+    }
+    
+    /// 批量读取，按最大包长分包接收直到缓冲区填满或收到短包
+    /// 
+    /// 返回实际读取的字节数，短包表示设备数据已发送完毕
+    pub fn bulk_in(&mut self, endpoint: u8, buf: &mut [u8]) -> Result<usize, DriverError> {
+        Self::check_endpoint(endpoint)?;
+        
+        let mut received = 0;
+        while received < buf.len() {
+            let end = (received + self.max_packet_size).min(buf.len());
+            let chunk = &mut buf[received..end];
+            let requested = chunk.len();
+            let (fifo, retries) = (&mut self.fifo, self.nak_retries);
+            let count = Self::with_nak_retry(retries, || fifo.read_packet(endpoint, chunk))?;
+            if count > requested {
+                return Err(UsbError::Babble.into());
+            }
+            
+            received += count;
+            if count < self.max_packet_size {
+                break;
+            }
+        }
+        Ok(received)
+    }
+    
+    /// 批量写入，按最大包长分包发送
+    /// 
+    /// 数据为空或长度恰为最大包长的整数倍时追加零长度包，通知设备传输结束
+    pub fn bulk_out(&mut self, endpoint: u8, data: &[u8]) -> Result<(), DriverError> {
+        Self::check_endpoint(endpoint)?;
+        
+        let zero_length_packet = data.len() % self.max_packet_size == 0;
+        let packets = data.chunks(self.max_packet_size).chain(zero_length_packet.then_some(&data[..0]));
+        for packet in packets {
+            let (fifo, retries) = (&mut self.fifo, self.nak_retries);
+            Self::with_nak_retry(retries, || fifo.write_packet(endpoint, packet))?;
+        }
+        Ok(())
+    }
+    
+    /// 批量端点号为1-15
+    fn check_endpoint(endpoint: u8) -> Result<(), UsbError> {
+        if endpoint == 0 || endpoint >= 16 {
+            return Err(UsbError::InvalidEndpoint);
+        }
+        Ok(())
+    }
+    
+    /// 执行一次包传输，NAK时重试，连续NAK超过`retries`次返回超时
+    fn with_nak_retry<T>(retries: u32, mut transfer: impl FnMut() -> Result<T, UsbError>) -> Result<T, UsbError> {
+        for _ in 0..=retries {
+            match transfer() {
+                Err(UsbError::Nak) => continue,
+                result => return result,
+            }
+        }
+        Err(UsbError::Timeout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::collections::VecDeque;
+    use alloc::vec::Vec;
+
+    /// 按预设响应序列返回的端点FIFO
+    struct MockFifo {
+        responses: VecDeque<Result<Vec<u8>, UsbError>>,
+        reads: usize,
+        writes: Vec<usize>,
+    }
+
+    impl UsbEndpointFifo for MockFifo {
+        fn read_packet(&mut self, _endpoint: u8, buf: &mut [u8]) -> Result<usize, UsbError> {
+            self.reads += 1;
+            let packet = self.responses.pop_front().unwrap_or(Err(UsbError::Nak))?;
+            buf[..packet.len()].copy_from_slice(&packet);
+            Ok(packet.len())
+        }
+
+        fn write_packet(&mut self, _endpoint: u8, data: &[u8]) -> Result<(), UsbError> {
+            self.responses.pop_front().unwrap_or(Err(UsbError::Nak))?;
+            self.writes.push(data.len());
+            Ok(())
+        }
+    }
+
+    fn packet(start: u8, len: usize) -> Result<Vec<u8>, UsbError> {
+        Ok((0..len).map(|i| start.wrapping_add(i as u8)).collect())
+    }
+
+    #[test]
+    fn test_multi_packet_read_assembles_buffer() {
+        // 两个满包（中间夹杂NAK）加一个短包拼成完整数据，短包结束传输
+        let responses = [packet(0, 64), Err(UsbError::Nak), Err(UsbError::Nak), packet(64, 64), packet(128, 20)];
+        let mut device = UsbBulkDevice::new(MockFifo { responses: responses.into_iter().collect(), reads: 0, writes: Vec::new() }, 64);
+
+        let mut buf = [0u8; 256];
+        assert_eq!(device.bulk_in(1, &mut buf), Ok(148));
+        assert!(buf[..148].iter().enumerate().all(|(i, &b)| b == i as u8));
+        assert_eq!(device.fifo().reads, 5);
+
+        assert_eq!(device.bulk_in(0, &mut buf), Err(DriverError::InvalidParameter));
+    }
+
+    #[test]
+    fn test_nak_storm_times_out() {
+        // 设备持续NAK时，重试次数用尽后返回超时
+        let mut device = UsbBulkDevice::new(MockFifo { responses: VecDeque::new(), reads: 0, writes: Vec::new() }, 512);
+        device.set_nak_retries(10);
+
+        let mut buf = [0u8; 512];
+        assert_eq!(device.bulk_in(2, &mut buf), Err(DriverError::Timeout));
+        assert_eq!(device.fifo().reads, 11);
+        assert_eq!(device.bulk_out(2, &[1, 2, 3]), Err(DriverError::Timeout));
+    }
+
+    #[test]
+    fn test_bulk_out_terminates_with_zero_length_packet() {
+        // 长度为最大包长整数倍或为空时追加零长度包，短包结尾时不追加
+        let responses = (0..8).map(|_| packet(0, 0)).collect();
+        let mut device = UsbBulkDevice::new(MockFifo { responses, reads: 0, writes: Vec::new() }, 64);
+
+        assert_eq!(device.bulk_out(1, &[0u8; 128]), Ok(()));
+        assert_eq!(device.bulk_out(1, &[0u8; 100]), Ok(()));
+        assert_eq!(device.bulk_out(1, &[]), Ok(()));
+        assert_eq!(device.fifo().writes, [64, 64, 0, 64, 36, 0]);
+    }
+}